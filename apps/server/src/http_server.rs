@@ -26,10 +26,10 @@ pub async fn run_http_server(config: StumpConfig) -> ServerResult<()> {
 	// in some initialization function. The server-specific things, e.g. watcher, scheduler,
 	// should be fully managed by the server and removed from the core...
 
-	// Cancel any islanded jobs from a previous run
+	// Requeue any jobs which were queued or interrupted during a previous run
 	core.get_context()
-		.apalis_state
-		.cancel_islanded_jobs()
+		.job_storage
+		.restore()
 		.await
 		.map_err(|e| ServerError::ServerStartError(e.to_string()))?;
 
@@ -95,13 +95,14 @@ pub async fn run_http_server(config: StumpConfig) -> ServerResult<()> {
 
 	// TODO: Experiment with higher concurrency, YEARS ago at this point (before enforcing WAL even)
 	// I experienced multi-writer issues but perhaps with SeaORM + WAL we can have parallel scans.
+	// Until then, the per-category limits keep jobs of the same kind from running side by side
 	let monitor = Monitor::new()
 		.register(
 			WorkerBuilder::new("stump-worker")
 				.enable_tracing()
 				.data(server_ctx.apalis_state.clone())
-				.concurrency(1)
-				.backend(server_ctx.job_storage.clone())
+				.concurrency(config.max_concurrent_jobs.max(1))
+				.backend(server_ctx.job_storage.backend())
 				.build_fn(dispatch_job),
 		)
 		.with_terminator(tokio::time::sleep(Duration::from_secs(30)))
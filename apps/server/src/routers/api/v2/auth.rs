@@ -9,6 +9,7 @@ use axum::{
 use axum_extra::{headers::UserAgent, TypedHeader};
 use chrono::{DateTime, Duration, FixedOffset, Utc};
 use graphql::data::{AuthContext, ServiceContext};
+use integrations::NotificationEvent;
 use models::entity::{
 	session,
 	user::{self, AuthUser, LoginUser},
@@ -445,6 +446,10 @@ pub async fn register(
 
 	tx.commit().await?;
 
+	ctx.notify(NotificationEvent::UserRegistered {
+		username: updated_user.username,
+	});
+
 	let auth_user = inject_avatar_url(AuthUser::from(auth_user), service);
 
 	Ok(Json(auth_user))
@@ -4,6 +4,7 @@ pub(crate) mod epub;
 pub(crate) mod library;
 pub(crate) mod media;
 mod oidc;
+mod reading_list;
 mod series;
 mod user;
 
@@ -30,6 +31,7 @@ pub(crate) fn mount(app_state: AppState) -> Router<AppState> {
 		.merge(media::mount(app_state.clone()))
 		.merge(epub::mount(app_state.clone()))
 		.merge(series::mount(app_state.clone()))
+		.merge(reading_list::mount(app_state.clone()))
 		.merge(library::mount(app_state.clone()))
 		.merge(user::mount(app_state))
 		.route("/claim", get(claim))
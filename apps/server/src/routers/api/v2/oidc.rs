@@ -4,6 +4,7 @@ use axum::{
 	routing::get,
 	Json, Router,
 };
+use integrations::NotificationEvent;
 use models::entity::{server_config, user, user_preferences};
 use sea_orm::{
 	ActiveModelTrait, ColumnTrait, EntityTrait, IntoActiveModel, PaginatorTrait,
@@ -258,6 +259,10 @@ async fn callback(
 
 		tx.commit().await?;
 
+		ctx.notify(NotificationEvent::UserRegistered {
+			username: user.username.clone(),
+		});
+
 		if let Some(picture_url) = &claims.picture {
 			match download_image(picture_url).await {
 				Ok((bytes, ext)) => {
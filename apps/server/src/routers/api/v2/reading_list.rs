@@ -0,0 +1,67 @@
+use axum::{
+	extract::{Path, State},
+	http::{header, HeaderMap, HeaderValue},
+	middleware,
+	routing::get,
+	Extension, Router,
+};
+use graphql::data::AuthContext;
+use models::{entity::reading_list, shared::enums::AccessRole};
+use stump_core::reading_list::cbl::{CBLBook, CBLCandidate, CBLReadingList};
+
+use crate::{
+	config::state::AppState,
+	errors::{APIError, APIResult},
+	middleware::auth::auth_middleware,
+	utils::http::Xml,
+};
+
+pub(crate) fn mount(app_state: AppState) -> Router<AppState> {
+	Router::new()
+		.nest(
+			"/reading-lists/{id}",
+			Router::new().route("/cbl", get(export_reading_list_cbl)),
+		)
+		.layer(middleware::from_fn_with_state(app_state, auth_middleware))
+}
+
+/// Export a reading list as a ComicRack reading list (`.cbl`) file. Only the books the user
+/// has access to are included.
+async fn export_reading_list_cbl(
+	Path(id): Path<String>,
+	State(ctx): State<AppState>,
+	Extension(req): Extension<AuthContext>,
+) -> APIResult<(HeaderMap, Xml)> {
+	let user = req.user();
+	let conn = ctx.conn.as_ref();
+
+	let reading_list =
+		reading_list::Entity::find_for_user_and_id(&user, AccessRole::Reader, &id)
+			.one(conn)
+			.await?
+			.ok_or(APIError::NotFound("Reading list not found".to_string()))?;
+
+	let books = CBLCandidate::find_in_reading_list(&user, &reading_list.id)
+		.into_model::<CBLCandidate>()
+		.all(conn)
+		.await?
+		.into_iter()
+		.map(CBLBook::from)
+		.collect::<Vec<_>>();
+	let cbl = CBLReadingList::new(reading_list.name.clone(), books).to_xml()?;
+
+	let filename = reading_list
+		.name
+		.chars()
+		.filter(|c| !matches!(c, '"' | '\\' | '/') && !c.is_control())
+		.collect::<String>();
+	let mut headers = HeaderMap::new();
+	headers.insert(
+		header::CONTENT_DISPOSITION,
+		format!("attachment; filename=\"{}.cbl\"", filename)
+			.parse()
+			.unwrap_or_else(|_| HeaderValue::from_static("attachment")),
+	);
+
+	Ok((headers, Xml(cbl)))
+}
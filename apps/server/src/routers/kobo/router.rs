@@ -8,12 +8,14 @@ use axum::{
 	Extension, Router,
 };
 use graphql::data::AuthContext;
+use models::entity::media;
 use models::shared::{
 	enums::UserPermission,
 	image_processor_options::{
 		FitWithinResize, ImageProcessorOptions, ImageResizeMethod, SupportedImageFormat,
 	},
 };
+use sea_orm::{ColumnTrait, QueryFilter};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Map};
 use stump_core::{
@@ -21,7 +23,14 @@ use stump_core::{
 		image::{GenericImageProcessor, ImageProcessor},
 		ContentType,
 	},
-	kobo::entity::MediaWithMetadataAndReadingSessions,
+	kobo::{
+		conversion::{
+			get_or_create_kobo_epub, kobo_download_size, kobo_syncable_condition,
+			requires_kobo_conversion,
+		},
+		entity::MediaWithMetadataAndReadingSessions,
+		reading_state::{apply_reading_state_update, KoboDevice},
+	},
 };
 
 use crate::{
@@ -91,6 +100,10 @@ pub(crate) fn mount(app_state: AppState) -> Router<AppState> {
 			.route("/v1/initialization", get(initialization))
 			.route("/v1/library/sync", get(library_sync))
 			.route("/v1/library/{book_id}/metadata", get(book_metadata))
+			.route(
+				"/v1/library/{book_id}/state",
+				get(book_reading_state).put(put_book_reading_state),
+			)
 			.route(
 				"/v1/books/{book_id}/thumbnail/{width}/{height}/{is_greyscale}/image.jpg",
 				get(book_thumbnail),
@@ -212,7 +225,9 @@ async fn library_sync(
 	.await?;
 
 	let kobo_api_base_url = format!("{}/kobo/{}", host.url(), api_key);
-	let sync_items = sync_page.sync_items(kobo_api_base_url.as_str()).await?;
+	let sync_items = sync_page
+		.sync_items(kobo_api_base_url.as_str(), &ctx.config)
+		.await?;
 
 	// if we don't send a sync token the client will send no sync token on its next sync,
 	// essentially starting the sync process from scratch. that's not a disaster, but it's
@@ -251,10 +266,89 @@ async fn book_metadata(
 		m.media.id
 	);
 
-	let result = BookMetadata::from_media(&m, book_url);
+	let download_size = kobo_download_size(&m.media, &ctx.config).await;
+	let result = BookMetadata::from_media(&m, book_url, download_size);
 	Ok(Json(vec![result]))
 }
 
+async fn book_reading_state(
+	State(ctx): State<AppState>,
+	Extension(req): Extension<AuthContext>,
+	Path(KoboAPIKeyAndBookId { book_id, .. }): Path<KoboAPIKeyAndBookId>,
+) -> APIResult<Json<Vec<ReadingState>>> {
+	let conn = ctx.conn.as_ref();
+	let user = req.user();
+
+	let m = MediaWithMetadataAndReadingSessions::find_by_id_for_user(book_id, &user)
+		.into_model::<MediaWithMetadataAndReadingSessions>()
+		.one(conn)
+		.await?
+		.ok_or(APIError::NotFound("Book not found".to_string()))?;
+
+	Ok(Json(vec![ReadingState::from_media(&m)]))
+}
+
+async fn put_book_reading_state(
+	State(ctx): State<AppState>,
+	Extension(req): Extension<AuthContext>,
+	Path(KoboAPIKeyAndBookId { book_id, .. }): Path<KoboAPIKeyAndBookId>,
+	headers: HeaderMap,
+	Json(ReadingStateUpdates { reading_states }): Json<ReadingStateUpdates>,
+) -> APIResult<Json<ReadingStateUpdateResponse>> {
+	let conn = ctx.conn.as_ref();
+	let user = req.user();
+
+	let device_id = headers
+		.get("x-kobo-deviceid")
+		.and_then(|h| h.to_str().ok())
+		.unwrap_or_default();
+	let device_model = headers
+		.get("x-kobo-devicemodel")
+		.and_then(|h| h.to_str().ok())
+		.unwrap_or("Kobo");
+	// registered device names are unique, so we tack on part of the device ID to
+	// distinguish between several devices of the same model
+	let device_name = format!(
+		"{} ({})",
+		device_model,
+		device_id.chars().take(8).collect::<String>()
+	);
+
+	let mut update_results = Vec::with_capacity(reading_states.len());
+	for update in reading_states {
+		if update.entitlement_id != book_id {
+			tracing::warn!(
+				?book_id,
+				entitlement_id = ?update.entitlement_id,
+				"Kobo reading state does not match the requested book"
+			);
+			update_results.push(ReadingStateUpdateResult::new(
+				update.entitlement_id,
+				RequestResult::Ignored,
+			));
+			continue;
+		}
+
+		let result = apply_reading_state_update(
+			conn,
+			&user,
+			&update,
+			Some(KoboDevice {
+				id: device_id,
+				name: device_name.as_str(),
+			}),
+			ctx.config.book_completion_dedup_timeout_secs,
+		)
+		.await?;
+		update_results.push(result);
+	}
+
+	Ok(Json(ReadingStateUpdateResponse {
+		request_result: RequestResult::Success,
+		update_results,
+	}))
+}
+
 async fn book_thumbnail(
 	State(ctx): State<AppState>,
 	Extension(req): Extension<AuthContext>,
@@ -286,11 +380,42 @@ async fn book_thumbnail(
 	Ok(ImageResponse::new(ContentType::JPEG, jpeg_buffer))
 }
 
+/// Serves a book to the Kobo. EPUBs are served as-is, while books in libraries which opted
+/// in to Kobo conversion are served as a generated fixed-layout EPUB.
 async fn book_download(
 	State(ctx): State<AppState>,
 	Extension(req): Extension<AuthContext>,
 	Path(KoboAPIKeyAndBookId { book_id, .. }): Path<KoboAPIKeyAndBookId>,
 	headers: HeaderMap,
-) -> APIResult<impl IntoResponse> {
-	serve_media::serve_media_file(req, headers, ctx.conn.as_ref(), book_id).await
+) -> APIResult<Response> {
+	let user = req
+		.user_and_enforce_permissions(&[UserPermission::DownloadFile])
+		.map_err(|_| {
+			tracing::error!("User does not have permission to download file");
+			APIError::forbidden_discreet()
+		})?;
+
+	let book = media::Entity::find_for_user(&user)
+		.filter(media::Column::Id.eq(book_id))
+		.filter(kobo_syncable_condition())
+		.one(ctx.conn.as_ref())
+		.await?
+		.ok_or(APIError::NotFound("Book not found".to_string()))?;
+
+	if !requires_kobo_conversion(&book.extension) {
+		return serve_media::serve_file_as_attachment(
+			headers,
+			std::path::Path::new(&book.path),
+			None,
+		)
+		.await;
+	}
+
+	let epub_path = get_or_create_kobo_epub(&book, &ctx.config).await?;
+	serve_media::serve_file_as_attachment(
+		headers,
+		&epub_path,
+		Some(&format!("{}.epub", book.name)),
+	)
+	.await
 }
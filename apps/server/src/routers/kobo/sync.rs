@@ -1,6 +1,10 @@
+use std::collections::HashSet;
+
 use models::entity::{
-	kobo_sync_session,
+	finished_reading_session,
+	kobo_sync_session::{self, KoboTagChanges, KoboTags},
 	media::{self},
+	reading_session,
 	user::AuthUser,
 };
 use sea_orm::Set;
@@ -8,8 +12,46 @@ use sea_orm::Set;
 use sea_orm::prelude::*;
 use sea_orm::query::*;
 
-use crate::routers::kobo::sync_token::SyncToken;
-use stump_core::kobo::{entity::MediaWithMetadataAndReadingSessions, sync_types::*};
+use crate::routers::kobo::{sync_token::SyncToken, tags::current_tags};
+use stump_core::{
+	config::StumpConfig,
+	kobo::{
+		conversion::{
+			kobo_conversion_enabled_since, kobo_download_size, kobo_syncable_condition,
+		},
+		entity::MediaWithMetadataAndReadingSessions,
+		sync_types::*,
+	},
+};
+
+/// A condition matching media whose reading state (active or finished reading sessions)
+/// changed for the user at or after the given time.
+fn reading_state_changed_since(
+	user: &AuthUser,
+	since: DateTimeWithTimeZone,
+) -> sea_orm::Condition {
+	let active_sessions = reading_session::Entity::find()
+		.select_only()
+		.column(reading_session::Column::MediaId)
+		.filter(reading_session::Column::UserId.eq(user.id.clone()))
+		.filter(
+			sea_orm::Condition::any()
+				.add(reading_session::Column::StartedAt.gte(since))
+				.add(reading_session::Column::UpdatedAt.gte(since)),
+		)
+		.into_query();
+
+	let finished_sessions = finished_reading_session::Entity::find()
+		.select_only()
+		.column(finished_reading_session::Column::MediaId)
+		.filter(finished_reading_session::Column::UserId.eq(user.id.clone()))
+		.filter(finished_reading_session::Column::CompletedAt.gte(since))
+		.into_query();
+
+	sea_orm::Condition::any()
+		.add(media::Column::Id.in_subquery(active_sessions))
+		.add(media::Column::Id.in_subquery(finished_sessions))
+}
 
 pub struct KoboSync {
 	model: kobo_sync_session::Model,
@@ -79,17 +121,22 @@ impl KoboSync {
 		user: &AuthUser,
 		device_id: Option<&str>,
 		device_metadata: serde_json::Value,
-		previous_sync_at: Option<DateTimeWithTimeZone>,
+		previous_session: Option<&kobo_sync_session::Model>,
 	) -> Result<Self, sea_orm::DbErr> {
-		let query = media::Entity::find_for_user(user)
-			.filter(media::Column::Extension.eq("epub"));
+		let previous_sync_at = previous_session.map(|s| s.created_at.fixed_offset());
+
+		let query = media::Entity::find_for_user(user).filter(kobo_syncable_condition());
 
 		let query = match previous_sync_at {
-			// load things created or modified since the last sync session
+			// load things created or modified since the last sync session, as well as
+			// anything the user made progress on elsewhere (e.g. the web reader) and books
+			// in libraries which opted in to conversion since
 			Some(previous_sync_at) => query.filter(
 				sea_orm::Condition::any()
 					.add(media::Column::CreatedAt.gte(previous_sync_at))
-					.add(media::Column::ModifiedAt.gte(previous_sync_at)),
+					.add(media::Column::ModifiedAt.gte(previous_sync_at))
+					.add(reading_state_changed_since(user, previous_sync_at))
+					.add(kobo_conversion_enabled_since(previous_sync_at)),
 			),
 			// we're starting from scratch, load absolutely everything
 			None => query,
@@ -97,12 +144,22 @@ impl KoboSync {
 
 		let media_ids = query.column(media::Column::Id).into_tuple().all(db).await?;
 
+		let tags = current_tags(db, user).await?;
+		let tag_changes = match previous_session {
+			Some(previous_session) => {
+				KoboTagChanges::between(&previous_session.tags, &tags)
+			},
+			None => KoboTagChanges::between(&KoboTags::default(), &tags),
+		};
+
 		let sync_session = kobo_sync_session::ActiveModel {
 			user_id: Set(user.id.clone()),
 			media_ids: Set(kobo_sync_session::MediaIds(media_ids)),
 			device_id: Set(device_id.unwrap_or("").to_string()),
 			device_metadata: Set(device_metadata),
 			previous_sync_at: Set(previous_sync_at),
+			tags: Set(tags),
+			tag_changes: Set(tag_changes),
 			..Default::default()
 		};
 		let sync_session = sync_session.insert(db).await?;
@@ -110,6 +167,9 @@ impl KoboSync {
 		tracing::debug!(
 			?previous_sync_at,
 			to_sync_media_count = sync_session.media_ids.0.len(),
+			new_tag_count = sync_session.tag_changes.new.len(),
+			changed_tag_count = sync_session.tag_changes.changed.len(),
+			deleted_tag_count = sync_session.tag_changes.deleted.len(),
 			sync_id = sync_session.id,
 			"Beginning new Kobo sync session"
 		);
@@ -151,8 +211,6 @@ impl KoboSync {
 			},
 			(_, prev_sync_session) => {
 				// there was no previous sync session, or the previous session completed
-				let previous_sync_began_at =
-					prev_sync_session.map(|s| s.model.created_at.fixed_offset());
 
 				// once the device has acknowledged session N (prev_sync_session) we no longer need session N-1.
 				let keep_sessions: Vec<String> = sync_id.into_iter().collect();
@@ -163,7 +221,7 @@ impl KoboSync {
 					user,
 					device_id,
 					device_metadata,
-					previous_sync_began_at,
+					prev_sync_session.as_ref().map(|s| &s.model),
 				)
 				.await?;
 
@@ -179,6 +237,10 @@ impl KoboSync {
 			offset,
 			limit,
 			session.model.previous_sync_at,
+		)
+		.with_tag_changes(
+			&session.model.tag_changes,
+			session.model.created_at.fixed_offset(),
 		))
 	}
 }
@@ -201,6 +263,10 @@ pub struct SyncPage<'a> {
 	/// IDs of database media objects that should be returned in this page.
 	media_ids: Vec<String>,
 
+	/// shelf changes that should be returned in this page, along with the time they were
+	/// computed. these are only sent with the final page of a session.
+	tag_changes: Option<(KoboTagChanges, DateTimeWithTimeZone)>,
+
 	/// are there more pages to retrieve in this sync session?
 	pub should_continue: bool,
 
@@ -234,47 +300,117 @@ impl<'a> SyncPage<'a> {
 			previous_sync_at,
 
 			media_ids: media_ids[start..next_offset].to_vec(),
+			tag_changes: None,
 			should_continue,
 
 			sync_token: SyncToken::new(sync_id, !should_continue, next_offset),
 		}
 	}
 
+	/// Attach the shelf changes of this session. Shelves are sent with the final page,
+	/// once every book they may reference has been sent to the device.
+	fn with_tag_changes(
+		mut self,
+		tag_changes: &KoboTagChanges,
+		changed_at: DateTimeWithTimeZone,
+	) -> Self {
+		if !self.should_continue && !tag_changes.is_empty() {
+			self.tag_changes = Some((tag_changes.clone(), changed_at));
+		}
+		self
+	}
+
+	fn tag_sync_items(&self) -> Vec<SyncItem> {
+		let Some((tag_changes, changed_at)) = self.tag_changes.as_ref() else {
+			return vec![];
+		};
+		let changed_at = changed_at.to_utc();
+
+		let new_tags = tag_changes
+			.new
+			.iter()
+			.map(|tag| SyncItem::NewTag(TagContainer::from_kobo_tag(tag, changed_at)));
+		let changed_tags = tag_changes.changed.iter().map(|tag| {
+			SyncItem::ChangedTag(TagContainer::from_kobo_tag(tag, changed_at))
+		});
+		let deleted_tags = tag_changes.deleted.iter().map(|id| {
+			SyncItem::DeletedTag(DeletedTagContainer {
+				tag: DeletedTag {
+					id: id.clone(),
+					last_modified: changed_at,
+				},
+			})
+		});
+
+		new_tags.chain(changed_tags).chain(deleted_tags).collect()
+	}
+
 	pub async fn sync_items(
 		&self,
 		kobo_api_base_url: &str,
+		config: &StumpConfig,
 	) -> Result<Vec<SyncItem>, DbErr> {
 		let items: Vec<MediaWithMetadataAndReadingSessions> =
 			MediaWithMetadataAndReadingSessions::find_by_ids_for_user(
 				&self.media_ids,
 				self.user,
 			)
-			.filter(media::Column::Extension.eq("epub"))
+			.filter(kobo_syncable_condition())
 			.into_model::<MediaWithMetadataAndReadingSessions>()
 			.all(self.db)
 			.await?;
 
-		let sync_items: Vec<SyncItem> = items
-			.into_iter()
-			.map(|m| {
-				let book_url =
-					format!("{}/v1/books/{}/file/epub", kobo_api_base_url, m.media.id);
+		// books in libraries which opted in to conversion since the last sync were never sent
+		// to the device, so they are new to it regardless of when they were created
+		let newly_converted_ids: HashSet<String> = match self.previous_sync_at {
+			Some(previous_sync_at) => media::Entity::find()
+				.select_only()
+				.column(media::Column::Id)
+				.filter(media::Column::Id.is_in(&self.media_ids))
+				.filter(kobo_conversion_enabled_since(previous_sync_at))
+				.into_tuple()
+				.all(self.db)
+				.await?
+				.into_iter()
+				.collect(),
+			None => HashSet::new(),
+		};
+
+		let mut sync_items = Vec::with_capacity(items.len());
+		for m in items {
+			let book_url =
+				format!("{}/v1/books/{}/file/epub", kobo_api_base_url, m.media.id);
+			let download_size = kobo_download_size(&m.media, config).await;
+
+			let Some(previous_sync_at) = self.previous_sync_at.filter(|ps| {
+				m.media.created_at < *ps && !newly_converted_ids.contains(&m.media.id)
+			}) else {
+				// the book is new to the device, so the entitlement carries its metadata
+				// and reading state.
+				sync_items.push(SyncItem::NewEntitlement(
+					BookEntitlementContainer::from_media(m, book_url, download_size),
+				));
+				continue;
+			};
 
-				let created_since_last_sync = self
-					.previous_sync_at
-					.is_none_or(|ps| m.media.created_at >= ps);
+			let metadata_changed = m
+				.media
+				.modified_at
+				.is_some_and(|modified_at| modified_at >= previous_sync_at);
+			let reading_state_changed = m.reading_state_changed_since(previous_sync_at);
 
-				if created_since_last_sync {
-					SyncItem::NewEntitlement(BookEntitlementContainer::from_media(
-						m, book_url,
-					))
-				} else {
-					SyncItem::ChangedProductMetadata(BookMetadata::from_media(
-						&m, book_url,
-					))
-				}
-			})
-			.collect();
+			if metadata_changed || !reading_state_changed {
+				sync_items.push(SyncItem::ChangedProductMetadata(
+					BookMetadata::from_media(&m, book_url, download_size),
+				));
+			}
+			if reading_state_changed {
+				sync_items.push(SyncItem::ChangedReadingState(ReadingStateContainer {
+					reading_state: ReadingState::from_media(&m),
+				}));
+			}
+		}
+		sync_items.extend(self.tag_sync_items());
 
 		tracing::debug!(
 			?self.offset,
@@ -289,11 +425,13 @@ impl<'a> SyncPage<'a> {
 
 #[cfg(test)]
 mod tests {
-	use chrono::Days;
-	use models::entity::{kobo_sync_session, user};
+	use chrono::{Days, Utc};
+	use models::entity::{
+		kobo_sync_session, library_config, reading_list, reading_list_item, user,
+	};
 	use sea_orm::prelude::DateTimeWithTimeZone;
 	use sea_orm::query::*;
-	use sea_orm::EntityTrait;
+	use sea_orm::{ActiveModelTrait, EntityTrait, ModelTrait, Set};
 	use tests::db::test_database;
 	use tests::fake_data;
 
@@ -301,7 +439,10 @@ mod tests {
 		sync::{KoboSync, SyncPage},
 		sync_token::SyncToken,
 	};
-	use stump_core::kobo::sync_types::SyncItem;
+	use stump_core::{
+		config::StumpConfig,
+		kobo::sync_types::{Format, SyncItem},
+	};
 
 	#[tokio::test]
 	async fn test_first_sync() {
@@ -524,7 +665,7 @@ mod tests {
 			Some(previous_sync_at),
 		);
 		let sync_items = sync_page
-			.sync_items("https://stump.example.org/")
+			.sync_items("https://stump.example.org/", &StumpConfig::debug())
 			.await
 			.expect("failed to retrieve sync items");
 
@@ -572,7 +713,7 @@ mod tests {
 			Some(previous_sync_at),
 		);
 		let sync_items = sync_page
-			.sync_items("https://stump.example.org/")
+			.sync_items("https://stump.example.org/", &StumpConfig::debug())
 			.await
 			.expect("failed to retrieve sync items");
 
@@ -583,6 +724,308 @@ mod tests {
 		assert_eq!(new_book.id, ent.entitlement_id);
 	}
 
+	#[tokio::test]
+	async fn test_represent_changed_reading_state() {
+		let db = test_database().await;
+
+		let user = fake_data::User::new("ishmael").insert(&db).await;
+		let user = user::AuthUser {
+			id: user.id,
+			permissions: vec![],
+			..Default::default()
+		};
+
+		let previous_sync_at: DateTimeWithTimeZone =
+			"2026-01-01T00:00:00Z".parse().unwrap();
+
+		let series = fake_data::Series::default().insert(&db).await;
+
+		// a book that was synced before, and has since been read elsewhere.
+		let book = fake_data::Media {
+			series_id: series.id.clone(),
+			id: Some("old-book".to_string()),
+			created_at: Some(previous_sync_at.checked_sub_days(Days::new(1)).unwrap()),
+			..Default::default()
+		}
+		.insert(&db)
+		.await;
+
+		fake_data::ReadingSession {
+			media_id: book.id.clone(),
+			user_id: user.id.clone(),
+			percentage_completed: 0.25,
+		}
+		.insert(&db)
+		.await;
+
+		let sync_page = SyncPage::new(
+			&db,
+			&user,
+			"sync_1234".to_string(),
+			std::slice::from_ref(&book.id),
+			0,
+			10,
+			Some(previous_sync_at),
+		);
+		let sync_items = sync_page
+			.sync_items("https://stump.example.org/", &StumpConfig::debug())
+			.await
+			.expect("failed to retrieve sync items");
+
+		// the book's metadata didn't change, so only the reading state is sent.
+		assert_eq!(1, sync_items.len());
+		let SyncItem::ChangedReadingState(ref container) = sync_items[0] else {
+			panic!("expected a ChangedReadingState")
+		};
+
+		assert_eq!(book.id, container.reading_state.entitlement_id);
+		assert_eq!(
+			Some(25.0),
+			container.reading_state.current_bookmark.progress_percent
+		);
+	}
+
+	#[tokio::test]
+	async fn test_incremental_sync_includes_reading_progress() {
+		let db = test_database().await;
+
+		let user = fake_data::User::new("ishmael").insert(&db).await;
+		let series = fake_data::Series::default().insert(&db).await;
+
+		for i in 1..=2 {
+			fake_data::Media {
+				series_id: series.id.clone(),
+				id: Some(format!("book-{i}")),
+				created_at: Some("2026-01-01T00:00:00Z".parse().unwrap()),
+				..Default::default()
+			}
+			.insert(&db)
+			.await;
+		}
+
+		let user = user::AuthUser {
+			id: user.id,
+			permissions: vec![],
+			..Default::default()
+		};
+
+		let sync_page = KoboSync::next_page(
+			&db,
+			&user,
+			Some("kobo-1"),
+			serde_json::json!({}),
+			None,
+			5,
+		)
+		.await
+		.expect("failed to initiate sync");
+		assert_eq!(vec!["book-1", "book-2"], sync_page.media_ids);
+
+		// after the first sync the user started reading a book in the web reader.
+		fake_data::ReadingSession {
+			media_id: "book-2".to_string(),
+			user_id: user.id.clone(),
+			percentage_completed: 0.5,
+		}
+		.insert(&db)
+		.await;
+
+		let sync_page = KoboSync::next_page(
+			&db,
+			&user,
+			Some("kobo-1"),
+			serde_json::json!({}),
+			Some(&sync_page.sync_token),
+			5,
+		)
+		.await
+		.expect("failed to continue sync");
+
+		assert_eq!(vec!["book-2"], sync_page.media_ids);
+	}
+
+	#[tokio::test]
+	async fn test_reading_list_shelves() {
+		let db = test_database().await;
+
+		let user = fake_data::User::new("ishmael").insert(&db).await;
+		let series = fake_data::Series::default().insert(&db).await;
+
+		for i in 1..=2 {
+			fake_data::Media {
+				series_id: series.id.clone(),
+				id: Some(format!("book-{i}")),
+				..Default::default()
+			}
+			.insert(&db)
+			.await;
+		}
+
+		let reading_list = fake_data::ReadingList {
+			id: Some("whaling".to_string()),
+			name: Some("Whaling".to_string()),
+			creating_user_id: user.id.clone(),
+			media_ids: vec!["book-2".to_string(), "book-1".to_string()],
+		}
+		.insert(&db)
+		.await;
+
+		let user = user::AuthUser {
+			id: user.id,
+			permissions: vec![],
+			..Default::default()
+		};
+
+		// the first sync creates the shelf
+		let sync_page = KoboSync::next_page(
+			&db,
+			&user,
+			Some("kobo-1"),
+			serde_json::json!({}),
+			None,
+			5,
+		)
+		.await
+		.expect("failed to initiate sync");
+		let sync_items = sync_page
+			.sync_items("https://stump.example.org/", &StumpConfig::debug())
+			.await
+			.expect("failed to retrieve sync items");
+
+		let Some(SyncItem::NewTag(container)) = sync_items.last() else {
+			panic!("expected a NewTag")
+		};
+		assert_eq!("whaling", container.tag.id);
+		assert_eq!("Whaling", container.tag.name);
+		let revision_ids: Vec<&str> = container
+			.tag
+			.items
+			.iter()
+			.map(|item| item.revision_id.as_str())
+			.collect();
+		assert_eq!(vec!["book-2", "book-1"], revision_ids);
+
+		// renaming the reading list changes the shelf
+		let mut active_model: reading_list::ActiveModel = reading_list.into();
+		active_model.name = Set("Whaling (revised)".to_string());
+		let reading_list = active_model.update(&db).await.unwrap();
+
+		let sync_page = KoboSync::next_page(
+			&db,
+			&user,
+			Some("kobo-1"),
+			serde_json::json!({}),
+			Some(&sync_page.sync_token),
+			5,
+		)
+		.await
+		.expect("failed to continue sync");
+		let sync_items = sync_page
+			.sync_items("https://stump.example.org/", &StumpConfig::debug())
+			.await
+			.expect("failed to retrieve sync items");
+
+		assert_eq!(1, sync_items.len());
+		let SyncItem::ChangedTag(ref container) = sync_items[0] else {
+			panic!("expected a ChangedTag")
+		};
+		assert_eq!("Whaling (revised)", container.tag.name);
+
+		// deleting the reading list deletes the shelf
+		reading_list_item::Entity::delete_many()
+			.exec(&db)
+			.await
+			.unwrap();
+		reading_list.delete(&db).await.unwrap();
+
+		let sync_page = KoboSync::next_page(
+			&db,
+			&user,
+			Some("kobo-1"),
+			serde_json::json!({}),
+			Some(&sync_page.sync_token),
+			5,
+		)
+		.await
+		.expect("failed to continue sync");
+		let sync_items = sync_page
+			.sync_items("https://stump.example.org/", &StumpConfig::debug())
+			.await
+			.expect("failed to retrieve sync items");
+
+		assert_eq!(1, sync_items.len());
+		let SyncItem::DeletedTag(ref container) = sync_items[0] else {
+			panic!("expected a DeletedTag")
+		};
+		assert_eq!("whaling", container.tag.id);
+	}
+
+	#[tokio::test]
+	async fn test_shelves_sent_with_final_page() {
+		let db = test_database().await;
+
+		let user = fake_data::User::new("ishmael").insert(&db).await;
+		let series = fake_data::Series::default().insert(&db).await;
+
+		for i in 1..=3 {
+			fake_data::Media {
+				series_id: series.id.clone(),
+				id: Some(format!("book-{i}")),
+				..Default::default()
+			}
+			.insert(&db)
+			.await;
+		}
+
+		fake_data::ReadingList {
+			creating_user_id: user.id.clone(),
+			media_ids: vec!["book-3".to_string()],
+			..Default::default()
+		}
+		.insert(&db)
+		.await;
+
+		let user = user::AuthUser {
+			id: user.id,
+			permissions: vec![],
+			..Default::default()
+		};
+
+		let first_page = KoboSync::next_page(
+			&db,
+			&user,
+			Some("kobo-1"),
+			serde_json::json!({}),
+			None,
+			2,
+		)
+		.await
+		.expect("failed to initiate sync");
+		let sync_items = first_page
+			.sync_items("https://stump.example.org/", &StumpConfig::debug())
+			.await
+			.expect("failed to retrieve sync items");
+		assert!(!sync_items
+			.iter()
+			.any(|item| matches!(item, SyncItem::NewTag(_))));
+
+		let second_page = KoboSync::next_page(
+			&db,
+			&user,
+			Some("kobo-1"),
+			serde_json::json!({}),
+			Some(&first_page.sync_token),
+			2,
+		)
+		.await
+		.expect("failed to continue sync");
+		let sync_items = second_page
+			.sync_items("https://stump.example.org/", &StumpConfig::debug())
+			.await
+			.expect("failed to retrieve sync items");
+		assert!(matches!(sync_items.last(), Some(SyncItem::NewTag(_))));
+	}
+
 	#[tokio::test]
 	async fn test_sync_pruning() {
 		let db = test_database().await;
@@ -688,4 +1131,149 @@ mod tests {
 		// we don't include CBZs or PDFs
 		assert_eq!(vec!["don-quixote",], sync_page.media_ids,);
 	}
+
+	#[tokio::test]
+	async fn test_includes_converted_books_when_library_opts_in() {
+		let db = test_database().await;
+
+		let user = fake_data::User::new("ishmael").insert(&db).await;
+		let comics = fake_data::Library {
+			kobo_convert_to_epub: true,
+			..Default::default()
+		}
+		.insert(&db)
+		.await;
+		let books = fake_data::Library::default().insert(&db).await;
+
+		let comic_series = fake_data::Series::in_library(&comics.id).insert(&db).await;
+		let book_series = fake_data::Series::in_library(&books.id).insert(&db).await;
+
+		fake_data::Media {
+			series_id: comic_series.id.clone(),
+			id: Some("action-comics-i".to_string()),
+			created_at: Some("1938-04-18T00:00:00Z".parse().unwrap()),
+			extension: Some("cbz".to_string()),
+			..Default::default()
+		}
+		.insert(&db)
+		.await;
+
+		fake_data::Media {
+			series_id: comic_series.id.clone(),
+			id: Some("detective-comics-i".to_string()),
+			created_at: Some("1937-03-01T00:00:00Z".parse().unwrap()),
+			extension: Some("txt".to_string()),
+			..Default::default()
+		}
+		.insert(&db)
+		.await;
+
+		fake_data::Media {
+			series_id: book_series.id.clone(),
+			id: Some("voynich-manuscript".to_string()),
+			created_at: Some("1400-01-01T00:00:00Z".parse().unwrap()),
+			extension: Some("pdf".to_string()),
+			..Default::default()
+		}
+		.insert(&db)
+		.await;
+
+		let user = user::AuthUser {
+			id: user.id,
+			permissions: vec![],
+			..Default::default()
+		};
+		let sync_page = KoboSync::next_page(
+			&db,
+			&user,
+			Some("kobo-1"),
+			serde_json::json!({}),
+			None,
+			10,
+		)
+		.await
+		.expect("failed to initiate sync");
+
+		// only convertible books in the opted in library are included
+		assert_eq!(vec!["action-comics-i"], sync_page.media_ids);
+
+		let temp_dir = tempfile::tempdir().unwrap();
+		let config = StumpConfig::new(temp_dir.path().to_string_lossy().to_string());
+		let items = sync_page
+			.sync_items("http://localhost", &config)
+			.await
+			.expect("failed to generate sync items");
+		let SyncItem::NewEntitlement(ref ent) = items[0] else {
+			panic!("expected a NewEntitlement")
+		};
+		assert!(matches!(
+			ent.book_metadata.download_urls[0].format,
+			Format::EPUB3FL
+		));
+	}
+
+	#[tokio::test]
+	async fn test_incremental_sync_includes_books_once_library_opts_in() {
+		let db = test_database().await;
+
+		let user = fake_data::User::new("ishmael").insert(&db).await;
+		let comics = fake_data::Library::default().insert(&db).await;
+		let series = fake_data::Series::in_library(&comics.id).insert(&db).await;
+
+		fake_data::Media {
+			series_id: series.id.clone(),
+			id: Some("action-comics-i".to_string()),
+			created_at: Some("1938-04-18T00:00:00Z".parse().unwrap()),
+			extension: Some("cbz".to_string()),
+			..Default::default()
+		}
+		.insert(&db)
+		.await;
+
+		let user = user::AuthUser {
+			id: user.id,
+			permissions: vec![],
+			..Default::default()
+		};
+		let sync_page = KoboSync::next_page(
+			&db,
+			&user,
+			Some("kobo-1"),
+			serde_json::json!({}),
+			None,
+			10,
+		)
+		.await
+		.expect("failed to initiate sync");
+		assert!(sync_page.media_ids.is_empty());
+
+		// the library opts in after the device synced, long after the comic was created
+		library_config::ActiveModel {
+			id: Set(comics.config_id),
+			kobo_convert_to_epub: Set(true),
+			kobo_convert_enabled_at: Set(Some(Utc::now().into())),
+			..Default::default()
+		}
+		.update(&db)
+		.await
+		.unwrap();
+
+		let sync_page = KoboSync::next_page(
+			&db,
+			&user,
+			Some("kobo-1"),
+			serde_json::json!({}),
+			Some(&sync_page.sync_token),
+			10,
+		)
+		.await
+		.expect("failed to continue sync");
+		assert_eq!(vec!["action-comics-i"], sync_page.media_ids);
+
+		let items = sync_page
+			.sync_items("http://localhost", &StumpConfig::debug())
+			.await
+			.expect("failed to generate sync items");
+		assert!(matches!(items[0], SyncItem::NewEntitlement(_)));
+	}
 }
@@ -0,0 +1,125 @@
+//! Kobo shelves (tags) are built from the reading lists and smart lists a user has access
+//! to. Each sync session stores a snapshot of the shelves, so that the next session can
+//! send only the shelves which were created, changed or deleted in the meantime.
+
+use std::collections::HashSet;
+
+use graphql::{
+	input::smart_lists::SmartListFilterGroupInput,
+	query::smart_lists_builder::build_books_query,
+};
+use models::{
+	entity::{
+		kobo_sync_session::{KoboTag, KoboTags},
+		media, reading_list, reading_list_item, smart_list,
+		user::AuthUser,
+	},
+	shared::enums::{AccessRole, UserPermission},
+};
+use sea_orm::{prelude::*, QueryOrder, QuerySelect, QueryTrait};
+use stump_core::kobo::conversion::kobo_syncable_condition;
+
+/// A query for the IDs of all media which are synced to the user's Kobo. Shelves may only
+/// reference these books.
+fn syncable_media_ids_query(user: &AuthUser) -> sea_orm::sea_query::SelectStatement {
+	media::Entity::find_for_user(user)
+		.select_only()
+		.column(media::Column::Id)
+		.filter(kobo_syncable_condition())
+		.into_query()
+}
+
+async fn reading_list_tags(
+	db: &DatabaseConnection,
+	user: &AuthUser,
+) -> Result<Vec<KoboTag>, DbErr> {
+	let reading_lists = reading_list::Entity::find_for_user(user, AccessRole::Reader)
+		.all(db)
+		.await?;
+
+	let reading_list_ids: Vec<String> =
+		reading_lists.iter().map(|rl| rl.id.clone()).collect();
+	let items = reading_list_item::Entity::find()
+		.filter(reading_list_item::Column::ReadingListId.is_in(reading_list_ids))
+		.filter(
+			reading_list_item::Column::MediaId
+				.in_subquery(syncable_media_ids_query(user)),
+		)
+		.order_by_asc(reading_list_item::Column::DisplayOrder)
+		.all(db)
+		.await?;
+
+	Ok(reading_lists
+		.into_iter()
+		.map(|rl| KoboTag {
+			media_ids: items
+				.iter()
+				.filter(|item| item.reading_list_id == rl.id)
+				.map(|item| item.media_id.clone())
+				.collect(),
+			id: rl.id,
+			name: rl.name,
+		})
+		.collect())
+}
+
+async fn smart_list_tags(
+	db: &DatabaseConnection,
+	user: &AuthUser,
+) -> Result<Vec<KoboTag>, DbErr> {
+	if !user.has_permission(UserPermission::AccessSmartList) {
+		return Ok(vec![]);
+	}
+
+	let smart_lists = smart_list::Entity::find_for_user(user, false, false, None)
+		.all(db)
+		.await?;
+
+	let mut tags = Vec::with_capacity(smart_lists.len());
+	for smart_list in smart_lists {
+		let filters: Vec<SmartListFilterGroupInput> =
+			match serde_json::from_slice(&smart_list.filters) {
+				Ok(filters) => filters,
+				Err(error) => {
+					tracing::warn!(
+						?error,
+						smart_list_id = smart_list.id,
+						"Failed to deserialize smart list filters, skipping Kobo shelf"
+					);
+					continue;
+				},
+			};
+
+		let media_ids: Vec<String> =
+			build_books_query(user, smart_list.joiner, &filters, None)
+				.filter(kobo_syncable_condition())
+				.select_only()
+				.column(media::Column::Id)
+				.into_tuple()
+				.all(db)
+				.await?;
+
+		tags.push(KoboTag {
+			id: smart_list.id,
+			name: smart_list.name,
+			media_ids,
+		});
+	}
+
+	Ok(tags)
+}
+
+/// Build the shelves which should currently be present on the user's Kobo
+pub async fn current_tags(
+	db: &DatabaseConnection,
+	user: &AuthUser,
+) -> Result<KoboTags, DbErr> {
+	let mut tags = reading_list_tags(db, user).await?;
+	tags.extend(smart_list_tags(db, user).await?);
+
+	// The device keys shelves by ID, so each shelf may only be sent once
+	let mut seen = HashSet::new();
+	tags.retain(|tag| seen.insert(tag.id.clone()));
+
+	Ok(KoboTags(tags))
+}
@@ -12,8 +12,8 @@ use chrono::Utc;
 use graphql::{data::AuthContext, pagination::OffsetPagination};
 use models::{
 	entity::{
-		finished_reading_session, library, media, media_metadata, reading_session,
-		series, series_metadata,
+		collection, collection_item, finished_reading_session, library, media,
+		media_metadata, reading_session, series, series_metadata,
 	},
 	shared::image_processor_options::{ImageProcessorOptions, SupportedImageFormat},
 };
@@ -71,6 +71,12 @@ pub(crate) fn mount(app_state: AppState) -> Router<AppState> {
 				.route("/latest", get(get_latest_series))
 				.route("/{id}", get(get_series_by_id)),
 		)
+		.nest(
+			"/collections",
+			Router::new()
+				.route("/", get(get_collections))
+				.route("/{id}", get(get_collection_by_id)),
+		)
 		.nest(
 			"/books",
 			Router::new()
@@ -198,6 +204,20 @@ async fn catalog(Extension(req): Extension<AuthContext>) -> APIResult<Xml> {
 			}]),
 			None,
 		),
+		OpdsEntry::new(
+			"allCollections".to_string(),
+			Utc::now().into(),
+			"All collections".to_string(),
+			None,
+			Some(String::from("Browse by collection")),
+			None,
+			Some(vec![OpdsLink {
+				link_type: OpdsLinkType::Navigation,
+				rel: OpdsLinkRel::Subsection,
+				href: catalog_url(&req, "collections"),
+			}]),
+			None,
+		),
 		OpdsEntry::new(
 			"allLibraries".to_string(),
 			Utc::now().into(),
@@ -573,6 +593,100 @@ async fn get_series_by_id(
 	Ok(Xml(feed.build()?))
 }
 
+/// A handler for GET /opds/v1.2/collections, accepts a `page` URL param
+async fn get_collections(
+	State(ctx): State<AppState>,
+	pagination: Query<OffsetPagination>,
+	Extension(req): Extension<AuthContext>,
+) -> APIResult<Xml> {
+	let user = req.user();
+	let collections = collection::Entity::find_for_user(&user)
+		.order_by_asc(collection::Column::Name)
+		.offset(pagination.offset())
+		.limit(pagination.limit())
+		.all(ctx.conn.as_ref())
+		.await?;
+	let count = collection::Entity::find_for_user(&user)
+		.count(ctx.conn.as_ref())
+		.await?;
+
+	let entries = collections
+		.into_iter()
+		.map(|c| {
+			OPDSEntryBuilder::<collection::Model>::new(c, req.api_key()).into_opds_entry()
+		})
+		.collect::<Vec<OpdsEntry>>();
+
+	let feed = OPDSFeedBuilder::new(req.api_key()).paginated(OPDSFeedBuilderParams {
+		id: "allCollections".to_string(),
+		title: "All Collections".to_string(),
+		entries,
+		href_postfix: "collections".to_string(),
+		page_params: Some(OPDSFeedBuilderPageParams {
+			page: pagination.page,
+			count,
+		}),
+		search: None,
+	})?;
+
+	Ok(Xml(feed.build()?))
+}
+
+/// A handler for GET /opds/v1.2/collections/{id}, which lists the series in the
+/// collection in their collection order
+async fn get_collection_by_id(
+	Path(OPDSURLParams {
+		params: OPDSIDURLParams { id },
+		..
+	}): Path<OPDSURLParams<OPDSIDURLParams>>,
+	State(ctx): State<AppState>,
+	pagination: Query<OffsetPagination>,
+	Extension(req): Extension<AuthContext>,
+) -> APIResult<Xml> {
+	let user = req.user();
+	let collection = collection::Entity::find_for_user(&user)
+		.filter(collection::Column::Id.eq(id.clone()))
+		.one(ctx.conn.as_ref())
+		.await?
+		.ok_or(APIError::NotFound(format!("Collection {id} not found")))?;
+
+	let series = collection_item::series_in_collection(
+		series::Entity::find_for_user(&user),
+		&collection.id,
+	)
+	.offset(pagination.offset())
+	.limit(pagination.limit())
+	.all(ctx.conn.as_ref())
+	.await?;
+	let count = collection_item::series_in_collection(
+		series::Entity::find_for_user(&user),
+		&collection.id,
+	)
+	.count(ctx.conn.as_ref())
+	.await?;
+
+	let entries = series
+		.into_iter()
+		.map(|s| {
+			OPDSEntryBuilder::<series::Model>::new(s, req.api_key()).into_opds_entry()
+		})
+		.collect::<Vec<OpdsEntry>>();
+
+	let feed = OPDSFeedBuilder::new(req.api_key()).paginated(OPDSFeedBuilderParams {
+		id: collection.id.clone(),
+		title: collection.name,
+		entries,
+		href_postfix: format!("collections/{}", &collection.id),
+		page_params: Some(OPDSFeedBuilderPageParams {
+			page: pagination.page,
+			count,
+		}),
+		search: None,
+	})?;
+
+	Ok(Xml(feed.build()?))
+}
+
 /// A handler for GET /opds/v1.2/search/feed, unified search returning libraries + series + books
 async fn search_feed(
 	State(ctx): State<AppState>,
@@ -10,8 +10,8 @@ use axum::{
 };
 use graphql::{data::AuthContext, pagination::OffsetPagination};
 use models::entity::{
-	library, media, media_metadata, reading_session, registered_reading_device, series,
-	series_metadata, user::AuthUser,
+	collection, collection_item, library, media, media_metadata, reading_session,
+	registered_reading_device, series, series_metadata, user::AuthUser,
 };
 use sea_orm::{prelude::*, Condition, Order, QueryOrder, QueryTrait};
 use sea_orm::{PaginatorTrait, QuerySelect};
@@ -75,6 +75,12 @@ pub(crate) fn mount(app_state: AppState) -> Router<AppState> {
 						Router::new().route("/", get(browse_series_by_id)),
 					),
 				)
+				.nest(
+					"/collections",
+					Router::new()
+						.route("/", get(browse_collections))
+						.route("/{id}", get(browse_collection_by_id)),
+				)
 				.nest(
 					"/books",
 					Router::new()
@@ -309,6 +315,42 @@ async fn catalog(
 		)
 		.build()?;
 
+	let collections = collection::Entity::find_for_user(&user)
+		.order_by_asc(collection::Column::Name)
+		.limit(DEFAULT_LIMIT)
+		.all(ctx.conn.as_ref())
+		.await?;
+	let collection_count = collection::Entity::find_for_user(&user)
+		.count(ctx.conn.as_ref())
+		.await?;
+	let collection_group = OPDSFeedGroupBuilder::default()
+		.metadata(
+			OPDSMetadataBuilder::default()
+				.title("Collections".to_string())
+				.pagination(Some(
+					OPDSPaginationMetadataBuilder::default()
+						.number_of_items(collection_count)
+						.items_per_page(DEFAULT_LIMIT)
+						.current_page(1)
+						.build()?,
+				))
+				.build()?,
+		)
+		.links(link_finalizer.finalize_all(vec![OPDSLink::Link(
+			OPDSBaseLinkBuilder::default()
+				.href("/opds/v2.0/collections".to_string())
+				.rel(OPDSLinkRel::SelfLink.item())
+				.build()?,
+		)]))
+		.navigation(
+			collections
+				.into_iter()
+				.map(OPDSNavigationLink::from)
+				.map(|link| link.finalize(&link_finalizer))
+				.collect::<Vec<OPDSNavigationLink>>(),
+		)
+		.build()?;
+
 	let latest_books = OPDSPublicationEntity::find_for_user(&user)
 		.limit(DEFAULT_LIMIT)
 		.order_by_desc(media::Column::CreatedAt)
@@ -417,16 +459,32 @@ async fn catalog(
 					.templated(true)
 					.build()?.as_link(),
 			]))
-			.navigation(vec![OPDSNavigationLinkBuilder::default()
-				.title("Libraries".to_string())
-				.base_link(
-					OPDSBaseLinkBuilder::default()
-						.href(link_finalizer.format_link("/opds/v2.0/libraries"))
-						.rel(OPDSLinkRel::Subsection.item())
-						.build()?,
-				)
-				.build()?])
-			.groups(vec![library_group, latest_books_group, keep_reading_group])
+			.navigation(vec![
+				OPDSNavigationLinkBuilder::default()
+					.title("Libraries".to_string())
+					.base_link(
+						OPDSBaseLinkBuilder::default()
+							.href(link_finalizer.format_link("/opds/v2.0/libraries"))
+							.rel(OPDSLinkRel::Subsection.item())
+							.build()?,
+					)
+					.build()?,
+				OPDSNavigationLinkBuilder::default()
+					.title("Collections".to_string())
+					.base_link(
+						OPDSBaseLinkBuilder::default()
+							.href(link_finalizer.format_link("/opds/v2.0/collections"))
+							.rel(OPDSLinkRel::Subsection.item())
+							.build()?,
+					)
+					.build()?,
+			])
+			.groups(vec![
+				library_group,
+				collection_group,
+				latest_books_group,
+				keep_reading_group,
+			])
 			.build()?,
 	))
 }
@@ -1115,6 +1173,158 @@ async fn browse_series_by_id(
 	.await
 }
 
+/// A helper function to generate a paginated OPDS feed of navigation links, e.g. to series
+/// or collections. This is not a route handler.
+fn generate_navigation_feed(
+	link_finalizer: OPDSLinkFinalizer,
+	navigation: Vec<OPDSNavigationLink>,
+	count: u64,
+	pagination: &OffsetPagination,
+	title: &str,
+	base_url: &str,
+) -> APIResult<Json<OPDSFeed>> {
+	let take = pagination.limit();
+	let next_page = pagination.next_page();
+	let previous_link = match pagination.previous_page() {
+		Some(page) => Some(
+			link_finalizer.finalize(OPDSLink::Link(
+				OPDSBaseLinkBuilder::default()
+					.href(format!("{base_url}?page={page}"))
+					.rel(OPDSLinkRel::Previous.item())
+					.build()?,
+			)),
+		),
+		None => None,
+	};
+	let has_more = (pagination.offset() + take) < count;
+	let next_link = (has_more).then_some(
+		link_finalizer.finalize(OPDSLink::Link(
+			OPDSBaseLinkBuilder::default()
+				.href(format!("{base_url}?page={next_page}"))
+				.rel(OPDSLinkRel::Next.item())
+				.build()?,
+		)),
+	);
+
+	let links = link_finalizer.finalize_all(chain_optional_iter(
+		[
+			OPDSLink::Link(
+				OPDSBaseLinkBuilder::default()
+					.href(base_url.to_string())
+					.rel(OPDSLinkRel::SelfLink.item())
+					.build()?,
+			),
+			OPDSLink::Link(
+				OPDSBaseLinkBuilder::default()
+					.href("/opds/v2.0/catalog".to_string())
+					.rel(OPDSLinkRel::Start.item())
+					.build()?,
+			),
+		],
+		[previous_link, next_link],
+	));
+
+	Ok(Json(
+		OPDSFeedBuilder::default()
+			.metadata(
+				OPDSMetadataBuilder::default()
+					.title(title.to_string())
+					.pagination(Some(
+						OPDSPaginationMetadataBuilder::default()
+							.number_of_items(count)
+							.items_per_page(take)
+							.current_page(pagination.page)
+							.build()?,
+					))
+					.build()?,
+			)
+			.links(links)
+			.navigation(
+				navigation
+					.into_iter()
+					.map(|link| link.finalize(&link_finalizer))
+					.collect::<Vec<OPDSNavigationLink>>(),
+			)
+			.build()?,
+	))
+}
+
+/// A route handler which returns a feed of the collections a user is able to see
+#[tracing::instrument(skip(ctx))]
+async fn browse_collections(
+	State(ctx): State<AppState>,
+	HostExtractor(host): HostExtractor,
+	pagination: Query<OffsetPagination>,
+	Extension(req): Extension<AuthContext>,
+) -> APIResult<Json<OPDSFeed>> {
+	let user = req.user();
+
+	let collections = collection::Entity::find_for_user(&user)
+		.limit(pagination.limit())
+		.offset(pagination.offset())
+		.order_by_asc(collection::Column::Name)
+		.all(ctx.conn.as_ref())
+		.await?;
+	let collection_count = collection::Entity::find_for_user(&user)
+		.count(ctx.conn.as_ref())
+		.await?;
+
+	generate_navigation_feed(
+		OPDSLinkFinalizer::from(host),
+		collections
+			.into_iter()
+			.map(OPDSNavigationLink::from)
+			.collect(),
+		collection_count,
+		&pagination,
+		"Browse Collections",
+		"/opds/v2.0/collections",
+	)
+}
+
+/// A route handler which returns a feed of the series in a collection, in their
+/// collection order
+#[tracing::instrument(skip(ctx))]
+async fn browse_collection_by_id(
+	State(ctx): State<AppState>,
+	HostExtractor(host): HostExtractor,
+	pagination: Query<OffsetPagination>,
+	Path(id): Path<String>,
+	Extension(req): Extension<AuthContext>,
+) -> APIResult<Json<OPDSFeed>> {
+	let user = req.user();
+
+	let collection = collection::Entity::find_for_user(&user)
+		.filter(collection::Column::Id.eq(id.clone()))
+		.one(ctx.conn.as_ref())
+		.await?
+		.ok_or(APIError::NotFound("Collection not found".to_string()))?;
+
+	let series = collection_item::series_in_collection(
+		series::Entity::find_for_user(&user),
+		&collection.id,
+	)
+	.limit(pagination.limit())
+	.offset(pagination.offset())
+	.all(ctx.conn.as_ref())
+	.await?;
+	let series_count = collection_item::series_in_collection(
+		series::Entity::find_for_user(&user),
+		&collection.id,
+	)
+	.count(ctx.conn.as_ref())
+	.await?;
+
+	generate_navigation_feed(
+		OPDSLinkFinalizer::from(host),
+		series.into_iter().map(OPDSNavigationLink::from).collect(),
+		series_count,
+		&pagination,
+		&collection.name,
+		&format!("/opds/v2.0/collections/{id}"),
+	)
+}
+
 /// A route handler which returns a feed of books for a user
 #[tracing::instrument(skip(ctx))]
 async fn browse_books(
@@ -4,7 +4,7 @@ use axum::{
 	body::Body,
 	extract::Request,
 	http::{header, HeaderMap},
-	response::IntoResponse,
+	response::{IntoResponse, Response},
 };
 use graphql::data::AuthContext;
 use models::{
@@ -39,15 +39,24 @@ pub async fn serve_media_file(
 		.await?
 		.ok_or(APIError::NotFound("Book not found".to_string()))?;
 
+	serve_file_as_attachment(headers, std::path::Path::new(&book.path), None).await
+}
+
+/// Serves a file from disk as an attachment. The filename sent to the client defaults to
+/// the name of the file on disk.
+pub async fn serve_file_as_attachment(
+	headers: HeaderMap,
+	path: &std::path::Path,
+	filename: Option<&str>,
+) -> APIResult<Response> {
 	// Note: I am reusing the original headers to support range requests
 	let mut serve_req = Request::new(Body::empty());
 	*serve_req.headers_mut() = headers;
 
-	match ServeFile::new(&book.path).try_call(serve_req).await {
+	match ServeFile::new(path).try_call(serve_req).await {
 		Ok(mut response) => {
-			if let Some(filename) = std::path::Path::new(&book.path)
-				.file_name()
-				.and_then(|os_str| os_str.to_str())
+			if let Some(filename) =
+				filename.or_else(|| path.file_name().and_then(|os_str| os_str.to_str()))
 			{
 				response.headers_mut().insert(
 					header::CONTENT_DISPOSITION,
@@ -56,10 +65,10 @@ pub async fn serve_media_file(
 						.unwrap_or_else(|_| "attachment".parse().unwrap()),
 				);
 			}
-			Ok(response)
+			Ok(response.into_response())
 		},
 		Err(e) => {
-			tracing::error!(error = ?e, path = %book.path, "Error serving media file");
+			tracing::error!(error = ?e, ?path, "Error serving media file");
 			Err(APIError::InternalServerError(format!(
 				"Failed to serve file: {}",
 				e
@@ -5,7 +5,6 @@ use std::{
 	time::Instant,
 };
 
-use apalis::prelude::MemoryStorage;
 use criterion::{criterion_group, BenchmarkId, Criterion};
 use models::{
 	entity::{job, library, library_config, media, series},
@@ -19,7 +18,7 @@ use stump_core::{
 	config::StumpConfig,
 	database::connect_at,
 	filesystem::scanner::LibraryScanJob,
-	job::{stump_job::StumpJob, ApalisWorkerState, JobContext, JobLifecycle},
+	job::{stump_job::StumpJob, ApalisWorkerState, JobContext, JobLifecycle, JobStorage},
 };
 use tempfile::{Builder as TempDirBuilder, TempDir};
 use tokio::{runtime::Builder, sync::broadcast};
@@ -216,6 +215,7 @@ async fn setup_test(
 		path: library.0.path.clone(),
 		config: Some(library.1.clone()),
 		options: Default::default(),
+		notify: false,
 	};
 
 	let job_id = Uuid::new_v4().to_string();
@@ -229,9 +229,10 @@ async fn setup_test(
 
 	let config_dir = format!("{}/benches/config", env!("CARGO_MANIFEST_DIR"));
 	let config = StumpConfig::new(config_dir);
-	let job_storage = MemoryStorage::new();
+	let conn = Arc::new(conn);
+	let job_storage = JobStorage::new(conn.clone());
 	let job_ctx = Arc::new(ApalisWorkerState::new(
-		Arc::new(conn),
+		conn,
 		Arc::new(config),
 		broadcast::channel(1024).0,
 		job_storage,
@@ -311,11 +312,7 @@ async fn scan_new_library(test_ctx: TestCtx) {
 	let handle = JobContext::new(
 		job_ctx,
 		job_id,
-		&StumpJob::LibraryScan {
-			id: job.id.clone(),
-			path: job.path.clone(),
-			options: Some(job.options),
-		},
+		&StumpJob::library_scan(job.id.clone(), job.path.clone(), Some(job.options)),
 	)
 	.await
 	.expect("Failed to start job context");
@@ -39,6 +39,16 @@ pub mod env_keys {
 	pub const SESSION_EXPIRY_INTERVAL_KEY: &str = "SESSION_EXPIRY_CLEANUP_INTERVAL";
 	pub const MAX_SCANNER_CONCURRENCY_KEY: &str = "STUMP_MAX_SCANNER_CONCURRENCY";
 	pub const MAX_THUMBNAIL_CONCURRENCY_KEY: &str = "STUMP_MAX_THUMBNAIL_CONCURRENCY";
+	pub const MAX_CONCURRENT_JOBS_KEY: &str = "STUMP_MAX_CONCURRENT_JOBS";
+	pub const MAX_CONCURRENT_SCAN_JOBS_KEY: &str = "STUMP_MAX_CONCURRENT_SCAN_JOBS";
+	pub const MAX_CONCURRENT_METADATA_JOBS_KEY: &str =
+		"STUMP_MAX_CONCURRENT_METADATA_JOBS";
+	pub const MAX_CONCURRENT_THUMBNAIL_JOBS_KEY: &str =
+		"STUMP_MAX_CONCURRENT_THUMBNAIL_JOBS";
+	pub const MAX_CONCURRENT_ANALYSIS_JOBS_KEY: &str =
+		"STUMP_MAX_CONCURRENT_ANALYSIS_JOBS";
+	pub const TASK_MAX_ATTEMPTS_KEY: &str = "STUMP_TASK_MAX_ATTEMPTS";
+	pub const TASK_RETRY_BACKOFF_SECS_KEY: &str = "STUMP_TASK_RETRY_BACKOFF_SECS";
 	pub const MAX_IMAGE_UPLOAD_SIZE_KEY: &str = "STUMP_MAX_IMAGE_UPLOAD_SIZE";
 	pub const ENABLE_UPLOAD_KEY: &str = "STUMP_ENABLE_UPLOAD";
 	pub const MAX_FILE_UPLOAD_SIZE_KEY: &str = "STUMP_MAX_FILE_UPLOAD_SIZE";
@@ -70,6 +80,13 @@ pub mod defaults {
 	pub const DEFAULT_SESSION_EXPIRY_CLEANUP_INTERVAL: u64 = 60 * 60 * 24; // 24 hours
 	pub const DEFAULT_MAX_SCANNER_CONCURRENCY: usize = 200;
 	pub const DEFAULT_MAX_THUMBNAIL_CONCURRENCY: usize = 10;
+	pub const DEFAULT_MAX_CONCURRENT_JOBS: usize = 4; // One for each job category
+	pub const DEFAULT_MAX_CONCURRENT_SCAN_JOBS: usize = 1;
+	pub const DEFAULT_MAX_CONCURRENT_METADATA_JOBS: usize = 1;
+	pub const DEFAULT_MAX_CONCURRENT_THUMBNAIL_JOBS: usize = 1;
+	pub const DEFAULT_MAX_CONCURRENT_ANALYSIS_JOBS: usize = 1;
+	pub const DEFAULT_TASK_MAX_ATTEMPTS: u32 = 3;
+	pub const DEFAULT_TASK_RETRY_BACKOFF_SECS: u64 = 1;
 	pub const DEFAULT_MAX_IMAGE_UPLOAD_SIZE: usize = 20 * 1024 * 1024; // 20 MB
 	pub const DEFAULT_ENABLE_UPLOAD: bool = false;
 	pub const DEFAULT_MAX_FILE_UPLOAD_SIZE: usize = 20 * 1024 * 1024; // 20 MB
@@ -233,6 +250,47 @@ pub struct StumpConfig {
 	#[env_key(MAX_THUMBNAIL_CONCURRENCY_KEY)]
 	pub max_thumbnail_concurrency: usize,
 
+	/// The maximum number of jobs of any kind which may run at the same time. This defaults to
+	/// one for each job category, so jobs of different kinds can run side by side while the
+	/// per-category limits below keep jobs of the same kind from contending for the database.
+	#[default_value(DEFAULT_MAX_CONCURRENT_JOBS)]
+	#[env_key(MAX_CONCURRENT_JOBS_KEY)]
+	pub max_concurrent_jobs: usize,
+
+	/// The maximum number of library and series scans which may run at the same time.
+	#[default_value(DEFAULT_MAX_CONCURRENT_SCAN_JOBS)]
+	#[env_key(MAX_CONCURRENT_SCAN_JOBS_KEY)]
+	pub max_concurrent_scan_jobs: usize,
+
+	/// The maximum number of metadata fetch jobs which may run at the same time.
+	#[default_value(DEFAULT_MAX_CONCURRENT_METADATA_JOBS)]
+	#[env_key(MAX_CONCURRENT_METADATA_JOBS_KEY)]
+	pub max_concurrent_metadata_jobs: usize,
+
+	/// The maximum number of thumbnail and placeholder generation jobs which may run at the
+	/// same time.
+	#[default_value(DEFAULT_MAX_CONCURRENT_THUMBNAIL_JOBS)]
+	#[env_key(MAX_CONCURRENT_THUMBNAIL_JOBS_KEY)]
+	pub max_concurrent_thumbnail_jobs: usize,
+
+	/// The maximum number of analysis jobs (e.g., media analysis, duplicate detection, metadata
+	/// write back and conversion) which may run at the same time.
+	#[default_value(DEFAULT_MAX_CONCURRENT_ANALYSIS_JOBS)]
+	#[env_key(MAX_CONCURRENT_ANALYSIS_JOBS_KEY)]
+	pub max_concurrent_analysis_jobs: usize,
+
+	/// The number of times a failed job task is attempted, including the first attempt. Some
+	/// jobs, e.g. scans, never retry their tasks regardless of this value.
+	#[default_value(DEFAULT_TASK_MAX_ATTEMPTS)]
+	#[env_key(TASK_MAX_ATTEMPTS_KEY)]
+	pub task_max_attempts: u32,
+
+	/// The number of seconds to wait before the first retry of a failed job task. Each retry
+	/// after it waits twice as long as the one before.
+	#[default_value(DEFAULT_TASK_RETRY_BACKOFF_SECS)]
+	#[env_key(TASK_RETRY_BACKOFF_SECS_KEY)]
+	pub task_retry_backoff_secs: u64,
+
 	/// The maximum file size, in bytes, of images that can be uploaded, e.g., as thumbnails for users,
 	/// libraries, series, or media.
 	#[default_value(DEFAULT_MAX_IMAGE_UPLOAD_SIZE)]
@@ -400,6 +458,11 @@ impl StumpConfig {
 		PathBuf::from(&self.config_dir).join("emojis")
 	}
 
+	/// Returns a `PathBuf` to the Stump database backups directory
+	pub fn get_backups_dir(&self) -> PathBuf {
+		PathBuf::from(&self.config_dir).join("backups")
+	}
+
 	/// Returns a `PathBuf` to the PDF page cache directory
 	pub fn get_pdf_cache_dir(&self) -> PathBuf {
 		self.get_cache_dir().join("pdf_pages")
@@ -480,6 +543,13 @@ mod tests {
 			expired_session_cleanup_interval: None,
 			max_scanner_concurrency: None,
 			max_thumbnail_concurrency: None,
+			max_concurrent_jobs: None,
+			max_concurrent_scan_jobs: None,
+			max_concurrent_metadata_jobs: None,
+			max_concurrent_thumbnail_jobs: None,
+			max_concurrent_analysis_jobs: None,
+			task_max_attempts: None,
+			task_retry_backoff_secs: None,
 			max_image_upload_size: None,
 			enable_upload: None,
 			max_file_upload_size: None,
@@ -533,6 +603,15 @@ mod tests {
 				),
 				max_scanner_concurrency: Some(DEFAULT_MAX_SCANNER_CONCURRENCY),
 				max_thumbnail_concurrency: Some(DEFAULT_MAX_THUMBNAIL_CONCURRENCY),
+				max_concurrent_jobs: Some(DEFAULT_MAX_CONCURRENT_JOBS),
+				max_concurrent_scan_jobs: Some(DEFAULT_MAX_CONCURRENT_SCAN_JOBS),
+				max_concurrent_metadata_jobs: Some(DEFAULT_MAX_CONCURRENT_METADATA_JOBS),
+				max_concurrent_thumbnail_jobs: Some(
+					DEFAULT_MAX_CONCURRENT_THUMBNAIL_JOBS
+				),
+				max_concurrent_analysis_jobs: Some(DEFAULT_MAX_CONCURRENT_ANALYSIS_JOBS),
+				task_max_attempts: Some(DEFAULT_TASK_MAX_ATTEMPTS),
+				task_retry_backoff_secs: Some(DEFAULT_TASK_RETRY_BACKOFF_SECS),
 				max_image_upload_size: Some(DEFAULT_MAX_IMAGE_UPLOAD_SIZE),
 				enable_upload: Some(DEFAULT_ENABLE_UPLOAD),
 				max_file_upload_size: Some(DEFAULT_MAX_FILE_UPLOAD_SIZE),
@@ -603,6 +682,16 @@ mod tests {
 
 						max_scanner_concurrency: DEFAULT_MAX_SCANNER_CONCURRENCY,
 						max_thumbnail_concurrency: DEFAULT_MAX_THUMBNAIL_CONCURRENCY,
+						max_concurrent_jobs: DEFAULT_MAX_CONCURRENT_JOBS,
+						max_concurrent_scan_jobs: DEFAULT_MAX_CONCURRENT_SCAN_JOBS,
+						max_concurrent_metadata_jobs:
+							DEFAULT_MAX_CONCURRENT_METADATA_JOBS,
+						max_concurrent_thumbnail_jobs:
+							DEFAULT_MAX_CONCURRENT_THUMBNAIL_JOBS,
+						max_concurrent_analysis_jobs:
+							DEFAULT_MAX_CONCURRENT_ANALYSIS_JOBS,
+						task_max_attempts: DEFAULT_TASK_MAX_ATTEMPTS,
+						task_retry_backoff_secs: DEFAULT_TASK_RETRY_BACKOFF_SECS,
 						max_image_upload_size: DEFAULT_MAX_IMAGE_UPLOAD_SIZE,
 						enable_upload: DEFAULT_ENABLE_UPLOAD,
 						max_file_upload_size: DEFAULT_MAX_FILE_UPLOAD_SIZE,
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use apalis::prelude::{MemoryStorage, MessageQueue};
+use integrations::NotificationEvent;
 use models::entity::server_config;
 use sea_orm::{prelude::*, DatabaseConnection, MockDatabase, SelectColumns};
 use tokio::sync::broadcast::{channel, Receiver, Sender};
@@ -10,8 +10,10 @@ use crate::{
 	database,
 	event::CoreEvent,
 	filesystem::scanner::LibraryWatcher,
-	job::{state::ApalisWorkerState, stump_job::StumpJob},
-	CoreError, CoreResult,
+	job::{
+		state::ApalisWorkerState, stump_job::StumpJob, JobConcurrencyLimits, JobStorage,
+	},
+	notifier, CoreError, CoreResult,
 };
 
 type EventChannel = (Sender<CoreEvent>, Receiver<CoreEvent>);
@@ -26,7 +28,7 @@ pub struct Ctx {
 	pub event_channel: Arc<EventChannel>,
 	pub library_watcher: Arc<LibraryWatcher>,
 	pub apalis_state: Arc<ApalisWorkerState>,
-	pub job_storage: MemoryStorage<StumpJob>,
+	pub job_storage: JobStorage,
 }
 
 impl Ctx {
@@ -54,7 +56,8 @@ impl Ctx {
 		);
 		let event_channel = Arc::new(channel::<CoreEvent>(1024));
 
-		let job_storage = MemoryStorage::<StumpJob>::new();
+		let job_storage = JobStorage::new(conn.clone())
+			.with_limits(JobConcurrencyLimits::from_config(&config));
 		let apalis_state = Arc::new(ApalisWorkerState::new(
 			conn.clone(),
 			config.clone(),
@@ -81,7 +84,7 @@ impl Ctx {
 		let event_channel = Arc::new(channel::<CoreEvent>(1024));
 		let conn = Arc::new(mock_db.into_connection());
 
-		let job_storage = MemoryStorage::<StumpJob>::new();
+		let job_storage = JobStorage::new(conn.clone());
 		let apalis_state = Arc::new(ApalisWorkerState::new(
 			conn.clone(),
 			config.clone(),
@@ -140,13 +143,12 @@ impl Ctx {
 		let _ = self.event_channel.0.send(event);
 	}
 
-	/// Enqueue a job into apalis storage
+	/// Enqueue a job into the persistent job storage
 	pub async fn enqueue(&self, job: StumpJob) -> CoreResult<()> {
-		let mut storage = self.job_storage.clone();
-		storage
-			.enqueue(job)
-			.await
-			.map_err(|_| CoreError::InternalError("Failed to enqueue job".to_string()))?;
+		self.job_storage.enqueue(job).await.map_err(|error| {
+			tracing::error!(?error, "Failed to enqueue job");
+			CoreError::InternalError("Failed to enqueue job".to_string())
+		})?;
 		Ok(())
 	}
 
@@ -159,6 +161,17 @@ impl Ctx {
 		}
 	}
 
+	/// Send a [`NotificationEvent`] to every notifier subscribed to it. Notifications are
+	/// sent in the background, so this never blocks the caller
+	pub fn notify(&self, event: NotificationEvent) {
+		let conn = self.conn.clone();
+		tokio::spawn(async move {
+			if let Err(error) = notifier::send_notification(conn.as_ref(), event).await {
+				tracing::error!(?error, "Failed to send notification");
+			}
+		});
+	}
+
 	/// Retrieves the encryption key from the server configuration
 	pub async fn get_encryption_key(&self) -> CoreResult<String> {
 		let record = server_config::Entity::find()
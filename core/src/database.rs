@@ -1,14 +1,19 @@
 use std::env;
+use std::path::{Path, PathBuf};
 
+use chrono::Utc;
 use migrations::{Migrator, MigratorTrait};
-use sea_orm::{self, DatabaseConnection, FromQueryResult};
+use sea_orm::{self, ConnectionTrait, DatabaseConnection, FromQueryResult};
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
-use crate::{config::StumpConfig, CoreError};
+use crate::{config::StumpConfig, CoreError, CoreResult};
 
 pub const FORCE_RESET_KEY: &str = "FORCE_DB_RESET";
 
+/// The prefix of database backup files, which is used to find old backups to prune
+const BACKUP_FILE_PREFIX: &str = "stump-backup-";
+
 /// A slightly lower max number of binding params for SQL queries, I believe
 /// the default is 999
 pub const SQLITE_BIND_LIMIT: usize = 900;
@@ -56,6 +61,46 @@ pub async fn connect_at(path: &str) -> Result<DatabaseConnection, CoreError> {
 	Ok(connection)
 }
 
+/// Back up the database into the given directory. `VACUUM INTO` is used, rather than copying
+/// the file, so that the backup is consistent while the server is in use. Only the
+/// `retain_count` most recent backups are kept
+pub async fn backup(
+	conn: &DatabaseConnection,
+	backups_dir: &Path,
+	retain_count: usize,
+) -> CoreResult<PathBuf> {
+	tokio::fs::create_dir_all(backups_dir).await?;
+
+	let path = backups_dir.join(format!(
+		"{BACKUP_FILE_PREFIX}{}.db",
+		Utc::now().format("%Y%m%d-%H%M%S%.3f")
+	));
+	let escaped_path = path.to_string_lossy().replace('\'', "''");
+	conn.execute_unprepared(&format!("VACUUM INTO '{escaped_path}'"))
+		.await?;
+
+	let mut backups = Vec::new();
+	let mut read_dir = tokio::fs::read_dir(backups_dir).await?;
+	while let Some(entry) = read_dir.next_entry().await? {
+		let file_name = entry.file_name();
+		let file_name = file_name.to_string_lossy();
+		if file_name.starts_with(BACKUP_FILE_PREFIX) && file_name.ends_with(".db") {
+			backups.push(entry.path());
+		}
+	}
+	// The timestamp in the file name sorts oldest first
+	backups.sort();
+
+	let excess = backups.len().saturating_sub(retain_count.max(1));
+	for old_backup in backups.into_iter().take(excess) {
+		if let Err(error) = tokio::fs::remove_file(&old_backup).await {
+			tracing::warn!(?error, path = ?old_backup, "Failed to remove old backup");
+		}
+	}
+
+	Ok(path)
+}
+
 #[derive(Deserialize, Serialize, Debug, Default)]
 pub struct CountQueryReturn {
 	pub count: i64,
@@ -136,3 +181,33 @@ where
 pub fn get_insert_batch_size(param_count: usize) -> usize {
 	SQLITE_BIND_LIMIT / param_count
 }
+
+#[cfg(test)]
+mod tests {
+	use ::tests::db::create_database_tables;
+
+	use super::*;
+
+	#[tokio::test]
+	async fn test_backup_retains_recent() {
+		let tempdir = tempfile::tempdir().expect("Failed to create temporary directory");
+		// An in-memory database is vacuumed into memory as well, so the backup is taken of
+		// a database on disk
+		let conn = sea_orm::Database::connect(format!(
+			"sqlite://{}/stump.db?mode=rwc",
+			tempdir.path().display()
+		))
+		.await
+		.unwrap();
+		create_database_tables(&conn).await.unwrap();
+
+		let backups_dir = tempdir.path().join("backups");
+		let first = backup(&conn, &backups_dir, 1).await.unwrap();
+		assert!(first.exists());
+
+		tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+		let second = backup(&conn, &backups_dir, 1).await.unwrap();
+		assert!(second.exists());
+		assert!(!first.exists());
+	}
+}
@@ -25,6 +25,8 @@ pub enum CoreError {
 	InitializationError(String),
 	#[error("{0}")]
 	EmailerError(#[from] email::EmailError),
+	#[error("{0}")]
+	NotificationError(#[from] integrations::NotificationError),
 	#[error(
 		"An attempt was made to reset the database, which is not allowed in this context"
 	)]
@@ -57,7 +57,8 @@ pub(crate) fn zip_dir(
 }
 
 /// Creates a zip file from a directory at `unpacked_path` and places it in the
-/// `destination` directory with the name `name` and the extension `ext`.
+/// `destination` directory with the name `name`. Comic formats (CBR, CB7 and PDF) get a
+/// `cbz` extension, anything else `zip`. Fails if the file already exists.
 /// Uses [`zip_dir`] to actually create the zip file.
 pub fn create_zip_archive(
 	unpacked_path: &Path,
@@ -66,15 +67,21 @@ pub fn create_zip_archive(
 	destination: &Path,
 ) -> zip::result::ZipResult<PathBuf> {
 	// TODO: does it make sense to leave this ext logic up to the caller?
-	let mut ext = "cbz";
-
-	if original_ext != "cbr" {
-		ext = "zip";
-	}
+	let ext = match original_ext.to_lowercase().as_str() {
+		"cbr" | "cb7" | "pdf" => "cbz",
+		_ => "zip",
+	};
 
 	trace!("Calculated extension for zip file: {}", ext);
 
 	let zip_path = destination.join(format!("{name}.{ext}"));
+	// Never clobber an existing file, e.g. a CBZ sitting next to the CBR it was made from
+	if zip_path.exists() {
+		return Err(zip::result::ZipError::Io(std::io::Error::new(
+			std::io::ErrorKind::AlreadyExists,
+			format!("{} already exists", zip_path.display()),
+		)));
+	}
 
 	zip_dir(unpacked_path, &zip_path, unpacked_path)?;
 
@@ -132,5 +139,11 @@ mod tests {
 		let res = create_zip_archive(&unpacked_path, "test_archive", "txt", destination);
 		assert!(res.is_ok(), "Failed to create zip archive: {:?}", res.err());
 		assert_eq!(res.unwrap().extension().unwrap().to_str().unwrap(), "zip");
+
+		let res = create_zip_archive(&unpacked_path, "test_pdf", "pdf", destination);
+		assert_eq!(res.unwrap().extension().unwrap().to_str().unwrap(), "cbz");
+
+		let res = create_zip_archive(&unpacked_path, "test_archive", "cbr", destination);
+		assert!(res.is_err(), "Existing archive should not be overwritten");
 	}
 }
@@ -19,6 +19,8 @@ pub enum ContentType {
 	COMIC_ZIP,
 	RAR,
 	COMIC_RAR,
+	SEVEN_ZIP,
+	COMIC_SEVEN_ZIP,
 	AVIF,
 	HEIF,
 	PNG,
@@ -77,6 +79,8 @@ impl ContentType {
 			"cbz" => ContentType::COMIC_ZIP,
 			"rar" => ContentType::RAR,
 			"cbr" => ContentType::COMIC_RAR,
+			"7z" => ContentType::SEVEN_ZIP,
+			"cb7" => ContentType::COMIC_SEVEN_ZIP,
 			"avif" => ContentType::AVIF,
 			"heif" => ContentType::HEIF,
 			"png" => ContentType::PNG,
@@ -242,6 +246,8 @@ impl ContentType {
 	///
 	/// See https://github.com/image-rs/image?tab=readme-ov-file#supported-image-formats
 	///
+	/// AVIF images are only decodable when the `avif-native` feature is enabled.
+	///
 	/// ## Example
 	///
 	/// ```no_run
@@ -251,10 +257,15 @@ impl ContentType {
 	/// assert!(content_type.is_decodable_image());
 	/// ```
 	pub fn is_decodable_image(&self) -> bool {
-		matches!(
-			self,
-			ContentType::PNG | ContentType::JPEG | ContentType::WEBP | ContentType::GIF
-		)
+		match self {
+			ContentType::PNG
+			| ContentType::JPEG
+			| ContentType::WEBP
+			| ContentType::GIF
+			| ContentType::JPEG_XL => true,
+			ContentType::AVIF => cfg!(feature = "avif-native"),
+			_ => false,
+		}
 	}
 
 	/// Returns true if the content type is a ZIP archive.
@@ -279,6 +290,7 @@ impl ContentType {
 			(ContentType::EPUB_ZIP, ContentType::ZIP)
 				| (ContentType::COMIC_ZIP, ContentType::ZIP)
 				| (ContentType::COMIC_RAR, ContentType::RAR)
+				| (ContentType::COMIC_SEVEN_ZIP, ContentType::SEVEN_ZIP)
 		)
 	}
 
@@ -296,6 +308,20 @@ impl ContentType {
 		self == &ContentType::RAR || self == &ContentType::COMIC_RAR
 	}
 
+	/// Returns true if the content type is a 7z archive.
+	///
+	/// ## Example
+	///
+	/// ```no_run
+	/// use stump_core::filesystem::ContentType;
+	///
+	/// let content_type = ContentType::COMIC_SEVEN_ZIP;
+	/// assert!(content_type.is_seven_zip());
+	/// ```
+	pub fn is_seven_zip(&self) -> bool {
+		self == &ContentType::SEVEN_ZIP || self == &ContentType::COMIC_SEVEN_ZIP
+	}
+
 	/// Returns true if the content type is an EPUB archive.
 	///
 	/// ## Example
@@ -323,6 +349,8 @@ impl ContentType {
 			ContentType::COMIC_ZIP => "cbz",
 			ContentType::RAR => "rar",
 			ContentType::COMIC_RAR => "cbr",
+			ContentType::SEVEN_ZIP => "7z",
+			ContentType::COMIC_SEVEN_ZIP => "cb7",
 			ContentType::HEIF => "heif",
 			ContentType::PNG => "png",
 			ContentType::JPEG => "jpg",
@@ -352,6 +380,8 @@ impl From<&str> for ContentType {
 			"application/vnd.comicbook+zip" => ContentType::COMIC_ZIP,
 			"application/vnd.rar" => ContentType::RAR,
 			"application/vnd.comicbook-rar" => ContentType::COMIC_RAR,
+			"application/x-7z-compressed" => ContentType::SEVEN_ZIP,
+			"application/x-cb7" => ContentType::COMIC_SEVEN_ZIP,
 			"image/heif" => ContentType::HEIF,
 			"image/png" => ContentType::PNG,
 			"image/jpeg" => ContentType::JPEG,
@@ -376,6 +406,8 @@ impl std::fmt::Display for ContentType {
 			ContentType::COMIC_ZIP => write!(f, "application/vnd.comicbook+zip"),
 			ContentType::RAR => write!(f, "application/vnd.rar"),
 			ContentType::COMIC_RAR => write!(f, "application/vnd.comicbook-rar"),
+			ContentType::SEVEN_ZIP => write!(f, "application/x-7z-compressed"),
+			ContentType::COMIC_SEVEN_ZIP => write!(f, "application/x-cb7"),
 			ContentType::AVIF => write!(f, "image/avif"),
 			ContentType::HEIF => write!(f, "image/heif"),
 			ContentType::PNG => write!(f, "image/png"),
@@ -389,14 +421,13 @@ impl std::fmt::Display for ContentType {
 	}
 }
 
-// TODO(339): Support JpegXl and Avif
-
 impl From<SupportedImageFormat> for ContentType {
 	fn from(format: SupportedImageFormat) -> Self {
 		match format {
 			SupportedImageFormat::Jpeg => ContentType::JPEG,
 			SupportedImageFormat::Png => ContentType::PNG,
 			SupportedImageFormat::Webp => ContentType::WEBP,
+			SupportedImageFormat::Avif => ContentType::AVIF,
 		}
 	}
 }
@@ -416,7 +447,7 @@ impl TryFrom<ContentType> for image::ImageFormat {
 		// Match values that are compatible with the image crate. Other values should return
 		// an error.
 		match value {
-			ContentType::AVIF => Err(unsupported_error("ContentType::AVIF")),
+			ContentType::AVIF => Ok(image::ImageFormat::Avif),
 			ContentType::HEIF => Err(unsupported_error("ContentType::HEIF")),
 			ContentType::PNG => Ok(image::ImageFormat::Png),
 			ContentType::JPEG => Ok(image::ImageFormat::Jpeg),
@@ -432,6 +463,10 @@ impl TryFrom<ContentType> for image::ImageFormat {
 			ContentType::COMIC_ZIP => Err(unsupported_error("ContentType::COMIC_ZIP")),
 			ContentType::RAR => Err(unsupported_error("ContentType::RAR")),
 			ContentType::COMIC_RAR => Err(unsupported_error("ContentType::COMIC_RAR")),
+			ContentType::SEVEN_ZIP => Err(unsupported_error("ContentType::SEVEN_ZIP")),
+			ContentType::COMIC_SEVEN_ZIP => {
+				Err(unsupported_error("ContentType::COMIC_SEVEN_ZIP"))
+			},
 			ContentType::TXT => Err(unsupported_error("ContentType::TXT")),
 			ContentType::UNKNOWN => Err(unsupported_error("ContentType::UNKNOWN")),
 		}
@@ -453,6 +488,11 @@ mod tests {
 		assert_eq!(ContentType::from_extension("cbz"), ContentType::COMIC_ZIP);
 		assert_eq!(ContentType::from_extension("rar"), ContentType::RAR);
 		assert_eq!(ContentType::from_extension("cbr"), ContentType::COMIC_RAR);
+		assert_eq!(ContentType::from_extension("7z"), ContentType::SEVEN_ZIP);
+		assert_eq!(
+			ContentType::from_extension("cb7"),
+			ContentType::COMIC_SEVEN_ZIP
+		);
 		assert_eq!(ContentType::from_extension("png"), ContentType::PNG);
 		assert_eq!(ContentType::from_extension("jpg"), ContentType::JPEG);
 		assert_eq!(ContentType::from_extension("jpeg"), ContentType::JPEG);
@@ -581,6 +621,14 @@ mod tests {
 			ContentType::COMIC_RAR.mime_type(),
 			"application/vnd.comicbook-rar".to_string()
 		);
+		assert_eq!(
+			ContentType::SEVEN_ZIP.mime_type(),
+			"application/x-7z-compressed".to_string()
+		);
+		assert_eq!(
+			ContentType::COMIC_SEVEN_ZIP.mime_type(),
+			"application/x-cb7".to_string()
+		);
 		assert_eq!(ContentType::PNG.mime_type(), "image/png".to_string());
 		assert_eq!(ContentType::JPEG.mime_type(), "image/jpeg".to_string());
 		assert_eq!(ContentType::WEBP.mime_type(), "image/webp".to_string());
@@ -630,6 +678,23 @@ mod tests {
 		assert!(!ContentType::AVIF.is_opds_legacy_image());
 	}
 
+	#[test]
+	fn test_content_type_is_decodable_image() {
+		assert!(ContentType::PNG.is_decodable_image());
+		assert!(ContentType::JPEG.is_decodable_image());
+		assert!(ContentType::WEBP.is_decodable_image());
+		assert!(ContentType::GIF.is_decodable_image());
+		assert_eq!(
+			ContentType::AVIF.is_decodable_image(),
+			cfg!(feature = "avif-native")
+		);
+		assert!(ContentType::JPEG_XL.is_decodable_image());
+
+		assert!(!ContentType::HEIF.is_decodable_image());
+		assert!(!ContentType::PDF.is_decodable_image());
+		assert!(!ContentType::UNKNOWN.is_decodable_image());
+	}
+
 	#[test]
 	fn test_content_type_is_zip() {
 		// ZIP archives
@@ -650,6 +715,16 @@ mod tests {
 		assert!(!ContentType::COMIC_ZIP.is_rar());
 	}
 
+	#[test]
+	fn test_content_type_is_seven_zip() {
+		// 7z archives
+		assert!(ContentType::SEVEN_ZIP.is_seven_zip());
+		assert!(ContentType::COMIC_SEVEN_ZIP.is_seven_zip());
+		// Not 7z archives
+		assert!(!ContentType::RAR.is_seven_zip());
+		assert!(!ContentType::COMIC_ZIP.is_seven_zip());
+	}
+
 	#[test]
 	fn test_content_type_is_epub() {
 		// EPUB archives
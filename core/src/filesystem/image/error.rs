@@ -12,6 +12,8 @@ pub enum ProcessorError {
 	InvalidSizedImage,
 	#[error("The processor configuration is invalid: {0}")]
 	InvalidConfiguration(String),
+	#[error("Failed to decode JPEG XL image: {0}")]
+	JxlDecodeError(String),
 	#[error("The image format is not supported")]
 	UnsupportedImageFormat,
 	#[error("An unknown error occurred: {0}")]
@@ -1,6 +1,8 @@
 use std::{fs, io::Cursor};
 
-use image::{imageops, GenericImageView, ImageFormat};
+use image::{
+	codecs::avif::AvifEncoder, imageops, DynamicImage, GenericImageView, ImageFormat,
+};
 use models::shared::image_processor_options::{
 	Dimension, ImageProcessorOptions, ScaledDimensionResize, SupportedImageFormat,
 };
@@ -8,20 +10,54 @@ use models::shared::image_processor_options::{
 use crate::filesystem::{image::process::resized_dimensions, FileError};
 
 use super::{
-	process::ImageProcessor, scale_height_dimension, scale_width_dimension,
+	load_image, process::ImageProcessor, scale_height_dimension, scale_width_dimension,
 	ProcessorError,
 };
 
+/// The speed to encode AVIF images at, from 1 (slowest) to 10 (fastest). The slower speeds
+/// only shave off a few more bytes, which isn't worth the time for thumbnails
+const AVIF_ENCODER_SPEED: u8 = 8;
+/// The quality to encode AVIF images at when none is given
+const AVIF_DEFAULT_QUALITY: u8 = 80;
+
 /// An image processor that works for the most common image types, primarily
-/// JPEG and PNG formats.
+/// JPEG, PNG and AVIF formats.
 pub struct GenericImageProcessor;
 
+impl GenericImageProcessor {
+	/// Encode the image in the given format. AVIF is encoded with an explicit speed and
+	/// quality, since the encoder's defaults are very slow
+	fn encode(
+		image: &DynamicImage,
+		format: ImageFormat,
+		quality: Option<u16>,
+	) -> Result<Vec<u8>, ProcessorError> {
+		let mut buffer = Cursor::new(vec![]);
+
+		if format == ImageFormat::Avif {
+			let quality = quality
+				.map(|q| q.clamp(1, 100) as u8)
+				.unwrap_or(AVIF_DEFAULT_QUALITY);
+			let encoder = AvifEncoder::new_with_speed_quality(
+				&mut buffer,
+				AVIF_ENCODER_SPEED,
+				quality,
+			);
+			image.write_with_encoder(encoder)?;
+		} else {
+			image.write_to(&mut buffer, format)?;
+		}
+
+		Ok(buffer.into_inner())
+	}
+}
+
 impl ImageProcessor for GenericImageProcessor {
 	fn generate(
 		buffer: &[u8],
 		options: ImageProcessorOptions,
 	) -> Result<Vec<u8>, ProcessorError> {
-		let mut image = image::load_from_memory(buffer)?;
+		let mut image = load_image(buffer)?;
 
 		if let Some(method) = options.resize_method {
 			let (current_width, current_height) = image.dimensions();
@@ -42,13 +78,11 @@ impl ImageProcessor for GenericImageProcessor {
 				Ok(ImageFormat::Jpeg)
 			},
 			SupportedImageFormat::Png => Ok(ImageFormat::Png),
+			SupportedImageFormat::Avif => Ok(ImageFormat::Avif),
 			_ => Err(FileError::IncorrectProcessorError),
 		}?;
 
-		let mut buffer = Cursor::new(vec![]);
-		image.write_to(&mut buffer, format)?;
-
-		Ok(buffer.into_inner())
+		Self::encode(&image, format, options.quality)
 	}
 
 	fn generate_from_path(
@@ -63,7 +97,7 @@ impl ImageProcessor for GenericImageProcessor {
 		buf: &[u8],
 		config: ScaledDimensionResize,
 	) -> Result<Vec<u8>, ProcessorError> {
-		let mut image = image::load_from_memory(buf)?;
+		let mut image = load_image(buf)?;
 
 		let read_format = image::guess_format(buf)?;
 		let format = match read_format {
@@ -78,6 +112,7 @@ impl ImageProcessor for GenericImageProcessor {
 				Ok(ImageFormat::Jpeg)
 			},
 			ImageFormat::Png => Ok(ImageFormat::Png),
+			ImageFormat::Avif => Ok(ImageFormat::Avif),
 			_ => Err(FileError::IncorrectProcessorError),
 		}?;
 
@@ -102,10 +137,7 @@ impl ImageProcessor for GenericImageProcessor {
 			},
 		}
 
-		let mut buffer = Cursor::new(vec![]);
-		image.write_to(&mut buffer, format)?;
-
-		Ok(buffer.into_inner())
+		Self::encode(&image, format, None)
 	}
 }
 
@@ -118,7 +150,11 @@ mod tests {
 	use rust_decimal::Decimal;
 
 	use super::*;
-	use crate::filesystem::image::tests::{get_test_jpg_path, get_test_png_path};
+	#[cfg(feature = "avif-native")]
+	use crate::filesystem::image::tests::get_test_avif_path;
+	use crate::filesystem::image::tests::{
+		get_test_jpg_path, get_test_jxl_path, get_test_png_path,
+	};
 
 	//JPG -> other Tests
 	//JPG -> JPG
@@ -412,4 +448,58 @@ mod tests {
 		assert_eq!(dimensions.0, 100);
 		assert_eq!(dimensions.1, 100);
 	}
+
+	#[test]
+	#[cfg(feature = "avif-native")]
+	fn test_generate_jpg_to_avif_with_resize() {
+		let jpg_path = get_test_jpg_path();
+		let options = ImageProcessorOptions {
+			format: SupportedImageFormat::Avif,
+			resize_method: Some(ImageResizeMethod::Exact(ExactDimensionResize {
+				width: 100,
+				height: 100,
+			})),
+			..Default::default()
+		};
+
+		let buffer = GenericImageProcessor::generate_from_path(&jpg_path, options)
+			.expect("Failed to generate image buffer");
+
+		let image =
+			image::load_from_memory_with_format(&buffer, image::ImageFormat::Avif)
+				.expect("Failed to load image from buffer");
+		assert_eq!(image.dimensions(), (100, 100));
+	}
+
+	#[test]
+	#[cfg(feature = "avif-native")]
+	fn test_generate_avif_to_jpg() {
+		let avif_path = get_test_avif_path();
+		let options = ImageProcessorOptions {
+			format: SupportedImageFormat::Jpeg,
+			..Default::default()
+		};
+
+		let buffer = GenericImageProcessor::generate_from_path(&avif_path, options)
+			.expect("Failed to generate image buffer");
+		assert!(
+			image::load_from_memory_with_format(&buffer, image::ImageFormat::Jpeg)
+				.is_ok()
+		);
+	}
+
+	#[test]
+	fn test_generate_jxl_to_png() {
+		let jxl_path = get_test_jxl_path();
+		let options = ImageProcessorOptions {
+			format: SupportedImageFormat::Png,
+			..Default::default()
+		};
+
+		let buffer = GenericImageProcessor::generate_from_path(&jxl_path, options)
+			.expect("Failed to generate image buffer");
+		assert!(
+			image::load_from_memory_with_format(&buffer, image::ImageFormat::Png).is_ok()
+		);
+	}
 }
@@ -7,19 +7,48 @@ mod webp;
 pub use self::webp::WebpProcessor;
 pub use error::ProcessorError;
 pub use generic::GenericImageProcessor;
-use image::ImageFormat;
+use image::{DynamicImage, ImageFormat};
+use jxl_oxide::integration::JxlDecoder;
 use models::shared::image_processor_options::{
 	ScaledDimensionResize, SupportedImageFormat,
 };
 pub use process::{ImageProcessor, ImageProcessorOptionsExt};
+use std::io::Cursor;
 pub use thumbnail::*;
 use tokio::{sync::oneshot, task::spawn_blocking};
 
+/// The signature of a bare JPEG XL codestream
+const JXL_CODESTREAM_SIGNATURE: [u8; 2] = [0xFF, 0x0A];
+/// The signature of a JPEG XL codestream wrapped in its (ISOBMFF) container
+const JXL_CONTAINER_SIGNATURE: [u8; 12] = [
+	0x00, 0x00, 0x00, 0x0C, 0x4A, 0x58, 0x4C, 0x20, 0x0D, 0x0A, 0x87, 0x0A,
+];
+
 pub fn into_image_format(format: SupportedImageFormat) -> ImageFormat {
 	match format {
 		SupportedImageFormat::Jpeg => ImageFormat::Jpeg,
 		SupportedImageFormat::Png => ImageFormat::Png,
 		SupportedImageFormat::Webp => ImageFormat::WebP,
+		SupportedImageFormat::Avif => ImageFormat::Avif,
+	}
+}
+
+/// Returns true if the buffer holds a JPEG XL image. The `image` crate doesn't know about
+/// JPEG XL, so [`image::guess_format`] can't be used for it
+pub fn is_jxl(buf: &[u8]) -> bool {
+	buf.starts_with(&JXL_CODESTREAM_SIGNATURE)
+		|| buf.starts_with(&JXL_CONTAINER_SIGNATURE)
+}
+
+/// Decode an image from memory. This should be preferred over [`image::load_from_memory`],
+/// since it also decodes JPEG XL images (which are handed off to `jxl-oxide`)
+pub fn load_image(buf: &[u8]) -> Result<DynamicImage, ProcessorError> {
+	if is_jxl(buf) {
+		let decoder = JxlDecoder::new(Cursor::new(buf))
+			.map_err(|e| ProcessorError::JxlDecodeError(e.to_string()))?;
+		Ok(DynamicImage::from_decoder(decoder)?)
+	} else {
+		Ok(image::load_from_memory(buf)?)
 	}
 }
 
@@ -27,9 +56,14 @@ fn _resize_image(
 	buf: &[u8],
 	dimension: ScaledDimensionResize,
 ) -> Result<Vec<u8>, ProcessorError> {
+	// JPEG XL can't be encoded, so resized JPEG XL images are re-encoded as WebP
+	if is_jxl(buf) {
+		return WebpProcessor::resize_scaled(buf, dimension);
+	}
+
 	match image::guess_format(buf)? {
 		ImageFormat::WebP => Ok(WebpProcessor::resize_scaled(buf, dimension)?),
-		ImageFormat::Jpeg | ImageFormat::Png => {
+		ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::Avif => {
 			Ok(GenericImageProcessor::resize_scaled(buf, dimension)?)
 		},
 		_ => Err(ProcessorError::UnsupportedImageFormat),
@@ -70,6 +104,10 @@ pub async fn resize_image(
 mod tests {
 	use std::path::PathBuf;
 
+	use models::shared::image_processor_options::Dimension;
+
+	use super::*;
+
 	pub fn get_test_webp_path() -> String {
 		PathBuf::from(env!("CARGO_MANIFEST_DIR"))
 			.join("integration-tests/data/example.webp")
@@ -91,15 +129,74 @@ mod tests {
 			.to_string()
 	}
 
-	// pub fn get_test_avif_path() -> String {
-	// 	PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-	// 		.join("integration-tests/data/example.avif")
-	// 		.to_string_lossy()
-	// 		.to_string()
-	// }
-
-	// TODO(339): Avif + Jxl support
-	// pub fn get_test_jxl_path() -> String {
-	// 	PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-	// 		.join("integration-tests/data/example.jxl")
+	pub fn get_test_avif_path() -> String {
+		PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+			.join("integration-tests/data/example.avif")
+			.to_string_lossy()
+			.to_string()
+	}
+
+	pub fn get_test_jxl_path() -> String {
+		PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+			.join("integration-tests/data/example.jxl")
+			.to_string_lossy()
+			.to_string()
+	}
+
+	#[test]
+	fn test_is_jxl() {
+		let jxl = std::fs::read(get_test_jxl_path()).unwrap();
+		assert!(is_jxl(&jxl));
+
+		let avif = std::fs::read(get_test_avif_path()).unwrap();
+		assert!(!is_jxl(&avif));
+	}
+
+	#[test]
+	#[cfg(feature = "avif-native")]
+	fn test_load_image_avif() {
+		let bytes = std::fs::read(get_test_avif_path()).unwrap();
+		let image = load_image(&bytes).unwrap();
+		assert!(image.width() > 0 && image.height() > 0);
+	}
+
+	#[test]
+	fn test_load_image_jxl() {
+		let bytes = std::fs::read(get_test_jxl_path()).unwrap();
+		let image = load_image(&bytes).unwrap();
+		assert!(image.width() > 0 && image.height() > 0);
+	}
+
+	#[test]
+	#[cfg(feature = "avif-native")]
+	fn test_resize_image_avif() {
+		let bytes = std::fs::read(get_test_avif_path()).unwrap();
+		let resized = _resize_image(
+			&bytes,
+			ScaledDimensionResize {
+				dimension: Dimension::Width,
+				size: 100,
+			},
+		)
+		.unwrap();
+
+		assert_eq!(image::guess_format(&resized).unwrap(), ImageFormat::Avif);
+		assert_eq!(load_image(&resized).unwrap().width(), 100);
+	}
+
+	#[test]
+	fn test_resize_image_jxl() {
+		let bytes = std::fs::read(get_test_jxl_path()).unwrap();
+		let resized = _resize_image(
+			&bytes,
+			ScaledDimensionResize {
+				dimension: Dimension::Width,
+				size: 100,
+			},
+		)
+		.unwrap();
+
+		assert_eq!(image::guess_format(&resized).unwrap(), ImageFormat::WebP);
+		assert!(load_image(&resized).unwrap().width() <= 100);
+	}
 }
@@ -8,7 +8,7 @@ use std::path::Path;
 use thumbhash::rgba_to_thumb_hash;
 use tokio::{sync::oneshot, task::spawn_blocking};
 
-use crate::filesystem::image::ProcessorError;
+use crate::filesystem::image::{load_image, ProcessorError};
 
 pub async fn generate_image_metadata(
 	path: &Path,
@@ -83,7 +83,7 @@ fn _generate_image_metadata_from_path(
 fn _generate_image_metadata_from_bytes(
 	bytes: &[u8],
 ) -> Result<ImageMetadata, ProcessorError> {
-	let img = image::load_from_memory(bytes)?;
+	let img = load_image(bytes)?;
 	_generate_image_metadata_from_image(img)
 }
 
@@ -126,7 +126,7 @@ pub fn process_image_colors_from_bytes(
 	bytes: &[u8],
 	palette_size: usize,
 ) -> Result<Vec<ImageColor>, ProcessorError> {
-	let dyn_img = image::load_from_memory(bytes)?;
+	let dyn_img = load_image(bytes)?;
 	process_image_colors_from_image(&dyn_img, palette_size)
 }
 
@@ -209,7 +209,7 @@ pub fn process_image_thumbhash(path: &Path) -> Result<String, ProcessorError> {
 pub fn process_image_thumbhash_from_bytes(
 	bytes: &[u8],
 ) -> Result<String, ProcessorError> {
-	let dyn_img = image::load_from_memory(bytes)?;
+	let dyn_img = load_image(bytes)?;
 	process_image_thumbhash_from_image(&dyn_img)
 }
 
@@ -9,7 +9,7 @@ use webp::Encoder;
 use crate::filesystem::{
 	error::FileError,
 	image::process::resized_dimensions,
-	image::{process::ImageProcessor, ProcessorError},
+	image::{load_image, process::ImageProcessor, ProcessorError},
 };
 
 use super::{scale_height_dimension, scale_width_dimension, ScaledDimensionResize};
@@ -22,7 +22,7 @@ impl ImageProcessor for WebpProcessor {
 		options: ImageProcessorOptions,
 	) -> Result<Vec<u8>, ProcessorError> {
 		// Load the image from memory
-		let mut image = image::load_from_memory(buffer)?;
+		let mut image = load_image(buffer)?;
 
 		// Apply resizing if requested
 		if let Some(method) = options.resize_method {
@@ -54,7 +54,7 @@ impl ImageProcessor for WebpProcessor {
 		buf: &[u8],
 		config: ScaledDimensionResize,
 	) -> Result<Vec<u8>, ProcessorError> {
-		let image = image::load_from_memory(buf)?;
+		let image = load_image(buf)?;
 
 		let (current_width, current_height) = image.dimensions();
 
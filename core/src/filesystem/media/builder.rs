@@ -180,7 +180,7 @@ impl MediaBuilder {
 #[cfg(test)]
 mod tests {
 	use models::shared::enums::{
-		LibraryPattern, LibraryType, LibraryViewMode, ReadingDirection,
+		LibraryPattern, LibraryType, LibraryViewMode, LibraryWatchMode, ReadingDirection,
 		ReadingImageScaleFit, ReadingMode,
 	};
 	use sea_orm::ActiveValue;
@@ -278,10 +278,14 @@ mod tests {
 			thumbnail_config: None,
 			process_thumbnail_colors_even_without_config: false,
 			watch: false,
+			watch_mode: LibraryWatchMode::Native,
+			watch_poll_interval_seconds: 60,
 			default_library_view_mode: LibraryViewMode::Series,
 			hide_series_view: false,
 			library_type: LibraryType::Mixed,
 			skip_book_overview: false,
+			kobo_convert_to_epub: false,
+			kobo_convert_enabled_at: None,
 		}
 	}
 }
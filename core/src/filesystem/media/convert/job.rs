@@ -0,0 +1,269 @@
+use std::path::{Path, PathBuf};
+
+use async_graphql::SimpleObject;
+use chrono::{DateTime, FixedOffset, Utc};
+use models::{entity::media, shared::image_processor_options::SupportedImageFormat};
+use sea_orm::{prelude::*, QuerySelect, Set};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	filesystem::{
+		error::FileError,
+		media::{convert_to_zip_async, process_async, FileProcessorOptions},
+		FileParts, PathUtils,
+	},
+	job::{
+		error::JobError, JobContext, JobExecuteLog, JobLifecycle, JobOutputExt,
+		JobProgress, JobTaskOutput, WorkingState,
+	},
+};
+
+type Id = String;
+
+/// The extensions of the formats which can be converted to a zip archive
+const CONVERTIBLE_EXTENSIONS: [&str; 5] = ["cbr", "rar", "cb7", "7z", "pdf"];
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum MediaConversionScope {
+	/// Convert an individual media item, specified by ID
+	Book(Id),
+	/// Convert all convertible media in a series, specified by series ID
+	Series(Id),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ConvertMediaJobConfig {
+	pub scope: MediaConversionScope,
+	/// Whether to delete the original file once it has been converted
+	pub delete_source: bool,
+	/// The image format to render pages to, for formats which need rendering (e.g. PDF).
+	/// Defaults to the configured PDF render format
+	pub image_format: Option<SupportedImageFormat>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum ConvertMediaTask {
+	ConvertBook(Id),
+}
+
+#[derive(Clone, Serialize, Deserialize, Default, Debug, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvertMediaOutput {
+	/// The number of media items which were converted
+	pub media_converted: u64,
+	/// The number of media items which were skipped, either because their format can't be
+	/// converted or because the conversion failed
+	pub media_skipped: u64,
+}
+
+impl JobOutputExt for ConvertMediaOutput {
+	fn update(&mut self, updated: Self) {
+		self.media_converted += updated.media_converted;
+		self.media_skipped += updated.media_skipped;
+	}
+}
+
+/// A job that converts media (e.g. CBR, CB7 or PDF) into zip archives. The converted file
+/// replaces the original in place, so the media item keeps its ID and everything attached
+/// to it (reading progress, bookmarks, etc).
+#[derive(Clone)]
+pub struct ConvertMediaJob {
+	pub config: ConvertMediaJobConfig,
+}
+
+impl ConvertMediaJob {
+	pub fn new(config: ConvertMediaJobConfig) -> Self {
+		Self { config }
+	}
+}
+
+fn is_convertible(extension: &str) -> bool {
+	CONVERTIBLE_EXTENSIONS.contains(&extension.to_lowercase().as_str())
+}
+
+/// Point the media item at the converted file, regenerating the hashes (only those the book
+/// already had) and page count from it
+async fn update_converted_book(
+	ctx: &JobContext,
+	book: media::Model,
+	zip_path: &Path,
+) -> Result<(), JobError> {
+	let processed = process_async(
+		zip_path,
+		FileProcessorOptions {
+			generate_file_hashes: book.hash.is_some(),
+			generate_koreader_hashes: book.koreader_hash.is_some(),
+			..Default::default()
+		},
+		ctx.config(),
+	)
+	.await;
+
+	let file_metadata = zip_path.metadata().map_err(FileError::from)?;
+	let modified_at: Option<DateTime<FixedOffset>> = file_metadata
+		.modified()
+		.ok()
+		.map(|t| DateTime::<Utc>::from(t).into());
+	let FileParts { extension, .. } = zip_path.file_parts();
+
+	let mut active_model: media::ActiveModel = book.into();
+	active_model.path = Set(zip_path.to_string_lossy().to_string());
+	active_model.extension = Set(extension);
+	active_model.size = Set(file_metadata.len().try_into().unwrap_or_default());
+	active_model.modified_at = Set(modified_at);
+	// The path must be updated regardless, since the original may be gone already
+	let result = match processed {
+		Ok(processed) => {
+			active_model.hash = Set(processed.hash);
+			active_model.koreader_hash = Set(processed.koreader_hash);
+			active_model.pages = Set(processed.pages);
+			Ok(())
+		},
+		Err(error) => Err(error.into()),
+	};
+	active_model.update(ctx.conn()).await?;
+
+	result
+}
+
+#[async_trait::async_trait]
+impl JobLifecycle for ConvertMediaJob {
+	const NAME: &'static str = "convert_media";
+
+	type Output = ConvertMediaOutput;
+	type Task = ConvertMediaTask;
+
+	fn description(&self) -> Option<String> {
+		match &self.config.scope {
+			MediaConversionScope::Book(id) => {
+				Some(format!("Convert media item with id: {id}"))
+			},
+			MediaConversionScope::Series(id) => {
+				Some(format!("Convert media in series with id: {id}"))
+			},
+		}
+	}
+
+	async fn init(
+		&mut self,
+		ctx: &JobContext,
+	) -> Result<WorkingState<Self::Output, Self::Task>, JobError> {
+		let output = Self::Output::default();
+
+		let tasks = match &self.config.scope {
+			MediaConversionScope::Book(id) => {
+				vec![ConvertMediaTask::ConvertBook(id.clone())]
+			},
+			MediaConversionScope::Series(id) => {
+				let books = media::Entity::find()
+					.select_only()
+					.columns(vec![media::Column::Id, media::Column::Extension])
+					.filter(media::Column::SeriesId.eq(id))
+					.into_tuple::<(String, String)>()
+					.all(ctx.conn())
+					.await
+					.map_err(|e| JobError::InitFailed(e.to_string()))?;
+
+				books
+					.into_iter()
+					.filter(|(_, extension)| is_convertible(extension))
+					.map(|(id, _)| ConvertMediaTask::ConvertBook(id))
+					.collect()
+			},
+		};
+
+		Ok(WorkingState {
+			output: Some(output),
+			tasks: tasks.into(),
+			logs: vec![],
+		})
+	}
+
+	async fn execute_task(
+		&self,
+		ctx: &JobContext,
+		task: Self::Task,
+	) -> Result<JobTaskOutput<Self>, JobError> {
+		let mut output = Self::Output::default();
+		let mut logs = vec![];
+
+		match task {
+			ConvertMediaTask::ConvertBook(id) => {
+				let book = media::Entity::find_by_id(id.clone())
+					.one(ctx.conn())
+					.await?
+					.ok_or_else(|| {
+						JobError::TaskFailed(format!(
+							"Unable to find media item with id: {id}"
+						))
+					})?;
+
+				let filename = Path::new(&book.path)
+					.file_name()
+					.and_then(|n| n.to_str())
+					.unwrap_or(&book.path)
+					.to_string();
+
+				if !is_convertible(&book.extension) {
+					logs.push(
+						JobExecuteLog::warn(&format!(
+							"{filename} is not in a format which can be converted"
+						))
+						.with_ctx(id),
+					);
+					output.media_skipped += 1;
+					return Ok(JobTaskOutput {
+						output,
+						subtasks: vec![],
+						logs,
+					});
+				}
+
+				ctx.report_progress(JobProgress::msg(&format!("Converting {filename}")));
+
+				let zip_path: PathBuf = match convert_to_zip_async(
+					&book.path,
+					self.config.delete_source,
+					self.config.image_format,
+					ctx.config(),
+				)
+				.await
+				{
+					Ok(path) => path,
+					Err(error) => {
+						tracing::error!(?error, %id, "Failed to convert media");
+						logs.push(
+							JobExecuteLog::error(format!(
+								"Failed to convert {filename}: {error}"
+							))
+							.with_ctx(id),
+						);
+						output.media_skipped += 1;
+						return Ok(JobTaskOutput {
+							output,
+							subtasks: vec![],
+							logs,
+						});
+					},
+				};
+
+				output.media_converted += 1;
+				if let Err(error) = update_converted_book(ctx, book, &zip_path).await {
+					tracing::error!(?error, %id, "Failed to update converted media");
+					logs.push(
+						JobExecuteLog::error(format!(
+							"Failed to update {filename} after conversion: {error}"
+						))
+						.with_ctx(id),
+					);
+				}
+			},
+		}
+
+		Ok(JobTaskOutput {
+			output,
+			subtasks: vec![],
+			logs,
+		})
+	}
+}
@@ -0,0 +1,3 @@
+mod job;
+
+pub use job::*;
@@ -0,0 +1,380 @@
+use std::collections::{BTreeMap, HashSet};
+
+use async_graphql::SimpleObject;
+use models::{
+	entity::{
+		duplicate_media_group, duplicate_media_group_member, ignored_duplicate_media,
+		media, media_metadata, series, series_metadata,
+	},
+	shared::enums::DuplicateReason,
+};
+use sea_orm::{
+	prelude::*, sea_query::Query, FromQueryResult, JoinType, QuerySelect, Set,
+	TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	job::{
+		error::JobError, JobContext, JobLifecycle, JobOutputExt, JobProgress,
+		JobTaskOutput, WorkingState,
+	},
+	utils::normalize::{format_number, normalize_name},
+};
+
+/// The reasons the job checks for, in the order the tasks are run
+const DUPLICATE_REASONS: [DuplicateReason; 3] = [
+	DuplicateReason::Hash,
+	DuplicateReason::Identifier,
+	DuplicateReason::SeriesNumber,
+];
+
+/// The information about a media item which is used to find its duplicates
+#[derive(Debug, Clone, Default, FromQueryResult)]
+pub(crate) struct DuplicateCandidate {
+	pub id: String,
+	pub hash: Option<String>,
+	pub isbn: Option<String>,
+	pub comicvine_id: Option<i32>,
+	pub number: Option<Decimal>,
+	pub volume: Option<i32>,
+	pub metadata_series: Option<String>,
+	pub series_name: Option<String>,
+}
+
+/// Strip the separators from an ISBN, so that e.g. `978-1-23` and `978123` compare as equal
+fn normalize_isbn(value: &str) -> String {
+	value
+		.chars()
+		.filter(|c| c.is_alphanumeric())
+		.flat_map(char::to_uppercase)
+		.collect()
+}
+
+/// The keys a media item is grouped by for the given reason. Items which share a key are
+/// flagged as duplicates of each other
+pub(crate) fn duplicate_keys(
+	reason: DuplicateReason,
+	candidate: &DuplicateCandidate,
+) -> Vec<String> {
+	match reason {
+		DuplicateReason::Hash => candidate
+			.hash
+			.iter()
+			.filter(|hash| !hash.is_empty())
+			.cloned()
+			.collect(),
+		DuplicateReason::Identifier => {
+			let isbn = candidate
+				.isbn
+				.as_deref()
+				.map(normalize_isbn)
+				.filter(|isbn| !isbn.is_empty())
+				.map(|isbn| format!("isbn:{isbn}"));
+			let comicvine = candidate.comicvine_id.zip(candidate.number).map(
+				|(comicvine_id, number)| {
+					format!("comicvine:{comicvine_id}:{}", format_number(&number))
+				},
+			);
+			isbn.into_iter().chain(comicvine).collect()
+		},
+		DuplicateReason::SeriesNumber => {
+			let series = candidate
+				.metadata_series
+				.as_deref()
+				.or(candidate.series_name.as_deref())
+				.map(normalize_name)
+				.filter(|series| !series.is_empty());
+			series
+				.zip(candidate.number)
+				.map(|(series, number)| {
+					format!(
+						"{series}:{}:{}",
+						candidate
+							.volume
+							.map(|volume| volume.to_string())
+							.unwrap_or_default(),
+						format_number(&number)
+					)
+				})
+				.into_iter()
+				.collect()
+		},
+	}
+}
+
+/// Group the candidates by their keys for the given reason, returning the (sorted) member
+/// IDs of every key shared by at least two items. Pairs of `(key, media_id)` in `ignored`
+/// are left out of the groups.
+pub(crate) fn group_candidates(
+	reason: DuplicateReason,
+	candidates: &[DuplicateCandidate],
+	ignored: &HashSet<(String, String)>,
+) -> Vec<(String, Vec<String>)> {
+	let mut groups = BTreeMap::<String, Vec<String>>::new();
+	for candidate in candidates {
+		for key in duplicate_keys(reason, candidate) {
+			if ignored.contains(&(key.clone(), candidate.id.clone())) {
+				continue;
+			}
+			groups.entry(key).or_default().push(candidate.id.clone());
+		}
+	}
+
+	groups
+		.into_iter()
+		.filter_map(|(key, mut media_ids)| {
+			media_ids.sort();
+			media_ids.dedup();
+			(media_ids.len() > 1).then_some((key, media_ids))
+		})
+		.collect()
+}
+
+async fn find_candidates(
+	conn: &DatabaseConnection,
+) -> Result<Vec<DuplicateCandidate>, DbErr> {
+	media::Entity::find()
+		.select_only()
+		.column_as(media::Column::Id, "id")
+		.column_as(media::Column::Hash, "hash")
+		.column_as(media_metadata::Column::IdentifierIsbn, "isbn")
+		.column_as(series_metadata::Column::Comicid, "comicvine_id")
+		.column_as(media_metadata::Column::Number, "number")
+		.column_as(media_metadata::Column::Volume, "volume")
+		.column_as(media_metadata::Column::Series, "metadata_series")
+		.column_as(series::Column::Name, "series_name")
+		.left_join(media_metadata::Entity)
+		.left_join(series::Entity)
+		.join_rev(
+			JoinType::LeftJoin,
+			series_metadata::Entity::belongs_to(series::Entity)
+				.from(series_metadata::Column::SeriesId)
+				.to(series::Column::Id)
+				.into(),
+		)
+		.filter(media::Column::DeletedAt.is_null())
+		.into_model::<DuplicateCandidate>()
+		.all(conn)
+		.await
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum DetectDuplicatesTask {
+	Detect(DuplicateReason),
+}
+
+#[derive(Clone, Serialize, Deserialize, Default, Debug, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectDuplicatesOutput {
+	/// The number of duplicate groups which were found
+	pub groups_found: u64,
+	/// The number of media items which were flagged. An item in more than one group is
+	/// counted once for each group
+	pub media_flagged: u64,
+}
+
+impl JobOutputExt for DetectDuplicatesOutput {
+	fn update(&mut self, updated: Self) {
+		self.groups_found += updated.groups_found;
+		self.media_flagged += updated.media_flagged;
+	}
+}
+
+/// A job that finds media items which are likely duplicates of each other. Each reason
+/// is checked in its own task, which replaces the groups previously found for it.
+#[derive(Clone)]
+pub struct DetectDuplicatesJob;
+
+#[async_trait::async_trait]
+impl JobLifecycle for DetectDuplicatesJob {
+	const NAME: &'static str = "detect_duplicates";
+
+	type Output = DetectDuplicatesOutput;
+	type Task = DetectDuplicatesTask;
+
+	fn description(&self) -> Option<String> {
+		Some("Detect duplicate media".to_string())
+	}
+
+	async fn init(
+		&mut self,
+		_ctx: &JobContext,
+	) -> Result<WorkingState<Self::Output, Self::Task>, JobError> {
+		Ok(WorkingState {
+			output: Some(Self::Output::default()),
+			tasks: DUPLICATE_REASONS
+				.into_iter()
+				.map(DetectDuplicatesTask::Detect)
+				.collect::<Vec<_>>()
+				.into(),
+			logs: vec![],
+		})
+	}
+
+	async fn execute_task(
+		&self,
+		ctx: &JobContext,
+		task: Self::Task,
+	) -> Result<JobTaskOutput<Self>, JobError> {
+		let mut output = Self::Output::default();
+
+		match task {
+			DetectDuplicatesTask::Detect(reason) => {
+				ctx.report_progress(JobProgress::msg(&format!(
+					"Detecting duplicates by {reason:?}"
+				)));
+
+				let candidates = find_candidates(ctx.conn()).await?;
+				let ignored = ignored_duplicate_media::Entity::find()
+					.filter(ignored_duplicate_media::Column::Reason.eq(reason))
+					.all(ctx.conn())
+					.await?
+					.into_iter()
+					.map(|ignored| (ignored.key, ignored.media_id))
+					.collect::<HashSet<_>>();
+				let groups = group_candidates(reason, &candidates, &ignored);
+
+				let txn = ctx.conn().begin().await?;
+
+				duplicate_media_group_member::Entity::delete_many()
+					.filter(
+						duplicate_media_group_member::Column::GroupId.in_subquery(
+							Query::select()
+								.column(duplicate_media_group::Column::Id)
+								.from(duplicate_media_group::Entity)
+								.and_where(
+									duplicate_media_group::Column::Reason.eq(reason),
+								)
+								.to_owned(),
+						),
+					)
+					.exec(&txn)
+					.await?;
+				duplicate_media_group::Entity::delete_many()
+					.filter(duplicate_media_group::Column::Reason.eq(reason))
+					.exec(&txn)
+					.await?;
+
+				for (key, media_ids) in groups {
+					let group = duplicate_media_group::ActiveModel {
+						reason: Set(reason),
+						key: Set(key),
+						..Default::default()
+					}
+					.insert(&txn)
+					.await?;
+
+					output.groups_found += 1;
+					output.media_flagged += media_ids.len() as u64;
+
+					duplicate_media_group_member::Entity::insert_many(
+						media_ids.into_iter().map(|media_id| {
+							duplicate_media_group_member::ActiveModel {
+								group_id: Set(group.id.clone()),
+								media_id: Set(media_id),
+								..Default::default()
+							}
+						}),
+					)
+					.exec(&txn)
+					.await?;
+				}
+
+				txn.commit().await?;
+			},
+		}
+
+		Ok(JobTaskOutput {
+			output,
+			subtasks: vec![],
+			logs: vec![],
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn candidate(id: &str) -> DuplicateCandidate {
+		DuplicateCandidate {
+			id: id.to_string(),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn test_duplicate_keys() {
+		let book = DuplicateCandidate {
+			hash: Some("abc".to_string()),
+			isbn: Some("978-1-60309-025-4".to_string()),
+			comicvine_id: Some(2127),
+			number: Some(Decimal::new(10, 1)),
+			volume: Some(2),
+			series_name: Some("The Amazing Spider-Man".to_string()),
+			..candidate("1")
+		};
+
+		assert_eq!(duplicate_keys(DuplicateReason::Hash, &book), vec!["abc"]);
+		assert_eq!(
+			duplicate_keys(DuplicateReason::Identifier, &book),
+			vec!["isbn:9781603090254", "comicvine:2127:1"]
+		);
+		assert_eq!(
+			duplicate_keys(DuplicateReason::SeriesNumber, &book),
+			vec!["theamazingspiderman:2:1"]
+		);
+
+		// The series from the book's own metadata takes precedence over the series name
+		let book = DuplicateCandidate {
+			metadata_series: Some("Amazing Spider-Man".to_string()),
+			..book
+		};
+		assert_eq!(
+			duplicate_keys(DuplicateReason::SeriesNumber, &book),
+			vec!["amazingspiderman:2:1"]
+		);
+
+		// Nothing to group a book without metadata by
+		assert!(duplicate_keys(DuplicateReason::Identifier, &candidate("2")).is_empty());
+		assert!(
+			duplicate_keys(DuplicateReason::SeriesNumber, &candidate("2")).is_empty()
+		);
+	}
+
+	#[test]
+	fn test_group_candidates() {
+		let with_number = |id: &str, series: &str, number: i64| DuplicateCandidate {
+			number: Some(Decimal::from(number)),
+			series_name: Some(series.to_string()),
+			..candidate(id)
+		};
+		let candidates = vec![
+			with_number("a", "Saga", 1),
+			with_number("b", "saga", 1),
+			with_number("c", "SAGA!", 1),
+			with_number("d", "Saga", 2),
+			with_number("e", "Monstress", 1),
+		];
+
+		let groups =
+			group_candidates(DuplicateReason::SeriesNumber, &candidates, &HashSet::new());
+		assert_eq!(
+			groups,
+			vec![(
+				"saga::1".to_string(),
+				vec!["a".to_string(), "b".to_string(), "c".to_string()]
+			)]
+		);
+
+		let ignored = HashSet::from([
+			("saga::1".to_string(), "b".to_string()),
+			("saga::1".to_string(), "c".to_string()),
+		]);
+		assert!(
+			group_candidates(DuplicateReason::SeriesNumber, &candidates, &ignored)
+				.is_empty()
+		);
+	}
+}
@@ -0,0 +1,5 @@
+mod job;
+mod resolve;
+
+pub use job::*;
+pub use resolve::*;
@@ -0,0 +1,511 @@
+use chrono::Utc;
+use models::{
+	entity::{
+		bookmark, duplicate_media_group, duplicate_media_group_member, favorite_media,
+		finished_reading_session, ignored_duplicate_media, media, media_annotation,
+		reading_list_item, reading_session,
+	},
+	shared::enums::DuplicateResolution,
+};
+use sea_orm::{
+	prelude::*,
+	sea_query::{Expr, Query},
+	DatabaseTransaction, QuerySelect, Set, TransactionTrait,
+};
+
+use crate::{CoreError, CoreResult};
+
+/// Move the reading sessions of the duplicates onto the survivor. A user can only have
+/// one session per book, so when both books have one the most recently active is kept.
+async fn merge_reading_sessions(
+	txn: &DatabaseTransaction,
+	duplicate_ids: &[String],
+	survivor_id: &str,
+) -> Result<(), DbErr> {
+	let sessions = reading_session::Entity::find()
+		.filter(reading_session::Column::MediaId.is_in(duplicate_ids))
+		.all(txn)
+		.await?;
+
+	for session in sessions {
+		let existing = reading_session::Entity::find()
+			.filter(reading_session::Column::MediaId.eq(survivor_id))
+			.filter(reading_session::Column::UserId.eq(&session.user_id))
+			.one(txn)
+			.await?;
+
+		if let Some(existing) = existing {
+			let last_active = |session: &reading_session::Model| {
+				session.updated_at.unwrap_or(session.started_at)
+			};
+			if last_active(&existing) >= last_active(&session) {
+				session.delete(txn).await?;
+				continue;
+			}
+			existing.delete(txn).await?;
+		}
+
+		reading_session::Entity::update_many()
+			.col_expr(reading_session::Column::MediaId, Expr::value(survivor_id))
+			.filter(reading_session::Column::Id.eq(session.id))
+			.exec(txn)
+			.await?;
+	}
+
+	Ok(())
+}
+
+/// Move the reading list memberships of the duplicates onto the survivor, dropping those
+/// for lists which already contain the survivor
+async fn merge_reading_list_items(
+	txn: &DatabaseTransaction,
+	duplicate_ids: &[String],
+	survivor_id: &str,
+) -> Result<(), DbErr> {
+	let items = reading_list_item::Entity::find()
+		.filter(reading_list_item::Column::MediaId.is_in(duplicate_ids))
+		.all(txn)
+		.await?;
+
+	for item in items {
+		let already_listed = reading_list_item::Entity::find()
+			.filter(reading_list_item::Column::ReadingListId.eq(&item.reading_list_id))
+			.filter(reading_list_item::Column::MediaId.eq(survivor_id))
+			.count(txn)
+			.await? > 0;
+
+		if already_listed {
+			item.delete(txn).await?;
+		} else {
+			reading_list_item::Entity::update_many()
+				.col_expr(reading_list_item::Column::MediaId, Expr::value(survivor_id))
+				.filter(reading_list_item::Column::Id.eq(item.id))
+				.exec(txn)
+				.await?;
+		}
+	}
+
+	Ok(())
+}
+
+/// Move the favorites of the duplicates onto the survivor, dropping those of users who
+/// already favorited the survivor
+async fn merge_favorites(
+	txn: &DatabaseTransaction,
+	duplicate_ids: &[String],
+	survivor_id: &str,
+) -> Result<(), DbErr> {
+	let favorites = favorite_media::Entity::find()
+		.filter(favorite_media::Column::MediaId.is_in(duplicate_ids))
+		.all(txn)
+		.await?;
+
+	for favorite in favorites {
+		let already_favorited = favorite_media::Entity::find_by_id((
+			favorite.user_id.clone(),
+			survivor_id.to_string(),
+		))
+		.one(txn)
+		.await?
+		.is_some();
+
+		if already_favorited {
+			favorite.delete(txn).await?;
+		} else {
+			favorite_media::Entity::update_many()
+				.col_expr(favorite_media::Column::MediaId, Expr::value(survivor_id))
+				.filter(favorite_media::Column::UserId.eq(&favorite.user_id))
+				.filter(favorite_media::Column::MediaId.eq(&favorite.media_id))
+				.exec(txn)
+				.await?;
+		}
+	}
+
+	Ok(())
+}
+
+/// Move everything users have attached to the duplicates (reading progress, history,
+/// bookmarks, annotations, reading list memberships and favorites) onto the survivor
+async fn merge_into_survivor(
+	txn: &DatabaseTransaction,
+	duplicate_ids: &[String],
+	survivor_id: &str,
+) -> Result<(), DbErr> {
+	merge_reading_sessions(txn, duplicate_ids, survivor_id).await?;
+	merge_reading_list_items(txn, duplicate_ids, survivor_id).await?;
+	merge_favorites(txn, duplicate_ids, survivor_id).await?;
+
+	finished_reading_session::Entity::update_many()
+		.col_expr(
+			finished_reading_session::Column::MediaId,
+			Expr::value(survivor_id),
+		)
+		.filter(finished_reading_session::Column::MediaId.is_in(duplicate_ids))
+		.exec(txn)
+		.await?;
+	bookmark::Entity::update_many()
+		.col_expr(bookmark::Column::MediaId, Expr::value(survivor_id))
+		.filter(bookmark::Column::MediaId.is_in(duplicate_ids))
+		.exec(txn)
+		.await?;
+	media_annotation::Entity::update_many()
+		.col_expr(media_annotation::Column::MediaId, Expr::value(survivor_id))
+		.filter(media_annotation::Column::MediaId.is_in(duplicate_ids))
+		.exec(txn)
+		.await?;
+
+	Ok(())
+}
+
+/// Resolve a duplicate group by keeping `survivor_id` and either deleting or ignoring the
+/// rest of its members. Before the other members are deleted, everything attached to them
+/// is moved onto the survivor. Deleted media are soft-deleted, the same as deleting them
+/// individually. Ignored media stay in the library, so they keep everything attached to
+/// them.
+pub async fn resolve_duplicate_group(
+	conn: &DatabaseConnection,
+	group_id: &str,
+	survivor_id: &str,
+	resolution: DuplicateResolution,
+) -> CoreResult<()> {
+	let group = duplicate_media_group::Entity::find_by_id(group_id)
+		.one(conn)
+		.await?
+		.ok_or_else(|| {
+			CoreError::NotFound(format!("Duplicate group with id {group_id}"))
+		})?;
+
+	let member_ids = duplicate_media_group_member::Entity::find()
+		.select_only()
+		.column(duplicate_media_group_member::Column::MediaId)
+		.filter(duplicate_media_group_member::Column::GroupId.eq(&group.id))
+		.into_tuple::<String>()
+		.all(conn)
+		.await?;
+
+	if !member_ids.iter().any(|id| id == survivor_id) {
+		return Err(CoreError::BadRequest(format!(
+			"Media {survivor_id} is not a member of duplicate group {group_id}"
+		)));
+	}
+	let duplicate_ids = member_ids
+		.into_iter()
+		.filter(|id| id != survivor_id)
+		.collect::<Vec<_>>();
+
+	let txn = conn.begin().await?;
+
+	match resolution {
+		DuplicateResolution::Delete => {
+			merge_into_survivor(&txn, &duplicate_ids, survivor_id).await?;
+			media::Entity::update_many()
+				.col_expr(
+					media::Column::DeletedAt,
+					Expr::value(DateTimeWithTimeZone::from(Utc::now())),
+				)
+				.filter(media::Column::Id.is_in(&duplicate_ids))
+				.exec(&txn)
+				.await?;
+			// Deleted media shouldn't linger in the groups found for other reasons
+			duplicate_media_group_member::Entity::delete_many()
+				.filter(
+					duplicate_media_group_member::Column::MediaId.is_in(&duplicate_ids),
+				)
+				.exec(&txn)
+				.await?;
+		},
+		DuplicateResolution::Ignore => {
+			for media_id in &duplicate_ids {
+				ignored_duplicate_media::ActiveModel {
+					reason: Set(group.reason),
+					key: Set(group.key.clone()),
+					media_id: Set(media_id.clone()),
+					..Default::default()
+				}
+				.insert(&txn)
+				.await?;
+			}
+		},
+	}
+
+	duplicate_media_group_member::Entity::delete_many()
+		.filter(duplicate_media_group_member::Column::GroupId.eq(&group.id))
+		.exec(&txn)
+		.await?;
+	group.delete(&txn).await?;
+
+	// Groups left with a single member are no longer duplicates of anything
+	let remaining_groups = Query::select()
+		.column(duplicate_media_group_member::Column::GroupId)
+		.from(duplicate_media_group_member::Entity)
+		.group_by_col(duplicate_media_group_member::Column::GroupId)
+		.and_having(
+			Expr::expr(Expr::col(duplicate_media_group_member::Column::Id).count()).gt(1),
+		)
+		.to_owned();
+	duplicate_media_group_member::Entity::delete_many()
+		.filter(
+			duplicate_media_group_member::Column::GroupId
+				.not_in_subquery(remaining_groups.clone()),
+		)
+		.exec(&txn)
+		.await?;
+	duplicate_media_group::Entity::delete_many()
+		.filter(duplicate_media_group::Column::Id.not_in_subquery(remaining_groups))
+		.exec(&txn)
+		.await?;
+
+	txn.commit().await?;
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ::tests::{db::test_database, fake_data};
+	use models::shared::enums::DuplicateReason;
+
+	async fn create_group(
+		conn: &DatabaseConnection,
+		reason: DuplicateReason,
+		key: &str,
+		media_ids: &[&str],
+	) -> duplicate_media_group::Model {
+		let group = duplicate_media_group::ActiveModel {
+			reason: Set(reason),
+			key: Set(key.to_string()),
+			..Default::default()
+		}
+		.insert(conn)
+		.await
+		.unwrap();
+
+		for media_id in media_ids {
+			duplicate_media_group_member::ActiveModel {
+				group_id: Set(group.id.clone()),
+				media_id: Set(String::from(*media_id)),
+				..Default::default()
+			}
+			.insert(conn)
+			.await
+			.unwrap();
+		}
+
+		group
+	}
+
+	async fn insert_media(conn: &DatabaseConnection, series_id: &str, id: &str) {
+		fake_data::Media {
+			series_id: series_id.to_string(),
+			id: Some(id.to_string()),
+			..Default::default()
+		}
+		.insert(conn)
+		.await;
+	}
+
+	#[tokio::test]
+	async fn test_resolve_by_deleting() {
+		let db = test_database().await;
+		let library = fake_data::Library::default().insert(&db).await;
+		let series = fake_data::Series::in_library(&library.id).insert(&db).await;
+		for id in ["survivor", "duplicate", "other"] {
+			insert_media(&db, &series.id, id).await;
+		}
+		let user = fake_data::User::new("reader").insert(&db).await;
+		let other_user = fake_data::User::new("other").insert(&db).await;
+
+		// The duplicate's progress should win for the user who read it more recently
+		fake_data::ReadingSession {
+			media_id: "survivor".to_string(),
+			user_id: user.id.clone(),
+			percentage_completed: 0.1,
+		}
+		.insert(&db)
+		.await;
+		let newer = fake_data::ReadingSession {
+			media_id: "duplicate".to_string(),
+			user_id: user.id.clone(),
+			percentage_completed: 0.5,
+		}
+		.insert(&db)
+		.await;
+		let mut newer: reading_session::ActiveModel = newer.into();
+		newer.page = Set(Some(10));
+		newer.update(&db).await.unwrap();
+		fake_data::ReadingSession {
+			media_id: "duplicate".to_string(),
+			user_id: other_user.id.clone(),
+			percentage_completed: 0.3,
+		}
+		.insert(&db)
+		.await;
+		fake_data::FinishedReadingSession {
+			media_id: "duplicate".to_string(),
+			user_id: user.id.clone(),
+		}
+		.insert(&db)
+		.await;
+		let list_with_both = fake_data::ReadingList {
+			creating_user_id: user.id.clone(),
+			media_ids: vec!["survivor".to_string(), "duplicate".to_string()],
+			..Default::default()
+		}
+		.insert(&db)
+		.await;
+		let list_with_duplicate = fake_data::ReadingList {
+			creating_user_id: user.id.clone(),
+			media_ids: vec!["duplicate".to_string()],
+			..Default::default()
+		}
+		.insert(&db)
+		.await;
+
+		let group = create_group(
+			&db,
+			DuplicateReason::Hash,
+			"abc",
+			&["duplicate", "survivor"],
+		)
+		.await;
+		// Only the duplicate is shared with this group, so it should be pruned
+		let other_group = create_group(
+			&db,
+			DuplicateReason::SeriesNumber,
+			"saga::1",
+			&["duplicate", "other"],
+		)
+		.await;
+
+		resolve_duplicate_group(&db, &group.id, "survivor", DuplicateResolution::Delete)
+			.await
+			.expect("Failed to resolve duplicate group");
+
+		let sessions = reading_session::Entity::find()
+			.filter(reading_session::Column::MediaId.eq("survivor"))
+			.all(&db)
+			.await
+			.unwrap();
+		assert_eq!(sessions.len(), 2);
+		let user_session = sessions.iter().find(|s| s.user_id == user.id).unwrap();
+		assert_eq!(user_session.page, Some(10));
+
+		let finished = finished_reading_session::Entity::find()
+			.filter(finished_reading_session::Column::MediaId.eq("survivor"))
+			.count(&db)
+			.await
+			.unwrap();
+		assert_eq!(finished, 1);
+
+		let items = reading_list_item::Entity::find()
+			.all(&db)
+			.await
+			.unwrap()
+			.into_iter()
+			.map(|item| (item.reading_list_id, item.media_id))
+			.collect::<Vec<_>>();
+		assert_eq!(items.len(), 2);
+		assert!(items.contains(&(list_with_both.id, "survivor".to_string())));
+		assert!(items.contains(&(list_with_duplicate.id, "survivor".to_string())));
+
+		let duplicate = media::Entity::find_by_id("duplicate")
+			.one(&db)
+			.await
+			.unwrap()
+			.unwrap();
+		assert!(duplicate.deleted_at.is_some());
+
+		assert!(duplicate_media_group::Entity::find_by_id(&group.id)
+			.one(&db)
+			.await
+			.unwrap()
+			.is_none());
+		assert!(duplicate_media_group::Entity::find_by_id(&other_group.id)
+			.one(&db)
+			.await
+			.unwrap()
+			.is_none());
+	}
+
+	#[tokio::test]
+	async fn test_resolve_by_ignoring() {
+		let db = test_database().await;
+		let library = fake_data::Library::default().insert(&db).await;
+		let series = fake_data::Series::in_library(&library.id).insert(&db).await;
+		for id in ["survivor", "duplicate"] {
+			insert_media(&db, &series.id, id).await;
+		}
+
+		let user = fake_data::User::new("reader").insert(&db).await;
+		fake_data::ReadingSession {
+			media_id: "duplicate".to_string(),
+			user_id: user.id.clone(),
+			percentage_completed: 0.5,
+		}
+		.insert(&db)
+		.await;
+		bookmark::ActiveModel {
+			id: Set("bookmark".to_string()),
+			page: Set(Some(3)),
+			media_id: Set("duplicate".to_string()),
+			user_id: Set(user.id.clone()),
+			..Default::default()
+		}
+		.insert(&db)
+		.await
+		.unwrap();
+
+		let group = create_group(
+			&db,
+			DuplicateReason::Identifier,
+			"isbn:9781603090254",
+			&["duplicate", "survivor"],
+		)
+		.await;
+
+		let result = resolve_duplicate_group(
+			&db,
+			&group.id,
+			"not-a-member",
+			DuplicateResolution::Ignore,
+		)
+		.await;
+		assert!(matches!(result, Err(CoreError::BadRequest(_))));
+
+		resolve_duplicate_group(&db, &group.id, "survivor", DuplicateResolution::Ignore)
+			.await
+			.expect("Failed to resolve duplicate group");
+
+		let ignored = ignored_duplicate_media::Entity::find()
+			.all(&db)
+			.await
+			.unwrap();
+		assert_eq!(ignored.len(), 1);
+		assert_eq!(ignored[0].media_id, "duplicate");
+		assert_eq!(ignored[0].reason, DuplicateReason::Identifier);
+		assert_eq!(ignored[0].key, "isbn:9781603090254");
+
+		let duplicate = media::Entity::find_by_id("duplicate")
+			.one(&db)
+			.await
+			.unwrap()
+			.unwrap();
+		assert!(duplicate.deleted_at.is_none());
+
+		// The ignored media is still in the library, so its progress and bookmarks stay
+		let session = reading_session::Entity::find()
+			.filter(reading_session::Column::UserId.eq(&user.id))
+			.one(&db)
+			.await
+			.unwrap()
+			.expect("missing reading session");
+		assert_eq!(session.media_id, "duplicate");
+		let bookmark = bookmark::Entity::find_by_id("bookmark")
+			.one(&db)
+			.await
+			.unwrap()
+			.expect("missing bookmark");
+		assert_eq!(bookmark.media_id, "duplicate");
+	}
+}
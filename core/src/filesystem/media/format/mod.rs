@@ -1,4 +1,5 @@
 pub mod epub;
 pub mod pdf;
 pub mod rar;
+pub mod seven_zip;
 pub mod zip;
@@ -704,9 +704,8 @@ impl FileConverter for PdfProcessor {
 			.set_path_smoothing(true);
 
 		// Prefer configured format, then provided format, then default to WebP for better compression
-		let output_format = format
-			.map(into_image_format)
-			.unwrap_or_else(|| into_image_format(config.get_pdf_render_format()));
+		let format = format.unwrap_or_else(|| config.get_pdf_render_format());
+		let output_format = into_image_format(format);
 
 		let converted_pages = iter
 			.enumerate()
@@ -740,24 +739,22 @@ impl FileConverter for PdfProcessor {
 		let path_buf = PathBuf::from(path);
 		let parent = path_buf.parent().unwrap_or_else(|| Path::new("/"));
 		let FileParts {
-			file_name,
 			file_stem,
 			extension,
+			..
 		} = path_buf.as_path().file_parts();
 
 		let cache_dir = config.get_cache_dir();
-		let unpacked_path = cache_dir.join(file_stem);
+		let unpacked_path = cache_dir.join(&file_stem);
 
 		// create folder for the zip
 		std::fs::create_dir_all(&unpacked_path)?;
 
-		// write each image to the folder
-		for image_buf in converted_pages {
-			// write the image to file with proper extension
-			let output_extension = format.as_ref().map_or("png", |f| f.extension());
-
-			let image_path =
-				unpacked_path.join(format!("{file_name}.{output_extension}"));
+		// write each image to the folder, padded so the pages sort in reading order
+		let output_extension = format.extension();
+		for (idx, image_buf) in converted_pages.into_iter().enumerate() {
+			let image_path = unpacked_path
+				.join(format!("{file_stem}_{:04}.{output_extension}", idx + 1));
 
 			// NOTE: This isn't bubbling up because I don't think at this point it should
 			// kill the whole conversion process.
@@ -766,15 +763,8 @@ impl FileConverter for PdfProcessor {
 			}
 		}
 
-		let zip_path =
-			create_zip_archive(&unpacked_path, &file_name, &extension, parent)?;
-
-		// TODO: won't work in docker
-		if delete_source {
-			if let Err(err) = trash::delete(path) {
-				tracing::error!(error = ?err, path, "Failed to delete converted PDF source file");
-			}
-		}
+		let zip_result =
+			create_zip_archive(&unpacked_path, &file_stem, &extension, parent);
 
 		// TODO: maybe check that this path isn't in a pre-defined list of important paths?
 		if let Err(err) = std::fs::remove_dir_all(&unpacked_path) {
@@ -783,6 +773,15 @@ impl FileConverter for PdfProcessor {
 			);
 		}
 
+		let zip_path = zip_result?;
+
+		// TODO: won't work in docker
+		if delete_source {
+			if let Err(err) = trash::delete(path) {
+				tracing::error!(error = ?err, path, "Failed to delete converted PDF source file");
+			}
+		}
+
 		Ok(zip_path)
 	}
 }
@@ -405,11 +405,11 @@ impl FileConverter for RarProcessor {
 		let FileParts {
 			extension,
 			file_stem,
-			file_name,
+			..
 		} = path_buf.as_path().file_parts();
 
 		let cache_dir = config.get_cache_dir();
-		let unpacked_path = cache_dir.join(file_stem);
+		let unpacked_path = cache_dir.join(&file_stem);
 
 		trace!(?unpacked_path, "Extracting RAR to disk");
 
@@ -422,15 +422,8 @@ impl FileConverter for RarProcessor {
 			};
 		}
 
-		let zip_path =
-			create_zip_archive(&unpacked_path, &file_name, &extension, parent)?;
-
-		// TODO: won't work in docker
-		if delete_source {
-			if let Err(err) = trash::delete(path) {
-				warn!(error = ?err, path, "Failed to delete converted RAR file");
-			}
-		}
+		let zip_result =
+			create_zip_archive(&unpacked_path, &file_stem, &extension, parent);
 
 		// TODO: maybe check that this path isn't in a pre-defined list of important paths?
 		if let Err(err) = std::fs::remove_dir_all(&unpacked_path) {
@@ -439,6 +432,15 @@ impl FileConverter for RarProcessor {
 			);
 		}
 
+		let zip_path = zip_result?;
+
+		// TODO: won't work in docker
+		if delete_source {
+			if let Err(err) = trash::delete(path) {
+				warn!(error = ?err, path, "Failed to delete converted RAR file");
+			}
+		}
+
 		Ok(zip_path)
 	}
 }
@@ -497,8 +499,10 @@ mod tests {
 
 		// We have a temporary file, so we may as well test deletion also
 		let zip_result = RarProcessor::to_zip(&temp_rar_file_path, true, None, &config);
-		// Assert that operation succeeded
-		assert!(zip_result.is_ok());
+		// Assert that operation succeeded, producing a zip named after the rar
+		let zip_path = zip_result.expect("Failed to convert rar to zip");
+		assert_eq!(zip_path, tempdir.path().join("book.zip"));
+		assert!(zip_path.exists());
 		// And that the original file was deleted
 		assert!(!Path::new(&temp_rar_file_path).exists());
 	}
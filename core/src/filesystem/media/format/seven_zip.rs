@@ -0,0 +1,442 @@
+use itertools::Itertools;
+use models::shared::image_processor_options::SupportedImageFormat;
+use sevenz_rust::{Archive, Password, SevenZReader};
+use std::{
+	collections::HashMap,
+	fs::File,
+	io::{self, Read},
+	path::{Path, PathBuf},
+};
+use tracing::{debug, error, trace, warn};
+
+use crate::{
+	config::StumpConfig,
+	filesystem::{
+		archive::create_zip_archive,
+		content_type::ContentType,
+		error::FileError,
+		hash::{self, HASH_SAMPLE_COUNT, HASH_SAMPLE_SIZE},
+		media::{
+			process::{
+				AnalyzedPage, FileConverter, FileProcessor, FileProcessorOptions,
+				ProcessedFile, ProcessedFileHashes,
+			},
+			utils::metadata_from_buf,
+			ProcessedMediaMetadata,
+		},
+		FileParts, PathUtils,
+	},
+};
+
+/// A file processor for 7z archives (CB7 files).
+pub struct SevenZipProcessor;
+
+/// Entry names are stored with whichever separator the archiver used, which is a backslash
+/// for archives created on Windows
+fn entry_path(name: &str) -> PathBuf {
+	PathBuf::from(name.replace('\\', "/"))
+}
+
+impl SevenZipProcessor {
+	fn open(path: &str) -> Result<SevenZReader<File>, FileError> {
+		Ok(SevenZReader::open(path, Password::empty())?)
+	}
+
+	/// The names of the entries which are pages, in reading order
+	fn sorted_page_names(archive: &Archive) -> Vec<String> {
+		archive
+			.files
+			.iter()
+			.filter(|entry| !entry.is_directory && entry.has_stream)
+			.map(|entry| entry.name.clone())
+			.filter(|name| {
+				let path = entry_path(name);
+				path.is_img() && !path.is_hidden_file()
+			})
+			.sorted_by(|a, b| {
+				alphanumeric_sort::compare_path(entry_path(a), entry_path(b))
+			})
+			.collect()
+	}
+
+	/// Read the entry `name` from the archive, stopping after `limit` bytes if one is given.
+	/// Most 7z archives are solid, so every entry before the target has to be decompressed
+	/// (and discarded) to get to it.
+	fn read_entry(
+		reader: &mut SevenZReader<File>,
+		name: &str,
+		limit: Option<u64>,
+	) -> Result<Option<Vec<u8>>, FileError> {
+		let mut contents = None;
+
+		reader.for_each_entries(|entry, entry_reader| {
+			if entry.name != name {
+				io::copy(entry_reader, &mut io::sink())?;
+				return Ok(true);
+			}
+
+			let mut buf = Vec::new();
+			match limit {
+				Some(limit) => entry_reader.take(limit).read_to_end(&mut buf)?,
+				None => entry_reader.read_to_end(&mut buf)?,
+			};
+			trace!(name, contents_len = buf.len(), "Read 7z entry");
+			contents = Some(buf);
+
+			Ok(false)
+		})?;
+
+		Ok(contents)
+	}
+
+	/// Read the page at the given (1-based) index, stopping after `limit` bytes if one is given
+	fn read_page(
+		path: &str,
+		page: i32,
+		limit: Option<u64>,
+	) -> Result<(PathBuf, Vec<u8>), FileError> {
+		let mut reader = SevenZipProcessor::open(path)?;
+
+		let name = usize::try_from(page - 1)
+			.ok()
+			.and_then(|idx| {
+				SevenZipProcessor::sorted_page_names(reader.archive())
+					.into_iter()
+					.nth(idx)
+			})
+			.ok_or(FileError::NoImageError)?;
+		trace!(?name, page, "Found targeted 7z entry");
+
+		let bytes = SevenZipProcessor::read_entry(&mut reader, &name, limit)?
+			.ok_or(FileError::NoImageError)?;
+
+		Ok((entry_path(&name), bytes))
+	}
+}
+
+impl FileProcessor for SevenZipProcessor {
+	fn get_sample_size(path: &str) -> Result<u64, FileError> {
+		let file = File::open(path)?;
+
+		let file_size = file.metadata()?.len();
+		let threshold = HASH_SAMPLE_SIZE * HASH_SAMPLE_COUNT;
+
+		if file_size < threshold {
+			return Ok(file_size);
+		}
+
+		let division = file_size / threshold;
+
+		// if the file size is 4x the threshold, we'll take up to the threshold.
+		if division > 4 {
+			Ok(threshold)
+		} else {
+			Ok(file_size / 2)
+		}
+	}
+
+	fn generate_stump_hash(path: &str) -> Option<String> {
+		let sample_result = SevenZipProcessor::get_sample_size(path).ok();
+
+		if let Some(sample) = sample_result {
+			match hash::generate(path, sample) {
+				Ok(digest) => Some(digest),
+				Err(e) => {
+					debug!(error = ?e, path, "Failed to digest 7z file");
+
+					None
+				},
+			}
+		} else {
+			None
+		}
+	}
+
+	fn generate_hashes(
+		path: &str,
+		FileProcessorOptions {
+			generate_file_hashes,
+			..
+		}: FileProcessorOptions,
+	) -> Result<ProcessedFileHashes, FileError> {
+		let hash = generate_file_hashes
+			.then(|| SevenZipProcessor::generate_stump_hash(path))
+			.flatten();
+
+		Ok(ProcessedFileHashes {
+			hash,
+			koreader_hash: None,
+		})
+	}
+
+	fn process_metadata(path: &str) -> Result<Option<ProcessedMediaMetadata>, FileError> {
+		let mut reader = SevenZipProcessor::open(path)?;
+
+		let comic_info_name = reader
+			.archive()
+			.files
+			.iter()
+			.filter(|entry| !entry.is_directory && entry.has_stream)
+			.find(|entry| {
+				let path = entry_path(&entry.name);
+				!path.is_hidden_file() && path.file_parts().file_name == "ComicInfo.xml"
+			})
+			.map(|entry| entry.name.clone());
+
+		let Some(name) = comic_info_name else {
+			return Ok(None);
+		};
+		trace!(?name, "Found ComicInfo.xml");
+
+		let metadata = SevenZipProcessor::read_entry(&mut reader, &name, None)?
+			.and_then(|buf| metadata_from_buf(&String::from_utf8_lossy(&buf)));
+
+		Ok(metadata)
+	}
+
+	fn process(
+		path: &str,
+		options: FileProcessorOptions,
+		_: &StumpConfig,
+	) -> Result<ProcessedFile, FileError> {
+		let ProcessedFileHashes {
+			hash,
+			koreader_hash,
+		} = SevenZipProcessor::generate_hashes(path, options)?;
+
+		let pages = {
+			let reader = SevenZipProcessor::open(path)?;
+			SevenZipProcessor::sorted_page_names(reader.archive()).len() as i32
+		};
+
+		let metadata = if options.process_metadata {
+			SevenZipProcessor::process_metadata(path)?
+		} else {
+			None
+		};
+
+		Ok(ProcessedFile {
+			path: PathBuf::from(path),
+			hash,
+			koreader_hash,
+			metadata,
+			pages,
+		})
+	}
+
+	fn get_page(
+		path: &str,
+		page: i32,
+		_: &StumpConfig,
+	) -> Result<(ContentType, Vec<u8>), FileError> {
+		let (entry_path, bytes) = SevenZipProcessor::read_page(path, page, None)?;
+		let FileParts { extension, .. } = entry_path.as_path().file_parts();
+
+		if bytes.is_empty() {
+			debug!(path, page, "7z entry is empty");
+			return Err(FileError::NoImageError);
+		}
+		let content_type = ContentType::from_bytes_with_fallback(&bytes, &extension);
+
+		Ok((content_type, bytes))
+	}
+
+	fn get_page_count(path: &str, _: &StumpConfig) -> Result<i32, FileError> {
+		let reader = SevenZipProcessor::open(path)?;
+		let page_count = SevenZipProcessor::sorted_page_names(reader.archive()).len();
+
+		Ok(page_count as i32)
+	}
+
+	fn get_page_content_types(
+		path: &str,
+		pages: Vec<i32>,
+	) -> Result<HashMap<i32, ContentType>, FileError> {
+		let reader = SevenZipProcessor::open(path)?;
+
+		let content_types = SevenZipProcessor::sorted_page_names(reader.archive())
+			.into_iter()
+			.enumerate()
+			.map(|(idx, name)| (idx as i32 + 1, name))
+			.filter(|(page, _)| pages.contains(page))
+			.map(|(page, name)| (page, entry_path(&name).naive_content_type()))
+			.collect();
+
+		Ok(content_types)
+	}
+
+	fn analyze_page(
+		path: &str,
+		page: i32,
+		_: &StumpConfig,
+	) -> Result<AnalyzedPage, FileError> {
+		// imagesize only needs the first few KB to read dimensions from headers, taking
+		// extra as a precaution. 64kb should be plenty.
+		const MAX_HEADER_BYTES: u64 = 64 * 1024;
+		let (entry_path, bytes) =
+			SevenZipProcessor::read_page(path, page, Some(MAX_HEADER_BYTES))?;
+		let content_type = entry_path.as_path().naive_content_type();
+
+		let size = imagesize::blob_size(&bytes).map_err(|e| {
+			FileError::UnknownError(format!("Failed to read image dimensions: {e}"))
+		})?;
+
+		Ok(AnalyzedPage {
+			width: size.width as u32,
+			height: size.height as u32,
+			content_type,
+		})
+	}
+}
+
+impl FileConverter for SevenZipProcessor {
+	fn to_zip(
+		path: &str,
+		delete_source: bool,
+		_: Option<SupportedImageFormat>,
+		config: &StumpConfig,
+	) -> Result<PathBuf, FileError> {
+		debug!(path, "Converting 7z to ZIP");
+
+		let path_buf = PathBuf::from(path);
+		let parent = path_buf.parent().unwrap_or_else(|| Path::new("/"));
+		let FileParts {
+			extension,
+			file_stem,
+			..
+		} = path_buf.as_path().file_parts();
+
+		let cache_dir = config.get_cache_dir();
+		let unpacked_path = cache_dir.join(&file_stem);
+
+		trace!(?unpacked_path, "Extracting 7z to disk");
+
+		let zip_result = sevenz_rust::decompress_file(path, &unpacked_path)
+			.map_err(FileError::from)
+			.and_then(|_| {
+				create_zip_archive(&unpacked_path, &file_stem, &extension, parent)
+					.map_err(FileError::from)
+			});
+
+		if let Err(err) = std::fs::remove_dir_all(&unpacked_path) {
+			error!(
+				error = ?err, ?cache_dir, ?unpacked_path, "Failed to delete unpacked 7z contents after conversion",
+			);
+		}
+
+		let zip_path = zip_result?;
+
+		if delete_source {
+			if let Err(err) = trash::delete(path) {
+				warn!(error = ?err, path, "Failed to delete converted 7z file");
+			}
+		}
+
+		Ok(zip_path)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::filesystem::media::tests::get_test_cb7_path;
+
+	#[test]
+	fn test_process() {
+		let path = get_test_cb7_path();
+		let config = StumpConfig::debug();
+
+		let processed_file = SevenZipProcessor::process(
+			&path,
+			FileProcessorOptions {
+				process_metadata: true,
+				generate_file_hashes: true,
+				..Default::default()
+			},
+			&config,
+		)
+		.expect("Failed to process 7z file");
+
+		// The hidden .DS_Store and ComicInfo.xml are not pages
+		assert_eq!(processed_file.pages, 3);
+		assert!(processed_file.hash.is_some());
+
+		let metadata = processed_file.metadata.expect("Missing metadata");
+		assert_eq!(
+			metadata.series.as_deref(),
+			Some("Miles Morales: Spider-Man")
+		);
+		assert_eq!(metadata.number, Some(4.0));
+	}
+
+	#[test]
+	fn test_get_page_count() {
+		let path = get_test_cb7_path();
+
+		let page_count = SevenZipProcessor::get_page_count(&path, &StumpConfig::debug())
+			.expect("Failed to get page count");
+		assert_eq!(page_count, 3);
+	}
+
+	#[test]
+	fn test_get_page() {
+		let path = get_test_cb7_path();
+		let config = StumpConfig::debug();
+
+		// Pages are naturally sorted, so "page 10" comes after "page 2"
+		let (content_type, buf) =
+			SevenZipProcessor::get_page(&path, 1, &config).expect("Failed to get page");
+		assert_eq!(content_type, ContentType::JPEG);
+		assert_eq!(buf.len(), 64410);
+
+		let (content_type, buf) =
+			SevenZipProcessor::get_page(&path, 2, &config).expect("Failed to get page");
+		assert_eq!(content_type, ContentType::WEBP);
+		assert_eq!(buf.len(), 30320);
+
+		let (content_type, buf) =
+			SevenZipProcessor::get_page(&path, 3, &config).expect("Failed to get page");
+		assert_eq!(content_type, ContentType::JPEG);
+		assert_eq!(buf.len(), 96623);
+
+		assert!(SevenZipProcessor::get_page(&path, 4, &config).is_err());
+	}
+
+	#[test]
+	fn test_get_page_content_types() {
+		let path = get_test_cb7_path();
+
+		let content_types = SevenZipProcessor::get_page_content_types(&path, vec![2, 3])
+			.expect("Failed to get page content types");
+		assert_eq!(content_types.len(), 2);
+		assert_eq!(content_types.get(&2), Some(&ContentType::WEBP));
+		assert_eq!(content_types.get(&3), Some(&ContentType::JPEG));
+	}
+
+	#[test]
+	fn test_analyze_page() {
+		let path = get_test_cb7_path();
+
+		let analyzed_page =
+			SevenZipProcessor::analyze_page(&path, 3, &StumpConfig::debug())
+				.expect("Failed to analyze page");
+		assert_eq!(analyzed_page.content_type, ContentType::JPEG);
+		assert!(analyzed_page.width > 0 && analyzed_page.height > 0);
+	}
+
+	#[test]
+	fn test_7z_to_zip() {
+		let tempdir = tempfile::tempdir().expect("Failed to create temporary directory");
+		let temp_path = tempdir.path().join("book.cb7");
+		std::fs::copy(get_test_cb7_path(), &temp_path).expect("Failed to copy book.cb7");
+		let temp_path = temp_path.to_string_lossy().to_string();
+
+		let mut config = StumpConfig::debug();
+		config.config_dir = tempdir.path().to_string_lossy().to_string();
+
+		let zip_path = SevenZipProcessor::to_zip(&temp_path, false, None, &config)
+			.expect("Failed to convert 7z to zip");
+		assert_eq!(zip_path, tempdir.path().join("book.cbz"));
+		assert!(Path::new(&temp_path).exists());
+	}
+}
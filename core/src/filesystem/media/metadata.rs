@@ -99,7 +99,7 @@ pub struct ProcessedMediaMetadata {
 	)]
 	pub tags: Option<Vec<String>>,
 	/// The language of the media
-	#[serde(alias = "Language")]
+	#[serde(alias = "Language", alias = "LanguageISO")]
 	pub language: Option<String>,
 
 	/// The year the media was published.
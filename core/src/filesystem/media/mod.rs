@@ -1,9 +1,12 @@
 pub mod analysis;
 mod builder;
+pub mod convert;
+pub mod duplicates;
 mod format;
 mod metadata;
 mod process;
 mod utils;
+pub mod write_back;
 
 pub use crate::filesystem::media::epub::EpubProcessor;
 pub(crate) use builder::{BuiltMedia, MediaBuilder};
@@ -50,6 +53,13 @@ pub(crate) mod tests {
 			.to_string()
 	}
 
+	pub fn get_test_cb7_path() -> String {
+		PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+			.join("integration-tests/data/book.cb7")
+			.to_string_lossy()
+			.to_string()
+	}
+
 	pub fn get_test_epub_path() -> String {
 		PathBuf::from(env!("CARGO_MANIFEST_DIR"))
 			.join("integration-tests/data/book.epub")
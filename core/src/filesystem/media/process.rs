@@ -18,7 +18,10 @@ use crate::{
 	},
 };
 
-use super::{metadata::ProcessedMediaMetadata, rar::RarProcessor, zip::ZipProcessor};
+use super::{
+	metadata::ProcessedMediaMetadata, rar::RarProcessor, seven_zip::SevenZipProcessor,
+	zip::ZipProcessor,
+};
 
 /// A struct representing the options for processing a file. This is a subset of [`LibraryConfig`]
 /// and is used to pass options to the [`FileProcessor`] implementations.
@@ -155,6 +158,7 @@ pub struct ProcessedFile {
 enum ProcessorType {
 	Zip,
 	Rar,
+	SevenZip,
 	Epub,
 	Pdf,
 }
@@ -177,6 +181,9 @@ fn determine_processor(path: &Path) -> Result<ProcessorType, FileError> {
 		("application/vnd.rar" | "application/vnd.comicbook-rar", _) => {
 			Ok(ProcessorType::Rar)
 		},
+		("application/x-7z-compressed" | "application/x-cb7", _) => {
+			Ok(ProcessorType::SevenZip)
+		},
 		("application/epub+zip", _) => Ok(ProcessorType::Epub),
 		("application/zip", "epub") => Ok(ProcessorType::Epub),
 		("application/pdf", _) => Ok(ProcessorType::Pdf),
@@ -194,6 +201,7 @@ macro_rules! dispatch_processor {
         match processor_type {
             ProcessorType::Zip => ZipProcessor::$method($($arg),*),
             ProcessorType::Rar => RarProcessor::$method($($arg),*),
+            ProcessorType::SevenZip => SevenZipProcessor::$method($($arg),*),
             ProcessorType::Epub => EpubProcessor::$method($($arg),*),
             ProcessorType::Pdf => PdfProcessor::$method($($arg),*),
         }
@@ -331,6 +339,74 @@ pub async fn generate_hashes_async(
 	Ok(processed_hashes)
 }
 
+/// A function to convert a file to a zip archive in a blocking manner. This will call the
+/// appropriate [`FileConverter::to_zip`] implementation based on the file's mime type, or return
+/// an error if the file type cannot be converted.
+pub fn convert_to_zip(
+	path: &Path,
+	delete_source: bool,
+	image_format: Option<SupportedImageFormat>,
+	config: &StumpConfig,
+) -> Result<PathBuf, FileError> {
+	let path_str = path.to_str().unwrap_or_default();
+	match determine_processor(path)? {
+		ProcessorType::Rar => {
+			RarProcessor::to_zip(path_str, delete_source, image_format, config)
+		},
+		ProcessorType::SevenZip => {
+			SevenZipProcessor::to_zip(path_str, delete_source, image_format, config)
+		},
+		ProcessorType::Pdf => {
+			PdfProcessor::to_zip(path_str, delete_source, image_format, config)
+		},
+		_ => Err(FileError::UnsupportedFileType(path.display().to_string())),
+	}
+}
+
+/// A function to convert a file to a zip archive in the context of a spawned, blocking task.
+/// This will call the [convert_to_zip] function and send the result back out through a
+/// oneshot channel.
+#[tracing::instrument(err, fields(path = %path.as_ref().display()))]
+pub async fn convert_to_zip_async(
+	path: impl AsRef<Path>,
+	delete_source: bool,
+	image_format: Option<SupportedImageFormat>,
+	config: &StumpConfig,
+) -> Result<PathBuf, FileError> {
+	let (tx, rx) = oneshot::channel();
+
+	let handle = spawn_blocking({
+		let path = path.as_ref().to_path_buf();
+		let config = config.clone();
+
+		move || {
+			let send_result = tx.send(convert_to_zip(
+				path.as_path(),
+				delete_source,
+				image_format,
+				&config,
+			));
+			tracing::trace!(
+				is_err = send_result.is_err(),
+				"Sending result of sync convert_to_zip"
+			);
+		}
+	});
+
+	let zip_path = if let Ok(recv) = rx.await {
+		recv?
+	} else {
+		handle
+			.await
+			.map_err(|e| FileError::UnknownError(e.to_string()))?;
+		return Err(FileError::UnknownError(
+			"Failed to receive converted file".to_string(),
+		));
+	};
+
+	Ok(zip_path)
+}
+
 /// A function to extract the bytes of a page from a file in a blocking manner. This will call the
 /// appropriate [`FileProcessor::get_page`] implementation based on the file's mime type, or return an
 /// error if the file type is not supported.
@@ -509,8 +585,8 @@ pub async fn get_content_type_for_page(
 mod tests {
 	use super::*;
 	use crate::filesystem::media::tests::{
-		get_test_cbz_path, get_test_epub_path, get_test_pdf_path, get_test_rar_path,
-		get_test_zip_path,
+		get_test_cb7_path, get_test_cbz_path, get_test_epub_path, get_test_pdf_path,
+		get_test_rar_path, get_test_zip_path,
 	};
 
 	#[test]
@@ -540,6 +616,15 @@ mod tests {
 		assert!(matches!(result.unwrap(), ProcessorType::Rar));
 	}
 
+	#[test]
+	fn test_determine_processor_cb7() {
+		let path_str = get_test_cb7_path();
+		let path = Path::new(&path_str);
+		let result = determine_processor(path);
+		assert!(result.is_ok());
+		assert!(matches!(result.unwrap(), ProcessorType::SevenZip));
+	}
+
 	#[test]
 	fn test_determine_processor_epub() {
 		let path_str = get_test_epub_path();
@@ -0,0 +1,214 @@
+use std::{
+	fs::File,
+	io::{Read, Write},
+	path::{Path, PathBuf},
+};
+
+use models::entity::media_metadata;
+use zip::{write::FileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+use super::{
+	comic_info::render_comic_info,
+	opf::{find_rootfile_path, render_opf},
+};
+use crate::filesystem::error::FileError;
+
+/// Read the first entry of a zip archive matching the predicate, returning its name and
+/// contents
+fn find_entry<F>(path: &Path, predicate: F) -> Result<Option<(String, String)>, FileError>
+where
+	F: Fn(&str) -> bool,
+{
+	let mut archive = ZipArchive::new(File::open(path)?)?;
+
+	let Some(name) = archive.file_names().find(|name| predicate(name)) else {
+		return Ok(None);
+	};
+	let name = name.to_string();
+
+	let mut contents = String::new();
+	archive.by_name(&name)?.read_to_string(&mut contents)?;
+
+	Ok(Some((name, contents)))
+}
+
+/// Write a copy of the archive at `source` to `destination` with the entry `name` replaced by
+/// (or, if missing, extended with) `contents`. Every other entry is copied as-is, without being
+/// decompressed, so pages and the position of an EPUB's `mimetype` are left untouched.
+fn copy_with_entry(
+	source: &Path,
+	destination: &Path,
+	name: &str,
+	contents: &[u8],
+) -> Result<(), FileError> {
+	let mut archive = ZipArchive::new(File::open(source)?)?;
+	let mut writer = ZipWriter::new(File::create(destination)?);
+	let options: FileOptions<()> =
+		FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+	let mut replaced = false;
+	for index in 0..archive.len() {
+		let entry = archive.by_index_raw(index)?;
+		if entry.name() == name {
+			if !replaced {
+				writer.start_file(name, options)?;
+				writer.write_all(contents)?;
+				replaced = true;
+			}
+			continue;
+		}
+		writer.raw_copy_file(entry)?;
+	}
+
+	if !replaced {
+		writer.start_file(name, options)?;
+		writer.write_all(contents)?;
+	}
+
+	writer.finish()?;
+
+	Ok(())
+}
+
+/// Replace a single entry of the zip archive at `path`. The new archive is written next to the
+/// original and renamed over it, so a failure part way through never leaves a broken file.
+fn replace_entry(path: &Path, name: &str, contents: &[u8]) -> Result<(), FileError> {
+	let extension = path
+		.extension()
+		.map(|ext| format!("{}.tmp", ext.to_string_lossy()))
+		.unwrap_or_else(|| "tmp".to_string());
+	let tmp_path: PathBuf = path.with_extension(extension);
+
+	if let Err(error) = copy_with_entry(path, &tmp_path, name, contents) {
+		let _ = std::fs::remove_file(&tmp_path);
+		return Err(error);
+	}
+	std::fs::rename(&tmp_path, path)?;
+
+	Ok(())
+}
+
+/// Regenerate the root ComicInfo.xml of a zip/cbz archive from the stored metadata, adding
+/// one if the archive doesn't have it yet
+pub(crate) fn write_comic_info(
+	path: &Path,
+	metadata: &media_metadata::Model,
+) -> Result<(), FileError> {
+	let existing = find_entry(path, |name| name.eq_ignore_ascii_case("ComicInfo.xml"))?;
+	let (name, existing) = match existing {
+		Some((name, xml)) => (name, Some(xml)),
+		None => ("ComicInfo.xml".to_string(), None),
+	};
+
+	let comic_info = render_comic_info(metadata, existing.as_deref())?;
+	replace_entry(path, &name, comic_info.as_bytes())
+}
+
+/// Update the OPF package document of an EPUB from the stored metadata
+pub(crate) fn write_opf(
+	path: &Path,
+	metadata: &media_metadata::Model,
+) -> Result<(), FileError> {
+	let (_, container) = find_entry(path, |name| name == "META-INF/container.xml")?
+		.ok_or_else(|| {
+			FileError::EpubReadError("Missing META-INF/container.xml".to_string())
+		})?;
+	let rootfile = find_rootfile_path(&container).ok_or_else(|| {
+		FileError::EpubReadError("Failed to find the OPF rootfile".to_string())
+	})?;
+	let (name, opf) = find_entry(path, |name| name == rootfile)?.ok_or_else(|| {
+		FileError::EpubReadError(format!("Missing OPF document {rootfile}"))
+	})?;
+
+	let opf = render_opf(metadata, &opf)?;
+	replace_entry(path, &name, opf.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::filesystem::media::{
+		process_metadata,
+		tests::{get_test_cbz_path, get_test_epub_path},
+	};
+
+	fn copy_to_temp(source: &str, name: &str) -> (tempfile::TempDir, PathBuf) {
+		let dir = tempfile::tempdir().expect("Failed to create temp dir");
+		let path = dir.path().join(name);
+		std::fs::copy(source, &path).expect("Failed to copy test file");
+		(dir, path)
+	}
+
+	fn entries(path: &Path) -> Vec<(String, u32)> {
+		let mut archive = ZipArchive::new(File::open(path).unwrap()).unwrap();
+		(0..archive.len())
+			.map(|index| {
+				let entry = archive.by_index_raw(index).unwrap();
+				(entry.name().to_string(), entry.crc32())
+			})
+			.collect()
+	}
+
+	#[test]
+	fn test_write_comic_info() {
+		let (_dir, path) = copy_to_temp(&get_test_cbz_path(), "book.cbz");
+		let before = entries(&path);
+
+		let metadata = media_metadata::Model {
+			title: Some("Written back".to_string()),
+			number: Some("3".parse().unwrap()),
+			..Default::default()
+		};
+		write_comic_info(&path, &metadata).expect("Failed to write ComicInfo.xml");
+
+		let processed = process_metadata(&path)
+			.expect("Failed to process metadata")
+			.expect("Missing metadata");
+		assert_eq!(processed.title.as_deref(), Some("Written back"));
+		assert_eq!(processed.number, Some(3.0));
+
+		// Every page is still there, byte for byte
+		let after = entries(&path);
+		for entry in before
+			.iter()
+			.filter(|(name, _)| !name.eq_ignore_ascii_case("ComicInfo.xml"))
+		{
+			assert!(after.contains(entry), "{entry:?} was modified");
+		}
+		assert_eq!(
+			after
+				.iter()
+				.filter(|(name, _)| name.eq_ignore_ascii_case("ComicInfo.xml"))
+				.count(),
+			1
+		);
+	}
+
+	#[test]
+	fn test_write_opf() {
+		let (_dir, path) = copy_to_temp(&get_test_epub_path(), "book.epub");
+		let before = entries(&path);
+
+		let metadata = media_metadata::Model {
+			title: Some("Alice in Wonderland".to_string()),
+			series: Some("Alice".to_string()),
+			..Default::default()
+		};
+		write_opf(&path, &metadata).expect("Failed to write OPF");
+
+		let processed = process_metadata(&path)
+			.expect("Failed to process metadata")
+			.expect("Missing metadata");
+		assert_eq!(processed.title.as_deref(), Some("Alice in Wonderland"));
+		assert_eq!(processed.series.as_deref(), Some("Alice"));
+		// Not managed, so preserved from the original
+		assert_eq!(processed.language.as_deref(), Some("en"));
+
+		let after = entries(&path);
+		assert_eq!(
+			after.first().map(|(name, _)| name.as_str()),
+			Some("mimetype")
+		);
+		assert_eq!(before.len(), after.len());
+	}
+}
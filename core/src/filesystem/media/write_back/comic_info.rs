@@ -0,0 +1,206 @@
+use models::entity::media_metadata;
+
+use super::xml::{escape, replace_managed_children};
+use crate::filesystem::error::FileError;
+
+/// The elements Stump owns when writing a ComicInfo.xml. Anything else found in an existing
+/// file (e.g. `<Pages>` or `<Tags>`) is carried over as-is.
+const MANAGED_ELEMENTS: [&str; 30] = [
+	"Title",
+	"TitleSort",
+	"Series",
+	"SeriesGroup",
+	"Number",
+	"Volume",
+	"StoryArc",
+	"StoryArcNumber",
+	"Summary",
+	"Notes",
+	"Year",
+	"Month",
+	"Day",
+	"Writer",
+	"Penciller",
+	"Inker",
+	"Colorist",
+	"Letterer",
+	"CoverArtist",
+	"Editor",
+	"Publisher",
+	"Genre",
+	"Web",
+	"PageCount",
+	"Language",
+	"LanguageISO",
+	"Format",
+	"AgeRating",
+	"Characters",
+	"Teams",
+];
+
+const EMPTY_COMIC_INFO: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<ComicInfo xmlns:xsd="http://www.w3.org/2001/XMLSchema" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+</ComicInfo>
+"#;
+
+/// Convert a stored age into one of the ComicInfo `AgeRating` values, falling back to the
+/// `N and up` form Stump also understands when there isn't an exact match
+fn age_rating(age: i32) -> String {
+	match age {
+		age if age <= 0 => "Everyone".to_string(),
+		8 => "Early Childhood".to_string(),
+		10 => "Everyone 10+".to_string(),
+		13 => "Teen".to_string(),
+		15 => "MA15+".to_string(),
+		17 => "Mature 17+".to_string(),
+		age if age >= 18 => "Adults Only 18+".to_string(),
+		age => format!("{age} and up"),
+	}
+}
+
+fn element(name: &str, value: impl ToString) -> String {
+	format!("<{name}>{}</{name}>", escape(&value.to_string()))
+}
+
+/// Build the managed ComicInfo elements for the given metadata. Missing values are omitted.
+fn comic_info_elements(metadata: &media_metadata::Model) -> Vec<String> {
+	let text = |name: &str, value: &Option<String>| {
+		value
+			.as_deref()
+			.map(str::trim)
+			.filter(|v| !v.is_empty())
+			.map(|v| element(name, v))
+	};
+	let number = |name: &str, value: Option<i32>| value.map(|v| element(name, v));
+	let decimal = |name: &str, value: Option<sea_orm::prelude::Decimal>| {
+		value.map(|v| element(name, v.normalize()))
+	};
+
+	[
+		text("Title", &metadata.title),
+		text("TitleSort", &metadata.title_sort),
+		text("Series", &metadata.series),
+		decimal("Number", metadata.number),
+		number("Volume", metadata.volume),
+		text("Summary", &metadata.summary),
+		text("Notes", &metadata.notes),
+		number("Year", metadata.year),
+		number("Month", metadata.month),
+		number("Day", metadata.day),
+		text("Writer", &metadata.writers),
+		text("Penciller", &metadata.pencillers),
+		text("Inker", &metadata.inkers),
+		text("Colorist", &metadata.colorists),
+		text("Letterer", &metadata.letterers),
+		text("CoverArtist", &metadata.cover_artists),
+		text("Editor", &metadata.editors),
+		text("Publisher", &metadata.publisher),
+		text("Genre", &metadata.genres),
+		text("Web", &metadata.links),
+		number("PageCount", metadata.page_count),
+		text("LanguageISO", &metadata.language),
+		text("Format", &metadata.format),
+		text("Characters", &metadata.characters),
+		text("Teams", &metadata.teams),
+		text("StoryArc", &metadata.story_arc),
+		decimal("StoryArcNumber", metadata.story_arc_number),
+		text("SeriesGroup", &metadata.series_group),
+		metadata
+			.age_rating
+			.map(|age| element("AgeRating", age_rating(age))),
+	]
+	.into_iter()
+	.flatten()
+	.collect()
+}
+
+/// Render a ComicInfo.xml for the given metadata. If an existing document is provided, every
+/// element Stump does not manage is preserved.
+pub(crate) fn render_comic_info(
+	metadata: &media_metadata::Model,
+	existing: Option<&str>,
+) -> Result<String, FileError> {
+	let existing = existing
+		.filter(|xml| !xml.trim().is_empty())
+		.unwrap_or(EMPTY_COMIC_INFO);
+
+	replace_managed_children(
+		existing,
+		b"ComicInfo",
+		|e| {
+			let name = e.local_name();
+			MANAGED_ELEMENTS
+				.iter()
+				.any(|managed| managed.as_bytes() == name.as_ref())
+		},
+		&comic_info_elements(metadata),
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::filesystem::media::utils::metadata_from_buf;
+
+	fn metadata() -> media_metadata::Model {
+		media_metadata::Model {
+			title: Some("Bits & Pieces".to_string()),
+			series: Some("Science Comics".to_string()),
+			number: Some("1.5".parse().unwrap()),
+			year: Some(2016),
+			writers: Some("Alice, Bob".to_string()),
+			genres: Some("Science, Education".to_string()),
+			age_rating: Some(13),
+			language: Some("en".to_string()),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn test_render_new_comic_info() {
+		let xml = render_comic_info(&metadata(), None).unwrap();
+
+		assert!(xml.contains("  <Title>Bits &amp; Pieces</Title>\n"));
+		assert!(xml.contains("<Number>1.5</Number>"));
+		assert!(xml.contains("<AgeRating>Teen</AgeRating>"));
+
+		let parsed = metadata_from_buf(&xml).expect("Failed to parse rendered XML");
+		assert_eq!(parsed.title.as_deref(), Some("Bits & Pieces"));
+		assert_eq!(parsed.series.as_deref(), Some("Science Comics"));
+		assert_eq!(parsed.number, Some(1.5));
+		assert_eq!(parsed.year, Some(2016));
+		assert_eq!(
+			parsed.writers,
+			Some(vec!["Alice".to_string(), "Bob".to_string()])
+		);
+		assert_eq!(parsed.age_rating, Some(13));
+		assert_eq!(parsed.language.as_deref(), Some("en"));
+	}
+
+	#[test]
+	fn test_render_preserves_unmanaged_elements() {
+		let existing = r#"<?xml version="1.0"?>
+<ComicInfo>
+  <Title>Old title</Title>
+  <Tags>keep-me</Tags>
+  <Pages>
+    <Page Image="0" Type="FrontCover" />
+  </Pages>
+</ComicInfo>"#;
+
+		let xml = render_comic_info(&metadata(), Some(existing)).unwrap();
+
+		assert!(!xml.contains("Old title"));
+		assert!(xml.contains("<Tags>keep-me</Tags>"));
+		assert!(xml.contains(r#"<Page Image="0" Type="FrontCover" />"#));
+		assert_eq!(xml.matches("<Title>").count(), 1);
+	}
+
+	#[test]
+	fn test_age_rating() {
+		assert_eq!(age_rating(0), "Everyone");
+		assert_eq!(age_rating(13), "Teen");
+		assert_eq!(age_rating(16), "16 and up");
+		assert_eq!(age_rating(21), "Adults Only 18+");
+	}
+}
@@ -0,0 +1,298 @@
+use std::path::{Path, PathBuf};
+
+use async_graphql::SimpleObject;
+use chrono::{DateTime, FixedOffset, Utc};
+use models::entity::{media, media_metadata, series};
+use sea_orm::{prelude::*, QuerySelect, Set};
+use serde::{Deserialize, Serialize};
+use tokio::task::spawn_blocking;
+
+use super::archive::{write_comic_info, write_opf};
+use crate::{
+	filesystem::{
+		error::FileError,
+		media::{generate_hashes_async, FileProcessorOptions, ProcessedFileHashes},
+	},
+	job::{
+		error::JobError, JobContext, JobExecuteLog, JobLifecycle, JobOutputExt,
+		JobProgress, JobTaskOutput, WorkingState,
+	},
+};
+
+type Id = String;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum MetadataWriteBackScope {
+	/// Write back the metadata of an individual media item, specified by ID
+	Book(Id),
+	/// Write back the metadata of a batch of media items, specified by a list of IDs
+	Books(Vec<Id>),
+	/// Write back the metadata of all media in a library, specified by library ID
+	Library(Id),
+	/// Write back the metadata of all media in a series, specified by series ID
+	Series(Id),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WriteBackMetadataJobConfig {
+	pub scope: MetadataWriteBackScope,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum WriteBackMetadataTask {
+	WriteBook(Id),
+}
+
+#[derive(Clone, Serialize, Deserialize, Default, Debug, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct WriteBackMetadataOutput {
+	/// The number of files which had their embedded metadata rewritten
+	pub files_written: u64,
+	/// The number of files which were skipped, either because they have no stored metadata
+	/// or because their format doesn't support writing metadata back
+	pub files_skipped: u64,
+}
+
+impl JobOutputExt for WriteBackMetadataOutput {
+	fn update(&mut self, updated: Self) {
+		self.files_written += updated.files_written;
+		self.files_skipped += updated.files_skipped;
+	}
+}
+
+/// The embedded metadata format of a file that Stump knows how to write
+enum WriteBackFormat {
+	ComicInfo,
+	Opf,
+}
+
+impl WriteBackFormat {
+	fn from_extension(extension: &str) -> Option<Self> {
+		match extension.to_lowercase().as_str() {
+			"cbz" | "zip" => Some(Self::ComicInfo),
+			"epub" => Some(Self::Opf),
+			_ => None,
+		}
+	}
+}
+
+/// A job that writes the metadata stored in the database back into the media files
+/// themselves: ComicInfo.xml for zip/cbz archives and the OPF package document for EPUBs.
+/// Page images and other entries are copied over untouched, and the stored file hashes are
+/// regenerated afterward.
+#[derive(Clone)]
+pub struct WriteBackMetadataJob {
+	pub config: WriteBackMetadataJobConfig,
+}
+
+impl WriteBackMetadataJob {
+	pub fn new(config: WriteBackMetadataJobConfig) -> Self {
+		Self { config }
+	}
+}
+
+async fn write_book(
+	book: &media::Model,
+	metadata: media_metadata::Model,
+	format: WriteBackFormat,
+) -> Result<(), FileError> {
+	let path = PathBuf::from(&book.path);
+	spawn_blocking(move || match format {
+		WriteBackFormat::ComicInfo => write_comic_info(&path, &metadata),
+		WriteBackFormat::Opf => write_opf(&path, &metadata),
+	})
+	.await
+	.map_err(|e| FileError::UnknownError(e.to_string()))?
+}
+
+/// Regenerate the hashes (only those the book already had) and file stats after the
+/// file was rewritten
+async fn refresh_file_state(
+	conn: &DatabaseConnection,
+	book: media::Model,
+) -> Result<(), JobError> {
+	let path = Path::new(&book.path);
+	let ProcessedFileHashes {
+		hash,
+		koreader_hash,
+	} = generate_hashes_async(
+		path,
+		FileProcessorOptions {
+			generate_file_hashes: book.hash.is_some(),
+			generate_koreader_hashes: book.koreader_hash.is_some(),
+			..Default::default()
+		},
+	)
+	.await?;
+
+	let file_metadata = path.metadata().map_err(FileError::from)?;
+	let modified_at: Option<DateTime<FixedOffset>> = file_metadata
+		.modified()
+		.ok()
+		.map(|t| DateTime::<Utc>::from(t).into());
+
+	let mut active_model: media::ActiveModel = book.into();
+	active_model.hash = Set(hash);
+	active_model.koreader_hash = Set(koreader_hash);
+	active_model.size = Set(file_metadata.len().try_into().unwrap_or_default());
+	active_model.modified_at = Set(modified_at);
+	active_model.update(conn).await?;
+
+	Ok(())
+}
+
+#[async_trait::async_trait]
+impl JobLifecycle for WriteBackMetadataJob {
+	const NAME: &'static str = "write_back_metadata";
+
+	type Output = WriteBackMetadataOutput;
+	type Task = WriteBackMetadataTask;
+
+	fn description(&self) -> Option<String> {
+		match &self.config.scope {
+			MetadataWriteBackScope::Book(id) => {
+				Some(format!("Write back metadata for media item with id: {id}"))
+			},
+			MetadataWriteBackScope::Library(id) => {
+				Some(format!("Write back metadata for library with id: {id}"))
+			},
+			MetadataWriteBackScope::Series(id) => {
+				Some(format!("Write back metadata for series with id: {id}"))
+			},
+			MetadataWriteBackScope::Books(ids) => Some(format!(
+				"Write back metadata for media group with ids: {ids:?}"
+			)),
+		}
+	}
+
+	async fn init(
+		&mut self,
+		ctx: &JobContext,
+	) -> Result<WorkingState<Self::Output, Self::Task>, JobError> {
+		let output = Self::Output::default();
+
+		let tasks = match &self.config.scope {
+			MetadataWriteBackScope::Book(id) => {
+				vec![WriteBackMetadataTask::WriteBook(id.clone())]
+			},
+			MetadataWriteBackScope::Library(id) => {
+				let books = media::Entity::find()
+					.select_only()
+					.columns(media::MediaIdentSelect::columns())
+					.inner_join(series::Entity)
+					.filter(series::Column::LibraryId.eq(id))
+					.into_model::<media::MediaIdentSelect>()
+					.all(ctx.conn())
+					.await
+					.map_err(|e| JobError::InitFailed(e.to_string()))?;
+
+				books
+					.into_iter()
+					.map(|media| WriteBackMetadataTask::WriteBook(media.id))
+					.collect()
+			},
+			MetadataWriteBackScope::Series(id) => {
+				let books = media::Entity::find()
+					.select_only()
+					.columns(media::MediaIdentSelect::columns())
+					.filter(media::Column::SeriesId.eq(id))
+					.into_model::<media::MediaIdentSelect>()
+					.all(ctx.conn())
+					.await?;
+
+				books
+					.into_iter()
+					.map(|media| WriteBackMetadataTask::WriteBook(media.id))
+					.collect()
+			},
+			MetadataWriteBackScope::Books(ids) => ids
+				.iter()
+				.map(|id| WriteBackMetadataTask::WriteBook(id.clone()))
+				.collect(),
+		};
+
+		Ok(WorkingState {
+			output: Some(output),
+			tasks: tasks.into(),
+			logs: vec![],
+		})
+	}
+
+	async fn execute_task(
+		&self,
+		ctx: &JobContext,
+		task: Self::Task,
+	) -> Result<JobTaskOutput<Self>, JobError> {
+		let mut output = Self::Output::default();
+		let mut logs = vec![];
+
+		match task {
+			WriteBackMetadataTask::WriteBook(id) => {
+				let (book, metadata) = media::Entity::find_by_id(id.clone())
+					.find_also_related(media_metadata::Entity)
+					.one(ctx.conn())
+					.await?
+					.ok_or_else(|| {
+						JobError::TaskFailed(format!(
+							"Unable to find media item with id: {id}"
+						))
+					})?;
+
+				let format = WriteBackFormat::from_extension(&book.extension);
+				let (Some(metadata), Some(format)) = (metadata, format) else {
+					tracing::debug!(
+						%id,
+						extension = %book.extension,
+						"Skipping metadata write back"
+					);
+					output.files_skipped += 1;
+					return Ok(JobTaskOutput {
+						output,
+						subtasks: vec![],
+						logs,
+					});
+				};
+
+				let filename = Path::new(&book.path)
+					.file_name()
+					.and_then(|n| n.to_str())
+					.unwrap_or(&book.path)
+					.to_string();
+				ctx.report_progress(JobProgress::msg(&format!(
+					"Writing metadata to {filename}"
+				)));
+
+				match write_book(&book, metadata, format).await {
+					Ok(()) => {
+						output.files_written += 1;
+						if let Err(error) = refresh_file_state(ctx.conn(), book).await {
+							tracing::error!(?error, %id, "Failed to refresh file state");
+							logs.push(
+								JobExecuteLog::error(format!(
+									"Failed to update hashes after writing metadata: {error}"
+								))
+								.with_ctx(id),
+							);
+						}
+					},
+					Err(error) => {
+						tracing::error!(?error, %id, "Failed to write metadata to file");
+						output.files_skipped += 1;
+						logs.push(
+							JobExecuteLog::error(format!(
+								"Failed to write metadata to {filename}: {error}"
+							))
+							.with_ctx(id),
+						);
+					},
+				}
+			},
+		}
+
+		Ok(JobTaskOutput {
+			output,
+			subtasks: vec![],
+			logs,
+		})
+	}
+}
@@ -0,0 +1,7 @@
+mod archive;
+mod comic_info;
+mod job;
+mod opf;
+mod xml;
+
+pub use job::*;
@@ -0,0 +1,283 @@
+use std::collections::{HashMap, HashSet};
+
+use models::entity::media_metadata;
+use quick_xml::{
+	events::{BytesStart, Event},
+	Reader,
+};
+
+use super::xml::{attribute, escape, replace_managed_children};
+use crate::filesystem::error::FileError;
+
+/// The roles Stump considers to be writers when replacing `dc:creator` elements. Creators
+/// with any other role (e.g. illustrators) are left alone.
+const WRITER_ROLES: [&str; 1] = ["aut"];
+
+/// Collect the `role` refinements (EPUB 3) in an OPF document, keyed by the id of the
+/// element they refine
+fn collect_refined_roles(opf: &str) -> Result<HashMap<String, String>, FileError> {
+	let mut reader = Reader::from_str(opf);
+	let mut roles = HashMap::new();
+	let mut current_target: Option<String> = None;
+
+	loop {
+		match reader.read_event() {
+			Ok(Event::Start(e)) if e.local_name().as_ref() == b"meta" => {
+				current_target = attribute(&e, b"property")
+					.filter(|property| property == "role")
+					.and(attribute(&e, b"refines"))
+					.map(|target| target.trim_start_matches('#').to_string());
+			},
+			Ok(Event::Text(text)) => {
+				if let Some(target) = current_target.take() {
+					let role = String::from_utf8_lossy(&text).trim().to_string();
+					roles.insert(target, role);
+				}
+			},
+			Ok(Event::End(_)) => current_target = None,
+			Ok(Event::Eof) => break,
+			Err(e) => {
+				return Err(FileError::UnknownError(format!(
+					"Failed to read OPF document: {e}"
+				)))
+			},
+			_ => {},
+		}
+	}
+
+	Ok(roles)
+}
+
+/// Decides which `<metadata>` children are replaced. Only elements for which Stump has a
+/// value are touched, so anything the database doesn't know about (or the required
+/// `dc:title`/`dc:language` when they aren't set) stays as it was.
+struct ManagedOpfElements<'a> {
+	metadata: &'a media_metadata::Model,
+	roles: HashMap<String, String>,
+	removed_ids: HashSet<String>,
+}
+
+impl<'a> ManagedOpfElements<'a> {
+	fn new(opf: &str, metadata: &'a media_metadata::Model) -> Result<Self, FileError> {
+		let mut managed = Self {
+			metadata,
+			roles: collect_refined_roles(opf)?,
+			removed_ids: HashSet::new(),
+		};
+
+		// Refinements (e.g. `file-as` or `role`) of removed elements must go with them
+		let mut reader = Reader::from_str(opf);
+		let mut removed_ids = HashSet::new();
+		loop {
+			match reader.read_event() {
+				Ok(Event::Start(e) | Event::Empty(e)) => {
+					if managed.is_managed_element(&e) {
+						removed_ids.extend(attribute(&e, b"id"));
+					}
+				},
+				Ok(Event::Eof) | Err(_) => break,
+				_ => {},
+			}
+		}
+		managed.removed_ids = removed_ids;
+
+		Ok(managed)
+	}
+
+	fn is_managed_element(&self, e: &BytesStart) -> bool {
+		let metadata = self.metadata;
+		match e.local_name().as_ref() {
+			b"title" => metadata.title.is_some(),
+			b"creator" => {
+				let role = attribute(e, b"opf:role").or_else(|| {
+					attribute(e, b"id").and_then(|id| self.roles.get(&id).cloned())
+				});
+				metadata.writers.is_some()
+					&& role.is_none_or(|role| WRITER_ROLES.contains(&role.as_str()))
+			},
+			b"description" => metadata.summary.is_some(),
+			b"publisher" => metadata.publisher.is_some(),
+			b"subject" => metadata.genres.is_some(),
+			b"date" => {
+				let event = attribute(e, b"opf:event");
+				metadata.year.is_some()
+					&& event.is_none_or(|event| event == "publication")
+			},
+			b"language" => metadata.language.is_some(),
+			b"meta" => match attribute(e, b"name").as_deref() {
+				Some("calibre:series") => metadata.series.is_some(),
+				Some("calibre:series_index") => metadata.number.is_some(),
+				Some("calibre:title_sort") => metadata.title_sort.is_some(),
+				_ => {
+					attribute(e, b"property").as_deref() == Some("belongs-to-collection")
+						&& metadata.series.is_some()
+				},
+			},
+			_ => false,
+		}
+	}
+
+	fn is_managed(&self, e: &BytesStart) -> bool {
+		self.is_managed_element(e)
+			|| attribute(e, b"refines").is_some_and(|target| {
+				self.removed_ids.contains(target.trim_start_matches('#'))
+			})
+	}
+}
+
+fn split_list(value: &str) -> impl Iterator<Item = &str> {
+	value.split(',').map(str::trim).filter(|v| !v.is_empty())
+}
+
+fn opf_elements(metadata: &media_metadata::Model) -> Vec<String> {
+	let dc =
+		|name: &str, value: &str| format!("<dc:{name}>{}</dc:{name}>", escape(value));
+	let meta = |name: &str, value: &str| {
+		format!(r#"<meta name="{name}" content="{}"/>"#, escape(value))
+	};
+
+	let mut elements = vec![];
+
+	if let Some(title) = &metadata.title {
+		elements.push(dc("title", title));
+	}
+	if let Some(writers) = &metadata.writers {
+		elements.extend(split_list(writers).map(|writer| dc("creator", writer)));
+	}
+	if let Some(summary) = &metadata.summary {
+		elements.push(dc("description", summary));
+	}
+	if let Some(publisher) = &metadata.publisher {
+		elements.push(dc("publisher", publisher));
+	}
+	if let Some(genres) = &metadata.genres {
+		elements.extend(split_list(genres).map(|genre| dc("subject", genre)));
+	}
+	if let Some(year) = metadata.year {
+		let date = match (metadata.month, metadata.day) {
+			(Some(month), Some(day)) => format!("{year:04}-{month:02}-{day:02}"),
+			(Some(month), None) => format!("{year:04}-{month:02}"),
+			_ => format!("{year:04}"),
+		};
+		elements.push(dc("date", &date));
+	}
+	if let Some(language) = &metadata.language {
+		elements.push(dc("language", language));
+	}
+	if let Some(series) = &metadata.series {
+		elements.push(meta("calibre:series", series));
+	}
+	if let Some(number) = metadata.number {
+		elements.push(meta(
+			"calibre:series_index",
+			&number.normalize().to_string(),
+		));
+	}
+	if let Some(title_sort) = &metadata.title_sort {
+		elements.push(meta("calibre:title_sort", title_sort));
+	}
+
+	elements
+}
+
+/// Rewrite the `<metadata>` section of an OPF package document from the stored metadata.
+/// The manifest, spine and any metadata Stump doesn't have a value for are preserved.
+pub(crate) fn render_opf(
+	metadata: &media_metadata::Model,
+	existing: &str,
+) -> Result<String, FileError> {
+	let managed = ManagedOpfElements::new(existing, metadata)?;
+
+	replace_managed_children(
+		existing,
+		b"metadata",
+		|e| managed.is_managed(e),
+		&opf_elements(metadata),
+	)
+}
+
+/// Find the path of the OPF package document from an EPUB's `META-INF/container.xml`
+pub(crate) fn find_rootfile_path(container_xml: &str) -> Option<String> {
+	let mut reader = Reader::from_str(container_xml);
+
+	loop {
+		match reader.read_event() {
+			Ok(Event::Start(e) | Event::Empty(e))
+				if e.local_name().as_ref() == b"rootfile" =>
+			{
+				return attribute(&e, b"full-path");
+			},
+			Ok(Event::Eof) | Err(_) => return None,
+			_ => {},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const OPF: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" xmlns:dc="http://purl.org/dc/elements/1.1/" version="3.0" unique-identifier="id">
+  <metadata>
+    <dc:identifier id="id">urn:uuid:1234</dc:identifier>
+    <dc:creator id="author_0">Lewis Carroll</dc:creator>
+    <meta property="file-as" refines="#author_0">Carroll, Lewis</meta>
+    <meta property="role" refines="#author_0" scheme="marc:relators">aut</meta>
+    <dc:creator id="ill_0">John Tenniel</dc:creator>
+    <meta property="role" refines="#ill_0" scheme="marc:relators">ill</meta>
+    <dc:title>Alice's Adventures in Wonderland</dc:title>
+    <dc:language>en</dc:language>
+    <dc:subject>Fantasy fiction</dc:subject>
+    <meta name="cover" content="cover-image"/>
+  </metadata>
+  <manifest>
+    <item href="cover.jpg" id="cover-image" media-type="image/jpeg"/>
+  </manifest>
+</package>"##;
+
+	#[test]
+	fn test_render_opf() {
+		let metadata = media_metadata::Model {
+			title: Some("Alice in Wonderland".to_string()),
+			writers: Some("Lewis Carroll, Charles Dodgson".to_string()),
+			series: Some("Alice".to_string()),
+			number: Some("1".parse().unwrap()),
+			year: Some(1865),
+			..Default::default()
+		};
+
+		let opf = render_opf(&metadata, OPF).unwrap();
+
+		// Replaced
+		assert!(!opf.contains("Alice's Adventures"));
+		assert!(opf.contains("    <dc:title>Alice in Wonderland</dc:title>\n"));
+		assert!(opf.contains("<dc:creator>Charles Dodgson</dc:creator>"));
+		assert!(!opf.contains(r##"refines="#author_0""##));
+		assert!(opf.contains(r#"<meta name="calibre:series" content="Alice"/>"#));
+		assert!(opf.contains(r#"<meta name="calibre:series_index" content="1"/>"#));
+		assert!(opf.contains("<dc:date>1865</dc:date>"));
+		// Preserved
+		assert!(opf.contains(r#"<dc:creator id="ill_0">John Tenniel</dc:creator>"#));
+		assert!(opf.contains(r##"refines="#ill_0""##));
+		assert!(opf.contains("<dc:language>en</dc:language>"));
+		assert!(opf.contains("<dc:subject>Fantasy fiction</dc:subject>"));
+		assert!(opf.contains(r#"<meta name="cover" content="cover-image"/>"#));
+		assert!(opf.contains(r#"<item href="cover.jpg""#));
+	}
+
+	#[test]
+	fn test_find_rootfile_path() {
+		let container = r#"<?xml version="1.0"?>
+<container xmlns="urn:oasis:names:tc:opendocument:xmlns:container" version="1.0">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>"#;
+
+		assert_eq!(
+			find_rootfile_path(container).as_deref(),
+			Some("OEBPS/content.opf")
+		);
+	}
+}
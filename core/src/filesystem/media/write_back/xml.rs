@@ -0,0 +1,251 @@
+use quick_xml::{
+	escape::unescape,
+	events::{BytesStart, BytesText, Event},
+	Reader, Writer,
+};
+
+use crate::filesystem::error::FileError;
+
+/// Escape a value so it can be safely embedded as XML text or an attribute value
+pub(crate) fn escape(value: &str) -> String {
+	quick_xml::escape::escape(value).to_string()
+}
+
+fn is_whitespace(text: &BytesText) -> bool {
+	text.iter().all(u8::is_ascii_whitespace)
+}
+
+fn xml_error(e: impl std::fmt::Display) -> FileError {
+	FileError::UnknownError(format!("Failed to rewrite XML: {e}"))
+}
+
+/// Rewrite the direct children of the first element named `container` (by local name),
+/// dropping every child for which `is_managed` returns true and appending `elements` (raw,
+/// already escaped XML) right before the container closes. Everything else in the document,
+/// including comments, unmanaged children and whitespace, is written back untouched.
+pub(crate) fn replace_managed_children<F>(
+	xml: &str,
+	container: &[u8],
+	is_managed: F,
+	elements: &[String],
+) -> Result<String, FileError>
+where
+	F: Fn(&BytesStart) -> bool,
+{
+	let mut reader = Reader::from_str(xml);
+	let mut writer = Writer::new(Vec::new());
+
+	let mut in_container = false;
+	let mut container_done = false;
+	// The depth relative to the container, where 0 means a direct child
+	let mut depth = 0usize;
+	// The depth of the managed element currently being skipped, if any
+	let mut skipping = 0usize;
+	// Whitespace between direct children is held back so the whitespace leading into a
+	// dropped element is dropped with it
+	let mut pending_whitespace: Option<String> = None;
+	let mut child_indent: Option<String> = None;
+
+	loop {
+		let event = reader.read_event().map_err(xml_error)?;
+
+		if skipping > 0 {
+			match event {
+				Event::Start(_) => skipping += 1,
+				Event::End(_) => skipping -= 1,
+				Event::Eof => break,
+				_ => {},
+			}
+			continue;
+		}
+
+		if !in_container || depth > 0 {
+			match &event {
+				Event::Start(e) if !in_container && !container_done => {
+					if e.local_name().as_ref() == container {
+						in_container = true;
+					}
+				},
+				Event::Start(_) => depth += 1,
+				Event::End(_) if in_container => depth -= 1,
+				Event::Empty(e) if !in_container && !container_done => {
+					// A self-closing container, e.g. <metadata/>, is expanded so there is
+					// somewhere to put the new children
+					if e.local_name().as_ref() == container {
+						writer
+							.write_event(Event::Start(e.to_owned()))
+							.map_err(xml_error)?;
+						write_elements(&mut writer, elements, "\n", None)?;
+						writer
+							.write_event(Event::End(e.to_end().into_owned()))
+							.map_err(xml_error)?;
+						container_done = true;
+						continue;
+					}
+				},
+				Event::Eof => break,
+				_ => {},
+			}
+			writer.write_event(event).map_err(xml_error)?;
+			continue;
+		}
+
+		match event {
+			Event::Text(ref text) if is_whitespace(text) => {
+				let text = String::from_utf8_lossy(text.as_ref()).to_string();
+				match pending_whitespace.as_mut() {
+					Some(pending) => pending.push_str(&text),
+					None => pending_whitespace = Some(text),
+				}
+			},
+			Event::Start(ref e) if is_managed(e) => {
+				pending_whitespace = None;
+				skipping = 1;
+			},
+			Event::Empty(ref e) if is_managed(e) => {
+				pending_whitespace = None;
+			},
+			Event::End(end) => {
+				let whitespace = pending_whitespace.take().unwrap_or_default();
+				write_elements(
+					&mut writer,
+					elements,
+					&whitespace,
+					child_indent.as_deref(),
+				)?;
+				writer.write_event(Event::End(end)).map_err(xml_error)?;
+				in_container = false;
+				container_done = true;
+			},
+			Event::Eof => break,
+			event => {
+				if let Some(whitespace) = pending_whitespace.take() {
+					if child_indent.is_none() && whitespace.contains('\n') {
+						child_indent = whitespace.rsplit('\n').next().map(String::from);
+					}
+					writer.get_mut().extend_from_slice(whitespace.as_bytes());
+				}
+				if matches!(event, Event::Start(_)) {
+					depth += 1;
+				}
+				writer.write_event(event).map_err(xml_error)?;
+			},
+		}
+	}
+
+	if !container_done {
+		return Err(FileError::UnknownError(format!(
+			"Failed to find <{}> element",
+			String::from_utf8_lossy(container)
+		)));
+	}
+
+	String::from_utf8(writer.into_inner()).map_err(xml_error)
+}
+
+/// Write the new child elements, one per line, followed by the indentation of the closing
+/// container tag
+fn write_elements(
+	writer: &mut Writer<Vec<u8>>,
+	elements: &[String],
+	trailing_whitespace: &str,
+	child_indent: Option<&str>,
+) -> Result<(), FileError> {
+	let closing_indent = trailing_whitespace.rsplit('\n').next().unwrap_or_default();
+	let child_indent = child_indent
+		.map(String::from)
+		.unwrap_or_else(|| format!("{closing_indent}  "));
+
+	let out = writer.get_mut();
+	if elements.is_empty() {
+		out.extend_from_slice(trailing_whitespace.as_bytes());
+		return Ok(());
+	}
+
+	out.push(b'\n');
+	for element in elements {
+		out.extend_from_slice(child_indent.as_bytes());
+		out.extend_from_slice(element.as_bytes());
+		out.push(b'\n');
+	}
+	out.extend_from_slice(closing_indent.as_bytes());
+
+	Ok(())
+}
+
+/// Read the value of an attribute by its full (prefixed) name
+pub(crate) fn attribute(element: &BytesStart, name: &[u8]) -> Option<String> {
+	element
+		.attributes()
+		.flatten()
+		.find(|attr| attr.key.as_ref() == name)
+		.map(|attr| {
+			let raw_value = String::from_utf8_lossy(&attr.value).to_string();
+			unescape(&raw_value)
+				.map(|v| v.into_owned())
+				.unwrap_or(raw_value)
+		})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_replace_managed_children() {
+		let xml =
+			"<root>\n  <keep>a</keep>\n  <drop>b<inner/></drop>\n  <drop/>\n</root>\n";
+		let result = replace_managed_children(
+			xml,
+			b"root",
+			|e| e.local_name().as_ref() == b"drop",
+			&["<new>c</new>".to_string()],
+		)
+		.unwrap();
+
+		assert_eq!(
+			result,
+			"<root>\n  <keep>a</keep>\n  <new>c</new>\n</root>\n"
+		);
+	}
+
+	#[test]
+	fn test_replace_managed_children_is_stable() {
+		let xml = "<root>\n    <keep>a</keep>\n</root>";
+		let elements = vec!["<new>c</new>".to_string()];
+		let is_managed = |e: &BytesStart| e.local_name().as_ref() == b"new";
+
+		let once = replace_managed_children(xml, b"root", is_managed, &elements).unwrap();
+		let twice =
+			replace_managed_children(&once, b"root", is_managed, &elements).unwrap();
+
+		assert_eq!(
+			once,
+			"<root>\n    <keep>a</keep>\n    <new>c</new>\n</root>"
+		);
+		assert_eq!(once, twice);
+	}
+
+	#[test]
+	fn test_replace_managed_children_self_closing_container() {
+		let result = replace_managed_children(
+			"<package><metadata/></package>",
+			b"metadata",
+			|_| false,
+			&["<new/>".to_string()],
+		)
+		.unwrap();
+
+		assert_eq!(
+			result,
+			"<package><metadata>\n  <new/>\n</metadata></package>"
+		);
+	}
+
+	#[test]
+	fn test_replace_managed_children_missing_container() {
+		assert!(
+			replace_managed_children("<root/>", b"metadata", |_| false, &[]).is_err()
+		);
+	}
+}
@@ -144,10 +144,11 @@ pub fn find_auto_apply_candidate(
 	provider_configs: &[models::entity::metadata_provider_config::Model],
 ) -> Option<(MatchCandidate, AutoApplyConfig)> {
 	for candidate in candidates {
-		if let Some(config) = provider_configs
-			.iter()
-			.find(|c| c.provider_type.to_string() == candidate.provider)
-		{
+		if let Some(config) = provider_configs.iter().find(|c| {
+			c.provider_type
+				.to_string()
+				.eq_ignore_ascii_case(&candidate.provider)
+		}) {
 			if let Some(auto_config) = config
 				.auto_apply_config
 				.as_ref()
@@ -382,12 +383,67 @@ fn apply_media_fields(
 		active.cover_artists = Set(v);
 	}
 
+	// Artist and penciller credits are both stored as pencillers, so they are merged once.
+	// A locked or excluded field doesn't contribute its credits
+	let pencil_field = if merger.can_write(MetadataField::Pencillers) {
+		MetadataField::Pencillers
+	} else {
+		MetadataField::Artists
+	};
+	let pencil_credits = combine_pencil_credits(
+		ext.pencillers
+			.as_ref()
+			.filter(|_| merger.can_write(MetadataField::Pencillers)),
+		ext.artists
+			.as_ref()
+			.filter(|_| merger.can_write(MetadataField::Artists)),
+	);
 	if let Some(v) =
-		merger.merge_comma_list(MetadataField::Artists, &model.pencillers, &ext.artists)
+		merger.merge_comma_list(pencil_field, &model.pencillers, &pencil_credits)
 	{
 		active.pencillers = Set(v);
 	}
 
+	if let Some(v) =
+		merger.merge_comma_list(MetadataField::Inkers, &model.inkers, &ext.inkers)
+	{
+		active.inkers = Set(v);
+	}
+
+	if let Some(v) =
+		merger.merge_comma_list(MetadataField::Editors, &model.editors, &ext.editors)
+	{
+		active.editors = Set(v);
+	}
+
+	if let Some(v) = merger.merge_comma_list(
+		MetadataField::Characters,
+		&model.characters,
+		&ext.characters,
+	) {
+		active.characters = Set(v);
+	}
+
+	if let Some(v) =
+		merger.merge_comma_list(MetadataField::Teams, &model.teams, &ext.teams)
+	{
+		active.teams = Set(v);
+	}
+
+	if let Some(v) = merger.merge_comma_list(
+		MetadataField::StoryArc,
+		&model.story_arc,
+		&ext.story_arcs,
+	) {
+		active.story_arc = Set(v);
+	}
+
+	if let Some(v) =
+		merger.merge_scalar(MetadataField::Publisher, &model.publisher, &ext.publisher)
+	{
+		active.publisher = Set(v);
+	}
+
 	if let Some(v) = merger.apply_scalar_override::<String>(MetadataField::Title) {
 		active.title = Set(v);
 	}
@@ -422,9 +478,46 @@ fn apply_media_fields(
 	if let Some(v) = merger.apply_comma_list_override(MetadataField::CoverArtists) {
 		active.cover_artists = Set(v);
 	}
-	if let Some(v) = merger.apply_comma_list_override(MetadataField::Artists) {
+	// An override of the pencillers takes precedence over one of the artists
+	if let Some(v) = merger
+		.apply_comma_list_override(MetadataField::Pencillers)
+		.or_else(|| merger.apply_comma_list_override(MetadataField::Artists))
+	{
 		active.pencillers = Set(v);
 	}
+	if let Some(v) = merger.apply_comma_list_override(MetadataField::Inkers) {
+		active.inkers = Set(v);
+	}
+	if let Some(v) = merger.apply_comma_list_override(MetadataField::Editors) {
+		active.editors = Set(v);
+	}
+	if let Some(v) = merger.apply_comma_list_override(MetadataField::Characters) {
+		active.characters = Set(v);
+	}
+	if let Some(v) = merger.apply_comma_list_override(MetadataField::Teams) {
+		active.teams = Set(v);
+	}
+	if let Some(v) = merger.apply_comma_list_override(MetadataField::StoryArc) {
+		active.story_arc = Set(v);
+	}
+	if let Some(v) = merger.apply_scalar_override::<String>(MetadataField::Publisher) {
+		active.publisher = Set(v);
+	}
+}
+
+/// Combine the penciller and artist credits of a book, which are both stored as pencillers.
+/// Penciller credits come first, followed by the artist credits which aren't among them
+fn combine_pencil_credits(
+	pencillers: Option<&Vec<String>>,
+	artists: Option<&Vec<String>>,
+) -> Option<Vec<String>> {
+	let mut credits = Vec::new();
+	for credit in pencillers.into_iter().chain(artists).flatten() {
+		if !credits.contains(credit) {
+			credits.push(credit.clone());
+		}
+	}
+	(!credits.is_empty()).then_some(credits)
 }
 
 fn build_series_metadata_insert(
@@ -475,9 +568,40 @@ fn build_media_metadata_insert(
 		colorists: Set(ext.colorists.as_ref().map(|c| c.join(", "))),
 		letterers: Set(ext.letterers.as_ref().map(|l| l.join(", "))),
 		cover_artists: Set(ext.cover_artists.as_ref().map(|c| c.join(", "))),
-		pencillers: Set(ext.artists.as_ref().map(|a| a.join(", "))),
+		pencillers: Set(combine_pencil_credits(
+			ext.pencillers.as_ref(),
+			ext.artists.as_ref(),
+		)
+		.map(|p| p.join(", "))),
+		inkers: Set(ext.inkers.as_ref().map(|i| i.join(", "))),
+		editors: Set(ext.editors.as_ref().map(|e| e.join(", "))),
+		characters: Set(ext.characters.as_ref().map(|c| c.join(", "))),
+		teams: Set(ext.teams.as_ref().map(|t| t.join(", "))),
+		story_arc: Set(ext.story_arcs.as_ref().map(|a| a.join(", "))),
+		publisher: Set(ext.publisher.clone()),
 		metadata_source: Set(Some(provider.to_string())),
 		metadata_external_id: Set(Some(external_id.to_string())),
 		..Default::default()
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_combine_pencil_credits() {
+		let pencillers = vec!["Joe Shuster".to_string()];
+		let artists = vec!["Jack Kirby".to_string(), "Joe Shuster".to_string()];
+
+		assert_eq!(
+			combine_pencil_credits(Some(&pencillers), Some(&artists)),
+			Some(vec!["Joe Shuster".to_string(), "Jack Kirby".to_string()])
+		);
+		assert_eq!(
+			combine_pencil_credits(None, Some(&artists)),
+			Some(artists.clone())
+		);
+		assert_eq!(combine_pencil_credits(None, Some(&vec![])), None);
+	}
+}
@@ -66,6 +66,7 @@ pub async fn fetch_series_metadata(
 	conn: &DatabaseConnection,
 	series_id: &str,
 	series_name: &str,
+	series_path: &str,
 	provider_cache: &ProviderClientCache,
 ) -> Result<Vec<MatchCandidate>, CoreError> {
 	let library_type = library_type_for_series(conn, series_id).await?;
@@ -93,6 +94,7 @@ pub async fn fetch_series_metadata(
 				let query = SearchQuery {
 					title: series_name.to_string(),
 					limit: Some(10),
+					path: Some(series_path.to_string()),
 					..Default::default()
 				};
 
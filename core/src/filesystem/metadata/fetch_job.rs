@@ -2,6 +2,7 @@ use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 
 use async_graphql::SimpleObject;
+use integrations::NotificationEvent;
 use metadata_integrations::{MatchCandidate, SearchQuery};
 use models::{
 	entity::{
@@ -17,9 +18,10 @@ use sea_orm::{
 };
 use serde::{Deserialize, Serialize};
 
+use crate::config::StumpConfig;
 use crate::job::{
 	error::JobError, JobContext, JobExecuteLog, JobLifecycle, JobOutputExt, JobProgress,
-	JobTaskOutput, WorkingState,
+	JobRetryPolicy, JobTaskOutput, WorkingState,
 };
 
 use super::{apply, ProviderClientCache};
@@ -85,12 +87,14 @@ pub enum MetadataFetchTask {
 	FetchSeries {
 		series_id: String,
 		series_name: String,
+		series_path: String,
 		library_type: LibraryType,
 	},
 	/// Fetch metadata for a media item
 	FetchMedia {
 		media_id: String,
 		media_name: String,
+		media_path: String,
 		series_name: Option<String>,
 		library_type: LibraryType,
 	},
@@ -208,6 +212,12 @@ impl JobLifecycle for MetadataFetchJob {
 		}
 	}
 
+	fn retry_policy(&self, config: &StumpConfig) -> JobRetryPolicy {
+		// Provider requests which were rate limited are already retried, and retrying the
+		// whole task would only send more requests to the providers
+		JobRetryPolicy::from_config(config).with_max_attempts(1)
+	}
+
 	async fn init(
 		&mut self,
 		ctx: &JobContext,
@@ -245,6 +255,7 @@ impl JobLifecycle for MetadataFetchJob {
 						Some(MetadataFetchTask::FetchSeries {
 							series_id: s.id,
 							series_name: s.name,
+							series_path: s.path,
 							library_type: s
 								.library_id
 								.as_ref()
@@ -267,6 +278,7 @@ impl JobLifecycle for MetadataFetchJob {
 					.map(|s| MetadataFetchTask::FetchSeries {
 						series_id: s.id,
 						series_name: s.name,
+						series_path: s.path,
 						library_type,
 					})
 					.collect()
@@ -298,6 +310,7 @@ impl JobLifecycle for MetadataFetchJob {
 						Some(MetadataFetchTask::FetchMedia {
 							media_id: m.id,
 							media_name: m.name,
+							media_path: m.path,
 							series_name: s.as_ref().map(|s| s.name.clone()),
 							library_type: s
 								.as_ref()
@@ -331,6 +344,7 @@ impl JobLifecycle for MetadataFetchJob {
 					.map(|(m, s)| MetadataFetchTask::FetchMedia {
 						media_id: m.id,
 						media_name: m.name,
+						media_path: m.path,
 						series_name: s.map(|s| s.name),
 						library_type,
 					})
@@ -360,6 +374,7 @@ impl JobLifecycle for MetadataFetchJob {
 					.map(|(m, s)| MetadataFetchTask::FetchMedia {
 						media_id: m.id,
 						media_name: m.name,
+						media_path: m.path,
 						series_name: s.map(|s| s.name),
 						library_type,
 					})
@@ -379,6 +394,11 @@ impl JobLifecycle for MetadataFetchJob {
 		})
 	}
 
+	async fn resume(&mut self, ctx: &JobContext) -> Result<(), JobError> {
+		self.get_or_init_cache(ctx).await?;
+		Ok(())
+	}
+
 	async fn execute_task(
 		&self,
 		ctx: &JobContext,
@@ -411,6 +431,7 @@ impl JobLifecycle for MetadataFetchJob {
 			MetadataFetchTask::FetchSeries {
 				series_id,
 				series_name,
+				series_path,
 				library_type,
 			} => {
 				let provider_configs: Vec<_> = all_provider_configs
@@ -467,6 +488,7 @@ impl JobLifecycle for MetadataFetchJob {
 							let query = SearchQuery {
 								title: series_name.clone(),
 								limit: Some(10),
+								path: Some(series_path.clone()),
 								..Default::default()
 							};
 
@@ -588,6 +610,7 @@ impl JobLifecycle for MetadataFetchJob {
 			MetadataFetchTask::FetchMedia {
 				media_id,
 				media_name,
+				media_path,
 				library_type,
 				..
 			} => {
@@ -645,6 +668,7 @@ impl JobLifecycle for MetadataFetchJob {
 							let query = SearchQuery {
 								title: media_name.clone(),
 								limit: Some(10),
+								path: Some(media_path.clone()),
 								..Default::default()
 							};
 
@@ -762,6 +786,18 @@ impl JobLifecycle for MetadataFetchJob {
 			subtasks: vec![],
 		})
 	}
+
+	async fn finalize(
+		&self,
+		ctx: &JobContext,
+		output: &Self::Output,
+	) -> Result<(), JobError> {
+		let count = output.matches_found.saturating_sub(output.auto_applied);
+		if count > 0 {
+			ctx.notify(NotificationEvent::MetadataAwaitingReview { count });
+		}
+		Ok(())
+	}
 }
 
 async fn resolve_library_type(
@@ -0,0 +1,101 @@
+use std::path::Path;
+
+use models::{
+	entity::{media, series},
+	shared::enums::FileStatus,
+};
+use sea_orm::{prelude::*, sea_query::Query, Condition, TransactionTrait};
+
+use crate::{filesystem::image::remove_thumbnails, CoreResult};
+
+/// The result of [`clean_library`]
+#[derive(Debug, Default)]
+pub struct CleanLibraryOutput {
+	pub deleted_media_count: usize,
+	pub deleted_series_count: usize,
+	/// Whether the library has no series left after cleaning
+	pub is_empty: bool,
+}
+
+/// Delete media and series from a library that match one of the following conditions:
+///
+/// - A series that is missing from disk (status is not `Ready`)
+/// - A media that is missing from disk (status is not `Ready`)
+/// - A series that is not associated with any media (i.e., no media in the series)
+///
+/// Any thumbnails of the deleted media and series are removed as well.
+pub async fn clean_library(
+	conn: &DatabaseConnection,
+	library_id: &str,
+	thumbnails_dir: &Path,
+) -> CoreResult<CleanLibraryOutput> {
+	let txn = conn.begin().await?;
+
+	let deleted_media_ids = media::Entity::delete_many()
+		.filter(
+			media::Column::Status.ne(FileStatus::Ready.to_string()).and(
+				media::Column::SeriesId.in_subquery(
+					Query::select()
+						.column(series::Column::Id)
+						.from(series::Entity)
+						.and_where(series::Column::LibraryId.eq(library_id))
+						.to_owned(),
+				),
+			),
+		)
+		.exec_with_returning(&txn)
+		.await?
+		.into_iter()
+		.map(|m| m.id)
+		.collect::<Vec<_>>();
+	tracing::trace!(?deleted_media_ids, "Deleted media ids");
+
+	let deleted_series_ids = series::Entity::delete_many()
+		.filter(series::Column::LibraryId.eq(library_id))
+		.filter(
+			Condition::any()
+				.add(series::Column::Status.ne(FileStatus::Ready.to_string()))
+				// TODO: Double check that this query is correct
+				.add(
+					series::Column::Id.not_in_subquery(
+						Query::select()
+							.column(media::Column::SeriesId)
+							.distinct()
+							.from(media::Entity)
+							.to_owned(),
+					),
+				),
+		)
+		.exec_with_returning(&txn)
+		.await?
+		.into_iter()
+		.map(|s| s.id)
+		.collect::<Vec<_>>();
+	tracing::trace!(?deleted_series_ids, "Deleted series ids");
+
+	let is_empty = series::Entity::find()
+		.filter(series::Column::LibraryId.eq(library_id))
+		.count(&txn)
+		.await?
+		== 0;
+
+	txn.commit().await?;
+
+	if !deleted_media_ids.is_empty() {
+		if let Err(error) = remove_thumbnails(&deleted_media_ids, thumbnails_dir).await {
+			tracing::error!(?error, "Failed to remove thumbnails for library media");
+		}
+	}
+
+	if !deleted_series_ids.is_empty() {
+		if let Err(error) = remove_thumbnails(&deleted_series_ids, thumbnails_dir).await {
+			tracing::error!(?error, "Failed to remove thumbnails for library series");
+		}
+	}
+
+	Ok(CleanLibraryOutput {
+		deleted_media_count: deleted_media_ids.len(),
+		deleted_series_count: deleted_series_ids.len(),
+		is_empty,
+	})
+}
@@ -0,0 +1,130 @@
+use async_graphql::SimpleObject;
+use models::entity::{library, scheduled_job::LibraryCleanupConfig};
+use sea_orm::{prelude::*, QuerySelect};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	filesystem::scanner::clean_library,
+	job::{
+		error::JobError, stump_job::StumpJob, JobContext, JobExecuteLog, JobLifecycle,
+		JobOutputExt, JobProgress, JobTaskOutput, WorkingState,
+	},
+};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum LibraryCleanupTask {
+	Clean(String),
+}
+
+#[derive(Clone, Serialize, Deserialize, Default, Debug, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryCleanupOutput {
+	/// The number of libraries which were cleaned
+	pub libraries_cleaned: u64,
+	/// The number of media which were deleted
+	pub deleted_media_count: u64,
+	/// The number of series which were deleted
+	pub deleted_series_count: u64,
+}
+
+impl JobOutputExt for LibraryCleanupOutput {
+	fn update(&mut self, updated: Self) {
+		self.libraries_cleaned += updated.libraries_cleaned;
+		self.deleted_media_count += updated.deleted_media_count;
+		self.deleted_series_count += updated.deleted_series_count;
+	}
+}
+
+/// A job that deletes the books and series of libraries which are missing from disk, one
+/// library per task. Duplicate detection is queued once every library has been cleaned,
+/// so that it doesn't flag books which were just removed
+#[derive(Clone)]
+pub struct LibraryCleanupJob {
+	pub config: LibraryCleanupConfig,
+}
+
+#[async_trait::async_trait]
+impl JobLifecycle for LibraryCleanupJob {
+	const NAME: &'static str = "library_cleanup";
+
+	type Output = LibraryCleanupOutput;
+	type Task = LibraryCleanupTask;
+
+	fn description(&self) -> Option<String> {
+		Some("Clean up libraries".to_string())
+	}
+
+	async fn init(
+		&mut self,
+		ctx: &JobContext,
+	) -> Result<WorkingState<Self::Output, Self::Task>, JobError> {
+		let tasks = if self.config.remove_missing {
+			let query = library::Entity::find()
+				.select_only()
+				.column(library::Column::Id);
+			let query = if self.config.library_ids.is_empty() {
+				query
+			} else {
+				query.filter(library::Column::Id.is_in(self.config.library_ids.clone()))
+			};
+			query
+				.into_tuple::<String>()
+				.all(ctx.conn())
+				.await?
+				.into_iter()
+				.map(LibraryCleanupTask::Clean)
+				.collect()
+		} else {
+			vec![]
+		};
+
+		Ok(WorkingState {
+			output: Some(Self::Output::default()),
+			tasks: tasks.into(),
+			logs: vec![],
+		})
+	}
+
+	async fn execute_task(
+		&self,
+		ctx: &JobContext,
+		task: Self::Task,
+	) -> Result<JobTaskOutput<Self>, JobError> {
+		let LibraryCleanupTask::Clean(library_id) = task;
+		ctx.report_progress(JobProgress::msg("Removing missing books and series"));
+
+		let cleaned =
+			clean_library(ctx.conn(), &library_id, &ctx.config().get_thumbnails_dir())
+				.await?;
+
+		let logs = if cleaned.is_empty {
+			vec![
+				JobExecuteLog::warn("The library has no series left after cleaning")
+					.with_ctx(library_id),
+			]
+		} else {
+			vec![]
+		};
+
+		Ok(JobTaskOutput {
+			output: LibraryCleanupOutput {
+				libraries_cleaned: 1,
+				deleted_media_count: cleaned.deleted_media_count as u64,
+				deleted_series_count: cleaned.deleted_series_count as u64,
+			},
+			subtasks: vec![],
+			logs,
+		})
+	}
+
+	async fn finalize(
+		&self,
+		ctx: &JobContext,
+		_output: &Self::Output,
+	) -> Result<(), JobError> {
+		if self.config.detect_duplicates {
+			ctx.enqueue(StumpJob::detect_duplicates()).await?;
+		}
+		Ok(())
+	}
+}
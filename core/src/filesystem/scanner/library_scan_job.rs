@@ -4,6 +4,7 @@ use std::{
 };
 
 use async_graphql::SimpleObject;
+use integrations::NotificationEvent;
 use models::{
 	entity::{
 		library, library_config, library_scan_record, media, metadata_provider_config,
@@ -20,6 +21,7 @@ use serde::{Deserialize, Serialize};
 // Also perhaps experiment with https://docs.rs/tokio-uring/latest/tokio_uring/index.html
 
 use crate::{
+	config::StumpConfig,
 	database::SQLITE_BIND_LIMIT,
 	event::{self, CreatedOrUpdatedManyMedia},
 	filesystem::{
@@ -32,7 +34,8 @@ use crate::{
 	},
 	job::{
 		error::JobError, stump_job::StumpJob, CoreJobOutput, JobContext, JobExecuteLog,
-		JobLifecycle, JobOutputExt, JobProgress, JobTaskOutput, WorkingState,
+		JobLifecycle, JobOutputExt, JobProgress, JobRetryPolicy, JobTaskOutput,
+		WorkingState,
 	},
 	utils::chain_optional_iter,
 	CoreEvent,
@@ -79,6 +82,9 @@ pub struct LibraryScanJob {
 	pub config: Option<library_config::Model>,
 	/// The scan options to use, if any
 	pub options: ScanOptions,
+	/// Whether to notify subscribers once the scan finishes. Scans started by the library
+	/// watcher don't
+	pub notify: bool,
 }
 
 impl LibraryScanJob {
@@ -88,8 +94,34 @@ impl LibraryScanJob {
 			path,
 			config: None,
 			options: options.unwrap_or_default(),
+			notify: true,
 		}
 	}
+
+	async fn find_config(
+		&self,
+		conn: &DatabaseConnection,
+	) -> Result<library_config::Model, JobError> {
+		library_config::Entity::find()
+			.filter(library_config::Column::LibraryId.eq(self.id.clone()))
+			.one(conn)
+			.await?
+			.ok_or(JobError::InitFailed(
+				"Library is missing configuration".to_string(),
+			))
+	}
+
+	/// Get the name of the library for notifications, falling back to its path if the
+	/// library can't be found
+	async fn library_name(&self, conn: &DatabaseConnection) -> String {
+		library::Entity::find_by_id(self.id.clone())
+			.one(conn)
+			.await
+			.ok()
+			.flatten()
+			.map(|library| library.name)
+			.unwrap_or_else(|| self.path.clone())
+	}
 }
 
 /// The data that is collected and updated during the execution of a library scan job
@@ -142,6 +174,12 @@ impl JobLifecycle for LibraryScanJob {
 		Some(self.path.clone())
 	}
 
+	fn retry_policy(&self, config: &StumpConfig) -> JobRetryPolicy {
+		// Scan tasks aren't idempotent, e.g. a retry may create media which the failed
+		// attempt already created. The next scan picks up whatever a failed task missed
+		JobRetryPolicy::from_config(config).with_max_attempts(1)
+	}
+
 	async fn init(
 		&mut self,
 		ctx: &JobContext,
@@ -150,13 +188,7 @@ impl JobLifecycle for LibraryScanJob {
 		// Note: We ignore the potential self.config here in the event that it was
 		// updated since being queued. This is perhaps a bit overly cautious, but it's
 		// just one additional query.
-		let config = library_config::Entity::find()
-			.filter(library_config::Column::LibraryId.eq(self.id.clone()))
-			.one(ctx.conn())
-			.await?
-			.ok_or(JobError::InitFailed(
-				"Library is missing configuration".to_string(),
-			))?;
+		let config = self.find_config(ctx.conn()).await?;
 		let is_collection_based = config.is_collection_based();
 		let ignore_rules = config.ignore_rules().build()?;
 
@@ -200,6 +232,10 @@ impl JobLifecycle for LibraryScanJob {
 					id: self.id.clone(),
 				},
 			));
+			ctx.notify(NotificationEvent::LibraryMissing {
+				library_name: self.library_name(ctx.conn()).await,
+				path: self.path.clone(),
+			});
 			return Err(JobError::InitFailed(
 				"Library could not be found on disk".to_string(),
 			));
@@ -239,6 +275,12 @@ impl JobLifecycle for LibraryScanJob {
 		})
 	}
 
+	async fn resume(&mut self, ctx: &JobContext) -> Result<(), JobError> {
+		let config = self.find_config(ctx.conn()).await?;
+		self.config = Some(config);
+		Ok(())
+	}
+
 	async fn finalize(
 		&self,
 		ctx: &JobContext,
@@ -261,6 +303,13 @@ impl JobLifecycle for LibraryScanJob {
 			tracing::error!(error = ?error, "Failed to handle scan completion");
 		}
 
+		if self.notify {
+			ctx.notify(NotificationEvent::ScanCompleted {
+				books_added: output.created_media,
+				library_name: self.library_name(ctx.conn()).await,
+			});
+		}
+
 		match image_options {
 			Some(options) if did_create || did_update => {
 				tracing::trace!("Thumbnail generation job should be enqueued");
@@ -482,6 +531,8 @@ impl JobLifecycle for LibraryScanJob {
 					let chunk_count = chunks.len();
 					tracing::trace!(chunk_count, "Batch inserting new series");
 
+					let mut discovered_series_names = Vec::new();
+
 					for (idx, chunk) in chunks.enumerate() {
 						ctx.report_progress(JobProgress::subtask_position_msg(
 							"Inserting built series in batches",
@@ -491,6 +542,9 @@ impl JobLifecycle for LibraryScanJob {
 						match safely_insert_series(chunk.to_vec(), ctx.conn()).await {
 							Ok(created_series) => {
 								output.created_series += created_series.len() as u64;
+								discovered_series_names.extend(
+									created_series.iter().map(|s| s.name.clone()),
+								);
 								ctx.emit_event(CoreEvent::CreatedManySeries(
 									event::CreatedManySeries {
 										count: created_series.len() as u64,
@@ -508,6 +562,13 @@ impl JobLifecycle for LibraryScanJob {
 						}
 					}
 
+					if !discovered_series_names.is_empty() {
+						ctx.notify(NotificationEvent::SeriesDiscovered {
+							library_name: self.library_name(ctx.conn()).await,
+							series_names: discovered_series_names,
+						});
+					}
+
 					current_subtask_index += 1;
 					ctx.report_progress(JobProgress::subtask_position_msg(
 						"Processed all chunks",
@@ -709,8 +770,9 @@ impl JobLifecycle for LibraryScanJob {
 					));
 					let MediaOperationOutput {
 						created_media,
+						updated_media,
 						logs: new_logs,
-						..
+						adopted_by_series_id,
 					} = safely_build_and_insert_media(
 						MediaBuildOperation {
 							series_id: series_id.clone(),
@@ -720,6 +782,7 @@ impl JobLifecycle for LibraryScanJob {
 								),
 							)?,
 							max_concurrency,
+							adopt_moved_series: true,
 						},
 						ctx,
 						paths,
@@ -729,12 +792,15 @@ impl JobLifecycle for LibraryScanJob {
 					ctx.report_progress(JobProgress::msg("Created new media"));
 					ctx.emit_event(CoreEvent::CreatedOrUpdatedManyMedia(
 						CreatedOrUpdatedManyMedia {
-							count: created_media,
-							series_id,
+							count: created_media + updated_media,
+							// the series may have been handed over to the one its books
+							// were moved from, in which case it no longer exists
+							series_id: adopted_by_series_id.unwrap_or(series_id),
 							library_id: self.id.clone(),
 						},
 					));
 					output.created_media += created_media;
+					output.updated_media += updated_media;
 					logs.extend(new_logs);
 				},
 				SeriesScanTask::VisitMedia(params) => {
@@ -755,6 +821,7 @@ impl JobLifecycle for LibraryScanJob {
 								),
 							)?,
 							max_concurrency,
+							adopt_moved_series: false,
 						},
 						ctx,
 						params,
@@ -1,37 +1,123 @@
-use crate::{job::stump_job::StumpJob, CoreError, CoreResult};
-use apalis::prelude::MemoryStorage;
+use super::polling_watcher::PollingWatcher;
+use crate::{
+	job::{stump_job::StumpJob, JobStorage},
+	CoreError, CoreResult,
+};
 use async_trait::async_trait;
-use models::entity::{library, library_config};
-use notify::{Event, RecommendedWatcher, Watcher};
-use sea_orm::prelude::*;
+use models::{
+	entity::{library, library_config, series},
+	shared::enums::LibraryWatchMode,
+};
+use notify::{
+	event::{ModifyKind, RenameMode},
+	Event, EventKind, RecommendedWatcher, Watcher,
+};
+use sea_orm::{prelude::*, FromQueryResult, QuerySelect};
 use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::mpsc::error::SendError;
 use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
 use tokio::sync::Mutex;
 
+/// The shortest interval a library can be polled at, to avoid constantly walking it
+const MIN_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How the watcher detects changes in a library's directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WatcherBackend {
+	/// Listen for the filesystem events of the host OS
+	Native,
+	/// Walk the directory on an interval and diff the files against the previous walk
+	Poll { interval: Duration },
+}
+
+impl WatcherBackend {
+	pub fn new(mode: LibraryWatchMode, poll_interval_seconds: i32) -> WatcherBackend {
+		match mode {
+			LibraryWatchMode::Native => WatcherBackend::Native,
+			LibraryWatchMode::Poll => WatcherBackend::Poll {
+				interval: Duration::from_secs(poll_interval_seconds.max(0) as u64)
+					.max(MIN_POLL_INTERVAL),
+			},
+		}
+	}
+}
+
+impl From<&library_config::Model> for WatcherBackend {
+	fn from(config: &library_config::Model) -> Self {
+		WatcherBackend::new(config.watch_mode, config.watch_poll_interval_seconds)
+	}
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum LibraryWatcherCommand {
-	AddWatcher(PathBuf),
+	AddWatcher(PathBuf, WatcherBackend),
 	RemoveWatcher(PathBuf),
+	/// Files which were created or had their contents modified
 	ChangedFiles(Vec<PathBuf>),
+	/// Files or directories which no longer exist on disk, either because they were
+	/// deleted or renamed to something else
+	RemovedFiles(Vec<PathBuf>),
+	/// The new location of files or directories which were renamed
+	RenamedFiles(Vec<PathBuf>),
 	Flush,
 	StopWatchers,
 }
 
+/// Translate a raw filesystem event into the command(s) the watcher should handle.
+///
+/// Renames are reported differently depending on the platform: inotify reports both
+/// sides of a rename as a single event, while other backends report each side on its
+/// own (or without any indication of which side the path is). When the side is unknown,
+/// whether the path still exists decides if it is the source or the destination.
+fn commands_for_event(event: Event) -> Vec<LibraryWatcherCommand> {
+	match event.kind {
+		EventKind::Create(_) => vec![LibraryWatcherCommand::ChangedFiles(event.paths)],
+		EventKind::Remove(_) => vec![LibraryWatcherCommand::RemovedFiles(event.paths)],
+		EventKind::Modify(ModifyKind::Name(mode)) => match mode {
+			RenameMode::From => vec![LibraryWatcherCommand::RemovedFiles(event.paths)],
+			RenameMode::To => vec![LibraryWatcherCommand::RenamedFiles(event.paths)],
+			RenameMode::Both => {
+				let mut paths = event.paths.into_iter();
+				let from = paths.next().into_iter().collect::<Vec<_>>();
+				let to = paths.collect::<Vec<_>>();
+				vec![
+					LibraryWatcherCommand::RemovedFiles(from),
+					LibraryWatcherCommand::RenamedFiles(to),
+				]
+			},
+			_ => {
+				let (existing, removed): (Vec<_>, Vec<_>) =
+					event.paths.into_iter().partition(|path| path.exists());
+				vec![
+					LibraryWatcherCommand::RemovedFiles(removed),
+					LibraryWatcherCommand::RenamedFiles(existing),
+				]
+			},
+		},
+		EventKind::Modify(_) => vec![LibraryWatcherCommand::ChangedFiles(event.paths)],
+		_ => vec![],
+	}
+	.into_iter()
+	.filter(|command| match command {
+		LibraryWatcherCommand::ChangedFiles(paths)
+		| LibraryWatcherCommand::RemovedFiles(paths)
+		| LibraryWatcherCommand::RenamedFiles(paths) => !paths.is_empty(),
+		_ => true,
+	})
+	.collect()
+}
+
 fn create_watcher(sender: UnboundedSender<LibraryWatcherCommand>) -> RecommendedWatcher {
 	notify::recommended_watcher(move |result: Result<Event, _>| match result {
-		Ok(event) => match event.kind {
-			notify::EventKind::Create(_) | notify::EventKind::Modify(_) => {
-				let _ = sender
-					.send(LibraryWatcherCommand::ChangedFiles(event.paths))
-					.map_err(|e| {
-						tracing::error!(error = ?e, "Error sending file paths");
-					});
-			},
-			_ => {},
+		Ok(event) => {
+			for command in commands_for_event(event) {
+				let _ = sender.send(command).map_err(|e| {
+					tracing::error!(error = ?e, "Error sending file paths");
+				});
+			}
 		},
 		Err(e) => {
 			tracing::error!(?e, "Error processing file");
@@ -40,12 +126,34 @@ fn create_watcher(sender: UnboundedSender<LibraryWatcherCommand>) -> Recommended
 	.expect("Failed to create watcher")
 }
 
+/// The changes accumulated by the watcher between two flushes
+#[derive(Debug, Default)]
+struct WatchedChanges {
+	changed: HashSet<PathBuf>,
+	removed: HashSet<PathBuf>,
+	renamed: HashSet<PathBuf>,
+}
+
+impl WatchedChanges {
+	fn is_empty(&self) -> bool {
+		self.changed.is_empty() && self.removed.is_empty() && self.renamed.is_empty()
+	}
+}
+
+/// A scan the watcher wants to run in response to changes on disk
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum WatcherScan {
+	Library { id: String, path: String },
+	Series { id: String, path: String },
+}
+
 struct LibraryWatcherInternal {
 	wait_interval: Duration,
 	sender: UnboundedSender<LibraryWatcherCommand>,
 	watcher: RecommendedWatcher,
+	polling_watchers: HashMap<PathBuf, PollingWatcher>,
 	last_update_time: Arc<Mutex<std::time::SystemTime>>,
-	accumulated_paths: HashSet<PathBuf>,
+	accumulated_changes: WatchedChanges,
 	wait_thread: Option<tokio::task::JoinHandle<()>>,
 }
 
@@ -59,20 +167,70 @@ impl LibraryWatcherInternal {
 			wait_interval: wait_duration,
 			sender: sender.clone(),
 			watcher,
+			polling_watchers: HashMap::new(),
 			last_update_time: Arc::new(Mutex::new(std::time::SystemTime::now())),
-			accumulated_paths: HashSet::new(),
+			accumulated_changes: WatchedChanges::default(),
 			wait_thread: None,
 		}
 	}
 
+	fn add_watcher(
+		&mut self,
+		path: PathBuf,
+		backend: WatcherBackend,
+	) -> notify::Result<()> {
+		// A library might be switching between backends, so any existing watcher for the
+		// path is removed first
+		self.remove_watcher(&path)?;
+
+		match backend {
+			WatcherBackend::Native => self
+				.watcher
+				.watch(path.as_path(), notify::RecursiveMode::Recursive),
+			WatcherBackend::Poll { interval } => {
+				let watcher =
+					PollingWatcher::new(path.clone(), interval, self.sender.clone());
+				self.polling_watchers.insert(path, watcher);
+				Ok(())
+			},
+		}
+	}
+
+	fn remove_watcher(&mut self, path: &Path) -> notify::Result<()> {
+		if self.polling_watchers.remove(path).is_some() {
+			return Ok(());
+		}
+
+		match self.watcher.unwatch(path) {
+			Err(notify::Error {
+				kind: notify::ErrorKind::WatchNotFound,
+				..
+			}) => Ok(()),
+			result => result,
+		}
+	}
+
 	async fn handle_changed_files(&mut self, paths: Vec<PathBuf>) {
+		self.accumulated_changes.changed.extend(paths);
+		self.debounce().await;
+	}
+
+	async fn handle_removed_files(&mut self, paths: Vec<PathBuf>) {
+		self.accumulated_changes.removed.extend(paths);
+		self.debounce().await;
+	}
+
+	async fn handle_renamed_files(&mut self, paths: Vec<PathBuf>) {
+		self.accumulated_changes.renamed.extend(paths);
+		self.debounce().await;
+	}
+
+	async fn debounce(&mut self) {
 		{
 			let mut last_update_time = self.last_update_time.lock().await;
 			*last_update_time = std::time::SystemTime::now();
 		}
 
-		self.accumulated_paths.extend(paths);
-
 		if self.wait_thread.is_none() {
 			let sender = self.sender.clone();
 			let interval = self.wait_interval;
@@ -99,15 +257,56 @@ impl LibraryWatcherInternal {
 		}
 	}
 
-	async fn flush(&mut self) -> HashSet<PathBuf> {
+	async fn flush(&mut self) -> WatchedChanges {
 		self.wait_thread = None;
-		std::mem::take(&mut self.accumulated_paths)
+		std::mem::take(&mut self.accumulated_changes)
+	}
+}
+
+/// Find the series a removed or renamed path should be rescanned through, if any. This
+/// is the innermost series containing the path, so long as that series still exists on
+/// disk. If the path is (or contains) a series directory, there is no series to target
+/// and the library needs to be scanned instead.
+fn find_series_for_path<'a>(
+	series: &'a [series::SeriesIdentSelect],
+	path: &Path,
+) -> Option<&'a series::SeriesIdentSelect> {
+	if series
+		.iter()
+		.any(|series| Path::new(&series.path).starts_with(path))
+	{
+		return None;
+	}
+
+	series
+		.iter()
+		.filter(|series| path.starts_with(&series.path))
+		.max_by_key(|series| Path::new(&series.path).components().count())
+		.filter(|series| Path::new(&series.path).exists())
+}
+
+/// A library which has watching enabled
+#[derive(Debug, Clone, FromQueryResult)]
+struct WatchedLibrary {
+	id: String,
+	path: String,
+	watch_mode: LibraryWatchMode,
+	watch_poll_interval_seconds: i32,
+}
+
+impl WatchedLibrary {
+	fn backend(&self) -> WatcherBackend {
+		WatcherBackend::new(self.watch_mode, self.watch_poll_interval_seconds)
 	}
 }
 
 #[async_trait]
 trait LibrariesProvider {
-	async fn get_libraries(&self) -> CoreResult<Vec<library::LibraryIdentSelect>>;
+	async fn get_libraries(&self) -> CoreResult<Vec<WatchedLibrary>>;
+	async fn get_series(
+		&self,
+		library_id: &str,
+	) -> CoreResult<Vec<series::SeriesIdentSelect>>;
 }
 
 #[derive(Debug, Clone)]
@@ -118,45 +317,65 @@ struct LibraryProvider {
 #[async_trait]
 impl LibrariesProvider for LibraryProvider {
 	#[tracing::instrument(skip(self), err)]
-	async fn get_libraries(&self) -> CoreResult<Vec<library::LibraryIdentSelect>> {
+	async fn get_libraries(&self) -> CoreResult<Vec<WatchedLibrary>> {
 		// get list of all libraries
 		// for each library, if watching is enabled, watch their directory
 		let conn = self.conn.as_ref();
 
-		let libraries: Vec<library::LibraryIdentSelect> = library::Entity::find()
+		let libraries = library::Entity::find()
+			.select_only()
+			.column(library::Column::Id)
+			.column(library::Column::Path)
+			.column(library_config::Column::WatchMode)
+			.column(library_config::Column::WatchPollIntervalSeconds)
 			.inner_join(library_config::Entity)
 			.filter(library::Column::Status.eq("READY"))
 			.filter(library_config::Column::Watch.eq(true))
-			.into_partial_model::<library::LibraryIdentSelect>()
+			.into_model::<WatchedLibrary>()
 			.all(conn)
 			.await?;
 
 		Ok(libraries)
 	}
+
+	#[tracing::instrument(skip(self), err)]
+	async fn get_series(
+		&self,
+		library_id: &str,
+	) -> CoreResult<Vec<series::SeriesIdentSelect>> {
+		let series = series::Entity::find()
+			.select_only()
+			.columns(series::SeriesIdentSelect::columns())
+			.filter(series::Column::LibraryId.eq(library_id))
+			.filter(series::Column::DeletedAt.is_null())
+			.into_model::<series::SeriesIdentSelect>()
+			.all(self.conn.as_ref())
+			.await?;
+
+		Ok(series)
+	}
 }
 
 #[async_trait]
 trait SubmitScanJob {
-	async fn submit(&self, id: String, path: String) -> Result<(), ()>;
+	async fn submit(&self, scan: WatcherScan) -> Result<(), ()>;
 }
 
 #[derive(Clone)]
 struct ApalisJobSubmitter {
-	storage: MemoryStorage<StumpJob>,
+	storage: JobStorage,
 }
 
 #[async_trait]
 impl SubmitScanJob for ApalisJobSubmitter {
-	async fn submit(&self, id: String, path: String) -> Result<(), ()> {
-		let mut storage = self.storage.clone();
-		use apalis::prelude::MessageQueue;
-		storage
-			.enqueue(StumpJob::library_scan(id, path, None))
-			.await
-			.map(|_| ())
-			.map_err(|e| {
-				tracing::error!(error = ?e, "Error enqueuing library scan job");
-			})
+	async fn submit(&self, scan: WatcherScan) -> Result<(), ()> {
+		let job = match scan {
+			WatcherScan::Library { id, path } => StumpJob::watched_library_scan(id, path),
+			WatcherScan::Series { id, path } => StumpJob::series_scan(id, path, None),
+		};
+		self.storage.enqueue(job).await.map(|_| ()).map_err(|e| {
+			tracing::error!(error = ?e, "Error enqueuing scan job");
+		})
 	}
 }
 
@@ -167,10 +386,7 @@ pub struct LibraryWatcher {
 }
 
 impl LibraryWatcher {
-	pub fn new(
-		conn: Arc<DatabaseConnection>,
-		storage: MemoryStorage<StumpJob>,
-	) -> LibraryWatcher {
+	pub fn new(conn: Arc<DatabaseConnection>, storage: JobStorage) -> LibraryWatcher {
 		let library_provider = LibraryProvider { conn };
 		let job_submitter = ApalisJobSubmitter { storage };
 		let (tx, rx) = unbounded_channel();
@@ -223,19 +439,16 @@ impl LibraryWatcher {
 				LibraryWatcherInternal::new(watcher, sender, wait_duration);
 			while let Some(command) = receiver.recv().await {
 				match command {
-					LibraryWatcherCommand::AddWatcher(path) => {
-						tracing::debug!("Adding watcher for path: {:?}", path);
-						if let Err(e) = lib_watcher
-							.watcher
-							.watch(path.as_path(), notify::RecursiveMode::Recursive)
-						{
+					LibraryWatcherCommand::AddWatcher(path, backend) => {
+						tracing::debug!(?backend, "Adding watcher for path: {:?}", path);
+						if let Err(e) = lib_watcher.add_watcher(path, backend) {
 							tracing::error!(error = ?e, "Error adding file watcher");
 							break;
 						}
 					},
 					LibraryWatcherCommand::RemoveWatcher(path) => {
 						tracing::debug!("Removing watcher for path: {:?}", path);
-						if let Err(e) = lib_watcher.watcher.unwatch(path.as_path()) {
+						if let Err(e) = lib_watcher.remove_watcher(path.as_path()) {
 							tracing::error!(error = ?e, "Error removing file watcher");
 							break;
 						}
@@ -243,6 +456,12 @@ impl LibraryWatcher {
 					LibraryWatcherCommand::ChangedFiles(paths) => {
 						lib_watcher.handle_changed_files(paths).await;
 					},
+					LibraryWatcherCommand::RemovedFiles(paths) => {
+						lib_watcher.handle_removed_files(paths).await;
+					},
+					LibraryWatcherCommand::RenamedFiles(paths) => {
+						lib_watcher.handle_renamed_files(paths).await;
+					},
 					LibraryWatcherCommand::Flush => {
 						let _ = Self::start_jobs(
 							&library_provider,
@@ -259,28 +478,83 @@ impl LibraryWatcher {
 		});
 	}
 
+	/// Determine which scans should run for the accumulated changes and submit them.
+	///
+	/// New or modified files trigger a scan of their library, since they may belong to a
+	/// series which doesn't exist yet. Removed and renamed files are instead rescanned
+	/// through the series which contains them, which is much quicker and is enough for
+	/// the affected books to be marked missing or reconciled with their new location.
+	/// If any path can't be attributed to an existing series (e.g. a series directory
+	/// was itself removed or renamed), the whole library is scanned instead.
 	async fn start_jobs(
 		library_provider: &Arc<dyn LibrariesProvider + Send + Sync + 'static>,
 		job_submitter: &Arc<dyn SubmitScanJob + Send + Sync + 'static>,
-		paths: HashSet<PathBuf>,
+		changes: WatchedChanges,
 	) -> Result<(), CoreError> {
+		if changes.is_empty() {
+			return Ok(());
+		}
+
 		let libraries = library_provider.as_ref().get_libraries().await?;
 
-		let mut libraries_to_scan = HashMap::new();
+		let mut scans = HashSet::new();
 		for library in libraries {
-			for path in &paths {
-				if path.starts_with(&library.path) {
-					libraries_to_scan.insert(library.id.clone(), library.path.clone());
-				}
+			let in_library = |path: &&PathBuf| path.starts_with(&library.path);
+			let library_scan = WatcherScan::Library {
+				id: library.id.clone(),
+				path: library.path.clone(),
+			};
+
+			if changes.changed.iter().any(|path| in_library(&path)) {
+				scans.insert(library_scan);
+				continue;
 			}
-		}
 
-		let results = libraries_to_scan
-			.into_iter()
-			.map(|(id, path_str)| job_submitter.submit(id, path_str));
+			let removed = changes
+				.removed
+				.iter()
+				.filter(in_library)
+				.collect::<Vec<_>>();
+			let renamed = changes
+				.renamed
+				.iter()
+				.filter(in_library)
+				.collect::<Vec<_>>();
+			if removed.is_empty() && renamed.is_empty() {
+				continue;
+			}
 
-		for result in results {
-			result.await.map_err(|e| {
+			// A renamed directory might contain series which don't exist yet, so only a
+			// library scan will pick up everything inside of it
+			let requires_library_scan = renamed.iter().any(|path| path.is_dir());
+
+			let series = library_provider.get_series(&library.id).await?;
+			let series_scans = removed
+				.iter()
+				.chain(renamed.iter())
+				.map(|path| {
+					find_series_for_path(&series, path).map(|series| {
+						WatcherScan::Series {
+							id: series.id.clone(),
+							path: series.path.clone(),
+						}
+					})
+				})
+				.collect::<Option<HashSet<_>>>();
+
+			match series_scans {
+				Some(series_scans) if !requires_library_scan => {
+					scans.extend(series_scans);
+				},
+				_ => {
+					scans.insert(library_scan);
+				},
+			}
+		}
+
+		for scan in scans {
+			tracing::debug!(?scan, "Submitting scan for watched changes");
+			job_submitter.submit(scan).await.map_err(|e| {
 				CoreError::InitializationError(format!("Failed to submit job: {:?}", e))
 			})?;
 		}
@@ -307,17 +581,24 @@ impl LibraryWatcher {
 	pub async fn add_watcher(
 		&self,
 		path: PathBuf,
+		backend: WatcherBackend,
 	) -> Result<(), SendError<LibraryWatcherCommand>> {
 		self.sender
-			.send(LibraryWatcherCommand::AddWatcher(path.clone()))
+			.send(LibraryWatcherCommand::AddWatcher(path.clone(), backend))
 	}
 
 	pub async fn init(&self) -> CoreResult<()> {
 		let libraries = self.library_provider.get_libraries().await?;
 		for library in libraries {
-			self.add_watcher(library.path.into()).await.map_err(|e| {
-				CoreError::InitializationError(format!("Failed to add watcher: {:?}", e))
-			})?;
+			let backend = library.backend();
+			self.add_watcher(library.path.into(), backend)
+				.await
+				.map_err(|e| {
+					CoreError::InitializationError(format!(
+						"Failed to add watcher: {:?}",
+						e
+					))
+				})?;
 		}
 		Ok(())
 	}
@@ -328,25 +609,33 @@ mod tests {
 
 	#[allow(dead_code)]
 	struct MockLibraryProvider {
-		libraries: Vec<library::LibraryIdentSelect>,
+		libraries: Vec<WatchedLibrary>,
+		series: Vec<series::SeriesIdentSelect>,
 	}
 
 	#[async_trait]
 	impl LibrariesProvider for MockLibraryProvider {
-		async fn get_libraries(&self) -> CoreResult<Vec<library::LibraryIdentSelect>> {
+		async fn get_libraries(&self) -> CoreResult<Vec<WatchedLibrary>> {
 			Ok(self.libraries.clone())
 		}
+
+		async fn get_series(
+			&self,
+			_library_id: &str,
+		) -> CoreResult<Vec<series::SeriesIdentSelect>> {
+			Ok(self.series.clone())
+		}
 	}
 
 	#[allow(dead_code)]
 	struct MockJobControllerSubmitter {
-		tx: UnboundedSender<(String, String)>,
+		tx: UnboundedSender<WatcherScan>,
 	}
 
 	#[async_trait]
 	impl SubmitScanJob for MockJobControllerSubmitter {
-		async fn submit(&self, id: String, path: String) -> Result<(), ()> {
-			let _ = self.tx.send((id, path)).map_err(|e| {
+		async fn submit(&self, scan: WatcherScan) -> Result<(), ()> {
+			let _ = self.tx.send(scan).map_err(|e| {
 				eprintln!("Error sending job: {:?}", e);
 			});
 			Ok(())
@@ -357,17 +646,25 @@ mod tests {
 	struct MockObjs {
 		library_watcher: LibraryWatcher,
 		sender: UnboundedSender<LibraryWatcherCommand>,
-		jobs_receiver: UnboundedReceiver<(String, String)>,
+		jobs_receiver: UnboundedReceiver<WatcherScan>,
 	}
 
 	#[allow(dead_code)]
 	async fn create_mock_library(
-		libraries: Vec<library::LibraryIdentSelect>,
+		libraries: Vec<WatchedLibrary>,
+	) -> Result<MockObjs, CoreError> {
+		create_mock_library_with_series(libraries, vec![]).await
+	}
+
+	#[allow(dead_code)]
+	async fn create_mock_library_with_series(
+		libraries: Vec<WatchedLibrary>,
+		series: Vec<series::SeriesIdentSelect>,
 	) -> Result<MockObjs, CoreError> {
 		let (tx_jobs, rx_jobs) = tokio::sync::mpsc::unbounded_channel();
 		let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
 
-		let library_provider = MockLibraryProvider { libraries };
+		let library_provider = MockLibraryProvider { libraries, series };
 		let job_submitter = MockJobControllerSubmitter {
 			tx: tx_jobs.clone(),
 		};
@@ -391,11 +688,12 @@ mod tests {
 	}
 
 	#[allow(dead_code)]
-	fn create_test_libraries(base_dir: String) -> Vec<library::LibraryIdentSelect> {
-		vec![library::LibraryIdentSelect {
+	fn create_test_libraries(base_dir: String) -> Vec<WatchedLibrary> {
+		vec![WatchedLibrary {
 			id: "42".to_string(),
-			name: "Test Library".to_string(),
 			path: base_dir,
+			watch_mode: LibraryWatchMode::Native,
+			watch_poll_interval_seconds: 60,
 		}]
 	}
 
@@ -409,7 +707,7 @@ mod tests {
 
 		assert!(mock_objs
 			.library_watcher
-			.add_watcher(tmp_dir.clone())
+			.add_watcher(tmp_dir.clone(), WatcherBackend::Native)
 			.await
 			.is_ok());
 		let new_file = tmp_dir.join("new_file");
@@ -420,9 +718,14 @@ mod tests {
 
 		// Wait for the background thread to trigger the flush
 		tokio::time::sleep(Duration::from_millis(100)).await;
-		let (id, path) = mock_objs.jobs_receiver.try_recv().expect("Expected a job");
-		assert_eq!(id, "42");
-		assert_eq!(path, tmp_dir.to_string_lossy().to_string());
+		let scan = mock_objs.jobs_receiver.try_recv().expect("Expected a job");
+		assert_eq!(
+			scan,
+			WatcherScan::Library {
+				id: "42".to_string(),
+				path: tmp_dir.to_string_lossy().to_string(),
+			}
+		);
 	}
 
 	#[tokio::test]
@@ -435,7 +738,7 @@ mod tests {
 
 		assert!(mock_objs
 			.library_watcher
-			.add_watcher(tmp_dir.clone())
+			.add_watcher(tmp_dir.clone(), WatcherBackend::Native)
 			.await
 			.is_ok());
 		assert!(mock_objs
@@ -461,12 +764,12 @@ mod tests {
 		let mock_objs = create_mock_library(libraries).await.unwrap();
 		assert!(mock_objs
 			.library_watcher
-			.add_watcher(tmp_dir.clone())
+			.add_watcher(tmp_dir.clone(), WatcherBackend::Native)
 			.await
 			.is_ok());
 		assert!(mock_objs
 			.library_watcher
-			.add_watcher(tmp_dir.clone())
+			.add_watcher(tmp_dir.clone(), WatcherBackend::Native)
 			.await
 			.is_ok());
 	}
@@ -481,7 +784,7 @@ mod tests {
 
 		assert!(mock_objs
 			.library_watcher
-			.add_watcher(tmp_dir.clone())
+			.add_watcher(tmp_dir.clone(), WatcherBackend::Native)
 			.await
 			.is_ok());
 		let new_file = tmp_dir.join("new_file");
@@ -505,14 +808,22 @@ mod tests {
 		assert!(LibraryWatcher::start_jobs(
 			&mock_objs.library_watcher.library_provider,
 			&mock_objs.library_watcher.job_submitter,
-			paths,
+			WatchedChanges {
+				changed: paths,
+				..Default::default()
+			},
 		)
 		.await
 		.is_ok());
 
-		let (id, path) = mock_objs.jobs_receiver.try_recv().expect("Expected a job");
-		assert_eq!(id, "42");
-		assert_eq!(path, tmp_dir.to_string_lossy().to_string());
+		let scan = mock_objs.jobs_receiver.try_recv().expect("Expected a job");
+		assert_eq!(
+			scan,
+			WatcherScan::Library {
+				id: "42".to_string(),
+				path: tmp_dir.to_string_lossy().to_string(),
+			}
+		);
 	}
 
 	#[tokio::test]
@@ -529,7 +840,11 @@ mod tests {
 		assert!(LibraryWatcher::start_jobs(
 			&mock_objs.library_watcher.library_provider,
 			&mock_objs.library_watcher.job_submitter,
-			bad_paths,
+			WatchedChanges {
+				changed: bad_paths.clone(),
+				removed: bad_paths,
+				..Default::default()
+			},
 		)
 		.await
 		.is_ok());
@@ -539,4 +854,321 @@ mod tests {
 			tokio::sync::mpsc::error::TryRecvError::Empty
 		);
 	}
+
+	#[allow(dead_code)]
+	fn create_test_series(
+		library_dir: &Path,
+		names: &[&str],
+	) -> Vec<series::SeriesIdentSelect> {
+		names
+			.iter()
+			.enumerate()
+			.map(|(idx, name)| {
+				let path = library_dir.join(name);
+				std::fs::create_dir_all(&path).unwrap();
+				series::SeriesIdentSelect {
+					id: format!("series-{}", idx),
+					path: path.to_string_lossy().to_string(),
+				}
+			})
+			.collect()
+	}
+
+	#[allow(dead_code)]
+	async fn start_jobs_for(
+		mock_objs: &mut MockObjs,
+		changes: WatchedChanges,
+	) -> Vec<WatcherScan> {
+		assert!(LibraryWatcher::start_jobs(
+			&mock_objs.library_watcher.library_provider,
+			&mock_objs.library_watcher.job_submitter,
+			changes,
+		)
+		.await
+		.is_ok());
+
+		let mut scans = vec![];
+		while let Ok(scan) = mock_objs.jobs_receiver.try_recv() {
+			scans.push(scan);
+		}
+		scans
+	}
+
+	#[test]
+	fn test_commands_for_event() {
+		let book = PathBuf::from("/library/series/book.cbz");
+		let renamed = PathBuf::from("/library/series/renamed.cbz");
+
+		let removed = Event::new(EventKind::Remove(notify::event::RemoveKind::File))
+			.add_path(book.clone());
+		assert_eq!(
+			commands_for_event(removed),
+			vec![LibraryWatcherCommand::RemovedFiles(vec![book.clone()])]
+		);
+
+		let rename = Event::new(EventKind::Modify(ModifyKind::Name(RenameMode::Both)))
+			.add_path(book.clone())
+			.add_path(renamed.clone());
+		assert_eq!(
+			commands_for_event(rename),
+			vec![
+				LibraryWatcherCommand::RemovedFiles(vec![book.clone()]),
+				LibraryWatcherCommand::RenamedFiles(vec![renamed.clone()]),
+			]
+		);
+
+		let rename_from =
+			Event::new(EventKind::Modify(ModifyKind::Name(RenameMode::From)))
+				.add_path(book.clone());
+		assert_eq!(
+			commands_for_event(rename_from),
+			vec![LibraryWatcherCommand::RemovedFiles(vec![book.clone()])]
+		);
+
+		let created = Event::new(EventKind::Create(notify::event::CreateKind::File))
+			.add_path(renamed.clone());
+		assert_eq!(
+			commands_for_event(created),
+			vec![LibraryWatcherCommand::ChangedFiles(vec![renamed])]
+		);
+
+		let accessed =
+			Event::new(EventKind::Access(notify::event::AccessKind::Any)).add_path(book);
+		assert!(commands_for_event(accessed).is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_removed_book_scans_series() {
+		let tmp_dir = tempfile::tempdir().unwrap();
+		let libraries =
+			create_test_libraries(tmp_dir.path().to_string_lossy().to_string());
+		let series = create_test_series(tmp_dir.path(), &["Batman", "Spider-Man"]);
+
+		let mut mock_objs = create_mock_library_with_series(libraries, series.clone())
+			.await
+			.unwrap();
+
+		let scans = start_jobs_for(
+			&mut mock_objs,
+			WatchedChanges {
+				removed: HashSet::from_iter(vec![
+					tmp_dir.path().join("Batman").join("book-1.cbz"),
+					tmp_dir.path().join("Batman").join("book-2.cbz"),
+				]),
+				..Default::default()
+			},
+		)
+		.await;
+
+		assert_eq!(
+			scans,
+			vec![WatcherScan::Series {
+				id: series[0].id.clone(),
+				path: series[0].path.clone(),
+			}]
+		);
+	}
+
+	#[tokio::test]
+	async fn test_renamed_book_scans_series() {
+		let tmp_dir = tempfile::tempdir().unwrap();
+		let libraries =
+			create_test_libraries(tmp_dir.path().to_string_lossy().to_string());
+		let series = create_test_series(tmp_dir.path(), &["Batman", "Batman/Annuals"]);
+
+		let mut mock_objs = create_mock_library_with_series(libraries, series.clone())
+			.await
+			.unwrap();
+
+		let renamed = tmp_dir.path().join("Batman/Annuals/annual-1.cbz");
+		std::fs::write(&renamed, b"").unwrap();
+
+		let scans = start_jobs_for(
+			&mut mock_objs,
+			WatchedChanges {
+				removed: HashSet::from_iter(vec![tmp_dir
+					.path()
+					.join("Batman/Annuals/old-name.cbz")]),
+				renamed: HashSet::from_iter(vec![renamed]),
+				..Default::default()
+			},
+		)
+		.await;
+
+		// The innermost series should be targeted
+		assert_eq!(
+			scans,
+			vec![WatcherScan::Series {
+				id: series[1].id.clone(),
+				path: series[1].path.clone(),
+			}]
+		);
+	}
+
+	#[tokio::test]
+	async fn test_removed_series_scans_library() {
+		let tmp_dir = tempfile::tempdir().unwrap();
+		let library_path = tmp_dir.path().to_string_lossy().to_string();
+		let libraries = create_test_libraries(library_path.clone());
+		let series = create_test_series(tmp_dir.path(), &["Batman", "Spider-Man"]);
+
+		let mut mock_objs = create_mock_library_with_series(libraries, series)
+			.await
+			.unwrap();
+
+		let removed_series = tmp_dir.path().join("Spider-Man");
+		std::fs::remove_dir_all(&removed_series).unwrap();
+
+		let scans = start_jobs_for(
+			&mut mock_objs,
+			WatchedChanges {
+				removed: HashSet::from_iter(vec![
+					tmp_dir.path().join("Batman").join("book-1.cbz"),
+					removed_series,
+				]),
+				..Default::default()
+			},
+		)
+		.await;
+
+		assert_eq!(
+			scans,
+			vec![WatcherScan::Library {
+				id: "42".to_string(),
+				path: library_path,
+			}]
+		);
+	}
+
+	#[tokio::test]
+	async fn test_renamed_directory_scans_library() {
+		let tmp_dir = tempfile::tempdir().unwrap();
+		let library_path = tmp_dir.path().to_string_lossy().to_string();
+		let libraries = create_test_libraries(library_path.clone());
+		let series = create_test_series(tmp_dir.path(), &["Batman"]);
+
+		let mut mock_objs = create_mock_library_with_series(libraries, series)
+			.await
+			.unwrap();
+
+		let renamed_dir = tmp_dir.path().join("Batman").join("Annuals");
+		std::fs::create_dir_all(&renamed_dir).unwrap();
+
+		let scans = start_jobs_for(
+			&mut mock_objs,
+			WatchedChanges {
+				removed: HashSet::from_iter(vec![tmp_dir
+					.path()
+					.join("Batman")
+					.join("Specials")]),
+				renamed: HashSet::from_iter(vec![renamed_dir]),
+				..Default::default()
+			},
+		)
+		.await;
+
+		assert_eq!(
+			scans,
+			vec![WatcherScan::Library {
+				id: "42".to_string(),
+				path: library_path,
+			}]
+		);
+	}
+
+	#[tokio::test]
+	async fn test_removed_files_are_debounced() {
+		let tmp_dir = tempfile::tempdir().unwrap();
+		let libraries =
+			create_test_libraries(tmp_dir.path().to_string_lossy().to_string());
+		let series = create_test_series(tmp_dir.path(), &["Batman"]);
+
+		let mut mock_objs = create_mock_library_with_series(libraries, series.clone())
+			.await
+			.unwrap();
+
+		for idx in 0..3 {
+			let removed = tmp_dir.path().join("Batman").join(format!("{}.cbz", idx));
+			assert!(mock_objs
+				.sender
+				.send(LibraryWatcherCommand::RemovedFiles(vec![removed]))
+				.is_ok());
+		}
+
+		// Wait for the background thread to trigger the flush
+		tokio::time::sleep(Duration::from_millis(100)).await;
+		let scan = mock_objs.jobs_receiver.try_recv().expect("Expected a job");
+		assert_eq!(
+			scan,
+			WatcherScan::Series {
+				id: series[0].id.clone(),
+				path: series[0].path.clone(),
+			}
+		);
+		assert!(mock_objs.jobs_receiver.try_recv().is_err());
+	}
+
+	#[tokio::test]
+	async fn test_polling_backend() {
+		let tmp_dir = tempfile::tempdir().unwrap();
+		let library_path = tmp_dir.path().to_string_lossy().to_string();
+		let libraries = vec![WatchedLibrary {
+			watch_mode: LibraryWatchMode::Poll,
+			..create_test_libraries(library_path.clone()).remove(0)
+		}];
+		let series = create_test_series(tmp_dir.path(), &["Batman"]);
+
+		let book = tmp_dir.path().join("Batman").join("book-1.cbz");
+		std::fs::write(&book, b"book").unwrap();
+
+		let mut mock_objs = create_mock_library_with_series(libraries, series.clone())
+			.await
+			.unwrap();
+		// Note: the backend is added directly to skip the minimum poll interval
+		assert!(mock_objs
+			.sender
+			.send(LibraryWatcherCommand::AddWatcher(
+				tmp_dir.path().to_path_buf(),
+				WatcherBackend::Poll {
+					interval: Duration::from_millis(10),
+				},
+			))
+			.is_ok());
+		tokio::time::sleep(Duration::from_millis(50)).await;
+
+		std::fs::remove_file(&book).unwrap();
+
+		let scan =
+			tokio::time::timeout(Duration::from_secs(1), mock_objs.jobs_receiver.recv())
+				.await
+				.expect("Expected a job")
+				.expect("Channel closed");
+		assert_eq!(
+			scan,
+			WatcherScan::Series {
+				id: series[0].id.clone(),
+				path: series[0].path.clone(),
+			}
+		);
+	}
+
+	#[test]
+	fn test_watcher_backend() {
+		assert_eq!(
+			WatcherBackend::new(LibraryWatchMode::Native, 1),
+			WatcherBackend::Native
+		);
+		assert_eq!(
+			WatcherBackend::new(LibraryWatchMode::Poll, 120),
+			WatcherBackend::Poll {
+				interval: Duration::from_secs(120)
+			}
+		);
+		assert_eq!(
+			WatcherBackend::new(LibraryWatchMode::Poll, 1),
+			WatcherBackend::Poll {
+				interval: MIN_POLL_INTERVAL
+			}
+		);
+	}
 }
@@ -1,12 +1,17 @@
+mod clean;
+mod clean_job;
 mod library_scan_job;
 mod library_watcher;
 mod options;
+mod polling_watcher;
 mod series_scan_job;
 mod utils;
 mod walk;
 
+pub use clean::{clean_library, CleanLibraryOutput};
+pub use clean_job::{LibraryCleanupJob, LibraryCleanupOutput};
 pub use library_scan_job::{LibraryScanJob, LibraryScanOutput};
-pub use library_watcher::LibraryWatcher;
+pub use library_watcher::{LibraryWatcher, WatcherBackend};
 pub use options::{CustomVisit, CustomVisitResult, ScanConfig, ScanOptions};
 pub use series_scan_job::{SeriesScanJob, SeriesScanOutput};
 pub use walk::{walk_library, walk_series, WalkedLibrary, WalkedSeries, WalkerCtx};
@@ -0,0 +1,305 @@
+use std::{
+	collections::HashMap,
+	path::{Path, PathBuf},
+	time::{Duration, SystemTime},
+};
+
+use tokio::{sync::mpsc::UnboundedSender, task::JoinHandle, time::MissedTickBehavior};
+use walkdir::WalkDir;
+
+use crate::filesystem::PathUtils;
+
+use super::library_watcher::LibraryWatcherCommand;
+
+/// The metadata of a file which is compared between polls
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileSnapshot {
+	modified: Option<SystemTime>,
+	size: u64,
+}
+
+type DirectorySnapshot = HashMap<PathBuf, FileSnapshot>;
+
+/// A walk of a watched directory
+#[derive(Debug, Default)]
+struct DirectoryWalk {
+	snapshot: DirectorySnapshot,
+	/// The paths which couldn't be read during the walk. Their files are missing from the
+	/// snapshot, but they weren't necessarily removed
+	unreadable: Vec<PathBuf>,
+}
+
+/// Walk the directory and record the modified time and size of every non-hidden file in
+/// it. Returns `None` if the directory can't be read (e.g. a network mount is down), since
+/// an empty snapshot would otherwise look like every file was removed.
+fn take_snapshot(root: &Path) -> Option<DirectoryWalk> {
+	if let Err(error) = std::fs::read_dir(root) {
+		tracing::warn!(?error, ?root, "Failed to read watched directory");
+		return None;
+	}
+
+	let mut walk = DirectoryWalk::default();
+	for entry in WalkDir::new(root)
+		.into_iter()
+		.filter_entry(|entry| entry.depth() == 0 || !entry.path().is_hidden_file())
+	{
+		let entry = match entry {
+			Ok(entry) => entry,
+			Err(error) => {
+				tracing::warn!(?error, ?root, "Failed to read part of watched directory");
+				// An error which can't be placed in the tree could hide any file, so the
+				// whole walk is skipped
+				walk.unreadable.push(error.path()?.to_path_buf());
+				continue;
+			},
+		};
+		if !entry.file_type().is_file() {
+			continue;
+		}
+
+		match entry.metadata() {
+			Ok(metadata) => {
+				let snapshot = FileSnapshot {
+					modified: metadata.modified().ok(),
+					size: metadata.len(),
+				};
+				walk.snapshot.insert(entry.into_path(), snapshot);
+			},
+			Err(error) => {
+				tracing::warn!(?error, path = ?entry.path(), "Failed to read watched file");
+				walk.unreadable.push(entry.into_path());
+			},
+		}
+	}
+
+	Some(walk)
+}
+
+/// Keep the previous snapshot of every file under a path which couldn't be read, so that
+/// a subtree which is only temporarily unreadable isn't mistaken for removed files
+fn carry_over_unreadable(
+	previous: &DirectorySnapshot,
+	current: &mut DirectorySnapshot,
+	unreadable: &[PathBuf],
+) {
+	for (path, snapshot) in previous {
+		if unreadable
+			.iter()
+			.any(|unreadable| path.starts_with(unreadable))
+		{
+			current.entry(path.clone()).or_insert(*snapshot);
+		}
+	}
+}
+
+/// Diff two snapshots of the same directory into the commands the library watcher
+/// would have received from filesystem events
+fn diff_snapshots(
+	previous: &DirectorySnapshot,
+	current: &DirectorySnapshot,
+) -> Vec<LibraryWatcherCommand> {
+	let changed = current
+		.iter()
+		.filter(|(path, snapshot)| previous.get(*path) != Some(*snapshot))
+		.map(|(path, _)| path.clone())
+		.collect::<Vec<_>>();
+	let removed = previous
+		.keys()
+		.filter(|path| !current.contains_key(*path))
+		.cloned()
+		.collect::<Vec<_>>();
+
+	[
+		LibraryWatcherCommand::ChangedFiles(changed),
+		LibraryWatcherCommand::RemovedFiles(removed),
+	]
+	.into_iter()
+	.filter(|command| match command {
+		LibraryWatcherCommand::ChangedFiles(paths)
+		| LibraryWatcherCommand::RemovedFiles(paths) => !paths.is_empty(),
+		_ => true,
+	})
+	.collect()
+}
+
+/// A watcher for filesystems which don't emit events, like NFS or SMB mounts. It walks the
+/// directory on an interval and diffs the modified time and size of each file against the
+/// previous walk, feeding any differences into the library watcher as if they came from
+/// the filesystem. The polling stops when the watcher is dropped.
+pub(crate) struct PollingWatcher {
+	handle: JoinHandle<()>,
+}
+
+impl PollingWatcher {
+	pub(crate) fn new(
+		root: PathBuf,
+		interval: Duration,
+		sender: UnboundedSender<LibraryWatcherCommand>,
+	) -> PollingWatcher {
+		let handle = tokio::spawn(async move {
+			let mut previous: Option<DirectorySnapshot> = None;
+			let mut ticker = tokio::time::interval(interval);
+			// A slow walk (e.g. on a large network mount) shouldn't cause a burst of polls
+			ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+			loop {
+				ticker.tick().await;
+
+				let walk_root = root.clone();
+				let DirectoryWalk {
+					mut snapshot,
+					unreadable,
+				} = match tokio::task::spawn_blocking(move || take_snapshot(&walk_root))
+					.await
+				{
+					Ok(Some(walk)) => walk,
+					Ok(None) => continue,
+					Err(error) => {
+						tracing::error!(
+							?error,
+							?root,
+							"Failed to poll watched directory"
+						);
+						continue;
+					},
+				};
+
+				if let Some(previous) = &previous {
+					carry_over_unreadable(previous, &mut snapshot, &unreadable);
+					for command in diff_snapshots(previous, &snapshot) {
+						if sender.send(command).is_err() {
+							tracing::debug!(
+								?root,
+								"Library watcher is gone, stopping poll"
+							);
+							return;
+						}
+					}
+				}
+
+				previous = Some(snapshot);
+			}
+		});
+
+		PollingWatcher { handle }
+	}
+}
+
+impl Drop for PollingWatcher {
+	fn drop(&mut self) {
+		self.handle.abort();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_diff_snapshots() {
+		let unchanged = PathBuf::from("/library/series/unchanged.cbz");
+		let modified = PathBuf::from("/library/series/modified.cbz");
+		let removed = PathBuf::from("/library/series/removed.cbz");
+		let created = PathBuf::from("/library/series/created.cbz");
+
+		let snapshot = |size| FileSnapshot {
+			modified: Some(SystemTime::UNIX_EPOCH),
+			size,
+		};
+
+		let previous = HashMap::from([
+			(unchanged.clone(), snapshot(10)),
+			(modified.clone(), snapshot(10)),
+			(removed.clone(), snapshot(10)),
+		]);
+		let current = HashMap::from([
+			(unchanged, snapshot(10)),
+			(modified.clone(), snapshot(20)),
+			(created.clone(), snapshot(10)),
+		]);
+
+		let commands = diff_snapshots(&previous, &current);
+		assert_eq!(commands.len(), 2);
+		match &commands[0] {
+			LibraryWatcherCommand::ChangedFiles(paths) => {
+				let mut paths = paths.clone();
+				paths.sort();
+				assert_eq!(paths, vec![created, modified]);
+			},
+			command => panic!("Unexpected command: {:?}", command),
+		}
+		assert_eq!(
+			commands[1],
+			LibraryWatcherCommand::RemovedFiles(vec![removed])
+		);
+
+		assert!(diff_snapshots(&current, &current).is_empty());
+	}
+
+	#[test]
+	fn test_carry_over_unreadable() {
+		let readable = PathBuf::from("/library/readable/book.cbz");
+		let unreadable = PathBuf::from("/library/unreadable/book.cbz");
+		let snapshot = FileSnapshot {
+			modified: Some(SystemTime::UNIX_EPOCH),
+			size: 10,
+		};
+
+		let previous =
+			HashMap::from([(readable.clone(), snapshot), (unreadable.clone(), snapshot)]);
+		let mut current = HashMap::new();
+		carry_over_unreadable(
+			&previous,
+			&mut current,
+			&[PathBuf::from("/library/unreadable")],
+		);
+
+		// Only the file under the readable directory was removed
+		assert_eq!(
+			diff_snapshots(&previous, &current),
+			vec![LibraryWatcherCommand::RemovedFiles(vec![readable])]
+		);
+	}
+
+	#[test]
+	fn test_take_snapshot() {
+		let tmp_dir = tempfile::tempdir().unwrap();
+		let series_dir = tmp_dir.path().join("series");
+		std::fs::create_dir_all(&series_dir).unwrap();
+		std::fs::write(series_dir.join("book.cbz"), b"book").unwrap();
+		std::fs::write(series_dir.join(".hidden"), b"hidden").unwrap();
+
+		let walk = take_snapshot(tmp_dir.path()).expect("Expected a snapshot");
+		assert!(walk.unreadable.is_empty());
+		let snapshot = walk.snapshot;
+		assert_eq!(snapshot.len(), 1);
+		assert_eq!(snapshot.get(&series_dir.join("book.cbz")).unwrap().size, 4);
+
+		assert!(take_snapshot(&tmp_dir.path().join("does-not-exist")).is_none());
+	}
+
+	#[tokio::test]
+	async fn test_polling_watcher_sends_changes() {
+		let tmp_dir = tempfile::tempdir().unwrap();
+		let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+		let book = tmp_dir.path().join("book.cbz");
+		std::fs::write(&book, b"book").unwrap();
+
+		let _watcher = PollingWatcher::new(
+			tmp_dir.path().to_path_buf(),
+			Duration::from_millis(10),
+			tx,
+		);
+		// Wait for the initial snapshot to be taken
+		tokio::time::sleep(Duration::from_millis(50)).await;
+
+		std::fs::remove_file(&book).unwrap();
+
+		let command = tokio::time::timeout(Duration::from_secs(1), rx.recv())
+			.await
+			.expect("Expected a command")
+			.expect("Channel closed");
+		assert_eq!(command, LibraryWatcherCommand::RemovedFiles(vec![book]));
+	}
+}
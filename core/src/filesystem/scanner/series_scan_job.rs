@@ -12,6 +12,7 @@ use sea_orm::{prelude::*, sea_query::Query, Condition, UpdateResult};
 use serde::{Deserialize, Serialize};
 
 use crate::{
+	config::StumpConfig,
 	event,
 	filesystem::image::{
 		PlaceholderGenerationJobConfig, PlaceholderGenerationJobScope,
@@ -19,7 +20,7 @@ use crate::{
 	},
 	job::{
 		error::JobError, stump_job::StumpJob, CoreJobOutput, JobContext, JobLifecycle,
-		JobOutputExt, JobProgress, JobTaskOutput, WorkingState,
+		JobOutputExt, JobProgress, JobRetryPolicy, JobTaskOutput, WorkingState,
 	},
 	utils::chain_optional_iter,
 	CoreEvent,
@@ -105,6 +106,11 @@ impl JobLifecycle for SeriesScanJob {
 		Some(self.path.clone())
 	}
 
+	fn retry_policy(&self, config: &StumpConfig) -> JobRetryPolicy {
+		// See LibraryScanJob::retry_policy
+		JobRetryPolicy::from_config(config).with_max_attempts(1)
+	}
+
 	async fn init(
 		&mut self,
 		ctx: &JobContext,
@@ -208,6 +214,26 @@ impl JobLifecycle for SeriesScanJob {
 		})
 	}
 
+	async fn resume(&mut self, ctx: &JobContext) -> Result<(), JobError> {
+		let config = library_config::Entity::find()
+			.filter(
+				library_config::Column::LibraryId.in_subquery(
+					Query::select()
+						.column(series::Column::LibraryId)
+						.from(series::Entity)
+						.and_where(series::Column::Id.eq(self.id.clone()))
+						.to_owned(),
+				),
+			)
+			.one(ctx.conn())
+			.await?
+			.ok_or(JobError::InitFailed(
+				"Library is missing a configuration".to_string(),
+			))?;
+		self.config = Some(config);
+		Ok(())
+	}
+
 	async fn finalize(
 		&self,
 		ctx: &JobContext,
@@ -327,6 +353,7 @@ impl JobLifecycle for SeriesScanJob {
 				));
 				let MediaOperationOutput {
 					created_media,
+					updated_media,
 					logs: new_logs,
 					..
 				} = safely_build_and_insert_media(
@@ -338,6 +365,8 @@ impl JobLifecycle for SeriesScanJob {
 							),
 						)?,
 						max_concurrency,
+						// the series being scanned must never be removed by its own scan
+						adopt_moved_series: false,
 					},
 					ctx,
 					paths,
@@ -347,13 +376,14 @@ impl JobLifecycle for SeriesScanJob {
 					ctx.report_progress(JobProgress::msg("Created new media"));
 					ctx.emit_event(CoreEvent::CreatedOrUpdatedManyMedia(
 						event::CreatedOrUpdatedManyMedia {
-							count: created_media,
+							count: created_media + updated_media,
 							series_id: self.id.clone(),
 							library_id,
 						},
 					));
 				}
 				output.created_media += created_media;
+				output.updated_media += updated_media;
 				logs.extend(new_logs);
 			},
 			SeriesScanTask::VisitMedia(params) => {
@@ -373,6 +403,7 @@ impl JobLifecycle for SeriesScanJob {
 							),
 						)?,
 						max_concurrency,
+						adopt_moved_series: false,
 					},
 					ctx,
 					params,
@@ -17,8 +17,8 @@ use models::{
 use sea_orm::{
 	prelude::*,
 	sea_query::{OnConflict, Query},
-	Condition, DatabaseConnection, DatabaseTransaction, IntoActiveModel, Iterable, Set,
-	TransactionTrait,
+	ActiveValue, Condition, DatabaseConnection, DatabaseTransaction, IntoActiveModel,
+	Iterable, PaginatorTrait, Set, TransactionTrait,
 };
 use tokio::{sync::oneshot, task::spawn_blocking};
 use walkdir::DirEntry;
@@ -29,7 +29,7 @@ use crate::{
 	error::{CoreError, CoreResult},
 	event::CreatedMedia,
 	filesystem::{
-		media::{BuiltMedia, MediaBuilder},
+		media::{generate_hashes, BuiltMedia, FileProcessorOptions, MediaBuilder},
 		scanner::options::{BookVisitOperation, CustomVisitResult},
 		series::{BuiltSeries, SeriesBuilder},
 	},
@@ -331,6 +331,9 @@ pub(crate) struct MediaOperationOutput {
 	pub created_media: u64,
 	pub updated_media: u64,
 	pub logs: Vec<JobExecuteLog>,
+	/// The series which took over the books of the operation's series, which no longer
+	/// exists. See [`adopt_moved_series`]
+	pub adopted_by_series_id: Option<String>,
 }
 
 /// Handles missing media by updating the database with the latest information. A media is
@@ -435,6 +438,239 @@ pub(crate) async fn handle_restored_media(
 	output
 }
 
+/// Returns the value of an active model field, if it was set
+fn active_value<V: Into<sea_orm::Value> + Clone>(value: &ActiveValue<V>) -> Option<V> {
+	match value {
+		ActiveValue::Set(v) | ActiveValue::Unchanged(v) => Some(v.clone()),
+		ActiveValue::NotSet => None,
+	}
+}
+
+/// Finds the media item a newly discovered file was moved or renamed from, if any. Only
+/// media in the same library whose file no longer exists on disk is considered, whether or
+/// not a scan has marked it as missing yet.
+///
+/// Candidates are matched on their stored hashes first. If the file wasn't hashed during the
+/// build (i.e. hashing is disabled for the library) but a candidate of the same size was, the
+/// file is hashed on demand. Otherwise, a candidate with the same size and file name is
+/// assumed to be the same book, so long as it is the only one.
+///
+/// # Arguments
+/// * `conn` - The database connection
+/// * `library_id` - The ID of the library the file was discovered in
+/// * `book` - The media built from the newly discovered file. Any hashes generated on demand
+///   are set on it, so they are persisted with the media it is matched to
+/// * `claimed` - The IDs of media already matched to another file during this operation
+pub(crate) async fn find_moved_media(
+	conn: &DatabaseConnection,
+	library_id: &str,
+	book: &mut BuiltMedia,
+	claimed: &HashSet<String>,
+) -> CoreResult<Option<media::Model>> {
+	let Some(path) = active_value(&book.media.path) else {
+		return Ok(None);
+	};
+	let size = active_value(&book.media.size).unwrap_or_default();
+	let mut hash = active_value(&book.media.hash).flatten();
+	let mut koreader_hash = active_value(&book.media.koreader_hash).flatten();
+
+	let mut matches_file = Condition::any().add(media::Column::Size.eq(size));
+	if let Some(hash) = hash.as_ref() {
+		matches_file = matches_file.add(media::Column::Hash.eq(hash.clone()));
+	}
+	if let Some(koreader_hash) = koreader_hash.as_ref() {
+		matches_file =
+			matches_file.add(media::Column::KoreaderHash.eq(koreader_hash.clone()));
+	}
+
+	let candidates = media::Entity::find()
+		.filter(
+			media::Column::SeriesId.in_subquery(
+				Query::select()
+					.column(series::Column::Id)
+					.from(series::Entity)
+					.and_where(series::Column::LibraryId.eq(library_id.to_string()))
+					.to_owned(),
+			),
+		)
+		.filter(media::Column::Path.ne(path.clone()))
+		.filter(media::Column::DeletedAt.is_null())
+		.filter(matches_file)
+		.all(conn)
+		.await?
+		.into_iter()
+		.filter(|m| !claimed.contains(&m.id) && !Path::new(&m.path).exists())
+		.collect::<Vec<_>>();
+
+	if candidates.is_empty() {
+		return Ok(None);
+	}
+
+	let needs_hashes = hash.is_none() && koreader_hash.is_none();
+	let can_compare_hashes = candidates
+		.iter()
+		.any(|m| m.size == size && (m.hash.is_some() || m.koreader_hash.is_some()));
+	if needs_hashes && can_compare_hashes {
+		let hashes = spawn_blocking({
+			let path = path.clone();
+			move || {
+				generate_hashes(
+					path,
+					FileProcessorOptions {
+						generate_file_hashes: true,
+						generate_koreader_hashes: true,
+						..Default::default()
+					},
+				)
+			}
+		})
+		.await
+		.map_err(|e| CoreError::Unknown(e.to_string()))?
+		.map_err(|e| CoreError::InternalError(e.to_string()))?;
+		hash = hashes.hash;
+		koreader_hash = hashes.koreader_hash;
+		book.media.hash = Set(hash.clone());
+		book.media.koreader_hash = Set(koreader_hash.clone());
+	}
+
+	let hash_match = candidates.iter().find(|m| {
+		let same_hash = hash.is_some() && m.hash == hash;
+		let same_koreader_hash =
+			koreader_hash.is_some() && m.koreader_hash == koreader_hash;
+		same_hash || same_koreader_hash
+	});
+	if let Some(found) = hash_match {
+		return Ok(Some(found.clone()));
+	}
+
+	// Without a hash to go on, fall back to the size and file name. A candidate with a
+	// different hash is a different file, so it is never matched this way
+	let name = active_value(&book.media.name);
+	let extension = active_value(&book.media.extension);
+	let mut heuristic_matches = candidates.into_iter().filter(|m| {
+		let hashes_differ = (hash.is_some() && m.hash.is_some())
+			|| (koreader_hash.is_some() && m.koreader_hash.is_some());
+		m.size == size
+			&& Some(&m.name) == name.as_ref()
+			&& Some(&m.extension) == extension.as_ref()
+			&& !hashes_differ
+	});
+
+	match (heuristic_matches.next(), heuristic_matches.next()) {
+		(Some(found), None) => Ok(Some(found)),
+		_ => Ok(None),
+	}
+}
+
+/// Points an existing media item at the file it was moved or renamed to, rather than
+/// creating a new one. Only the file-related columns are updated, so everything attached to
+/// the media (reading progress, bookmarks, manual metadata edits, etc) is kept. The file's
+/// metadata is only used when the media doesn't have any yet.
+pub(crate) async fn repoint_moved_media(
+	db: &DatabaseConnection,
+	existing: media::Model,
+	BuiltMedia {
+		media,
+		metadata,
+		tags,
+	}: BuiltMedia,
+) -> CoreResult<media::Model> {
+	let txn = db.begin().await?;
+
+	let id = existing.id.clone();
+	let mut active_model = existing.into_active_model();
+	active_model.path = media.path;
+	active_model.name = media.name;
+	active_model.extension = media.extension;
+	active_model.size = media.size;
+	active_model.modified_at = media.modified_at;
+	active_model.series_id = media.series_id;
+	active_model.status = Set(FileStatus::Ready);
+	if let Some(Some(hash)) = active_value(&media.hash) {
+		active_model.hash = Set(Some(hash));
+	}
+	if let Some(Some(koreader_hash)) = active_value(&media.koreader_hash) {
+		active_model.koreader_hash = Set(Some(koreader_hash));
+	}
+	let updated_media = active_model.update(&txn).await?;
+
+	let has_metadata = media_metadata::Entity::find()
+		.filter(media_metadata::Column::MediaId.eq(id.clone()))
+		.count(&txn)
+		.await?
+		> 0;
+	if let Some(mut meta) = metadata.filter(|_| !has_metadata) {
+		meta.media_id = Set(Some(id.clone()));
+		meta.insert(&txn).await?;
+	}
+
+	ensure_tags_linked(&txn, &id, &tags).await?;
+
+	txn.commit().await?;
+
+	Ok(updated_media)
+}
+
+/// Hands a newly created series over to the series its books were moved from, when the
+/// folder of that series was moved (or renamed) as a whole. The older series takes on the
+/// new path and name, keeping its ID and everything attached to it, and the newly created
+/// series is removed.
+///
+/// Returns whether the series was adopted, which only happens if the previous folder no
+/// longer exists and no books were left behind in it.
+pub(crate) async fn adopt_moved_series(
+	db: &DatabaseConnection,
+	previous_series_id: &str,
+	new_series_id: &str,
+) -> CoreResult<bool> {
+	let Some(previous_series) =
+		series::Entity::find_by_id(previous_series_id.to_string())
+			.one(db)
+			.await?
+	else {
+		return Ok(false);
+	};
+	let Some(new_series) = series::Entity::find_by_id(new_series_id.to_string())
+		.one(db)
+		.await?
+	else {
+		return Ok(false);
+	};
+
+	let left_behind = media::Entity::find()
+		.filter(media::Column::SeriesId.eq(previous_series_id.to_string()))
+		.count(db)
+		.await?;
+	if left_behind > 0 || Path::new(&previous_series.path).exists() {
+		return Ok(false);
+	}
+
+	let txn = db.begin().await?;
+
+	media::Entity::update_many()
+		.filter(media::Column::SeriesId.eq(new_series_id.to_string()))
+		.col_expr(
+			media::Column::SeriesId,
+			Expr::value(previous_series_id.to_string()),
+		)
+		.exec(&txn)
+		.await?;
+	// The new series must be gone before its path can be taken over
+	series::Entity::delete_by_id(new_series_id.to_string())
+		.exec(&txn)
+		.await?;
+
+	let mut active_model = previous_series.into_active_model();
+	active_model.path = Set(new_series.path);
+	active_model.name = Set(new_series.name);
+	active_model.status = Set(FileStatus::Ready);
+	active_model.update(&txn).await?;
+
+	txn.commit().await?;
+
+	Ok(true)
+}
+
 /// Builds a series from the given path
 ///
 /// # Arguments
@@ -583,6 +819,10 @@ pub(crate) struct MediaBuildOperation {
 	pub series_id: String,
 	pub library_config: library_config::Model,
 	pub max_concurrency: usize,
+	/// Whether the series may be handed over to the series its books were moved from. The
+	/// series is removed when it is, so this is only allowed for series created by the
+	/// current scan
+	pub adopt_moved_series: bool,
 }
 
 /// Builds a media from the given path
@@ -715,6 +955,7 @@ pub(crate) async fn safely_build_and_insert_media(
 		series_id,
 		library_config,
 		max_concurrency,
+		adopt_moved_series: may_adopt_moved_series,
 	}: MediaBuildOperation,
 	worker_ctx: &JobContext,
 	paths: Vec<PathBuf>,
@@ -820,11 +1061,69 @@ pub(crate) async fn safely_build_and_insert_media(
 
 	let atomic_cursor = Arc::new(AtomicUsize::new(1));
 
-	while let Some(book) = books.pop_front() {
+	// A series without any books was just created, so it may be a moved series folder
+	let series_was_empty = media::Entity::find()
+		.filter(media::Column::SeriesId.eq(series_id.clone()))
+		.count(worker_ctx.conn())
+		.await?
+		== 0;
+	let mut moved_media_ids = HashSet::new();
+	let mut moved_from_series = HashSet::new();
+
+	while let Some(mut book) = books.pop_front() {
 		let Some(path) = book.path() else {
 			tracing::warn!(?book, "Book has no path?");
 			continue;
 		};
+
+		let moved_from = match find_moved_media(
+			worker_ctx.conn(),
+			&library_id,
+			&mut book,
+			&moved_media_ids,
+		)
+		.await
+		{
+			Ok(moved_from) => moved_from,
+			Err(error) => {
+				tracing::warn!(?error, ?path, "Failed to look for a moved media match");
+				None
+			},
+		};
+
+		if let Some(existing) = moved_from {
+			let previous_path = existing.path.clone();
+			let previous_series_id = existing.series_id.clone();
+			moved_media_ids.insert(existing.id.clone());
+			match repoint_moved_media(worker_ctx.conn(), existing, book).await {
+				Ok(moved_media) => {
+					tracing::debug!(
+						id = %moved_media.id,
+						?previous_path,
+						?path,
+						"Reconciled moved media"
+					);
+					output.updated_media += 1;
+					moved_from_series.extend(previous_series_id);
+				},
+				Err(e) => {
+					tracing::error!(error = ?e, ?path, "Failed to reconcile moved media");
+					output.logs.push(
+						JobExecuteLog::error(format!(
+							"Failed to reconcile moved media: {:?}",
+							e.to_string()
+						))
+						.with_ctx(path),
+					);
+				},
+			}
+			worker_ctx.report_progress(JobProgress::subtask_position(
+				atomic_cursor.fetch_add(1, Ordering::SeqCst) as i32,
+				task_count,
+			));
+			continue;
+		}
+
 		match create_media(worker_ctx.conn(), book).await {
 			Ok(created_media) => {
 				// TODO(metadata-fetching): Track this as needing fetching (assuming enabled)
@@ -856,10 +1155,44 @@ pub(crate) async fn safely_build_and_insert_media(
 		}
 	}
 
-	let success_count = output.created_media;
+	let success_count = output.created_media + output.updated_media;
 	let error_count = output.logs.len() - error_count; // Subtract the errors from the previous step
 	tracing::debug!(success_count, error_count, elapsed = ?start.elapsed(), "Inserted books into database");
 
+	// If every moved book came from the same series, and this series was only just created
+	// for them, the whole series folder was likely moved
+	moved_from_series.remove(&series_id);
+	let previous_series_id =
+		(may_adopt_moved_series && series_was_empty && moved_from_series.len() == 1)
+			.then(|| moved_from_series.into_iter().next())
+			.flatten();
+	if let Some(previous_series_id) = previous_series_id {
+		match adopt_moved_series(worker_ctx.conn(), &previous_series_id, &series_id).await
+		{
+			Ok(adopted) => {
+				tracing::debug!(
+					adopted,
+					%previous_series_id,
+					%series_id,
+					"Handled moved series"
+				);
+				if adopted {
+					output.adopted_by_series_id = Some(previous_series_id);
+				}
+			},
+			Err(e) => {
+				tracing::error!(error = ?e, %previous_series_id, "Failed to adopt moved series");
+				output.logs.push(
+					JobExecuteLog::error(format!(
+						"Failed to reconcile moved series: {:?}",
+						e.to_string()
+					))
+					.with_ctx(format!("Series ID: {previous_series_id}")),
+				);
+			},
+		}
+	}
+
 	Ok(output)
 }
 
@@ -875,6 +1208,7 @@ pub(crate) async fn visit_and_update_media(
 		series_id,
 		library_config,
 		max_concurrency,
+		..
 	}: MediaBuildOperation,
 	worker_ctx: &JobContext,
 	params: Vec<(PathBuf, BookVisitOperation)>,
@@ -1026,6 +1360,7 @@ pub(crate) async fn visit_and_update_media(
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::filesystem::media::tests::get_test_epub_path;
 	use ::tests::db::test_database;
 	use ::tests::fake_data;
 	use sea_orm::{ActiveModelTrait, EntityTrait, QueryOrder};
@@ -1210,4 +1545,255 @@ mod tests {
 		assert_eq!(count_media_tag_rows(&db, "book-a").await, 1);
 		assert_eq!(count_media_tag_rows(&db, "book-b").await, 1);
 	}
+
+	/// A media whose file is no longer at its stored path
+	fn moved_media(id: &str, series_id: &str, hash: Option<&str>) -> media::ActiveModel {
+		let mut media = built_media(id, series_id, vec![]).media;
+		media.name = Set("book".to_string());
+		media.hash = Set(hash.map(String::from));
+		media.path = Set(format!("/stump-missing/{id}/book.cbz"));
+		media.status = Set(FileStatus::Ready);
+		media
+	}
+
+	/// A media built from a newly discovered file
+	fn discovered_media(
+		series_id: &str,
+		name: &str,
+		hash: Option<&str>,
+		metadata_title: Option<&str>,
+	) -> BuiltMedia {
+		let id = uuid::Uuid::new_v4().to_string();
+		let mut book = built_media(&id, series_id, vec![]);
+		book.media.name = Set(name.to_string());
+		book.media.hash = Set(hash.map(String::from));
+		book.media.path = Set(format!("/stump-moved/{series_id}/{name}.cbz"));
+		book.media.status = Set(FileStatus::Ready);
+		book.metadata = metadata_title.map(|title| media_metadata::ActiveModel {
+			title: Set(Some(title.to_string())),
+			..Default::default()
+		});
+		book
+	}
+
+	#[tokio::test]
+	async fn test_find_moved_media_by_hash() {
+		let db = test_database().await;
+		let library = fake_data::Library::default().insert(&db).await;
+		let old_series = fake_data::Series::in_library(&library.id).insert(&db).await;
+		let new_series = fake_data::Series::in_library(&library.id).insert(&db).await;
+
+		let existing = moved_media("moved-1", &old_series.id, Some("abc"))
+			.insert(&db)
+			.await
+			.expect("media insert failed");
+		moved_media("other", &old_series.id, Some("def"))
+			.insert(&db)
+			.await
+			.expect("media insert failed");
+
+		// A rename, so only the hash can tie the two together
+		let mut book = discovered_media(&new_series.id, "renamed", Some("abc"), None);
+		let found = find_moved_media(&db, &library.id, &mut book, &HashSet::new())
+			.await
+			.expect("find_moved_media failed");
+		assert_eq!(found.map(|m| m.id), Some(existing.id.clone()));
+
+		// Media already claimed by another file isn't matched twice
+		let claimed = HashSet::from([existing.id]);
+		let found = find_moved_media(&db, &library.id, &mut book, &claimed)
+			.await
+			.expect("find_moved_media failed");
+		assert!(found.is_none());
+	}
+
+	#[tokio::test]
+	async fn test_find_moved_media_ignores_files_still_on_disk() {
+		let db = test_database().await;
+		let library = fake_data::Library::default().insert(&db).await;
+		let series = fake_data::Series::in_library(&library.id).insert(&db).await;
+
+		let file = tempfile::NamedTempFile::new().expect("failed to create temp file");
+		let mut copied = moved_media("copied", &series.id, Some("abc"));
+		copied.path = Set(file.path().to_string_lossy().to_string());
+		copied.insert(&db).await.expect("media insert failed");
+
+		let mut book = discovered_media(&series.id, "copy", Some("abc"), None);
+		let found = find_moved_media(&db, &library.id, &mut book, &HashSet::new())
+			.await
+			.expect("find_moved_media failed");
+		assert!(found.is_none(), "a copy must not steal the original's row");
+	}
+
+	#[tokio::test]
+	async fn test_find_moved_media_by_name_and_size() {
+		let db = test_database().await;
+		let library = fake_data::Library::default().insert(&db).await;
+		let old_series = fake_data::Series::in_library(&library.id).insert(&db).await;
+		let new_series = fake_data::Series::in_library(&library.id).insert(&db).await;
+
+		let existing = moved_media("unhashed-1", &old_series.id, None)
+			.insert(&db)
+			.await
+			.expect("media insert failed");
+
+		let mut book = discovered_media(&new_series.id, "book", None, None);
+		let found = find_moved_media(&db, &library.id, &mut book, &HashSet::new())
+			.await
+			.expect("find_moved_media failed");
+		assert_eq!(found.map(|m| m.id), Some(existing.id));
+
+		// Two equally good candidates are ambiguous, so neither is matched
+		moved_media("unhashed-2", &old_series.id, None)
+			.insert(&db)
+			.await
+			.expect("media insert failed");
+		let found = find_moved_media(&db, &library.id, &mut book, &HashSet::new())
+			.await
+			.expect("find_moved_media failed");
+		assert!(found.is_none());
+	}
+
+	#[tokio::test]
+	async fn test_moved_media_keeps_hash_generated_on_demand() {
+		let db = test_database().await;
+		let library = fake_data::Library::default().insert(&db).await;
+		let old_series = fake_data::Series::in_library(&library.id).insert(&db).await;
+		let new_series = fake_data::Series::in_library(&library.id).insert(&db).await;
+
+		let path = get_test_epub_path();
+		let size = std::fs::metadata(&path).expect("missing test file").len() as i64;
+		let hashes = generate_hashes(
+			&path,
+			FileProcessorOptions {
+				generate_file_hashes: true,
+				generate_koreader_hashes: true,
+				..Default::default()
+			},
+		)
+		.expect("failed to hash test file");
+
+		// Only the KOReader hash was stored for the book before it was moved
+		let mut existing = moved_media("koreader-hashed", &old_series.id, None);
+		existing.size = Set(size);
+		existing.koreader_hash = Set(hashes.koreader_hash.clone());
+		let existing = existing.insert(&db).await.expect("media insert failed");
+
+		// Hashing is disabled for the library, so the discovered file has no hashes
+		let mut book = discovered_media(&new_series.id, "renamed", None, None);
+		book.media.path = Set(path);
+		book.media.size = Set(size);
+		book.media.extension = Set("epub".to_string());
+		let found = find_moved_media(&db, &library.id, &mut book, &HashSet::new())
+			.await
+			.expect("find_moved_media failed")
+			.expect("moved media was not found");
+		assert_eq!(found.id, existing.id);
+
+		let updated = repoint_moved_media(&db, found, book)
+			.await
+			.expect("repoint_moved_media failed");
+		assert!(updated.hash.is_some());
+		assert_eq!(updated.hash, hashes.hash);
+	}
+
+	#[tokio::test]
+	async fn test_repoint_moved_media_keeps_id_and_metadata() {
+		let db = test_database().await;
+		let library = fake_data::Library::default().insert(&db).await;
+		let old_series = fake_data::Series::in_library(&library.id).insert(&db).await;
+		let new_series = fake_data::Series::in_library(&library.id).insert(&db).await;
+
+		let existing = moved_media("repointed", &old_series.id, Some("abc"))
+			.insert(&db)
+			.await
+			.expect("media insert failed");
+		media_metadata::ActiveModel {
+			media_id: Set(Some(existing.id.clone())),
+			title: Set(Some("Edited title".to_string())),
+			..Default::default()
+		}
+		.insert(&db)
+		.await
+		.expect("metadata insert failed");
+
+		let book = discovered_media(
+			&new_series.id,
+			"renamed",
+			Some("abc"),
+			Some("Title from file"),
+		);
+		let new_path = format!("/stump-moved/{}/renamed.cbz", new_series.id);
+		let updated = repoint_moved_media(&db, existing.clone(), book)
+			.await
+			.expect("repoint_moved_media failed");
+
+		assert_eq!(updated.id, existing.id);
+		assert_eq!(updated.path, new_path);
+		assert_eq!(updated.name, "renamed");
+		assert_eq!(updated.series_id, Some(new_series.id));
+
+		let metadata = media_metadata::Entity::find()
+			.filter(media_metadata::Column::MediaId.eq(existing.id))
+			.all(&db)
+			.await
+			.expect("metadata query failed");
+		assert_eq!(metadata.len(), 1);
+		assert_eq!(metadata[0].title.as_deref(), Some("Edited title"));
+	}
+
+	#[tokio::test]
+	async fn test_adopt_moved_series() {
+		let db = test_database().await;
+		let library = fake_data::Library::default().insert(&db).await;
+		let old_series = fake_data::Series::in_library(&library.id).insert(&db).await;
+		let new_series = fake_data::Series::in_library(&library.id).insert(&db).await;
+
+		let existing = moved_media("adopted", &old_series.id, Some("abc"))
+			.insert(&db)
+			.await
+			.expect("media insert failed");
+		let book = discovered_media(&new_series.id, "book", Some("abc"), None);
+		repoint_moved_media(&db, existing.clone(), book)
+			.await
+			.expect("repoint_moved_media failed");
+
+		let adopted = adopt_moved_series(&db, &old_series.id, &new_series.id)
+			.await
+			.expect("adopt_moved_series failed");
+		assert!(adopted);
+
+		let series = series::Entity::find()
+			.all(&db)
+			.await
+			.expect("series query failed");
+		assert_eq!(series.len(), 1);
+		assert_eq!(series[0].id, old_series.id);
+		assert_eq!(series[0].path, new_series.path);
+
+		let media = media::Entity::find_by_id(existing.id)
+			.one(&db)
+			.await
+			.expect("media query failed")
+			.expect("media is missing");
+		assert_eq!(media.series_id, Some(old_series.id));
+	}
+
+	#[tokio::test]
+	async fn test_adopt_moved_series_with_books_left_behind() {
+		let db = test_database().await;
+		let library = fake_data::Library::default().insert(&db).await;
+		let old_series = fake_data::Series::in_library(&library.id).insert(&db).await;
+		let new_series = fake_data::Series::in_library(&library.id).insert(&db).await;
+
+		moved_media("left-behind", &old_series.id, Some("abc"))
+			.insert(&db)
+			.await
+			.expect("media insert failed");
+
+		let adopted = adopt_moved_series(&db, &old_series.id, &new_series.id)
+			.await
+			.expect("adopt_moved_series failed");
+		assert!(!adopted);
+	}
 }
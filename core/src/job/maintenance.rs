@@ -0,0 +1,244 @@
+use async_graphql::SimpleObject;
+use chrono::{Duration, Utc};
+use models::entity::{
+	job, log,
+	scheduled_job::{DatabaseBackupConfig, HistoryPruningConfig},
+};
+use models::shared::enums::JobStatus;
+use sea_orm::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{config::StumpConfig, database, CoreResult};
+
+use super::{
+	error::JobError, JobContext, JobLifecycle, JobOutputExt, JobProgress, JobRetryPolicy,
+	JobTaskOutput, WorkingState,
+};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum DatabaseBackupTask {
+	Backup,
+}
+
+#[derive(Clone, Serialize, Deserialize, Default, Debug, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseBackupOutput {
+	/// The path of the backup which was created
+	pub path: Option<String>,
+}
+
+impl JobOutputExt for DatabaseBackupOutput {}
+
+/// A job that backs up the database into the backups directory, keeping only the most
+/// recent backups
+#[derive(Clone)]
+pub struct DatabaseBackupJob {
+	pub config: DatabaseBackupConfig,
+}
+
+#[async_trait::async_trait]
+impl JobLifecycle for DatabaseBackupJob {
+	const NAME: &'static str = "database_backup";
+
+	type Output = DatabaseBackupOutput;
+	type Task = DatabaseBackupTask;
+
+	fn description(&self) -> Option<String> {
+		Some("Back up the database".to_string())
+	}
+
+	/// The backup is the only task of the job, so the job fails along with it. Otherwise the
+	/// job would complete without a backup
+	fn retry_policy(&self, config: &StumpConfig) -> JobRetryPolicy {
+		JobRetryPolicy::from_config(config).with_fail_fast(true)
+	}
+
+	async fn init(
+		&mut self,
+		_ctx: &JobContext,
+	) -> Result<WorkingState<Self::Output, Self::Task>, JobError> {
+		Ok(WorkingState {
+			output: Some(Self::Output::default()),
+			tasks: vec![DatabaseBackupTask::Backup].into(),
+			logs: vec![],
+		})
+	}
+
+	async fn execute_task(
+		&self,
+		ctx: &JobContext,
+		task: Self::Task,
+	) -> Result<JobTaskOutput<Self>, JobError> {
+		let DatabaseBackupTask::Backup = task;
+		ctx.report_progress(JobProgress::msg("Backing up the database"));
+
+		let path = database::backup(
+			ctx.conn(),
+			&ctx.config().get_backups_dir(),
+			self.config.retain_count as usize,
+		)
+		.await?;
+
+		Ok(JobTaskOutput {
+			output: DatabaseBackupOutput {
+				path: Some(path.to_string_lossy().to_string()),
+			},
+			subtasks: vec![],
+			logs: vec![],
+		})
+	}
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum HistoryPruningTask {
+	Prune,
+}
+
+#[derive(Clone, Serialize, Deserialize, Default, Debug, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryPruningOutput {
+	/// The number of logs which were deleted
+	pub deleted_logs: u64,
+	/// The number of finished jobs which were deleted
+	pub deleted_jobs: u64,
+}
+
+impl JobOutputExt for HistoryPruningOutput {
+	fn update(&mut self, updated: Self) {
+		self.deleted_logs += updated.deleted_logs;
+		self.deleted_jobs += updated.deleted_jobs;
+	}
+}
+
+/// A job that deletes logs and finished jobs which are older than the configured retention
+#[derive(Clone)]
+pub struct HistoryPruningJob {
+	pub config: HistoryPruningConfig,
+}
+
+#[async_trait::async_trait]
+impl JobLifecycle for HistoryPruningJob {
+	const NAME: &'static str = "history_pruning";
+
+	type Output = HistoryPruningOutput;
+	type Task = HistoryPruningTask;
+
+	fn description(&self) -> Option<String> {
+		Some("Prune logs and job history".to_string())
+	}
+
+	async fn init(
+		&mut self,
+		_ctx: &JobContext,
+	) -> Result<WorkingState<Self::Output, Self::Task>, JobError> {
+		Ok(WorkingState {
+			output: Some(Self::Output::default()),
+			tasks: vec![HistoryPruningTask::Prune].into(),
+			logs: vec![],
+		})
+	}
+
+	async fn execute_task(
+		&self,
+		ctx: &JobContext,
+		task: Self::Task,
+	) -> Result<JobTaskOutput<Self>, JobError> {
+		let HistoryPruningTask::Prune = task;
+		ctx.report_progress(JobProgress::msg("Pruning logs and job history"));
+
+		let (deleted_logs, deleted_jobs) =
+			prune_history(ctx.conn(), &self.config).await?;
+
+		Ok(JobTaskOutput {
+			output: HistoryPruningOutput {
+				deleted_logs,
+				deleted_jobs,
+			},
+			subtasks: vec![],
+			logs: vec![],
+		})
+	}
+}
+
+/// Delete logs and finished jobs older than the configured retention, returning the number
+/// of each which were deleted
+async fn prune_history(
+	conn: &DatabaseConnection,
+	config: &HistoryPruningConfig,
+) -> CoreResult<(u64, u64)> {
+	let cutoff = |days: u32| -> DateTimeWithTimeZone {
+		(Utc::now() - Duration::days(i64::from(days))).into()
+	};
+
+	let deleted_logs = match config.log_retention_days {
+		Some(days) => {
+			log::Entity::delete_many()
+				.filter(log::Column::Timestamp.lt(cutoff(days)))
+				.exec(conn)
+				.await?
+				.rows_affected
+		},
+		None => 0,
+	};
+
+	let deleted_jobs = match config.job_retention_days {
+		Some(days) => {
+			job::Entity::delete_many()
+				.filter(job::Column::Status.is_not_in([
+					JobStatus::Running,
+					JobStatus::Paused,
+					JobStatus::Queued,
+				]))
+				.filter(job::Column::CreatedAt.lt(cutoff(days)))
+				.exec(conn)
+				.await?
+				.rows_affected
+		},
+		None => 0,
+	};
+
+	Ok((deleted_logs, deleted_jobs))
+}
+
+#[cfg(test)]
+mod tests {
+	use ::tests::db::test_database;
+	use sea_orm::Set;
+
+	use super::*;
+
+	#[tokio::test]
+	async fn test_prune_history() {
+		let conn = test_database().await;
+
+		let old: DateTimeWithTimeZone = (Utc::now() - Duration::days(10)).into();
+		for (id, status, created_at) in [
+			("old", JobStatus::Completed, old),
+			("old-queued", JobStatus::Queued, old),
+			("recent", JobStatus::Completed, Utc::now().into()),
+		] {
+			job::Entity::insert(job::ActiveModel {
+				id: Set(id.to_string()),
+				name: Set("test".to_string()),
+				status: Set(status),
+				ms_elapsed: Set(0),
+				created_at: Set(created_at),
+				..Default::default()
+			})
+			.exec_without_returning(&conn)
+			.await
+			.unwrap();
+		}
+
+		let config = HistoryPruningConfig {
+			log_retention_days: None,
+			job_retention_days: Some(7),
+		};
+		let (deleted_logs, deleted_jobs) = prune_history(&conn, &config).await.unwrap();
+		assert_eq!(deleted_logs, 0);
+		assert_eq!(deleted_jobs, 1);
+
+		let remaining = job::Entity::find().count(&conn).await.unwrap();
+		assert_eq!(remaining, 2);
+	}
+}
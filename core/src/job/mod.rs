@@ -15,23 +15,33 @@ use models::shared::enums::LogLevel;
 use serde::{de, Deserialize, Serialize};
 
 pub mod error;
+mod maintenance;
 mod output;
 mod progress;
+mod retry;
 mod run;
 mod scheduler;
+mod storage;
 
 pub mod state;
 pub mod stump_job;
 
 use chrono::{DateTime, Utc};
 use error::JobError;
+
+use crate::config::StumpConfig;
+pub use maintenance::{
+	DatabaseBackupJob, DatabaseBackupOutput, HistoryPruningJob, HistoryPruningOutput,
+};
 pub use models::shared::enums::JobStatus;
 pub use output::*;
 pub use progress::*;
+pub use retry::JobRetryPolicy;
 pub use run::dispatch_job;
 pub use scheduler::JobScheduler;
 
 pub use state::{ApalisWorkerState, JobContext};
+pub use storage::{ClaimedJob, JobConcurrencyLimits, JobStorage, QueueSignal};
 
 /// A log that will be persisted from a job's execution
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -128,12 +138,25 @@ pub trait JobLifecycle: Send + Sync + Sized + 'static {
 	/// The description of the job, if any
 	fn description(&self) -> Option<String>;
 
+	/// How failed tasks of the job are retried, and whether the job fails along with them.
+	/// This defaults to the policy configured for the server
+	fn retry_policy(&self, config: &StumpConfig) -> JobRetryPolicy {
+		JobRetryPolicy::from_config(config)
+	}
+
 	/// Initialize the job and gather the required tasks
 	async fn init(
 		&mut self,
 		ctx: &JobContext,
 	) -> Result<WorkingState<Self::Output, Self::Task>, JobError>;
 
+	/// Restore any state which [`JobLifecycle::init`] sets up outside of the
+	/// [`WorkingState`], for a job resuming from its saved state after an interruption.
+	/// `init` is not called for a resumed job
+	async fn resume(&mut self, _ctx: &JobContext) -> Result<(), JobError> {
+		Ok(())
+	}
+
 	/// Execute a single task. Called repeatedly until all tasks are completed
 	async fn execute_task(
 		&self,
@@ -3,22 +3,39 @@ use std::fmt::Debug;
 use async_graphql::Union;
 use serde::{de, Deserialize, Serialize};
 
-use crate::filesystem::{
-	image::{PlaceholderGenerationOutput, ThumbnailGenerationOutput},
-	media::analysis::AnalyzeMediaOutput,
-	metadata::MetadataFetchJobOutput,
-	scanner::{LibraryScanOutput, SeriesScanOutput},
+use crate::{
+	filesystem::{
+		image::{PlaceholderGenerationOutput, ThumbnailGenerationOutput},
+		media::{
+			analysis::AnalyzeMediaOutput, convert::ConvertMediaOutput,
+			duplicates::DetectDuplicatesOutput, write_back::WriteBackMetadataOutput,
+		},
+		metadata::MetadataFetchJobOutput,
+		scanner::{LibraryCleanupOutput, LibraryScanOutput, SeriesScanOutput},
+	},
+	opds::OpdsCacheWarmupOutput,
 };
 
+use super::{DatabaseBackupOutput, HistoryPruningOutput};
+
+/// The output of any job. It is stored with the job, tagged with its variant, since several
+/// outputs default all of their fields and would otherwise be indistinguishable
 #[derive(Debug, Clone, Serialize, Deserialize, Union)]
-#[serde(untagged, rename_all = "camelCase")]
+#[serde(tag = "type", rename_all = "camelCase")]
 pub enum CoreJobOutput {
 	LibraryScan(LibraryScanOutput),
 	SeriesScan(SeriesScanOutput),
 	ThumbnailGeneration(ThumbnailGenerationOutput),
 	PlaceholderGeneration(PlaceholderGenerationOutput),
+	WriteBackMetadata(WriteBackMetadataOutput),
+	ConvertMedia(ConvertMediaOutput),
+	DetectDuplicates(DetectDuplicatesOutput),
 	MetadataFetch(MetadataFetchJobOutput),
 	AnalyzeMedia(AnalyzeMediaOutput),
+	LibraryCleanup(LibraryCleanupOutput),
+	DatabaseBackup(DatabaseBackupOutput),
+	HistoryPruning(HistoryPruningOutput),
+	OpdsCacheWarmup(OpdsCacheWarmupOutput),
 }
 
 /// A trait to extend the output type for a job with a common interface. Job output starts
@@ -42,3 +59,37 @@ pub trait JobOutputExt: Serialize + de::DeserializeOwned + Debug {
 		)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_output_round_trip() {
+		let outputs = [
+			CoreJobOutput::LibraryScan(LibraryScanOutput::default()),
+			CoreJobOutput::SeriesScan(SeriesScanOutput::default()),
+			CoreJobOutput::ThumbnailGeneration(ThumbnailGenerationOutput::default()),
+			CoreJobOutput::PlaceholderGeneration(PlaceholderGenerationOutput::default()),
+			CoreJobOutput::WriteBackMetadata(WriteBackMetadataOutput::default()),
+			CoreJobOutput::ConvertMedia(ConvertMediaOutput::default()),
+			CoreJobOutput::DetectDuplicates(DetectDuplicatesOutput::default()),
+			CoreJobOutput::MetadataFetch(MetadataFetchJobOutput::default()),
+			CoreJobOutput::AnalyzeMedia(AnalyzeMediaOutput::default()),
+			CoreJobOutput::LibraryCleanup(LibraryCleanupOutput::default()),
+			CoreJobOutput::DatabaseBackup(DatabaseBackupOutput::default()),
+			CoreJobOutput::HistoryPruning(HistoryPruningOutput::default()),
+			CoreJobOutput::OpdsCacheWarmup(OpdsCacheWarmupOutput::default()),
+		];
+
+		for output in outputs {
+			let json = serde_json::to_vec(&output).unwrap();
+			let parsed: CoreJobOutput = serde_json::from_slice(&json).unwrap();
+			assert_eq!(
+				std::mem::discriminant(&output),
+				std::mem::discriminant(&parsed),
+				"{output:?} was parsed as {parsed:?}"
+			);
+		}
+	}
+}
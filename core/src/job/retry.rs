@@ -0,0 +1,114 @@
+use std::time::Duration;
+
+use crate::config::{
+	defaults::{DEFAULT_TASK_MAX_ATTEMPTS, DEFAULT_TASK_RETRY_BACKOFF_SECS},
+	StumpConfig,
+};
+
+/// The longest to wait between retries of a task by default
+pub const DEFAULT_TASK_MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// How a job handles the failure of one of its tasks. A failed task is retried with an
+/// exponential backoff until it runs out of attempts, at which point it is recorded on the
+/// job so it can be retried later.
+///
+/// The attempts and backoff default to the [`StumpConfig`] of the server. See [`JobLifecycle::retry_policy`](super::JobLifecycle::retry_policy)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JobRetryPolicy {
+	/// The number of times a task is attempted, including the first attempt. A value of 1
+	/// means failed tasks aren't retried
+	pub max_attempts: u32,
+	/// How long to wait before the first retry. Each retry after it waits twice as long as
+	/// the one before
+	pub backoff: Duration,
+	/// The longest to wait between retries
+	pub max_backoff: Duration,
+	/// Whether the whole job fails once a task runs out of attempts. Otherwise, the job
+	/// carries on with its remaining tasks
+	pub fail_fast: bool,
+}
+
+impl JobRetryPolicy {
+	/// The policy configured by `task_max_attempts` and `task_retry_backoff_secs`
+	pub fn from_config(config: &StumpConfig) -> Self {
+		Self::default()
+			.with_max_attempts(config.task_max_attempts)
+			.with_backoff(Duration::from_secs(config.task_retry_backoff_secs))
+	}
+
+	pub fn with_max_attempts(self, max_attempts: u32) -> Self {
+		Self {
+			max_attempts: max_attempts.max(1),
+			..self
+		}
+	}
+
+	pub fn with_backoff(self, backoff: Duration) -> Self {
+		Self { backoff, ..self }
+	}
+
+	pub fn with_fail_fast(self, fail_fast: bool) -> Self {
+		Self { fail_fast, ..self }
+	}
+
+	/// How long to wait before retrying a task which has failed `attempt` times
+	pub fn backoff_for(&self, attempt: u32) -> Duration {
+		let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+		self.backoff
+			.checked_mul(factor)
+			.unwrap_or(self.max_backoff)
+			.min(self.max_backoff)
+	}
+}
+
+impl Default for JobRetryPolicy {
+	fn default() -> Self {
+		Self {
+			max_attempts: DEFAULT_TASK_MAX_ATTEMPTS,
+			backoff: Duration::from_secs(DEFAULT_TASK_RETRY_BACKOFF_SECS),
+			max_backoff: DEFAULT_TASK_MAX_BACKOFF,
+			fail_fast: false,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_backoff_for() {
+		let policy = JobRetryPolicy::default().with_backoff(Duration::from_secs(2));
+		assert_eq!(policy.backoff_for(1), Duration::from_secs(2));
+		assert_eq!(policy.backoff_for(2), Duration::from_secs(4));
+		assert_eq!(policy.backoff_for(3), Duration::from_secs(8));
+		assert_eq!(policy.backoff_for(100), DEFAULT_TASK_MAX_BACKOFF);
+	}
+
+	#[test]
+	fn test_from_config() {
+		let config = StumpConfig {
+			task_max_attempts: 5,
+			task_retry_backoff_secs: 10,
+			..StumpConfig::debug()
+		};
+		let policy = JobRetryPolicy::from_config(&config);
+		assert_eq!(policy.max_attempts, 5);
+		assert_eq!(policy.backoff, Duration::from_secs(10));
+		assert!(!policy.fail_fast);
+
+		let config = StumpConfig {
+			task_max_attempts: 0,
+			..config
+		};
+		assert_eq!(JobRetryPolicy::from_config(&config).max_attempts, 1);
+	}
+
+	#[test]
+	fn test_max_attempts_at_least_one() {
+		assert_eq!(
+			JobRetryPolicy::default().with_max_attempts(0).max_attempts,
+			1
+		);
+	}
+}
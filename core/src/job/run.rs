@@ -1,26 +1,88 @@
-use std::sync::Arc;
+use std::{
+	sync::Arc,
+	time::{Duration, Instant},
+};
 
 use apalis::prelude::Data;
+use integrations::NotificationEvent;
 use models::shared::enums::JobStatus;
-use uuid::Uuid;
 
 use crate::{
 	event::JobStarted,
 	filesystem::{
 		image::{PlaceholderGenerationJob, ThumbnailGenerationJob},
-		media::analysis::AnalyzeMediaJob,
+		media::{
+			analysis::AnalyzeMediaJob, convert::ConvertMediaJob,
+			duplicates::DetectDuplicatesJob, write_back::WriteBackMetadataJob,
+		},
 		metadata::MetadataFetchJob,
-		scanner::{LibraryScanJob, SeriesScanJob},
+		scanner::{LibraryCleanupJob, LibraryScanJob, SeriesScanJob},
 	},
 	job::{
-		error::JobError, stump_job::StumpJob, ApalisWorkerState, CoreJobOutput,
-		JobContext, JobLifecycle, JobOutputExt, JobProgress, WorkingState,
+		error::JobError, stump_job::StumpJob, ApalisWorkerState, ClaimedJob,
+		CoreJobOutput, DatabaseBackupJob, HistoryPruningJob, JobContext, JobExecuteLog,
+		JobLifecycle, JobOutputExt, JobProgress, JobRetryPolicy, JobTaskOutput,
+		QueueSignal, WorkingState,
 	},
+	opds::OpdsCacheWarmupJob,
 	CoreEvent,
 };
 
-/// Run a job through its full lifecycle
-async fn run_job<J>(ctx: &JobContext, job: &mut J) -> Result<(), JobError>
+/// How often the working state of a job is persisted while its tasks execute
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Execute a task, retrying it with a backoff as allowed by the [`JobRetryPolicy`] of the
+/// job. Tasks aren't required to be `Clone`, so each retry runs a copy of the task which
+/// is deserialized from its payload
+async fn execute_task_with_retry<J: JobLifecycle>(
+	ctx: &JobContext,
+	job: &J,
+	task: J::Task,
+	payload: &serde_json::Value,
+	policy: &JobRetryPolicy,
+) -> Result<JobTaskOutput<J>, JobError> {
+	let mut attempt = 1;
+	let mut result = job.execute_task(ctx, task).await;
+
+	while let Err(error) = &result {
+		if attempt >= policy.max_attempts || ctx.is_canceled() {
+			break;
+		}
+
+		let backoff = policy.backoff_for(attempt);
+		tracing::warn!(
+			?error,
+			job = J::NAME,
+			attempt,
+			?backoff,
+			"Task failed, retrying"
+		);
+		tokio::select! {
+			_ = ctx.cancel_token.cancelled() => break,
+			_ = tokio::time::sleep(backoff) => {},
+		}
+
+		let task = serde_json::from_value(payload.clone())
+			.map_err(|e| JobError::StateLoadFailed(e.to_string()))?;
+		attempt += 1;
+		result = job.execute_task(ctx, task).await;
+	}
+
+	result
+}
+
+/// Run a job through its full lifecycle. If the job has a saved state from a previous,
+/// interrupted run it resumes from its remaining tasks instead of being initialized again.
+///
+/// Tasks which fail are retried according to the [`JobRetryPolicy`] of the job. A task
+/// which runs out of attempts is recorded on the job, along with any recorded by earlier
+/// runs in `failed_tasks`, so it can be retried later
+async fn run_job<J>(
+	ctx: &JobContext,
+	job: &mut J,
+	save_state: Option<Vec<u8>>,
+	mut failed_tasks: Vec<serde_json::Value>,
+) -> Result<(), JobError>
 where
 	J: JobLifecycle,
 	J::Output: Clone + Into<CoreJobOutput>,
@@ -33,7 +95,24 @@ where
 		"Initializing job",
 	));
 
-	let working_state = match job.init(ctx).await {
+	let saved_state = save_state.and_then(|bytes| {
+		serde_json::from_slice::<WorkingState<J::Output, J::Task>>(&bytes)
+			.inspect_err(|error| {
+				tracing::warn!(?error, job = J::NAME, "Failed to load saved state");
+			})
+			.ok()
+	});
+
+	let is_resumed = saved_state.is_some();
+	let working_state = match saved_state {
+		Some(state) => {
+			tracing::info!(job = J::NAME, tasks = state.tasks.len(), "Resuming job");
+			job.resume(ctx).await.map(|_| state)
+		},
+		None => job.init(ctx).await,
+	};
+
+	let working_state = match working_state {
 		Ok(state) => state,
 		Err(e) => {
 			ctx.fail(JobStatus::Failed, &format!("Init failed: {e}"))
@@ -50,6 +129,16 @@ where
 
 	let mut output = initial_output.unwrap_or_default();
 	let total_tasks = tasks.len();
+	let retry_policy = job.retry_policy(ctx.config());
+
+	if is_resumed {
+		ctx.report_progress(JobProgress::restored(0, total_tasks as i32));
+	}
+
+	if let Err(error) = ctx.checkpoint(&output, &tasks, &logs).await {
+		tracing::warn!(?error, job = J::NAME, "Failed to save job state");
+	}
+	let mut last_checkpoint = Instant::now();
 
 	let mut completed = 0u64;
 	while let Some(task) = tasks.pop_front() {
@@ -58,12 +147,21 @@ where
 			return Ok(());
 		}
 
+		if ctx.is_pause_requested() {
+			tasks.push_front(task);
+			ctx.pause(&output, &tasks, &logs).await?;
+			return Ok(());
+		}
+
 		ctx.report_progress(JobProgress::subtask_position(
 			completed as i32,
 			total_tasks as i32,
 		));
 
-		match job.execute_task(ctx, task).await {
+		let payload = serde_json::to_value(&task)
+			.map_err(|e| JobError::StateSaveFailed(e.to_string()))?;
+
+		match execute_task_with_retry(ctx, job, task, &payload, &retry_policy).await {
 			Ok(task_output) => {
 				output.update(task_output.output);
 				logs.extend(task_output.logs);
@@ -71,28 +169,84 @@ where
 					tasks.push_front(subtask);
 				}
 				completed += 1;
+
+				if last_checkpoint.elapsed() >= CHECKPOINT_INTERVAL {
+					if let Err(error) = ctx.checkpoint(&output, &tasks, &logs).await {
+						tracing::warn!(?error, job = J::NAME, "Failed to save job state");
+					}
+					last_checkpoint = Instant::now();
+				}
 			},
 			Err(e) => {
+				if ctx.is_canceled() {
+					ctx.cancel().await?;
+					return Ok(());
+				}
+
 				tracing::error!(error = ?e, job = J::NAME, "Task failed");
-				// TODO: Should single task fail entire job? Maybe a fail fast flag?
-				ctx.fail(JobStatus::Failed, &format!("Task failed: {e}"))
-					.await?;
-				return Err(e);
+				failed_tasks.push(payload);
+				ctx.record_failed_tasks(&failed_tasks).await?;
+
+				if retry_policy.fail_fast {
+					ctx.fail(JobStatus::Failed, &format!("Task failed: {e}"))
+						.await?;
+					return Err(e);
+				}
+
+				logs.push(JobExecuteLog::error(format!("Task failed: {e}")));
+				completed += 1;
 			},
 		}
 	}
 
 	job.finalize(ctx, &output).await?;
-	ctx.report_output(output.clone().into());
+	let output: CoreJobOutput = output.into();
+	ctx.report_output(output.clone());
 	ctx.complete(&output, logs).await
 }
 
-/// The top-level apalis handler function for all jobs
+/// Mark a claimed job as failed if it is still running, so it isn't run again when the
+/// queue is restored, and wake a worker since the job no longer counts toward the
+/// concurrency limit of its category
+async fn fail_claimed_job(ctx: &ApalisWorkerState, job_id: &str, error: &JobError) {
+	if let Err(error) = ctx
+		.job_storage
+		.fail_running(job_id, &error.to_string())
+		.await
+	{
+		tracing::error!(?error, %job_id, "Failed to mark job as failed");
+	}
+	ctx.cancellation_tokens.remove(job_id);
+	ctx.pause_requests.remove(job_id);
+
+	if let Err(error) = ctx.job_storage.signal().await {
+		tracing::error!(?error, "Failed to signal job worker");
+	}
+}
+
+/// The top-level apalis handler function for all jobs. Each [`QueueSignal`] claims the
+/// next queued job from the [`JobStorage`](super::JobStorage) which isn't held back by the
+/// concurrency limit of its category
 pub async fn dispatch_job(
-	job: StumpJob,
+	_signal: QueueSignal,
 	ctx: Data<Arc<ApalisWorkerState>>,
 ) -> Result<(), apalis::prelude::Error> {
-	let job_id = Uuid::new_v4().to_string();
+	let ClaimedJob {
+		id: job_id,
+		job,
+		save_state,
+		failed_tasks,
+	} = match ctx.job_storage.claim_next().await {
+		Ok(Some(claimed)) => claimed,
+		Ok(None) => {
+			tracing::debug!("No queued job to claim");
+			return Ok(());
+		},
+		Err(e) => {
+			tracing::error!(?e, "Failed to claim queued job");
+			return Err(apalis::prelude::Error::Failed(Arc::new(Box::new(e))));
+		},
+	};
 	let job_name = job.name();
 
 	tracing::info!(%job_id, job_name, "Starting job");
@@ -101,12 +255,18 @@ pub async fn dispatch_job(
 		Ok(h) => h,
 		Err(e) => {
 			tracing::error!(?e, "Failed to start job");
+			fail_claimed_job(&ctx, &job_id, &e).await;
 			return Err(apalis::prelude::Error::Failed(Arc::new(Box::new(e))));
 		},
 	};
 
 	let result = match job {
-		StumpJob::LibraryScan { id, path, options } => {
+		StumpJob::LibraryScan {
+			id,
+			path,
+			options,
+			watched,
+		} => {
 			run_job(
 				&job_ctx,
 				&mut LibraryScanJob {
@@ -114,7 +274,10 @@ pub async fn dispatch_job(
 					path,
 					config: None,
 					options: options.unwrap_or_default(),
+					notify: !watched,
 				},
+				save_state,
+				failed_tasks,
 			)
 			.await
 		},
@@ -127,14 +290,28 @@ pub async fn dispatch_job(
 					config: None,
 					options: options.unwrap_or_default(),
 				},
+				save_state,
+				failed_tasks,
 			)
 			.await
 		},
 		StumpJob::ThumbnailGeneration { options, params } => {
-			run_job(&job_ctx, &mut ThumbnailGenerationJob { options, params }).await
+			run_job(
+				&job_ctx,
+				&mut ThumbnailGenerationJob { options, params },
+				save_state,
+				failed_tasks,
+			)
+			.await
 		},
 		StumpJob::PlaceholderGeneration { config } => {
-			run_job(&job_ctx, &mut PlaceholderGenerationJob { config }).await
+			run_job(
+				&job_ctx,
+				&mut PlaceholderGenerationJob { config },
+				save_state,
+				failed_tasks,
+			)
+			.await
 		},
 		StumpJob::MetadataFetch { params } => {
 			run_job(
@@ -143,18 +320,318 @@ pub async fn dispatch_job(
 					params,
 					provider_cache: None,
 				},
+				save_state,
+				failed_tasks,
 			)
 			.await
 		},
 		StumpJob::AnalyzeMedia { config } => {
-			run_job(&job_ctx, &mut AnalyzeMediaJob { config }).await
+			run_job(
+				&job_ctx,
+				&mut AnalyzeMediaJob { config },
+				save_state,
+				failed_tasks,
+			)
+			.await
+		},
+		StumpJob::WriteBackMetadata { config } => {
+			run_job(
+				&job_ctx,
+				&mut WriteBackMetadataJob { config },
+				save_state,
+				failed_tasks,
+			)
+			.await
+		},
+		StumpJob::ConvertMedia { config } => {
+			run_job(
+				&job_ctx,
+				&mut ConvertMediaJob { config },
+				save_state,
+				failed_tasks,
+			)
+			.await
+		},
+		StumpJob::DetectDuplicates => {
+			run_job(&job_ctx, &mut DetectDuplicatesJob, save_state, failed_tasks).await
+		},
+		StumpJob::LibraryCleanup { config } => {
+			run_job(
+				&job_ctx,
+				&mut LibraryCleanupJob { config },
+				save_state,
+				failed_tasks,
+			)
+			.await
+		},
+		StumpJob::DatabaseBackup { config } => {
+			run_job(
+				&job_ctx,
+				&mut DatabaseBackupJob { config },
+				save_state,
+				failed_tasks,
+			)
+			.await
+		},
+		StumpJob::HistoryPruning { config } => {
+			run_job(
+				&job_ctx,
+				&mut HistoryPruningJob { config },
+				save_state,
+				failed_tasks,
+			)
+			.await
+		},
+		StumpJob::OpdsCacheWarmup { config } => {
+			run_job(
+				&job_ctx,
+				&mut OpdsCacheWarmupJob { config },
+				save_state,
+				failed_tasks,
+			)
+			.await
 		},
 	};
 
 	if let Err(e) = result {
 		tracing::error!(?e, "Job failed");
+		// The job may have returned before it could record its own failure, e.g. if it
+		// failed to finalize
+		fail_claimed_job(&ctx, &job_id, &e).await;
+		job_ctx.notify(NotificationEvent::JobFailed {
+			job_id,
+			job_name: job_name.to_string(),
+			error: e.to_string(),
+		});
 		return Err(apalis::prelude::Error::Failed(Arc::new(Box::new(e))));
 	}
 
+	// A job which was held back by its concurrency limit may be claimed now that this one
+	// has finished
+	if let Err(error) = ctx.job_storage.signal().await {
+		tracing::error!(?error, "Failed to signal job worker");
+	}
+
 	Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+	use std::sync::atomic::{AtomicU32, Ordering};
+
+	use ::tests::db::test_database;
+	use models::entity::{job, log};
+	use sea_orm::prelude::*;
+	use serde::{Deserialize, Serialize};
+	use tokio::sync::broadcast;
+
+	use super::*;
+	use crate::{
+		config::StumpConfig,
+		job::{DatabaseBackupOutput, JobStorage},
+	};
+
+	#[derive(Serialize, Deserialize, Debug)]
+	enum FlakyTask {
+		Flaky,
+		Steady,
+	}
+
+	/// A job whose flaky task fails its first `failures` attempts, followed by a task which
+	/// always succeeds
+	struct FlakyJob {
+		failures: u32,
+		policy: JobRetryPolicy,
+		flaky_attempts: Arc<AtomicU32>,
+		steady_runs: Arc<AtomicU32>,
+	}
+
+	impl FlakyJob {
+		fn new(failures: u32, policy: JobRetryPolicy) -> Self {
+			Self {
+				failures,
+				policy,
+				flaky_attempts: Arc::new(AtomicU32::new(0)),
+				steady_runs: Arc::new(AtomicU32::new(0)),
+			}
+		}
+	}
+
+	#[async_trait::async_trait]
+	impl JobLifecycle for FlakyJob {
+		const NAME: &'static str = "flaky";
+
+		type Output = DatabaseBackupOutput;
+		type Task = FlakyTask;
+
+		fn description(&self) -> Option<String> {
+			None
+		}
+
+		fn retry_policy(&self, _config: &StumpConfig) -> JobRetryPolicy {
+			self.policy
+		}
+
+		async fn init(
+			&mut self,
+			_ctx: &JobContext,
+		) -> Result<WorkingState<Self::Output, Self::Task>, JobError> {
+			Ok(WorkingState {
+				output: None,
+				tasks: vec![FlakyTask::Flaky, FlakyTask::Steady].into(),
+				logs: vec![],
+			})
+		}
+
+		async fn execute_task(
+			&self,
+			_ctx: &JobContext,
+			task: Self::Task,
+		) -> Result<JobTaskOutput<Self>, JobError> {
+			match task {
+				FlakyTask::Flaky => {
+					let attempt = self.flaky_attempts.fetch_add(1, Ordering::SeqCst);
+					if attempt < self.failures {
+						return Err(JobError::TaskFailed(format!("Attempt {attempt}")));
+					}
+				},
+				FlakyTask::Steady => {
+					self.steady_runs.fetch_add(1, Ordering::SeqCst);
+				},
+			}
+
+			Ok(JobTaskOutput {
+				output: DatabaseBackupOutput::default(),
+				subtasks: vec![],
+				logs: vec![],
+			})
+		}
+	}
+
+	async fn test_context() -> JobContext {
+		let conn = Arc::new(test_database().await);
+		let state = Arc::new(ApalisWorkerState::new(
+			conn.clone(),
+			Arc::new(StumpConfig::debug()),
+			broadcast::channel(16).0,
+			JobStorage::new(conn),
+		));
+		JobContext::new(state, "flaky".to_string(), &StumpJob::detect_duplicates())
+			.await
+			.unwrap()
+	}
+
+	async fn record_of(ctx: &JobContext) -> (JobStatus, Vec<serde_json::Value>) {
+		let record = job::Entity::find_by_id(&ctx.job_id)
+			.one(ctx.conn())
+			.await
+			.unwrap()
+			.unwrap();
+		let failed_tasks = record
+			.failed_tasks
+			.map(|bytes| serde_json::from_slice(&bytes).unwrap())
+			.unwrap_or_default();
+		(record.status, failed_tasks)
+	}
+
+	fn quick_policy(max_attempts: u32) -> JobRetryPolicy {
+		JobRetryPolicy::default()
+			.with_max_attempts(max_attempts)
+			.with_backoff(Duration::from_millis(10))
+	}
+
+	#[tokio::test]
+	async fn test_task_succeeds_after_retries() {
+		let ctx = test_context().await;
+		let mut job = FlakyJob::new(2, quick_policy(3));
+
+		let start = Instant::now();
+		run_job(&ctx, &mut job, None, vec![]).await.unwrap();
+
+		assert_eq!(job.flaky_attempts.load(Ordering::SeqCst), 3);
+		assert_eq!(job.steady_runs.load(Ordering::SeqCst), 1);
+		// Waited 10ms before the first retry and 20ms before the second
+		assert!(start.elapsed() >= Duration::from_millis(30));
+
+		let (status, failed_tasks) = record_of(&ctx).await;
+		assert_eq!(status, JobStatus::Completed);
+		assert!(failed_tasks.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_failed_task_is_recorded() {
+		let ctx = test_context().await;
+		let mut job = FlakyJob::new(u32::MAX, quick_policy(2));
+
+		// Tasks which failed during an earlier run are kept alongside the new ones
+		let earlier = serde_json::to_value(FlakyTask::Steady).unwrap();
+		run_job(&ctx, &mut job, None, vec![earlier.clone()])
+			.await
+			.unwrap();
+
+		assert_eq!(job.flaky_attempts.load(Ordering::SeqCst), 2);
+		// The job carries on with its remaining tasks
+		assert_eq!(job.steady_runs.load(Ordering::SeqCst), 1);
+
+		let (status, failed_tasks) = record_of(&ctx).await;
+		assert_eq!(status, JobStatus::Completed);
+		assert_eq!(
+			failed_tasks,
+			vec![earlier, serde_json::to_value(FlakyTask::Flaky).unwrap()]
+		);
+
+		let logs = log::Entity::find()
+			.filter(log::Column::JobId.eq(&ctx.job_id))
+			.count(ctx.conn())
+			.await
+			.unwrap();
+		assert_eq!(logs, 1);
+	}
+
+	#[tokio::test]
+	async fn test_fail_fast() {
+		let ctx = test_context().await;
+		let mut job = FlakyJob::new(u32::MAX, quick_policy(2).with_fail_fast(true));
+
+		assert!(run_job(&ctx, &mut job, None, vec![]).await.is_err());
+
+		assert_eq!(job.flaky_attempts.load(Ordering::SeqCst), 2);
+		assert_eq!(job.steady_runs.load(Ordering::SeqCst), 0);
+
+		let (status, failed_tasks) = record_of(&ctx).await;
+		assert_eq!(status, JobStatus::Failed);
+		assert_eq!(
+			failed_tasks,
+			vec![serde_json::to_value(FlakyTask::Flaky).unwrap()]
+		);
+	}
+
+	#[tokio::test]
+	async fn test_cancel_during_backoff() {
+		let ctx = test_context().await;
+		let policy = quick_policy(3).with_backoff(Duration::from_secs(60));
+		let mut job = FlakyJob::new(u32::MAX, policy);
+
+		let cancel_token = ctx.cancel_token.clone();
+		tokio::spawn(async move {
+			tokio::time::sleep(Duration::from_millis(50)).await;
+			cancel_token.cancel();
+		});
+
+		tokio::time::timeout(
+			Duration::from_secs(5),
+			run_job(&ctx, &mut job, None, vec![]),
+		)
+		.await
+		.expect("The backoff should stop once the job is cancelled")
+		.unwrap();
+
+		assert_eq!(job.flaky_attempts.load(Ordering::SeqCst), 1);
+		assert_eq!(job.steady_runs.load(Ordering::SeqCst), 0);
+
+		// A cancelled task didn't run out of attempts, so it isn't recorded
+		let (status, failed_tasks) = record_of(&ctx).await;
+		assert_eq!(status, JobStatus::Cancelled);
+		assert!(failed_tasks.is_empty());
+	}
+}
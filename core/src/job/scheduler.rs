@@ -3,10 +3,12 @@ use std::sync::Arc;
 
 use chrono::Utc;
 use cron::Schedule;
-use models::entity::{library, metadata_fetch_record, scheduled_job};
+use models::entity::{library, library_config, metadata_fetch_record, scheduled_job};
 use models::shared::enums::{MetadataFetchStatus, ScheduledJobKind};
 use sea_orm::{prelude::*, EntityTrait, QueryFilter};
 
+use crate::filesystem::image::ThumbnailGenerationJobParams;
+use crate::filesystem::media::analysis::{AnalysisJobConfig, MediaAnalysisJobScope};
 use crate::filesystem::metadata::MetadataFetchJobParams;
 use crate::job::stump_job::StumpJob;
 use crate::{CoreError, CoreResult, Ctx};
@@ -42,7 +44,6 @@ impl JobScheduler {
 					scheduler.handles.push(handle);
 				},
 				Err(error) => {
-					// TODO: Persisted log for UI to see
 					tracing::error!(
 						id = job.id,
 						name = %job.name,
@@ -50,6 +51,17 @@ impl JobScheduler {
 						?error,
 						"Invalid cron expression, skipping scheduled job"
 					);
+					// Persist the error so it is visible in the UI, not only in the logs
+					scheduled_job::Entity::update_many()
+						.col_expr(
+							scheduled_job::Column::LastError,
+							Expr::value(Some(format!(
+								"Invalid cron expression: {error}"
+							))),
+						)
+						.filter(scheduled_job::Column::Id.eq(job.id))
+						.exec(ctx.conn.as_ref())
+						.await?;
 				},
 			}
 		}
@@ -96,20 +108,25 @@ async fn cron_loop(job: scheduled_job::Model, schedule: Schedule, ctx: Arc<Ctx>)
 
 		tracing::info!("Firing scheduled job");
 
-		if let Err(error) = dispatch(&job, &ctx).await {
-			tracing::error!(
-				id = job.id,
-				name = %job.name,
-				?error,
-				"Scheduled job dispatch failed"
-			);
-		}
+		let last_error = match dispatch(&job, &ctx).await {
+			Ok(()) => None,
+			Err(error) => {
+				tracing::error!(
+					id = job.id,
+					name = %job.name,
+					?error,
+					"Scheduled job dispatch failed"
+				);
+				Some(error.to_string())
+			},
+		};
 
 		if let Err(error) = scheduled_job::Entity::update_many()
 			.col_expr(
 				scheduled_job::Column::LastRunAt,
 				sea_orm::sea_query::Expr::value(Utc::now()),
 			)
+			.col_expr(scheduled_job::Column::LastError, Expr::value(last_error))
 			.filter(scheduled_job::Column::Id.eq(job.id))
 			.exec(ctx.conn.as_ref())
 			.await
@@ -129,23 +146,43 @@ async fn dispatch(job: &scheduled_job::Model, ctx: &Ctx) -> CoreResult<()> {
 	match job.kind {
 		ScheduledJobKind::LibraryScan => dispatch_library_scan(job, ctx).await,
 		ScheduledJobKind::MetadataRetry => dispatch_metadata_retry(job, ctx).await,
+		ScheduledJobKind::ThumbnailRegeneration => {
+			dispatch_thumbnail_regeneration(job, ctx).await
+		},
+		ScheduledJobKind::MediaAnalysis => dispatch_media_analysis(job, ctx).await,
+		ScheduledJobKind::LibraryCleanup => dispatch_library_cleanup(job, ctx).await,
+		ScheduledJobKind::DatabaseBackup => dispatch_database_backup(job, ctx).await,
+		ScheduledJobKind::HistoryPruning => dispatch_history_pruning(job, ctx).await,
+		ScheduledJobKind::OpdsCacheWarmup => dispatch_opds_cache_warmup(job, ctx).await,
 	}
 }
 
-async fn dispatch_library_scan(job: &scheduled_job::Model, ctx: &Ctx) -> CoreResult<()> {
-	let config = job.library_scan_config().ok_or(CoreError::InternalError(
-		"Invalid scheduled scan config".to_string(),
-	))?;
+fn invalid_config(job: &scheduled_job::Model) -> CoreError {
+	CoreError::InternalError(format!("Invalid scheduled {} config", job.kind))
+}
 
-	let libraries = if config.library_ids.is_empty() {
-		library::Entity::find().all(ctx.conn.as_ref()).await?
+/// Find the libraries a scheduled job targets. An empty list targets all libraries
+async fn find_libraries(
+	library_ids: &[String],
+	ctx: &Ctx,
+) -> CoreResult<Vec<library::Model>> {
+	let query = library::Entity::find();
+	let query = if library_ids.is_empty() {
+		query
 	} else {
-		library::Entity::find()
-			.filter(library::Column::Id.is_in(config.library_ids.clone()))
-			.all(ctx.conn.as_ref())
-			.await?
+		query.filter(library::Column::Id.is_in(library_ids.to_vec()))
 	};
 
+	Ok(query.all(ctx.conn.as_ref()).await?)
+}
+
+async fn dispatch_library_scan(job: &scheduled_job::Model, ctx: &Ctx) -> CoreResult<()> {
+	let config = job
+		.library_scan_config()
+		.ok_or_else(|| invalid_config(job))?;
+
+	let libraries = find_libraries(&config.library_ids, ctx).await?;
+
 	if libraries.is_empty() {
 		tracing::warn!("No libraries found for scheduled scan");
 		return Ok(());
@@ -222,3 +259,119 @@ async fn dispatch_metadata_retry(
 
 	Ok(())
 }
+
+async fn dispatch_thumbnail_regeneration(
+	job: &scheduled_job::Model,
+	ctx: &Ctx,
+) -> CoreResult<()> {
+	let config = job
+		.thumbnail_regeneration_config()
+		.ok_or_else(|| invalid_config(job))?;
+
+	let query = library::Entity::find().find_also_related(library_config::Entity);
+	let query = if config.library_ids.is_empty() {
+		query
+	} else {
+		query.filter(library::Column::Id.is_in(config.library_ids.clone()))
+	};
+	let libraries = query.all(ctx.conn.as_ref()).await?;
+
+	for (lib, lib_config) in libraries {
+		tracing::info!(
+			library_name = %lib.name,
+			"Enqueuing thumbnail generation from scheduler"
+		);
+		let options = lib_config
+			.and_then(|c| c.thumbnail_config)
+			.unwrap_or_default();
+		ctx.enqueue(StumpJob::thumbnail_generation(
+			options,
+			ThumbnailGenerationJobParams::books_in_library(
+				lib.id,
+				config.force_regenerate,
+			),
+		))
+		.await?;
+	}
+
+	Ok(())
+}
+
+async fn dispatch_media_analysis(
+	job: &scheduled_job::Model,
+	ctx: &Ctx,
+) -> CoreResult<()> {
+	let config = job
+		.media_analysis_config()
+		.ok_or_else(|| invalid_config(job))?;
+
+	for lib in find_libraries(&config.library_ids, ctx).await? {
+		tracing::info!(
+			library_name = %lib.name,
+			"Enqueuing media analysis from scheduler"
+		);
+		ctx.enqueue(StumpJob::analyze_media(AnalysisJobConfig {
+			scope: MediaAnalysisJobScope::Library(lib.id),
+			force_reanalysis: config.force_reanalysis,
+		}))
+		.await?;
+	}
+
+	Ok(())
+}
+
+async fn dispatch_library_cleanup(
+	job: &scheduled_job::Model,
+	ctx: &Ctx,
+) -> CoreResult<()> {
+	let config = job
+		.library_cleanup_config()
+		.ok_or_else(|| invalid_config(job))?;
+
+	tracing::info!("Enqueuing library cleanup from scheduler");
+	ctx.enqueue(StumpJob::library_cleanup(config)).await?;
+
+	Ok(())
+}
+
+async fn dispatch_database_backup(
+	job: &scheduled_job::Model,
+	ctx: &Ctx,
+) -> CoreResult<()> {
+	let config = job
+		.database_backup_config()
+		.ok_or_else(|| invalid_config(job))?;
+
+	tracing::info!("Enqueuing database backup from scheduler");
+	ctx.enqueue(StumpJob::database_backup(config)).await?;
+
+	Ok(())
+}
+
+async fn dispatch_history_pruning(
+	job: &scheduled_job::Model,
+	ctx: &Ctx,
+) -> CoreResult<()> {
+	let config = job
+		.history_pruning_config()
+		.ok_or_else(|| invalid_config(job))?;
+
+	tracing::info!("Enqueuing history pruning from scheduler");
+	ctx.enqueue(StumpJob::history_pruning(config)).await?;
+
+	Ok(())
+}
+
+async fn dispatch_opds_cache_warmup(
+	job: &scheduled_job::Model,
+	ctx: &Ctx,
+) -> CoreResult<()> {
+	let config = job
+		.opds_cache_warmup_config()
+		.ok_or_else(|| invalid_config(job))?;
+
+	tracing::info!("Enqueuing OPDS cache warmup from scheduler");
+	ctx.enqueue(StumpJob::opds_cache_warmup(config)).await?;
+
+	Ok(())
+}
@@ -1,12 +1,13 @@
-use std::{sync::Arc, time::Instant};
+use std::{collections::VecDeque, sync::Arc, time::Instant};
 
 use crate::{
 	config::StumpConfig,
 	event::{CoreEvent, JobOutput},
 	job::JobUpdate,
-	CoreError,
+	notifier, CoreError,
 };
-use dashmap::DashMap;
+use dashmap::{DashMap, DashSet};
+use integrations::NotificationEvent;
 use models::entity::{job, log, server_config};
 use sea_orm::{
 	prelude::*, sea_query::OnConflict, sqlx::types::chrono::Utc, ActiveValue::Set,
@@ -16,11 +17,9 @@ use serde::Serialize;
 use tokio::sync::broadcast;
 use tokio_util::sync::CancellationToken;
 
-use apalis::prelude::{MemoryStorage, MessageQueue};
-
 use super::{
-	error::JobError, stump_job::StumpJob, CoreJobOutput, JobExecuteLog, JobOutputExt,
-	JobProgress, JobStatus,
+	error::JobError, storage::JobStorage, stump_job::StumpJob, CoreJobOutput,
+	JobExecuteLog, JobProgress, JobStatus,
 };
 
 #[derive(Clone)]
@@ -29,7 +28,9 @@ pub struct ApalisWorkerState {
 	pub config: Arc<StumpConfig>,
 	pub core_event_tx: broadcast::Sender<CoreEvent>,
 	pub cancellation_tokens: Arc<DashMap<String, CancellationToken>>,
-	pub job_storage: MemoryStorage<StumpJob>,
+	/// The IDs of running jobs which should pause once their current task finishes
+	pub pause_requests: Arc<DashSet<String>>,
+	pub job_storage: JobStorage,
 }
 
 impl ApalisWorkerState {
@@ -37,13 +38,14 @@ impl ApalisWorkerState {
 		conn: Arc<DatabaseConnection>,
 		config: Arc<StumpConfig>,
 		core_event_tx: broadcast::Sender<CoreEvent>,
-		job_storage: MemoryStorage<StumpJob>,
+		job_storage: JobStorage,
 	) -> Self {
 		Self {
 			conn,
 			config,
 			core_event_tx,
 			cancellation_tokens: Arc::new(DashMap::new()),
+			pause_requests: Arc::new(DashSet::new()),
 			job_storage,
 		}
 	}
@@ -58,24 +60,15 @@ impl ApalisWorkerState {
 		}
 	}
 
-	/// Cancel all jobs still marked as Running in the DB
-	pub async fn cancel_islanded_jobs(&self) -> Result<(), JobError> {
-		let affected_rows = job::Entity::update_many()
-			.filter(job::Column::Status.eq(JobStatus::Running.to_string()))
-			.col_expr(
-				job::Column::Status,
-				Expr::value(JobStatus::Cancelled.to_string()),
-			)
-			.col_expr(
-				job::Column::CompletedAt,
-				Expr::value(Some(Utc::now().to_rfc3339())),
-			)
-			.exec(self.conn.as_ref())
-			.await?
-			.rows_affected;
-
-		tracing::debug!(affected_rows, "Cancelled islanded jobs");
-		Ok(())
+	/// Request that a running job pauses once its current task finishes, returning true if
+	/// the job is running
+	pub fn pause_job(&self, job_id: &str) -> bool {
+		if self.cancellation_tokens.contains_key(job_id) {
+			self.pause_requests.insert(job_id.to_string());
+			true
+		} else {
+			false
+		}
 	}
 }
 
@@ -131,6 +124,11 @@ impl JobContext {
 		self.cancel_token.is_cancelled()
 	}
 
+	/// Check if this job has been asked to pause
+	pub fn is_pause_requested(&self) -> bool {
+		self.apalis_state.pause_requests.contains(&self.job_id)
+	}
+
 	/// Sends an event to the core event channel
 	pub fn emit_event(&self, event: CoreEvent) {
 		if let Err(e) = self.apalis_state.core_event_tx.send(event) {
@@ -138,6 +136,16 @@ impl JobContext {
 		}
 	}
 
+	/// Sends a [`NotificationEvent`] to any notifiers subscribed to it, in the background
+	pub fn notify(&self, event: NotificationEvent) {
+		let conn = self.apalis_state.conn.clone();
+		tokio::spawn(async move {
+			if let Err(error) = notifier::send_notification(conn.as_ref(), event).await {
+				tracing::error!(?error, "Failed to send notification");
+			}
+		});
+	}
+
 	/// Sends a [`JobProgress`] update event to the core event channel
 	pub fn report_progress(&self, progress: JobProgress) {
 		self.emit_event(CoreEvent::JobUpdate(JobUpdate {
@@ -170,6 +178,57 @@ impl JobContext {
 		Ok(encryption_key)
 	}
 
+	/// Persist the working state of the job, so that it resumes from its remaining tasks
+	/// if the server stops before it finishes
+	pub async fn checkpoint<O: Serialize, T: Serialize>(
+		&self,
+		output: &O,
+		tasks: &VecDeque<T>,
+		logs: &[JobExecuteLog],
+	) -> Result<(), JobError> {
+		/// A borrowed [`WorkingState`](super::WorkingState), to avoid cloning the remaining
+		/// tasks on every checkpoint. It serializes to the same shape
+		#[derive(Serialize)]
+		struct WorkingStateRef<'a, O, T> {
+			output: Option<&'a O>,
+			tasks: &'a VecDeque<T>,
+			logs: &'a [JobExecuteLog],
+		}
+
+		let save_state = serde_json::to_vec(&WorkingStateRef {
+			output: Some(output),
+			tasks,
+			logs,
+		})
+		.map_err(|e| JobError::StateSaveFailed(e.to_string()))?;
+
+		job::Entity::update_many()
+			.filter(job::Column::Id.eq(&self.job_id))
+			.col_expr(job::Column::SaveState, Expr::value(Some(save_state)))
+			.exec(self.conn())
+			.await?;
+
+		Ok(())
+	}
+
+	/// Persist the payloads of the tasks which failed during the job, so they can be retried
+	/// once the job has finished
+	pub async fn record_failed_tasks(
+		&self,
+		failed_tasks: &[serde_json::Value],
+	) -> Result<(), JobError> {
+		let failed_tasks = serde_json::to_vec(failed_tasks)
+			.map_err(|e| JobError::StateSaveFailed(e.to_string()))?;
+
+		job::Entity::update_many()
+			.filter(job::Column::Id.eq(&self.job_id))
+			.col_expr(job::Column::FailedTasks, Expr::value(Some(failed_tasks)))
+			.exec(self.conn())
+			.await?;
+
+		Ok(())
+	}
+
 	/// Send a [`JobOutput`] event to the core event channel with the given output data
 	pub fn report_output(&self, output: CoreJobOutput) {
 		let event = CoreEvent::JobOutput(JobOutput {
@@ -180,9 +239,9 @@ impl JobContext {
 	}
 
 	/// A convenience method to take the outputs and logs of a job and persist them into the database
-	pub async fn complete<O: Serialize + JobOutputExt + std::fmt::Debug>(
+	pub async fn complete(
 		&self,
-		output: &O,
+		output: &CoreJobOutput,
 		logs: Vec<JobExecuteLog>,
 	) -> Result<(), JobError> {
 		let elapsed = self.start.elapsed();
@@ -205,13 +264,15 @@ impl JobContext {
 		job::Entity::update_many()
 			.filter(job::Column::Id.eq(&self.job_id))
 			.col_expr(job::Column::OutputData, Expr::value(output_data))
+			.col_expr(job::Column::SaveState, Expr::value(Option::<Vec<u8>>::None))
 			.col_expr(
 				job::Column::Status,
 				Expr::value(JobStatus::Completed.to_string()),
 			)
+			// A job which was paused has already recorded some of its time
 			.col_expr(
 				job::Column::MsElapsed,
-				Expr::value(elapsed.as_millis() as i64),
+				Expr::col(job::Column::MsElapsed).add(elapsed.as_millis() as i64),
 			)
 			.col_expr(
 				job::Column::CompletedAt,
@@ -221,6 +282,7 @@ impl JobContext {
 			.await?;
 
 		self.apalis_state.cancellation_tokens.remove(&self.job_id);
+		self.apalis_state.pause_requests.remove(&self.job_id);
 
 		Ok(())
 	}
@@ -233,9 +295,11 @@ impl JobContext {
 		job::Entity::update_many()
 			.filter(job::Column::Id.eq(&self.job_id))
 			.col_expr(job::Column::Status, Expr::value(status.to_string()))
+			.col_expr(job::Column::SaveState, Expr::value(Option::<Vec<u8>>::None))
+			// A job which was paused has already recorded some of its time
 			.col_expr(
 				job::Column::MsElapsed,
-				Expr::value(elapsed.as_millis() as i64),
+				Expr::col(job::Column::MsElapsed).add(elapsed.as_millis() as i64),
 			)
 			.col_expr(
 				job::Column::CompletedAt,
@@ -245,6 +309,41 @@ impl JobContext {
 			.await?;
 
 		self.apalis_state.cancellation_tokens.remove(&self.job_id);
+		self.apalis_state.pause_requests.remove(&self.job_id);
+
+		Ok(())
+	}
+
+	/// Persist the working state of the job and mark it as paused. The job picks back up
+	/// from its remaining tasks once it is resumed
+	pub async fn pause<O: Serialize, T: Serialize>(
+		&self,
+		output: &O,
+		tasks: &VecDeque<T>,
+		logs: &[JobExecuteLog],
+	) -> Result<(), JobError> {
+		let elapsed = self.start.elapsed();
+		self.checkpoint(output, tasks, logs).await?;
+
+		job::Entity::update_many()
+			.filter(job::Column::Id.eq(&self.job_id))
+			.col_expr(
+				job::Column::Status,
+				Expr::value(JobStatus::Paused.to_string()),
+			)
+			.col_expr(
+				job::Column::MsElapsed,
+				Expr::col(job::Column::MsElapsed).add(elapsed.as_millis() as i64),
+			)
+			.exec(self.conn())
+			.await?;
+
+		self.apalis_state.cancellation_tokens.remove(&self.job_id);
+		self.apalis_state.pause_requests.remove(&self.job_id);
+		self.report_progress(JobProgress::status_msg(
+			JobStatus::Paused,
+			"Job was paused",
+		));
 
 		Ok(())
 	}
@@ -256,11 +355,13 @@ impl JobContext {
 
 	/// A convenience method to enqueue a follow-up job from this job's execution
 	pub async fn enqueue(&self, job: StumpJob) -> Result<(), JobError> {
-		let mut storage = self.apalis_state.job_storage.clone();
-		storage.enqueue(job).await.map_err(|error| {
-			tracing::error!(?error, "Failed to enqueue follow-up job!");
-			JobError::Unknown(format!("Failed to enqueue follow-up job! {error:?}"))
-		})?;
+		self.apalis_state
+			.job_storage
+			.enqueue(job)
+			.await
+			.inspect_err(|error| {
+				tracing::error!(?error, "Failed to enqueue follow-up job!");
+			})?;
 		Ok(())
 	}
 }
@@ -0,0 +1,858 @@
+use std::{collections::HashMap, sync::Arc};
+
+use apalis::prelude::{MemoryStorage, MessageQueue};
+use models::{
+	entity::{job, log},
+	shared::enums::{JobCategory, LogLevel},
+};
+use sea_orm::{
+	prelude::*, sqlx::types::chrono::Utc, QueryOrder, QuerySelect, Select, Set,
+	TransactionTrait,
+};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use super::{error::JobError, stump_job::StumpJob, JobStatus, WorkingState};
+use crate::config::{
+	defaults::{
+		DEFAULT_MAX_CONCURRENT_ANALYSIS_JOBS, DEFAULT_MAX_CONCURRENT_METADATA_JOBS,
+		DEFAULT_MAX_CONCURRENT_SCAN_JOBS, DEFAULT_MAX_CONCURRENT_THUMBNAIL_JOBS,
+	},
+	StumpConfig,
+};
+
+/// The message sent through apalis to wake a worker. It deliberately carries no job, since
+/// the database is the source of truth for what is queued: the worker claims the next
+/// queued job when it receives a signal
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QueueSignal;
+
+/// A queued job which was claimed by a worker
+#[derive(Debug)]
+pub struct ClaimedJob {
+	pub id: String,
+	pub job: StumpJob,
+	/// The serialized [`WorkingState`](super::WorkingState) of the job when it was last
+	/// interrupted, if it was
+	pub save_state: Option<Vec<u8>>,
+	/// The payloads of the tasks which failed before the job was interrupted, if it was
+	pub failed_tasks: Vec<serde_json::Value>,
+}
+
+/// The number of jobs in each [`JobCategory`] which may run at the same time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JobConcurrencyLimits {
+	pub scan: usize,
+	pub metadata: usize,
+	pub thumbnail: usize,
+	pub analysis: usize,
+}
+
+impl JobConcurrencyLimits {
+	pub fn from_config(config: &StumpConfig) -> Self {
+		Self {
+			scan: config.max_concurrent_scan_jobs.max(1),
+			metadata: config.max_concurrent_metadata_jobs.max(1),
+			thumbnail: config.max_concurrent_thumbnail_jobs.max(1),
+			analysis: config.max_concurrent_analysis_jobs.max(1),
+		}
+	}
+
+	/// The number of jobs in the given category which may run at the same time
+	pub fn of(&self, category: JobCategory) -> usize {
+		match category {
+			JobCategory::Scan => self.scan,
+			JobCategory::Metadata => self.metadata,
+			JobCategory::Thumbnail => self.thumbnail,
+			JobCategory::Analysis => self.analysis,
+		}
+	}
+}
+
+impl Default for JobConcurrencyLimits {
+	fn default() -> Self {
+		Self {
+			scan: DEFAULT_MAX_CONCURRENT_SCAN_JOBS,
+			metadata: DEFAULT_MAX_CONCURRENT_METADATA_JOBS,
+			thumbnail: DEFAULT_MAX_CONCURRENT_THUMBNAIL_JOBS,
+			analysis: DEFAULT_MAX_CONCURRENT_ANALYSIS_JOBS,
+		}
+	}
+}
+
+/// A SQLite-backed storage for [`StumpJob`]s. Each enqueued job is persisted to the `jobs`
+/// table with its payload before a worker is signalled, so that queued jobs (and jobs
+/// interrupted mid-execution) survive a restart.
+///
+/// Queued jobs are claimed by priority, then by their position in the queue. A job is only
+/// claimed while fewer than the [`JobConcurrencyLimits`] of its category are running, so a
+/// long thumbnail job doesn't hold up an interactive scan
+#[derive(Clone)]
+pub struct JobStorage {
+	conn: Arc<DatabaseConnection>,
+	signals: MemoryStorage<QueueSignal>,
+	limits: JobConcurrencyLimits,
+	/// Serializes changes to the order of the queue, so concurrent workers don't claim past
+	/// a limit and identical jobs aren't enqueued twice
+	queue_lock: Arc<Mutex<()>>,
+}
+
+impl JobStorage {
+	pub fn new(conn: Arc<DatabaseConnection>) -> Self {
+		Self {
+			conn,
+			signals: MemoryStorage::new(),
+			limits: JobConcurrencyLimits::default(),
+			queue_lock: Arc::new(Mutex::new(())),
+		}
+	}
+
+	pub fn with_limits(self, limits: JobConcurrencyLimits) -> Self {
+		Self { limits, ..self }
+	}
+
+	pub fn limits(&self) -> JobConcurrencyLimits {
+		self.limits
+	}
+
+	/// The apalis backend which workers should be built with
+	pub fn backend(&self) -> MemoryStorage<QueueSignal> {
+		self.signals.clone()
+	}
+
+	/// Persist a job as queued and signal a worker to pick it up, returning the ID of the job.
+	/// If an identical job is already waiting in the queue, its ID is returned instead
+	pub async fn enqueue(&self, job: StumpJob) -> Result<String, JobError> {
+		let conn = self.conn.as_ref();
+		let payload = serde_json::to_vec(&job)
+			.map_err(|e| JobError::StateSaveFailed(e.to_string()))?;
+
+		let _guard = self.queue_lock.lock().await;
+
+		// A retry only runs the tasks which failed during another job, so it isn't identical
+		let existing_id = job::Entity::find()
+			.select_only()
+			.column(job::Column::Id)
+			.filter(job::Column::Status.eq(JobStatus::Queued.to_string()))
+			.filter(job::Column::Payload.eq(payload.clone()))
+			.filter(job::Column::RetryOf.is_null())
+			.into_tuple::<String>()
+			.one(conn)
+			.await?;
+		if let Some(id) = existing_id {
+			tracing::debug!(%id, name = job.name(), "Identical job is already queued");
+			return Ok(id);
+		}
+
+		let id = Uuid::new_v4().to_string();
+		job::ActiveModel {
+			id: Set(id.clone()),
+			name: Set(job.name().to_string()),
+			description: Set(job.description()),
+			status: Set(JobStatus::Queued),
+			payload: Set(Some(payload)),
+			category: Set(Some(job.category())),
+			priority: Set(job.category().priority()),
+			queue_position: Set(Self::next_queue_position(conn).await?),
+			..Default::default()
+		}
+		.insert(conn)
+		.await?;
+
+		self.signal().await?;
+
+		tracing::debug!(%id, name = job.name(), "Enqueued job");
+
+		Ok(id)
+	}
+
+	/// The position at the back of the queue. Callers should hold the queue lock
+	async fn next_queue_position<C: ConnectionTrait>(conn: &C) -> Result<i64, JobError> {
+		let last_position =
+			job::Entity::find()
+				.select_only()
+				.column_as(
+					Expr::col(job::Column::QueuePosition).max(),
+					"queue_position",
+				)
+				.filter(job::Column::Status.is_in([
+					JobStatus::Queued.to_string(),
+					JobStatus::Paused.to_string(),
+				]))
+				.into_tuple::<Option<i64>>()
+				.one(conn)
+				.await?
+				.flatten();
+
+		Ok(last_position.map_or(0, |position| position + 1))
+	}
+
+	/// Queue a new job which runs only the tasks that failed during a finished job, returning
+	/// the new job. The failed tasks are moved to the new job, so they aren't retried twice.
+	/// Returns `None` if the job isn't finished or has no failed tasks
+	pub async fn retry_failed_tasks(
+		&self,
+		id: &str,
+	) -> Result<Option<job::Model>, JobError> {
+		let _guard = self.queue_lock.lock().await;
+
+		let txn = self.conn.begin().await?;
+
+		let Some(record) = job::Entity::find_by_id(id)
+			.filter(job::Column::Status.is_in([
+				JobStatus::Completed.to_string(),
+				JobStatus::Failed.to_string(),
+			]))
+			.filter(job::Column::Payload.is_not_null())
+			.one(&txn)
+			.await?
+		else {
+			return Ok(None);
+		};
+
+		let failed_tasks = match record.failed_tasks.as_deref() {
+			Some(bytes) => serde_json::from_slice::<Vec<serde_json::Value>>(bytes)
+				.map_err(|e| JobError::StateLoadFailed(e.to_string()))?,
+			None => vec![],
+		};
+		if failed_tasks.is_empty() {
+			return Ok(None);
+		}
+
+		// The new job resumes from a saved state holding only the failed tasks, rather than
+		// being initialized again
+		let save_state = serde_json::to_vec(&WorkingState::<(), serde_json::Value> {
+			output: None,
+			tasks: failed_tasks.into(),
+			logs: vec![],
+		})
+		.map_err(|e| JobError::StateSaveFailed(e.to_string()))?;
+
+		let retry = job::ActiveModel {
+			id: Set(Uuid::new_v4().to_string()),
+			name: Set(record.name.clone()),
+			description: Set(record.description.clone()),
+			status: Set(JobStatus::Queued),
+			payload: Set(record.payload.clone()),
+			save_state: Set(Some(save_state)),
+			retry_of: Set(Some(record.id.clone())),
+			category: Set(record.category),
+			priority: Set(record
+				.category
+				.map_or(record.priority, |category| category.priority())),
+			queue_position: Set(Self::next_queue_position(&txn).await?),
+			..Default::default()
+		}
+		.insert(&txn)
+		.await?;
+
+		job::Entity::update_many()
+			.filter(job::Column::Id.eq(&record.id))
+			.col_expr(
+				job::Column::FailedTasks,
+				Expr::value(Option::<Vec<u8>>::None),
+			)
+			.exec(&txn)
+			.await?;
+
+		txn.commit().await?;
+
+		self.signal().await?;
+
+		tracing::debug!(id = %retry.id, retried = %record.id, "Enqueued failed tasks");
+
+		Ok(Some(retry))
+	}
+
+	/// Wake a worker to claim the next queued job. This should be called whenever a job
+	/// finishes, since a job which was held back by its concurrency limit may now be claimed
+	pub async fn signal(&self) -> Result<(), JobError> {
+		let mut signals = self.signals.clone();
+		signals.enqueue(QueueSignal).await.map_err(|error| {
+			JobError::Unknown(format!("Failed to signal job worker: {error:?}"))
+		})
+	}
+
+	/// The queued jobs, in the order they will be claimed
+	fn queued() -> Select<job::Entity> {
+		job::Entity::find()
+			.filter(job::Column::Status.eq(JobStatus::Queued.to_string()))
+			.filter(job::Column::Payload.is_not_null())
+			.order_by_desc(job::Column::Priority)
+			.order_by_asc(job::Column::QueuePosition)
+			.order_by_asc(job::Column::CreatedAt)
+	}
+
+	/// List the jobs which are waiting in the queue, in the order they will be claimed
+	pub async fn list_queued(&self) -> Result<Vec<job::Model>, JobError> {
+		Ok(Self::queued().all(self.conn.as_ref()).await?)
+	}
+
+	/// Claim the next queued job whose category isn't at its concurrency limit, marking it as
+	/// running. Returns `None` if there is nothing which may be claimed right now, e.g. when a
+	/// queued job was cancelled before a worker got to it
+	pub async fn claim_next(&self) -> Result<Option<ClaimedJob>, JobError> {
+		let conn = self.conn.as_ref();
+
+		let _guard = self.queue_lock.lock().await;
+
+		let mut running = HashMap::<JobCategory, usize>::new();
+		let running_categories = job::Entity::find()
+			.select_only()
+			.column(job::Column::Category)
+			.filter(job::Column::Status.eq(JobStatus::Running.to_string()))
+			.filter(job::Column::Category.is_not_null())
+			.into_tuple::<JobCategory>()
+			.all(conn)
+			.await?;
+		for category in running_categories {
+			*running.entry(category).or_default() += 1;
+		}
+
+		loop {
+			let candidates = Self::queued()
+				.select_only()
+				.columns([job::Column::Id, job::Column::Category])
+				.into_tuple::<(String, Option<JobCategory>)>()
+				.all(conn)
+				.await?;
+			let Some((id, _)) = candidates.into_iter().find(|(_, category)| {
+				category.is_none_or(|category| {
+					running.get(&category).copied().unwrap_or_default()
+						< self.limits.of(category)
+				})
+			}) else {
+				return Ok(None);
+			};
+
+			let Some(record) = job::Entity::find_by_id(&id).one(conn).await? else {
+				continue;
+			};
+
+			// Guard against the job being cancelled or paused in the meantime
+			let claimed = job::Entity::update_many()
+				.filter(job::Column::Id.eq(&record.id))
+				.filter(job::Column::Status.eq(JobStatus::Queued.to_string()))
+				.col_expr(
+					job::Column::Status,
+					Expr::value(JobStatus::Running.to_string()),
+				)
+				.exec(conn)
+				.await?
+				.rows_affected
+				> 0;
+			if !claimed {
+				continue;
+			}
+
+			let payload = record.payload.unwrap_or_default();
+			match serde_json::from_slice::<StumpJob>(&payload) {
+				Ok(job) => {
+					let failed_tasks = record
+						.failed_tasks
+						.and_then(|bytes| serde_json::from_slice(&bytes).ok())
+						.unwrap_or_default();
+					return Ok(Some(ClaimedJob {
+						id: record.id,
+						job,
+						save_state: record.save_state,
+						failed_tasks,
+					}));
+				},
+				Err(error) => {
+					// A payload from an older version which no longer deserializes shouldn't
+					// block the rest of the queue
+					tracing::error!(id = %record.id, ?error, "Failed to load queued job");
+					job::Entity::update_many()
+						.filter(job::Column::Id.eq(&record.id))
+						.col_expr(
+							job::Column::Status,
+							Expr::value(JobStatus::Failed.to_string()),
+						)
+						.col_expr(
+							job::Column::CompletedAt,
+							Expr::value(Some(Utc::now().to_rfc3339())),
+						)
+						.exec(conn)
+						.await?;
+				},
+			}
+		}
+	}
+
+	/// Mark a claimed job which is still running as failed, recording the error in its logs.
+	/// Returns true if the job was still running, i.e. it hadn't already been finished by its
+	/// [`JobContext`](super::JobContext)
+	pub async fn fail_running(&self, id: &str, message: &str) -> Result<bool, JobError> {
+		let txn = self.conn.begin().await?;
+
+		let affected_rows = job::Entity::update_many()
+			.filter(job::Column::Id.eq(id))
+			.filter(job::Column::Status.eq(JobStatus::Running.to_string()))
+			.col_expr(
+				job::Column::Status,
+				Expr::value(JobStatus::Failed.to_string()),
+			)
+			.col_expr(job::Column::SaveState, Expr::value(Option::<Vec<u8>>::None))
+			.col_expr(
+				job::Column::CompletedAt,
+				Expr::value(Some(Utc::now().to_rfc3339())),
+			)
+			.exec(&txn)
+			.await?
+			.rows_affected;
+
+		if affected_rows > 0 {
+			log::ActiveModel {
+				job_id: Set(Some(id.to_string())),
+				message: Set(message.to_string()),
+				level: Set(LogLevel::Error),
+				timestamp: Set(Utc::now().into()),
+				..Default::default()
+			}
+			.insert(&txn)
+			.await?;
+		}
+
+		txn.commit().await?;
+
+		Ok(affected_rows > 0)
+	}
+
+	/// Cancel a job which is still waiting in the queue or is paused, returning true if one
+	/// was found
+	pub async fn cancel_queued(&self, id: &str) -> Result<bool, JobError> {
+		let affected_rows =
+			job::Entity::update_many()
+				.filter(job::Column::Id.eq(id))
+				.filter(job::Column::Status.is_in([
+					JobStatus::Queued.to_string(),
+					JobStatus::Paused.to_string(),
+				]))
+				.col_expr(
+					job::Column::Status,
+					Expr::value(JobStatus::Cancelled.to_string()),
+				)
+				.col_expr(
+					job::Column::CompletedAt,
+					Expr::value(Some(Utc::now().to_rfc3339())),
+				)
+				.exec(self.conn.as_ref())
+				.await?
+				.rows_affected;
+
+		Ok(affected_rows > 0)
+	}
+
+	/// Pause a job which is still waiting in the queue, so it isn't started until it is
+	/// resumed. Returns true if one was found
+	pub async fn pause_queued(&self, id: &str) -> Result<bool, JobError> {
+		let affected_rows = job::Entity::update_many()
+			.filter(job::Column::Id.eq(id))
+			.filter(job::Column::Status.eq(JobStatus::Queued.to_string()))
+			.col_expr(
+				job::Column::Status,
+				Expr::value(JobStatus::Paused.to_string()),
+			)
+			.exec(self.conn.as_ref())
+			.await?
+			.rows_affected;
+
+		Ok(affected_rows > 0)
+	}
+
+	/// Requeue a paused job, returning true if one was found. A job which was paused while
+	/// running resumes from its remaining tasks
+	pub async fn resume(&self, id: &str) -> Result<bool, JobError> {
+		let affected_rows = job::Entity::update_many()
+			.filter(job::Column::Id.eq(id))
+			.filter(job::Column::Status.eq(JobStatus::Paused.to_string()))
+			.filter(job::Column::Payload.is_not_null())
+			.col_expr(
+				job::Column::Status,
+				Expr::value(JobStatus::Queued.to_string()),
+			)
+			.exec(self.conn.as_ref())
+			.await?
+			.rows_affected;
+
+		if affected_rows > 0 {
+			self.signal().await?;
+		}
+
+		Ok(affected_rows > 0)
+	}
+
+	/// Move queued jobs to the front of the queue, in the given order. Moved jobs take the
+	/// highest priority in the queue, so passing every queued job reorders the whole queue.
+	/// Jobs which aren't queued are ignored. Returns the number of jobs which were moved
+	pub async fn reorder_queued(&self, ids: &[String]) -> Result<u64, JobError> {
+		let _guard = self.queue_lock.lock().await;
+
+		let txn = self.conn.begin().await?;
+
+		let (priority, first_position) = job::Entity::find()
+			.select_only()
+			.column_as(Expr::col(job::Column::Priority).max(), "priority")
+			.column_as(
+				Expr::col(job::Column::QueuePosition).min(),
+				"queue_position",
+			)
+			.filter(job::Column::Status.eq(JobStatus::Queued.to_string()))
+			.into_tuple::<(Option<i32>, Option<i64>)>()
+			.one(&txn)
+			.await?
+			.unwrap_or_default();
+		let (Some(priority), Some(first_position)) = (priority, first_position) else {
+			return Ok(0);
+		};
+
+		let mut moved = 0;
+		for (index, id) in ids.iter().enumerate() {
+			let position = first_position - ids.len() as i64 + index as i64;
+			moved += job::Entity::update_many()
+				.filter(job::Column::Id.eq(id))
+				.filter(job::Column::Status.eq(JobStatus::Queued.to_string()))
+				.col_expr(job::Column::Priority, Expr::value(priority))
+				.col_expr(job::Column::QueuePosition, Expr::value(position))
+				.exec(&txn)
+				.await?
+				.rows_affected;
+		}
+
+		txn.commit().await?;
+
+		Ok(moved)
+	}
+
+	/// Restore the queue after a restart. Jobs which were running when the server stopped
+	/// are requeued so they resume from their last saved state, and a worker is signalled
+	/// for every queued job. Running jobs without a payload can't be resumed, so they are
+	/// cancelled instead. Paused jobs stay paused until they are resumed
+	pub async fn restore(&self) -> Result<u64, JobError> {
+		let conn = self.conn.as_ref();
+
+		let cancelled = job::Entity::update_many()
+			.filter(job::Column::Status.eq(JobStatus::Running.to_string()))
+			.filter(job::Column::Payload.is_null())
+			.col_expr(
+				job::Column::Status,
+				Expr::value(JobStatus::Cancelled.to_string()),
+			)
+			.col_expr(
+				job::Column::CompletedAt,
+				Expr::value(Some(Utc::now().to_rfc3339())),
+			)
+			.exec(conn)
+			.await?
+			.rows_affected;
+
+		let interrupted = job::Entity::update_many()
+			.filter(job::Column::Status.eq(JobStatus::Running.to_string()))
+			.col_expr(
+				job::Column::Status,
+				Expr::value(JobStatus::Queued.to_string()),
+			)
+			.exec(conn)
+			.await?
+			.rows_affected;
+
+		let queued = job::Entity::find()
+			.filter(job::Column::Status.eq(JobStatus::Queued.to_string()))
+			.filter(job::Column::Payload.is_not_null())
+			.count(conn)
+			.await?;
+		for _ in 0..queued {
+			self.signal().await?;
+		}
+
+		tracing::info!(cancelled, interrupted, queued, "Restored job queue");
+
+		Ok(queued)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use ::tests::db::test_database;
+
+	use super::*;
+
+	async fn test_storage() -> JobStorage {
+		JobStorage::new(Arc::new(test_database().await))
+	}
+
+	async fn status_of(storage: &JobStorage, id: &str) -> JobStatus {
+		job::Entity::find_by_id(id)
+			.one(storage.conn.as_ref())
+			.await
+			.unwrap()
+			.unwrap()
+			.status
+	}
+
+	fn library_scan(id: &str) -> StumpJob {
+		StumpJob::library_scan(id.to_string(), format!("/books/{id}"), None)
+	}
+
+	#[tokio::test]
+	async fn test_claim_in_order() {
+		let storage = test_storage().await.with_limits(JobConcurrencyLimits {
+			scan: 2,
+			..Default::default()
+		});
+		let first = storage.enqueue(library_scan("1")).await.unwrap();
+		let second = storage.enqueue(library_scan("2")).await.unwrap();
+		assert_eq!(status_of(&storage, &first).await, JobStatus::Queued);
+
+		let claimed = storage.claim_next().await.unwrap().unwrap();
+		assert_eq!(claimed.id, first);
+		assert!(matches!(claimed.job, StumpJob::LibraryScan { .. }));
+		assert_eq!(status_of(&storage, &first).await, JobStatus::Running);
+
+		let claimed = storage.claim_next().await.unwrap().unwrap();
+		assert_eq!(claimed.id, second);
+		assert!(storage.claim_next().await.unwrap().is_none());
+	}
+
+	#[tokio::test]
+	async fn test_claim_by_priority() {
+		let storage = test_storage().await;
+		let analysis = storage
+			.enqueue(StumpJob::detect_duplicates())
+			.await
+			.unwrap();
+		let scan = storage.enqueue(library_scan("1")).await.unwrap();
+
+		let queued = storage.list_queued().await.unwrap();
+		assert_eq!(
+			queued.iter().map(|job| job.id.clone()).collect::<Vec<_>>(),
+			vec![scan.clone(), analysis.clone()]
+		);
+		assert_eq!(queued[0].category, Some(JobCategory::Scan));
+		assert_eq!(queued[1].category, Some(JobCategory::Analysis));
+
+		assert_eq!(storage.claim_next().await.unwrap().unwrap().id, scan);
+		assert_eq!(storage.claim_next().await.unwrap().unwrap().id, analysis);
+	}
+
+	#[tokio::test]
+	async fn test_concurrency_limit() {
+		let storage = test_storage().await;
+		let first = storage.enqueue(library_scan("1")).await.unwrap();
+		let second = storage.enqueue(library_scan("2")).await.unwrap();
+		let analysis = storage
+			.enqueue(StumpJob::detect_duplicates())
+			.await
+			.unwrap();
+
+		assert_eq!(storage.claim_next().await.unwrap().unwrap().id, first);
+		// The second scan is held back while the first is running, but other categories
+		// aren't
+		assert_eq!(storage.claim_next().await.unwrap().unwrap().id, analysis);
+		assert!(storage.claim_next().await.unwrap().is_none());
+		assert_eq!(status_of(&storage, &second).await, JobStatus::Queued);
+
+		job::Entity::update_many()
+			.filter(job::Column::Id.eq(&first))
+			.col_expr(
+				job::Column::Status,
+				Expr::value(JobStatus::Completed.to_string()),
+			)
+			.exec(storage.conn.as_ref())
+			.await
+			.unwrap();
+		assert_eq!(storage.claim_next().await.unwrap().unwrap().id, second);
+	}
+
+	#[tokio::test]
+	async fn test_enqueue_deduplicates() {
+		let storage = test_storage().await;
+		let first = storage.enqueue(library_scan("1")).await.unwrap();
+		assert_eq!(storage.enqueue(library_scan("1")).await.unwrap(), first);
+		assert_ne!(storage.enqueue(library_scan("2")).await.unwrap(), first);
+
+		// Once the job has started, an identical job may be queued again
+		storage.claim_next().await.unwrap().unwrap();
+		assert_ne!(storage.enqueue(library_scan("1")).await.unwrap(), first);
+	}
+
+	#[tokio::test]
+	async fn test_reorder_queued() {
+		let storage = test_storage().await;
+		let scan = storage.enqueue(library_scan("1")).await.unwrap();
+		let first = storage
+			.enqueue(StumpJob::detect_duplicates())
+			.await
+			.unwrap();
+		let second = storage.enqueue(library_scan("2")).await.unwrap();
+
+		// The analysis job is moved ahead of both scans, despite its lower priority
+		let moved = storage
+			.reorder_queued(&[first.clone(), second.clone(), "missing".to_string()])
+			.await
+			.unwrap();
+		assert_eq!(moved, 2);
+
+		let queued = storage.list_queued().await.unwrap();
+		assert_eq!(
+			queued.iter().map(|job| job.id.clone()).collect::<Vec<_>>(),
+			vec![first, second, scan]
+		);
+	}
+
+	#[tokio::test]
+	async fn test_retry_failed_tasks() {
+		let storage = test_storage().await;
+		let id = storage
+			.enqueue(StumpJob::detect_duplicates())
+			.await
+			.unwrap();
+		storage.claim_next().await.unwrap().unwrap();
+
+		// A running job can't be retried yet
+		assert!(storage.retry_failed_tasks(&id).await.unwrap().is_none());
+
+		let failed_tasks = serde_json::to_vec(&vec!["first", "second"]).unwrap();
+		job::Entity::update_many()
+			.filter(job::Column::Id.eq(&id))
+			.col_expr(
+				job::Column::Status,
+				Expr::value(JobStatus::Completed.to_string()),
+			)
+			.col_expr(job::Column::FailedTasks, Expr::value(Some(failed_tasks)))
+			.exec(storage.conn.as_ref())
+			.await
+			.unwrap();
+
+		let retry = storage.retry_failed_tasks(&id).await.unwrap().unwrap();
+		assert_ne!(retry.id, id);
+		assert_eq!(retry.status, JobStatus::Queued);
+		assert_eq!(retry.category, Some(JobCategory::Analysis));
+		// The failed tasks were moved to the retry, so they can't be retried twice
+		assert!(storage.retry_failed_tasks(&id).await.unwrap().is_none());
+
+		// A retry only runs some of the tasks, so a new identical job isn't deduplicated
+		assert_ne!(
+			storage
+				.enqueue(StumpJob::detect_duplicates())
+				.await
+				.unwrap(),
+			retry.id
+		);
+
+		let claimed = storage.claim_next().await.unwrap().unwrap();
+		assert_eq!(claimed.id, retry.id);
+		let state = serde_json::from_slice::<WorkingState<(), String>>(
+			&claimed.save_state.unwrap(),
+		)
+		.unwrap();
+		assert_eq!(state.tasks, vec!["first", "second"]);
+		assert!(claimed.failed_tasks.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_fail_running() {
+		let storage = test_storage().await;
+		let id = storage.enqueue(library_scan("1")).await.unwrap();
+		// Only a job which was claimed can be failed this way
+		assert!(!storage.fail_running(&id, "Boom").await.unwrap());
+
+		storage.claim_next().await.unwrap().unwrap();
+		assert!(storage.fail_running(&id, "Boom").await.unwrap());
+		assert_eq!(status_of(&storage, &id).await, JobStatus::Failed);
+		assert!(!storage.fail_running(&id, "Boom").await.unwrap());
+
+		let logs = log::Entity::find()
+			.filter(log::Column::JobId.eq(&id))
+			.all(storage.conn.as_ref())
+			.await
+			.unwrap();
+		assert_eq!(logs.len(), 1);
+		assert_eq!(logs[0].level, LogLevel::Error);
+		assert_eq!(logs[0].message, "Boom");
+
+		// The failed job isn't requeued when the queue is restored
+		assert_eq!(storage.restore().await.unwrap(), 0);
+	}
+
+	#[tokio::test]
+	async fn test_cancel_queued() {
+		let storage = test_storage().await;
+		let id = storage
+			.enqueue(StumpJob::detect_duplicates())
+			.await
+			.unwrap();
+
+		assert!(storage.cancel_queued(&id).await.unwrap());
+		assert_eq!(status_of(&storage, &id).await, JobStatus::Cancelled);
+		assert!(storage.claim_next().await.unwrap().is_none());
+		assert!(!storage.cancel_queued(&id).await.unwrap());
+	}
+
+	#[tokio::test]
+	async fn test_pause_and_resume_queued() {
+		let storage = test_storage().await;
+		let id = storage
+			.enqueue(StumpJob::detect_duplicates())
+			.await
+			.unwrap();
+
+		assert!(storage.pause_queued(&id).await.unwrap());
+		assert_eq!(status_of(&storage, &id).await, JobStatus::Paused);
+		assert!(storage.claim_next().await.unwrap().is_none());
+
+		// Paused jobs are left alone on restart
+		assert_eq!(storage.restore().await.unwrap(), 0);
+		assert_eq!(status_of(&storage, &id).await, JobStatus::Paused);
+
+		assert!(storage.resume(&id).await.unwrap());
+		assert!(!storage.resume(&id).await.unwrap());
+		let claimed = storage.claim_next().await.unwrap().unwrap();
+		assert_eq!(claimed.id, id);
+	}
+
+	#[tokio::test]
+	async fn test_restore_interrupted_job() {
+		let storage = test_storage().await;
+		let id = storage
+			.enqueue(StumpJob::detect_duplicates())
+			.await
+			.unwrap();
+		storage.claim_next().await.unwrap().unwrap();
+
+		job::Entity::update_many()
+			.filter(job::Column::Id.eq(&id))
+			.col_expr(job::Column::SaveState, Expr::value(Some(b"{}".to_vec())))
+			.exec(storage.conn.as_ref())
+			.await
+			.unwrap();
+
+		// A job from before the queue was persisted, which can't be resumed
+		let legacy = job::ActiveModel {
+			id: Set("legacy".to_string()),
+			name: Set("library_scan".to_string()),
+			status: Set(JobStatus::Running),
+			..Default::default()
+		}
+		.insert(storage.conn.as_ref())
+		.await
+		.unwrap();
+
+		assert_eq!(storage.restore().await.unwrap(), 1);
+		assert_eq!(status_of(&storage, &legacy.id).await, JobStatus::Cancelled);
+
+		// The requeued job still counts as an identical job, even though it has a saved state
+		assert_eq!(
+			storage
+				.enqueue(StumpJob::detect_duplicates())
+				.await
+				.unwrap(),
+			id
+		);
+
+		let claimed = storage.claim_next().await.unwrap().unwrap();
+		assert_eq!(claimed.id, id);
+		assert_eq!(claimed.save_state, Some(b"{}".to_vec()));
+	}
+}
@@ -1,16 +1,34 @@
 use serde::{Deserialize, Serialize};
 
-use crate::filesystem::{
-	image::{PlaceholderGenerationJobConfig, ThumbnailGenerationJobParams},
-	media::analysis::AnalysisJobConfig,
-	metadata::MetadataFetchJobParams,
-	scanner::ScanOptions,
+use crate::{
+	filesystem::{
+		image::{
+			PlaceholderGenerationJob, PlaceholderGenerationJobConfig,
+			ThumbnailGenerationJob, ThumbnailGenerationJobParams,
+		},
+		media::{
+			analysis::{AnalysisJobConfig, AnalyzeMediaJob},
+			convert::{ConvertMediaJob, ConvertMediaJobConfig},
+			duplicates::DetectDuplicatesJob,
+			write_back::{WriteBackMetadataJob, WriteBackMetadataJobConfig},
+		},
+		metadata::{MetadataFetchJob, MetadataFetchJobParams},
+		scanner::{LibraryCleanupJob, LibraryScanJob, ScanOptions, SeriesScanJob},
+	},
+	job::{DatabaseBackupJob, HistoryPruningJob, JobLifecycle},
+	opds::OpdsCacheWarmupJob,
 };
 
-use models::shared::image_processor_options::ImageProcessorOptions;
+use models::{
+	entity::scheduled_job::{
+		DatabaseBackupConfig, HistoryPruningConfig, LibraryCleanupConfig,
+		OpdsCacheWarmupConfig,
+	},
+	shared::{enums::JobCategory, image_processor_options::ImageProcessorOptions},
+};
 
 /// A unified job enum that can represent any job in the system.
-/// This is the type stored in the apalis `MemoryStorage` and is what
+/// This is the type persisted in the [`JobStorage`](super::JobStorage) and is what
 /// gets enqueued via `Ctx::enqueue()`.
 ///
 /// Each variant contains the data needed to construct and run the corresponding job.
@@ -21,6 +39,10 @@ pub enum StumpJob {
 		id: String,
 		path: String,
 		options: Option<ScanOptions>,
+		/// Whether the scan was started by the library watcher, rather than by a user or
+		/// the scheduler
+		#[serde(default)]
+		watched: bool,
 	},
 	SeriesScan {
 		id: String,
@@ -40,18 +62,65 @@ pub enum StumpJob {
 	AnalyzeMedia {
 		config: AnalysisJobConfig,
 	},
+	WriteBackMetadata {
+		config: WriteBackMetadataJobConfig,
+	},
+	ConvertMedia {
+		config: ConvertMediaJobConfig,
+	},
+	DetectDuplicates,
+	LibraryCleanup {
+		config: LibraryCleanupConfig,
+	},
+	DatabaseBackup {
+		config: DatabaseBackupConfig,
+	},
+	HistoryPruning {
+		config: HistoryPruningConfig,
+	},
+	OpdsCacheWarmup {
+		config: OpdsCacheWarmupConfig,
+	},
 }
 
 impl StumpJob {
-	/// Returns the human-readable name of the job
+	/// Returns the human-readable name of the job, which is the [`JobLifecycle::NAME`] of
+	/// the job it runs
 	pub fn name(&self) -> &'static str {
 		match self {
-			StumpJob::LibraryScan { .. } => "library_scan",
-			StumpJob::SeriesScan { .. } => "series_scan",
-			StumpJob::ThumbnailGeneration { .. } => "thumbnail_generation",
-			StumpJob::PlaceholderGeneration { .. } => "placeholder_generation",
-			StumpJob::MetadataFetch { .. } => "metadata_fetch",
-			StumpJob::AnalyzeMedia { .. } => "analyze_media",
+			StumpJob::LibraryScan { .. } => LibraryScanJob::NAME,
+			StumpJob::SeriesScan { .. } => SeriesScanJob::NAME,
+			StumpJob::ThumbnailGeneration { .. } => ThumbnailGenerationJob::NAME,
+			StumpJob::PlaceholderGeneration { .. } => PlaceholderGenerationJob::NAME,
+			StumpJob::MetadataFetch { .. } => MetadataFetchJob::NAME,
+			StumpJob::AnalyzeMedia { .. } => AnalyzeMediaJob::NAME,
+			StumpJob::WriteBackMetadata { .. } => WriteBackMetadataJob::NAME,
+			StumpJob::ConvertMedia { .. } => ConvertMediaJob::NAME,
+			StumpJob::DetectDuplicates => DetectDuplicatesJob::NAME,
+			StumpJob::LibraryCleanup { .. } => LibraryCleanupJob::NAME,
+			StumpJob::DatabaseBackup { .. } => DatabaseBackupJob::NAME,
+			StumpJob::HistoryPruning { .. } => HistoryPruningJob::NAME,
+			StumpJob::OpdsCacheWarmup { .. } => OpdsCacheWarmupJob::NAME,
+		}
+	}
+
+	/// Returns the [`JobCategory`] of the job, which determines its priority in the queue
+	/// and how many jobs like it may run at once
+	pub fn category(&self) -> JobCategory {
+		match self {
+			StumpJob::LibraryScan { .. }
+			| StumpJob::SeriesScan { .. }
+			| StumpJob::LibraryCleanup { .. } => JobCategory::Scan,
+			StumpJob::MetadataFetch { .. } => JobCategory::Metadata,
+			StumpJob::ThumbnailGeneration { .. }
+			| StumpJob::PlaceholderGeneration { .. }
+			| StumpJob::OpdsCacheWarmup { .. } => JobCategory::Thumbnail,
+			StumpJob::AnalyzeMedia { .. }
+			| StumpJob::WriteBackMetadata { .. }
+			| StumpJob::ConvertMedia { .. }
+			| StumpJob::DetectDuplicates
+			| StumpJob::DatabaseBackup { .. }
+			| StumpJob::HistoryPruning { .. } => JobCategory::Analysis,
 		}
 	}
 
@@ -73,11 +142,41 @@ impl StumpJob {
 			StumpJob::AnalyzeMedia { config } => {
 				Some(format!("Analyze media: {:?}", config.scope))
 			},
+			StumpJob::WriteBackMetadata { config } => {
+				Some(format!("Write back metadata: {:?}", config.scope))
+			},
+			StumpJob::ConvertMedia { config } => {
+				Some(format!("Convert media: {:?}", config.scope))
+			},
+			StumpJob::DetectDuplicates => Some("Detect duplicate media".to_string()),
+			StumpJob::LibraryCleanup { .. } => Some("Clean up libraries".to_string()),
+			StumpJob::DatabaseBackup { .. } => Some("Back up the database".to_string()),
+			StumpJob::HistoryPruning { .. } => {
+				Some("Prune logs and job history".to_string())
+			},
+			StumpJob::OpdsCacheWarmup { .. } => {
+				Some("Warm the OPDS page cache".to_string())
+			},
 		}
 	}
 
 	pub fn library_scan(id: String, path: String, options: Option<ScanOptions>) -> Self {
-		StumpJob::LibraryScan { id, path, options }
+		StumpJob::LibraryScan {
+			id,
+			path,
+			options,
+			watched: false,
+		}
+	}
+
+	/// A library scan started by the library watcher after a change on disk
+	pub fn watched_library_scan(id: String, path: String) -> Self {
+		StumpJob::LibraryScan {
+			id,
+			path,
+			options: None,
+			watched: true,
+		}
 	}
 
 	pub fn series_scan(id: String, path: String, options: Option<ScanOptions>) -> Self {
@@ -102,4 +201,53 @@ impl StumpJob {
 	pub fn analyze_media(config: AnalysisJobConfig) -> Self {
 		StumpJob::AnalyzeMedia { config }
 	}
+
+	pub fn write_back_metadata(config: WriteBackMetadataJobConfig) -> Self {
+		StumpJob::WriteBackMetadata { config }
+	}
+
+	pub fn convert_media(config: ConvertMediaJobConfig) -> Self {
+		StumpJob::ConvertMedia { config }
+	}
+
+	pub fn detect_duplicates() -> Self {
+		StumpJob::DetectDuplicates
+	}
+
+	pub fn library_cleanup(config: LibraryCleanupConfig) -> Self {
+		StumpJob::LibraryCleanup { config }
+	}
+
+	pub fn database_backup(config: DatabaseBackupConfig) -> Self {
+		StumpJob::DatabaseBackup { config }
+	}
+
+	pub fn history_pruning(config: HistoryPruningConfig) -> Self {
+		StumpJob::HistoryPruning { config }
+	}
+
+	pub fn opds_cache_warmup(config: OpdsCacheWarmupConfig) -> Self {
+		StumpJob::OpdsCacheWarmup { config }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_library_scan_payload_without_watched() {
+		let job: StumpJob = serde_json::from_str(
+			r#"{"type":"LibraryScan","id":"1","path":"/books","options":null}"#,
+		)
+		.unwrap();
+		assert!(matches!(job, StumpJob::LibraryScan { watched: false, .. }));
+	}
+
+	#[test]
+	fn test_category_priority() {
+		assert!(JobCategory::Scan.priority() > JobCategory::Metadata.priority());
+		assert!(JobCategory::Metadata.priority() > JobCategory::Thumbnail.priority());
+		assert!(JobCategory::Thumbnail.priority() > JobCategory::Analysis.priority());
+	}
 }
@@ -0,0 +1,513 @@
+//! Kobo devices only sync EPUBs. Libraries may opt in to converting their comics and PDFs
+//! into fixed-layout EPUBs, which are generated when a book is first downloaded from the pages
+//! the file processors already expose and cached on disk until the source file changes.
+
+use std::{
+	io::{Cursor, Seek, Write},
+	path::{Path, PathBuf},
+};
+
+use models::entity::{library, library_config, media, series};
+use sea_orm::{prelude::*, Condition, QuerySelect, QueryTrait};
+use tokio::task::spawn_blocking;
+use zip::{write::FileOptions, CompressionMethod, ZipWriter};
+
+use crate::{
+	config::StumpConfig,
+	filesystem::{
+		image::load_image,
+		media::{get_page, get_page_count},
+		ContentType, FileError,
+	},
+};
+
+/// The extensions of books which may be converted to an EPUB for Kobo sync
+pub const KOBO_CONVERTIBLE_EXTENSIONS: [&str; 7] =
+	["cbz", "zip", "cbr", "rar", "cb7", "7z", "pdf"];
+
+/// Whether a book with the given extension must be converted before it can be sent to a Kobo
+pub fn requires_kobo_conversion(extension: &str) -> bool {
+	!extension.eq_ignore_ascii_case("epub")
+}
+
+/// A condition matching the convertible books in libraries whose config matches the given
+/// condition
+fn convertible_in_libraries(config_condition: Condition) -> Condition {
+	let configs = library_config::Entity::find()
+		.select_only()
+		.column(library_config::Column::Id)
+		.filter(config_condition)
+		.into_query();
+	let libraries = library::Entity::find()
+		.select_only()
+		.column(library::Column::Id)
+		.filter(library::Column::ConfigId.in_subquery(configs))
+		.into_query();
+	let series = series::Entity::find()
+		.select_only()
+		.column(series::Column::Id)
+		.filter(series::Column::LibraryId.in_subquery(libraries))
+		.into_query();
+
+	Condition::all()
+		.add(media::Column::Extension.is_in(KOBO_CONVERTIBLE_EXTENSIONS))
+		.add(media::Column::SeriesId.in_subquery(series))
+}
+
+/// A condition matching the media which can be synced to a Kobo: EPUBs, as well as any
+/// convertible books in libraries which opted in to Kobo conversion.
+pub fn kobo_syncable_condition() -> Condition {
+	Condition::any()
+		.add(media::Column::Extension.eq("epub"))
+		.add(convertible_in_libraries(
+			Condition::all().add(library_config::Column::KoboConvertToEpub.eq(true)),
+		))
+}
+
+/// A condition matching the convertible books in libraries which opted in to Kobo conversion
+/// at or after the given time. These books weren't synced before then, regardless of when
+/// they were created.
+pub fn kobo_conversion_enabled_since(since: DateTimeWithTimeZone) -> Condition {
+	convertible_in_libraries(
+		Condition::all()
+			.add(library_config::Column::KoboConvertToEpub.eq(true))
+			.add(library_config::Column::KoboConvertEnabledAt.gte(since)),
+	)
+}
+
+/// The key used to identify a generated EPUB for a given version of the source file. The
+/// file hash is preferred, but libraries may not generate hashes, in which case the size and
+/// modification time of the source file are used instead.
+fn cache_key(book: &media::Model) -> Result<String, FileError> {
+	if let Some(hash) = book.hash.as_ref().filter(|h| !h.is_empty()) {
+		return Ok(hash.clone());
+	}
+
+	let metadata = std::fs::metadata(&book.path)?;
+	let modified = metadata
+		.modified()?
+		.duration_since(std::time::UNIX_EPOCH)
+		.map(|d| d.as_secs())
+		.unwrap_or_default();
+	Ok(format!("{}-{}", metadata.len(), modified))
+}
+
+/// The extension of the temporary files EPUBs are written to while they're generated
+const TEMP_EXTENSION: &str = ".tmp";
+
+/// Returns a `PathBuf` to the directory containing the generated Kobo EPUBs
+fn kobo_cache_dir(config: &StumpConfig) -> PathBuf {
+	config.get_cache_dir().join("kobo")
+}
+
+/// Get the path to the fixed-layout EPUB for the given book, generating it if it hasn't been
+/// generated yet or if the source file changed since it was generated.
+pub async fn get_or_create_kobo_epub(
+	book: &media::Model,
+	config: &StumpConfig,
+) -> Result<PathBuf, FileError> {
+	let book = book.clone();
+	let config = config.clone();
+
+	spawn_blocking(move || get_or_create_kobo_epub_sync(&book, &config))
+		.await
+		.map_err(|e| FileError::UnknownError(e.to_string()))?
+}
+
+/// The size of the file a Kobo downloads for the given book. Books are only converted once
+/// they are downloaded, so a book which requires conversion reports the size of its cached
+/// EPUB if it was already generated, and the size of its source file as an estimate otherwise.
+pub async fn kobo_download_size(book: &media::Model, config: &StumpConfig) -> u64 {
+	let source_size = u64::try_from(book.size).unwrap_or(0);
+	if !requires_kobo_conversion(&book.extension) {
+		return source_size;
+	}
+
+	let epub_path = match kobo_epub_path(book, config) {
+		Ok(path) => path,
+		Err(error) => {
+			tracing::warn!(?error, book_id = book.id, "Failed to locate Kobo EPUB");
+			return source_size;
+		},
+	};
+	tokio::fs::metadata(epub_path)
+		.await
+		.map_or(source_size, |metadata| metadata.len())
+}
+
+/// The path the fixed-layout EPUB for the current version of the given book is cached at
+fn kobo_epub_path(
+	book: &media::Model,
+	config: &StumpConfig,
+) -> Result<PathBuf, FileError> {
+	let key = cache_key(book)?;
+	Ok(kobo_cache_dir(config).join(format!("{}-{}.epub", book.id, key)))
+}
+
+fn get_or_create_kobo_epub_sync(
+	book: &media::Model,
+	config: &StumpConfig,
+) -> Result<PathBuf, FileError> {
+	let cache_dir = kobo_cache_dir(config);
+	std::fs::create_dir_all(&cache_dir)?;
+
+	let epub_path = kobo_epub_path(book, config)?;
+	if epub_path.exists() {
+		tracing::trace!(?epub_path, "Using cached Kobo EPUB");
+		return Ok(epub_path);
+	}
+
+	remove_stale_epubs(&cache_dir, &book.id)?;
+
+	tracing::debug!(book_id = book.id, ?epub_path, "Generating Kobo EPUB");
+	// write to a temporary file first, so an interrupted conversion is never served. each
+	// conversion gets its own file, since the same book may be requested concurrently. the
+	// temporary file is removed when it is dropped, unless it was persisted
+	let mut tmp_file = tempfile::Builder::new()
+		.prefix(&format!("{}-", book.id))
+		.suffix(TEMP_EXTENSION)
+		.tempfile_in(&cache_dir)?;
+	write_fixed_layout_epub(book, tmp_file.as_file_mut(), config)?;
+	// another request may have generated the same EPUB in the meantime, in which case
+	// either copy is fine to keep
+	tmp_file.persist(&epub_path).map_err(|e| e.error)?;
+
+	Ok(epub_path)
+}
+
+/// Remove any previously generated EPUBs for the book, which were generated from an older
+/// version of the source file. Temporary files belong to conversions which are still in
+/// progress, so they are left alone
+fn remove_stale_epubs(cache_dir: &Path, book_id: &str) -> Result<(), FileError> {
+	let prefix = format!("{book_id}-");
+	for entry in std::fs::read_dir(cache_dir)?.filter_map(Result::ok) {
+		let is_stale = entry.file_name().to_str().is_some_and(|name| {
+			name.starts_with(&prefix) && !name.ends_with(TEMP_EXTENSION)
+		});
+		if is_stale {
+			tracing::trace!(path = ?entry.path(), "Removing stale Kobo EPUB");
+			std::fs::remove_file(entry.path())?;
+		}
+	}
+	Ok(())
+}
+
+/// A page image which the Kobo is able to render
+struct EpubPage {
+	content_type: ContentType,
+	bytes: Vec<u8>,
+	width: usize,
+	height: usize,
+}
+
+impl EpubPage {
+	fn new(content_type: ContentType, bytes: Vec<u8>) -> Result<Self, FileError> {
+		let (content_type, bytes) = match content_type {
+			ContentType::JPEG | ContentType::PNG | ContentType::GIF => {
+				(content_type, bytes)
+			},
+			// other formats (e.g. the WebP renders of PDF pages) aren't supported in EPUBs
+			_ => {
+				let image = load_image(&bytes)
+					.map_err(|e| FileError::UnknownError(e.to_string()))?;
+				let mut buffer = Cursor::new(Vec::new());
+				image
+					.to_rgb8()
+					.write_to(&mut buffer, image::ImageFormat::Jpeg)?;
+				(ContentType::JPEG, buffer.into_inner())
+			},
+		};
+
+		let size = imagesize::blob_size(&bytes)
+			.map_err(|e| FileError::UnknownError(e.to_string()))?;
+
+		Ok(Self {
+			content_type,
+			bytes,
+			width: size.width,
+			height: size.height,
+		})
+	}
+}
+
+fn escape_xml(value: &str) -> String {
+	value
+		.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+		.replace('"', "&quot;")
+		.replace('\'', "&apos;")
+}
+
+const CONTAINER_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>
+"#;
+
+fn page_xhtml(index: usize, image_name: &str, page: &EpubPage) -> String {
+	format!(
+		r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE html>
+<html xmlns="http://www.w3.org/1999/xhtml" xmlns:epub="http://www.idpf.org/2007/ops">
+<head>
+  <title>Page {index}</title>
+  <meta name="viewport" content="width={width}, height={height}"/>
+  <style>html, body {{ margin: 0; padding: 0; }} img {{ display: block; width: 100%; height: 100%; }}</style>
+</head>
+<body>
+  <img src="../images/{image_name}" alt="Page {index}"/>
+</body>
+</html>
+"#,
+		width = page.width,
+		height = page.height,
+	)
+}
+
+fn nav_xhtml(title: &str) -> String {
+	format!(
+		r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE html>
+<html xmlns="http://www.w3.org/1999/xhtml" xmlns:epub="http://www.idpf.org/2007/ops">
+<head>
+  <title>{title}</title>
+</head>
+<body>
+  <nav epub:type="toc">
+    <ol>
+      <li><a href="pages/page-0001.xhtml">{title}</a></li>
+    </ol>
+  </nav>
+</body>
+</html>
+"#
+	)
+}
+
+fn content_opf(
+	book: &media::Model,
+	title: &str,
+	pages: &[(String, ContentType)],
+) -> String {
+	let modified = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ");
+
+	let mut manifest = String::new();
+	let mut spine = String::new();
+	for (index, (image_name, content_type)) in pages.iter().enumerate() {
+		let number = index + 1;
+		let cover_property = if number == 1 {
+			r#" properties="cover-image""#
+		} else {
+			""
+		};
+		manifest.push_str(&format!(
+			"    <item id=\"image-{number:04}\" href=\"images/{image_name}\" media-type=\"{}\"{cover_property}/>\n",
+			content_type.mime_type()
+		));
+		manifest.push_str(&format!(
+			"    <item id=\"page-{number:04}\" href=\"pages/page-{number:04}.xhtml\" media-type=\"application/xhtml+xml\"/>\n"
+		));
+		spine.push_str(&format!("    <itemref idref=\"page-{number:04}\"/>\n"));
+	}
+
+	format!(
+		r#"<?xml version="1.0" encoding="UTF-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0" unique-identifier="book-id" prefix="rendition: http://www.idpf.org/vocab/rendition/#">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:identifier id="book-id">urn:stump:{id}</dc:identifier>
+    <dc:title>{title}</dc:title>
+    <dc:language>en</dc:language>
+    <meta property="dcterms:modified">{modified}</meta>
+    <meta property="rendition:layout">pre-paginated</meta>
+    <meta property="rendition:orientation">auto</meta>
+    <meta property="rendition:spread">none</meta>
+    <meta name="cover" content="image-0001"/>
+  </metadata>
+  <manifest>
+    <item id="nav" href="nav.xhtml" media-type="application/xhtml+xml" properties="nav"/>
+{manifest}  </manifest>
+  <spine>
+{spine}  </spine>
+</package>
+"#,
+		id = escape_xml(&book.id),
+	)
+}
+
+/// Write a fixed-layout EPUB to `destination`, with one page per page of the source book.
+/// Pages are written as they are read, so only one page is held in memory at a time
+fn write_fixed_layout_epub<W: Write + Seek>(
+	book: &media::Model,
+	destination: W,
+	config: &StumpConfig,
+) -> Result<(), FileError> {
+	let page_count = get_page_count(&book.path, config)?;
+	if page_count < 1 {
+		return Err(FileError::NoImageError);
+	}
+
+	let title = escape_xml(&book.name);
+
+	let mut zip = ZipWriter::new(destination);
+	// the mimetype must be the first entry, and must not be compressed
+	let stored: FileOptions<()> =
+		FileOptions::default().compression_method(CompressionMethod::Stored);
+	let deflated: FileOptions<()> =
+		FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+	zip.start_file("mimetype", stored)?;
+	zip.write_all(b"application/epub+zip")?;
+
+	zip.start_file("META-INF/container.xml", deflated)?;
+	zip.write_all(CONTAINER_XML.as_bytes())?;
+
+	let mut pages = Vec::with_capacity(page_count as usize);
+	for number in 1..=page_count {
+		let (content_type, bytes) = get_page(&book.path, number, config)?;
+		let page = EpubPage::new(content_type, bytes)?;
+
+		let number = number as usize;
+		let image_name = format!("page-{number:04}.{}", page.content_type.extension());
+		zip.start_file(format!("OEBPS/pages/page-{number:04}.xhtml"), deflated)?;
+		zip.write_all(page_xhtml(number, &image_name, &page).as_bytes())?;
+
+		// images are already compressed
+		zip.start_file(format!("OEBPS/images/{image_name}"), stored)?;
+		zip.write_all(&page.bytes)?;
+
+		pages.push((image_name, page.content_type));
+	}
+
+	// the package document lists every page, so it is written once they all are
+	zip.start_file("OEBPS/content.opf", deflated)?;
+	zip.write_all(content_opf(book, &title, &pages).as_bytes())?;
+
+	zip.start_file("OEBPS/nav.xhtml", deflated)?;
+	zip.write_all(nav_xhtml(&title).as_bytes())?;
+
+	zip.finish()?;
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{fs::File, io::Read};
+
+	use models::shared::enums::FileStatus;
+	use zip::ZipArchive;
+
+	use super::*;
+	use crate::filesystem::media::tests::get_test_cbz_path;
+
+	fn book() -> media::Model {
+		media::Model {
+			id: "action-comics-i".to_string(),
+			name: "Action Comics #1".to_string(),
+			size: 2000,
+			extension: "cbz".to_string(),
+			pages: 0,
+			updated_at: None,
+			created_at: chrono::Utc::now().into(),
+			modified_at: None,
+			hash: Some("abc".to_string()),
+			koreader_hash: None,
+			path: get_test_cbz_path(),
+			status: FileStatus::Ready,
+			thumbnail_meta: None,
+			thumbnail_path: None,
+			series_id: None,
+			deleted_at: None,
+		}
+	}
+
+	#[test]
+	fn test_requires_kobo_conversion() {
+		assert!(!requires_kobo_conversion("epub"));
+		assert!(!requires_kobo_conversion("EPUB"));
+		assert!(requires_kobo_conversion("cbz"));
+		assert!(requires_kobo_conversion("pdf"));
+	}
+
+	#[test]
+	fn test_generate_fixed_layout_epub() {
+		let temp_dir = tempfile::tempdir().unwrap();
+		let config = StumpConfig::new(temp_dir.path().to_string_lossy().to_string());
+
+		let book = book();
+		let epub_path = get_or_create_kobo_epub_sync(&book, &config)
+			.expect("failed to generate Kobo EPUB");
+
+		let mut archive = ZipArchive::new(File::open(&epub_path).unwrap()).unwrap();
+		assert_eq!("mimetype", archive.by_index(0).unwrap().name());
+
+		let mut opf = String::new();
+		archive
+			.by_name("OEBPS/content.opf")
+			.unwrap()
+			.read_to_string(&mut opf)
+			.unwrap();
+		assert!(opf.contains("pre-paginated"));
+		assert!(opf.contains("Action Comics #1"));
+
+		let page_count = get_page_count(&book.path, &config).unwrap() as usize;
+		let pages = archive
+			.file_names()
+			.filter(|name| name.starts_with("OEBPS/pages/"))
+			.count();
+		assert_eq!(page_count, pages);
+	}
+
+	#[test]
+	fn test_epub_invalidated_when_hash_changes() {
+		let temp_dir = tempfile::tempdir().unwrap();
+		let config = StumpConfig::new(temp_dir.path().to_string_lossy().to_string());
+
+		let mut book = book();
+		let first = get_or_create_kobo_epub_sync(&book, &config).unwrap();
+		assert_eq!(first, get_or_create_kobo_epub_sync(&book, &config).unwrap());
+
+		book.hash = Some("def".to_string());
+		let second = get_or_create_kobo_epub_sync(&book, &config).unwrap();
+		assert_ne!(first, second);
+		assert!(!first.exists());
+		assert!(second.exists());
+	}
+
+	#[test]
+	fn test_stale_epub_removal_skips_in_progress_conversions() {
+		let temp_dir = tempfile::tempdir().unwrap();
+		let stale = temp_dir.path().join("action-comics-i-abc.epub");
+		let in_progress = temp_dir.path().join("action-comics-i-x1y2z3.tmp");
+		let other_book = temp_dir.path().join("detective-comics-i-abc.epub");
+		for path in [&stale, &in_progress, &other_book] {
+			std::fs::write(path, b"").unwrap();
+		}
+
+		remove_stale_epubs(temp_dir.path(), "action-comics-i").unwrap();
+
+		assert!(!stale.exists());
+		assert!(in_progress.exists());
+		assert!(other_book.exists());
+	}
+
+	#[tokio::test]
+	async fn test_download_size() {
+		let temp_dir = tempfile::tempdir().unwrap();
+		let config = StumpConfig::new(temp_dir.path().to_string_lossy().to_string());
+
+		// The book isn't converted just to report its size
+		let book = book();
+		let source_size = u64::try_from(book.size).unwrap();
+		assert_eq!(kobo_download_size(&book, &config).await, source_size);
+		assert!(!kobo_cache_dir(&config).exists());
+
+		let epub_path = get_or_create_kobo_epub_sync(&book, &config).unwrap();
+		let size = kobo_download_size(&book, &config).await;
+		assert_eq!(std::fs::metadata(epub_path).unwrap().len(), size);
+		assert_ne!(source_size, size);
+	}
+}
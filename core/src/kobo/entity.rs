@@ -1,6 +1,7 @@
 use models::{
 	entity::{
-		finished_reading_session, media, media_metadata, reading_session, user::AuthUser,
+		finished_reading_session, kobo_sync_session::KoboTag, media, media_metadata,
+		reading_session, user::AuthUser,
 	},
 	prefixer::{parse_query_to_model, parse_query_to_model_optional},
 };
@@ -9,8 +10,8 @@ use sea_orm::{
 	prelude::*, sea_query::IntoCondition, FromQueryResult, JoinType, QuerySelect, Select,
 };
 
-use crate::kobo::sync_types::*;
-use chrono::Utc;
+use crate::kobo::{conversion::requires_kobo_conversion, sync_types::*};
+use chrono::{DateTime, Utc};
 
 #[derive(Debug, Clone, FromQueryResult)]
 pub struct ReadingSession {
@@ -105,6 +106,20 @@ impl MediaWithMetadataAndReadingSessions {
 			.filter(media::Column::Id.is_in(ids));
 		apply_reading_session_joins(select, user)
 	}
+
+	/// Whether the user's reading state for this book changed at or after the given time,
+	/// e.g. because progress was made in the web reader or on another device.
+	pub fn reading_state_changed_since(&self, since: DateTimeWithTimeZone) -> bool {
+		let active_session_changed = self
+			.reading_session
+			.as_ref()
+			.is_some_and(|rs| rs.updated_at.unwrap_or(rs.started_at) >= since);
+		let completed_since = self
+			.finished_reading_session_last_completed_at
+			.is_some_and(|completed_at| completed_at >= since);
+
+		active_session_changed || completed_since
+	}
 }
 
 impl FromQueryResult for MediaWithMetadataAndReadingSessions {
@@ -137,7 +152,13 @@ impl FromQueryResult for MediaWithMetadataAndReadingSessions {
 const DUMMY_UUID: &str = "00000000-0000-0000-0000-000000000001";
 
 impl BookMetadata {
-	pub fn from_media(m: &MediaWithMetadataAndReadingSessions, book_url: String) -> Self {
+	/// Build the metadata of a book. The `download_size` is the size of the file the device
+	/// downloads, which differs from the size of the book when it is converted to an EPUB
+	pub fn from_media(
+		m: &MediaWithMetadataAndReadingSessions,
+		book_url: String,
+		download_size: u64,
+	) -> Self {
 		let media_id = &m.media.id;
 
 		let writers = m.metadata.as_ref().and_then(|mm| mm.writers.clone());
@@ -187,8 +208,12 @@ impl BookMetadata {
 				drm_type: "None".to_string(),
 				// this seems to be unrelated to the EPUB 3 spec.
 				// the Kobo ignores books with format: "EPUB".
-				format: Format::EPUB3,
-				size: u64::try_from(m.media.size).unwrap_or(0),
+				format: if requires_kobo_conversion(&m.media.extension) {
+					Format::EPUB3FL
+				} else {
+					Format::EPUB3
+				},
+				size: download_size,
 				platform: "Generic".to_string(),
 				url: book_url,
 			}],
@@ -227,6 +252,28 @@ impl BookMetadata {
 }
 
 impl ReadingState {
+	/// Build the reading state of a book from the user's active and finished reading
+	/// sessions. An active session takes precedence over any previous completions.
+	pub fn from_media(m: &MediaWithMetadataAndReadingSessions) -> Self {
+		let media_id = m.media.id.clone();
+
+		match (
+			m.reading_session.as_ref(),
+			m.finished_reading_session_last_completed_at,
+		) {
+			(Some(active_reading_session), _) => {
+				ReadingState::from_active_reading_session(
+					media_id,
+					active_reading_session,
+				)
+			},
+			(_, Some(last_completed_at)) => {
+				ReadingState::finished(media_id, last_completed_at)
+			},
+			(_, _) => ReadingState::unread(media_id),
+		}
+	}
+
 	fn unread(media_id: String) -> Self {
 		let now = Utc::now();
 
@@ -305,24 +352,13 @@ impl ReadingState {
 }
 
 impl BookEntitlementContainer {
-	pub fn from_media(m: MediaWithMetadataAndReadingSessions, book_url: String) -> Self {
+	pub fn from_media(
+		m: MediaWithMetadataAndReadingSessions,
+		book_url: String,
+		download_size: u64,
+	) -> Self {
 		let media_id = &m.media.id;
-
-		let reading_state = match (
-			m.reading_session.as_ref(),
-			m.finished_reading_session_last_completed_at,
-		) {
-			(Some(active_reading_session), _) => {
-				ReadingState::from_active_reading_session(
-					media_id.to_string(),
-					active_reading_session,
-				)
-			},
-			(_, Some(last_completed_at)) => {
-				ReadingState::finished(media_id.to_string(), last_completed_at)
-			},
-			(_, _) => ReadingState::unread(media_id.to_string()),
-		};
+		let reading_state = ReadingState::from_media(&m);
 
 		BookEntitlementContainer {
 			book_entitlement: BookEntitlement {
@@ -343,12 +379,34 @@ impl BookEntitlementContainer {
 				revision_id: media_id.clone(),
 				status: "Active".to_string(),
 			},
-			book_metadata: BookMetadata::from_media(&m, book_url),
+			book_metadata: BookMetadata::from_media(&m, book_url, download_size),
 			reading_state: Some(reading_state),
 		}
 	}
 }
 
+impl TagContainer {
+	pub fn from_kobo_tag(tag: &KoboTag, last_modified: DateTime<Utc>) -> Self {
+		TagContainer {
+			tag: Tag {
+				created: last_modified,
+				id: tag.id.clone(),
+				items: tag
+					.media_ids
+					.iter()
+					.map(|media_id| TagItem {
+						revision_id: media_id.clone(),
+						type_: "ProductRevisionTagItem".to_string(),
+					})
+					.collect(),
+				last_modified,
+				name: tag.name.clone(),
+				type_: "UserTag".to_string(),
+			},
+		}
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use models::entity::user;
@@ -398,8 +456,11 @@ mod tests {
 
 		let m = load_media(&db, &user, media.id).await;
 
-		let entitlement =
-			BookEntitlementContainer::from_media(m, "https://example.org/".to_string());
+		let entitlement = BookEntitlementContainer::from_media(
+			m,
+			"https://example.org/".to_string(),
+			0,
+		);
 
 		// this is an unread book.
 		let reading_state = entitlement.reading_state.unwrap();
@@ -445,8 +506,11 @@ mod tests {
 
 		let m = load_media(&db, &user, media.id).await;
 
-		let entitlement =
-			BookEntitlementContainer::from_media(m, "https://example.org/".to_string());
+		let entitlement = BookEntitlementContainer::from_media(
+			m,
+			"https://example.org/".to_string(),
+			0,
+		);
 
 		// we're partway through this book.
 		let reading_state = entitlement.reading_state.unwrap();
@@ -491,8 +555,11 @@ mod tests {
 
 		let m = load_media(&db, &user, media.id).await;
 
-		let entitlement =
-			BookEntitlementContainer::from_media(m, "https://example.org/".to_string());
+		let entitlement = BookEntitlementContainer::from_media(
+			m,
+			"https://example.org/".to_string(),
+			0,
+		);
 
 		// we finished this book.
 		let reading_state = entitlement.reading_state.unwrap();
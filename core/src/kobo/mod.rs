@@ -1,2 +1,4 @@
+pub mod conversion;
 pub mod entity;
+pub mod reading_state;
 pub mod sync_types;
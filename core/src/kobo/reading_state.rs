@@ -0,0 +1,393 @@
+// applying reading states reported by a Kobo device to Stump's reading sessions.
+
+use models::entity::{
+	finished_reading_session, media, reading_session, registered_reading_device,
+	user::AuthUser,
+};
+use rust_decimal::prelude::FromPrimitive;
+use sea_orm::{prelude::*, sea_query::OnConflict, Iterable, Set, TransactionTrait};
+
+use crate::kobo::sync_types::*;
+
+/// A Kobo device which reported a reading state. The device is registered as a reading
+/// device so that sessions can be attributed to it.
+pub struct KoboDevice<'a> {
+	pub id: &'a str,
+	pub name: &'a str,
+}
+
+/// The status a reading state update implies. The device doesn't always send a status, in
+/// which case it is inferred from the reported progress.
+fn update_status(update: &ReadingStateUpdate) -> Option<Status> {
+	if let Some(status_info) = update.status_info.as_ref() {
+		return Some(status_info.status);
+	}
+
+	update
+		.current_bookmark
+		.as_ref()
+		.and_then(|b| b.progress_percent)
+		.map(|percent| {
+			if percent >= 100.0 {
+				Status::Finished
+			} else {
+				Status::Reading
+			}
+		})
+}
+
+/// Apply a reading state reported by a Kobo device to the user's reading sessions:
+///
+/// - `Reading` upserts the active reading session with the reported progress
+/// - `Finished` moves the active reading session to a finished reading session, unless the
+///   book was already completed within `dedup_timeout_secs`
+/// - `ReadyToRead` (the book was marked as unread on the device) drops the active reading
+///   session, leaving the reading history untouched
+pub async fn apply_reading_state_update(
+	conn: &DatabaseConnection,
+	user: &AuthUser,
+	update: &ReadingStateUpdate,
+	device: Option<KoboDevice<'_>>,
+	dedup_timeout_secs: i64,
+) -> Result<ReadingStateUpdateResult, DbErr> {
+	let media_id = update.entitlement_id.clone();
+
+	let book = media::Entity::find_for_user(user)
+		.filter(media::Column::Id.eq(media_id.clone()))
+		.one(conn)
+		.await?;
+	if book.is_none() {
+		tracing::warn!(
+			?media_id,
+			"Kobo reported a reading state for an unknown book"
+		);
+		return Ok(ReadingStateUpdateResult::new(
+			media_id,
+			RequestResult::Ignored,
+		));
+	}
+
+	let Some(status) = update_status(update) else {
+		tracing::debug!(?media_id, "Kobo reading state did not contain any progress");
+		return Ok(ReadingStateUpdateResult::new(
+			media_id,
+			RequestResult::Ignored,
+		));
+	};
+
+	let txn = conn.begin().await?;
+
+	let device_id = match device {
+		Some(KoboDevice { id, name }) if !id.is_empty() => {
+			registered_reading_device::Entity::insert(
+				registered_reading_device::ActiveModel {
+					id: Set(id.to_string()),
+					name: Set(name.to_string()),
+					kind: Set(Some("kobo".to_string())),
+				},
+			)
+			.on_conflict(
+				OnConflict::column(registered_reading_device::Column::Id)
+					.update_columns(registered_reading_device::Column::iter().filter(
+						|col| matches!(col, registered_reading_device::Column::Name),
+					))
+					.to_owned(),
+			)
+			.exec(&txn)
+			.await?;
+			Some(id.to_string())
+		},
+		_ => None,
+	};
+
+	let existing_active_session = reading_session::Entity::find()
+		.filter(reading_session::Column::UserId.eq(user.id.clone()))
+		.filter(reading_session::Column::MediaId.eq(media_id.clone()))
+		.one(&txn)
+		.await?;
+
+	let elapsed_seconds = update
+		.statistics
+		.as_ref()
+		.and_then(|s| s.spent_reading_minutes)
+		.map(|minutes| minutes * 60);
+
+	match status {
+		Status::Reading => {
+			let percentage_completed = update
+				.current_bookmark
+				.as_ref()
+				.and_then(|b| b.progress_percent.or(b.content_source_progress_percent))
+				.and_then(|percent| Decimal::from_f32(percent / 100.0))
+				.map(|percent| percent.round_dp(4));
+
+			match existing_active_session {
+				Some(active_session) => {
+					let mut active_session: reading_session::ActiveModel =
+						active_session.into();
+					active_session.device_id = Set(device_id);
+					// A status-only update doesn't carry a location, so the progress is kept
+					if percentage_completed.is_some() {
+						active_session.percentage_completed = Set(percentage_completed);
+					}
+					if elapsed_seconds.is_some() {
+						active_session.elapsed_seconds = Set(elapsed_seconds);
+					}
+					active_session.updated_at = Set(Some(chrono::Utc::now().into()));
+					active_session.update(&txn).await?;
+				},
+				None => {
+					reading_session::ActiveModel {
+						user_id: Set(user.id.clone()),
+						media_id: Set(media_id.clone()),
+						device_id: Set(device_id),
+						percentage_completed: Set(percentage_completed),
+						elapsed_seconds: Set(elapsed_seconds),
+						updated_at: Set(Some(chrono::Utc::now().into())),
+						..Default::default()
+					}
+					.insert(&txn)
+					.await?;
+				},
+			}
+		},
+		Status::Finished => {
+			let recent_completion =
+				finished_reading_session::Entity::recent_completed_record(
+					&txn,
+					&user.id,
+					&media_id,
+					dedup_timeout_secs,
+				)
+				.await?;
+
+			let started_at = existing_active_session
+				.as_ref()
+				.map(|s| s.started_at)
+				.unwrap_or_else(|| chrono::Utc::now().into());
+			let elapsed_seconds = elapsed_seconds.or_else(|| {
+				existing_active_session
+					.as_ref()
+					.and_then(|s| s.elapsed_seconds)
+			});
+
+			if let Some(active_session) = existing_active_session {
+				active_session.delete(&txn).await?;
+			}
+
+			if recent_completion.is_none() {
+				finished_reading_session::ActiveModel {
+					user_id: Set(user.id.clone()),
+					media_id: Set(media_id.clone()),
+					device_id: Set(device_id),
+					started_at: Set(started_at),
+					completed_at: Set(chrono::Utc::now().into()),
+					elapsed_seconds: Set(elapsed_seconds),
+					..Default::default()
+				}
+				.insert(&txn)
+				.await?;
+			} else {
+				tracing::debug!(
+					?media_id,
+					"Book was completed recently, skipping duplicate completion"
+				);
+			}
+		},
+		Status::ReadyToRead => {
+			if let Some(active_session) = existing_active_session {
+				active_session.delete(&txn).await?;
+			}
+		},
+	}
+
+	txn.commit().await?;
+
+	Ok(ReadingStateUpdateResult::new(
+		media_id,
+		RequestResult::Success,
+	))
+}
+
+#[cfg(test)]
+mod tests {
+	use ::tests::db::test_database;
+	use ::tests::fake_data;
+	use sea_orm::DbConn;
+
+	use super::*;
+
+	async fn setup(db: &DbConn) -> (AuthUser, String) {
+		let user = fake_data::User::default().insert(db).await;
+		let user = AuthUser {
+			id: user.id,
+			permissions: vec![],
+			..Default::default()
+		};
+
+		let series = fake_data::Series::default().insert(db).await;
+		let media = fake_data::Media {
+			series_id: series.id.clone(),
+			id: Some("don-quixote".to_string()),
+			..Default::default()
+		}
+		.insert(db)
+		.await;
+
+		(user, media.id)
+	}
+
+	fn update(
+		media_id: &str,
+		status: Status,
+		percent: Option<f32>,
+	) -> ReadingStateUpdate {
+		serde_json::from_value(serde_json::json!({
+			"EntitlementId": media_id,
+			"CurrentBookmark": {
+				"ProgressPercent": percent,
+				"Location": {
+					"Value": "kobo.12.3",
+					"Type": "KoboSpan",
+					"Source": "OEBPS/chapter1.xhtml"
+				}
+			},
+			"Statistics": { "SpentReadingMinutes": 12 },
+			"StatusInfo": { "Status": status },
+		}))
+		.expect("invalid reading state update")
+	}
+
+	#[tokio::test]
+	async fn test_reading_update_creates_active_session() {
+		let db = test_database().await;
+		let (user, media_id) = setup(&db).await;
+
+		let result = apply_reading_state_update(
+			&db,
+			&user,
+			&update(&media_id, Status::Reading, Some(42.0)),
+			None,
+			60,
+		)
+		.await
+		.expect("failed to apply reading state");
+		assert_eq!(RequestResult::Success, result.status_info_result.result);
+
+		let session = reading_session::Entity::find()
+			.one(&db)
+			.await
+			.unwrap()
+			.expect("expected an active reading session");
+		assert_eq!(Some(Decimal::new(42, 2)), session.percentage_completed);
+		assert_eq!(Some(12 * 60), session.elapsed_seconds);
+	}
+
+	#[tokio::test]
+	async fn test_status_only_update_keeps_progress() {
+		let db = test_database().await;
+		let (user, media_id) = setup(&db).await;
+
+		fake_data::ReadingSession {
+			media_id: media_id.clone(),
+			user_id: user.id.clone(),
+			percentage_completed: 0.5,
+		}
+		.insert(&db)
+		.await;
+
+		let status_only: ReadingStateUpdate = serde_json::from_value(serde_json::json!({
+			"EntitlementId": media_id,
+			"StatusInfo": { "Status": Status::Reading },
+		}))
+		.expect("invalid reading state update");
+		apply_reading_state_update(&db, &user, &status_only, None, 60)
+			.await
+			.expect("failed to apply reading state");
+
+		let session = reading_session::Entity::find()
+			.one(&db)
+			.await
+			.unwrap()
+			.expect("expected an active reading session");
+		assert_eq!(Some(Decimal::new(5, 1)), session.percentage_completed);
+	}
+
+	#[tokio::test]
+	async fn test_finished_update_completes_book() {
+		let db = test_database().await;
+		let (user, media_id) = setup(&db).await;
+
+		fake_data::ReadingSession {
+			media_id: media_id.clone(),
+			user_id: user.id.clone(),
+			percentage_completed: 0.9,
+		}
+		.insert(&db)
+		.await;
+
+		apply_reading_state_update(
+			&db,
+			&user,
+			&update(&media_id, Status::Finished, Some(100.0)),
+			Some(KoboDevice {
+				id: "kobo-1",
+				name: "Kobo Libra",
+			}),
+			60,
+		)
+		.await
+		.expect("failed to apply reading state");
+
+		let active_sessions = reading_session::Entity::find().count(&db).await.unwrap();
+		assert_eq!(0, active_sessions);
+
+		let finished_sessions = finished_reading_session::Entity::find()
+			.all(&db)
+			.await
+			.unwrap();
+		assert_eq!(1, finished_sessions.len());
+		assert_eq!(Some("kobo-1".to_string()), finished_sessions[0].device_id);
+	}
+
+	#[tokio::test]
+	async fn test_finished_update_is_deduplicated() {
+		let db = test_database().await;
+		let (user, media_id) = setup(&db).await;
+
+		for _ in 0..2 {
+			apply_reading_state_update(
+				&db,
+				&user,
+				&update(&media_id, Status::Finished, None),
+				None,
+				60,
+			)
+			.await
+			.expect("failed to apply reading state");
+		}
+
+		let finished_sessions = finished_reading_session::Entity::find()
+			.count(&db)
+			.await
+			.unwrap();
+		assert_eq!(1, finished_sessions);
+	}
+
+	#[tokio::test]
+	async fn test_unknown_book_is_ignored() {
+		let db = test_database().await;
+		let (user, _) = setup(&db).await;
+
+		let result = apply_reading_state_update(
+			&db,
+			&user,
+			&update("not-a-book", Status::Reading, Some(10.0)),
+			None,
+			60,
+		)
+		.await
+		.expect("failed to apply reading state");
+		assert_eq!(RequestResult::Ignored, result.status_info_result.result);
+	}
+}
@@ -9,6 +9,9 @@ pub enum SyncItem {
 	ChangedEntitlement(BookEntitlementContainer),
 	ChangedProductMetadata(BookMetadata),
 	ChangedReadingState(ReadingStateContainer),
+	NewTag(TagContainer),
+	ChangedTag(TagContainer),
+	DeletedTag(DeletedTagContainer),
 }
 
 #[derive(Serialize)]
@@ -25,6 +28,46 @@ pub struct ReadingStateContainer {
 	pub reading_state: ReadingState,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct TagContainer {
+	pub tag: Tag,
+}
+
+/// A shelf on the device. Stump's reading lists and smart lists are synced as shelves.
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Tag {
+	pub created: DateTime<Utc>,
+	pub id: String,
+	pub items: Vec<TagItem>,
+	pub last_modified: DateTime<Utc>,
+	pub name: String,
+	// always "UserTag" for shelves created outside of the Kobo store
+	pub type_: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct TagItem {
+	pub revision_id: String,
+	// always "ProductRevisionTagItem" for books
+	pub type_: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DeletedTagContainer {
+	pub tag: DeletedTag,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DeletedTag {
+	pub id: String,
+	pub last_modified: DateTime<Utc>,
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct BookEntitlement {
@@ -153,11 +196,12 @@ pub struct CurrentBookmark {
 	pub location: Option<Location>,
 }
 
-#[derive(Serialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 pub struct Location {
 	pub value: Option<String>,
 	pub type_: Option<String>,
+	#[serde(default)]
 	pub source: String,
 }
 
@@ -175,7 +219,7 @@ pub struct StatusInfo {
 	pub times_started_reading: u32,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
 pub enum Status {
 	ReadyToRead,
 	Finished,
@@ -184,3 +228,87 @@ pub enum Status {
 
 #[derive(Serialize)]
 pub struct Empty {}
+
+/// The body of a `PUT /v1/library/{book_id}/state` request, sent by the device whenever
+/// the reading position or status of a book changes.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct ReadingStateUpdates {
+	pub reading_states: Vec<ReadingStateUpdate>,
+}
+
+/// A reading state as reported by the device. Every section is optional since the device
+/// only sends the parts which changed.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct ReadingStateUpdate {
+	pub entitlement_id: String,
+	pub last_modified: Option<DateTime<Utc>>,
+	pub current_bookmark: Option<CurrentBookmarkUpdate>,
+	pub statistics: Option<StatisticsUpdate>,
+	pub status_info: Option<StatusInfoUpdate>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct CurrentBookmarkUpdate {
+	pub last_modified: Option<DateTime<Utc>>,
+	pub progress_percent: Option<f32>,
+	pub content_source_progress_percent: Option<f32>,
+	pub location: Option<Location>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct StatisticsUpdate {
+	pub last_modified: Option<DateTime<Utc>>,
+	pub spent_reading_minutes: Option<i64>,
+	pub remaining_time_minutes: Option<i64>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct StatusInfoUpdate {
+	pub last_modified: Option<DateTime<Utc>>,
+	pub status: Status,
+}
+
+/// The response to a `PUT /v1/library/{book_id}/state` request.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct ReadingStateUpdateResponse {
+	pub request_result: RequestResult,
+	pub update_results: Vec<ReadingStateUpdateResult>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct ReadingStateUpdateResult {
+	pub entitlement_id: String,
+	pub current_bookmark_result: UpdateResult,
+	pub statistics_result: UpdateResult,
+	pub status_info_result: UpdateResult,
+}
+
+impl ReadingStateUpdateResult {
+	pub fn new(entitlement_id: String, result: RequestResult) -> Self {
+		ReadingStateUpdateResult {
+			entitlement_id,
+			current_bookmark_result: UpdateResult { result },
+			statistics_result: UpdateResult { result },
+			status_info_result: UpdateResult { result },
+		}
+	}
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct UpdateResult {
+	pub result: RequestResult,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum RequestResult {
+	Success,
+	Ignored,
+}
@@ -15,7 +15,9 @@ mod event;
 pub mod filesystem;
 pub mod job;
 pub mod kobo;
+pub mod notifier;
 pub mod opds;
+pub mod reading_list;
 pub mod utils;
 
 use config::logging::STUMP_SHADOW_TEXT;
@@ -0,0 +1,133 @@
+use integrations::{
+	DiscordClient, NotificationClient, NotificationEvent, TelegramClient, WebhookClient,
+};
+use models::entity::{
+	notifier::{self, NotificationEventKind, NotifierConfig},
+	server_config,
+};
+use sea_orm::{prelude::*, DatabaseConnection, SelectColumns};
+
+use crate::{utils::encryption::decrypt_string, CoreError, CoreResult};
+
+/// Get the kind of a [`NotificationEvent`], which is what notifiers subscribe to
+pub fn event_kind(event: &NotificationEvent) -> NotificationEventKind {
+	match event {
+		NotificationEvent::ScanCompleted { .. } => NotificationEventKind::ScanCompleted,
+		NotificationEvent::JobFailed { .. } => NotificationEventKind::JobFailed,
+		NotificationEvent::SeriesDiscovered { .. } => {
+			NotificationEventKind::SeriesDiscovered
+		},
+		NotificationEvent::LibraryMissing { .. } => NotificationEventKind::LibraryMissing,
+		NotificationEvent::MetadataAwaitingReview { .. } => {
+			NotificationEventKind::MetadataAwaitingReview
+		},
+		NotificationEvent::UserRegistered { .. } => NotificationEventKind::UserRegistered,
+		NotificationEvent::BookClubMessage { .. } => {
+			NotificationEventKind::BookClubMessage
+		},
+	}
+}
+
+/// Send an event to every notifier subscribed to it. A notifier which fails to send is
+/// logged and skipped, so that one misconfigured notifier doesn't block the rest
+pub async fn send_notification(
+	conn: &DatabaseConnection,
+	event: NotificationEvent,
+) -> CoreResult<()> {
+	let kind = event_kind(&event);
+	let notifiers = notifier::Entity::find()
+		.all(conn)
+		.await?
+		.into_iter()
+		.filter(|notifier| notifier.is_subscribed_to(kind))
+		.collect::<Vec<_>>();
+
+	if notifiers.is_empty() {
+		tracing::trace!(?kind, "No notifiers subscribed to event");
+		return Ok(());
+	}
+
+	let encryption_key = server_config::Entity::find()
+		.select_column(server_config::Column::EncryptionKey)
+		.one(conn)
+		.await?
+		.and_then(|config| config.encryption_key)
+		.ok_or(CoreError::EncryptionKeyNotSet)?;
+
+	for notifier in notifiers {
+		let result = match NotifierConfig::from_bytes(&notifier.config) {
+			Ok(config) => send_to(config, &encryption_key, event.clone()).await,
+			Err(error) => Err(CoreError::InternalError(error.message)),
+		};
+
+		if let Err(error) = result {
+			tracing::error!(
+				id = notifier.id,
+				?kind,
+				?error,
+				"Failed to send notification"
+			);
+		}
+	}
+
+	Ok(())
+}
+
+async fn send_to(
+	config: NotifierConfig,
+	encryption_key: &String,
+	event: NotificationEvent,
+) -> CoreResult<()> {
+	match config {
+		NotifierConfig::Discord(config) => {
+			DiscordClient::new(config.webhook_url)
+				.send_message(event)
+				.await?
+		},
+		NotifierConfig::Telegram(config) => {
+			let token = decrypt_string(&config.encrypted_token, encryption_key)?;
+			TelegramClient::new(token, config.chat_id)
+				.send_message(event)
+				.await?
+		},
+		NotifierConfig::Webhook(config) => {
+			let secret = config
+				.encrypted_secret
+				.map(|secret| decrypt_string(&secret, encryption_key))
+				.transpose()?;
+			let headers = config
+				.headers
+				.into_iter()
+				.map(|header| {
+					decrypt_string(&header.encrypted_value, encryption_key)
+						.map(|value| (header.name, value))
+				})
+				.collect::<Result<_, _>>()?;
+			WebhookClient::new(config.url, headers, secret)
+				.send_message(event)
+				.await?
+		},
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_event_kind() {
+		let event = NotificationEvent::JobFailed {
+			job_id: "1".to_string(),
+			job_name: "library_scan".to_string(),
+			error: "Oops".to_string(),
+		};
+		assert_eq!(event_kind(&event), NotificationEventKind::JobFailed);
+
+		let event = NotificationEvent::UserRegistered {
+			username: "oromei".to_string(),
+		};
+		assert_eq!(event_kind(&event), NotificationEventKind::UserRegistered);
+	}
+}
@@ -1,2 +1,5 @@
 pub mod v1_2;
 pub mod v2_0;
+mod warmup_job;
+
+pub use warmup_job::{OpdsCacheWarmupJob, OpdsCacheWarmupOutput};
@@ -6,7 +6,7 @@ use std::path::PathBuf;
 use std::vec;
 
 use chrono::{self, DateTime, FixedOffset};
-use models::entity::{library, series};
+use models::entity::{collection, library, series};
 use urlencoding::encode;
 use xml::{writer::XmlEvent, EventWriter};
 
@@ -182,6 +182,31 @@ impl IntoOPDSEntry for OPDSEntryBuilder<series::Model> {
 	}
 }
 
+impl IntoOPDSEntry for OPDSEntryBuilder<collection::Model> {
+	fn into_opds_entry(self) -> OpdsEntry {
+		let mut links = Vec::new();
+
+		let nav_link = OpdsLink::new(
+			OpdsLinkType::Navigation,
+			OpdsLinkRel::Subsection,
+			self.format_url(&format!("collections/{}", self.data.id)),
+		);
+
+		links.push(nav_link);
+
+		OpdsEntry {
+			id: self.data.id,
+			updated: self.data.updated_at.unwrap_or(self.data.created_at),
+			title: self.data.name,
+			summary: None,
+			content: self.data.description,
+			authors: None,
+			links,
+			stream_link: None,
+		}
+	}
+}
+
 impl IntoOPDSEntry for OPDSEntryBuilder<OPDSPublicationEntity> {
 	fn into_opds_entry(self) -> OpdsEntry {
 		let base_url = self.format_url(&format!("books/{}", self.data.media.id));
@@ -2,7 +2,7 @@
 //! https://drafts.opds.io/opds-2.0
 
 use derive_builder::Builder;
-use models::entity::{library, series};
+use models::entity::{collection, library, series};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 
@@ -337,6 +337,20 @@ impl From<series::Model> for OPDSNavigationLink {
 	}
 }
 
+impl From<collection::Model> for OPDSNavigationLink {
+	fn from(collection: collection::Model) -> Self {
+		OPDSNavigationLink {
+			title: collection.name,
+			base_link: OPDSBaseLink {
+				href: format!("/opds/v2.0/collections/{}", collection.id),
+				_type: Some(OPDSLinkType::OpdsJson),
+				rel: Some(OPDSLinkRel::Subsection.item()),
+				..Default::default()
+			},
+		}
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use models::shared::enums::FileStatus;
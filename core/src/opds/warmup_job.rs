@@ -0,0 +1,170 @@
+use async_graphql::SimpleObject;
+use chrono::{Duration, Utc};
+use models::{
+	entity::{library, media, scheduled_job::OpdsCacheWarmupConfig, series},
+	shared::enums::FileStatus,
+};
+use sea_orm::{prelude::*, sea_query::Query, QuerySelect};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	config::StumpConfig,
+	filesystem::media::get_page_async,
+	job::{
+		error::JobError, JobContext, JobExecuteLog, JobLifecycle, JobOutputExt,
+		JobProgress, JobTaskOutput, WorkingState,
+	},
+};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum OpdsCacheWarmupTask {
+	/// Render the first `pages` pages of the PDF at `path`
+	WarmBook { path: String, pages: i32 },
+}
+
+#[derive(Clone, Serialize, Deserialize, Default, Debug, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct OpdsCacheWarmupOutput {
+	/// The number of books whose pages were rendered
+	pub books_warmed: u64,
+	/// The number of pages which were rendered
+	pub pages_rendered: u64,
+}
+
+impl JobOutputExt for OpdsCacheWarmupOutput {
+	fn update(&mut self, updated: Self) {
+		self.books_warmed += updated.books_warmed;
+		self.pages_rendered += updated.pages_rendered;
+	}
+}
+
+/// A job that pre-renders the first pages of PDF books into the PDF page cache, so that
+/// OPDS clients streaming their pages don't wait on the renderer. Each book is its own
+/// task
+#[derive(Clone)]
+pub struct OpdsCacheWarmupJob {
+	pub config: OpdsCacheWarmupConfig,
+}
+
+#[async_trait::async_trait]
+impl JobLifecycle for OpdsCacheWarmupJob {
+	const NAME: &'static str = "opds_cache_warmup";
+
+	type Output = OpdsCacheWarmupOutput;
+	type Task = OpdsCacheWarmupTask;
+
+	fn description(&self) -> Option<String> {
+		Some("Warm the OPDS page cache".to_string())
+	}
+
+	async fn init(
+		&mut self,
+		ctx: &JobContext,
+	) -> Result<WorkingState<Self::Output, Self::Task>, JobError> {
+		if !ctx.config().pdf_cache_pages {
+			return Ok(WorkingState {
+				output: Some(Self::Output::default()),
+				tasks: Default::default(),
+				logs: vec![JobExecuteLog::warn(
+					"PDF page caching is disabled, so there is nothing to warm",
+				)],
+			});
+		}
+
+		// An empty list of libraries warms all of them
+		let mut libraries = Query::select()
+			.column(library::Column::Id)
+			.from(library::Entity)
+			.to_owned();
+		if !self.config.library_ids.is_empty() {
+			libraries
+				.and_where(library::Column::Id.is_in(self.config.library_ids.clone()));
+		}
+
+		let query = media::Entity::find()
+			.select_only()
+			.columns([media::Column::Path, media::Column::Pages])
+			.filter(media::Column::Extension.eq("pdf"))
+			.filter(media::Column::Status.eq(FileStatus::Ready.to_string()))
+			.filter(
+				media::Column::SeriesId.in_subquery(
+					Query::select()
+						.column(series::Column::Id)
+						.from(series::Entity)
+						.and_where(series::Column::LibraryId.in_subquery(libraries))
+						.to_owned(),
+				),
+			);
+		let query = match self.config.added_within_days {
+			Some(days) => {
+				let cutoff: DateTimeWithTimeZone =
+					(Utc::now() - Duration::days(i64::from(days))).into();
+				query.filter(media::Column::CreatedAt.gte(cutoff))
+			},
+			None => query,
+		};
+
+		let page_count = i32::try_from(self.config.page_count).unwrap_or(i32::MAX);
+		let tasks = query
+			.into_tuple::<(String, i32)>()
+			.all(ctx.conn())
+			.await?
+			.into_iter()
+			.map(|(path, pages)| OpdsCacheWarmupTask::WarmBook {
+				path,
+				pages: pages.min(page_count),
+			})
+			.collect::<Vec<_>>();
+
+		Ok(WorkingState {
+			output: Some(Self::Output::default()),
+			tasks: tasks.into(),
+			logs: vec![],
+		})
+	}
+
+	async fn execute_task(
+		&self,
+		ctx: &JobContext,
+		task: Self::Task,
+	) -> Result<JobTaskOutput<Self>, JobError> {
+		let OpdsCacheWarmupTask::WarmBook { path, pages } = task;
+		ctx.report_progress(JobProgress::msg(&format!("Warming pages of {path}")));
+
+		// Every page which should be warmed is rendered explicitly, so there is no need to
+		// prerender the pages around it
+		let render_config = StumpConfig {
+			pdf_prerender_range: 0,
+			..ctx.config().clone()
+		};
+
+		let mut output = OpdsCacheWarmupOutput {
+			books_warmed: 1,
+			pages_rendered: 0,
+		};
+		let mut logs = vec![];
+		for page in 1..=pages {
+			// Rendering a page can be slow, so a cancelled job stops between pages rather
+			// than once the whole book is done
+			if ctx.is_canceled() {
+				break;
+			}
+
+			if let Err(error) = get_page_async(&path, page, &render_config).await {
+				tracing::warn!(?error, %path, page, "Failed to warm PDF page");
+				logs.push(
+					JobExecuteLog::warn(&format!("Failed to warm page {page}: {error}"))
+						.with_ctx(path.clone()),
+				);
+				break;
+			}
+			output.pages_rendered += 1;
+		}
+
+		Ok(JobTaskOutput {
+			output,
+			subtasks: vec![],
+			logs,
+		})
+	}
+}
@@ -0,0 +1,357 @@
+//! Support for ComicRack reading lists (`.cbl` files), which are the most common way comic
+//! reading orders are shared. A CBL file lists each book by its series, number, volume and
+//! year rather than by any ID, so importing one means matching those to the books in the
+//! database.
+
+use std::collections::HashMap;
+
+use models::entity::{
+	media, media_metadata, reading_list_item, series, series_metadata, user::AuthUser,
+};
+use sea_orm::{prelude::*, FromQueryResult, QueryOrder, QuerySelect};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	utils::normalize::{format_number, normalize_name, normalize_number},
+	CoreError, CoreResult,
+};
+
+const XML_DECLARATION: &str = r#"<?xml version="1.0" encoding="utf-8"?>"#;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename = "ReadingList")]
+pub struct CBLReadingList {
+	#[serde(rename = "Name")]
+	pub name: String,
+	#[serde(rename = "NumIssues", default, skip_serializing_if = "Option::is_none")]
+	pub num_issues: Option<usize>,
+	#[serde(rename = "Books", default)]
+	pub books: CBLBooks,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CBLBooks {
+	#[serde(rename = "Book", default)]
+	pub books: Vec<CBLBook>,
+}
+
+/// A book in a CBL file. Any other information about the book, e.g. the database IDs some
+/// tools add, is ignored
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CBLBook {
+	#[serde(rename = "@Series", default, skip_serializing_if = "Option::is_none")]
+	pub series: Option<String>,
+	#[serde(rename = "@Number", default, skip_serializing_if = "Option::is_none")]
+	pub number: Option<String>,
+	#[serde(rename = "@Volume", default, skip_serializing_if = "Option::is_none")]
+	pub volume: Option<String>,
+	#[serde(rename = "@Year", default, skip_serializing_if = "Option::is_none")]
+	pub year: Option<String>,
+}
+
+impl CBLReadingList {
+	pub fn new(name: String, books: Vec<CBLBook>) -> Self {
+		Self {
+			name,
+			num_issues: Some(books.len()),
+			books: CBLBooks { books },
+		}
+	}
+
+	/// Parse the contents of a CBL file
+	pub fn from_xml(contents: &str) -> CoreResult<Self> {
+		// Some tools write a BOM, which the XML parser doesn't expect
+		let contents = contents.trim_start_matches('\u{feff}').trim();
+		quick_xml::de::from_str(contents)
+			.map_err(|error| CoreError::BadRequest(format!("Invalid CBL file: {error}")))
+	}
+
+	pub fn to_xml(&self) -> CoreResult<String> {
+		let body = quick_xml::se::to_string(self)
+			.map_err(|error| CoreError::InternalError(error.to_string()))?;
+		Ok(format!("{XML_DECLARATION}\n{body}"))
+	}
+
+	pub fn books(&self) -> &[CBLBook] {
+		&self.books.books
+	}
+}
+
+/// The information about a media item which is used to match it to a [CBLBook]
+#[derive(Debug, Clone, Default, FromQueryResult)]
+pub struct CBLCandidate {
+	pub id: String,
+	pub series_name: String,
+	pub series_title: Option<String>,
+	pub metadata_series: Option<String>,
+	pub number: Option<Decimal>,
+	pub volume: Option<i32>,
+	pub series_volume: Option<i32>,
+	pub year: Option<i32>,
+}
+
+impl CBLCandidate {
+	fn select_for_user(user: &AuthUser) -> Select<media::Entity> {
+		media::Entity::find_for_user(user)
+			.select_only()
+			.column_as(media::Column::Id, "id")
+			.column_as(series::Column::Name, "series_name")
+			.column_as(series_metadata::Column::Title, "series_title")
+			.column_as(media_metadata::Column::Series, "metadata_series")
+			.column_as(media_metadata::Column::Number, "number")
+			.column_as(media_metadata::Column::Volume, "volume")
+			.column_as(series_metadata::Column::Volume, "series_volume")
+			.column_as(media_metadata::Column::Year, "year")
+			.filter(media::Column::DeletedAt.is_null())
+	}
+
+	/// A select of the candidates the user has access to, ordered by name so that the
+	/// first of several equally good matches is stable
+	pub fn find_for_user(user: &AuthUser) -> Select<media::Entity> {
+		Self::select_for_user(user).order_by_asc(media::Column::Name)
+	}
+
+	/// A select of the books in a reading list which the user has access to, in the order
+	/// of the reading list
+	pub fn find_in_reading_list(
+		user: &AuthUser,
+		reading_list_id: &str,
+	) -> Select<media::Entity> {
+		Self::select_for_user(user)
+			.inner_join(reading_list_item::Entity)
+			.filter(reading_list_item::Column::ReadingListId.eq(reading_list_id))
+			.order_by_asc(reading_list_item::Column::DisplayOrder)
+	}
+
+	/// The normalized series names the candidate is known by
+	fn series_keys(&self) -> Vec<String> {
+		[
+			Some(self.series_name.as_str()),
+			self.series_title.as_deref(),
+			self.metadata_series.as_deref(),
+		]
+		.into_iter()
+		.flatten()
+		.map(normalize_name)
+		.filter(|name| !name.is_empty())
+		.collect()
+	}
+
+	fn volume(&self) -> Option<i32> {
+		self.volume.or(self.series_volume)
+	}
+}
+
+impl From<CBLCandidate> for CBLBook {
+	fn from(candidate: CBLCandidate) -> Self {
+		Self {
+			number: candidate.number.as_ref().map(format_number),
+			volume: candidate.volume().map(|volume| volume.to_string()),
+			year: candidate.year.map(|year| year.to_string()),
+			series: Some(
+				candidate
+					.metadata_series
+					.or(candidate.series_title)
+					.unwrap_or(candidate.series_name),
+			),
+		}
+	}
+}
+
+/// Matches [CBLBook]s to candidates. A book must match a candidate's series and number,
+/// while the volume and year are only used to choose between several candidates
+pub struct CBLMatcher {
+	candidates: HashMap<(String, String), Vec<CBLCandidate>>,
+}
+
+impl CBLMatcher {
+	pub fn new(candidates: Vec<CBLCandidate>) -> Self {
+		let mut by_key = HashMap::<(String, String), Vec<CBLCandidate>>::new();
+		for candidate in candidates {
+			let Some(number) = candidate.number.as_ref().map(format_number) else {
+				continue;
+			};
+			let mut series_keys = candidate.series_keys();
+			series_keys.sort_unstable();
+			series_keys.dedup();
+			for series in series_keys {
+				by_key
+					.entry((series, number.clone()))
+					.or_default()
+					.push(candidate.clone());
+			}
+		}
+		Self { candidates: by_key }
+	}
+
+	/// Find the ID of the media the book refers to, if any
+	pub fn find_match(&self, book: &CBLBook) -> Option<String> {
+		let series = normalize_name(book.series.as_deref()?);
+		let number = normalize_number(book.number.as_deref()?);
+		let candidates = self.candidates.get(&(series, number))?;
+
+		let parse = |value: &Option<String>| {
+			value
+				.as_deref()
+				.and_then(|value| value.trim().parse::<i32>().ok())
+		};
+		let volume = parse(&book.volume);
+		let year = parse(&book.year);
+
+		// CBL volumes are commonly the year the series started, so a volume may match
+		// either the volume or the year of the book
+		let narrowed = candidates
+			.iter()
+			.filter(|candidate| {
+				volume.is_none_or(|volume| {
+					candidate.volume() == Some(volume) || candidate.year == Some(volume)
+				})
+			})
+			.collect::<Vec<_>>();
+		let narrowed = if narrowed.is_empty() {
+			candidates.iter().collect()
+		} else {
+			narrowed
+		};
+		let best = narrowed
+			.iter()
+			.find(|candidate| year.is_some_and(|year| candidate.year == Some(year)))
+			.or(narrowed.first())?;
+
+		Some(best.id.clone())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const CBL: &str = r#"<?xml version="1.0"?>
+<ReadingList xmlns:xsd="http://www.w3.org/2001/XMLSchema" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+  <Name>Batman: Year One</Name>
+  <NumIssues>2</NumIssues>
+  <Books>
+    <Book Series="Batman" Number="404" Volume="1940" Year="1987">
+      <Database Name="cv" Series="796" Issue="38581" />
+    </Book>
+    <Book Series="Batman" Number="405" Volume="1940" Year="1987" />
+  </Books>
+  <Matchers />
+</ReadingList>"#;
+
+	fn candidate(id: &str, series: &str, number: i64) -> CBLCandidate {
+		CBLCandidate {
+			id: id.to_string(),
+			series_name: series.to_string(),
+			number: Some(Decimal::from(number)),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn test_from_xml() {
+		let reading_list = CBLReadingList::from_xml(CBL).unwrap();
+		assert_eq!(reading_list.name, "Batman: Year One");
+		assert_eq!(reading_list.num_issues, Some(2));
+		assert_eq!(
+			reading_list.books(),
+			&[
+				CBLBook {
+					series: Some("Batman".to_string()),
+					number: Some("404".to_string()),
+					volume: Some("1940".to_string()),
+					year: Some("1987".to_string()),
+				},
+				CBLBook {
+					series: Some("Batman".to_string()),
+					number: Some("405".to_string()),
+					volume: Some("1940".to_string()),
+					year: Some("1987".to_string()),
+				},
+			]
+		);
+
+		assert!(CBLReadingList::from_xml("not a reading list").is_err());
+	}
+
+	#[test]
+	fn test_to_xml_round_trip() {
+		let reading_list = CBLReadingList::new(
+			"Crisis & Aftermath".to_string(),
+			vec![CBLBook {
+				series: Some("Crisis on Infinite Earths".to_string()),
+				number: Some("1".to_string()),
+				..Default::default()
+			}],
+		);
+
+		let xml = reading_list.to_xml().unwrap();
+		assert!(xml.starts_with(XML_DECLARATION));
+		assert_eq!(CBLReadingList::from_xml(&xml).unwrap(), reading_list);
+	}
+
+	#[test]
+	fn test_find_match() {
+		let matcher = CBLMatcher::new(vec![
+			candidate("1", "The Amazing Spider-Man", 1),
+			CBLCandidate {
+				year: Some(2018),
+				..candidate("2", "Amazing Spider-Man", 1)
+			},
+			CBLCandidate {
+				metadata_series: Some("Amazing Spider-Man".to_string()),
+				year: Some(1963),
+				..candidate("3", "ASM", 1)
+			},
+		]);
+
+		let book = |series: &str, number: &str, year: Option<&str>| CBLBook {
+			series: Some(series.to_string()),
+			number: Some(number.to_string()),
+			year: year.map(str::to_string),
+			..Default::default()
+		};
+
+		assert_eq!(
+			matcher.find_match(&book("the amazing spider man", "001", None)),
+			Some("1".to_string())
+		);
+		// The year decides between the books in the series
+		assert_eq!(
+			matcher.find_match(&book("Amazing Spider-Man", "1", Some("1963"))),
+			Some("3".to_string())
+		);
+		// A volume may also be the year the series started
+		assert_eq!(
+			matcher.find_match(&CBLBook {
+				volume: Some("2018".to_string()),
+				..book("Amazing Spider-Man", "1.0", None)
+			}),
+			Some("2".to_string())
+		);
+		assert_eq!(
+			matcher.find_match(&book("Amazing Spider-Man", "2", None)),
+			None
+		);
+		assert_eq!(matcher.find_match(&book("Batman", "1", None)), None);
+	}
+
+	#[test]
+	fn test_book_from_candidate() {
+		let book = CBLBook::from(CBLCandidate {
+			number: Some(Decimal::new(10, 1)),
+			series_volume: Some(2018),
+			year: Some(2018),
+			..candidate("1", "Amazing Spider-Man (2018)", 1)
+		});
+		assert_eq!(
+			book,
+			CBLBook {
+				series: Some("Amazing Spider-Man (2018)".to_string()),
+				number: Some("1".to_string()),
+				volume: Some("2018".to_string()),
+				year: Some("2018".to_string()),
+			}
+		);
+	}
+}
@@ -1,4 +1,5 @@
 pub mod encryption;
+pub mod normalize;
 pub mod serde;
 
 pub fn chain_optional_iter<T>(
@@ -0,0 +1,42 @@
+//! Helpers to normalize names and numbers, so that values which are written differently
+//! but mean the same thing compare as equal
+
+use sea_orm::prelude::Decimal;
+
+/// Lowercase the value and strip anything which isn't alphanumeric, so that e.g.
+/// `Spider-Man` and `spider man` compare as equal
+pub fn normalize_name(value: &str) -> String {
+	value
+		.chars()
+		.filter(|c| c.is_alphanumeric())
+		.flat_map(char::to_lowercase)
+		.collect()
+}
+
+/// Format a number without trailing zeros, e.g. `1` rather than `1.00`
+pub fn format_number(number: &Decimal) -> String {
+	number.normalize().to_string()
+}
+
+/// Normalize an issue number, so that e.g. `001`, `1.0` and `1` compare as equal. Numbers
+/// which aren't decimals, like `½`, are compared as they are
+pub fn normalize_number(value: &str) -> String {
+	let value = value.trim();
+	value
+		.parse::<Decimal>()
+		.map(|number| format_number(&number))
+		.unwrap_or_else(|_| value.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_normalize_number() {
+		assert_eq!(normalize_number("001"), "1");
+		assert_eq!(normalize_number(" 1.50 "), "1.5");
+		assert_eq!(normalize_number("½"), "½");
+		assert_eq!(normalize_number("Annual"), "annual");
+	}
+}
@@ -612,8 +612,8 @@ mod tests {
 		shared::{
 			api_key::APIKeyPermissions,
 			enums::{
-				FileStatus, LibraryPattern, LibraryViewMode, ReadingDirection,
-				ReadingImageScaleFit, ReadingMode,
+				EntityVisibility, FileStatus, LibraryPattern, LibraryViewMode,
+				ReadingDirection, ReadingImageScaleFit, ReadingMode,
 			},
 			readium::ReadiumLocator,
 		},
@@ -698,6 +698,7 @@ mod tests {
 			default_library_view_mode: Set(LibraryViewMode::Series),
 			hide_series_view: Set(false),
 			skip_book_overview: Set(false),
+			kobo_convert_to_epub: Set(false),
 			process_thumbnail_colors_even_without_config: Set(false),
 			..Default::default()
 		}
@@ -800,8 +801,8 @@ mod tests {
 			user_id: Set(local_user.id.clone()),
 			media_id: Set(media_list[0].id.clone()),
 			rating: Set(4),
-			content: Set(Some("Great book!".to_string())),
-			is_private: Set(false),
+			review_text: Set(Some("Great book!".to_string())),
+			visibility: Set(EntityVisibility::Public),
 			..Default::default()
 		}
 		.insert(db)
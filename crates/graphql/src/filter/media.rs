@@ -1,20 +1,23 @@
 use async_graphql::InputObject;
 use models::{
-	entity::{finished_reading_session, media, media_tag, reading_session, tag},
-	shared::enums::{FileStatus, ReadingStatus},
+	entity::{
+		finished_reading_session, media, media_tag, reading_session,
+		registered_reading_device, review, tag,
+	},
+	shared::enums::{EntityVisibility, FileStatus, ReadingStatus},
 };
 use sea_orm::{
 	prelude::{DateTimeWithTimeZone, *},
-	sea_query::{ConditionExpression, Expr, Query, SelectStatement},
+	sea_query::{ConditionExpression, Expr, Func, Query, SelectStatement},
 	Condition,
 };
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 
 use super::{
-	apply_numeric_filter, apply_string_filter, media_metadata::MediaMetadataFilterInput,
-	series::SeriesFilterInput, ConceptualFilter, IntoFilter, NumericFilter,
-	StringLikeFilter,
+	apply_numeric_expr_filter, apply_numeric_filter, apply_string_filter,
+	media_metadata::MediaMetadataFilterInput, series::SeriesFilterInput,
+	ConceptualFilter, IntoFilter, NumericFilter, StringLikeFilter,
 };
 
 fn apply_reading_status_filter(
@@ -57,6 +60,36 @@ fn media_tag_name_subquery(filter: StringLikeFilter<String>) -> SelectStatement
 		.to_owned()
 }
 
+/// A subquery for the IDs of the media whose average public rating matches the filter.
+/// Media without any public reviews never match.
+fn media_average_rating_subquery(filter: NumericFilter<f32>) -> SelectStatement {
+	let average = Expr::expr(Func::avg(Expr::col((
+		review::Entity,
+		review::Column::Rating,
+	))));
+
+	// SELECT media_id FROM reviews WHERE visibility = 'PUBLIC' GROUP BY media_id HAVING
+	Query::select()
+		.column(review::Column::MediaId)
+		.from(review::Entity)
+		.and_where(review::Column::Visibility.eq(EntityVisibility::Public))
+		.group_by_col(review::Column::MediaId)
+		.and_having(apply_numeric_expr_filter(average, filter))
+		.to_owned()
+}
+
+/// A subquery for the IDs of the reading devices whose name matches the filter
+fn reading_device_name_subquery(filter: StringLikeFilter<String>) -> SelectStatement {
+	Query::select()
+		.column(registered_reading_device::Column::Id)
+		.from(registered_reading_device::Entity)
+		.cond_where(apply_string_filter(
+			registered_reading_device::Column::Name,
+			filter,
+		))
+		.to_owned()
+}
+
 #[skip_serializing_none]
 #[derive(InputObject, Clone, Serialize, Deserialize, Debug, Default)]
 #[serde(rename_all = "camelCase")]
@@ -84,6 +117,26 @@ pub struct MediaFilterInput {
 
 	#[graphql(default)]
 	pub reading_status: Option<ConceptualFilter<ReadingStatus>>,
+	/// The progress of the user's current reading session for the media, from 0 to 1
+	#[graphql(default)]
+	pub reading_progress: Option<NumericFilter<f32>>,
+	/// When the user started their current reading session for the media
+	#[graphql(default)]
+	pub reading_started_at: Option<NumericFilter<DateTimeWithTimeZone>>,
+	/// When the user last made progress in their current reading session for the media
+	#[graphql(default)]
+	pub reading_updated_at: Option<NumericFilter<DateTimeWithTimeZone>>,
+	/// When the user finished reading the media. Media which were read more than once
+	/// match if any of the times they were finished do
+	#[graphql(default)]
+	pub finished_at: Option<NumericFilter<DateTimeWithTimeZone>>,
+	/// The name of a device the user read the media on, in either their current or a
+	/// finished reading session
+	#[graphql(default)]
+	pub read_on_device: Option<StringLikeFilter<String>>,
+	/// The average rating of the media, from its public reviews
+	#[graphql(default)]
+	pub average_rating: Option<NumericFilter<f32>>,
 
 	#[graphql(default)]
 	pub metadata: Option<MediaMetadataFilterInput>,
@@ -100,6 +153,23 @@ pub struct MediaFilterInput {
 	pub _or: Option<Vec<MediaFilterInput>>,
 }
 
+impl MediaFilterInput {
+	/// Whether the filter, or any filter nested in it, needs the user's reading sessions
+	/// to be joined to the media
+	pub fn uses_reading_sessions(&self) -> bool {
+		self.reading_status.is_some()
+			|| self.reading_progress.is_some()
+			|| self.reading_started_at.is_some()
+			|| self.reading_updated_at.is_some()
+			|| self.finished_at.is_some()
+			|| self.read_on_device.is_some()
+			|| [&self._and, &self._or, &self._not]
+				.into_iter()
+				.flatten()
+				.any(|filters| filters.iter().any(Self::uses_reading_sessions))
+	}
+}
+
 impl IntoFilter for MediaFilterInput {
 	fn into_filter(self) -> sea_orm::Condition {
 		sea_orm::Condition::all()
@@ -181,6 +251,30 @@ impl IntoFilter for MediaFilterInput {
 						.not(),
 				}
 			}))
+			.add_option(self.reading_progress.map(|f| {
+				apply_numeric_filter(reading_session::Column::PercentageCompleted, f)
+			}))
+			.add_option(
+				self.reading_started_at
+					.map(|f| apply_numeric_filter(reading_session::Column::StartedAt, f)),
+			)
+			.add_option(
+				self.reading_updated_at
+					.map(|f| apply_numeric_filter(reading_session::Column::UpdatedAt, f)),
+			)
+			.add_option(self.finished_at.map(|f| {
+				apply_numeric_filter(finished_reading_session::Column::CompletedAt, f)
+			}))
+			.add_option(self.read_on_device.map(|f| {
+				let subquery = reading_device_name_subquery(f);
+				Condition::any()
+					.add(reading_session::Column::DeviceId.in_subquery(subquery.clone()))
+					.add(finished_reading_session::Column::DeviceId.in_subquery(subquery))
+			}))
+			.add_option(self.average_rating.map(|f| {
+				let subquery = media_average_rating_subquery(f);
+				Condition::all().add(media::Column::Id.in_subquery(subquery))
+			}))
 			.add_option(self.tags.map(|f| {
 				let subquery = media_tag_name_subquery(f);
 				Condition::all().add(media::Column::Id.in_subquery(subquery))
@@ -217,14 +311,20 @@ mod tests {
 		let filter = MediaFilterInput {
 			id: None,
 			_and: None,
+			average_rating: None,
 			created_at: None,
 			extension: None,
+			finished_at: None,
 			metadata: None,
 			name: None,
 			_not: None,
 			_or: None,
 			pages: None,
 			path: None,
+			read_on_device: None,
+			reading_progress: None,
+			reading_started_at: None,
+			reading_updated_at: None,
 			reading_status: Some(ConceptualFilter::IsAnyOf(vec![
 				ReadingStatus::Reading,
 				ReadingStatus::Finished,
@@ -253,14 +353,20 @@ mod tests {
 		let filter = MediaFilterInput {
 			id: None,
 			_and: None,
+			average_rating: None,
 			created_at: None,
 			extension: None,
+			finished_at: None,
 			metadata: None,
 			name: None,
 			_not: None,
 			_or: None,
 			pages: None,
 			path: None,
+			read_on_device: None,
+			reading_progress: None,
+			reading_started_at: None,
+			reading_updated_at: None,
 			reading_status: Some(ConceptualFilter::IsNoneOf(vec![
 				ReadingStatus::Reading,
 				ReadingStatus::Finished,
@@ -283,6 +389,63 @@ mod tests {
 			r#"SELECT  FROM "media" WHERE NOT ("reading_sessions"."id" IS NOT NULL OR "finished_reading_sessions"."id" IS NOT NULL)"#
 		);
 	}
+
+	#[test]
+	fn test_reading_progress() {
+		let filter = MediaFilterInput {
+			reading_progress: Some(NumericFilter::Gt(0.5)),
+			read_on_device: Some(StringLikeFilter::Eq("Kobo".to_string())),
+			..Default::default()
+		};
+		assert!(filter.uses_reading_sessions());
+
+		let query = media::Entity::find().filter(filter.into_filter());
+		let sql = query
+			.select_only()
+			.into_query()
+			.to_string(SqliteQueryBuilder);
+
+		assert_eq!(
+			sql,
+			r#"SELECT  FROM "media" WHERE "reading_sessions"."percentage_completed" > 0.5 AND ("reading_sessions"."device_id" IN (SELECT "id" FROM "registered_reading_devices" WHERE "registered_reading_devices"."name" = 'Kobo') OR "finished_reading_sessions"."device_id" IN (SELECT "id" FROM "registered_reading_devices" WHERE "registered_reading_devices"."name" = 'Kobo'))"#
+		);
+	}
+
+	#[test]
+	fn test_uses_reading_sessions_nested() {
+		let filter = MediaFilterInput {
+			_or: Some(vec![MediaFilterInput {
+				_and: Some(vec![MediaFilterInput {
+					finished_at: Some(NumericFilter::Lt(
+						"2025-01-01T00:00:00Z".parse().unwrap(),
+					)),
+					..Default::default()
+				}]),
+				..Default::default()
+			}]),
+			..Default::default()
+		};
+		assert!(filter.uses_reading_sessions());
+		assert!(!MediaFilterInput::default().uses_reading_sessions());
+	}
+
+	#[test]
+	fn test_average_rating() {
+		let filter = MediaFilterInput {
+			average_rating: Some(NumericFilter::Gte(4.0)),
+			..Default::default()
+		};
+		let query = media::Entity::find().filter(filter.into_filter());
+		let sql = query
+			.select_only()
+			.into_query()
+			.to_string(SqliteQueryBuilder);
+
+		assert_eq!(
+			sql,
+			r#"SELECT  FROM "media" WHERE "media"."id" IN (SELECT "media_id" FROM "reviews" WHERE "reviews"."visibility" = 'PUBLIC' GROUP BY "media_id" HAVING AVG("reviews"."rating") >= 4)"#
+		);
+	}
 }
 
 // #[cfg(test)]
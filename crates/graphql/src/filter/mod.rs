@@ -2,7 +2,9 @@ use async_graphql::{InputObject, InputType, OneofObject};
 use filter_gen::IntoFilter;
 use models::shared::enums::{FileStatus, LibraryType, ReadingStatus};
 use sea_orm::{
-	prelude::DateTimeWithTimeZone, sea_query::ConditionExpression, Condition, Value,
+	prelude::DateTimeWithTimeZone,
+	sea_query::{ConditionExpression, Expr, SimpleExpr},
+	Condition, Value,
 };
 use serde::{Deserialize, Serialize};
 
@@ -152,6 +154,35 @@ where
 	}
 }
 
+/// The same as [apply_numeric_filter], but for an expression rather than a column, e.g.
+/// an aggregate in a `HAVING` clause
+pub(crate) fn apply_numeric_expr_filter<T>(
+	expr: Expr,
+	filter: NumericFilter<T>,
+) -> SimpleExpr
+where
+	T: InputType + Into<Value>,
+	NumericRange<T>: InputType,
+{
+	match filter {
+		NumericFilter::Eq(value) => expr.eq(value),
+		NumericFilter::Neq(value) => expr.ne(value),
+		NumericFilter::AnyOf(values) => expr.is_in(values),
+		NumericFilter::NoneOf(values) => expr.is_not_in(values),
+		NumericFilter::Gt(value) => expr.gt(value),
+		NumericFilter::Gte(value) => expr.gte(value),
+		NumericFilter::Lt(value) => expr.lt(value),
+		NumericFilter::Lte(value) => expr.lte(value),
+		NumericFilter::Range(range) => {
+			if range.inclusive {
+				expr.clone().gte(range.from).and(expr.lte(range.to))
+			} else {
+				expr.clone().gt(range.from).and(expr.lt(range.to))
+			}
+		},
+	}
+}
+
 #[derive(OneofObject, Clone, Debug, Serialize, Deserialize)]
 #[graphql(concrete(name = "ComputedFilterReadingStatus", params(ReadingStatus)))]
 #[graphql(concrete(name = "ComputedFilterLibraryType", params(LibraryType)))]
@@ -0,0 +1,68 @@
+use async_graphql::{InputObject, ID};
+use models::{
+	entity::{collection, collection_access_rule},
+	shared::enums::{AccessRole, EntityVisibility},
+};
+use sea_orm::Set;
+
+#[derive(Debug, InputObject)]
+pub struct CreateCollectionInput {
+	pub name: String,
+	pub description: Option<String>,
+	#[graphql(default)]
+	pub visibility: EntityVisibility,
+	/// The series to add to the collection, in order
+	#[graphql(default)]
+	pub series_ids: Vec<ID>,
+}
+
+impl CreateCollectionInput {
+	pub fn into_active_model(self, user_id: &str) -> collection::ActiveModel {
+		collection::ActiveModel {
+			name: Set(self.name),
+			description: Set(self.description),
+			visibility: Set(self.visibility),
+			creator_id: Set(user_id.to_string()),
+			cover_series_id: Set(None),
+			..Default::default()
+		}
+	}
+}
+
+#[derive(Debug, InputObject)]
+pub struct UpdateCollectionInput {
+	pub name: String,
+	pub description: Option<String>,
+	pub visibility: EntityVisibility,
+	/// The series to use as the cover of the collection. It must be in the collection
+	pub cover_series_id: Option<ID>,
+}
+
+impl UpdateCollectionInput {
+	pub fn apply(self, active_model: &mut collection::ActiveModel) {
+		active_model.name = Set(self.name);
+		active_model.description = Set(self.description);
+		active_model.visibility = Set(self.visibility);
+		active_model.cover_series_id = Set(self.cover_series_id.map(|id| id.to_string()));
+	}
+}
+
+#[derive(Debug, InputObject)]
+pub struct CollectionAccessRuleInput {
+	pub user_id: ID,
+	pub role: AccessRole,
+}
+
+impl CollectionAccessRuleInput {
+	pub fn into_active_model(
+		self,
+		collection_id: &str,
+	) -> collection_access_rule::ActiveModel {
+		collection_access_rule::ActiveModel {
+			role: Set(self.role),
+			user_id: Set(self.user_id.to_string()),
+			collection_id: Set(collection_id.to_string()),
+			..Default::default()
+		}
+	}
+}
@@ -3,14 +3,14 @@ use models::{
 	entity::{library, library_config},
 	shared::{
 		enums::{
-			LibraryPattern, LibraryType, LibraryViewMode, ReadingDirection,
-			ReadingImageScaleFit, ReadingMode,
+			LibraryPattern, LibraryType, LibraryViewMode, LibraryWatchMode,
+			ReadingDirection, ReadingImageScaleFit, ReadingMode,
 		},
 		ignore_rules::IgnoreRules,
 		image_processor_options::ImageProcessorOptions,
 	},
 };
-use sea_orm::{prelude::*, Set};
+use sea_orm::{prelude::*, NotSet, Set};
 
 #[derive(Debug, InputObject)]
 pub struct CreateOrUpdateLibraryInput {
@@ -64,11 +64,18 @@ pub struct LibraryConfigInput {
 	pub generate_koreader_hashes: bool,
 	pub process_metadata: bool,
 	pub watch: bool,
+	#[graphql(default)]
+	pub watch_mode: LibraryWatchMode,
+	#[graphql(default = 60, validator(minimum = 1))]
+	pub watch_poll_interval_seconds: i32,
 	pub library_pattern: LibraryPattern,
 	pub library_type: LibraryType,
 	pub default_library_view_mode: LibraryViewMode,
 	pub hide_series_view: bool,
 	pub skip_book_overview: bool,
+	/// Whether comics and PDFs are converted to EPUBs for Kobo sync. This is left unchanged
+	/// when omitted, and off for new libraries
+	pub kobo_convert_to_epub: Option<bool>,
 	pub thumbnail_config: Option<ImageProcessorOptions>,
 	pub process_thumbnail_colors_even_without_config: bool,
 	pub default_reading_dir: ReadingDirection,
@@ -86,11 +93,14 @@ impl LibraryConfigInput {
 			generate_koreader_hashes,
 			process_metadata,
 			watch,
+			watch_mode,
+			watch_poll_interval_seconds,
 			library_pattern,
 			library_type,
 			default_library_view_mode,
 			hide_series_view,
 			skip_book_overview,
+			kobo_convert_to_epub,
 			thumbnail_config,
 			process_thumbnail_colors_even_without_config,
 			default_reading_dir,
@@ -111,11 +121,14 @@ impl LibraryConfigInput {
 			generate_koreader_hashes: Set(generate_koreader_hashes),
 			process_metadata: Set(process_metadata),
 			watch: Set(watch),
+			watch_mode: Set(watch_mode),
+			watch_poll_interval_seconds: Set(watch_poll_interval_seconds),
 			library_pattern: Set(library_pattern),
 			library_type: Set(library_type),
 			default_library_view_mode: Set(default_library_view_mode),
 			hide_series_view: Set(hide_series_view),
 			skip_book_overview: Set(skip_book_overview),
+			kobo_convert_to_epub: kobo_convert_to_epub.map_or(NotSet, Set),
 			thumbnail_config: Set(thumbnail_config),
 			process_thumbnail_colors_even_without_config: Set(
 				process_thumbnail_colors_even_without_config,
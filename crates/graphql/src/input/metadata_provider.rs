@@ -9,7 +9,8 @@ use stump_core::utils::encryption::encrypt_string;
 pub struct CreateMetadataProviderConfigInput {
 	/// The provider type
 	pub provider_type: MetadataProvider,
-	/// The API token for authenticating with the provider
+	/// The API token for authenticating with the provider. This may be empty for providers
+	/// which don't require a token
 	pub api_token: String,
 	/// Whether the provider is enabled
 	pub enabled: Option<bool>,
@@ -25,7 +26,14 @@ impl CreateMetadataProviderConfigInput {
 		self,
 		encryption_key: &String,
 	) -> Result<metadata_provider_config::ActiveModel> {
-		let encrypted_api_token = encrypt_string(&self.api_token, encryption_key)?;
+		let encrypted_api_token = if self.api_token.is_empty() {
+			if self.provider_type.requires_api_token() {
+				return Err("An API token is required for this provider".into());
+			}
+			None
+		} else {
+			Some(encrypt_string(&self.api_token, encryption_key)?)
+		};
 
 		let auto_apply_json = self
 			.auto_apply_config
@@ -37,7 +45,7 @@ impl CreateMetadataProviderConfigInput {
 			id: NotSet,
 			provider_type: Set(self.provider_type),
 			enabled: Set(self.enabled.unwrap_or(true)),
-			encrypted_api_token: Set(Some(encrypted_api_token)),
+			encrypted_api_token: Set(encrypted_api_token),
 			api_token_expires_at: Set(self.api_token_expires_at),
 			auto_apply_config: auto_apply_json.map(|v| Set(Some(v))).unwrap_or(NotSet),
 			created_at: NotSet,
@@ -1,5 +1,6 @@
 pub mod api_key;
 pub mod book_club;
+pub mod collection;
 pub mod email_device;
 pub mod emailer;
 pub mod filesystem;
@@ -8,6 +9,7 @@ pub mod media;
 pub mod metadata_provider;
 pub mod notifier;
 pub mod reading_list;
+pub mod review;
 pub mod scheduled_job_config;
 pub mod series;
 pub mod smart_list_view;
@@ -1,5 +1,7 @@
 use async_graphql::{InputObject, OneofObject, Result};
-use models::entity::notifier::{self, NotifierConfig, NotifierType};
+use models::entity::notifier::{
+	self, NotificationEventKind, NotifierConfig, NotifierEvents, NotifierType,
+};
 use sea_orm::{ActiveValue::NotSet, Set};
 use stump_core::utils::encryption::encrypt_string;
 
@@ -14,14 +16,36 @@ pub struct TelegramConfigInput {
 	pub chat_id: String,
 }
 
+#[derive(InputObject)]
+pub struct WebhookHeaderInput {
+	pub name: String,
+	pub value: String,
+}
+
+#[derive(InputObject)]
+pub struct WebhookConfigInput {
+	pub url: String,
+	pub headers: Option<Vec<WebhookHeaderInput>>,
+	/// A secret used to sign the body of each request. The signature is sent in the
+	/// `X-Stump-Signature` header
+	pub secret: Option<String>,
+}
+
 #[derive(OneofObject)]
 pub enum NotifierInput {
 	Discord(DiscordConfigInput),
 	Telegram(TelegramConfigInput),
+	Webhook(WebhookConfigInput),
 }
 
 impl NotifierInput {
-	pub fn try_into_active_model(self, key: &String) -> Result<notifier::ActiveModel> {
+	/// Convert the input into an active model. When `events` is `None`, the notifier is
+	/// sent every event
+	pub fn try_into_active_model(
+		self,
+		key: &String,
+		events: Option<Vec<NotificationEventKind>>,
+	) -> Result<notifier::ActiveModel> {
 		let (notifier_type, notifier_config) = match self {
 			NotifierInput::Discord(config) => (
 				NotifierType::Discord,
@@ -36,12 +60,34 @@ impl NotifierInput {
 					chat_id: config.chat_id,
 				}),
 			),
+			NotifierInput::Webhook(config) => (
+				NotifierType::Webhook,
+				NotifierConfig::Webhook(notifier::WebhookConfig {
+					url: config.url,
+					headers: config
+						.headers
+						.unwrap_or_default()
+						.into_iter()
+						.map(|header| {
+							Ok(notifier::WebhookHeader {
+								name: header.name,
+								encrypted_value: encrypt_string(&header.value, key)?,
+							})
+						})
+						.collect::<Result<_>>()?,
+					encrypted_secret: config
+						.secret
+						.map(|secret| encrypt_string(&secret, key))
+						.transpose()?,
+				}),
+			),
 		};
 
 		Ok(notifier::ActiveModel {
 			id: NotSet, // auto-incremented
 			r#type: Set(notifier_type.to_string()),
 			config: Set(notifier_config.into_bytes()?),
+			events: Set(events.map(NotifierEvents)),
 		})
 	}
 }
@@ -1,5 +1,8 @@
-use async_graphql::InputObject;
-use models::{entity::reading_list, shared::enums::EntityVisibility};
+use async_graphql::{InputObject, ID};
+use models::{
+	entity::{reading_list, reading_list_rule},
+	shared::enums::{AccessRole, EntityVisibility},
+};
 use sea_orm::ActiveValue::Set;
 
 #[derive(InputObject)]
@@ -21,12 +24,33 @@ impl ReadingListInput {
 				.unwrap_or_default()
 				.to_string()
 				.to_uppercase()),
+			ordering: Set("MANUAL".to_string()),
 			creating_user_id: Set(user_id.to_string()),
 			..Default::default()
 		}
 	}
 }
 
+#[derive(InputObject)]
+pub struct ReadingListRuleInput {
+	pub user_id: ID,
+	pub role: AccessRole,
+}
+
+impl ReadingListRuleInput {
+	pub fn into_active_model(
+		self,
+		reading_list_id: &str,
+	) -> reading_list_rule::ActiveModel {
+		reading_list_rule::ActiveModel {
+			role: Set(self.role),
+			user_id: Set(self.user_id.to_string()),
+			reading_list_id: Set(reading_list_id.to_string()),
+			..Default::default()
+		}
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
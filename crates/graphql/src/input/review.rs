@@ -0,0 +1,48 @@
+use async_graphql::{InputObject, ID};
+use models::{entity::review, shared::enums::EntityVisibility};
+use sea_orm::Set;
+
+fn default_visibility() -> EntityVisibility {
+	EntityVisibility::Public
+}
+
+#[derive(Debug, InputObject)]
+pub struct CreateReviewInput {
+	pub media_id: ID,
+	/// The rating given to the book, from 1 to 5
+	#[graphql(validator(minimum = 1, maximum = 5))]
+	pub rating: i32,
+	pub review_text: Option<String>,
+	#[graphql(default_with = "default_visibility()")]
+	pub visibility: EntityVisibility,
+}
+
+impl CreateReviewInput {
+	pub fn into_active_model(self, user_id: &str) -> review::ActiveModel {
+		review::ActiveModel {
+			rating: Set(self.rating),
+			review_text: Set(self.review_text),
+			visibility: Set(self.visibility),
+			media_id: Set(self.media_id.to_string()),
+			user_id: Set(user_id.to_string()),
+			..Default::default()
+		}
+	}
+}
+
+#[derive(Debug, InputObject)]
+pub struct UpdateReviewInput {
+	/// The rating given to the book, from 1 to 5
+	#[graphql(validator(minimum = 1, maximum = 5))]
+	pub rating: i32,
+	pub review_text: Option<String>,
+	pub visibility: EntityVisibility,
+}
+
+impl UpdateReviewInput {
+	pub fn apply(self, active_model: &mut review::ActiveModel) {
+		active_model.rating = Set(self.rating);
+		active_model.review_text = Set(self.review_text);
+		active_model.visibility = Set(self.visibility);
+	}
+}
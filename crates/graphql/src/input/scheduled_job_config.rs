@@ -8,20 +8,94 @@ use serde::Serialize;
 pub enum ScheduledJobConfigInput {
 	LibraryScan(LibraryScanConfigInput),
 	MetadataRetry(MetadataRetryConfigInput),
+	ThumbnailRegeneration(ThumbnailRegenerationConfigInput),
+	MediaAnalysis(MediaAnalysisConfigInput),
+	LibraryCleanup(LibraryCleanupConfigInput),
+	DatabaseBackup(DatabaseBackupConfigInput),
+	HistoryPruning(HistoryPruningConfigInput),
+	OpdsCacheWarmup(OpdsCacheWarmupConfigInput),
 }
 
 #[derive(InputObject, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct LibraryScanConfigInput {
 	/// Library IDs to scan. An empty list means "all libraries"
 	pub library_ids: Vec<String>,
 }
 
 #[derive(InputObject, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct MetadataRetryConfigInput {
 	/// Which metadata fetch statuses to retry (e.g. RATE_LIMITED, FAILED)
 	pub statuses: Vec<MetadataFetchStatus>,
 }
 
+#[derive(InputObject, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThumbnailRegenerationConfigInput {
+	/// Library IDs to generate thumbnails for. An empty list means "all libraries"
+	pub library_ids: Vec<String>,
+	/// Whether to regenerate existing thumbnails, rather than only generating missing ones
+	#[graphql(default)]
+	pub force_regenerate: bool,
+}
+
+#[derive(InputObject, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaAnalysisConfigInput {
+	/// Library IDs to analyze. An empty list means "all libraries"
+	pub library_ids: Vec<String>,
+	/// Whether to analyze books which were already analyzed
+	#[graphql(default)]
+	pub force_reanalysis: bool,
+}
+
+#[derive(InputObject, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryCleanupConfigInput {
+	/// Library IDs to clean. An empty list means "all libraries"
+	pub library_ids: Vec<String>,
+	/// Whether to delete books and series which are missing from disk
+	#[graphql(default = true)]
+	pub remove_missing: bool,
+	/// Whether to run duplicate detection across all libraries
+	#[graphql(default = true)]
+	pub detect_duplicates: bool,
+}
+
+#[derive(InputObject, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseBackupConfigInput {
+	/// The number of backups to keep. Older backups are deleted after a new one is made
+	#[graphql(default = 7, validator(minimum = 1))]
+	pub retain_count: u32,
+}
+
+#[derive(InputObject, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryPruningConfigInput {
+	/// Delete logs older than this many days. Logs aren't pruned if this is not set
+	#[graphql(validator(minimum = 1))]
+	pub log_retention_days: Option<u32>,
+	/// Delete finished jobs older than this many days. Job history isn't pruned if this is
+	/// not set
+	#[graphql(validator(minimum = 1))]
+	pub job_retention_days: Option<u32>,
+}
+
+#[derive(InputObject, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpdsCacheWarmupConfigInput {
+	/// Library IDs to warm. An empty list means "all libraries"
+	pub library_ids: Vec<String>,
+	/// The number of pages to pre-render, from the start of each PDF
+	#[graphql(default = 5, validator(minimum = 1))]
+	pub page_count: u32,
+	/// Only warm books added within this many days. All books are warmed if this is not set
+	#[graphql(validator(minimum = 1))]
+	pub added_within_days: Option<u32>,
+}
+
 #[derive(InputObject)]
 pub struct CreateScheduledJobInput {
 	pub name: String,
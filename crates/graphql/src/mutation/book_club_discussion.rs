@@ -1,8 +1,9 @@
 use async_graphql::{Context, Object, Result, ID};
 use chrono::Utc;
+use integrations::NotificationEvent;
 use models::{
 	entity::{
-		book_club_book, book_club_discussion, book_club_discussion_message,
+		book_club, book_club_book, book_club_discussion, book_club_discussion_message,
 		book_club_discussion_message_reaction, book_club_member, custom_emoji,
 		user::AuthUser,
 	},
@@ -32,7 +33,8 @@ impl BookClubDiscussionMutation {
 		input: SendMessageInput,
 	) -> Result<BookClubDiscussionMessage> {
 		let AuthContext { user, .. } = ctx.data::<AuthContext>()?;
-		let conn = ctx.data::<CoreContext>()?.conn.as_ref();
+		let core_ctx = ctx.data::<CoreContext>()?;
+		let conn = core_ctx.conn.as_ref();
 
 		let discussion = book_club_discussion::Entity::find_by_id(discussion_id.as_ref())
 			.one(conn)
@@ -80,6 +82,11 @@ impl BookClubDiscussionMutation {
 			}
 		}
 
+		let book_club = book_club::Entity::find_by_id(&discussion.book_club_id)
+			.one(conn)
+			.await?
+			.ok_or("Book club not found")?;
+
 		let message = book_club_discussion_message::ActiveModel {
 			id: Set(Uuid::new_v4().to_string()),
 			content: Set(input.content),
@@ -95,7 +102,11 @@ impl BookClubDiscussionMutation {
 
 		let created_message = message.insert(conn).await?;
 
-		// TODO: Emit some kind of message event when event broker is implemented
+		core_ctx.notify(NotificationEvent::BookClubMessage {
+			book_club_name: book_club.name,
+			username: user.username.clone(),
+			content: created_message.content.clone(),
+		});
 
 		Ok(created_message.into())
 	}
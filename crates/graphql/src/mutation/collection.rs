@@ -0,0 +1,322 @@
+use std::collections::HashSet;
+
+use async_graphql::{Context, Object, Result, ID};
+use models::{
+	entity::{
+		collection, collection_access_rule, collection_item, series, user::AuthUser,
+	},
+	shared::enums::AccessRole,
+};
+use sea_orm::{
+	prelude::*, sea_query::SelectStatement, ActiveValue::Set, IntoActiveModel,
+	QueryOrder, QuerySelect, QueryTrait, TransactionTrait,
+};
+
+use crate::{
+	data::{AuthContext, CoreContext},
+	input::collection::{
+		CollectionAccessRuleInput, CreateCollectionInput, UpdateCollectionInput,
+	},
+	object::collection::Collection,
+};
+
+#[derive(Default)]
+pub struct CollectionMutation;
+
+#[Object]
+impl CollectionMutation {
+	/// Create a new collection, optionally with an initial set of series
+	async fn create_collection(
+		&self,
+		ctx: &Context<'_>,
+		input: CreateCollectionInput,
+	) -> Result<Collection> {
+		let AuthContext { user, .. } = ctx.data::<AuthContext>()?;
+		let conn = ctx.data::<CoreContext>()?.conn.as_ref();
+
+		let series_ids = get_accessible_series_ids(conn, user, &input.series_ids).await?;
+
+		let txn = conn.begin().await?;
+		let collection = input.into_active_model(&user.id).insert(&txn).await?;
+		insert_items(&txn, &collection.id, series_ids, 0).await?;
+		txn.commit().await?;
+
+		Ok(Collection::from(collection))
+	}
+
+	/// Update the details of a collection. This requires the writer role
+	async fn update_collection(
+		&self,
+		ctx: &Context<'_>,
+		id: ID,
+		input: UpdateCollectionInput,
+	) -> Result<Collection> {
+		let AuthContext { user, .. } = ctx.data::<AuthContext>()?;
+		let conn = ctx.data::<CoreContext>()?.conn.as_ref();
+
+		let collection =
+			get_collection_for_role(conn, user, &id, AccessRole::Writer).await?;
+
+		if let Some(cover_series_id) = &input.cover_series_id {
+			let is_in_collection = collection_item::Entity::find()
+				.filter(collection_item::Column::CollectionId.eq(collection.id.clone()))
+				.filter(collection_item::Column::SeriesId.eq(cover_series_id.to_string()))
+				.count(conn)
+				.await? > 0;
+			if !is_in_collection {
+				return Err("The cover series must be in the collection".into());
+			}
+		}
+
+		let mut active_model = collection.into_active_model();
+		input.apply(&mut active_model);
+		let updated_collection = active_model.update(conn).await?;
+
+		Ok(Collection::from(updated_collection))
+	}
+
+	/// Delete a collection. This requires the co-creator role
+	async fn delete_collection(&self, ctx: &Context<'_>, id: ID) -> Result<Collection> {
+		let AuthContext { user, .. } = ctx.data::<AuthContext>()?;
+		let conn = ctx.data::<CoreContext>()?.conn.as_ref();
+
+		let collection =
+			get_collection_for_role(conn, user, &id, AccessRole::CoCreator).await?;
+		collection.clone().delete(conn).await?;
+
+		Ok(Collection::from(collection))
+	}
+
+	/// Add series to the end of a collection. Series which are already in the collection
+	/// are skipped. This requires the writer role
+	async fn add_series_to_collection(
+		&self,
+		ctx: &Context<'_>,
+		id: ID,
+		series_ids: Vec<ID>,
+	) -> Result<Collection> {
+		let AuthContext { user, .. } = ctx.data::<AuthContext>()?;
+		let conn = ctx.data::<CoreContext>()?.conn.as_ref();
+
+		let collection =
+			get_collection_for_role(conn, user, &id, AccessRole::Writer).await?;
+		let series_ids = get_accessible_series_ids(conn, user, &series_ids).await?;
+
+		let txn = conn.begin().await?;
+		let existing_items = collection_item::Entity::find()
+			.filter(collection_item::Column::CollectionId.eq(collection.id.clone()))
+			.order_by_asc(collection_item::Column::Position)
+			.all(&txn)
+			.await?;
+		let existing_series_ids = existing_items
+			.iter()
+			.map(|item| item.series_id.clone())
+			.collect::<HashSet<_>>();
+		let next_position = existing_items
+			.last()
+			.map(|item| item.position + 1)
+			.unwrap_or_default();
+		let series_ids = series_ids
+			.into_iter()
+			.filter(|series_id| !existing_series_ids.contains(series_id))
+			.collect();
+		insert_items(&txn, &collection.id, series_ids, next_position).await?;
+		txn.commit().await?;
+
+		Ok(Collection::from(collection))
+	}
+
+	/// Remove series from a collection. This requires the writer role
+	async fn remove_series_from_collection(
+		&self,
+		ctx: &Context<'_>,
+		id: ID,
+		series_ids: Vec<ID>,
+	) -> Result<Collection> {
+		let AuthContext { user, .. } = ctx.data::<AuthContext>()?;
+		let conn = ctx.data::<CoreContext>()?.conn.as_ref();
+
+		let collection =
+			get_collection_for_role(conn, user, &id, AccessRole::Writer).await?;
+
+		collection_item::Entity::delete_many()
+			.filter(collection_item::Column::CollectionId.eq(collection.id.clone()))
+			.filter(
+				collection_item::Column::SeriesId
+					.is_in(series_ids.iter().map(|id| id.to_string())),
+			)
+			.exec(conn)
+			.await?;
+
+		Ok(Collection::from(collection))
+	}
+
+	/// Replace the series in a collection with the given series, in the given order. Series
+	/// in the collection which the user is unable to see are kept, after the given series.
+	/// This requires the writer role
+	async fn set_collection_series(
+		&self,
+		ctx: &Context<'_>,
+		id: ID,
+		series_ids: Vec<ID>,
+	) -> Result<Collection> {
+		let AuthContext { user, .. } = ctx.data::<AuthContext>()?;
+		let conn = ctx.data::<CoreContext>()?.conn.as_ref();
+
+		let collection =
+			get_collection_for_role(conn, user, &id, AccessRole::Writer).await?;
+		let series_ids = get_accessible_series_ids(conn, user, &series_ids).await?;
+
+		let txn = conn.begin().await?;
+		collection_item::Entity::delete_many()
+			.filter(collection_item::Column::CollectionId.eq(collection.id.clone()))
+			.filter(
+				collection_item::Column::SeriesId
+					.in_subquery(accessible_series_query(user)),
+			)
+			.exec(&txn)
+			.await?;
+		let hidden_items = collection_item::Entity::find()
+			.filter(collection_item::Column::CollectionId.eq(collection.id.clone()))
+			.order_by_asc(collection_item::Column::Position)
+			.all(&txn)
+			.await?;
+
+		let hidden_offset = series_ids.len() as i32;
+		insert_items(&txn, &collection.id, series_ids, 0).await?;
+		for (idx, item) in hidden_items.into_iter().enumerate() {
+			let mut active_model = item.into_active_model();
+			active_model.position = Set(hidden_offset + idx as i32);
+			active_model.update(&txn).await?;
+		}
+		txn.commit().await?;
+
+		Ok(Collection::from(collection))
+	}
+
+	/// Replace the access rules of a collection, which grant other users a role in it. Rules
+	/// have no effect on private collections. This requires the co-creator role
+	async fn set_collection_access_rules(
+		&self,
+		ctx: &Context<'_>,
+		id: ID,
+		rules: Vec<CollectionAccessRuleInput>,
+	) -> Result<Collection> {
+		let AuthContext { user, .. } = ctx.data::<AuthContext>()?;
+		let conn = ctx.data::<CoreContext>()?.conn.as_ref();
+
+		let collection =
+			get_collection_for_role(conn, user, &id, AccessRole::CoCreator).await?;
+
+		if rules
+			.iter()
+			.any(|rule| rule.user_id.as_str() == collection.creator_id)
+		{
+			return Err(
+				"Cannot add an access rule for the creator of a collection".into()
+			);
+		}
+		let unique_user_ids = rules
+			.iter()
+			.map(|rule| rule.user_id.to_string())
+			.collect::<HashSet<_>>();
+		if unique_user_ids.len() != rules.len() {
+			return Err("Cannot add more than one access rule for a user".into());
+		}
+
+		let txn = conn.begin().await?;
+		collection_access_rule::Entity::delete_many()
+			.filter(
+				collection_access_rule::Column::CollectionId.eq(collection.id.clone()),
+			)
+			.exec(&txn)
+			.await?;
+		if !rules.is_empty() {
+			collection_access_rule::Entity::insert_many(
+				rules
+					.into_iter()
+					.map(|rule| rule.into_active_model(&collection.id)),
+			)
+			.exec(&txn)
+			.await?;
+		}
+		txn.commit().await?;
+
+		Ok(Collection::from(collection))
+	}
+}
+
+async fn get_collection_for_role(
+	conn: &DatabaseConnection,
+	user: &AuthUser,
+	id: &ID,
+	role: AccessRole,
+) -> Result<collection::Model> {
+	let collection = collection::Entity::find_for_user_with_role(user, role)
+		.filter(collection::Column::Id.eq(id.to_string()))
+		.one(conn)
+		.await?
+		.ok_or("Collection not found")?;
+
+	Ok(collection)
+}
+
+/// A query for the IDs of the series the user is able to see
+fn accessible_series_query(user: &AuthUser) -> SelectStatement {
+	series::Entity::find_for_user(user)
+		.select_only()
+		.column(series::Column::Id)
+		.into_query()
+}
+
+/// Deduplicate the given series IDs, keeping their order, and ensure the user is able to
+/// see every one of them
+async fn get_accessible_series_ids(
+	conn: &DatabaseConnection,
+	user: &AuthUser,
+	series_ids: &[ID],
+) -> Result<Vec<String>> {
+	let mut seen = HashSet::new();
+	let series_ids = series_ids
+		.iter()
+		.map(|id| id.to_string())
+		.filter(|id| seen.insert(id.clone()))
+		.collect::<Vec<_>>();
+	if series_ids.is_empty() {
+		return Ok(series_ids);
+	}
+
+	let found_count = series::Entity::find_for_user(user)
+		.filter(series::Column::Id.is_in(series_ids.clone()))
+		.count(conn)
+		.await?;
+	if found_count != series_ids.len() as u64 {
+		return Err("One or more series not found".into());
+	}
+
+	Ok(series_ids)
+}
+
+async fn insert_items<C: ConnectionTrait>(
+	conn: &C,
+	collection_id: &str,
+	series_ids: Vec<String>,
+	start_position: i32,
+) -> Result<(), DbErr> {
+	if series_ids.is_empty() {
+		return Ok(());
+	}
+
+	collection_item::Entity::insert_many(series_ids.into_iter().enumerate().map(
+		|(idx, series_id)| collection_item::ActiveModel {
+			position: Set(start_position + idx as i32),
+			collection_id: Set(collection_id.to_string()),
+			series_id: Set(series_id),
+			..Default::default()
+		},
+	))
+	.exec(conn)
+	.await?;
+
+	Ok(())
+}
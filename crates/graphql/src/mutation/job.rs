@@ -1,7 +1,7 @@
 use crate::{
 	data::CoreContext,
 	guard::PermissionGuard,
-	object::job::{DeleteJobAssociatedLogs, DeleteJobHistory},
+	object::job::{DeleteJobAssociatedLogs, DeleteJobHistory, Job},
 };
 use async_graphql::{Context, Error, ErrorExtensions, Object, Result, ID};
 use models::{
@@ -9,6 +9,10 @@ use models::{
 	shared::enums::{JobStatus, UserPermission},
 };
 use sea_orm::{prelude::*, EntityTrait, QueryFilter, QuerySelect};
+use stump_core::{
+	job::{JobProgress, JobUpdate},
+	CoreEvent,
+};
 
 #[derive(Default)]
 pub struct JobMutation;
@@ -19,13 +23,91 @@ impl JobMutation {
 	async fn cancel_job(&self, ctx: &Context<'_>, id: ID) -> Result<bool> {
 		let core = ctx.data::<CoreContext>()?;
 		let job_id = id.to_string();
-		let cancelled = core.apalis_state.cancel_job(&job_id);
+		let cancelled = core.apalis_state.cancel_job(&job_id)
+			|| core.job_storage.cancel_queued(&job_id).await?;
 		if !cancelled {
 			tracing::warn!(%job_id, "Job not found or already completed");
 		}
 		Ok(cancelled)
 	}
 
+	/// Pause a job. A running job pauses once its current task finishes, and a queued job
+	/// won't start until it is resumed
+	#[graphql(guard = "PermissionGuard::one(UserPermission::ManageJobs)")]
+	async fn pause_job(&self, ctx: &Context<'_>, id: ID) -> Result<bool> {
+		let core = ctx.data::<CoreContext>()?;
+		let job_id = id.to_string();
+
+		if core.apalis_state.pause_job(&job_id) {
+			return Ok(true);
+		}
+
+		let paused = core.job_storage.pause_queued(&job_id).await?;
+		if paused {
+			core.emit_event(CoreEvent::JobUpdate(JobUpdate {
+				id: job_id,
+				payload: JobProgress::status_msg(JobStatus::Paused, "Job was paused"),
+			}));
+		} else {
+			tracing::warn!(%job_id, "Job not found or not pausable");
+		}
+		Ok(paused)
+	}
+
+	/// Resume a paused job. The job is queued again, and picks up from where it left off
+	#[graphql(guard = "PermissionGuard::one(UserPermission::ManageJobs)")]
+	async fn resume_job(&self, ctx: &Context<'_>, id: ID) -> Result<bool> {
+		let core = ctx.data::<CoreContext>()?;
+		let job_id = id.to_string();
+
+		let resumed = core.job_storage.resume(&job_id).await?;
+		if resumed {
+			core.emit_event(CoreEvent::JobUpdate(JobUpdate {
+				id: job_id,
+				payload: JobProgress::status_msg(JobStatus::Queued, "Job was resumed"),
+			}));
+		} else {
+			tracing::warn!(%job_id, "Job not found or not paused");
+		}
+		Ok(resumed)
+	}
+
+	/// Move queued jobs to the front of the queue, in the given order, returning the updated
+	/// queue. Passing every queued job reorders the whole queue. A queued job can be dropped
+	/// with `cancelJob` or `deleteJob`
+	#[graphql(guard = "PermissionGuard::one(UserPermission::ManageJobs)")]
+	async fn reorder_queued_jobs(
+		&self,
+		ctx: &Context<'_>,
+		ids: Vec<ID>,
+	) -> Result<Vec<Job>> {
+		let core = ctx.data::<CoreContext>()?;
+		let ids = ids.into_iter().map(|id| id.to_string()).collect::<Vec<_>>();
+
+		let moved = core.job_storage.reorder_queued(&ids).await?;
+		tracing::debug!(moved, "Reordered queued jobs");
+
+		let models = core.job_storage.list_queued().await?;
+		Ok(models.into_iter().map(Job::from).collect())
+	}
+
+	/// Queue a new job which runs only the tasks that failed during a finished job, returning
+	/// the new job. The failed tasks are moved to the new job, so they can only be retried
+	/// once
+	#[graphql(guard = "PermissionGuard::one(UserPermission::ManageJobs)")]
+	async fn retry_failed_job_tasks(&self, ctx: &Context<'_>, id: ID) -> Result<Job> {
+		let core = ctx.data::<CoreContext>()?;
+		let job_id = id.to_string();
+
+		let model = core
+			.job_storage
+			.retry_failed_tasks(&job_id)
+			.await?
+			.ok_or("Job not found, not finished or has no failed tasks")?;
+
+		Ok(model.into())
+	}
+
 	#[graphql(guard = "PermissionGuard::one(UserPermission::ManageJobs)")]
 	async fn delete_job(
 		&self,
@@ -76,7 +158,11 @@ impl JobMutation {
 		let core = ctx.data::<CoreContext>()?;
 
 		let affected_rows = job::Entity::delete_many()
-			.filter(job::Column::Status.ne(JobStatus::Running))
+			.filter(job::Column::Status.is_not_in([
+				JobStatus::Running,
+				JobStatus::Paused,
+				JobStatus::Queued,
+			]))
 			.exec(core.conn.as_ref())
 			.await
 			.map_err(|error| {
@@ -14,7 +14,7 @@ use models::{
 use sea_orm::{
 	prelude::*,
 	sea_query::{OnConflict, Query},
-	Condition, IntoActiveModel, QuerySelect, Set, TransactionTrait,
+	IntoActiveModel, QuerySelect, Set, TransactionTrait,
 };
 use stump_core::filesystem::{
 	image::{
@@ -22,9 +22,12 @@ use stump_core::filesystem::{
 		ImageProcessorOptionsExt, PlaceholderGenerationJobConfig,
 		PlaceholderGenerationJobScope, ThumbnailGenerationJobParams,
 	},
-	media::analysis::{AnalysisJobConfig, MediaAnalysisJobScope},
+	media::{
+		analysis::{AnalysisJobConfig, MediaAnalysisJobScope},
+		write_back::{MetadataWriteBackScope, WriteBackMetadataJobConfig},
+	},
 	metadata::{MetadataFetchJobParams, MetadataFetchScope},
-	scanner::ScanOptions,
+	scanner::{clean_library, CleanLibraryOutput, ScanOptions, WatcherBackend},
 };
 use stump_core::job::stump_job::StumpJob;
 use tokio::fs;
@@ -76,6 +79,32 @@ impl LibraryMutation {
 		Ok(true)
 	}
 
+	/// Write the stored metadata of every book in a library back into the files
+	#[graphql(guard = "PermissionGuard::one(UserPermission::WriteBackMetadata)")]
+	async fn write_back_library_metadata(
+		&self,
+		ctx: &Context<'_>,
+		id: ID,
+	) -> Result<bool> {
+		let AuthContext { user, .. } = ctx.data::<AuthContext>()?;
+		let core = ctx.data::<CoreContext>()?;
+		let conn = core.conn.as_ref();
+
+		let model = library::Entity::find_for_user(user)
+			.filter(library::Column::Id.eq(id.to_string()))
+			.into_model::<LibraryIdentSelect>()
+			.one(conn)
+			.await?
+			.ok_or("Library not found")?;
+
+		core.enqueue(StumpJob::write_back_metadata(WriteBackMetadataJobConfig {
+			scope: MetadataWriteBackScope::Library(model.id),
+		}))
+		.await?;
+
+		Ok(true)
+	}
+
 	/// Delete media and series from a library that match one of the following conditions:
 	///
 	/// - A series that is missing from disk (status is not `Ready`)
@@ -100,79 +129,21 @@ impl LibraryMutation {
 			.await?
 			.ok_or("Library not found")?;
 
-		let thumbnails_dir = core.config.get_thumbnails_dir();
-
-		let txn = core.conn.as_ref().begin().await?;
-
-		let deleted_media_ids = media::Entity::delete_many()
-			.filter(
-				media::Column::Status.ne(FileStatus::Ready.to_string()).and(
-					media::Column::SeriesId.in_subquery(
-						Query::select()
-							.column(series::Column::Id)
-							.from(series::Entity)
-							.and_where(series::Column::LibraryId.eq(id.to_string()))
-							.to_owned(),
-					),
-				),
-			)
-			.exec_with_returning(&txn)
-			.await?
-			.into_iter()
-			.map(|m| m.id)
-			.collect::<Vec<_>>();
-		tracing::trace!(?deleted_media_ids, "Deleted media ids");
-
-		let deleted_series_ids = series::Entity::delete_many()
-			.filter(series::Column::LibraryId.eq(id.to_string()))
-			.filter(
-				Condition::any()
-					.add(series::Column::Status.ne(FileStatus::Ready.to_string()))
-					// TODO: Double check that this query is correct
-					.add(
-						series::Column::Id.not_in_subquery(
-							Query::select()
-								.column(media::Column::SeriesId)
-								.distinct()
-								.from(media::Entity)
-								.to_owned(),
-						),
-					),
-			)
-			.exec_with_returning(&txn)
-			.await?
-			.into_iter()
-			.map(|s| s.id)
-			.collect::<Vec<_>>();
-		tracing::trace!(?deleted_series_ids, "Deleted series ids");
-
-		let is_library_empty = series::Entity::find()
-			.filter(series::Column::LibraryId.eq(id.to_string()))
-			.count(&txn)
-			.await? == 0;
-
-		txn.commit().await?;
-
-		if !deleted_media_ids.is_empty() {
-			if let Err(error) =
-				remove_thumbnails(&deleted_media_ids, &thumbnails_dir).await
-			{
-				tracing::error!(?error, "Failed to remove thumbnails for library media");
-			}
-		}
-
-		if !deleted_series_ids.is_empty() {
-			if let Err(error) =
-				remove_thumbnails(&deleted_series_ids, &thumbnails_dir).await
-			{
-				tracing::error!(?error, "Failed to remove thumbnails for library series");
-			}
-		}
+		let CleanLibraryOutput {
+			deleted_media_count,
+			deleted_series_count,
+			is_empty,
+		} = clean_library(
+			core.conn.as_ref(),
+			id.as_ref(),
+			&core.config.get_thumbnails_dir(),
+		)
+		.await?;
 
 		Ok(CleanLibraryResponse {
-			deleted_media_count: deleted_media_ids.len(),
-			deleted_series_count: deleted_series_ids.len(),
-			is_empty: is_library_empty,
+			deleted_media_count,
+			deleted_series_count,
+			is_empty,
 		})
 	}
 
@@ -231,6 +202,7 @@ impl LibraryMutation {
 		let (library, config) = input.into_active_model();
 
 		let created_config = config.insert(&txn).await?;
+		let watcher_backend = WatcherBackend::from(&created_config);
 		let created_library = library::ActiveModel {
 			id: Set(created_config
 				.library_id
@@ -275,7 +247,7 @@ impl LibraryMutation {
 
 		if add_watcher {
 			core.library_watcher
-				.add_watcher(created_library.path.clone().into())
+				.add_watcher(created_library.path.clone().into(), watcher_backend)
 				.await?;
 		}
 
@@ -412,9 +384,16 @@ impl LibraryMutation {
 
 		let txn = core.conn.as_ref().begin().await?;
 
-		let (library, config) = input.into_active_model();
+		let (library, mut config) = input.into_active_model();
+		// Devices which already synced need to know when the library opted in, since its
+		// books were skipped until then
+		if config.kobo_convert_to_epub == Set(true)
+			&& !existing_config.kobo_convert_to_epub
+		{
+			config.kobo_convert_enabled_at = Set(Some(Utc::now().into()));
+		}
 
-		let _updated_config = library_config::ActiveModel {
+		let updated_config = library_config::ActiveModel {
 			id: Set(existing_config.id),
 			library_id: Set(existing_config.library_id.clone()),
 			..config
@@ -471,13 +450,17 @@ impl LibraryMutation {
 			.await?;
 		}
 
+		// The path or watch mode might have changed, so the existing watcher is always
+		// removed before (potentially) adding it back
+		core.library_watcher
+			.remove_watcher(existing_library.path.clone().into())
+			.await?;
 		if add_watcher {
 			core.library_watcher
-				.add_watcher(updated_library.path.clone().into())
-				.await?;
-		} else {
-			core.library_watcher
-				.remove_watcher(existing_library.path.clone().into())
+				.add_watcher(
+					updated_library.path.clone().into(),
+					WatcherBackend::from(&updated_config),
+				)
 				.await?;
 		}
 
@@ -5,7 +5,10 @@ use models::{
 		favorite_media, finished_reading_session, library, library_config, media,
 		reading_session, series, user::AuthUser,
 	},
-	shared::enums::UserPermission,
+	shared::{
+		enums::{DuplicateResolution, UserPermission},
+		image_processor_options::SupportedImageFormat,
+	},
 };
 use sea_orm::{
 	prelude::*,
@@ -15,7 +18,12 @@ use sea_orm::{
 use stump_core::{
 	filesystem::{
 		image::{generate_book_thumbnail, GenerateThumbnailOptions},
-		media::analysis::{AnalysisJobConfig, MediaAnalysisJobScope},
+		media::{
+			analysis::{AnalysisJobConfig, MediaAnalysisJobScope},
+			convert::{ConvertMediaJobConfig, MediaConversionScope},
+			duplicates::resolve_duplicate_group,
+			write_back::{MetadataWriteBackScope, WriteBackMetadataJobConfig},
+		},
 	},
 	job::stump_job::StumpJob,
 	utils::chain_optional_iter,
@@ -71,14 +79,46 @@ impl MediaMutation {
 		Ok(true)
 	}
 
-	// TODO: Support converting other formats in the future
+	/// Write the stored metadata of a book back into its file (ComicInfo.xml for zip/cbz,
+	/// the OPF for epub)
+	#[graphql(guard = "PermissionGuard::one(UserPermission::WriteBackMetadata)")]
+	async fn write_back_media_metadata(&self, ctx: &Context<'_>, id: ID) -> Result<bool> {
+		let AuthContext { user, .. } = ctx.data::<AuthContext>()?;
+		let core = ctx.data::<CoreContext>()?;
+		let conn = core.conn.as_ref();
+
+		let model = media::Entity::find_for_user(user)
+			.select_only()
+			.columns(vec![media::Column::Id, media::Column::Path])
+			.filter(media::Column::Id.eq(id.to_string()))
+			.into_model::<media::MediaIdentSelect>()
+			.one(conn)
+			.await?
+			.ok_or("Media not found")?;
+
+		core.enqueue(StumpJob::write_back_metadata(WriteBackMetadataJobConfig {
+			scope: MetadataWriteBackScope::Book(model.id),
+		}))
+		.await?;
+
+		Ok(true)
+	}
+
+	/// Convert a book (CBR, CB7 or PDF) into a CBZ archive. The book keeps its ID, so
+	/// reading progress and anything else attached to it is preserved.
 	#[graphql(guard = "PermissionGuard::one(UserPermission::ManageLibrary)")]
-	async fn convert_media(&self, ctx: &Context<'_>, id: ID) -> Result<bool> {
+	async fn convert_media(
+		&self,
+		ctx: &Context<'_>,
+		id: ID,
+		#[graphql(default = false)] delete_source: bool,
+		image_format: Option<SupportedImageFormat>,
+	) -> Result<bool> {
 		let AuthContext { user, .. } = ctx.data::<AuthContext>()?;
 		let core = ctx.data::<CoreContext>()?;
 		let conn = core.conn.as_ref();
 
-		let _model = media::Entity::find_for_user(user)
+		let model = media::Entity::find_for_user(user)
 			.select_only()
 			.columns(vec![media::Column::Id, media::Column::Path])
 			.filter(media::Column::Id.eq(id.to_string()))
@@ -87,13 +127,53 @@ impl MediaMutation {
 			.await?
 			.ok_or("Media not found")?;
 
-		// if media.extension != "cbr" || media.extension != "rar" {
-		//     return Err(APIError::BadRequest(String::from(
-		//         "Stump only supports RAR to ZIP conversions at this time",
-		//     )));
-		// }
+		core.enqueue(StumpJob::convert_media(ConvertMediaJobConfig {
+			scope: MediaConversionScope::Book(model.id),
+			delete_source,
+			image_format,
+		}))
+		.await?;
 
-		Err("Not implemented".into())
+		Ok(true)
+	}
+
+	/// Start a job which finds groups of likely duplicate media, replacing the groups found
+	/// by any previous run
+	#[graphql(guard = "PermissionGuard::one(UserPermission::ManageLibrary)")]
+	async fn detect_duplicate_media(&self, ctx: &Context<'_>) -> Result<bool> {
+		let core = ctx.data::<CoreContext>()?;
+
+		core.enqueue(StumpJob::detect_duplicates()).await?;
+
+		Ok(true)
+	}
+
+	/// Resolve a group of duplicate media by keeping one of its members. When the other
+	/// members are deleted, their reading progress, bookmarks, annotations, reading list
+	/// memberships and favorites are moved onto it first. Ignored members keep theirs, and
+	/// are left out of future detection runs.
+	#[graphql(guard = "PermissionGuard::one(UserPermission::ManageLibrary)")]
+	async fn resolve_duplicate_media(
+		&self,
+		ctx: &Context<'_>,
+		group_id: ID,
+		survivor_id: ID,
+		action: DuplicateResolution,
+	) -> Result<Media> {
+		let AuthContext { user, .. } = ctx.data::<AuthContext>()?;
+		let core = ctx.data::<CoreContext>()?;
+		let conn = core.conn.as_ref();
+
+		let model = media::ModelWithMetadata::find_for_user(user)
+			.filter(media::Column::Id.eq(survivor_id.to_string()))
+			.into_model::<media::ModelWithMetadata>()
+			.one(conn)
+			.await?
+			.ok_or("Media not found")?;
+
+		resolve_duplicate_group(conn, &group_id, &model.media.id, action).await?;
+
+		Ok(Media::from(model))
 	}
 
 	#[graphql(guard = "PermissionGuard::one(UserPermission::ManageLibrary)")]
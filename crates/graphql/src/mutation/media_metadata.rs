@@ -102,6 +102,7 @@ impl MediaMetadataMutation {
 				title,
 				author,
 				isbn,
+				path: Some(model.media.path.clone()),
 				..Default::default()
 			},
 			&provider_cache,
@@ -5,6 +5,7 @@ mod book_club_discussion;
 mod book_club_invitation;
 mod book_club_member;
 mod book_club_suggestion;
+mod collection;
 mod custom_emoji;
 mod email_device;
 mod emailer;
@@ -17,6 +18,7 @@ mod media_metadata;
 mod metadata_provider;
 mod notifier;
 mod reading_list;
+mod review;
 mod scheduled_job_config;
 mod series;
 mod series_metadata;
@@ -34,6 +36,7 @@ use book_club_discussion::BookClubDiscussionMutation;
 use book_club_invitation::BookClubInvitationMutation;
 use book_club_member::BookClubMemberMutation;
 use book_club_suggestion::BookClubSuggestionMutation;
+use collection::CollectionMutation;
 use custom_emoji::CustomEmojiMutation;
 use email_device::EmailDeviceMutation;
 use emailer::EmailerMutation;
@@ -46,6 +49,7 @@ use media_metadata::MediaMetadataMutation;
 use metadata_provider::MetadataProviderMutation;
 use notifier::NotifierMutation;
 use reading_list::ReadingListMutation;
+use review::ReviewMutation;
 use scheduled_job_config::ScheduledJobConfigMutation;
 use series::SeriesMutation;
 use series_metadata::SeriesMetadataMutation;
@@ -76,6 +80,7 @@ struct ContentMutations(
 	EpubMutation,
 	TagMutation,
 	UploadMutation,
+	ReviewMutation,
 );
 
 #[derive(async_graphql::MergedObject, Default)]
@@ -97,6 +102,7 @@ struct ListMutations(
 	SmartListMutation,
 	SmartListViewMutation,
 	ReadingListMutation,
+	CollectionMutation,
 	CustomEmojiMutation,
 );
 
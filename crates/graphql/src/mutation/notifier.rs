@@ -3,9 +3,9 @@ use crate::{
 	object::notifier::Notifier,
 };
 use async_graphql::{Context, Object, Result};
-use models::entity::notifier;
+use models::entity::notifier::{self, NotificationEventKind};
 use models::shared::enums::UserPermission;
-use sea_orm::{prelude::*, ActiveModelTrait, Set};
+use sea_orm::{prelude::*, ActiveModelTrait, NotSet, Set};
 
 #[derive(Default)]
 pub struct NotifierMutation;
@@ -17,12 +17,14 @@ impl NotifierMutation {
 		&self,
 		ctx: &Context<'_>,
 		input: NotifierInput,
+		#[graphql(desc = "The events to send to the notifier. Omit to send every event")]
+		events: Option<Vec<NotificationEventKind>>,
 	) -> Result<Notifier> {
 		let core_ctx = ctx.data::<CoreContext>()?;
 		let conn = core_ctx.conn.as_ref();
 		let encryption_key = core_ctx.get_encryption_key().await?;
 
-		let active_model = input.try_into_active_model(&encryption_key)?;
+		let active_model = input.try_into_active_model(&encryption_key, events)?;
 		let result = active_model.insert(conn).await?;
 
 		Ok(Notifier::from(result))
@@ -34,13 +36,21 @@ impl NotifierMutation {
 		ctx: &Context<'_>,
 		id: i32,
 		input: NotifierInput,
+		#[graphql(
+			desc = "The events to send to the notifier. Omit to keep its current events"
+		)]
+		events: Option<Vec<NotificationEventKind>>,
 	) -> Result<Notifier> {
 		let core_ctx = ctx.data::<CoreContext>()?;
 		let conn = core_ctx.conn.as_ref();
 		let encryption_key = core_ctx.get_encryption_key().await?;
 
-		let mut active_model = input.try_into_active_model(&encryption_key)?;
+		let keep_events = events.is_none();
+		let mut active_model = input.try_into_active_model(&encryption_key, events)?;
 		active_model.id = Set(id);
+		if keep_events {
+			active_model.events = NotSet;
+		}
 		let result = active_model.update(conn).await?;
 
 		Ok(Notifier::from(result))
@@ -1,15 +1,21 @@
-use async_graphql::{Context, Object, Result};
-use models::entity::{reading_list, reading_list_item};
+use async_graphql::{Context, Object, Result, Upload, ID};
+use chrono::Utc;
+use models::{
+	entity::{reading_list, reading_list_item, reading_list_rule, user::AuthUser},
+	shared::enums::{AccessRole, EntityVisibility},
+};
 use sea_orm::{
 	ActiveValue::Set,
-	TransactionTrait,
+	IntoActiveModel, TransactionTrait,
 	{prelude::*, DatabaseTransaction},
 };
+use std::{collections::HashSet, io::Read};
+use stump_core::reading_list::cbl::{CBLCandidate, CBLMatcher, CBLReadingList};
 
 use crate::{
 	data::{AuthContext, CoreContext},
-	input::reading_list::ReadingListInput,
-	object::reading_list::ReadingList,
+	input::reading_list::{ReadingListInput, ReadingListRuleInput},
+	object::reading_list::{ImportCBLOutput, ReadingList, UnmatchedCBLBook},
 };
 
 #[derive(Default)]
@@ -42,7 +48,81 @@ impl ReadingListMutation {
 		})
 	}
 
-	/// Updates an existing reading list.
+	/// Creates a new reading list from a ComicRack reading list (`.cbl`) file. Each book in
+	/// the file is matched to a book on the server by its series, number, volume and year,
+	/// and books which can't be matched are left out of the reading list.
+	///
+	/// # Returns
+	///
+	/// A result containing the newly created reading list and the books which could not be
+	/// matched, or an error if the file is not a valid CBL file.
+	async fn import_cbl_reading_list(
+		&self,
+		ctx: &Context<'_>,
+		upload: Upload,
+		#[graphql(
+			desc = "The name of the reading list. Defaults to the name in the file"
+		)]
+		name: Option<String>,
+		#[graphql(default)] visibility: EntityVisibility,
+	) -> Result<ImportCBLOutput> {
+		let AuthContext { user, .. } = ctx.data::<AuthContext>()?;
+		let conn = ctx.data::<CoreContext>()?.conn.as_ref();
+
+		let mut contents = String::new();
+		upload
+			.value(ctx)?
+			.content
+			.read_to_string(&mut contents)
+			.map_err(|e| format!("Failed to read upload data: {e}"))?;
+		let cbl = CBLReadingList::from_xml(&contents)?;
+
+		let candidates = CBLCandidate::find_for_user(user)
+			.into_model::<CBLCandidate>()
+			.all(conn)
+			.await?;
+		let matcher = CBLMatcher::new(candidates);
+
+		let mut media_ids = Vec::new();
+		let mut unmatched = Vec::new();
+		for (position, book) in cbl.books().iter().enumerate() {
+			match matcher.find_match(book) {
+				Some(media_id) => media_ids.push(media_id),
+				None => unmatched.push(UnmatchedCBLBook {
+					position: position as i32,
+					series: book.series.clone(),
+					number: book.number.clone(),
+					volume: book.volume.clone(),
+					year: book.year.clone(),
+				}),
+			}
+		}
+		let matched_count = media_ids.len() as u64;
+
+		let input = ReadingListInput {
+			id: Uuid::new_v4().to_string(),
+			name: name.unwrap_or(cbl.name),
+			visibility: Some(visibility),
+			media_ids,
+		};
+		let media_ids = input.media_ids.clone();
+
+		let txn = conn.begin().await?;
+		let reading_list = create_reading_list_for_user_id(&user.id, input, &txn).await?;
+		create_reading_list_items(reading_list.id.clone(), media_ids, &txn).await?;
+		txn.commit().await?;
+
+		Ok(ImportCBLOutput {
+			reading_list: ReadingList {
+				model: reading_list,
+			},
+			matched_count,
+			unmatched,
+		})
+	}
+
+	/// Updates an existing reading list, replacing its books with the given ones. This
+	/// requires the writer role, and changing the visibility requires the co-creator role.
 	///
 	/// # Returns
 	///
@@ -52,16 +132,43 @@ impl ReadingListMutation {
 		ctx: &Context<'_>,
 		input: ReadingListInput,
 	) -> Result<ReadingList> {
-		let user_id = ctx.data::<AuthContext>()?.id();
+		let AuthContext { user, .. } = ctx.data::<AuthContext>()?;
 		let conn = ctx.data::<CoreContext>()?.conn.as_ref();
 		let reading_list_id = input.id.clone();
 
-		let _ = get_for_owner(&reading_list_id, conn, user_id).await?;
+		let reading_list =
+			get_for_role(&reading_list_id, conn, user, AccessRole::Writer).await?;
+
+		let visibility = input
+			.visibility
+			.map(|visibility| visibility.to_string().to_uppercase());
+		if visibility
+			.as_ref()
+			.is_some_and(|visibility| *visibility != reading_list.visibility)
+		{
+			get_for_role(&reading_list_id, conn, user, AccessRole::CoCreator).await?;
+		}
 
-		Err("Not implemented".to_string().into())
+		let txn = conn.begin().await?;
+		let mut active_model = reading_list.into_active_model();
+		active_model.name = Set(input.name);
+		if let Some(visibility) = visibility {
+			active_model.visibility = Set(visibility);
+		}
+		active_model.updated_at = Set(Utc::now().into());
+		let updated_reading_list = active_model.update(&txn).await?;
+
+		reading_list_item::Entity::delete_many()
+			.filter(reading_list_item::Column::ReadingListId.eq(reading_list_id.clone()))
+			.exec(&txn)
+			.await?;
+		create_reading_list_items(reading_list_id, input.media_ids, &txn).await?;
+		txn.commit().await?;
+
+		Ok(ReadingList::from(updated_reading_list))
 	}
 
-	/// Deletes a reading list by ID.
+	/// Deletes a reading list by ID. This requires the co-creator role.
 	///
 	/// # Returns
 	///
@@ -71,10 +178,10 @@ impl ReadingListMutation {
 		ctx: &Context<'_>,
 		id: String,
 	) -> Result<ReadingList> {
-		let user_id = ctx.data::<AuthContext>()?.id();
+		let AuthContext { user, .. } = ctx.data::<AuthContext>()?;
 		let conn = ctx.data::<CoreContext>()?.conn.as_ref();
 
-		let reading_list = get_for_owner(&id, conn, user_id).await?;
+		let reading_list = get_for_role(&id, conn, user, AccessRole::CoCreator).await?;
 
 		// Delete reading list
 		let _ = reading_list.clone().delete(conn).await?;
@@ -83,6 +190,56 @@ impl ReadingListMutation {
 			model: reading_list,
 		})
 	}
+
+	/// Replaces the rules which grant other users a role on a reading list. Rules have no
+	/// effect on private reading lists. This requires the co-creator role.
+	///
+	/// # Returns
+	///
+	/// A result containing the reading list, or an error if the rules could not be set.
+	async fn set_reading_list_rules(
+		&self,
+		ctx: &Context<'_>,
+		id: ID,
+		rules: Vec<ReadingListRuleInput>,
+	) -> Result<ReadingList> {
+		let AuthContext { user, .. } = ctx.data::<AuthContext>()?;
+		let conn = ctx.data::<CoreContext>()?.conn.as_ref();
+
+		let reading_list = get_for_role(&id, conn, user, AccessRole::CoCreator).await?;
+
+		if rules
+			.iter()
+			.any(|rule| rule.user_id.as_str() == reading_list.creating_user_id)
+		{
+			return Err("Cannot add a rule for the creator of a reading list".into());
+		}
+		let unique_user_ids = rules
+			.iter()
+			.map(|rule| rule.user_id.to_string())
+			.collect::<HashSet<_>>();
+		if unique_user_ids.len() != rules.len() {
+			return Err("Cannot add more than one rule for a user".into());
+		}
+
+		let txn = conn.begin().await?;
+		reading_list_rule::Entity::delete_many()
+			.filter(reading_list_rule::Column::ReadingListId.eq(reading_list.id.clone()))
+			.exec(&txn)
+			.await?;
+		if !rules.is_empty() {
+			reading_list_rule::Entity::insert_many(
+				rules
+					.into_iter()
+					.map(|rule| rule.into_active_model(&reading_list.id)),
+			)
+			.exec(&txn)
+			.await?;
+		}
+		txn.commit().await?;
+
+		Ok(ReadingList::from(reading_list))
+	}
 }
 
 async fn create_reading_list_for_user_id(
@@ -98,6 +255,10 @@ async fn create_reading_list_items(
 	media_ids: Vec<String>,
 	txn: &DatabaseTransaction,
 ) -> Result<Vec<reading_list_item::Model>, DbErr> {
+	if media_ids.is_empty() {
+		return Ok(vec![]);
+	}
+
 	let item_creates = media_ids
 		.iter()
 		.enumerate()
@@ -114,31 +275,40 @@ async fn create_reading_list_items(
 		.await
 }
 
-async fn get_for_owner(
+/// Get a reading list which the user has at least the given role for. A user who can see
+/// the reading list but lacks the role gets a permission error rather than a not found one
+async fn get_for_role(
 	id: &str,
 	conn: &DbConn,
-	user_id: String,
+	user: &AuthUser,
+	role: AccessRole,
 ) -> Result<reading_list::Model> {
-	// Check if reading list exists
-	let reading_list = reading_list::Entity::find()
-		.filter(reading_list::Column::Id.eq(id.to_owned()))
+	let reading_list = reading_list::Entity::find_for_user_and_id(user, role, id)
 		.one(conn)
-		.await?
-		.ok_or("Reading list not found")?;
+		.await?;
+	if let Some(reading_list) = reading_list {
+		return Ok(reading_list);
+	}
 
-	if reading_list.creating_user_id != user_id {
+	let is_visible =
+		reading_list::Entity::find_for_user_and_id(user, AccessRole::Reader, id)
+			.one(conn)
+			.await?
+			.is_some();
+	if is_visible {
 		// TODO: log bad access attempt to DB
 		return Err("You do not have permission to access this resource."
 			.to_string()
 			.into());
 	}
 
-	Ok(reading_list)
+	Err("Reading list not found".into())
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::tests::common::*;
 	use models::shared::enums::EntityVisibility;
 	use sea_orm::MockDatabase;
 
@@ -155,29 +325,45 @@ mod tests {
 	}
 
 	#[tokio::test]
-	async fn test_get_for_owner() {
+	async fn test_get_for_role() {
 		let mock_db = MockDatabase::new(sea_orm::DatabaseBackend::Sqlite)
 			.append_query_results(vec![vec![get_reading_list_test_object()]])
 			.into_connection();
 
-		let reading_list = get_for_owner("123", &mock_db, "42".to_string())
-			.await
-			.unwrap();
+		let reading_list =
+			get_for_role("123", &mock_db, &get_default_user(), AccessRole::Writer)
+				.await
+				.unwrap();
 
 		assert_eq!(reading_list, get_reading_list_test_object());
 	}
 
 	#[tokio::test]
-	async fn test_get_for_not_owner() {
-		let mut test_model = get_reading_list_test_object();
-		test_model.creating_user_id = "123".to_string();
+	async fn test_get_for_role_without_permission() {
+		let mock_db = MockDatabase::new(sea_orm::DatabaseBackend::Sqlite)
+			.append_query_results::<reading_list::Model, _, _>(vec![vec![]])
+			.append_query_results(vec![vec![get_reading_list_test_object()]])
+			.into_connection();
+
+		let result =
+			get_for_role("123", &mock_db, &get_default_user(), AccessRole::Writer).await;
+
+		assert_eq!(
+			result.unwrap_err().message,
+			"You do not have permission to access this resource."
+		);
+	}
+
+	#[tokio::test]
+	async fn test_get_for_role_not_found() {
 		let mock_db = MockDatabase::new(sea_orm::DatabaseBackend::Sqlite)
-			.append_query_results(vec![vec![test_model]])
+			.append_query_results::<reading_list::Model, _, _>(vec![vec![], vec![]])
 			.into_connection();
 
-		let result = get_for_owner("123", &mock_db, "user".to_string()).await;
+		let result =
+			get_for_role("123", &mock_db, &get_default_user(), AccessRole::Writer).await;
 
-		assert!(result.is_err());
+		assert_eq!(result.unwrap_err().message, "Reading list not found");
 	}
 
 	#[tokio::test]
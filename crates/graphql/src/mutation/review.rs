@@ -0,0 +1,90 @@
+use async_graphql::{Context, Object, Result, ID};
+use models::entity::{media, review};
+use sea_orm::{prelude::*, IntoActiveModel, QuerySelect};
+
+use crate::{
+	data::{AuthContext, CoreContext},
+	input::review::{CreateReviewInput, UpdateReviewInput},
+	object::review::Review,
+};
+
+#[derive(Default)]
+pub struct ReviewMutation;
+
+#[Object]
+impl ReviewMutation {
+	/// Rate and optionally review a book. A user can only review each book once
+	async fn create_review(
+		&self,
+		ctx: &Context<'_>,
+		input: CreateReviewInput,
+	) -> Result<Review> {
+		let AuthContext { user, .. } = ctx.data::<AuthContext>()?;
+		let conn = ctx.data::<CoreContext>()?.conn.as_ref();
+
+		let media_id: String = media::Entity::find_for_user(user)
+			.select_only()
+			.column(media::Column::Id)
+			.filter(media::Column::Id.eq(input.media_id.to_string()))
+			.into_tuple()
+			.one(conn)
+			.await?
+			.ok_or("Media not found")?;
+
+		let existing = review::Entity::find()
+			.filter(review::Column::MediaId.eq(media_id))
+			.filter(review::Column::UserId.eq(user.id.clone()))
+			.count(conn)
+			.await?;
+		if existing > 0 {
+			return Err("You have already reviewed this book".into());
+		}
+
+		let created = input.into_active_model(&user.id).insert(conn).await?;
+
+		Ok(Review::from(created))
+	}
+
+	/// Update a review. Only the author of a review can update it
+	async fn update_review(
+		&self,
+		ctx: &Context<'_>,
+		id: ID,
+		input: UpdateReviewInput,
+	) -> Result<Review> {
+		let AuthContext { user, .. } = ctx.data::<AuthContext>()?;
+		let conn = ctx.data::<CoreContext>()?.conn.as_ref();
+
+		let review = review::Entity::find_by_id(id.to_string())
+			.filter(review::Column::UserId.eq(user.id.clone()))
+			.one(conn)
+			.await?
+			.ok_or("Review not found")?;
+
+		let mut active_model = review.into_active_model();
+		input.apply(&mut active_model);
+		let updated = active_model.update(conn).await?;
+
+		Ok(Review::from(updated))
+	}
+
+	/// Delete a review. Reviews can be deleted by their author or the server owner
+	async fn delete_review(&self, ctx: &Context<'_>, id: ID) -> Result<Review> {
+		let AuthContext { user, .. } = ctx.data::<AuthContext>()?;
+		let conn = ctx.data::<CoreContext>()?.conn.as_ref();
+
+		let review = review::Entity::find_for_user(user)
+			.filter(review::Column::Id.eq(id.to_string()))
+			.one(conn)
+			.await?
+			.ok_or("Review not found")?;
+
+		if review.user_id != user.id && !user.is_server_owner {
+			return Err("You can only delete your own reviews".into());
+		}
+
+		review.clone().delete(conn).await?;
+
+		Ok(Review::from(review))
+	}
+}
@@ -21,6 +21,14 @@ fn kind_from_config(config: &ScheduledJobConfigInput) -> ScheduledJobKind {
 	match config {
 		ScheduledJobConfigInput::LibraryScan(_) => ScheduledJobKind::LibraryScan,
 		ScheduledJobConfigInput::MetadataRetry(_) => ScheduledJobKind::MetadataRetry,
+		ScheduledJobConfigInput::ThumbnailRegeneration(_) => {
+			ScheduledJobKind::ThumbnailRegeneration
+		},
+		ScheduledJobConfigInput::MediaAnalysis(_) => ScheduledJobKind::MediaAnalysis,
+		ScheduledJobConfigInput::LibraryCleanup(_) => ScheduledJobKind::LibraryCleanup,
+		ScheduledJobConfigInput::DatabaseBackup(_) => ScheduledJobKind::DatabaseBackup,
+		ScheduledJobConfigInput::HistoryPruning(_) => ScheduledJobKind::HistoryPruning,
+		ScheduledJobConfigInput::OpdsCacheWarmup(_) => ScheduledJobKind::OpdsCacheWarmup,
 	}
 }
 
@@ -73,9 +81,11 @@ impl ScheduledJobConfigMutation {
 			active.name = Set(name);
 		}
 
+		// A previous error is likely stale once the schedule or config changes
 		if let Some(schedule) = input.schedule {
 			validate_cron_expression(&schedule).map_err(async_graphql::Error::new)?;
 			active.schedule = Set(schedule);
+			active.last_error = Set(None);
 		}
 
 		if let Some(ref config) = input.config {
@@ -83,6 +93,7 @@ impl ScheduledJobConfigMutation {
 			let config_json = serde_json::to_value(config)?;
 			active.kind = Set(kind);
 			active.config = Set(Some(config_json));
+			active.last_error = Set(None);
 		}
 
 		if let Some(enabled) = input.enabled {
@@ -5,7 +5,7 @@ use models::{
 		favorite_series, finished_reading_session, library, library_config, media,
 		reading_session, series, user::AuthUser,
 	},
-	shared::enums::UserPermission,
+	shared::{enums::UserPermission, image_processor_options::SupportedImageFormat},
 };
 use sea_orm::{
 	prelude::*,
@@ -18,7 +18,11 @@ use stump_core::{
 	database::SQLITE_BIND_LIMIT,
 	filesystem::{
 		image::{generate_book_thumbnail, GenerateThumbnailOptions},
-		media::analysis::{AnalysisJobConfig, MediaAnalysisJobScope},
+		media::{
+			analysis::{AnalysisJobConfig, MediaAnalysisJobScope},
+			convert::{ConvertMediaJobConfig, MediaConversionScope},
+			write_back::{MetadataWriteBackScope, WriteBackMetadataJobConfig},
+		},
 	},
 };
 
@@ -61,6 +65,62 @@ impl SeriesMutation {
 		Ok(true)
 	}
 
+	/// Write the stored metadata of every book in a series back into the files
+	#[graphql(guard = "PermissionGuard::one(UserPermission::WriteBackMetadata)")]
+	async fn write_back_series_metadata(
+		&self,
+		ctx: &Context<'_>,
+		id: ID,
+	) -> Result<bool> {
+		let AuthContext { user, .. } = ctx.data::<AuthContext>()?;
+		let core = ctx.data::<CoreContext>()?;
+		let conn = core.conn.as_ref();
+
+		let model =
+			series::Entity::find_series_ident_for_user_and_id(user, id.to_string())
+				.into_model::<series::SeriesIdentSelect>()
+				.one(conn)
+				.await?
+				.ok_or("Series not found")?;
+
+		core.enqueue(StumpJob::write_back_metadata(WriteBackMetadataJobConfig {
+			scope: MetadataWriteBackScope::Series(model.id),
+		}))
+		.await?;
+
+		Ok(true)
+	}
+
+	/// Convert every CBR, CB7 and PDF book in a series into a CBZ archive
+	#[graphql(guard = "PermissionGuard::one(UserPermission::ManageLibrary)")]
+	async fn convert_series(
+		&self,
+		ctx: &Context<'_>,
+		id: ID,
+		#[graphql(default = false)] delete_source: bool,
+		image_format: Option<SupportedImageFormat>,
+	) -> Result<bool> {
+		let AuthContext { user, .. } = ctx.data::<AuthContext>()?;
+		let core = ctx.data::<CoreContext>()?;
+		let conn = core.conn.as_ref();
+
+		let model =
+			series::Entity::find_series_ident_for_user_and_id(user, id.to_string())
+				.into_model::<series::SeriesIdentSelect>()
+				.one(conn)
+				.await?
+				.ok_or("Series not found")?;
+
+		core.enqueue(StumpJob::convert_media(ConvertMediaJobConfig {
+			scope: MediaConversionScope::Series(model.id),
+			delete_source,
+			image_format,
+		}))
+		.await?;
+
+		Ok(true)
+	}
+
 	async fn favorite_series(
 		&self,
 		ctx: &Context<'_>,
@@ -152,6 +152,7 @@ impl SeriesMetadataMutation {
 			conn,
 			&model.series.id,
 			&search_name,
+			&model.series.path,
 			&provider_cache,
 		)
 		.await?;
@@ -10,6 +10,7 @@ use crate::{
 	utils::save_user_session,
 };
 use async_graphql::{Context, Object, Result, Upload, ID};
+use integrations::NotificationEvent;
 use models::{
 	entity::{
 		age_restriction, session,
@@ -250,7 +251,12 @@ impl UserMutation {
 
 		txn.commit().await?;
 
-		Ok(User::from(user_model.try_into_model()?))
+		let user_model = user_model.try_into_model()?;
+		core_ctx.notify(NotificationEvent::UserRegistered {
+			username: user_model.username.clone(),
+		});
+
+		Ok(User::from(user_model))
 	}
 
 	async fn update_viewer(
@@ -0,0 +1,145 @@
+use async_graphql::{ComplexObject, Context, Result, SimpleObject};
+use models::{
+	entity::{collection, collection_access_rule, collection_item, series, user},
+	shared::{enums::AccessRole, image::ImageRef},
+};
+use sea_orm::{prelude::*, QuerySelect};
+
+use crate::{
+	data::{AuthContext, CoreContext, ServiceContext},
+	object::{series::Series, user::User},
+};
+
+#[derive(Debug, SimpleObject)]
+#[graphql(complex)]
+pub struct Collection {
+	#[graphql(flatten)]
+	pub model: collection::Model,
+}
+
+impl From<collection::Model> for Collection {
+	fn from(model: collection::Model) -> Self {
+		Self { model }
+	}
+}
+
+#[ComplexObject]
+impl Collection {
+	/// The series in the collection which the user has access to, in order
+	async fn series(&self, ctx: &Context<'_>) -> Result<Vec<Series>> {
+		let AuthContext { user, .. } = ctx.data::<AuthContext>()?;
+		let conn = ctx.data::<CoreContext>()?.conn.as_ref();
+
+		let models = collection_item::series_in_collection(
+			series::ModelWithMetadata::find_for_user(user),
+			&self.model.id,
+		)
+		.into_model::<series::ModelWithMetadata>()
+		.all(conn)
+		.await?;
+
+		Ok(models.into_iter().map(Series::from).collect())
+	}
+
+	async fn series_count(&self, ctx: &Context<'_>) -> Result<u64> {
+		let AuthContext { user, .. } = ctx.data::<AuthContext>()?;
+		let conn = ctx.data::<CoreContext>()?.conn.as_ref();
+
+		let count = collection_item::series_in_collection(
+			series::Entity::find_for_user(user),
+			&self.model.id,
+		)
+		.count(conn)
+		.await?;
+
+		Ok(count)
+	}
+
+	/// The cover of the collection, which is the thumbnail of the cover series or the
+	/// first series in the collection. This is null for an empty collection
+	async fn thumbnail(&self, ctx: &Context<'_>) -> Result<Option<ImageRef>> {
+		let AuthContext { user, .. } = ctx.data::<AuthContext>()?;
+		let conn = ctx.data::<CoreContext>()?.conn.as_ref();
+		let service = ctx.data::<ServiceContext>()?;
+
+		let cover_series = match &self.model.cover_series_id {
+			Some(id) => {
+				collection_item::series_in_collection(
+					series::Entity::find_for_user(user),
+					&self.model.id,
+				)
+				.filter(series::Column::Id.eq(id))
+				.one(conn)
+				.await?
+			},
+			None => None,
+		};
+		let cover_series = match cover_series {
+			Some(series) => Some(series),
+			None => {
+				collection_item::series_in_collection(
+					series::Entity::find_for_user(user),
+					&self.model.id,
+				)
+				.one(conn)
+				.await?
+			},
+		};
+
+		Ok(cover_series.map(|series| {
+			let dimensions = series
+				.thumbnail_meta
+				.as_ref()
+				.and_then(|meta| meta.dimensions.as_ref())
+				.map(|dim| (dim.width, dim.height));
+
+			ImageRef {
+				url: service
+					.format_url(format!("/api/v2/series/{}/thumbnail", series.id)),
+				height: dimensions.map(|dim| dim.1),
+				width: dimensions.map(|dim| dim.0),
+				metadata: series.thumbnail_meta,
+			}
+		}))
+	}
+
+	async fn creator(&self, ctx: &Context<'_>) -> Result<User> {
+		let conn = ctx.data::<CoreContext>()?.conn.as_ref();
+		let model = user::Entity::find_by_id(self.model.creator_id.clone())
+			.one(conn)
+			.await?
+			.ok_or("User not found")?;
+
+		Ok(User::from(model))
+	}
+
+	/// The access rules of the collection. These are only visible to the users who are able
+	/// to manage them, and will be empty for everyone else
+	async fn access_rules(
+		&self,
+		ctx: &Context<'_>,
+	) -> Result<Vec<collection_access_rule::Model>> {
+		let AuthContext { user, .. } = ctx.data::<AuthContext>()?;
+		let conn = ctx.data::<CoreContext>()?.conn.as_ref();
+
+		let can_manage =
+			collection::Entity::find_for_user_with_role(user, AccessRole::CoCreator)
+				.select_only()
+				.column(collection::Column::Id)
+				.filter(collection::Column::Id.eq(self.model.id.clone()))
+				.count(conn)
+				.await? > 0;
+		if !can_manage {
+			return Ok(vec![]);
+		}
+
+		let rules = collection_access_rule::Entity::find()
+			.filter(
+				collection_access_rule::Column::CollectionId.eq(self.model.id.clone()),
+			)
+			.all(conn)
+			.await?;
+
+		Ok(rules)
+	}
+}
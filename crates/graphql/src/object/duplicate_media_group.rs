@@ -0,0 +1,50 @@
+use async_graphql::{ComplexObject, Context, Result, SimpleObject};
+use models::entity::{duplicate_media_group, duplicate_media_group_member, media};
+use sea_orm::{prelude::*, sea_query::Query, QueryOrder};
+
+use crate::{
+	data::{AuthContext, CoreContext},
+	object::media::Media,
+};
+
+#[derive(Debug, SimpleObject)]
+#[graphql(complex)]
+pub struct DuplicateMediaGroup {
+	#[graphql(flatten)]
+	pub model: duplicate_media_group::Model,
+}
+
+#[ComplexObject]
+impl DuplicateMediaGroup {
+	/// The media items in the group which the user has access to
+	async fn media(&self, ctx: &Context<'_>) -> Result<Vec<Media>> {
+		let conn = ctx.data::<CoreContext>()?.conn.as_ref();
+		let AuthContext { user, .. } = ctx.data::<AuthContext>()?;
+
+		let models = media::ModelWithMetadata::find_for_user(user)
+			.filter(
+				media::Column::Id.in_subquery(
+					Query::select()
+						.column(duplicate_media_group_member::Column::MediaId)
+						.from(duplicate_media_group_member::Entity)
+						.and_where(
+							duplicate_media_group_member::Column::GroupId
+								.eq(&self.model.id),
+						)
+						.to_owned(),
+				),
+			)
+			.order_by_asc(media::Column::CreatedAt)
+			.into_model::<media::ModelWithMetadata>()
+			.all(conn)
+			.await?;
+
+		Ok(models.into_iter().map(Media::from).collect())
+	}
+}
+
+impl From<duplicate_media_group::Model> for DuplicateMediaGroup {
+	fn from(model: duplicate_media_group::Model) -> Self {
+		Self { model }
+	}
+}
@@ -48,6 +48,21 @@ impl Job {
 		}
 	}
 
+	/// The payloads of the tasks which failed during the job. These can be run again with
+	/// `retryFailedJobTasks`
+	async fn failed_tasks(&self) -> Vec<Json<serde_json::Value>> {
+		match &self.model.failed_tasks {
+			Some(data) => serde_json::from_slice::<Vec<serde_json::Value>>(data.as_ref())
+				.map_err(|error| {
+					tracing::error!(?error, "Failed to parse job failed tasks");
+					error
+				})
+				.map(|tasks| tasks.into_iter().map(Json).collect())
+				.unwrap_or_default(),
+			None => vec![],
+		}
+	}
+
 	#[graphql(guard = "PermissionGuard::one(UserPermission::ReadPersistedLogs)")]
 	async fn log_count(&self, ctx: &Context<'_>) -> Result<u64> {
 		let loader = ctx.data::<DataLoader<JobAssociatedLogLoader>>()?;
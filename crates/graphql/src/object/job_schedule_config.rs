@@ -13,6 +13,9 @@ pub struct ScheduledJob {
 	pub enabled: bool,
 	pub created_at: DateTimeUtc,
 	pub last_run_at: Option<DateTimeUtc>,
+	/// Why the job last failed to start or run, e.g. an invalid cron expression. This is
+	/// cleared after the next successful run
+	pub last_error: Option<String>,
 }
 
 impl From<scheduled_job::Model> for ScheduledJob {
@@ -26,6 +29,7 @@ impl From<scheduled_job::Model> for ScheduledJob {
 			enabled: m.enabled,
 			created_at: m.created_at,
 			last_run_at: m.last_run_at,
+			last_error: m.last_error,
 		}
 	}
 }
@@ -3,13 +3,17 @@ use async_graphql::{
 };
 
 use models::{
-	entity::{library, media, media_analysis, series, tag},
+	entity::{
+		library, media, media_analysis,
+		review::{self, RatingSummary},
+		series, tag,
+	},
 	shared::{analysis::MediaAnalysisData, image::ImageRef},
 };
 use num_traits::cast::ToPrimitive;
 use sea_orm::{
-	prelude::*, sea_query::Query, DatabaseBackend, FromQueryResult, QuerySelect,
-	Statement,
+	prelude::*, sea_query::Query, DatabaseBackend, FromQueryResult, QueryOrder,
+	QuerySelect, Statement,
 };
 
 use crate::{
@@ -33,6 +37,7 @@ use super::{
 	library_config::LibraryConfig,
 	media_metadata::MediaMetadata,
 	reading_session::{ActiveReadingSession, FinishedReadingSession},
+	review::Review,
 	series::Series,
 	tag::Tag,
 };
@@ -265,6 +270,34 @@ impl Media {
 		Ok(history)
 	}
 
+	/// The reviews of the book which the user is able to see, newest first
+	async fn reviews(&self, ctx: &Context<'_>) -> Result<Vec<Review>> {
+		let AuthContext { user, .. } = ctx.data::<AuthContext>()?;
+		let conn = ctx.data::<CoreContext>()?.conn.as_ref();
+
+		let models = review::Entity::find_for_user(user)
+			.filter(review::Column::MediaId.eq(self.model.id.clone()))
+			.order_by_desc(review::Column::CreatedAt)
+			.all(conn)
+			.await?;
+
+		Ok(models.into_iter().map(Review::from).collect())
+	}
+
+	/// The average rating of the book, from its public reviews
+	async fn rating_summary(&self, ctx: &Context<'_>) -> Result<RatingSummary> {
+		let conn = ctx.data::<CoreContext>()?.conn.as_ref();
+
+		let summary = review::rating_summary()
+			.filter(review::Column::MediaId.eq(self.model.id.clone()))
+			.into_model::<RatingSummary>()
+			.one(conn)
+			.await?
+			.unwrap_or_default();
+
+		Ok(summary)
+	}
+
 	async fn series_position(&self, ctx: &Context<'_>) -> Result<Option<i32>> {
 		let conn = ctx.data::<CoreContext>()?.conn.as_ref();
 
@@ -8,8 +8,10 @@ pub mod book_club_discussion_message;
 pub mod book_club_invitation;
 pub mod book_club_member;
 pub mod bookmark;
+pub mod collection;
 pub mod custom_emoji;
 pub mod directory_listing;
+pub mod duplicate_media_group;
 pub mod email_device;
 pub mod emailer;
 pub mod emailer_send_record;
@@ -30,6 +32,7 @@ pub mod notifier;
 pub mod reading_list;
 pub mod reading_list_item;
 pub mod reading_session;
+pub mod review;
 pub mod series;
 pub mod series_metadata;
 pub mod smart_list_item;
@@ -19,4 +19,9 @@ impl Notifier {
 	pub async fn config(&self, _ctx: &Context<'_>) -> Result<notifier::NotifierConfig> {
 		notifier::NotifierConfig::from_bytes(&self.model.config)
 	}
+
+	/// The events the notifier is sent
+	pub async fn events(&self) -> Vec<notifier::NotificationEventKind> {
+		self.model.events()
+	}
 }
@@ -1,15 +1,69 @@
-use async_graphql::SimpleObject;
+use async_graphql::{ComplexObject, Context, Result, SimpleObject};
 
-use models::entity::reading_list;
+use models::{
+	entity::{reading_list, reading_list_rule},
+	shared::enums::AccessRole,
+};
+use sea_orm::prelude::*;
+
+use crate::data::{AuthContext, CoreContext};
 
 #[derive(Debug, SimpleObject)]
+#[graphql(complex)]
 pub struct ReadingList {
 	#[graphql(flatten)]
 	pub model: reading_list::Model,
 }
 
+/// A book in an imported CBL file which could not be matched to a book on the server
+#[derive(Debug, SimpleObject)]
+pub struct UnmatchedCBLBook {
+	/// The position of the book in the CBL file, starting at 0
+	pub position: i32,
+	pub series: Option<String>,
+	pub number: Option<String>,
+	pub volume: Option<String>,
+	pub year: Option<String>,
+}
+
+#[derive(Debug, SimpleObject)]
+pub struct ImportCBLOutput {
+	pub reading_list: ReadingList,
+	/// The number of books in the CBL file which were added to the reading list
+	pub matched_count: u64,
+	pub unmatched: Vec<UnmatchedCBLBook>,
+}
+
 impl From<reading_list::Model> for ReadingList {
 	fn from(entity: reading_list::Model) -> Self {
 		Self { model: entity }
 	}
 }
+
+#[ComplexObject]
+impl ReadingList {
+	/// The rules which grant other users a role on the reading list. These are only visible
+	/// to the users who are able to manage them, and will be empty for everyone else
+	async fn rules(&self, ctx: &Context<'_>) -> Result<Vec<reading_list_rule::Model>> {
+		let AuthContext { user, .. } = ctx.data::<AuthContext>()?;
+		let conn = ctx.data::<CoreContext>()?.conn.as_ref();
+
+		let can_manage = reading_list::Entity::find_for_user_and_id(
+			user,
+			AccessRole::CoCreator,
+			&self.model.id,
+		)
+		.count(conn)
+		.await? > 0;
+		if !can_manage {
+			return Ok(vec![]);
+		}
+
+		let rules = reading_list_rule::Entity::find()
+			.filter(reading_list_rule::Column::ReadingListId.eq(self.model.id.clone()))
+			.all(conn)
+			.await?;
+
+		Ok(rules)
+	}
+}
@@ -0,0 +1,48 @@
+use async_graphql::{ComplexObject, Context, Result, SimpleObject};
+use models::entity::{media, review, user};
+use sea_orm::prelude::*;
+
+use crate::{
+	data::{AuthContext, CoreContext},
+	object::{media::Media, user::User},
+};
+
+#[derive(Debug, SimpleObject)]
+#[graphql(complex)]
+pub struct Review {
+	#[graphql(flatten)]
+	pub model: review::Model,
+}
+
+impl From<review::Model> for Review {
+	fn from(model: review::Model) -> Self {
+		Self { model }
+	}
+}
+
+#[ComplexObject]
+impl Review {
+	async fn user(&self, ctx: &Context<'_>) -> Result<User> {
+		let core = ctx.data::<CoreContext>()?;
+		let model = user::Entity::find_by_id(self.model.user_id.clone())
+			.one(core.conn.as_ref())
+			.await?
+			.ok_or_else(|| async_graphql::Error::new("User not found"))?;
+
+		Ok(User::from(model))
+	}
+
+	/// The reviewed book, if the user has access to it
+	async fn media(&self, ctx: &Context<'_>) -> Result<Option<Media>> {
+		let AuthContext { user, .. } = ctx.data::<AuthContext>()?;
+		let core = ctx.data::<CoreContext>()?;
+
+		let model = media::ModelWithMetadata::find_for_user(user)
+			.filter(media::Column::Id.eq(self.model.media_id.clone()))
+			.into_model::<media::ModelWithMetadata>()
+			.one(core.conn.as_ref())
+			.await?;
+
+		Ok(model.map(Media::from))
+	}
+}
@@ -6,8 +6,9 @@ use async_graphql::{
 
 use models::{
 	entity::{
-		finished_reading_session, library, media, reading_session, series, series_tag,
-		tag,
+		finished_reading_session, library, media, reading_session,
+		review::{self, RatingSummary},
+		series, series_tag, tag,
 	},
 	shared::{
 		alphabet::{AvailableAlphabet, EntityLetter},
@@ -123,6 +124,29 @@ impl Series {
 		Ok(media_count)
 	}
 
+	/// The average rating of the books in the series, from their public reviews
+	async fn rating_summary(&self, ctx: &Context<'_>) -> Result<RatingSummary> {
+		let conn = ctx.data::<CoreContext>()?.conn.as_ref();
+
+		let summary = review::rating_summary()
+			.filter(
+				review::Column::MediaId.in_subquery(
+					Query::select()
+						.column(media::Column::Id)
+						.from(media::Entity)
+						.and_where(media::Column::SeriesId.eq(self.model.id.clone()))
+						.and_where(media::Column::DeletedAt.is_null())
+						.to_owned(),
+				),
+			)
+			.into_model::<RatingSummary>()
+			.one(conn)
+			.await?
+			.unwrap_or_default();
+
+		Ok(summary)
+	}
+
 	async fn media_alphabet(&self, ctx: &Context<'_>) -> Result<HashMap<String, i64>> {
 		let conn = ctx.data::<CoreContext>()?.conn.as_ref();
 
@@ -3,10 +3,13 @@ use std::str::FromStr;
 use async_graphql::{InputObject, OneofObject};
 use heck::ToSnakeCase;
 use models::{
-	entity::{media, media_metadata, series, series_metadata},
+	entity::{media, media_metadata, review, series, series_metadata},
 	shared::ordering::{OrderBy, OrderDirection},
 };
-use sea_orm::QueryOrder;
+use sea_orm::{
+	sea_query::{SimpleExpr, SubQueryStatement},
+	QueryOrder,
+};
 
 #[derive(InputObject, Clone)]
 #[graphql(concrete(name = "MediaOrderByField", params(media::MediaModelOrdering)))]
@@ -28,6 +31,8 @@ pub struct OrderByField<OrderBy: Send + Sync + async_graphql::InputType> {
 pub enum MediaOrderBy {
 	Media(OrderByField<media::MediaModelOrdering>),
 	Metadata(OrderByField<media_metadata::MediaMetadataModelOrdering>),
+	/// Order by the average rating of the media, from its public reviews
+	AverageRating(OrderDirection),
 }
 
 #[derive(OneofObject, Clone)]
@@ -77,6 +82,16 @@ impl OrderBy<media::Entity, MediaOrderBy> for MediaOrderBy {
 					)?;
 					query = query.order_by(field, order);
 				},
+				MediaOrderBy::AverageRating(direction) => {
+					let order = sea_orm::Order::from(*direction);
+					let average_rating = SimpleExpr::SubQuery(
+						None,
+						Box::new(SubQueryStatement::SelectStatement(
+							review::average_rating_subquery(),
+						)),
+					);
+					query = query.order_by(average_rating, order);
+				},
 			}
 		}
 		Ok(query)
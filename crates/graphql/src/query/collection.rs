@@ -0,0 +1,50 @@
+use async_graphql::{Context, Object, Result, ID};
+use models::entity::collection;
+use sea_orm::{prelude::*, QueryOrder, QueryTrait};
+
+use crate::{
+	data::{AuthContext, CoreContext},
+	object::collection::Collection,
+};
+
+#[derive(Default)]
+pub struct CollectionQuery;
+
+#[Object]
+impl CollectionQuery {
+	/// The collections the current user is able to see, ordered by name
+	async fn collections(
+		&self,
+		ctx: &Context<'_>,
+		search: Option<String>,
+	) -> Result<Vec<Collection>> {
+		let AuthContext { user, .. } = ctx.data::<AuthContext>()?;
+		let conn = ctx.data::<CoreContext>()?.conn.as_ref();
+
+		let collections = collection::Entity::find_for_user(user)
+			.apply_if(search, |query, search| {
+				query.filter(collection::Column::Name.contains(search))
+			})
+			.order_by_asc(collection::Column::Name)
+			.all(conn)
+			.await?;
+
+		Ok(collections.into_iter().map(Collection::from).collect())
+	}
+
+	async fn collection_by_id(
+		&self,
+		ctx: &Context<'_>,
+		id: ID,
+	) -> Result<Option<Collection>> {
+		let AuthContext { user, .. } = ctx.data::<AuthContext>()?;
+		let conn = ctx.data::<CoreContext>()?.conn.as_ref();
+
+		let collection = collection::Entity::find_for_user(user)
+			.filter(collection::Column::Id.eq(id.to_string()))
+			.one(conn)
+			.await?;
+
+		Ok(collection.map(Collection::from))
+	}
+}
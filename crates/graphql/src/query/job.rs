@@ -94,6 +94,14 @@ impl JobQuery {
 		Ok(job.map(Job::from))
 	}
 
+	/// The jobs which are waiting to be started, in the order they will be started
+	#[graphql(guard = "PermissionGuard::one(UserPermission::ReadJobs)")]
+	async fn queued_jobs(&self, ctx: &Context<'_>) -> Result<Vec<Job>> {
+		let core = ctx.data::<CoreContext>()?;
+		let models = core.job_storage.list_queued().await?;
+		Ok(models.into_iter().map(Job::from).collect())
+	}
+
 	// TODO(permissions): Determine if folks generally agree with this access
 	#[graphql(guard = "PermissionGuard::one(UserPermission::ReadJobs)")]
 	async fn scheduled_jobs(&self, ctx: &Context<'_>) -> Result<Vec<ScheduledJob>> {
@@ -3,11 +3,12 @@ use std::collections::HashMap;
 use async_graphql::{Context, Object, Result, ID};
 use models::{
 	entity::{
-		finished_reading_session, media, media_metadata, reading_session, user::AuthUser,
+		duplicate_media_group, finished_reading_session, media, media_metadata,
+		reading_session, user::AuthUser,
 	},
 	shared::{
 		alphabet::{AvailableAlphabet, EntityLetter},
-		enums::UserPermission,
+		enums::{DuplicateReason, UserPermission},
 		ordering::OrderBy,
 	},
 };
@@ -15,14 +16,14 @@ use sea_orm::{
 	prelude::*,
 	sea_query::{ExprTrait, Query},
 	Condition, DatabaseBackend, FromQueryResult, JoinType, QueryOrder, QuerySelect,
-	Statement,
+	QueryTrait, Statement,
 };
 
 use crate::{
 	data::{AuthContext, CoreContext},
 	filter::{media::MediaFilterInput, IntoFilter},
 	guard::{PermissionGuard, ServerOwnerGuard},
-	object::media::Media,
+	object::{duplicate_media_group::DuplicateMediaGroup, media::Media},
 	order::MediaOrderBy,
 	pagination::{
 		CursorPaginationInfo, OffsetPaginationInfo, PaginatedResponse, Pagination,
@@ -33,55 +34,53 @@ use crate::{
 #[derive(Default)]
 pub struct MediaQuery;
 
-pub fn should_add_sessions_join_for_filter(filter: &MediaFilterInput) -> bool {
-	filter.reading_status.is_some()
-		|| [&filter._and, &filter._or, &filter._not]
-			.iter()
-			.filter_map(|opt| opt.as_ref())
-			.any(|filters| filters.iter().any(|f| f.reading_status.is_some()))
-}
-
 pub fn add_sessions_join_for_filter(
 	user: &AuthUser,
 	filter: &MediaFilterInput,
 	query: Select<media::Entity>,
 ) -> Select<media::Entity> {
-	let should_join_sessions = should_add_sessions_join_for_filter(filter);
-
-	if should_join_sessions {
-		let user_id = user.id.clone();
-		let user_id_cpy = user_id.clone();
-		query
-			.join_rev(
-				JoinType::LeftJoin,
-				reading_session::Entity::belongs_to(media::Entity)
-					.from(reading_session::Column::MediaId)
-					.to(media::Column::Id)
-					.on_condition(move |_left, _right| {
-						Condition::all()
-							.add(reading_session::Column::UserId.eq(user_id.clone()))
-					})
-					.into(),
-			)
-			.join_rev(
-				JoinType::LeftJoin,
-				finished_reading_session::Entity::belongs_to(media::Entity)
-					.from(finished_reading_session::Column::MediaId)
-					.to(media::Column::Id)
-					.on_condition(move |_left, _right| {
-						Condition::all().add(
-							finished_reading_session::Column::UserId
-								.eq(user_id_cpy.clone()),
-						)
-					})
-					.into(),
-			)
-			.group_by(media::Column::Id)
+	if filter.uses_reading_sessions() {
+		add_reading_sessions_join(user, query)
 	} else {
 		query
 	}
 }
 
+/// Join the user's current and finished reading sessions to the media, grouping by the
+/// media since a book may have been finished more than once
+pub fn add_reading_sessions_join(
+	user: &AuthUser,
+	query: Select<media::Entity>,
+) -> Select<media::Entity> {
+	let user_id = user.id.clone();
+	let user_id_cpy = user_id.clone();
+	query
+		.join_rev(
+			JoinType::LeftJoin,
+			reading_session::Entity::belongs_to(media::Entity)
+				.from(reading_session::Column::MediaId)
+				.to(media::Column::Id)
+				.on_condition(move |_left, _right| {
+					Condition::all()
+						.add(reading_session::Column::UserId.eq(user_id.clone()))
+				})
+				.into(),
+		)
+		.join_rev(
+			JoinType::LeftJoin,
+			finished_reading_session::Entity::belongs_to(media::Entity)
+				.from(finished_reading_session::Column::MediaId)
+				.to(media::Column::Id)
+				.on_condition(move |_left, _right| {
+					Condition::all().add(
+						finished_reading_session::Column::UserId.eq(user_id_cpy.clone()),
+					)
+				})
+				.into(),
+		)
+		.group_by(media::Column::Id)
+}
+
 #[Object]
 impl MediaQuery {
 	async fn media_count(&self, ctx: &Context<'_>) -> Result<i64> {
@@ -761,6 +760,28 @@ impl MediaQuery {
 
 		Ok(models.into_iter().map(Media::from).collect())
 	}
+
+	/// The groups of likely duplicate media found by the last duplicate detection job,
+	/// optionally limited to a single reason
+	#[graphql(guard = "PermissionGuard::one(UserPermission::ManageLibrary)")]
+	async fn duplicate_media_groups(
+		&self,
+		ctx: &Context<'_>,
+		reason: Option<DuplicateReason>,
+	) -> Result<Vec<DuplicateMediaGroup>> {
+		let conn = ctx.data::<CoreContext>()?.conn.as_ref();
+
+		let groups = duplicate_media_group::Entity::find()
+			.apply_if(reason, |query, reason| {
+				query.filter(duplicate_media_group::Column::Reason.eq(reason))
+			})
+			.order_by_asc(duplicate_media_group::Column::Reason)
+			.order_by_asc(duplicate_media_group::Column::Key)
+			.all(conn)
+			.await?;
+
+		Ok(groups.into_iter().map(DuplicateMediaGroup::from).collect())
+	}
 }
 
 #[cfg(test)]
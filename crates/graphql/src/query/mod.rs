@@ -5,6 +5,7 @@ mod book_club_book;
 mod book_club_discussion;
 mod book_club_invitation;
 mod book_club_suggestion;
+mod collection;
 mod config;
 mod custom_emoji;
 mod email_device;
@@ -23,7 +24,7 @@ mod series;
 mod server_config;
 mod smart_list_view;
 mod smart_lists;
-pub(crate) mod smart_lists_builder;
+pub mod smart_lists_builder;
 mod tag;
 pub(crate) mod user;
 
@@ -34,6 +35,7 @@ use book_club_book::BookClubBookQuery;
 use book_club_discussion::BookClubDiscussionQuery;
 use book_club_invitation::BookClubInvitationQuery;
 use book_club_suggestion::BookClubSuggestionQuery;
+use collection::CollectionQuery;
 use config::ConfigQuery;
 use custom_emoji::CustomEmojiQuery;
 use email_device::EmailDeviceQuery;
@@ -99,6 +101,7 @@ struct ListQueries(
 	SmartListsQuery,
 	SmartListViewQuery,
 	ReadingListQuery,
+	CollectionQuery,
 	CustomEmojiQuery,
 );
 
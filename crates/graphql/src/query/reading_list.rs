@@ -1,7 +1,10 @@
 use async_graphql::{Context, Object, Result, ID};
 
 use crate::pagination::get_paginated_results;
-use models::entity::{reading_list, user::AuthUser};
+use models::{
+	entity::{reading_list, user::AuthUser},
+	shared::enums::AccessRole,
+};
 use sea_orm::prelude::*;
 
 use crate::{
@@ -39,10 +42,14 @@ impl ReadingListQuery {
 		let AuthContext { user, .. } = ctx.data::<AuthContext>()?;
 		let conn = ctx.data::<CoreContext>()?.conn.as_ref();
 
-		let query = reading_list::Entity::find_for_user_and_id(user, 1, id.as_ref())
-			.into_model::<reading_list::Model>()
-			.one(conn)
-			.await?;
+		let query = reading_list::Entity::find_for_user_and_id(
+			user,
+			AccessRole::Reader,
+			id.as_ref(),
+		)
+		.into_model::<reading_list::Model>()
+		.one(conn)
+		.await?;
 
 		Ok(ReadingList::from(query.ok_or("Reading list not found")?))
 	}
@@ -53,7 +60,7 @@ async fn get_paginated_reading_list(
 	conn: &DbConn,
 	pagination: Pagination,
 ) -> Result<PaginatedResponse<ReadingList>> {
-	let query = reading_list::Entity::find_for_user(user, 1);
+	let query = reading_list::Entity::find_for_user(user, AccessRole::Reader);
 	let get_cursor =
 		|m: &<models::entity::reading_list::Entity as sea_orm::EntityTrait>::Model| {
 			m.id.to_string()
@@ -10,7 +10,7 @@ use crate::{
 			SmartListUngrouped,
 		},
 	},
-	query::media::{add_sessions_join_for_filter, should_add_sessions_join_for_filter},
+	query::media::add_reading_sessions_join,
 };
 use async_graphql::Result;
 use models::entity::{
@@ -236,27 +236,21 @@ fn add_sessions_join(
 	query: Select<media::Entity>,
 	filters: &[SmartListFilterGroupInput],
 ) -> Select<media::Entity> {
-	let filter_using_session = filters.iter().find(|filter_group| {
-		for filter in &filter_group.groups {
-			if let SmartListFilterInput::Media(media_filter) = filter {
-				if should_add_sessions_join_for_filter(media_filter) {
-					return true;
-				}
-			}
-		}
-
-		false
-	});
+	let is_using_sessions = filters
+		.iter()
+		.flat_map(|filter_group| &filter_group.groups)
+		.any(|filter| match filter {
+			SmartListFilterInput::Media(media_filter) => {
+				media_filter.uses_reading_sessions()
+			},
+			_ => false,
+		});
 
-	if let Some(filter_group) = filter_using_session {
-		for filter in &filter_group.groups {
-			if let SmartListFilterInput::Media(media_filter) = filter {
-				return add_sessions_join_for_filter(user, media_filter, query);
-			}
-		}
+	if is_using_sessions {
+		add_reading_sessions_join(user, query)
+	} else {
+		query
 	}
-
-	query
 }
 
 #[cfg(test)]
@@ -264,7 +258,8 @@ mod tests {
 	use super::*;
 	use crate::{
 		filter::{
-			library::LibraryFilterInput, media::MediaFilterInput, StringLikeFilter,
+			library::LibraryFilterInput, media::MediaFilterInput, NumericFilter,
+			StringLikeFilter,
 		},
 		tests::common::get_default_user,
 	};
@@ -296,13 +291,19 @@ mod tests {
 				id: None,
 				name: Some(StringLikeFilter::Eq("Test".to_string())),
 				_and: None,
+				average_rating: None,
 				created_at: None,
 				extension: None,
+				finished_at: None,
 				metadata: None,
 				_not: None,
 				_or: None,
 				pages: None,
 				path: None,
+				read_on_device: None,
+				reading_progress: None,
+				reading_started_at: None,
+				reading_updated_at: None,
 				reading_status: None,
 				series: None,
 				series_id: None,
@@ -326,13 +327,19 @@ mod tests {
 					id: None,
 					name: Some(StringLikeFilter::Eq("Book".to_string())),
 					_and: None,
+					average_rating: None,
 					created_at: None,
 					extension: None,
+					finished_at: None,
 					metadata: None,
 					_not: None,
 					_or: None,
 					pages: None,
 					path: None,
+					read_on_device: None,
+					reading_progress: None,
+					reading_started_at: None,
+					reading_updated_at: None,
 					reading_status: None,
 					series: None,
 					series_id: None,
@@ -362,6 +369,42 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn test_build_books_query_with_reading_progress() {
+		let filters: Vec<SmartListFilterGroupInput> = vec![
+			SmartListFilterGroupInput {
+				joiner: SmartListGroupJoiner::And,
+				groups: vec![SmartListFilterInput::Library(LibraryFilterInput {
+					id: None,
+					name: Some(StringLikeFilter::Eq("Comics".to_string())),
+					path: None,
+					_and: None,
+					_not: None,
+					_or: None,
+				})],
+			},
+			SmartListFilterGroupInput {
+				joiner: SmartListGroupJoiner::And,
+				groups: vec![SmartListFilterInput::Media(MediaFilterInput {
+					reading_progress: Some(NumericFilter::Gte(0.5)),
+					..Default::default()
+				})],
+			},
+		];
+		let user = get_default_user();
+		let query =
+			build_books_query(&user, smart_list::SmartListJoiner::And, &filters, None);
+
+		let sql = query
+			.select_only()
+			.into_query()
+			.to_string(SqliteQueryBuilder);
+		assert_eq!(
+			sql,
+			r#"SELECT  FROM "media" LEFT JOIN "media_metadata" ON "media"."id" = "media_metadata"."media_id" INNER JOIN "series" ON "media"."series_id" = "series"."id" LEFT JOIN "series_metadata" ON "series_metadata"."series_id" = "series"."id" LEFT JOIN "reading_sessions" ON "reading_sessions"."media_id" = "media"."id" AND "reading_sessions"."user_id" = '42' LEFT JOIN "finished_reading_sessions" ON "finished_reading_sessions"."media_id" = "media"."id" AND "finished_reading_sessions"."user_id" = '42' INNER JOIN "libraries" ON "libraries"."id" = "series"."library_id" WHERE "series"."library_id" NOT IN (SELECT "library_id" FROM "library_exclusions" WHERE "library_exclusions"."user_id" = '42') AND "libraries"."name" = 'Comics' AND "reading_sessions"."percentage_completed" >= 0.5 GROUP BY "media"."id""#
+		);
+	}
+
 	#[test]
 	fn test_build_books_query() {
 		let filters: Vec<SmartListFilterGroupInput> = vec![
@@ -371,13 +414,19 @@ mod tests {
 					id: None,
 					name: Some(StringLikeFilter::Eq("Book".to_string())),
 					_and: None,
+					average_rating: None,
 					created_at: None,
 					extension: None,
+					finished_at: None,
 					metadata: None,
 					_not: None,
 					_or: None,
 					pages: None,
 					path: None,
+					read_on_device: None,
+					reading_progress: None,
+					reading_started_at: None,
+					reading_updated_at: None,
 					reading_status: None,
 					series: None,
 					series_id: None,
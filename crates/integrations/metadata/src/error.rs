@@ -6,6 +6,8 @@ pub enum MetadataProviderError {
 	MiddlewareReqwestError(#[from] reqwest_middleware::Error),
 	#[error("Failed to parse response: {0}")]
 	ParseError(#[from] serde_json::Error),
+	#[error("Failed to read file: {0}")]
+	IoError(#[from] std::io::Error),
 	#[error("This operation is not supported by the provider")]
 	OperationNotSupported,
 	#[error("A token is required for this provider but was not provided")]
@@ -19,7 +19,9 @@ pub use types::{
 	MatchCandidate, MediaType, MetadataField, PublicationStatus, SearchQuery,
 };
 
-use providers::HardcoverClient;
+use providers::{
+	AnilistClient, ComicVineClient, HardcoverClient, LocalSidecarProvider, MetronClient,
+};
 
 pub fn create_provider(
 	provider_type: &str,
@@ -27,6 +29,10 @@ pub fn create_provider(
 ) -> MetadataResult<Box<dyn MetadataProvider + Send + Sync>> {
 	match provider_type {
 		"HARDCOVER" => Ok(Box::new(HardcoverClient::new(api_token, None))),
+		"ANILIST" => Ok(Box::new(AnilistClient::new(Some(api_token), None))),
+		"METRON" => Ok(Box::new(MetronClient::new(api_token, None))),
+		"COMIC_VINE" => Ok(Box::new(ComicVineClient::new(api_token, None))),
+		"LOCAL" => Ok(Box::new(LocalSidecarProvider::new())),
 		_ => Err(MetadataProviderError::UnsupportedProvider(
 			provider_type.to_string(),
 		)),
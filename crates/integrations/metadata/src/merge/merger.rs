@@ -53,7 +53,8 @@ impl FieldMerger {
 		Self::new(config.strategy, locked, config.exclude_fields.clone())
 	}
 
-	fn can_write(&self, field: MetadataField) -> bool {
+	/// Whether the field may be written, i.e. it is neither locked nor excluded
+	pub fn can_write(&self, field: MetadataField) -> bool {
 		!self.locked_fields.contains(&field) && !self.exclude_fields.contains(&field)
 	}
 
@@ -1,6 +1,65 @@
+use std::collections::HashSet;
+
 use reqwest_middleware::ClientWithMiddleware;
+use serde::{de::DeserializeOwned, Deserialize};
+
+use super::html::strip_html;
+use crate::{
+	client::{build_client_with_retry, RetryClientConfig},
+	error::MetadataProviderError,
+	types::{
+		ExternalMediaMetadata, ExternalSeriesMetadata, MatchCandidate, MediaType,
+		PublicationStatus, SearchQuery,
+	},
+	ExternalMetadata, MetadataProvider, RateLimiter,
+};
+
+/// AniList allows up to 90 requests per minute, and may lower that limit when under load
+/// See: https://docs.anilist.co/guide/rate-limiting
+const ANILIST_DEFAULT_RATE_LIMIT: u32 = 1;
 
-use crate::RateLimiter;
+/// The fields requested for every manga, used by both searches and direct lookups
+const MEDIA_FIELDS: &str = r#"
+	id
+	siteUrl
+	title {
+		romaji
+		english
+		native
+	}
+	synonyms
+	description(asHtml: false)
+	status
+	isAdult
+	startDate {
+		year
+		month
+		day
+	}
+	endDate {
+		year
+	}
+	volumes
+	genres
+	tags {
+		name
+		isMediaSpoiler
+	}
+	coverImage {
+		extraLarge
+		large
+	}
+	staff(perPage: 25, sort: [RELEVANCE, ID]) {
+		edges {
+			role
+			node {
+				name {
+					full
+				}
+			}
+		}
+	}
+"#;
 
 pub struct AnilistClient {
 	client: ClientWithMiddleware,
@@ -10,101 +69,630 @@ pub struct AnilistClient {
 
 impl AnilistClient {
 	const API_URL: &'static str = "https://graphql.anilist.co";
+
+	/// Create a new AniList client. AniList does not require authentication for public
+	/// data, so the token is optional and only sent when present.
+	pub fn new(api_token: Option<String>, rate_limit: Option<u32>) -> Self {
+		Self {
+			client: build_client_with_retry(
+				reqwest::Client::new(),
+				RetryClientConfig::default(),
+			),
+			api_token: api_token.filter(|token| !token.trim().is_empty()),
+			rate_limiter: RateLimiter::new(
+				rate_limit.unwrap_or(ANILIST_DEFAULT_RATE_LIMIT),
+			),
+		}
+	}
+
+	async fn execute_graphql<T: DeserializeOwned>(
+		&self,
+		query: &str,
+		variables: serde_json::Value,
+	) -> Result<T, MetadataProviderError> {
+		self.rate_limiter.until_ready().await;
+
+		let body = serde_json::json!({ "query": query, "variables": variables });
+
+		let mut request = self.client.post(Self::API_URL).json(&body);
+		if let Some(token) = self.api_token.as_ref() {
+			request = request.bearer_auth(token);
+		}
+
+		let response = request
+			.send()
+			.await?
+			.error_for_status()?
+			.json::<GraphQLResponse<T>>()
+			.await?;
+
+		response.into_data()
+	}
+
+	#[tracing::instrument(skip(self))]
+	async fn search(
+		&self,
+		search: &str,
+		limit: u32,
+	) -> Result<Vec<AnilistMedia>, MetadataProviderError> {
+		let query = format!(
+			r#"query Search($search: String!, $perPage: Int) {{
+				Page(page: 1, perPage: $perPage) {{
+					media(search: $search, type: MANGA, sort: [SEARCH_MATCH]) {{ {MEDIA_FIELDS} }}
+				}}
+			}}"#
+		);
+		tracing::trace!(?search, "Searching AniList...");
+
+		let data: PageData = self
+			.execute_graphql(
+				&query,
+				serde_json::json!({ "search": search, "perPage": limit }),
+			)
+			.await?;
+		Ok(data.page.media)
+	}
+
+	async fn fetch_manga(&self, id: i64) -> Result<AnilistMedia, MetadataProviderError> {
+		let query = format!(
+			r#"query GetManga($id: Int!) {{
+				Media(id: $id, type: MANGA) {{ {MEDIA_FIELDS} }}
+			}}"#
+		);
+
+		let data: MediaData = self
+			.execute_graphql(&query, serde_json::json!({ "id": id }))
+			.await?;
+		data.media
+			.ok_or_else(|| MetadataProviderError::NotFound(format!("Manga {}", id)))
+	}
+
+	fn parse_id(external_id: &str) -> Result<i64, MetadataProviderError> {
+		external_id.parse().map_err(|_| {
+			MetadataProviderError::Other(format!("Invalid AniList ID: {}", external_id))
+		})
+	}
+
+	fn to_candidates<F>(&self, media: Vec<AnilistMedia>, map: F) -> Vec<MatchCandidate>
+	where
+		F: Fn(AnilistMedia) -> ExternalMetadata,
+	{
+		media
+			.into_iter()
+			.map(|m| MatchCandidate {
+				external_id: m.id.to_string(),
+				metadata: map(m),
+				provider: self.id().to_string(),
+				confidence: 0.0,
+				confidence_factors: Vec::new(),
+			})
+			.collect()
+	}
 }
 
-/*
-Basic Example:
+#[async_trait::async_trait]
+impl MetadataProvider for AnilistClient {
+	fn id(&self) -> &'static str {
+		"anilist"
+	}
 
-query ($search: String!) {
-  Page {
-	media(search: $search, type: ANIME) {
-	  id
-	  title {
-		romaji
-		english
-		native
-	  }
-	}
-  }
-}
-
-Advanced Example:
-
-query (
-  $page: Int = 1
-  $id: Int
-  $type: MediaType
-  $isAdult: Boolean = false
-  $search: String
-  $format: [MediaFormat]
-  $status: MediaStatus
-  $countryOfOrigin: CountryCode
-  $source: MediaSource
-  $season: MediaSeason
-  $seasonYear: Int
-  $year: String
-  $onList: Boolean
-  $yearLesser: FuzzyDateInt
-  $yearGreater: FuzzyDateInt
-  $episodeLesser: Int
-  $episodeGreater: Int
-  $durationLesser: Int
-  $durationGreater: Int
-  $chapterLesser: Int
-  $chapterGreater: Int
-  $volumeLesser: Int
-  $volumeGreater: Int
-  $licensedBy: [Int]
-  $isLicensed: Boolean
-  $genres: [String]
-  $excludedGenres: [String]
-  $tags: [String]
-  $excludedTags: [String]
-  $minimumTagRank: Int
-  $sort: [MediaSort] = [POPULARITY_DESC, SCORE_DESC]
-) {
-  Page(page: $page, perPage: 20) {
-	pageInfo {
-	  hasNextPage
-	}
-	media(
-	  id: $id
-	  type: $type
-	  season: $season
-	  format_in: $format
-	  status: $status
-	  countryOfOrigin: $countryOfOrigin
-	  source: $source
-	  search: $search
-	  onList: $onList
-	  seasonYear: $seasonYear
-	  startDate_like: $year
-	  startDate_lesser: $yearLesser
-	  startDate_greater: $yearGreater
-	  episodes_lesser: $episodeLesser
-	  episodes_greater: $episodeGreater
-	  duration_lesser: $durationLesser
-	  duration_greater: $durationGreater
-	  chapters_lesser: $chapterLesser
-	  chapters_greater: $chapterGreater
-	  volumes_lesser: $volumeLesser
-	  volumes_greater: $volumeGreater
-	  licensedById_in: $licensedBy
-	  isLicensed: $isLicensed
-	  genre_in: $genres
-	  genre_not_in: $excludedGenres
-	  tag_in: $tags
-	  tag_not_in: $excludedTags
-	  minimumTagRank: $minimumTagRank
-	  sort: $sort
-	  isAdult: $isAdult
-	) {
-	  id
-	  title {
-		romaji
-	  }
+	fn name(&self) -> &'static str {
+		"AniList"
+	}
+
+	fn supported_media_types(&self) -> Vec<MediaType> {
+		vec![
+			MediaType::Manga,
+			MediaType::Manhwa,
+			MediaType::LightNovel,
+			MediaType::Webtoon,
+		]
+	}
+
+	/// Search for manga on AniList. The search results already contain the full metadata,
+	/// so unlike other providers there is no follow-up fetch for each result.
+	async fn search_series(
+		&self,
+		query: &SearchQuery,
+	) -> Result<Vec<MatchCandidate>, MetadataProviderError> {
+		let media = self.search(&query.title, query.limit.unwrap_or(10)).await?;
+		let candidates = self.to_candidates(media, |m| {
+			ExternalMetadata::Series(m.into_series_metadata())
+		});
+		Ok(self.score_search(query, candidates))
+	}
+
+	/// AniList does not track individual volumes, so books are matched against the manga
+	/// they belong to
+	#[tracing::instrument(skip(self))]
+	async fn search_media(
+		&self,
+		query: &SearchQuery,
+	) -> Result<Vec<MatchCandidate>, MetadataProviderError> {
+		let media = self.search(&query.title, query.limit.unwrap_or(10)).await?;
+		let candidates = self
+			.to_candidates(media, |m| ExternalMetadata::Media(m.into_media_metadata()));
+		Ok(self.score_search(query, candidates))
+	}
+
+	async fn fetch_series_metadata(
+		&self,
+		external_id: &str,
+	) -> Result<ExternalSeriesMetadata, MetadataProviderError> {
+		let id = Self::parse_id(external_id)?;
+		Ok(self.fetch_manga(id).await?.into_series_metadata())
+	}
+
+	async fn fetch_media_metadata(
+		&self,
+		external_id: &str,
+	) -> Result<ExternalMediaMetadata, MetadataProviderError> {
+		let id = Self::parse_id(external_id)?;
+		Ok(self.fetch_manga(id).await?.into_media_metadata())
+	}
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GraphQLResponse<T> {
+	pub data: Option<T>,
+	pub errors: Option<Vec<GraphQLError>>,
+}
+
+impl<T> GraphQLResponse<T> {
+	fn into_data(self) -> Result<T, MetadataProviderError> {
+		if let Some(errors) = self.errors.filter(|errors| !errors.is_empty()) {
+			// AniList reports unknown IDs as a 404 error alongside a null result
+			if errors.iter().any(|e| e.status == Some(404)) {
+				return Err(MetadataProviderError::NotFound(errors[0].message.clone()));
+			}
+
+			let messages: Vec<_> = errors.iter().map(|e| e.message.as_str()).collect();
+			return Err(MetadataProviderError::Other(format!(
+				"GraphQL errors: {}",
+				messages.join("; ")
+			)));
+		}
+
+		self.data.ok_or(MetadataProviderError::EmptyResponse)
+	}
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GraphQLError {
+	pub message: String,
+	pub status: Option<u16>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PageData {
+	#[serde(rename = "Page")]
+	pub page: Page,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Page {
+	#[serde(default)]
+	pub media: Vec<AnilistMedia>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MediaData {
+	#[serde(rename = "Media")]
+	pub media: Option<AnilistMedia>,
+}
+
+/// See https://docs.anilist.co/reference/object/media
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnilistMedia {
+	pub id: i64,
+	pub site_url: Option<String>,
+	pub title: MediaTitle,
+	#[serde(default)]
+	pub synonyms: Vec<String>,
+	pub description: Option<String>,
+	pub status: Option<MediaStatus>,
+	pub is_adult: Option<bool>,
+	pub start_date: Option<FuzzyDate>,
+	pub end_date: Option<FuzzyDate>,
+	pub volumes: Option<i32>,
+	#[serde(default)]
+	pub genres: Vec<String>,
+	#[serde(default)]
+	pub tags: Vec<MediaTag>,
+	pub cover_image: Option<CoverImage>,
+	pub staff: Option<StaffConnection>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MediaTitle {
+	pub romaji: Option<String>,
+	pub english: Option<String>,
+	pub native: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MediaStatus {
+	Finished,
+	Releasing,
+	NotYetReleased,
+	Cancelled,
+	Hiatus,
+}
+
+impl From<MediaStatus> for PublicationStatus {
+	fn from(status: MediaStatus) -> Self {
+		match status {
+			MediaStatus::Finished => PublicationStatus::Completed,
+			MediaStatus::Releasing => PublicationStatus::Ongoing,
+			MediaStatus::NotYetReleased => PublicationStatus::Upcoming,
+			MediaStatus::Cancelled => PublicationStatus::Cancelled,
+			MediaStatus::Hiatus => PublicationStatus::Hiatus,
+		}
 	}
-  }
 }
 
-*/
+#[derive(Debug, Deserialize)]
+pub struct FuzzyDate {
+	pub year: Option<i32>,
+	pub month: Option<i32>,
+	pub day: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaTag {
+	pub name: String,
+	#[serde(default)]
+	pub is_media_spoiler: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverImage {
+	pub extra_large: Option<String>,
+	pub large: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StaffConnection {
+	#[serde(default)]
+	pub edges: Vec<StaffEdge>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StaffEdge {
+	pub role: Option<String>,
+	pub node: Option<StaffNode>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StaffNode {
+	pub name: StaffName,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StaffName {
+	pub full: Option<String>,
+}
+
+/// The kinds of staff credits Stump tracks, derived from AniList's free-form staff roles
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StaffCredit {
+	Writer,
+	Artist,
+	Letterer,
+}
+
+impl StaffCredit {
+	/// Map an AniList role (e.g. "Story & Art", "Original Story", "Art (ch. 1-10)") to
+	/// the credits it implies. Roles for localization staff (e.g. translators) and
+	/// touch-ups are ignored, with the exception of lettering.
+	fn from_role(role: &str) -> Vec<StaffCredit> {
+		// strip qualifiers like "(eng)" or "(ch. 1-10)"
+		let role = role.split(" (").next().unwrap_or(role).to_lowercase();
+
+		let mut credits = Vec::new();
+		if role.contains("lettering") {
+			credits.push(StaffCredit::Letterer);
+		}
+		if role.contains("touch-up") || role.contains("translator") {
+			return credits;
+		}
+		if role.contains("story") || role.contains("original creator") {
+			credits.push(StaffCredit::Writer);
+		}
+		if role.contains("art") || role.contains("illustration") {
+			credits.push(StaffCredit::Artist);
+		}
+		credits
+	}
+}
+
+impl AnilistMedia {
+	/// The preferred display title: English if available, falling back to romaji and then
+	/// the native title
+	fn display_title(&self) -> String {
+		self.title
+			.english
+			.clone()
+			.or_else(|| self.title.romaji.clone())
+			.or_else(|| self.title.native.clone())
+			.unwrap_or_default()
+	}
+
+	/// Every other title the manga is known by, including synonyms
+	fn alternative_titles(&self) -> Vec<String> {
+		let display_title = self.display_title();
+		let mut seen = HashSet::new();
+		[
+			self.title.english.as_ref(),
+			self.title.romaji.as_ref(),
+			self.title.native.as_ref(),
+		]
+		.into_iter()
+		.flatten()
+		.chain(self.synonyms.iter())
+		.filter(|title| **title != display_title)
+		.filter(|title| seen.insert(*title))
+		.cloned()
+		.collect()
+	}
+
+	fn staff_with_credit(&self, credit: StaffCredit) -> Option<Vec<String>> {
+		let edges = &self.staff.as_ref()?.edges;
+		let mut names: Vec<String> = Vec::new();
+		for edge in edges {
+			let is_credited = edge
+				.role
+				.as_deref()
+				.is_some_and(|role| StaffCredit::from_role(role).contains(&credit));
+			let name = edge.node.as_ref().and_then(|n| n.name.full.clone());
+			if let (true, Some(name)) = (is_credited, name) {
+				if !names.contains(&name) {
+					names.push(name);
+				}
+			}
+		}
+		Some(names)
+	}
+
+	fn summary(&self) -> Option<String> {
+		self.description
+			.as_deref()
+			.map(strip_html)
+			.filter(|description| !description.is_empty())
+	}
+
+	fn tag_names(&self) -> Vec<String> {
+		self.tags
+			.iter()
+			.filter(|tag| !tag.is_media_spoiler)
+			.map(|tag| tag.name.clone())
+			.collect()
+	}
+
+	fn cover_url(&self) -> Option<String> {
+		self.cover_image
+			.as_ref()
+			.and_then(|c| c.extra_large.clone().or_else(|| c.large.clone()))
+	}
+
+	pub fn into_series_metadata(self) -> ExternalSeriesMetadata {
+		ExternalSeriesMetadata {
+			provider: "anilist".to_string(),
+			external_id: self.id.to_string(),
+			title: self.display_title(),
+			alternative_titles: self.alternative_titles(),
+			summary: self.summary(),
+			status: self.status.map(PublicationStatus::from),
+			year: self.start_date.as_ref().and_then(|d| d.year),
+			end_year: self.end_date.as_ref().and_then(|d| d.year),
+			genres: Some(self.genres.clone()),
+			tags: Some(self.tag_names()),
+			age_rating: self
+				.is_adult
+				.filter(|adult| *adult)
+				.map(|_| "18".to_string()),
+			authors: self.staff_with_credit(StaffCredit::Writer),
+			artists: self.staff_with_credit(StaffCredit::Artist),
+			publisher: None,
+			cover_url: self.cover_url(),
+			volume_count: self.volumes,
+		}
+	}
+
+	pub fn into_media_metadata(self) -> ExternalMediaMetadata {
+		let start_date = self.start_date.as_ref();
+
+		ExternalMediaMetadata {
+			provider: "anilist".to_string(),
+			external_id: self.id.to_string(),
+			title: Some(self.display_title()),
+			summary: self.summary(),
+			series_name: Some(self.display_title()),
+			series_external_id: Some(self.id.to_string()),
+			year: start_date.and_then(|d| d.year),
+			month: start_date.and_then(|d| d.month),
+			day: start_date.and_then(|d| d.day),
+			genres: Some(self.genres.clone()),
+			tags: Some(self.tag_names()),
+			writers: self.staff_with_credit(StaffCredit::Writer),
+			artists: self.staff_with_credit(StaffCredit::Artist),
+			letterers: self.staff_with_credit(StaffCredit::Letterer),
+			cover_url: self.cover_url(),
+			provider_url: self.site_url.clone(),
+			..Default::default()
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn recorded<T: DeserializeOwned>(json: &str) -> T {
+		serde_json::from_str::<GraphQLResponse<T>>(json)
+			.expect("failed to parse recorded response")
+			.into_data()
+			.expect("recorded response contained errors")
+	}
+
+	fn recorded_search() -> Vec<AnilistMedia> {
+		recorded::<PageData>(include_str!("../../fixtures/anilist/search.json"))
+			.page
+			.media
+	}
+
+	fn recorded_manga() -> AnilistMedia {
+		recorded::<MediaData>(include_str!("../../fixtures/anilist/manga.json"))
+			.media
+			.expect("recorded response should contain a manga")
+	}
+
+	#[test]
+	fn test_parse_recorded_search() {
+		let media = recorded_search();
+		assert_eq!(2, media.len());
+		assert_eq!(30013, media[0].id);
+		assert_eq!(Some(MediaStatus::Releasing), media[0].status);
+		assert_eq!(Some(MediaStatus::Finished), media[1].status);
+	}
+
+	#[test]
+	fn test_series_metadata_from_recorded_manga() {
+		let metadata = recorded_manga().into_series_metadata();
+
+		assert_eq!("anilist", metadata.provider);
+		assert_eq!("30013", metadata.external_id);
+		assert_eq!("One Piece", metadata.title);
+		assert_eq!(
+			vec!["ONE PIECE".to_string(), "ワンピース".to_string()],
+			metadata.alternative_titles
+		);
+		assert!(matches!(metadata.status, Some(PublicationStatus::Ongoing)));
+		assert_eq!(Some(1997), metadata.year);
+		assert_eq!(None, metadata.end_year);
+		assert_eq!(Some(110), metadata.volume_count);
+		assert_eq!(
+			Some(vec!["Action".to_string(), "Adventure".to_string()]),
+			metadata.genres
+		);
+		// spoiler tags are excluded
+		assert_eq!(Some(vec!["Pirates".to_string()]), metadata.tags);
+		assert_eq!(Some(vec!["Eiichiro Oda".to_string()]), metadata.authors);
+		assert_eq!(Some(vec!["Eiichiro Oda".to_string()]), metadata.artists);
+		assert_eq!(None, metadata.age_rating);
+		assert_eq!(
+			Some(
+				"https://s4.anilist.co/file/anilistcdn/media/manga/cover/large/bx30013.jpg"
+					.to_string()
+			),
+			metadata.cover_url
+		);
+		assert_eq!(
+			Some("Gol D. Roger was known as the Pirate King.\nThe capture and execution of Roger brought a new era.".to_string()),
+			metadata.summary
+		);
+	}
+
+	#[test]
+	fn test_alternative_titles_are_unique() {
+		let mut manga = recorded_manga();
+		manga.synonyms = vec![
+			"ONE PIECE".to_string(),
+			"Wan Pīsu".to_string(),
+			"ONE PIECE".to_string(),
+		];
+
+		assert_eq!(
+			vec![
+				"ONE PIECE".to_string(),
+				"ワンピース".to_string(),
+				"Wan Pīsu".to_string()
+			],
+			manga.alternative_titles()
+		);
+	}
+
+	#[test]
+	fn test_media_metadata_from_recorded_manga() {
+		let metadata = recorded_manga().into_media_metadata();
+
+		assert_eq!(Some("30013".to_string()), metadata.series_external_id);
+		assert_eq!(Some("One Piece".to_string()), metadata.series_name);
+		assert_eq!(Some(1997), metadata.year);
+		assert_eq!(Some(7), metadata.month);
+		assert_eq!(Some(22), metadata.day);
+		assert_eq!(Some(vec!["Stephen Paul".to_string()]), metadata.letterers);
+		assert_eq!(
+			Some("https://anilist.co/manga/30013".to_string()),
+			metadata.provider_url
+		);
+	}
+
+	#[test]
+	fn test_not_found_response() {
+		let response: GraphQLResponse<MediaData> = serde_json::from_str(
+			r#"{"errors":[{"message":"Not Found.","status":404}],"data":{"Media":null}}"#,
+		)
+		.unwrap();
+		assert!(matches!(
+			response.into_data(),
+			Err(MetadataProviderError::NotFound(_))
+		));
+	}
+
+	#[test]
+	fn test_staff_credit_from_role() {
+		assert_eq!(
+			vec![StaffCredit::Writer, StaffCredit::Artist],
+			StaffCredit::from_role("Story & Art")
+		);
+		assert_eq!(
+			vec![StaffCredit::Writer],
+			StaffCredit::from_role("Original Story")
+		);
+		assert_eq!(
+			vec![StaffCredit::Artist],
+			StaffCredit::from_role("Art (ch. 1-10)")
+		);
+		assert_eq!(
+			vec![StaffCredit::Letterer],
+			StaffCredit::from_role("Touch-up Art & Lettering (English)")
+		);
+		assert!(StaffCredit::from_role("Translator (English)").is_empty());
+	}
+
+	fn get_test_client() -> AnilistClient {
+		dotenvy::dotenv().ok();
+		AnilistClient::new(std::env::var("ANILIST_API_TOKEN").ok(), None)
+	}
+
+	#[ignore = "Makes requests to the AniList API"]
+	#[tokio::test]
+	async fn test_search_series() {
+		let client = get_test_client();
+		let query = SearchQuery {
+			title: "One Piece".to_string(),
+			limit: Some(5),
+			..Default::default()
+		};
+
+		let results = client.search_series(&query).await;
+		println!("search_series results: {:#?}", results);
+		assert!(results.is_ok());
+		assert!(!results.unwrap().is_empty());
+	}
+
+	#[ignore = "Makes requests to the AniList API"]
+	#[tokio::test]
+	async fn test_fetch_series_metadata() {
+		let client = get_test_client();
+
+		let metadata = client.fetch_series_metadata("30013").await;
+		println!("fetch_series_metadata result: {:#?}", metadata);
+		assert!(metadata.is_ok());
+	}
+}
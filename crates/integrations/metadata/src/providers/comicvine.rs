@@ -0,0 +1,589 @@
+use chrono::Datelike;
+use reqwest_middleware::ClientWithMiddleware;
+use serde::{de::DeserializeOwned, Deserialize};
+
+use super::html::strip_html;
+use crate::{
+	client::{build_client_with_retry, RetryClientConfig},
+	error::MetadataProviderError,
+	types::{
+		ExternalMediaMetadata, ExternalSeriesMetadata, MatchCandidate, MediaType,
+		SearchQuery,
+	},
+	ExternalMetadata, MetadataProvider, RateLimiter,
+};
+
+/// ComicVine allows 200 requests per resource per hour, and asks that clients don't send
+/// more than one request per second
+/// See: https://comicvine.gamespot.com/api/documentation
+const COMICVINE_DEFAULT_RATE_LIMIT: u32 = 1;
+
+/// ComicVine rejects requests without a unique user agent
+const USER_AGENT: &str = concat!("Stump/", env!("CARGO_PKG_VERSION"));
+
+/// Status codes ComicVine reports in the body of a response
+const STATUS_OK: i32 = 1;
+const STATUS_INVALID_API_KEY: i32 = 100;
+const STATUS_OBJECT_NOT_FOUND: i32 = 101;
+
+pub struct ComicVineClient {
+	client: ClientWithMiddleware,
+	api_token: Option<String>,
+	rate_limiter: RateLimiter,
+}
+
+/// Resource types supported by ComicVine's search API
+#[derive(Debug, Clone, Copy)]
+pub enum ComicVineResource {
+	Issue,
+	Volume,
+}
+
+impl ComicVineResource {
+	fn as_str(&self) -> &'static str {
+		match self {
+			Self::Issue => "issue",
+			Self::Volume => "volume",
+		}
+	}
+}
+
+impl ComicVineClient {
+	const API_URL: &'static str = "https://comicvine.gamespot.com/api";
+
+	pub fn new(api_token: String, rate_limit: Option<u32>) -> Self {
+		Self {
+			client: build_client_with_retry(
+				reqwest::Client::new(),
+				RetryClientConfig::default(),
+			),
+			api_token: Some(api_token).filter(|token| !token.trim().is_empty()),
+			rate_limiter: RateLimiter::new(
+				rate_limit.unwrap_or(COMICVINE_DEFAULT_RATE_LIMIT),
+			),
+		}
+	}
+
+	pub fn token(&self) -> Result<String, MetadataProviderError> {
+		self.api_token
+			.clone()
+			.ok_or(MetadataProviderError::MissingToken)
+	}
+
+	async fn get<T: DeserializeOwned>(
+		&self,
+		path: &str,
+		params: &[(&str, String)],
+	) -> Result<T, MetadataProviderError> {
+		let token = self.token()?;
+		self.rate_limiter.until_ready().await;
+
+		let response = self
+			.client
+			.get(format!("{}/{}", Self::API_URL, path))
+			.header(reqwest::header::USER_AGENT, USER_AGENT)
+			.query(&[("api_key", token.as_str()), ("format", "json")])
+			.query(params)
+			.send()
+			.await?;
+
+		if response.status() == reqwest::StatusCode::NOT_FOUND {
+			return Err(MetadataProviderError::NotFound(path.to_string()));
+		}
+
+		response
+			.error_for_status()?
+			.json::<ComicVineResponse>()
+			.await?
+			.into_results(path)
+	}
+
+	#[tracing::instrument(skip(self))]
+	async fn search(
+		&self,
+		query: &str,
+		resource: ComicVineResource,
+		limit: u32,
+	) -> Result<Vec<SearchResult>, MetadataProviderError> {
+		self.get(
+			"search/",
+			&[
+				("query", query.to_string()),
+				("resources", resource.as_str().to_string()),
+				("limit", limit.to_string()),
+			],
+		)
+		.await
+	}
+
+	async fn fetch_volume(&self, id: &str) -> Result<Volume, MetadataProviderError> {
+		self.get(&format!("volume/4050-{id}/"), &[]).await
+	}
+
+	async fn fetch_issue(&self, id: &str) -> Result<Issue, MetadataProviderError> {
+		self.get(&format!("issue/4000-{id}/"), &[]).await
+	}
+
+	fn validate_id(external_id: &str) -> Result<&str, MetadataProviderError> {
+		if external_id.is_empty() || !external_id.chars().all(|c| c.is_ascii_digit()) {
+			return Err(MetadataProviderError::Other(format!(
+				"Invalid ComicVine ID: {}",
+				external_id
+			)));
+		}
+		Ok(external_id)
+	}
+}
+
+#[async_trait::async_trait]
+impl MetadataProvider for ComicVineClient {
+	fn id(&self) -> &'static str {
+		"comic_vine"
+	}
+
+	fn name(&self) -> &'static str {
+		"ComicVine"
+	}
+
+	fn supported_media_types(&self) -> Vec<MediaType> {
+		vec![MediaType::Comic]
+	}
+
+	/// Search for volumes on ComicVine. Search results already contain everything that is
+	/// mapped for a series, so no further requests are needed
+	async fn search_series(
+		&self,
+		query: &SearchQuery,
+	) -> Result<Vec<MatchCandidate>, MetadataProviderError> {
+		let limit = query.limit.unwrap_or(10);
+		let results = self
+			.search(&query.title, ComicVineResource::Volume, limit)
+			.await?;
+
+		let candidates = results
+			.into_iter()
+			.filter_map(|result| match result {
+				SearchResult::Volume(volume) => Some(volume),
+				_ => None,
+			})
+			.map(|volume| MatchCandidate {
+				external_id: volume.id.to_string(),
+				metadata: ExternalMetadata::Series(volume.into_series_metadata()),
+				provider: self.id().to_string(),
+				confidence: 0.0,
+				confidence_factors: Vec::new(),
+			})
+			.collect();
+
+		Ok(self.score_search(query, candidates))
+	}
+
+	/// Search for issues on ComicVine and fetch full metadata for each result, since
+	/// search results don't include credits
+	#[tracing::instrument(skip(self))]
+	async fn search_media(
+		&self,
+		query: &SearchQuery,
+	) -> Result<Vec<MatchCandidate>, MetadataProviderError> {
+		let limit = query.limit.unwrap_or(10);
+		let results = self
+			.search(&query.title, ComicVineResource::Issue, limit)
+			.await?;
+
+		let mut candidates = Vec::with_capacity(results.len());
+		for result in results {
+			let SearchResult::Issue { id } = result else {
+				continue;
+			};
+
+			let external_id = id.to_string();
+			match self.fetch_media_metadata(&external_id).await {
+				Ok(metadata) => candidates.push(MatchCandidate {
+					external_id,
+					metadata: ExternalMetadata::Media(metadata),
+					provider: self.id().to_string(),
+					confidence: 0.0,
+					confidence_factors: Vec::new(),
+				}),
+				Err(e) => {
+					tracing::error!(
+						external_id,
+						error = ?e,
+						"Failed to fetch issue metadata for search result"
+					);
+				},
+			}
+		}
+
+		Ok(self.score_search(query, candidates))
+	}
+
+	async fn fetch_series_metadata(
+		&self,
+		external_id: &str,
+	) -> Result<ExternalSeriesMetadata, MetadataProviderError> {
+		let id = Self::validate_id(external_id)?;
+		Ok(self.fetch_volume(id).await?.into_series_metadata())
+	}
+
+	async fn fetch_media_metadata(
+		&self,
+		external_id: &str,
+	) -> Result<ExternalMediaMetadata, MetadataProviderError> {
+		let id = Self::validate_id(external_id)?;
+		Ok(self.fetch_issue(id).await?.into_media_metadata())
+	}
+}
+
+/// Every ComicVine response is wrapped in an envelope which reports errors with a status
+/// code, rather than through the HTTP status. Failed responses contain an empty list of
+/// results, so they are only deserialized once the status is known
+#[derive(Debug, Deserialize)]
+pub struct ComicVineResponse {
+	pub error: String,
+	pub status_code: i32,
+	pub results: Option<serde_json::Value>,
+}
+
+impl ComicVineResponse {
+	fn into_results<T: DeserializeOwned>(
+		self,
+		path: &str,
+	) -> Result<T, MetadataProviderError> {
+		match self.status_code {
+			STATUS_OK => match self.results {
+				Some(results) if !results.is_null() => {
+					Ok(serde_json::from_value(results)?)
+				},
+				_ => Err(MetadataProviderError::EmptyResponse),
+			},
+			STATUS_OBJECT_NOT_FOUND => {
+				Err(MetadataProviderError::NotFound(path.to_string()))
+			},
+			STATUS_INVALID_API_KEY => Err(MetadataProviderError::Other(
+				"Invalid ComicVine API key".to_string(),
+			)),
+			_ => Err(MetadataProviderError::Other(format!(
+				"ComicVine error: {}",
+				self.error
+			))),
+		}
+	}
+}
+
+/// A single search result. Only the resource types which are searched for are mapped,
+/// ComicVine returns a `resource_type` field on each result to tell them apart
+#[derive(Debug, Deserialize)]
+#[serde(tag = "resource_type", rename_all = "snake_case")]
+pub enum SearchResult {
+	Issue {
+		id: i64,
+	},
+	Volume(Volume),
+	#[serde(other)]
+	Other,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Named {
+	pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Image {
+	pub original_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PersonCredit {
+	pub name: String,
+	/// A comma separated list of roles, e.g. "penciler, inker"
+	pub role: String,
+}
+
+impl PersonCredit {
+	fn has_role(&self, role: &str) -> bool {
+		self.role
+			.split(',')
+			.any(|r| r.trim().eq_ignore_ascii_case(role))
+	}
+}
+
+/// A series on ComicVine
+/// See https://comicvine.gamespot.com/api/documentation#toc-0-42
+#[derive(Debug, Deserialize)]
+pub struct Volume {
+	pub id: i64,
+	pub name: String,
+	/// Newline separated alternative names
+	pub aliases: Option<String>,
+	pub deck: Option<String>,
+	pub description: Option<String>,
+	pub start_year: Option<String>,
+	pub count_of_issues: Option<i32>,
+	pub publisher: Option<Named>,
+	pub image: Option<Image>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IssueVolume {
+	pub id: i64,
+	pub name: String,
+}
+
+/// See https://comicvine.gamespot.com/api/documentation#toc-0-11
+#[derive(Debug, Deserialize)]
+pub struct Issue {
+	pub id: i64,
+	pub name: Option<String>,
+	pub issue_number: Option<String>,
+	pub cover_date: Option<String>,
+	pub deck: Option<String>,
+	pub description: Option<String>,
+	pub image: Option<Image>,
+	pub volume: IssueVolume,
+	#[serde(default)]
+	pub person_credits: Vec<PersonCredit>,
+	#[serde(default)]
+	pub character_credits: Vec<Named>,
+	#[serde(default)]
+	pub team_credits: Vec<Named>,
+	#[serde(default)]
+	pub story_arc_credits: Vec<Named>,
+	pub site_detail_url: Option<String>,
+}
+
+fn names(values: &[Named]) -> Vec<String> {
+	values.iter().map(|v| v.name.clone()).collect()
+}
+
+/// ComicVine descriptions are HTML, while decks are a short plain text summary
+fn summary(description: Option<&str>, deck: Option<&str>) -> Option<String> {
+	description
+		.map(strip_html)
+		.filter(|d| !d.is_empty())
+		.or_else(|| deck.map(|d| d.trim().to_string()).filter(|d| !d.is_empty()))
+}
+
+impl Volume {
+	pub fn into_series_metadata(self) -> ExternalSeriesMetadata {
+		let alternative_titles = self
+			.aliases
+			.as_deref()
+			.map(|aliases| {
+				aliases
+					.lines()
+					.map(str::trim)
+					.filter(|alias| !alias.is_empty())
+					.map(String::from)
+					.collect()
+			})
+			.unwrap_or_default();
+
+		ExternalSeriesMetadata {
+			provider: "comic_vine".to_string(),
+			external_id: self.id.to_string(),
+			summary: summary(self.description.as_deref(), self.deck.as_deref()),
+			title: self.name,
+			alternative_titles,
+			year: self
+				.start_year
+				.as_deref()
+				.and_then(|y| y.trim().parse().ok()),
+			publisher: self.publisher.map(|p| p.name),
+			cover_url: self.image.and_then(|i| i.original_url),
+			volume_count: self.count_of_issues,
+			..Default::default()
+		}
+	}
+}
+
+impl Issue {
+	fn creators_with_role(&self, roles: &[&str]) -> Option<Vec<String>> {
+		Some(
+			self.person_credits
+				.iter()
+				.filter(|credit| roles.iter().any(|role| credit.has_role(role)))
+				.map(|credit| credit.name.clone())
+				.collect(),
+		)
+	}
+
+	pub fn into_media_metadata(self) -> ExternalMediaMetadata {
+		let (year, month, day) = match self
+			.cover_date
+			.as_deref()
+			.and_then(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+		{
+			Some(date) => (
+				Some(date.year()),
+				Some(date.month() as i32),
+				Some(date.day() as i32),
+			),
+			None => (None, None, None),
+		};
+
+		let title = self.name.clone().filter(|n| !n.is_empty()).or_else(|| {
+			self.issue_number
+				.as_ref()
+				.map(|number| format!("{} #{}", self.volume.name, number))
+		});
+
+		ExternalMediaMetadata {
+			provider: "comic_vine".to_string(),
+			external_id: self.id.to_string(),
+			title,
+			summary: summary(self.description.as_deref(), self.deck.as_deref()),
+			series_name: Some(self.volume.name.clone()),
+			series_external_id: Some(self.volume.id.to_string()),
+			number: self.issue_number.as_ref().and_then(|n| n.parse().ok()),
+			day,
+			month,
+			year,
+			writers: self.creators_with_role(&["writer"]),
+			pencillers: self.creators_with_role(&["penciler", "penciller", "artist"]),
+			inkers: self.creators_with_role(&["inker", "artist"]),
+			colorists: self.creators_with_role(&["colorist"]),
+			letterers: self.creators_with_role(&["letterer"]),
+			cover_artists: self.creators_with_role(&["cover"]),
+			editors: self.creators_with_role(&["editor"]),
+			characters: Some(names(&self.character_credits)),
+			teams: Some(names(&self.team_credits)),
+			story_arcs: Some(names(&self.story_arc_credits)),
+			cover_url: self.image.as_ref().and_then(|i| i.original_url.clone()),
+			provider_url: self.site_detail_url.clone(),
+			..Default::default()
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn recorded<T: DeserializeOwned>(json: &str) -> Result<T, MetadataProviderError> {
+		serde_json::from_str::<ComicVineResponse>(json)
+			.expect("failed to parse recorded response")
+			.into_results("recorded")
+	}
+
+	#[test]
+	fn test_parse_recorded_search() {
+		let results: Vec<SearchResult> =
+			recorded(include_str!("../../fixtures/comicvine/search.json")).unwrap();
+		assert_eq!(3, results.len());
+		assert!(matches!(&results[0], SearchResult::Volume(v) if v.id == 43_224));
+		assert!(matches!(results[1], SearchResult::Issue { id: 338_632 }));
+		assert!(matches!(results[2], SearchResult::Other));
+	}
+
+	#[test]
+	fn test_series_metadata_from_recorded_volume() {
+		let volume: Volume =
+			recorded(include_str!("../../fixtures/comicvine/volume.json")).unwrap();
+		let metadata = volume.into_series_metadata();
+
+		assert_eq!("comic_vine", metadata.provider);
+		assert_eq!("43224", metadata.external_id);
+		assert_eq!("Saga", metadata.title);
+		assert_eq!(vec!["Saga (2012)".to_string()], metadata.alternative_titles);
+		assert_eq!(Some(2012), metadata.year);
+		assert_eq!(Some("Image".to_string()), metadata.publisher);
+		assert_eq!(Some(66), metadata.volume_count);
+		assert_eq!(
+			Some(
+				"An epic space opera/fantasy from Brian K. Vaughan and Fiona Staples."
+					.to_string()
+			),
+			metadata.summary
+		);
+	}
+
+	#[test]
+	fn test_media_metadata_from_recorded_issue() {
+		let issue: Issue =
+			recorded(include_str!("../../fixtures/comicvine/issue.json")).unwrap();
+		let metadata = issue.into_media_metadata();
+
+		assert_eq!("338632", metadata.external_id);
+		assert_eq!(Some("Chapter One".to_string()), metadata.title);
+		assert_eq!(Some("Saga".to_string()), metadata.series_name);
+		assert_eq!(Some("43224".to_string()), metadata.series_external_id);
+		assert_eq!(Some(1.0), metadata.number);
+		assert_eq!(
+			(Some(2012), Some(5), Some(1)),
+			(metadata.year, metadata.month, metadata.day)
+		);
+		assert_eq!(Some(vec!["Brian K. Vaughan".to_string()]), metadata.writers);
+		assert_eq!(Some(vec!["Fiona Staples".to_string()]), metadata.pencillers);
+		assert_eq!(Some(vec!["Fiona Staples".to_string()]), metadata.inkers);
+		assert_eq!(Some(vec!["Fiona Staples".to_string()]), metadata.colorists);
+		assert_eq!(Some(vec!["Fonografiks".to_string()]), metadata.letterers);
+		assert_eq!(
+			Some(vec!["Fiona Staples".to_string()]),
+			metadata.cover_artists
+		);
+		assert_eq!(Some(vec!["Eric Stephenson".to_string()]), metadata.editors);
+		assert_eq!(
+			Some(vec!["Alana".to_string(), "Marko".to_string()]),
+			metadata.characters
+		);
+		assert_eq!(Some(vec!["The Will".to_string()]), metadata.teams);
+		assert_eq!(
+			Some(vec!["Saga Volume One".to_string()]),
+			metadata.story_arcs
+		);
+	}
+
+	#[test]
+	fn test_error_responses() {
+		let not_found = recorded::<Issue>(
+			r#"{"error": "Object Not Found", "status_code": 101, "results": []}"#,
+		);
+		assert!(matches!(not_found, Err(MetadataProviderError::NotFound(_))));
+
+		let invalid_key = recorded::<Issue>(
+			r#"{"error": "Invalid API Key", "status_code": 100, "results": []}"#,
+		);
+		assert!(matches!(invalid_key, Err(MetadataProviderError::Other(_))));
+	}
+
+	#[test]
+	fn test_blank_token_is_missing() {
+		let client = ComicVineClient::new("  ".to_string(), None);
+		assert!(matches!(
+			client.token(),
+			Err(MetadataProviderError::MissingToken)
+		));
+	}
+
+	#[test]
+	fn test_validate_id() {
+		assert!(ComicVineClient::validate_id("338632").is_ok());
+		assert!(ComicVineClient::validate_id("4000-338632").is_err());
+		assert!(ComicVineClient::validate_id("").is_err());
+	}
+
+	fn get_test_client() -> ComicVineClient {
+		dotenvy::dotenv().ok();
+		let api_token =
+			std::env::var("COMICVINE_API_TOKEN").expect("COMICVINE_API_TOKEN not set");
+		ComicVineClient::new(api_token, None)
+	}
+
+	#[ignore = "Requires COMICVINE_API_TOKEN env var"]
+	#[tokio::test]
+	async fn test_search_series() {
+		let client = get_test_client();
+		let query = SearchQuery {
+			title: "Saga".to_string(),
+			limit: Some(3),
+			..Default::default()
+		};
+
+		let results = client.search_series(&query).await;
+		println!("search_series results: {:#?}", results);
+		assert!(results.is_ok());
+		assert!(!results.unwrap().is_empty());
+	}
+}
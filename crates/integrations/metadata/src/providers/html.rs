@@ -0,0 +1,56 @@
+/// Strip the HTML tags from a description, keeping paragraphs on separate lines and
+/// decoding the most common entities. Providers use HTML to varying degrees, but
+/// Stump stores summaries as plain text.
+pub(crate) fn strip_html(value: &str) -> String {
+	let mut stripped = String::with_capacity(value.len());
+	let mut tag: Option<String> = None;
+	for c in value.chars() {
+		if let Some(name) = tag.as_mut() {
+			if c != '>' {
+				name.push(c);
+				continue;
+			}
+
+			let name = name.trim().to_lowercase();
+			let is_block_end = ["/p", "/div", "/h1", "/h2", "/h3", "/h4", "/li"]
+				.iter()
+				.any(|block| name.starts_with(block));
+			if is_block_end && !stripped.ends_with('\n') {
+				stripped.push('\n');
+			}
+			tag = None;
+		} else if c == '<' {
+			tag = Some(String::new());
+		} else {
+			stripped.push(c);
+		}
+	}
+
+	stripped
+		.replace("&nbsp;", " ")
+		.replace("&quot;", "\"")
+		.replace("&#39;", "'")
+		.replace("&apos;", "'")
+		.replace("&lt;", "<")
+		.replace("&gt;", ">")
+		.replace("&amp;", "&")
+		.trim()
+		.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_strip_html() {
+		assert_eq!(
+			"A story.\nWith breaks and italics.",
+			strip_html("A story.<br>\nWith breaks and <i>italics</i>.<br><br>")
+		);
+		assert_eq!(
+			"First paragraph.\nSecond & last paragraph.",
+			strip_html("<p>First paragraph.</p><p>Second &amp; last paragraph.</p>")
+		);
+	}
+}
@@ -0,0 +1,842 @@
+use std::{
+	collections::HashMap,
+	path::{Path, PathBuf},
+};
+
+use quick_xml::{
+	escape::{resolve_predefined_entity, unescape},
+	events::{BytesStart, Event},
+	Reader,
+};
+use serde::Deserialize;
+
+use super::html::strip_html;
+use crate::{
+	error::MetadataProviderError,
+	types::{
+		ConfidenceFactor, ExternalMediaMetadata, ExternalSeriesMetadata, MatchCandidate,
+		MediaType, PublicationStatus, SearchQuery,
+	},
+	ExternalMetadata, MetadataProvider,
+};
+
+/// The confidence given to a sidecar which is named after the book it sits beside, or which
+/// describes the series folder it is in. There is no guesswork involved in these matches.
+const EXACT_SIDECAR_CONFIDENCE: f32 = 1.0;
+/// The confidence given to a sidecar which is shared by a folder, e.g. a Calibre
+/// `metadata.opf` or a loose `ComicInfo.xml`. These usually describe a single book, but may
+/// have been left in a folder which holds several.
+const FOLDER_SIDECAR_CONFIDENCE: f32 = 0.9;
+
+/// An offline provider which reads metadata from sidecar files stored alongside series and
+/// books on disk. Since the files are matched by location rather than by title, queries
+/// must include a path. The external ID of each match is the path of the sidecar file.
+///
+/// The following sidecars are supported:
+///
+/// - `series.json` (Mylar) in a series folder
+/// - `metadata.opf` (Calibre) in a series or book folder
+/// - `ComicInfo.xml` in a series or book folder
+/// - `<book>.opf` and `<book>.xml` (ComicInfo) next to a book
+#[derive(Default)]
+pub struct LocalSidecarProvider;
+
+impl LocalSidecarProvider {
+	pub fn new() -> Self {
+		Self
+	}
+
+	fn candidate(
+		&self,
+		path: &Path,
+		metadata: ExternalMetadata,
+		confidence: f32,
+	) -> MatchCandidate {
+		MatchCandidate {
+			provider: self.id().to_string(),
+			external_id: path.to_string_lossy().to_string(),
+			metadata,
+			confidence,
+			confidence_factors: vec![ConfidenceFactor {
+				factor: "sidecar_file".to_string(),
+				weight: confidence,
+				matched: true,
+			}],
+		}
+	}
+}
+
+/// The kinds of sidecar files which can be read, determined by the file name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SidecarKind {
+	ComicInfo,
+	Opf,
+	SeriesJson,
+}
+
+impl SidecarKind {
+	fn from_path(path: &Path) -> Option<Self> {
+		let file_name = path.file_name()?.to_str()?.to_lowercase();
+		if file_name == "series.json" {
+			return Some(Self::SeriesJson);
+		}
+
+		match path.extension()?.to_str()?.to_lowercase().as_str() {
+			"opf" => Some(Self::Opf),
+			"xml" => Some(Self::ComicInfo),
+			_ => None,
+		}
+	}
+}
+
+/// Find a file in a directory, ignoring the case of the file name
+async fn find_file(dir: &Path, file_name: &str) -> Option<PathBuf> {
+	let exact = dir.join(file_name);
+	if tokio::fs::try_exists(&exact).await.unwrap_or(false) {
+		return Some(exact);
+	}
+
+	let mut entries = tokio::fs::read_dir(dir).await.ok()?;
+	while let Ok(Some(entry)) = entries.next_entry().await {
+		if entry
+			.file_name()
+			.to_str()
+			.is_some_and(|name| name.eq_ignore_ascii_case(file_name))
+		{
+			return Some(entry.path());
+		}
+	}
+
+	None
+}
+
+async fn read_sidecar(path: &Path) -> Result<SidecarMetadata, MetadataProviderError> {
+	let kind = SidecarKind::from_path(path).ok_or_else(|| {
+		MetadataProviderError::NotFound(format!(
+			"{} is not a supported sidecar file",
+			path.display()
+		))
+	})?;
+
+	let contents = match tokio::fs::read_to_string(path).await {
+		Ok(contents) => contents,
+		Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+			return Err(MetadataProviderError::NotFound(path.display().to_string()))
+		},
+		Err(e) => return Err(e.into()),
+	};
+
+	match kind {
+		SidecarKind::ComicInfo => parse_comic_info(&contents),
+		SidecarKind::Opf => parse_opf(&contents),
+		SidecarKind::SeriesJson => parse_series_json(&contents),
+	}
+}
+
+#[async_trait::async_trait]
+impl MetadataProvider for LocalSidecarProvider {
+	fn id(&self) -> &'static str {
+		"local"
+	}
+
+	fn name(&self) -> &'static str {
+		"Local files"
+	}
+
+	fn supported_media_types(&self) -> Vec<MediaType> {
+		vec![
+			MediaType::Comic,
+			MediaType::Manga,
+			MediaType::Book,
+			MediaType::LightNovel,
+			MediaType::Manhwa,
+			MediaType::WebNovel,
+			MediaType::Webtoon,
+		]
+	}
+
+	/// Read the sidecars in the series folder at the query's path
+	async fn search_series(
+		&self,
+		query: &SearchQuery,
+	) -> Result<Vec<MatchCandidate>, MetadataProviderError> {
+		let Some(series_dir) = query.path.as_deref().map(Path::new) else {
+			return Ok(vec![]);
+		};
+
+		let sidecars = [
+			("series.json", EXACT_SIDECAR_CONFIDENCE),
+			("metadata.opf", EXACT_SIDECAR_CONFIDENCE),
+			("ComicInfo.xml", FOLDER_SIDECAR_CONFIDENCE),
+		];
+
+		let mut candidates = Vec::new();
+		for (file_name, confidence) in sidecars {
+			let Some(path) = find_file(series_dir, file_name).await else {
+				continue;
+			};
+
+			match read_sidecar(&path).await {
+				Ok(sidecar) => candidates.push(self.candidate(
+					&path,
+					ExternalMetadata::Series(sidecar.into_series_metadata(&path)),
+					confidence,
+				)),
+				Err(e) => {
+					tracing::warn!(?path, error = ?e, "Failed to read series sidecar");
+				},
+			}
+		}
+
+		Ok(candidates)
+	}
+
+	/// Read the sidecars next to the book at the query's path. Sidecars named after the book
+	/// are preferred over those shared by the folder.
+	async fn search_media(
+		&self,
+		query: &SearchQuery,
+	) -> Result<Vec<MatchCandidate>, MetadataProviderError> {
+		let Some(book_path) = query.path.as_deref().map(Path::new) else {
+			return Ok(vec![]);
+		};
+		let (Some(dir), Some(stem)) = (
+			book_path.parent(),
+			book_path.file_stem().and_then(|s| s.to_str()),
+		) else {
+			return Ok(vec![]);
+		};
+
+		let sidecars = [
+			(format!("{stem}.opf"), EXACT_SIDECAR_CONFIDENCE),
+			(format!("{stem}.xml"), EXACT_SIDECAR_CONFIDENCE),
+			("metadata.opf".to_string(), FOLDER_SIDECAR_CONFIDENCE),
+			("ComicInfo.xml".to_string(), FOLDER_SIDECAR_CONFIDENCE),
+		];
+
+		let mut candidates = Vec::new();
+		for (file_name, confidence) in sidecars {
+			let Some(path) = find_file(dir, &file_name).await else {
+				continue;
+			};
+
+			match read_sidecar(&path).await {
+				Ok(sidecar) => candidates.push(self.candidate(
+					&path,
+					ExternalMetadata::Media(sidecar.into_media_metadata(&path)),
+					confidence,
+				)),
+				Err(e) => {
+					tracing::warn!(?path, error = ?e, "Failed to read media sidecar");
+				},
+			}
+		}
+
+		Ok(candidates)
+	}
+
+	/// Sidecars are matched by location, so there is nothing to score
+	fn score_search(
+		&self,
+		_: &SearchQuery,
+		candidates: Vec<MatchCandidate>,
+	) -> Vec<MatchCandidate> {
+		candidates
+	}
+
+	async fn fetch_series_metadata(
+		&self,
+		external_id: &str,
+	) -> Result<ExternalSeriesMetadata, MetadataProviderError> {
+		let path = Path::new(external_id);
+		Ok(read_sidecar(path).await?.into_series_metadata(path))
+	}
+
+	async fn fetch_media_metadata(
+		&self,
+		external_id: &str,
+	) -> Result<ExternalMediaMetadata, MetadataProviderError> {
+		let path = Path::new(external_id);
+		Ok(read_sidecar(path).await?.into_media_metadata(path))
+	}
+}
+
+/// The metadata read from a sidecar file, before it is shaped into series or media metadata
+#[derive(Debug, Default)]
+struct SidecarMetadata {
+	title: Option<String>,
+	series: Option<String>,
+	number: Option<f32>,
+	summary: Option<String>,
+	publisher: Option<String>,
+	status: Option<PublicationStatus>,
+	age_rating: Option<String>,
+	issue_count: Option<i32>,
+	page_count: Option<i32>,
+
+	year: Option<i32>,
+	month: Option<i32>,
+	day: Option<i32>,
+
+	isbn: Option<String>,
+	genres: Vec<String>,
+	tags: Vec<String>,
+
+	writers: Vec<String>,
+	artists: Vec<String>,
+	pencillers: Vec<String>,
+	inkers: Vec<String>,
+	colorists: Vec<String>,
+	letterers: Vec<String>,
+	cover_artists: Vec<String>,
+	editors: Vec<String>,
+
+	characters: Vec<String>,
+	teams: Vec<String>,
+	story_arcs: Vec<String>,
+
+	url: Option<String>,
+}
+
+fn non_empty(values: Vec<String>) -> Option<Vec<String>> {
+	(!values.is_empty()).then_some(values)
+}
+
+/// The name of the folder the sidecar is in, used when a sidecar doesn't name its series
+fn folder_name(path: &Path) -> String {
+	path.parent()
+		.and_then(|dir| dir.file_name())
+		.map(|name| name.to_string_lossy().to_string())
+		.unwrap_or_default()
+}
+
+impl SidecarMetadata {
+	fn split_isbn(&self) -> (Option<String>, Option<String>) {
+		match self.isbn.as_deref().map(|isbn| isbn.replace('-', "")) {
+			Some(isbn) if isbn.len() == 13 => (None, Some(isbn)),
+			Some(isbn) if !isbn.is_empty() => (Some(isbn), None),
+			_ => (None, None),
+		}
+	}
+
+	fn into_series_metadata(self, path: &Path) -> ExternalSeriesMetadata {
+		let title = self
+			.series
+			.clone()
+			.or_else(|| self.title.clone())
+			.unwrap_or_else(|| folder_name(path));
+
+		ExternalSeriesMetadata {
+			provider: "local".to_string(),
+			external_id: path.to_string_lossy().to_string(),
+			title,
+			summary: self.summary,
+			status: self.status,
+			year: self.year,
+			genres: non_empty(self.genres),
+			tags: non_empty(self.tags),
+			age_rating: self.age_rating,
+			authors: non_empty(self.writers),
+			artists: non_empty(self.artists),
+			publisher: self.publisher,
+			volume_count: self.issue_count,
+			..Default::default()
+		}
+	}
+
+	fn into_media_metadata(self, path: &Path) -> ExternalMediaMetadata {
+		let (isbn, isbn_13) = self.split_isbn();
+
+		ExternalMediaMetadata {
+			provider: "local".to_string(),
+			external_id: path.to_string_lossy().to_string(),
+			title: self.title,
+			summary: self.summary,
+			page_count: self.page_count,
+			series_name: self.series,
+			number: self.number,
+			day: self.day,
+			month: self.month,
+			year: self.year,
+			genres: non_empty(self.genres),
+			tags: non_empty(self.tags),
+			isbn,
+			isbn_13,
+			writers: non_empty(self.writers),
+			artists: non_empty(self.artists),
+			colorists: non_empty(self.colorists),
+			letterers: non_empty(self.letterers),
+			cover_artists: non_empty(self.cover_artists),
+			pencillers: non_empty(self.pencillers),
+			inkers: non_empty(self.inkers),
+			editors: non_empty(self.editors),
+			characters: non_empty(self.characters),
+			teams: non_empty(self.teams),
+			story_arcs: non_empty(self.story_arcs),
+			publisher: self.publisher,
+			provider_url: self.url,
+			..Default::default()
+		}
+	}
+}
+
+/// An element read from a sidecar XML document. Namespace prefixes are dropped and names
+/// are lowercased, so `dc:creator` and `<Creator>` are both read as `creator`.
+#[derive(Debug)]
+struct XmlElement {
+	name: String,
+	attributes: HashMap<String, String>,
+	text: String,
+}
+
+impl XmlElement {
+	fn from_start(start: &BytesStart<'_>) -> Self {
+		let attributes = start
+			.attributes()
+			.flatten()
+			.map(|attr| {
+				let key = String::from_utf8_lossy(attr.key.local_name().as_ref())
+					.to_lowercase();
+				let raw_value = String::from_utf8_lossy(&attr.value).to_string();
+				let value = unescape(&raw_value)
+					.map(|v| v.into_owned())
+					.unwrap_or(raw_value);
+				(key, value)
+			})
+			.collect();
+
+		Self {
+			name: String::from_utf8_lossy(start.local_name().as_ref()).to_lowercase(),
+			attributes,
+			text: String::new(),
+		}
+	}
+
+	fn attribute(&self, name: &str) -> Option<&str> {
+		self.attributes.get(name).map(String::as_str)
+	}
+
+	fn text(&self) -> Option<String> {
+		let text = self.text.trim();
+		(!text.is_empty()).then(|| text.to_string())
+	}
+}
+
+/// Read every element of an XML document, in document order of their closing tags
+fn read_xml_elements(xml: &str) -> Result<Vec<XmlElement>, MetadataProviderError> {
+	let mut reader = Reader::from_str(xml);
+	let mut open: Vec<XmlElement> = Vec::new();
+	let mut elements = Vec::new();
+
+	loop {
+		match reader.read_event() {
+			Ok(Event::Start(start)) => open.push(XmlElement::from_start(&start)),
+			Ok(Event::Empty(start)) => elements.push(XmlElement::from_start(&start)),
+			Ok(Event::Text(text)) => {
+				if let Some(element) = open.last_mut() {
+					element.text.push_str(&String::from_utf8_lossy(&text));
+				}
+			},
+			Ok(Event::CData(text)) => {
+				if let Some(element) = open.last_mut() {
+					element.text.push_str(&String::from_utf8_lossy(&text));
+				}
+			},
+			Ok(Event::GeneralRef(reference)) => {
+				if let Some(element) = open.last_mut() {
+					let name = String::from_utf8_lossy(&reference).to_string();
+					let resolved = match reference.resolve_char_ref() {
+						Ok(Some(c)) => c.to_string(),
+						_ => resolve_predefined_entity(&name)
+							.map(String::from)
+							.unwrap_or_else(|| format!("&{name};")),
+					};
+					element.text.push_str(&resolved);
+				}
+			},
+			Ok(Event::End(_)) => {
+				if let Some(element) = open.pop() {
+					elements.push(element);
+				}
+			},
+			Ok(Event::Eof) => break,
+			Err(e) => {
+				return Err(MetadataProviderError::Other(format!(
+					"Failed to parse sidecar XML: {e}"
+				)))
+			},
+			_ => {},
+		}
+	}
+
+	Ok(elements)
+}
+
+/// Split a comma separated ComicInfo list, e.g. `Writer` or `Genre`
+fn split_list(value: &str) -> Vec<String> {
+	value
+		.split(',')
+		.map(str::trim)
+		.filter(|v| !v.is_empty())
+		.map(String::from)
+		.collect()
+}
+
+/// Parse a loose `ComicInfo.xml` file
+/// See https://anansi-project.github.io/docs/comicinfo/documentation
+fn parse_comic_info(xml: &str) -> Result<SidecarMetadata, MetadataProviderError> {
+	let mut metadata = SidecarMetadata::default();
+
+	for element in read_xml_elements(xml)? {
+		let Some(text) = element.text() else {
+			continue;
+		};
+
+		match element.name.as_str() {
+			"title" => metadata.title = Some(text),
+			"series" => metadata.series = Some(text),
+			"number" => metadata.number = text.parse().ok(),
+			"count" => metadata.issue_count = text.parse().ok(),
+			"summary" => metadata.summary = Some(strip_html(&text)),
+			"publisher" => metadata.publisher = Some(text),
+			"agerating" => metadata.age_rating = Some(text),
+			"pagecount" => metadata.page_count = text.parse().ok(),
+			"year" => metadata.year = text.parse().ok(),
+			"month" => metadata.month = text.parse().ok(),
+			"day" => metadata.day = text.parse().ok(),
+			"gtin" => metadata.isbn = Some(text),
+			"genre" => metadata.genres = split_list(&text),
+			"tags" => metadata.tags = split_list(&text),
+			"writer" => metadata.writers = split_list(&text),
+			"penciller" => metadata.pencillers = split_list(&text),
+			"inker" => metadata.inkers = split_list(&text),
+			"colorist" => metadata.colorists = split_list(&text),
+			"letterer" => metadata.letterers = split_list(&text),
+			"coverartist" => metadata.cover_artists = split_list(&text),
+			"editor" => metadata.editors = split_list(&text),
+			"characters" => metadata.characters = split_list(&text),
+			"teams" => metadata.teams = split_list(&text),
+			"storyarc" => metadata.story_arcs = split_list(&text),
+			"web" => metadata.url = split_list(&text).into_iter().next(),
+			_ => {},
+		}
+	}
+
+	Ok(metadata)
+}
+
+/// Parse an OPF package document, e.g. a Calibre `metadata.opf`. Both OPF 2 (`opf:role`
+/// attributes) and OPF 3 (`refines` meta elements) creator roles are supported.
+/// See https://www.w3.org/TR/epub/#sec-package-doc
+fn parse_opf(xml: &str) -> Result<SidecarMetadata, MetadataProviderError> {
+	let elements = read_xml_elements(xml)?;
+	let mut metadata = SidecarMetadata::default();
+
+	// OPF 3 refines creators and collections by ID in separate meta elements
+	let refinements = |id: Option<&str>, property: &str| -> Option<String> {
+		let target = format!("#{}", id?);
+		elements
+			.iter()
+			.filter(|e| e.name == "meta")
+			.filter(|e| e.attribute("refines") == Some(target.as_str()))
+			.find(|e| e.attribute("property") == Some(property))
+			.and_then(XmlElement::text)
+	};
+
+	for element in &elements {
+		match element.name.as_str() {
+			"title" if metadata.title.is_none() => metadata.title = element.text(),
+			"description" => metadata.summary = element.text().map(|d| strip_html(&d)),
+			"publisher" => metadata.publisher = element.text(),
+			"subject" => metadata.genres.extend(element.text()),
+			"date" => {
+				let Some(date) = element.text() else {
+					continue;
+				};
+				let mut parts = date.get(..10).unwrap_or(&date).split('-');
+				metadata.year = parts.next().and_then(|y| y.parse().ok());
+				metadata.month = parts.next().and_then(|m| m.parse().ok());
+				metadata.day = parts.next().and_then(|d| d.parse().ok());
+			},
+			"identifier" => {
+				let Some(identifier) = element.text() else {
+					continue;
+				};
+				let is_isbn_scheme = element
+					.attribute("scheme")
+					.is_some_and(|s| s.eq_ignore_ascii_case("isbn"));
+				if is_isbn_scheme {
+					metadata.isbn = Some(identifier);
+				} else if let Some((scheme, value)) = identifier.split_once(':') {
+					if scheme.eq_ignore_ascii_case("isbn") {
+						metadata.isbn = Some(value.to_string());
+					}
+				}
+			},
+			"creator" | "contributor" => {
+				let Some(name) = element.text() else {
+					continue;
+				};
+				let role = element
+					.attribute("role")
+					.map(String::from)
+					.or_else(|| refinements(element.attribute("id"), "role"));
+				let credits = match role.as_deref() {
+					None if element.name == "creator" => &mut metadata.writers,
+					Some("aut") => &mut metadata.writers,
+					Some("ill" | "art") => &mut metadata.artists,
+					Some("clr") => &mut metadata.colorists,
+					Some("cov") => &mut metadata.cover_artists,
+					Some("edt") => &mut metadata.editors,
+					_ => continue,
+				};
+				credits.push(name);
+			},
+			"meta" => match (element.attribute("name"), element.attribute("property")) {
+				(Some("calibre:series"), _) => {
+					metadata.series = element.attribute("content").map(String::from);
+				},
+				(Some("calibre:series_index"), _) => {
+					metadata.number =
+						element.attribute("content").and_then(|n| n.parse().ok());
+				},
+				(_, Some("belongs-to-collection")) if metadata.series.is_none() => {
+					metadata.series = element.text();
+					metadata.number =
+						refinements(element.attribute("id"), "group-position")
+							.and_then(|n| n.parse().ok());
+				},
+				_ => {},
+			},
+			_ => {},
+		}
+	}
+
+	Ok(metadata)
+}
+
+/// A Mylar `series.json` file
+/// See https://github.com/mylar3/mylar3/wiki/series.json-schema-%28version-1.0.1%29
+#[derive(Debug, Deserialize)]
+struct SeriesJson {
+	metadata: SeriesJsonMetadata,
+}
+
+#[derive(Debug, Deserialize)]
+struct SeriesJsonMetadata {
+	name: Option<String>,
+	description_text: Option<String>,
+	publisher: Option<String>,
+	year: Option<i32>,
+	total_issues: Option<i32>,
+	status: Option<String>,
+	age_rating: Option<serde_json::Value>,
+}
+
+fn parse_series_json(json: &str) -> Result<SidecarMetadata, MetadataProviderError> {
+	let SeriesJson { metadata } = serde_json::from_str(json)?;
+
+	let status = metadata.status.as_deref().and_then(|status| {
+		match status.to_lowercase().as_str() {
+			"continuing" | "ongoing" => Some(PublicationStatus::Ongoing),
+			"ended" | "completed" => Some(PublicationStatus::Completed),
+			_ => None,
+		}
+	});
+
+	let age_rating = metadata.age_rating.and_then(|rating| match rating {
+		serde_json::Value::String(rating) if !rating.is_empty() => Some(rating),
+		serde_json::Value::Number(rating) => Some(rating.to_string()),
+		_ => None,
+	});
+
+	Ok(SidecarMetadata {
+		series: metadata.name,
+		summary: metadata.description_text,
+		publisher: metadata.publisher,
+		year: metadata.year,
+		issue_count: metadata.total_issues,
+		status,
+		age_rating,
+		..Default::default()
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const COMIC_INFO: &str = include_str!("../../fixtures/local/ComicInfo.xml");
+	const CALIBRE_OPF: &str = include_str!("../../fixtures/local/metadata.opf");
+	const SERIES_JSON: &str = include_str!("../../fixtures/local/series.json");
+
+	#[test]
+	fn test_sidecar_kind_from_path() {
+		assert_eq!(
+			Some(SidecarKind::SeriesJson),
+			SidecarKind::from_path(Path::new("/comics/Saga/series.json"))
+		);
+		assert_eq!(
+			Some(SidecarKind::Opf),
+			SidecarKind::from_path(Path::new("/books/Dune/metadata.opf"))
+		);
+		assert_eq!(
+			Some(SidecarKind::ComicInfo),
+			SidecarKind::from_path(Path::new("/comics/Saga/Saga 001.xml"))
+		);
+		assert_eq!(
+			None,
+			SidecarKind::from_path(Path::new("/comics/Saga/Saga 001.cbz"))
+		);
+	}
+
+	#[test]
+	fn test_parse_comic_info() {
+		let metadata = parse_comic_info(COMIC_INFO)
+			.unwrap()
+			.into_media_metadata(Path::new("/comics/Saga/Saga 001.xml"));
+
+		assert_eq!("local", metadata.provider);
+		assert_eq!("/comics/Saga/Saga 001.xml", metadata.external_id);
+		assert_eq!(Some("Chapter One".to_string()), metadata.title);
+		assert_eq!(Some("Saga".to_string()), metadata.series_name);
+		assert_eq!(Some(1.0), metadata.number);
+		assert_eq!(
+			(Some(2012), Some(3), Some(14)),
+			(metadata.year, metadata.month, metadata.day)
+		);
+		assert_eq!(Some(44), metadata.page_count);
+		assert_eq!(Some(vec!["Brian K. Vaughan".to_string()]), metadata.writers);
+		assert_eq!(Some(vec!["Fiona Staples".to_string()]), metadata.pencillers);
+		assert_eq!(
+			Some(vec!["Alana".to_string(), "Marko".to_string()]),
+			metadata.characters
+		);
+		assert_eq!(
+			Some(vec!["Saga Volume One".to_string()]),
+			metadata.story_arcs
+		);
+		assert_eq!(
+			Some("Star-crossed lovers & their newborn daughter.".to_string()),
+			metadata.summary
+		);
+	}
+
+	#[test]
+	fn test_parse_calibre_opf() {
+		let metadata = parse_opf(CALIBRE_OPF)
+			.unwrap()
+			.into_media_metadata(Path::new("/books/Wayfarers/metadata.opf"));
+
+		assert_eq!(
+			Some("The Long Way to a Small, Angry Planet".to_string()),
+			metadata.title
+		);
+		assert_eq!(Some("Wayfarers".to_string()), metadata.series_name);
+		assert_eq!(Some(1.0), metadata.number);
+		assert_eq!(Some(vec!["Becky Chambers".to_string()]), metadata.writers);
+		assert_eq!(Some(vec!["Jane Doe".to_string()]), metadata.artists);
+		assert_eq!(Some("9780062444134".to_string()), metadata.isbn_13);
+		assert_eq!(Some("Harper Voyager".to_string()), metadata.publisher);
+		assert_eq!(
+			(Some(2014), Some(7), Some(29)),
+			(metadata.year, metadata.month, metadata.day)
+		);
+		assert_eq!(
+			Some(vec![
+				"Science fiction".to_string(),
+				"Space Opera".to_string()
+			]),
+			metadata.genres
+		);
+		assert_eq!(
+			Some(
+				"Follow a motley crew on an exciting journey through space.".to_string()
+			),
+			metadata.summary
+		);
+	}
+
+	#[test]
+	fn test_parse_series_json() {
+		let metadata = parse_series_json(SERIES_JSON)
+			.unwrap()
+			.into_series_metadata(Path::new("/comics/Saga/series.json"));
+
+		assert_eq!("Saga", metadata.title);
+		assert_eq!(Some("Image".to_string()), metadata.publisher);
+		assert_eq!(Some(2012), metadata.year);
+		assert_eq!(Some(66), metadata.volume_count);
+		assert!(matches!(metadata.status, Some(PublicationStatus::Ongoing)));
+	}
+
+	#[tokio::test]
+	async fn test_search_media_prefers_named_sidecars() {
+		let dir = tempfile::tempdir().unwrap();
+		std::fs::write(dir.path().join("Saga 001.xml"), COMIC_INFO).unwrap();
+		std::fs::write(dir.path().join("metadata.opf"), CALIBRE_OPF).unwrap();
+
+		let query = SearchQuery {
+			title: "Saga".to_string(),
+			path: Some(
+				dir.path()
+					.join("Saga 001.cbz")
+					.to_string_lossy()
+					.to_string(),
+			),
+			..Default::default()
+		};
+		let candidates = LocalSidecarProvider::new()
+			.search_media(&query)
+			.await
+			.unwrap();
+
+		assert_eq!(2, candidates.len());
+		assert!(candidates[0].external_id.ends_with("Saga 001.xml"));
+		assert_eq!(EXACT_SIDECAR_CONFIDENCE, candidates[0].confidence);
+		assert!(candidates[1].external_id.ends_with("metadata.opf"));
+		assert_eq!(FOLDER_SIDECAR_CONFIDENCE, candidates[1].confidence);
+	}
+
+	#[tokio::test]
+	async fn test_search_series_reads_series_folder() {
+		let dir = tempfile::tempdir().unwrap();
+		std::fs::write(dir.path().join("series.json"), SERIES_JSON).unwrap();
+
+		let query = SearchQuery {
+			title: "Saga".to_string(),
+			path: Some(dir.path().to_string_lossy().to_string()),
+			..Default::default()
+		};
+		let provider = LocalSidecarProvider::new();
+		let candidates = provider.search_series(&query).await.unwrap();
+
+		assert_eq!(1, candidates.len());
+		let refetched = provider
+			.fetch_series_metadata(&candidates[0].external_id)
+			.await
+			.unwrap();
+		assert_eq!("Saga", refetched.title);
+	}
+
+	#[tokio::test]
+	async fn test_search_without_path() {
+		let query = SearchQuery {
+			title: "Saga".to_string(),
+			..Default::default()
+		};
+		let candidates = LocalSidecarProvider::new()
+			.search_media(&query)
+			.await
+			.unwrap();
+		assert!(candidates.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_fetch_rejects_other_files() {
+		let result = LocalSidecarProvider::new()
+			.fetch_media_metadata("/comics/Saga/Saga 001.cbz")
+			.await;
+		assert!(matches!(result, Err(MetadataProviderError::NotFound(_))));
+	}
+}
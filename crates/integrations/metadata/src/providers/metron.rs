@@ -0,0 +1,565 @@
+use chrono::Datelike;
+use reqwest_middleware::ClientWithMiddleware;
+use serde::{de::DeserializeOwned, Deserialize};
+
+use super::html::strip_html;
+use crate::{
+	client::{build_client_with_retry, RetryClientConfig},
+	error::MetadataProviderError,
+	types::{
+		ExternalMediaMetadata, ExternalSeriesMetadata, MatchCandidate, MediaType,
+		PublicationStatus, SearchQuery,
+	},
+	ExternalMetadata, MetadataProvider, RateLimiter,
+};
+
+/// Metron allows 30 requests per minute (and 10,000 per day)
+/// See: https://metron-project.github.io/docs/category/api
+const METRON_DEFAULT_RATE_LIMIT: u32 = 30;
+
+pub struct MetronClient {
+	client: ClientWithMiddleware,
+	api_token: String,
+	rate_limiter: RateLimiter,
+}
+
+impl MetronClient {
+	const API_URL: &'static str = "https://metron.cloud/api";
+
+	/// Create a new Metron client. Metron uses basic authentication, so the token is
+	/// expected to be in the form `username:password`.
+	pub fn new(api_token: String, rate_limit_per_minute: Option<u32>) -> Self {
+		Self {
+			client: build_client_with_retry(
+				reqwest::Client::new(),
+				RetryClientConfig::default(),
+			),
+			api_token,
+			rate_limiter: RateLimiter::per_minute(
+				rate_limit_per_minute.unwrap_or(METRON_DEFAULT_RATE_LIMIT),
+			),
+		}
+	}
+
+	fn credentials(&self) -> Result<(String, String), MetadataProviderError> {
+		if self.api_token.is_empty() {
+			return Err(MetadataProviderError::MissingToken);
+		}
+		let (username, password) = self.api_token.split_once(':').ok_or_else(|| {
+			MetadataProviderError::Other(
+				"Metron credentials must be in the form username:password".to_string(),
+			)
+		})?;
+		Ok((username.to_string(), password.to_string()))
+	}
+
+	async fn get<T: DeserializeOwned>(
+		&self,
+		path: &str,
+		params: &[(&str, String)],
+	) -> Result<T, MetadataProviderError> {
+		let (username, password) = self.credentials()?;
+		self.rate_limiter.until_ready().await;
+
+		let response = self
+			.client
+			.get(format!("{}/{}", Self::API_URL, path))
+			.basic_auth(username, Some(password))
+			.query(params)
+			.send()
+			.await?;
+
+		if response.status() == reqwest::StatusCode::NOT_FOUND {
+			return Err(MetadataProviderError::NotFound(path.to_string()));
+		}
+
+		Ok(response.error_for_status()?.json::<T>().await?)
+	}
+
+	#[tracing::instrument(skip(self))]
+	async fn search_series_list(
+		&self,
+		name: &str,
+	) -> Result<Vec<SeriesListItem>, MetadataProviderError> {
+		let page: Paginated<SeriesListItem> =
+			self.get("series/", &[("name", name.to_string())]).await?;
+		Ok(page.results)
+	}
+
+	#[tracing::instrument(skip(self))]
+	async fn search_issue_list(
+		&self,
+		series_name: &str,
+		number: Option<&str>,
+	) -> Result<Vec<IssueListItem>, MetadataProviderError> {
+		let mut params = vec![("series_name", series_name.to_string())];
+		if let Some(number) = number {
+			params.push(("number", number.to_string()));
+		}
+
+		let page: Paginated<IssueListItem> = self.get("issue/", &params).await?;
+		Ok(page.results)
+	}
+
+	async fn fetch_series(&self, id: i64) -> Result<SeriesDetail, MetadataProviderError> {
+		self.get(&format!("series/{id}/"), &[]).await
+	}
+
+	async fn fetch_issue(&self, id: i64) -> Result<IssueDetail, MetadataProviderError> {
+		self.get(&format!("issue/{id}/"), &[]).await
+	}
+
+	fn parse_id(external_id: &str) -> Result<i64, MetadataProviderError> {
+		external_id.parse().map_err(|_| {
+			MetadataProviderError::Other(format!("Invalid Metron ID: {}", external_id))
+		})
+	}
+}
+
+/// Split a query like "Saga #12" into the series name and the issue number, since Metron
+/// filters issues by series name and number rather than free text
+fn split_issue_number(title: &str) -> (&str, Option<&str>) {
+	match title.rsplit_once('#') {
+		Some((series, number))
+			if !series.trim().is_empty() && !number.trim().is_empty() =>
+		{
+			(series.trim(), Some(number.trim()))
+		},
+		_ => (title.trim(), None),
+	}
+}
+
+#[async_trait::async_trait]
+impl MetadataProvider for MetronClient {
+	fn id(&self) -> &'static str {
+		"metron"
+	}
+
+	fn name(&self) -> &'static str {
+		"Metron"
+	}
+
+	fn supported_media_types(&self) -> Vec<MediaType> {
+		vec![MediaType::Comic]
+	}
+
+	/// Search for series on Metron and fetch full metadata for each result
+	/// See: https://metron.cloud/api/docs/#/series/series_list
+	async fn search_series(
+		&self,
+		query: &SearchQuery,
+	) -> Result<Vec<MatchCandidate>, MetadataProviderError> {
+		let limit = query.limit.unwrap_or(10) as usize;
+		let hits = self.search_series_list(&query.title).await?;
+
+		let mut candidates = Vec::with_capacity(hits.len().min(limit));
+		for hit in hits.into_iter().take(limit) {
+			let external_id = hit.id.to_string();
+			match self.fetch_series_metadata(&external_id).await {
+				Ok(metadata) => candidates.push(MatchCandidate {
+					external_id,
+					metadata: ExternalMetadata::Series(metadata),
+					provider: self.id().to_string(),
+					confidence: 0.0,
+					confidence_factors: Vec::new(),
+				}),
+				Err(e) => {
+					tracing::error!(
+						external_id,
+						error = ?e,
+						"Failed to fetch series metadata for search result"
+					);
+				},
+			}
+		}
+
+		Ok(self.score_search(query, candidates))
+	}
+
+	/// Search for issues on Metron and fetch full metadata for each result. An issue number
+	/// may be included in the query title, e.g. "Saga #12"
+	/// See: https://metron.cloud/api/docs/#/issue/issue_list
+	#[tracing::instrument(skip(self))]
+	async fn search_media(
+		&self,
+		query: &SearchQuery,
+	) -> Result<Vec<MatchCandidate>, MetadataProviderError> {
+		let limit = query.limit.unwrap_or(10) as usize;
+		let (series_name, number) = split_issue_number(&query.title);
+		let hits = self.search_issue_list(series_name, number).await?;
+
+		let mut candidates = Vec::with_capacity(hits.len().min(limit));
+		for hit in hits.into_iter().take(limit) {
+			let external_id = hit.id.to_string();
+			match self.fetch_media_metadata(&external_id).await {
+				Ok(metadata) => candidates.push(MatchCandidate {
+					external_id,
+					metadata: ExternalMetadata::Media(metadata),
+					provider: self.id().to_string(),
+					confidence: 0.0,
+					confidence_factors: Vec::new(),
+				}),
+				Err(e) => {
+					tracing::error!(
+						external_id,
+						error = ?e,
+						"Failed to fetch issue metadata for search result"
+					);
+				},
+			}
+		}
+
+		Ok(self.score_search(query, candidates))
+	}
+
+	async fn fetch_series_metadata(
+		&self,
+		external_id: &str,
+	) -> Result<ExternalSeriesMetadata, MetadataProviderError> {
+		let id = Self::parse_id(external_id)?;
+		Ok(self.fetch_series(id).await?.into_series_metadata())
+	}
+
+	async fn fetch_media_metadata(
+		&self,
+		external_id: &str,
+	) -> Result<ExternalMediaMetadata, MetadataProviderError> {
+		let id = Self::parse_id(external_id)?;
+		Ok(self.fetch_issue(id).await?.into_media_metadata())
+	}
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Paginated<T> {
+	#[serde(default = "Vec::new")]
+	pub results: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SeriesListItem {
+	pub id: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IssueListItem {
+	pub id: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NamedRef {
+	pub name: String,
+}
+
+/// See https://metron.cloud/api/docs/#/series/series_retrieve
+#[derive(Debug, Deserialize)]
+pub struct SeriesDetail {
+	pub id: i64,
+	pub name: String,
+	pub sort_name: Option<String>,
+	pub status: Option<String>,
+	pub publisher: Option<NamedRef>,
+	pub year_began: Option<i32>,
+	pub year_end: Option<i32>,
+	pub desc: Option<String>,
+	#[serde(default)]
+	pub genres: Vec<NamedRef>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IssueSeries {
+	pub id: i64,
+	pub name: String,
+	#[serde(default)]
+	pub genres: Vec<NamedRef>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Credit {
+	pub creator: String,
+	#[serde(default)]
+	pub role: Vec<NamedRef>,
+}
+
+/// See https://metron.cloud/api/docs/#/issue/issue_retrieve
+#[derive(Debug, Deserialize)]
+pub struct IssueDetail {
+	pub id: i64,
+	pub publisher: Option<NamedRef>,
+	pub series: IssueSeries,
+	pub number: Option<String>,
+	pub title: Option<String>,
+	/// The names of the stories in the issue
+	#[serde(default)]
+	pub name: Vec<String>,
+	pub cover_date: Option<String>,
+	pub isbn: Option<String>,
+	pub page: Option<i32>,
+	pub desc: Option<String>,
+	pub image: Option<String>,
+	#[serde(default)]
+	pub arcs: Vec<NamedRef>,
+	#[serde(default)]
+	pub credits: Vec<Credit>,
+	#[serde(default)]
+	pub characters: Vec<NamedRef>,
+	#[serde(default)]
+	pub teams: Vec<NamedRef>,
+	pub resource_url: Option<String>,
+}
+
+fn names(refs: &[NamedRef]) -> Vec<String> {
+	refs.iter().map(|r| r.name.clone()).collect()
+}
+
+fn description(desc: Option<&str>) -> Option<String> {
+	desc.map(strip_html).filter(|d| !d.is_empty())
+}
+
+impl SeriesDetail {
+	pub fn into_series_metadata(self) -> ExternalSeriesMetadata {
+		let status = self.status.as_deref().and_then(|status| {
+			match status.to_lowercase().as_str() {
+				"ongoing" => Some(PublicationStatus::Ongoing),
+				"completed" => Some(PublicationStatus::Completed),
+				"cancelled" => Some(PublicationStatus::Cancelled),
+				"hiatus" => Some(PublicationStatus::Hiatus),
+				_ => None,
+			}
+		});
+
+		let alternative_titles = self
+			.sort_name
+			.clone()
+			.filter(|sort_name| *sort_name != self.name)
+			.into_iter()
+			.collect();
+
+		ExternalSeriesMetadata {
+			provider: "metron".to_string(),
+			external_id: self.id.to_string(),
+			title: self.name,
+			alternative_titles,
+			summary: description(self.desc.as_deref()),
+			status,
+			year: self.year_began,
+			end_year: self.year_end,
+			genres: Some(names(&self.genres)),
+			publisher: self.publisher.map(|p| p.name),
+			// metron only counts the issues of a series, not its volumes
+			volume_count: None,
+			..Default::default()
+		}
+	}
+}
+
+impl IssueDetail {
+	/// Collect the creators credited with any of the given roles, in credit order
+	fn creators_with_role(&self, is_role: impl Fn(&str) -> bool) -> Option<Vec<String>> {
+		let creators: Vec<String> = self
+			.credits
+			.iter()
+			.filter(|credit| credit.role.iter().any(|role| is_role(&role.name)))
+			.map(|credit| credit.creator.clone())
+			.collect();
+		Some(creators)
+	}
+
+	pub fn into_media_metadata(self) -> ExternalMediaMetadata {
+		let (year, month, day) = match self
+			.cover_date
+			.as_deref()
+			.and_then(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+		{
+			Some(date) => (
+				Some(date.year()),
+				Some(date.month() as i32),
+				Some(date.day() as i32),
+			),
+			None => (None, None, None),
+		};
+
+		let (isbn, isbn_13) = match self.isbn.as_deref().map(|isbn| isbn.replace('-', ""))
+		{
+			Some(isbn) if isbn.len() == 13 => (None, Some(isbn)),
+			Some(isbn) if !isbn.is_empty() => (Some(isbn), None),
+			_ => (None, None),
+		};
+
+		let title = self
+			.title
+			.clone()
+			.filter(|title| !title.is_empty())
+			.or_else(|| self.name.first().cloned())
+			.or_else(|| {
+				self.number
+					.as_ref()
+					.map(|number| format!("{} #{}", self.series.name, number))
+			});
+
+		ExternalMediaMetadata {
+			provider: "metron".to_string(),
+			external_id: self.id.to_string(),
+			title,
+			summary: description(self.desc.as_deref()),
+			page_count: self.page,
+			series_name: Some(self.series.name.clone()),
+			series_external_id: Some(self.series.id.to_string()),
+			number: self.number.as_ref().and_then(|n| n.parse().ok()),
+			day,
+			month,
+			year,
+			genres: Some(names(&self.series.genres)),
+			isbn,
+			isbn_13,
+			writers: self.creators_with_role(|role| {
+				matches!(role, "Writer" | "Script" | "Story" | "Plot")
+			}),
+			pencillers: self.creators_with_role(|role| {
+				matches!(
+					role,
+					"Penciller" | "Artist" | "Illustrator" | "Breakdowns" | "Layouts"
+				)
+			}),
+			inkers: self.creators_with_role(|role| {
+				matches!(
+					role,
+					"Inker" | "Artist" | "Illustrator" | "Embellisher" | "Finishes"
+				)
+			}),
+			colorists: self.creators_with_role(|role| {
+				matches!(role, "Colorist" | "Color Separations")
+			}),
+			letterers: self.creators_with_role(|role| role == "Letterer"),
+			cover_artists: self.creators_with_role(|role| role == "Cover"),
+			editors: self.creators_with_role(|role| {
+				role.contains("Editor") && role != "Editor In Chief"
+			}),
+			characters: Some(names(&self.characters)),
+			teams: Some(names(&self.teams)),
+			story_arcs: Some(names(&self.arcs)),
+			publisher: self.publisher.as_ref().map(|p| p.name.clone()),
+			cover_url: self.image.clone(),
+			provider_url: self.resource_url.clone(),
+			..Default::default()
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn recorded<T: DeserializeOwned>(json: &str) -> T {
+		serde_json::from_str(json).expect("failed to parse recorded response")
+	}
+
+	#[test]
+	fn test_split_issue_number() {
+		assert_eq!(("Saga", Some("12")), split_issue_number("Saga #12"));
+		assert_eq!(("Saga", None), split_issue_number("Saga"));
+		assert_eq!(("#", None), split_issue_number("#"));
+	}
+
+	#[test]
+	fn test_parse_recorded_series_list() {
+		let page: Paginated<SeriesListItem> =
+			recorded(include_str!("../../fixtures/metron/series_list.json"));
+		assert_eq!(2, page.results.len());
+		assert_eq!(2118, page.results[0].id);
+	}
+
+	#[test]
+	fn test_series_metadata_from_recorded_series() {
+		let series: SeriesDetail =
+			recorded(include_str!("../../fixtures/metron/series.json"));
+		let metadata = series.into_series_metadata();
+
+		assert_eq!("metron", metadata.provider);
+		assert_eq!("2118", metadata.external_id);
+		assert_eq!("Saga", metadata.title);
+		assert!(matches!(metadata.status, Some(PublicationStatus::Ongoing)));
+		assert_eq!(Some(2012), metadata.year);
+		assert_eq!(Some("Image".to_string()), metadata.publisher);
+		assert_eq!(None, metadata.volume_count);
+		assert_eq!(
+			Some(vec!["Fantasy".to_string(), "Science Fiction".to_string()]),
+			metadata.genres
+		);
+	}
+
+	#[test]
+	fn test_media_metadata_from_recorded_issue() {
+		let issue: IssueDetail =
+			recorded(include_str!("../../fixtures/metron/issue.json"));
+		let metadata = issue.into_media_metadata();
+
+		assert_eq!("metron", metadata.provider);
+		assert_eq!("19633", metadata.external_id);
+		assert_eq!(Some("Chapter One".to_string()), metadata.title);
+		assert_eq!(Some("Saga".to_string()), metadata.series_name);
+		assert_eq!(Some("2118".to_string()), metadata.series_external_id);
+		assert_eq!(Some(1.0), metadata.number);
+		assert_eq!(
+			(Some(2012), Some(5), Some(1)),
+			(metadata.year, metadata.month, metadata.day)
+		);
+		assert_eq!(Some(44), metadata.page_count);
+		assert_eq!(Some(vec!["Brian K. Vaughan".to_string()]), metadata.writers);
+		assert_eq!(Some(vec!["Fiona Staples".to_string()]), metadata.pencillers);
+		assert_eq!(Some(vec!["Fiona Staples".to_string()]), metadata.inkers);
+		assert_eq!(Some(vec!["Fiona Staples".to_string()]), metadata.colorists);
+		assert_eq!(Some(vec!["Fonografiks".to_string()]), metadata.letterers);
+		assert_eq!(
+			Some(vec!["Fiona Staples".to_string()]),
+			metadata.cover_artists
+		);
+		assert_eq!(Some(vec!["Eric Stephenson".to_string()]), metadata.editors);
+		assert_eq!(
+			Some(vec![
+				"Alana".to_string(),
+				"Hazel".to_string(),
+				"Marko".to_string()
+			]),
+			metadata.characters
+		);
+		assert_eq!(Some(vec!["The Will".to_string()]), metadata.teams);
+		assert_eq!(
+			Some(vec!["Saga Volume One".to_string()]),
+			metadata.story_arcs
+		);
+		assert_eq!(Some("Image".to_string()), metadata.publisher);
+		assert_eq!(
+			Some("When two soldiers from opposite sides of a never-ending galactic war fall in love, they risk everything.".to_string()),
+			metadata.summary
+		);
+	}
+
+	fn get_test_client() -> MetronClient {
+		dotenvy::dotenv().ok();
+		let api_token =
+			std::env::var("METRON_CREDENTIALS").expect("METRON_CREDENTIALS not set");
+		MetronClient::new(api_token, None)
+	}
+
+	#[test]
+	fn test_invalid_credentials() {
+		let client = MetronClient::new("no-password".to_string(), None);
+		assert!(client.credentials().is_err());
+	}
+
+	#[ignore = "Requires METRON_CREDENTIALS env var"]
+	#[tokio::test]
+	async fn test_search_media() {
+		let client = get_test_client();
+		let query = SearchQuery {
+			title: "Saga #1".to_string(),
+			limit: Some(2),
+			..Default::default()
+		};
+
+		let results = client.search_media(&query).await;
+		println!("search_media results: {:#?}", results);
+		assert!(results.is_ok());
+		assert!(!results.unwrap().is_empty());
+	}
+}
@@ -1,3 +1,12 @@
+mod anilist;
+mod comicvine;
 mod hardcover;
+mod html;
+mod local;
+mod metron;
 
+pub use anilist::AnilistClient;
+pub use comicvine::ComicVineClient;
 pub use hardcover::HardcoverClient;
+pub use local::LocalSidecarProvider;
+pub use metron::MetronClient;
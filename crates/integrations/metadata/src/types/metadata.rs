@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::types::PublicationStatus;
 
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, Serialize, Deserialize, Union)]
 pub enum ExternalMetadata {
 	Media(ExternalMediaMetadata),
@@ -58,6 +59,15 @@ pub struct ExternalMediaMetadata {
 	pub colorists: Option<Vec<String>>,
 	pub letterers: Option<Vec<String>>,
 	pub cover_artists: Option<Vec<String>>,
+	pub pencillers: Option<Vec<String>>,
+	pub inkers: Option<Vec<String>>,
+	pub editors: Option<Vec<String>>,
+
+	pub characters: Option<Vec<String>>,
+	pub teams: Option<Vec<String>>,
+	pub story_arcs: Option<Vec<String>>,
+
+	pub publisher: Option<String>,
 
 	pub cover_url: Option<String>,
 
@@ -5,6 +5,8 @@ pub struct SearchQuery {
 	pub isbn: Option<String>,
 	pub year: Option<i32>,
 	pub limit: Option<u32>,
+	/// The path of the series or book on disk, for providers which read from the filesystem
+	pub path: Option<String>,
 }
 
 impl Default for SearchQuery {
@@ -15,6 +17,7 @@ impl Default for SearchQuery {
 			isbn: None,
 			year: None,
 			limit: Some(10),
+			path: None,
 		}
 	}
 }
@@ -27,20 +27,16 @@ impl NotificationClient for DiscordClient {
 	fn payload_from_event(
 		event: NotificationEvent,
 	) -> NotificationResult<serde_json::Value> {
-		let payload = match event {
-			NotificationEvent::ScanCompleted {
-				books_added,
-				library_name,
-			} => json!({
-				"username" : NOTIFIER_ID,
-				"avatar_url" : FAVICON_URL,
-				"embeds" : [{
-					"title" : "Scan Completed!",
-					"description": format!("{books_added} books added to {library_name}"),
-					"color" : 13605239,
-				}]
-			}),
-		};
+		let title = event.title();
+		let payload = json!({
+			"username" : NOTIFIER_ID,
+			"avatar_url" : FAVICON_URL,
+			"embeds" : [{
+				"title" : title,
+				"description": event.into_message(),
+				"color" : 13605239,
+			}]
+		});
 		Ok(payload)
 	}
 
@@ -4,6 +4,8 @@ pub type NotificationResult<T> = Result<T, NotificationError>;
 pub enum NotificationError {
 	#[error("Request failed with error: {0}")]
 	ReqwestError(#[from] reqwest::Error),
+	#[error("Failed to serialize payload: {0}")]
+	SerializationError(#[from] serde_json::Error),
 	#[error("{0}")]
 	Unimplemented(String),
 	#[error("Request was unsuccessful")]
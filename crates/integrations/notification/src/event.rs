@@ -1,11 +1,54 @@
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", content = "data", rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum NotificationEvent {
 	ScanCompleted {
 		books_added: u64,
 		library_name: String,
 	},
+	JobFailed {
+		job_id: String,
+		job_name: String,
+		error: String,
+	},
+	SeriesDiscovered {
+		library_name: String,
+		series_names: Vec<String>,
+	},
+	LibraryMissing {
+		library_name: String,
+		path: String,
+	},
+	MetadataAwaitingReview {
+		count: u64,
+	},
+	UserRegistered {
+		username: String,
+	},
+	BookClubMessage {
+		book_club_name: String,
+		username: String,
+		content: String,
+	},
 }
 
 impl NotificationEvent {
+	/// A short title for the event, e.g. for the heading of an embed
+	pub fn title(&self) -> &'static str {
+		match self {
+			NotificationEvent::ScanCompleted { .. } => "Scan Completed!",
+			NotificationEvent::JobFailed { .. } => "Job Failed",
+			NotificationEvent::SeriesDiscovered { .. } => "New Series Discovered",
+			NotificationEvent::LibraryMissing { .. } => "Library Missing",
+			NotificationEvent::MetadataAwaitingReview { .. } => {
+				"Metadata Awaiting Review"
+			},
+			NotificationEvent::UserRegistered { .. } => "User Registered",
+			NotificationEvent::BookClubMessage { .. } => "New Book Club Message",
+		}
+	}
+
 	pub fn into_message(self) -> String {
 		match self {
 			NotificationEvent::ScanCompleted {
@@ -19,6 +62,74 @@ impl NotificationEvent {
 					books_added, book_or_books, library_name
 				)
 			},
+			NotificationEvent::JobFailed {
+				job_name, error, ..
+			} => format!("The {job_name} job failed: {error}"),
+			NotificationEvent::SeriesDiscovered {
+				library_name,
+				series_names,
+			} => match series_names.as_slice() {
+				[name] => format!("{name} was added to {library_name}"),
+				names => format!(
+					"{} series added to {}: {}",
+					names.len(),
+					library_name,
+					names.join(", ")
+				),
+			},
+			NotificationEvent::LibraryMissing { library_name, path } => {
+				format!("{library_name} could not be found at {path}")
+			},
+			NotificationEvent::MetadataAwaitingReview { count } => {
+				let is_plural = count == 0 || count > 1;
+				let match_or_matches = if is_plural { "matches" } else { "match" };
+				format!("{count} metadata {match_or_matches} awaiting review")
+			},
+			NotificationEvent::UserRegistered { username } => {
+				format!("{username} registered an account")
+			},
+			NotificationEvent::BookClubMessage {
+				book_club_name,
+				username,
+				content,
+			} => format!("{username} in {book_club_name}: {content}"),
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_into_message() {
+		let event = NotificationEvent::SeriesDiscovered {
+			library_name: "Comics".to_string(),
+			series_names: vec!["Saga".to_string()],
+		};
+		assert_eq!(event.into_message(), "Saga was added to Comics");
+
+		let event = NotificationEvent::SeriesDiscovered {
+			library_name: "Comics".to_string(),
+			series_names: vec!["Saga".to_string(), "Monstress".to_string()],
+		};
+		assert_eq!(
+			event.into_message(),
+			"2 series added to Comics: Saga, Monstress"
+		);
+
+		let event = NotificationEvent::MetadataAwaitingReview { count: 1 };
+		assert_eq!(event.into_message(), "1 metadata match awaiting review");
+	}
+
+	#[test]
+	fn test_serialize() {
+		let event = NotificationEvent::UserRegistered {
+			username: "oromei".to_string(),
+		};
+		assert_eq!(
+			serde_json::to_string(&event).unwrap(),
+			r#"{"event":"USER_REGISTERED","data":{"username":"oromei"}}"#
+		);
+	}
+}
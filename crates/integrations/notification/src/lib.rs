@@ -2,12 +2,14 @@ mod discord_client;
 mod error;
 mod event;
 mod telegram_client;
+mod webhook_client;
 
 pub use discord_client::DiscordClient;
 pub use event::NotificationEvent;
 pub use telegram_client::TelegramClient;
+pub use webhook_client::WebhookClient;
 
-use self::error::NotificationResult;
+pub use self::error::{NotificationError, NotificationResult};
 
 pub const NOTIFIER_ID: &str = "Stump Notifier";
 pub const FAVICON_URL: &str = "https://stumpapp.dev/favicon.png";
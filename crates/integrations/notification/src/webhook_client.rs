@@ -0,0 +1,126 @@
+use hmac::{Hmac, Mac};
+use reqwest::header::CONTENT_TYPE;
+use sha2::Sha256;
+
+use super::{
+	error::{NotificationError, NotificationResult},
+	NotificationClient, NotificationEvent,
+};
+
+/// The header which holds the signature of the request body, when the webhook has a secret
+pub const SIGNATURE_HEADER: &str = "X-Stump-Signature";
+
+/// A client for generic HTTP webhooks. Each event is sent as a JSON body in a POST
+/// request, with any configured headers added to it
+pub struct WebhookClient {
+	url: String,
+	headers: Vec<(String, String)>,
+	secret: Option<String>,
+	client: reqwest::Client,
+}
+
+impl WebhookClient {
+	pub fn new(
+		url: String,
+		headers: Vec<(String, String)>,
+		secret: Option<String>,
+	) -> Self {
+		let client = reqwest::Client::new();
+		Self {
+			url,
+			headers,
+			secret,
+			client,
+		}
+	}
+
+	/// Sign the body with HMAC-SHA256, in the `sha256=<hex digest>` format used by most
+	/// webhook providers so receivers can verify the request came from this server
+	pub fn sign(secret: &str, body: &[u8]) -> String {
+		let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+			.expect("HMAC accepts keys of any length");
+		mac.update(body);
+		format!(
+			"sha256={}",
+			data_encoding::HEXLOWER.encode(&mac.finalize().into_bytes())
+		)
+	}
+}
+
+#[async_trait::async_trait]
+impl NotificationClient for WebhookClient {
+	fn payload_from_event(
+		event: NotificationEvent,
+	) -> NotificationResult<serde_json::Value> {
+		let mut payload = serde_json::to_value(&event)?;
+		payload["title"] = event.title().into();
+		payload["message"] = event.into_message().into();
+		Ok(payload)
+	}
+
+	async fn send_message(&self, event: NotificationEvent) -> NotificationResult<()> {
+		let body = serde_json::to_vec(&Self::payload_from_event(event)?)?;
+
+		let mut request = self
+			.client
+			.post(&self.url)
+			.header(CONTENT_TYPE, "application/json");
+		for (name, value) in &self.headers {
+			request = request.header(name, value);
+		}
+		if let Some(secret) = &self.secret {
+			request = request.header(SIGNATURE_HEADER, Self::sign(secret, &body));
+		}
+
+		let response = request.body(body).send().await?;
+		if !response.status().is_success() {
+			let errmsg = response
+				.text()
+				.await
+				.unwrap_or_else(|_| "Webhook request failed".to_string());
+			Err(NotificationError::RequestFailed(errmsg))
+		} else {
+			Ok(())
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_payload_from_event() {
+		let event = NotificationEvent::JobFailed {
+			job_id: "42".to_string(),
+			job_name: "library_scan".to_string(),
+			error: "Library could not be found on disk".to_string(),
+		};
+		let payload = WebhookClient::payload_from_event(event).unwrap();
+		assert_eq!(payload["event"], "JOB_FAILED");
+		assert_eq!(payload["data"]["job_id"], "42");
+		assert_eq!(payload["title"], "Job Failed");
+		assert_eq!(
+			payload["message"],
+			"The library_scan job failed: Library could not be found on disk"
+		);
+	}
+
+	#[test]
+	fn test_sign() {
+		// See https://datatracker.ietf.org/doc/html/rfc4231#section-4.3
+		assert_eq!(
+			WebhookClient::sign("Jefe", b"what do ya want for nothing?"),
+			"sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+		);
+	}
+
+	#[tokio::test]
+	async fn test_send_message_failed() {
+		let client = WebhookClient::new("http://localhost:1".to_string(), vec![], None);
+		let event = NotificationEvent::UserRegistered {
+			username: "oromei".to_string(),
+		};
+		assert!(client.send_message(event).await.is_err());
+	}
+}
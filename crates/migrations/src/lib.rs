@@ -22,6 +22,18 @@ mod m20260311_000000_scheduled_jobs_redesign;
 mod m20260404_185829_add_name_indexes;
 mod m20260406_000000_add_kobo_sync_sessions;
 mod m20260505_231341_jwt_secrets;
+mod m20261018_000000_kobo_sync_session_tags;
+mod m20261018_000001_library_kobo_convert_to_epub;
+mod m20261018_000002_library_watch_mode;
+mod m20261018_000003_duplicate_media;
+mod m20261018_000004_rewrite_reviews;
+mod m20261018_000005_collections;
+mod m20261018_000006_reading_lists;
+mod m20261018_000007_notifier_events;
+mod m20261018_000008_persistent_job_queue;
+mod m20261018_000009_job_queue_priority;
+mod m20261018_000010_scheduled_job_last_error;
+mod m20261018_000011_job_failed_tasks;
 
 pub struct Migrator;
 
@@ -50,6 +62,18 @@ impl MigratorTrait for Migrator {
 			Box::new(m20260404_185829_add_name_indexes::Migration),
 			Box::new(m20260406_000000_add_kobo_sync_sessions::Migration),
 			Box::new(m20260505_231341_jwt_secrets::Migration),
+			Box::new(m20261018_000000_kobo_sync_session_tags::Migration),
+			Box::new(m20261018_000001_library_kobo_convert_to_epub::Migration),
+			Box::new(m20261018_000002_library_watch_mode::Migration),
+			Box::new(m20261018_000003_duplicate_media::Migration),
+			Box::new(m20261018_000004_rewrite_reviews::Migration),
+			Box::new(m20261018_000005_collections::Migration),
+			Box::new(m20261018_000006_reading_lists::Migration),
+			Box::new(m20261018_000007_notifier_events::Migration),
+			Box::new(m20261018_000008_persistent_job_queue::Migration),
+			Box::new(m20261018_000009_job_queue_priority::Migration),
+			Box::new(m20261018_000010_scheduled_job_last_error::Migration),
+			Box::new(m20261018_000011_job_failed_tasks::Migration),
 		]
 	}
 }
@@ -0,0 +1,68 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+	async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.alter_table(
+				Table::alter()
+					.table(KoboSyncSessions::Table)
+					.add_column(
+						ColumnDef::new(KoboSyncSessions::Tags)
+							.json()
+							.not_null()
+							.default("[]"),
+					)
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.alter_table(
+				Table::alter()
+					.table(KoboSyncSessions::Table)
+					.add_column(
+						ColumnDef::new(KoboSyncSessions::TagChanges)
+							.json()
+							.not_null()
+							.default("{}"),
+					)
+					.to_owned(),
+			)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.alter_table(
+				Table::alter()
+					.table(KoboSyncSessions::Table)
+					.drop_column(KoboSyncSessions::TagChanges)
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.alter_table(
+				Table::alter()
+					.table(KoboSyncSessions::Table)
+					.drop_column(KoboSyncSessions::Tags)
+					.to_owned(),
+			)
+			.await?;
+
+		Ok(())
+	}
+}
+
+#[derive(DeriveIden)]
+enum KoboSyncSessions {
+	Table,
+	Tags,
+	TagChanges,
+}
@@ -0,0 +1,67 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+	async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.alter_table(
+				Table::alter()
+					.table(LibraryConfigs::Table)
+					.add_column(
+						ColumnDef::new(LibraryConfigs::KoboConvertToEpub)
+							.boolean()
+							.not_null()
+							.default(false),
+					)
+					.to_owned(),
+			)
+			.await?;
+
+		// When the library last opted in, so devices which already synced pick up the books
+		// that weren't synced before
+		manager
+			.alter_table(
+				Table::alter()
+					.table(LibraryConfigs::Table)
+					.add_column(
+						ColumnDef::new(LibraryConfigs::KoboConvertEnabledAt).timestamp(),
+					)
+					.to_owned(),
+			)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.alter_table(
+				Table::alter()
+					.table(LibraryConfigs::Table)
+					.drop_column(LibraryConfigs::KoboConvertEnabledAt)
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.alter_table(
+				Table::alter()
+					.table(LibraryConfigs::Table)
+					.drop_column(LibraryConfigs::KoboConvertToEpub)
+					.to_owned(),
+			)
+			.await?;
+
+		Ok(())
+	}
+}
+
+#[derive(DeriveIden)]
+enum LibraryConfigs {
+	Table,
+	KoboConvertToEpub,
+	KoboConvertEnabledAt,
+}
@@ -0,0 +1,68 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+	async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.alter_table(
+				Table::alter()
+					.table(LibraryConfigs::Table)
+					.add_column(
+						ColumnDef::new(LibraryConfigs::WatchMode)
+							.text()
+							.not_null()
+							.default("NATIVE"),
+					)
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.alter_table(
+				Table::alter()
+					.table(LibraryConfigs::Table)
+					.add_column(
+						ColumnDef::new(LibraryConfigs::WatchPollIntervalSeconds)
+							.integer()
+							.not_null()
+							.default(60),
+					)
+					.to_owned(),
+			)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.alter_table(
+				Table::alter()
+					.table(LibraryConfigs::Table)
+					.drop_column(LibraryConfigs::WatchPollIntervalSeconds)
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.alter_table(
+				Table::alter()
+					.table(LibraryConfigs::Table)
+					.drop_column(LibraryConfigs::WatchMode)
+					.to_owned(),
+			)
+			.await?;
+
+		Ok(())
+	}
+}
+
+#[derive(DeriveIden)]
+enum LibraryConfigs {
+	Table,
+	WatchMode,
+	WatchPollIntervalSeconds,
+}
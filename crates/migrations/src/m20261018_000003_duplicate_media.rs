@@ -0,0 +1,197 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+	async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.create_table(
+				Table::create()
+					.table(DuplicateMediaGroups::Table)
+					.if_not_exists()
+					.col(
+						ColumnDef::new(DuplicateMediaGroups::Id)
+							.text()
+							.not_null()
+							.primary_key(),
+					)
+					.col(
+						ColumnDef::new(DuplicateMediaGroups::Reason)
+							.text()
+							.not_null(),
+					)
+					.col(ColumnDef::new(DuplicateMediaGroups::Key).text().not_null())
+					.col(
+						ColumnDef::new(DuplicateMediaGroups::CreatedAt)
+							.timestamp()
+							.not_null()
+							.default(Expr::current_timestamp()),
+					)
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.create_table(
+				Table::create()
+					.table(DuplicateMediaGroupMembers::Table)
+					.if_not_exists()
+					.col(
+						ColumnDef::new(DuplicateMediaGroupMembers::Id)
+							.integer()
+							.not_null()
+							.auto_increment()
+							.primary_key(),
+					)
+					.col(
+						ColumnDef::new(DuplicateMediaGroupMembers::GroupId)
+							.text()
+							.not_null(),
+					)
+					.col(
+						ColumnDef::new(DuplicateMediaGroupMembers::MediaId)
+							.text()
+							.not_null(),
+					)
+					.foreign_key(
+						ForeignKey::create()
+							.name("fk-duplicate_media_group_members-group")
+							.from(
+								DuplicateMediaGroupMembers::Table,
+								DuplicateMediaGroupMembers::GroupId,
+							)
+							.to(DuplicateMediaGroups::Table, DuplicateMediaGroups::Id)
+							.on_delete(ForeignKeyAction::Cascade)
+							.on_update(ForeignKeyAction::Cascade),
+					)
+					.foreign_key(
+						ForeignKey::create()
+							.name("fk-duplicate_media_group_members-media")
+							.from(
+								DuplicateMediaGroupMembers::Table,
+								DuplicateMediaGroupMembers::MediaId,
+							)
+							.to(Media::Table, Media::Id)
+							.on_delete(ForeignKeyAction::Cascade)
+							.on_update(ForeignKeyAction::Cascade),
+					)
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.create_table(
+				Table::create()
+					.table(IgnoredDuplicateMedia::Table)
+					.if_not_exists()
+					.col(
+						ColumnDef::new(IgnoredDuplicateMedia::Id)
+							.integer()
+							.not_null()
+							.auto_increment()
+							.primary_key(),
+					)
+					.col(
+						ColumnDef::new(IgnoredDuplicateMedia::Reason)
+							.text()
+							.not_null(),
+					)
+					.col(ColumnDef::new(IgnoredDuplicateMedia::Key).text().not_null())
+					.col(
+						ColumnDef::new(IgnoredDuplicateMedia::MediaId)
+							.text()
+							.not_null(),
+					)
+					.col(
+						ColumnDef::new(IgnoredDuplicateMedia::IgnoredAt)
+							.timestamp()
+							.not_null()
+							.default(Expr::current_timestamp()),
+					)
+					.foreign_key(
+						ForeignKey::create()
+							.name("fk-ignored_duplicate_media-media")
+							.from(
+								IgnoredDuplicateMedia::Table,
+								IgnoredDuplicateMedia::MediaId,
+							)
+							.to(Media::Table, Media::Id)
+							.on_delete(ForeignKeyAction::Cascade)
+							.on_update(ForeignKeyAction::Cascade),
+					)
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.create_index(
+				Index::create()
+					.name("idx-ignored_duplicate_media-reason-key-media")
+					.table(IgnoredDuplicateMedia::Table)
+					.col(IgnoredDuplicateMedia::Reason)
+					.col(IgnoredDuplicateMedia::Key)
+					.col(IgnoredDuplicateMedia::MediaId)
+					.unique()
+					.if_not_exists()
+					.to_owned(),
+			)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.drop_table(Table::drop().table(IgnoredDuplicateMedia::Table).to_owned())
+			.await?;
+
+		manager
+			.drop_table(
+				Table::drop()
+					.table(DuplicateMediaGroupMembers::Table)
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.drop_table(Table::drop().table(DuplicateMediaGroups::Table).to_owned())
+			.await?;
+
+		Ok(())
+	}
+}
+
+#[derive(DeriveIden)]
+enum DuplicateMediaGroups {
+	Table,
+	Id,
+	Reason,
+	Key,
+	CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum DuplicateMediaGroupMembers {
+	Table,
+	Id,
+	GroupId,
+	MediaId,
+}
+
+#[derive(DeriveIden)]
+enum IgnoredDuplicateMedia {
+	Table,
+	Id,
+	Reason,
+	Key,
+	MediaId,
+	IgnoredAt,
+}
+
+#[derive(DeriveIden)]
+enum Media {
+	Table,
+	Id,
+}
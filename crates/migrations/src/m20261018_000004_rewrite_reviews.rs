@@ -0,0 +1,191 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::Statement;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+	async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		// Existing reviews are kept, so the table is altered in place. SQLite only supports
+		// one change per ALTER TABLE statement
+		manager
+			.alter_table(
+				Table::alter()
+					.table(Reviews::Table)
+					.rename_column(Reviews::Content, Reviews::ReviewText)
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.alter_table(
+				Table::alter()
+					.table(Reviews::Table)
+					.add_column(
+						ColumnDef::new(Reviews::Visibility)
+							.text()
+							.not_null()
+							.default("PUBLIC"),
+					)
+					.to_owned(),
+			)
+			.await?;
+		manager
+			.exec_stmt(
+				Query::update()
+					.table(Reviews::Table)
+					.value(Reviews::Visibility, "PRIVATE")
+					.and_where(Expr::col(Reviews::IsPrivate).eq(true))
+					.to_owned(),
+			)
+			.await?;
+		manager
+			.alter_table(
+				Table::alter()
+					.table(Reviews::Table)
+					.drop_column(Reviews::IsPrivate)
+					.to_owned(),
+			)
+			.await?;
+
+		// SQLite can't add a column with a non-constant default, so existing reviews are
+		// dated to when the migration runs. New reviews always set their own date
+		manager
+			.alter_table(
+				Table::alter()
+					.table(Reviews::Table)
+					.add_column(
+						ColumnDef::new(Reviews::CreatedAt)
+							.timestamp()
+							.not_null()
+							.default("1970-01-01 00:00:00"),
+					)
+					.to_owned(),
+			)
+			.await?;
+		manager
+			.exec_stmt(
+				Query::update()
+					.table(Reviews::Table)
+					.value(Reviews::CreatedAt, Expr::current_timestamp())
+					.to_owned(),
+			)
+			.await?;
+		manager
+			.alter_table(
+				Table::alter()
+					.table(Reviews::Table)
+					.add_column(ColumnDef::new(Reviews::UpdatedAt).timestamp())
+					.to_owned(),
+			)
+			.await?;
+
+		// A user may only review a book once. Reviews weren't unique before, so only the
+		// most recent review of each book is kept for a user
+		let db = manager.get_connection();
+		db.execute(Statement::from_string(
+			db.get_database_backend(),
+			"DELETE FROM reviews WHERE rowid NOT IN (SELECT MAX(rowid) FROM reviews GROUP BY media_id, user_id)".to_string(),
+		))
+		.await?;
+
+		manager
+			.create_index(
+				Index::create()
+					.name("idx-reviews-media-user")
+					.table(Reviews::Table)
+					.col(Reviews::MediaId)
+					.col(Reviews::UserId)
+					.unique()
+					.if_not_exists()
+					.to_owned(),
+			)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.drop_index(
+				Index::drop()
+					.name("idx-reviews-media-user")
+					.table(Reviews::Table)
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.alter_table(
+				Table::alter()
+					.table(Reviews::Table)
+					.drop_column(Reviews::UpdatedAt)
+					.to_owned(),
+			)
+			.await?;
+		manager
+			.alter_table(
+				Table::alter()
+					.table(Reviews::Table)
+					.drop_column(Reviews::CreatedAt)
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.alter_table(
+				Table::alter()
+					.table(Reviews::Table)
+					.add_column(
+						ColumnDef::new(Reviews::IsPrivate)
+							.boolean()
+							.not_null()
+							.default(false),
+					)
+					.to_owned(),
+			)
+			.await?;
+		manager
+			.exec_stmt(
+				Query::update()
+					.table(Reviews::Table)
+					.value(Reviews::IsPrivate, true)
+					.and_where(Expr::col(Reviews::Visibility).ne("PUBLIC"))
+					.to_owned(),
+			)
+			.await?;
+		manager
+			.alter_table(
+				Table::alter()
+					.table(Reviews::Table)
+					.drop_column(Reviews::Visibility)
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.alter_table(
+				Table::alter()
+					.table(Reviews::Table)
+					.rename_column(Reviews::ReviewText, Reviews::Content)
+					.to_owned(),
+			)
+			.await?;
+
+		Ok(())
+	}
+}
+
+#[derive(DeriveIden)]
+enum Reviews {
+	Table,
+	ReviewText,
+	Content,
+	IsPrivate,
+	Visibility,
+	MediaId,
+	UserId,
+	CreatedAt,
+	UpdatedAt,
+}
@@ -0,0 +1,233 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+	async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.create_table(
+				Table::create()
+					.table(Collections::Table)
+					.if_not_exists()
+					.col(
+						ColumnDef::new(Collections::Id)
+							.text()
+							.not_null()
+							.primary_key(),
+					)
+					.col(ColumnDef::new(Collections::Name).text().not_null())
+					.col(ColumnDef::new(Collections::Description).text())
+					.col(ColumnDef::new(Collections::Visibility).text().not_null())
+					.col(ColumnDef::new(Collections::CreatorId).text().not_null())
+					.col(ColumnDef::new(Collections::CoverSeriesId).text())
+					.col(
+						ColumnDef::new(Collections::CreatedAt)
+							.timestamp()
+							.not_null()
+							.default(Expr::current_timestamp()),
+					)
+					.col(ColumnDef::new(Collections::UpdatedAt).timestamp())
+					.foreign_key(
+						ForeignKey::create()
+							.name("fk-collections-creator")
+							.from(Collections::Table, Collections::CreatorId)
+							.to(Users::Table, Users::Id)
+							.on_delete(ForeignKeyAction::Cascade)
+							.on_update(ForeignKeyAction::Cascade),
+					)
+					.foreign_key(
+						ForeignKey::create()
+							.name("fk-collections-cover_series")
+							.from(Collections::Table, Collections::CoverSeriesId)
+							.to(Series::Table, Series::Id)
+							.on_delete(ForeignKeyAction::SetNull)
+							.on_update(ForeignKeyAction::Cascade),
+					)
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.create_table(
+				Table::create()
+					.table(CollectionItems::Table)
+					.if_not_exists()
+					.col(
+						ColumnDef::new(CollectionItems::Id)
+							.integer()
+							.not_null()
+							.auto_increment()
+							.primary_key(),
+					)
+					.col(
+						ColumnDef::new(CollectionItems::Position)
+							.integer()
+							.not_null(),
+					)
+					.col(
+						ColumnDef::new(CollectionItems::CollectionId)
+							.text()
+							.not_null(),
+					)
+					.col(ColumnDef::new(CollectionItems::SeriesId).text().not_null())
+					.foreign_key(
+						ForeignKey::create()
+							.name("fk-collection_items-collection")
+							.from(CollectionItems::Table, CollectionItems::CollectionId)
+							.to(Collections::Table, Collections::Id)
+							.on_delete(ForeignKeyAction::Cascade)
+							.on_update(ForeignKeyAction::Cascade),
+					)
+					.foreign_key(
+						ForeignKey::create()
+							.name("fk-collection_items-series")
+							.from(CollectionItems::Table, CollectionItems::SeriesId)
+							.to(Series::Table, Series::Id)
+							.on_delete(ForeignKeyAction::Cascade)
+							.on_update(ForeignKeyAction::Cascade),
+					)
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.create_index(
+				Index::create()
+					.name("idx-collection_items-collection-series")
+					.table(CollectionItems::Table)
+					.col(CollectionItems::CollectionId)
+					.col(CollectionItems::SeriesId)
+					.unique()
+					.if_not_exists()
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.create_table(
+				Table::create()
+					.table(CollectionAccessRules::Table)
+					.if_not_exists()
+					.col(
+						ColumnDef::new(CollectionAccessRules::Id)
+							.integer()
+							.not_null()
+							.auto_increment()
+							.primary_key(),
+					)
+					.col(
+						ColumnDef::new(CollectionAccessRules::Role)
+							.integer()
+							.not_null(),
+					)
+					.col(
+						ColumnDef::new(CollectionAccessRules::UserId)
+							.text()
+							.not_null(),
+					)
+					.col(
+						ColumnDef::new(CollectionAccessRules::CollectionId)
+							.text()
+							.not_null(),
+					)
+					.foreign_key(
+						ForeignKey::create()
+							.name("fk-collection_access_rules-user")
+							.from(
+								CollectionAccessRules::Table,
+								CollectionAccessRules::UserId,
+							)
+							.to(Users::Table, Users::Id)
+							.on_delete(ForeignKeyAction::Cascade)
+							.on_update(ForeignKeyAction::Cascade),
+					)
+					.foreign_key(
+						ForeignKey::create()
+							.name("fk-collection_access_rules-collection")
+							.from(
+								CollectionAccessRules::Table,
+								CollectionAccessRules::CollectionId,
+							)
+							.to(Collections::Table, Collections::Id)
+							.on_delete(ForeignKeyAction::Cascade)
+							.on_update(ForeignKeyAction::Cascade),
+					)
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.create_index(
+				Index::create()
+					.name("idx-collection_access_rules-collection-user")
+					.table(CollectionAccessRules::Table)
+					.col(CollectionAccessRules::CollectionId)
+					.col(CollectionAccessRules::UserId)
+					.unique()
+					.if_not_exists()
+					.to_owned(),
+			)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.drop_table(Table::drop().table(CollectionAccessRules::Table).to_owned())
+			.await?;
+		manager
+			.drop_table(Table::drop().table(CollectionItems::Table).to_owned())
+			.await?;
+		manager
+			.drop_table(Table::drop().table(Collections::Table).to_owned())
+			.await?;
+
+		Ok(())
+	}
+}
+
+#[derive(DeriveIden)]
+enum Collections {
+	Table,
+	Id,
+	Name,
+	Description,
+	Visibility,
+	CreatorId,
+	CoverSeriesId,
+	CreatedAt,
+	UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum CollectionItems {
+	Table,
+	Id,
+	Position,
+	CollectionId,
+	SeriesId,
+}
+
+#[derive(DeriveIden)]
+enum CollectionAccessRules {
+	Table,
+	Id,
+	Role,
+	UserId,
+	CollectionId,
+}
+
+#[derive(DeriveIden)]
+enum Series {
+	Table,
+	Id,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+	Table,
+	Id,
+}
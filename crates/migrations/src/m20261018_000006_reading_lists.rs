@@ -0,0 +1,206 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+	async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.create_table(
+				Table::create()
+					.table(ReadingLists::Table)
+					.if_not_exists()
+					.col(
+						ColumnDef::new(ReadingLists::Id)
+							.text()
+							.not_null()
+							.primary_key(),
+					)
+					.col(ColumnDef::new(ReadingLists::Name).text().not_null())
+					.col(ColumnDef::new(ReadingLists::Description).text())
+					.col(
+						ColumnDef::new(ReadingLists::UpdatedAt)
+							.timestamp()
+							.not_null()
+							.default(Expr::current_timestamp()),
+					)
+					.col(ColumnDef::new(ReadingLists::Visibility).text().not_null())
+					.col(ColumnDef::new(ReadingLists::Ordering).text().not_null())
+					.col(
+						ColumnDef::new(ReadingLists::CreatingUserId)
+							.text()
+							.not_null(),
+					)
+					.foreign_key(
+						ForeignKey::create()
+							.name("fk-reading_lists-creating_user")
+							.from(ReadingLists::Table, ReadingLists::CreatingUserId)
+							.to(Users::Table, Users::Id)
+							.on_delete(ForeignKeyAction::Cascade)
+							.on_update(ForeignKeyAction::Cascade),
+					)
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.create_table(
+				Table::create()
+					.table(ReadingListItems::Table)
+					.if_not_exists()
+					.col(
+						ColumnDef::new(ReadingListItems::Id)
+							.integer()
+							.not_null()
+							.auto_increment()
+							.primary_key(),
+					)
+					.col(
+						ColumnDef::new(ReadingListItems::DisplayOrder)
+							.integer()
+							.not_null(),
+					)
+					.col(ColumnDef::new(ReadingListItems::MediaId).text().not_null())
+					.col(
+						ColumnDef::new(ReadingListItems::ReadingListId)
+							.text()
+							.not_null(),
+					)
+					.foreign_key(
+						ForeignKey::create()
+							.name("fk-reading_list_items-media")
+							.from(ReadingListItems::Table, ReadingListItems::MediaId)
+							.to(Media::Table, Media::Id)
+							.on_delete(ForeignKeyAction::Cascade)
+							.on_update(ForeignKeyAction::Cascade),
+					)
+					.foreign_key(
+						ForeignKey::create()
+							.name("fk-reading_list_items-reading_list")
+							.from(
+								ReadingListItems::Table,
+								ReadingListItems::ReadingListId,
+							)
+							.to(ReadingLists::Table, ReadingLists::Id)
+							.on_delete(ForeignKeyAction::Cascade)
+							.on_update(ForeignKeyAction::Cascade),
+					)
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.create_table(
+				Table::create()
+					.table(ReadingListRules::Table)
+					.if_not_exists()
+					.col(
+						ColumnDef::new(ReadingListRules::Id)
+							.integer()
+							.not_null()
+							.auto_increment()
+							.primary_key(),
+					)
+					.col(ColumnDef::new(ReadingListRules::Role).integer().not_null())
+					.col(ColumnDef::new(ReadingListRules::UserId).text().not_null())
+					.col(
+						ColumnDef::new(ReadingListRules::ReadingListId)
+							.text()
+							.not_null(),
+					)
+					.foreign_key(
+						ForeignKey::create()
+							.name("fk-reading_list_rules-user")
+							.from(ReadingListRules::Table, ReadingListRules::UserId)
+							.to(Users::Table, Users::Id)
+							.on_delete(ForeignKeyAction::Cascade)
+							.on_update(ForeignKeyAction::Cascade),
+					)
+					.foreign_key(
+						ForeignKey::create()
+							.name("fk-reading_list_rules-reading_list")
+							.from(
+								ReadingListRules::Table,
+								ReadingListRules::ReadingListId,
+							)
+							.to(ReadingLists::Table, ReadingLists::Id)
+							.on_delete(ForeignKeyAction::Cascade)
+							.on_update(ForeignKeyAction::Cascade),
+					)
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.create_index(
+				Index::create()
+					.name("idx-reading_list_rules-reading_list-user")
+					.table(ReadingListRules::Table)
+					.col(ReadingListRules::ReadingListId)
+					.col(ReadingListRules::UserId)
+					.unique()
+					.if_not_exists()
+					.to_owned(),
+			)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.drop_table(Table::drop().table(ReadingListRules::Table).to_owned())
+			.await?;
+		manager
+			.drop_table(Table::drop().table(ReadingListItems::Table).to_owned())
+			.await?;
+		manager
+			.drop_table(Table::drop().table(ReadingLists::Table).to_owned())
+			.await?;
+
+		Ok(())
+	}
+}
+
+#[derive(DeriveIden)]
+enum ReadingLists {
+	Table,
+	Id,
+	Name,
+	Description,
+	UpdatedAt,
+	Visibility,
+	Ordering,
+	CreatingUserId,
+}
+
+#[derive(DeriveIden)]
+enum ReadingListItems {
+	Table,
+	Id,
+	DisplayOrder,
+	MediaId,
+	ReadingListId,
+}
+
+#[derive(DeriveIden)]
+enum ReadingListRules {
+	Table,
+	Id,
+	Role,
+	UserId,
+	ReadingListId,
+}
+
+#[derive(DeriveIden)]
+enum Media {
+	Table,
+	Id,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+	Table,
+	Id,
+}
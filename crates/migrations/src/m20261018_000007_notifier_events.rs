@@ -0,0 +1,50 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+	async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		// A null list means the notifier is sent every event
+		manager
+			.alter_table(
+				Table::alter()
+					.table(Notifiers::Table)
+					.add_column(ColumnDef::new(Notifiers::Events).json())
+					.to_owned(),
+			)
+			.await?;
+		// Existing notifiers were only ever meant for scan results, so they are subscribed to
+		// those alone rather than to every new kind of event
+		manager
+			.exec_stmt(
+				Query::update()
+					.table(Notifiers::Table)
+					.value(Notifiers::Events, r#"["SCAN_COMPLETED"]"#)
+					.to_owned(),
+			)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.alter_table(
+				Table::alter()
+					.table(Notifiers::Table)
+					.drop_column(Notifiers::Events)
+					.to_owned(),
+			)
+			.await?;
+
+		Ok(())
+	}
+}
+
+#[derive(DeriveIden)]
+enum Notifiers {
+	Table,
+	Events,
+}
@@ -0,0 +1,63 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+	async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		// The serialized job which was enqueued, so queued and interrupted jobs can be
+		// picked back up after a restart
+		manager
+			.alter_table(
+				Table::alter()
+					.table(Jobs::Table)
+					.add_column(ColumnDef::new(Jobs::Payload).blob())
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.create_index(
+				Index::create()
+					.name("idx_jobs_status_created_at")
+					.table(Jobs::Table)
+					.col(Jobs::Status)
+					.col(Jobs::CreatedAt)
+					.to_owned(),
+			)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.drop_index(
+				Index::drop()
+					.name("idx_jobs_status_created_at")
+					.table(Jobs::Table)
+					.to_owned(),
+			)
+			.await?;
+
+		manager
+			.alter_table(
+				Table::alter()
+					.table(Jobs::Table)
+					.drop_column(Jobs::Payload)
+					.to_owned(),
+			)
+			.await?;
+
+		Ok(())
+	}
+}
+
+#[derive(DeriveIden)]
+enum Jobs {
+	Table,
+	Status,
+	CreatedAt,
+	Payload,
+}
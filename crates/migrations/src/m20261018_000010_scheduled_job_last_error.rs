@@ -0,0 +1,41 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+	async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		// Why a scheduled job last failed to start or run, e.g. an invalid cron expression,
+		// so it can be surfaced in the UI
+		manager
+			.alter_table(
+				Table::alter()
+					.table(ScheduledJobs::Table)
+					.add_column(ColumnDef::new(ScheduledJobs::LastError).text())
+					.to_owned(),
+			)
+			.await?;
+
+		Ok(())
+	}
+
+	async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+		manager
+			.alter_table(
+				Table::alter()
+					.table(ScheduledJobs::Table)
+					.drop_column(ScheduledJobs::LastError)
+					.to_owned(),
+			)
+			.await?;
+
+		Ok(())
+	}
+}
+
+#[derive(DeriveIden)]
+enum ScheduledJobs {
+	Table,
+	LastError,
+}
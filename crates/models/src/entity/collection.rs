@@ -1,6 +1,16 @@
-use sea_orm::entity::prelude::*;
+use async_graphql::SimpleObject;
+use chrono::Utc;
+use sea_orm::{
+	entity::prelude::*, prelude::async_trait::async_trait, sea_query::Query, ActiveValue,
+	Condition,
+};
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+use super::{collection_access_rule, user::AuthUser};
+use crate::shared::enums::{AccessRole, EntityVisibility};
+
+/// A user-curated collection of series, which may span multiple libraries
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, SimpleObject)]
+#[graphql(name = "CollectionModel")]
 #[sea_orm(table_name = "collections")]
 pub struct Model {
 	#[sea_orm(primary_key, auto_increment = false, column_type = "Text")]
@@ -9,11 +19,148 @@ pub struct Model {
 	pub name: String,
 	#[sea_orm(column_type = "Text", nullable)]
 	pub description: Option<String>,
+	pub visibility: EntityVisibility,
+	#[sea_orm(column_type = "Text")]
+	pub creator_id: String,
+	/// The series whose thumbnail is used as the cover of the collection. If not set, the
+	/// first series in the collection is used
+	#[sea_orm(column_type = "Text", nullable)]
+	pub cover_series_id: Option<String>,
 	#[sea_orm(column_type = "custom(\"DATETIME\")")]
-	pub updated_at: String,
+	pub created_at: DateTimeWithTimeZone,
+	#[sea_orm(column_type = "custom(\"DATETIME\")", nullable)]
+	pub updated_at: Option<DateTimeWithTimeZone>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {}
+pub enum Relation {
+	#[sea_orm(has_many = "super::collection_access_rule::Entity")]
+	CollectionAccessRule,
+	#[sea_orm(has_many = "super::collection_item::Entity")]
+	CollectionItem,
+	#[sea_orm(
+		belongs_to = "super::series::Entity",
+		from = "Column::CoverSeriesId",
+		to = "super::series::Column::Id",
+		on_update = "Cascade",
+		on_delete = "SetNull"
+	)]
+	Series,
+	#[sea_orm(
+		belongs_to = "super::user::Entity",
+		from = "Column::CreatorId",
+		to = "super::user::Column::Id",
+		on_update = "Cascade",
+		on_delete = "Cascade"
+	)]
+	User,
+}
+
+impl Related<super::collection_access_rule::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::CollectionAccessRule.def()
+	}
+}
+
+impl Related<super::collection_item::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::CollectionItem.def()
+	}
+}
+
+impl Related<super::user::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::User.def()
+	}
+}
+
+#[async_trait]
+impl ActiveModelBehavior for ActiveModel {
+	async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+	where
+		C: ConnectionTrait,
+	{
+		if insert {
+			self.id = ActiveValue::Set(Uuid::new_v4().to_string());
+			self.created_at = ActiveValue::Set(DateTimeWithTimeZone::from(Utc::now()));
+		} else {
+			self.updated_at =
+				ActiveValue::Set(Some(DateTimeWithTimeZone::from(Utc::now())));
+		}
 
-impl ActiveModelBehavior for ActiveModel {}
+		Ok(self)
+	}
+}
+
+/// A condition for the collections a user has at least the given role for:
+///
+/// - The creator of a collection has every role
+/// - An access rule grants its role on public and shared collections, but never on
+///   private ones
+/// - Everyone is a reader of public collections
+pub fn get_access_condition_for_user(
+	user: &AuthUser,
+	minimum_role: AccessRole,
+) -> Condition {
+	let ruled_collection_ids = Query::select()
+		.column(collection_access_rule::Column::CollectionId)
+		.from(collection_access_rule::Entity)
+		.and_where(collection_access_rule::Column::UserId.eq(user.id.clone()))
+		.and_where(collection_access_rule::Column::Role.gte(minimum_role))
+		.to_owned();
+
+	Condition::any()
+		.add(Column::CreatorId.eq(user.id.clone()))
+		.add(
+			Condition::all()
+				.add(Column::Visibility.ne(EntityVisibility::Private))
+				.add(Column::Id.in_subquery(ruled_collection_ids)),
+		)
+		.add_option((minimum_role == AccessRole::Reader).then(|| {
+			Condition::all().add(Column::Visibility.eq(EntityVisibility::Public))
+		}))
+}
+
+impl Entity {
+	/// Find the collections the user is able to see
+	pub fn find_for_user(user: &AuthUser) -> Select<Entity> {
+		Entity::find_for_user_with_role(user, AccessRole::Reader)
+	}
+
+	/// Find the collections the user has at least the given role for, e.g. [AccessRole::Writer]
+	/// for the collections they are able to edit
+	pub fn find_for_user_with_role(
+		user: &AuthUser,
+		minimum_role: AccessRole,
+	) -> Select<Entity> {
+		Entity::find().filter(get_access_condition_for_user(user, minimum_role))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::common::*;
+	use pretty_assertions::assert_eq;
+
+	#[test]
+	fn test_find_for_user() {
+		let user = get_default_user();
+		assert_eq!(
+			select_no_cols_to_string(Entity::find_for_user(&user)),
+			r#"SELECT  FROM "collections" WHERE "collections"."creator_id" = '42' OR ("collections"."visibility" <> 'PRIVATE' AND "collections"."id" IN (SELECT "collection_id" FROM "collection_access_rules" WHERE "collection_access_rules"."user_id" = '42' AND "collection_access_rules"."role" >= 1)) OR "collections"."visibility" = 'PUBLIC'"#
+		);
+	}
+
+	#[test]
+	fn test_find_for_user_with_role() {
+		let user = get_default_user();
+		assert_eq!(
+			select_no_cols_to_string(Entity::find_for_user_with_role(
+				&user,
+				AccessRole::Writer
+			)),
+			r#"SELECT  FROM "collections" WHERE "collections"."creator_id" = '42' OR ("collections"."visibility" <> 'PRIVATE' AND "collections"."id" IN (SELECT "collection_id" FROM "collection_access_rules" WHERE "collection_access_rules"."user_id" = '42' AND "collection_access_rules"."role" >= 2))"#
+		);
+	}
+}
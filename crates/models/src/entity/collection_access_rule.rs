@@ -0,0 +1,53 @@
+use async_graphql::SimpleObject;
+use sea_orm::entity::prelude::*;
+
+use crate::shared::enums::AccessRole;
+
+/// Grants a user a role on a collection: readers can browse it, writers can edit it and
+/// co-creators can also manage who has access to it or delete it
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, SimpleObject)]
+#[graphql(name = "CollectionAccessRuleModel")]
+#[sea_orm(table_name = "collection_access_rules")]
+pub struct Model {
+	#[sea_orm(primary_key)]
+	pub id: i32,
+	pub role: AccessRole,
+	#[sea_orm(column_type = "Text")]
+	pub user_id: String,
+	#[sea_orm(column_type = "Text")]
+	pub collection_id: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+	#[sea_orm(
+		belongs_to = "super::collection::Entity",
+		from = "Column::CollectionId",
+		to = "super::collection::Column::Id",
+		on_update = "Cascade",
+		on_delete = "Cascade"
+	)]
+	Collection,
+	#[sea_orm(
+		belongs_to = "super::user::Entity",
+		from = "Column::UserId",
+		to = "super::user::Column::Id",
+		on_update = "Cascade",
+		on_delete = "Cascade"
+	)]
+	User,
+}
+
+impl Related<super::collection::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::Collection.def()
+	}
+}
+
+impl Related<super::user::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::User.def()
+	}
+}
+
+impl ActiveModelBehavior for ActiveModel {}
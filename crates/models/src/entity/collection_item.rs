@@ -0,0 +1,79 @@
+use async_graphql::SimpleObject;
+use sea_orm::{entity::prelude::*, JoinType, QueryOrder, QuerySelect};
+
+use super::series;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, SimpleObject)]
+#[graphql(name = "CollectionItemModel")]
+#[sea_orm(table_name = "collection_items")]
+pub struct Model {
+	#[sea_orm(primary_key)]
+	pub id: i32,
+	/// The position of the series in the collection, starting from 0
+	pub position: i32,
+	#[sea_orm(column_type = "Text")]
+	pub collection_id: String,
+	#[sea_orm(column_type = "Text")]
+	pub series_id: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+	#[sea_orm(
+		belongs_to = "super::collection::Entity",
+		from = "Column::CollectionId",
+		to = "super::collection::Column::Id",
+		on_update = "Cascade",
+		on_delete = "Cascade"
+	)]
+	Collection,
+	#[sea_orm(
+		belongs_to = "super::series::Entity",
+		from = "Column::SeriesId",
+		to = "super::series::Column::Id",
+		on_update = "Cascade",
+		on_delete = "Cascade"
+	)]
+	Series,
+}
+
+impl Related<super::collection::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::Collection.def()
+	}
+}
+
+impl Related<super::series::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::Series.def()
+	}
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// Narrow a select of series down to the series in the collection, in their order
+pub fn series_in_collection(
+	select: Select<series::Entity>,
+	collection_id: &str,
+) -> Select<series::Entity> {
+	select
+		.join_rev(JoinType::InnerJoin, Relation::Series.def())
+		.filter(Column::CollectionId.eq(collection_id))
+		.order_by_asc(Column::Position)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::common::*;
+	use pretty_assertions::assert_eq;
+
+	#[test]
+	fn test_series_in_collection() {
+		let select = series_in_collection(series::Entity::find(), "314");
+		assert_eq!(
+			select_no_cols_to_string(select),
+			r#"SELECT  FROM "series" INNER JOIN "collection_items" ON "collection_items"."series_id" = "series"."id" WHERE "collection_items"."collection_id" = '314' ORDER BY "collection_items"."position" ASC"#
+		);
+	}
+}
@@ -0,0 +1,48 @@
+use async_graphql::SimpleObject;
+use chrono::Utc;
+use sea_orm::{entity::prelude::*, prelude::async_trait::async_trait, ActiveValue};
+
+use crate::shared::enums::DuplicateReason;
+
+/// A group of media items which are likely duplicates of each other, as found by the
+/// duplicate detection job
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, SimpleObject)]
+#[graphql(name = "DuplicateMediaGroupModel")]
+#[sea_orm(table_name = "duplicate_media_groups")]
+pub struct Model {
+	#[sea_orm(primary_key, auto_increment = false, column_type = "Text")]
+	pub id: String,
+	pub reason: DuplicateReason,
+	/// The value the members of the group have in common, e.g. the hash or ISBN
+	#[sea_orm(column_type = "Text")]
+	pub key: String,
+	#[sea_orm(column_type = "custom(\"DATETIME\")")]
+	pub created_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+	#[sea_orm(has_many = "super::duplicate_media_group_member::Entity")]
+	DuplicateMediaGroupMember,
+}
+
+impl Related<super::duplicate_media_group_member::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::DuplicateMediaGroupMember.def()
+	}
+}
+
+#[async_trait]
+impl ActiveModelBehavior for ActiveModel {
+	async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+	where
+		C: ConnectionTrait,
+	{
+		if insert {
+			self.id = ActiveValue::Set(Uuid::new_v4().to_string());
+			self.created_at = ActiveValue::Set(DateTimeWithTimeZone::from(Utc::now()));
+		}
+
+		Ok(self)
+	}
+}
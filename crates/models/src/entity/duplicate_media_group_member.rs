@@ -0,0 +1,46 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "duplicate_media_group_members")]
+pub struct Model {
+	#[sea_orm(primary_key)]
+	pub id: i32,
+	#[sea_orm(column_type = "Text")]
+	pub group_id: String,
+	#[sea_orm(column_type = "Text")]
+	pub media_id: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+	#[sea_orm(
+		belongs_to = "super::duplicate_media_group::Entity",
+		from = "Column::GroupId",
+		to = "super::duplicate_media_group::Column::Id",
+		on_update = "Cascade",
+		on_delete = "Cascade"
+	)]
+	DuplicateMediaGroup,
+	#[sea_orm(
+		belongs_to = "super::media::Entity",
+		from = "Column::MediaId",
+		to = "super::media::Column::Id",
+		on_update = "Cascade",
+		on_delete = "Cascade"
+	)]
+	Media,
+}
+
+impl Related<super::duplicate_media_group::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::DuplicateMediaGroup.def()
+	}
+}
+
+impl Related<super::media::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::Media.def()
+	}
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,52 @@
+use chrono::Utc;
+use sea_orm::{entity::prelude::*, prelude::async_trait::async_trait, ActiveValue};
+
+use crate::shared::enums::DuplicateReason;
+
+/// A media item which was dismissed as a duplicate. It will not be flagged again for the
+/// same reason and key, e.g. the same ISBN
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "ignored_duplicate_media")]
+pub struct Model {
+	#[sea_orm(primary_key)]
+	pub id: i32,
+	pub reason: DuplicateReason,
+	#[sea_orm(column_type = "Text")]
+	pub key: String,
+	#[sea_orm(column_type = "Text")]
+	pub media_id: String,
+	#[sea_orm(column_type = "custom(\"DATETIME\")")]
+	pub ignored_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+	#[sea_orm(
+		belongs_to = "super::media::Entity",
+		from = "Column::MediaId",
+		to = "super::media::Column::Id",
+		on_update = "Cascade",
+		on_delete = "Cascade"
+	)]
+	Media,
+}
+
+impl Related<super::media::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::Media.def()
+	}
+}
+
+#[async_trait]
+impl ActiveModelBehavior for ActiveModel {
+	async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+	where
+		C: ConnectionTrait,
+	{
+		if insert {
+			self.ignored_at = ActiveValue::Set(DateTimeWithTimeZone::from(Utc::now()));
+		}
+
+		Ok(self)
+	}
+}
@@ -3,7 +3,7 @@ use async_trait::async_trait;
 use chrono::Utc;
 use sea_orm::{entity::prelude::*, ActiveValue::Set, FromQueryResult};
 
-use crate::shared::enums::JobStatus;
+use crate::shared::enums::{JobCategory, JobStatus};
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, SimpleObject)]
 #[graphql(name = "JobModel")]
@@ -23,6 +23,28 @@ pub struct Model {
 	#[sea_orm(column_type = "Blob", nullable)]
 	#[graphql(skip)]
 	pub output_data: Option<Vec<u8>>,
+	#[sea_orm(column_type = "Blob", nullable)]
+	#[graphql(skip)]
+	pub payload: Option<Vec<u8>>,
+	/// The serialized tasks which failed during the job, as a JSON array
+	#[sea_orm(column_type = "Blob", nullable)]
+	#[graphql(skip)]
+	pub failed_tasks: Option<Vec<u8>>,
+	/// The ID of the job whose failed tasks this job retries, if it is a retry
+	#[sea_orm(column_type = "Text", nullable)]
+	#[graphql(skip)]
+	pub retry_of: Option<String>,
+	/// The category of the job, which limits how many jobs like it may run at once. Jobs
+	/// created before categories were recorded have none, and aren't limited
+	#[sea_orm(column_type = "Text", nullable)]
+	#[graphql(skip)]
+	pub category: Option<JobCategory>,
+	/// Queued jobs with a higher priority are started first
+	#[sea_orm(default_value = 0)]
+	pub priority: i32,
+	#[sea_orm(default_value = 0)]
+	#[graphql(skip)]
+	pub queue_position: i64,
 	pub ms_elapsed: i64,
 	#[sea_orm(column_type = "custom(\"DATETIME\")")]
 	pub created_at: DateTimeWithTimeZone,
@@ -6,6 +6,62 @@ use serde::{Deserialize, Serialize};
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult)]
 pub struct MediaIds(pub Vec<String>);
 
+/// A shelf (tag) on the Kobo, backed by a reading list or smart list
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KoboTag {
+	pub id: String,
+	pub name: String,
+	pub media_ids: Vec<String>,
+}
+
+#[derive(
+	Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult,
+)]
+pub struct KoboTags(pub Vec<KoboTag>);
+
+/// The shelf changes which should be sent to the device as part of a sync session,
+/// relative to the shelves sent in the previous session.
+#[derive(
+	Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult,
+)]
+#[serde(default)]
+pub struct KoboTagChanges {
+	pub new: Vec<KoboTag>,
+	pub changed: Vec<KoboTag>,
+	pub deleted: Vec<String>,
+}
+
+impl KoboTagChanges {
+	/// Compute the changes required to bring a device from the `previous` shelves to the
+	/// `current` ones
+	pub fn between(previous: &KoboTags, current: &KoboTags) -> Self {
+		let mut changes = KoboTagChanges::default();
+
+		for tag in &current.0 {
+			match previous.0.iter().find(|t| t.id == tag.id) {
+				None => changes.new.push(tag.clone()),
+				Some(previous_tag) if previous_tag != tag => {
+					changes.changed.push(tag.clone())
+				},
+				_ => {},
+			}
+		}
+
+		changes.deleted = previous
+			.0
+			.iter()
+			.filter(|t| !current.0.iter().any(|ct| ct.id == t.id))
+			.map(|t| t.id.clone())
+			.collect();
+
+		changes
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.new.is_empty() && self.changed.is_empty() && self.deleted.is_empty()
+	}
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
 #[sea_orm(table_name = "kobo_sync_sessions")]
 pub struct Model {
@@ -29,6 +85,14 @@ pub struct Model {
 	/// the time that the sync session before this one began.
 	/// or None if there was no sync session before this one.
 	pub previous_sync_at: Option<DateTimeWithTimeZone>,
+
+	/// a JSON array of the shelves present on the device once this session completes.
+	#[sea_orm(column_type = "Json")]
+	pub tags: KoboTags,
+
+	/// a JSON object of the shelf changes that should be sent as part of this session.
+	#[sea_orm(column_type = "Json")]
+	pub tag_changes: KoboTagChanges,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -65,3 +129,45 @@ impl ActiveModelBehavior for ActiveModel {
 		Ok(self)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	fn tag(id: &str, name: &str, media_ids: &[&str]) -> KoboTag {
+		KoboTag {
+			id: id.to_string(),
+			name: name.to_string(),
+			media_ids: media_ids.iter().map(|id| String::from(*id)).collect(),
+		}
+	}
+
+	#[test]
+	fn test_tag_changes_between() {
+		let previous = KoboTags(vec![
+			tag("unchanged", "Unchanged", &["1"]),
+			tag("renamed", "Old name", &["1", "2"]),
+			tag("removed", "Removed", &[]),
+		]);
+		let current = KoboTags(vec![
+			tag("unchanged", "Unchanged", &["1"]),
+			tag("renamed", "New name", &["1", "2"]),
+			tag("added", "Added", &["3"]),
+		]);
+
+		let changes = KoboTagChanges::between(&previous, &current);
+		assert_eq!(vec![tag("added", "Added", &["3"])], changes.new);
+		assert_eq!(
+			vec![tag("renamed", "New name", &["1", "2"])],
+			changes.changed
+		);
+		assert_eq!(vec!["removed".to_string()], changes.deleted);
+	}
+
+	#[test]
+	fn test_tag_changes_empty() {
+		let tags = KoboTags(vec![tag("unchanged", "Unchanged", &["1"])]);
+		assert!(KoboTagChanges::between(&tags, &tags).is_empty());
+	}
+}
@@ -3,7 +3,7 @@ use sea_orm::{entity::prelude::*, FromQueryResult};
 
 use crate::shared::{
 	enums::{
-		LibraryPattern, LibraryType, LibraryViewMode, ReadingDirection,
+		LibraryPattern, LibraryType, LibraryViewMode, LibraryWatchMode, ReadingDirection,
 		ReadingImageScaleFit, ReadingMode,
 	},
 	ignore_rules::IgnoreRules,
@@ -28,6 +28,11 @@ pub struct Model {
 	pub generate_koreader_hashes: bool,
 	pub process_metadata: bool,
 	pub watch: bool,
+	#[sea_orm(column_type = "Text", default_value = "NATIVE")]
+	pub watch_mode: LibraryWatchMode,
+	/// The number of seconds between checks when [LibraryWatchMode::Poll] is used
+	#[sea_orm(default_value = "60")]
+	pub watch_poll_interval_seconds: i32,
 	#[sea_orm(column_type = "Text")]
 	pub library_pattern: LibraryPattern,
 	#[sea_orm(column_type = "Text")]
@@ -37,6 +42,12 @@ pub struct Model {
 	pub library_type: LibraryType,
 	#[sea_orm(default_value = "false")]
 	pub skip_book_overview: bool,
+	#[sea_orm(default_value = "false")]
+	pub kobo_convert_to_epub: bool,
+	/// When [Model::kobo_convert_to_epub] was last turned on
+	#[sea_orm(column_type = "custom(\"DATETIME\")", nullable)]
+	#[graphql(skip)]
+	pub kobo_convert_enabled_at: Option<DateTimeWithTimeZone>,
 	#[graphql(skip)]
 	#[sea_orm(column_type = "Json", nullable)]
 	pub thumbnail_config: Option<ImageProcessorOptions>,
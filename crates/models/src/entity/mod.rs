@@ -12,13 +12,18 @@ pub mod book_club_member;
 pub mod book_club_member_favorite_book;
 pub mod bookmark;
 pub mod collection;
+pub mod collection_access_rule;
+pub mod collection_item;
 pub mod custom_emoji;
+pub mod duplicate_media_group;
+pub mod duplicate_media_group_member;
 pub mod emailer;
 pub mod emailer_send_record;
 pub mod favorite_library;
 pub mod favorite_media;
 pub mod favorite_series;
 pub mod finished_reading_session;
+pub mod ignored_duplicate_media;
 pub mod job;
 pub mod kobo_sync_session;
 pub mod last_library_visit;
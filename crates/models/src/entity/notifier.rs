@@ -1,5 +1,5 @@
 use async_graphql::{Enum, Result, SimpleObject, Union};
-use sea_orm::entity::prelude::*;
+use sea_orm::{entity::prelude::*, FromJsonQueryResult, Iterable};
 use serde::{Deserialize, Serialize};
 use strum::{Display, EnumString};
 
@@ -15,6 +15,27 @@ pub struct Model {
 	#[graphql(skip)] // unmarshalled later on graphql side
 	#[sea_orm(column_type = "Blob")]
 	pub config: Vec<u8>,
+
+	#[graphql(skip)] // resolved with a default on graphql side
+	#[sea_orm(column_type = "Json", nullable)]
+	pub events: Option<NotifierEvents>,
+}
+
+impl Model {
+	/// The events the notifier is sent. A notifier which was never given a list of events
+	/// is sent all of them
+	pub fn events(&self) -> Vec<NotificationEventKind> {
+		self.events
+			.clone()
+			.map(|events| events.0)
+			.unwrap_or_else(|| NotificationEventKind::iter().collect())
+	}
+
+	pub fn is_subscribed_to(&self, kind: NotificationEventKind) -> bool {
+		self.events
+			.as_ref()
+			.is_none_or(|events| events.0.contains(&kind))
+	}
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -47,8 +68,36 @@ impl ActiveModelBehavior for ActiveModel {}
 pub enum NotifierType {
 	Discord,
 	Telegram,
+	Webhook,
 }
 
+/// The kinds of events a notifier can be subscribed to
+#[derive(
+	Eq, Copy, Hash, Debug, Clone, EnumIter, PartialEq, Serialize, Deserialize, Enum,
+)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum NotificationEventKind {
+	/// A manual or scheduled library scan finished
+	ScanCompleted,
+	/// A job failed
+	JobFailed,
+	/// New series were found while scanning a library
+	SeriesDiscovered,
+	/// A library could not be found on disk
+	LibraryMissing,
+	/// Fetched metadata matches are waiting to be reviewed
+	MetadataAwaitingReview,
+	/// A new user account was created
+	UserRegistered,
+	/// A message was sent in a book club discussion
+	BookClubMessage,
+}
+
+#[derive(
+	Default, Debug, Clone, Serialize, Deserialize, PartialEq, Eq, FromJsonQueryResult,
+)]
+pub struct NotifierEvents(pub Vec<NotificationEventKind>);
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
 pub struct DiscordConfig {
 	pub webhook_url: String,
@@ -60,11 +109,28 @@ pub struct TelegramConfig {
 	pub chat_id: String,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct WebhookHeader {
+	pub name: String,
+	/// The encrypted value of the header, since it typically holds a token
+	pub encrypted_value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct WebhookConfig {
+	pub url: String,
+	#[serde(default)]
+	pub headers: Vec<WebhookHeader>,
+	/// The encrypted secret used to sign the body of each request, if any
+	pub encrypted_secret: Option<String>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Union)]
 #[serde(untagged)]
 pub enum NotifierConfig {
 	Discord(DiscordConfig),
 	Telegram(TelegramConfig),
+	Webhook(WebhookConfig),
 }
 
 impl NotifierConfig {
@@ -93,6 +159,40 @@ mod tests {
 		assert_eq!(deserialized, expected);
 	}
 
+	#[test]
+	fn test_deserialize_webhook_config() {
+		let bytes = r#"{"url":"https://example.com/hook","headers":[{"name":"Authorization","encrypted_value":"encrypted"}],"encrypted_secret":null}"#.as_bytes().to_vec();
+		let deserialized = NotifierConfig::from_bytes(&bytes).unwrap();
+		let expected = NotifierConfig::Webhook(WebhookConfig {
+			url: "https://example.com/hook".to_string(),
+			headers: vec![WebhookHeader {
+				name: "Authorization".to_string(),
+				encrypted_value: "encrypted".to_string(),
+			}],
+			encrypted_secret: None,
+		});
+		assert_eq!(deserialized, expected);
+	}
+
+	#[test]
+	fn test_is_subscribed_to() {
+		let notifier = Model {
+			id: 1,
+			r#type: NotifierType::Webhook.to_string(),
+			config: vec![],
+			events: None,
+		};
+		assert!(notifier.is_subscribed_to(NotificationEventKind::JobFailed));
+		assert_eq!(notifier.events().len(), 7);
+
+		let notifier = Model {
+			events: Some(NotifierEvents(vec![NotificationEventKind::LibraryMissing])),
+			..notifier
+		};
+		assert!(!notifier.is_subscribed_to(NotificationEventKind::JobFailed));
+		assert!(notifier.is_subscribed_to(NotificationEventKind::LibraryMissing));
+	}
+
 	#[test]
 	fn test_serialize_notifier_config() {
 		let config = NotifierConfig::Telegram(TelegramConfig {
@@ -1,7 +1,8 @@
 use async_graphql::SimpleObject;
-use sea_orm::{prelude::*, Condition, FromQueryResult, QueryOrder};
+use sea_orm::{prelude::*, sea_query::Query, Condition, FromQueryResult, QueryOrder};
 
 use super::{reading_list_rule, user::AuthUser};
+use crate::shared::enums::AccessRole;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, SimpleObject)]
 #[graphql(name = "ReadingListModel")]
@@ -59,33 +60,29 @@ impl Related<super::user::Entity> for Entity {
 
 impl ActiveModelBehavior for ActiveModel {}
 
-fn get_reading_list_rbac_for_user(user_id: &str, minimum_role: i32) -> Condition {
-	// A common condition that asserts there is a RBAC entry for the user that has a role
-	// greater than or equal to the minimum role:
-	// 1 for reader, 2 for collaborator, 3 for creator
-	let base_rbac = Condition::all()
-		.add(reading_list_rule::Column::UserId.eq(user_id.to_owned()))
-		.add(reading_list_rule::Column::Role.gte(minimum_role));
+fn get_reading_list_rbac_for_user(user_id: &str, minimum_role: AccessRole) -> Condition {
+	// The lists which have a rule granting the user at least the minimum role
+	let ruled_reading_list_ids = Query::select()
+		.column(reading_list_rule::Column::ReadingListId)
+		.from(reading_list_rule::Entity)
+		.and_where(reading_list_rule::Column::UserId.eq(user_id.to_owned()))
+		.and_where(reading_list_rule::Column::Role.gte(minimum_role))
+		.to_owned();
 
 	Condition::any()
 		// creator always has access
 		.add(Column::CreatingUserId.eq(user_id.to_owned()))
-		// condition where visibility is PUBLIC:
+		// rules only apply to lists which are not private:
 		.add(
 			Condition::all()
-				.add(Column::Visibility.eq("PUBLIC".to_string()))
-				// This asserts the reader RBAC is present OR there is no RBAC present
-				.add(
-					Condition::any()
-						.add(base_rbac.clone())
-						.add(reading_list_rule::Column::UserId.is_null()),
-				),
+				.add(Column::Visibility.ne("PRIVATE".to_string()))
+				.add(Column::Id.in_subquery(ruled_reading_list_ids)),
 		)
-		// condition where visibility is SHARED:
-		.add(
-			Condition::all()
-				.add(Column::Visibility.eq("SHARED".to_string()))
-				.add(base_rbac),
+		// anyone can read a PUBLIC list:
+		.add_option(
+			(minimum_role == AccessRole::Reader).then(|| {
+				Condition::all().add(Column::Visibility.eq("PUBLIC".to_string()))
+			}),
 		)
 }
 
@@ -95,20 +92,20 @@ pub struct ReadingListIdCmpSelect {
 }
 
 impl Entity {
-	pub fn find_for_user(user: &AuthUser, minimum_role: i32) -> Select<Entity> {
+	/// Find the reading lists the user has at least the given role for, e.g.
+	/// [AccessRole::Writer] for the lists they are able to edit
+	pub fn find_for_user(user: &AuthUser, minimum_role: AccessRole) -> Select<Entity> {
 		Entity::find()
-			.left_join(reading_list_rule::Entity)
 			.filter(get_reading_list_rbac_for_user(&user.id, minimum_role))
 			.order_by_asc(Column::Id)
 	}
 
 	pub fn find_for_user_and_id(
 		user: &AuthUser,
-		minimum_role: i32,
+		minimum_role: AccessRole,
 		id: &str,
 	) -> Select<Entity> {
 		Entity::find()
-			.left_join(reading_list_rule::Entity)
 			.filter(Column::Id.eq(id))
 			.filter(get_reading_list_rbac_for_user(&user.id, minimum_role))
 	}
@@ -122,41 +119,52 @@ mod tests {
 
 	#[test]
 	fn test_reading_list_rbac() {
-		let condition = get_reading_list_rbac_for_user("42", 1);
+		let condition = get_reading_list_rbac_for_user("42", AccessRole::Reader);
+		assert_eq!(
+			condition_to_string(&condition),
+			r#"SELECT  WHERE "#.to_string()
+				+ r#""reading_lists"."creating_user_id" = '42' OR "#
+				+ r#"("reading_lists"."visibility" <> 'PRIVATE' AND "reading_lists"."id" IN (SELECT "reading_list_id" FROM "reading_list_rules" WHERE "reading_list_rules"."user_id" = '42' AND "reading_list_rules"."role" >= 1)) OR "#
+				+ r#""reading_lists"."visibility" = 'PUBLIC'"#
+		);
+	}
+
+	#[test]
+	fn test_reading_list_rbac_for_writer() {
+		let condition = get_reading_list_rbac_for_user("42", AccessRole::Writer);
 		assert_eq!(
 			condition_to_string(&condition),
 			r#"SELECT  WHERE "#.to_string()
 				+ r#""reading_lists"."creating_user_id" = '42' OR "#
-				+ r#"("reading_lists"."visibility" = 'PUBLIC' AND (("reading_list_rules"."user_id" = '42' AND "reading_list_rules"."role" >= 1) OR "reading_list_rules"."user_id" IS NULL)) OR "#
-				+ r#"("reading_lists"."visibility" = 'SHARED' AND ("reading_list_rules"."user_id" = '42' AND "reading_list_rules"."role" >= 1))"#
+				+ r#"("reading_lists"."visibility" <> 'PRIVATE' AND "reading_lists"."id" IN (SELECT "reading_list_id" FROM "reading_list_rules" WHERE "reading_list_rules"."user_id" = '42' AND "reading_list_rules"."role" >= 2))"#
 		);
 	}
 
 	#[test]
 	fn test_find_for_user() {
 		let user = get_default_user();
-		let stmt = Entity::find_for_user(&user, 1);
+		let stmt = Entity::find_for_user(&user, AccessRole::Reader);
 		assert_eq!(
 			select_no_cols_to_string(stmt),
-			r#"SELECT  FROM "reading_lists" LEFT JOIN "reading_list_rules" ON "reading_lists"."id" = "reading_list_rules"."reading_list_id" WHERE "#.to_string()
+			r#"SELECT  FROM "reading_lists" WHERE "#.to_string()
 				+ r#""reading_lists"."creating_user_id" = '42' OR "#
-				+ r#"("reading_lists"."visibility" = 'PUBLIC' AND (("reading_list_rules"."user_id" = '42' AND "reading_list_rules"."role" >= 1) OR "reading_list_rules"."user_id" IS NULL)) OR "#
-				+ r#"("reading_lists"."visibility" = 'SHARED' AND ("reading_list_rules"."user_id" = '42' AND "reading_list_rules"."role" >= 1))"#
-                + r#" ORDER BY "reading_lists"."id" ASC"#
+				+ r#"("reading_lists"."visibility" <> 'PRIVATE' AND "reading_lists"."id" IN (SELECT "reading_list_id" FROM "reading_list_rules" WHERE "reading_list_rules"."user_id" = '42' AND "reading_list_rules"."role" >= 1)) OR "#
+				+ r#""reading_lists"."visibility" = 'PUBLIC'"#
+				+ r#" ORDER BY "reading_lists"."id" ASC"#
 		);
 	}
 
 	#[test]
 	fn test_find_for_user_and_id() {
 		let user = get_default_user();
-		let stmt = Entity::find_for_user_and_id(&user, 1, "314");
+		let stmt = Entity::find_for_user_and_id(&user, AccessRole::Reader, "314");
 		assert_eq!(
 			select_no_cols_to_string(stmt),
-			r#"SELECT  FROM "reading_lists" LEFT JOIN "reading_list_rules" ON "reading_lists"."id" = "reading_list_rules"."reading_list_id" WHERE "#.to_string()
+			r#"SELECT  FROM "reading_lists" WHERE "#.to_string()
 				+ r#""reading_lists"."id" = '314' AND "#
 				+ r#"("reading_lists"."creating_user_id" = '42' OR "#
-				+ r#"("reading_lists"."visibility" = 'PUBLIC' AND (("reading_list_rules"."user_id" = '42' AND "reading_list_rules"."role" >= 1) OR "reading_list_rules"."user_id" IS NULL)) OR "#
-				+ r#"("reading_lists"."visibility" = 'SHARED' AND ("reading_list_rules"."user_id" = '42' AND "reading_list_rules"."role" >= 1)))"#
+				+ r#"("reading_lists"."visibility" <> 'PRIVATE' AND "reading_lists"."id" IN (SELECT "reading_list_id" FROM "reading_list_rules" WHERE "reading_list_rules"."user_id" = '42' AND "reading_list_rules"."role" >= 1)) OR "#
+				+ r#""reading_lists"."visibility" = 'PUBLIC')"#
 		);
 	}
 }
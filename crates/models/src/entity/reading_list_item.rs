@@ -21,7 +21,7 @@ pub enum Relation {
 		from = "Column::MediaId",
 		to = "super::media::Column::Id",
 		on_update = "Cascade",
-		on_delete = "Restrict"
+		on_delete = "Cascade"
 	)]
 	Media,
 	#[sea_orm(
@@ -29,7 +29,7 @@ pub enum Relation {
 		from = "Column::ReadingListId",
 		to = "super::reading_list::Column::Id",
 		on_update = "Cascade",
-		on_delete = "Restrict"
+		on_delete = "Cascade"
 	)]
 	ReadingList,
 }
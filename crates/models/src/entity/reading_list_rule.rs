@@ -1,11 +1,17 @@
+use async_graphql::SimpleObject;
 use sea_orm::entity::prelude::*;
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+use crate::shared::enums::AccessRole;
+
+/// Grants a user a role on a reading list: readers can view it, writers can edit it and
+/// co-creators can also manage who has access to it or delete it
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, SimpleObject)]
+#[graphql(name = "ReadingListRuleModel")]
 #[sea_orm(table_name = "reading_list_rules")]
 pub struct Model {
 	#[sea_orm(primary_key)]
 	pub id: i32,
-	pub role: i32,
+	pub role: AccessRole,
 	#[sea_orm(column_type = "Text")]
 	pub user_id: String,
 	#[sea_orm(column_type = "Text")]
@@ -19,9 +25,17 @@ pub enum Relation {
 		from = "Column::ReadingListId",
 		to = "super::reading_list::Column::Id",
 		on_update = "Cascade",
-		on_delete = "Restrict"
+		on_delete = "Cascade"
 	)]
 	ReadingList,
+	#[sea_orm(
+		belongs_to = "super::user::Entity",
+		from = "Column::UserId",
+		to = "super::user::Column::Id",
+		on_update = "Cascade",
+		on_delete = "Cascade"
+	)]
+	User,
 }
 
 impl Related<super::reading_list::Entity> for Entity {
@@ -30,4 +44,10 @@ impl Related<super::reading_list::Entity> for Entity {
 	}
 }
 
+impl Related<super::user::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::User.def()
+	}
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -1,21 +1,37 @@
-use sea_orm::{entity::prelude::*, prelude::async_trait::async_trait, ActiveValue};
+use async_graphql::SimpleObject;
+use chrono::Utc;
+use sea_orm::{
+	entity::prelude::*,
+	prelude::async_trait::async_trait,
+	sea_query::{Func, Query, SelectStatement, SimpleExpr},
+	ActiveValue, Condition, FromQueryResult, QuerySelect,
+};
 
-// TODO(reviews): Add timestamp
-// TODO(reviews): Rename content to review_text or something
+use crate::shared::enums::EntityVisibility;
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+use super::{book_club_member, media, user::AuthUser};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, SimpleObject)]
+#[graphql(name = "ReviewModel")]
 #[sea_orm(table_name = "reviews")]
 pub struct Model {
 	#[sea_orm(primary_key, auto_increment = false, column_type = "Text")]
 	pub id: String,
+	/// The rating given to the book, from 1 to 5
 	pub rating: i32,
 	#[sea_orm(column_type = "Text", nullable)]
-	pub content: Option<String>,
-	pub is_private: bool,
+	pub review_text: Option<String>,
+	/// Who is able to see the review. Shared reviews are visible to the members of the
+	/// book clubs the reviewer is in
+	pub visibility: EntityVisibility,
 	#[sea_orm(column_type = "Text")]
 	pub media_id: String,
 	#[sea_orm(column_type = "Text")]
 	pub user_id: String,
+	#[sea_orm(column_type = "custom(\"DATETIME\")")]
+	pub created_at: DateTimeWithTimeZone,
+	#[sea_orm(column_type = "custom(\"DATETIME\")", nullable)]
+	pub updated_at: Option<DateTimeWithTimeZone>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -58,8 +74,113 @@ impl ActiveModelBehavior for ActiveModel {
 	{
 		if insert {
 			self.id = ActiveValue::Set(Uuid::new_v4().to_string());
+			self.created_at = ActiveValue::Set(DateTimeWithTimeZone::from(Utc::now()));
+		} else {
+			self.updated_at =
+				ActiveValue::Set(Some(DateTimeWithTimeZone::from(Utc::now())));
 		}
 
 		Ok(self)
 	}
 }
+
+/// A condition for the reviews a user is able to see: their own, any public review and
+/// shared reviews written by someone they are in a book club with
+pub fn get_access_condition_for_user(user: &AuthUser) -> Condition {
+	let user_club_ids = Query::select()
+		.column(book_club_member::Column::BookClubId)
+		.from(book_club_member::Entity)
+		.and_where(book_club_member::Column::UserId.eq(user.id.clone()))
+		.to_owned();
+	let club_member_ids = Query::select()
+		.column(book_club_member::Column::UserId)
+		.from(book_club_member::Entity)
+		.and_where(book_club_member::Column::BookClubId.in_subquery(user_club_ids))
+		.to_owned();
+
+	Condition::any()
+		.add(Column::UserId.eq(user.id.clone()))
+		.add(Column::Visibility.eq(EntityVisibility::Public))
+		.add(
+			Condition::all()
+				.add(Column::Visibility.eq(EntityVisibility::Shared))
+				.add(Column::UserId.in_subquery(club_member_ids)),
+		)
+}
+
+impl Entity {
+	pub fn find_for_user(user: &AuthUser) -> Select<Entity> {
+		Entity::find().filter(get_access_condition_for_user(user))
+	}
+
+	/// Find the public reviews, which are the only reviews counted towards the average
+	/// rating of a book or series
+	pub fn find_public() -> Select<Entity> {
+		Entity::find().filter(Column::Visibility.eq(EntityVisibility::Public))
+	}
+}
+
+/// The aggregate of the public ratings of a book or series
+#[derive(Debug, Default, FromQueryResult, SimpleObject)]
+pub struct RatingSummary {
+	/// The average rating, or null if there are no public reviews
+	pub average_rating: Option<f64>,
+	pub rating_count: i64,
+}
+
+/// A select of the [RatingSummary] over the public reviews. Callers are expected to
+/// narrow it down to the reviews of a book or series
+pub fn rating_summary() -> Select<Entity> {
+	Entity::find_public()
+		.select_only()
+		.column_as(
+			SimpleExpr::from(Func::avg(Expr::col((Entity, Column::Rating)))),
+			"average_rating",
+		)
+		.column_as(
+			SimpleExpr::from(Func::count(Expr::col((Entity, Column::Id)))),
+			"rating_count",
+		)
+}
+
+/// A subquery for the average public rating of the media in the outer query, which can be
+/// used to order media by their rating
+pub fn average_rating_subquery() -> SelectStatement {
+	Query::select()
+		.expr(Func::avg(Expr::col((Entity, Column::Rating))))
+		.from(Entity)
+		.and_where(Column::Visibility.eq(EntityVisibility::Public))
+		.and_where(
+			Expr::col((Entity, Column::MediaId))
+				.equals((media::Entity, media::Column::Id)),
+		)
+		.to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::common::*;
+	use pretty_assertions::assert_eq;
+	use sea_orm::sea_query::SqliteQueryBuilder;
+
+	#[test]
+	fn test_find_for_user() {
+		let user = get_default_user();
+		let select = Entity::find_for_user(&user);
+		let stmt_str = select_no_cols_to_string(select);
+		assert_eq!(
+			stmt_str,
+			r#"SELECT  FROM "reviews" WHERE "reviews"."user_id" = '42' OR "reviews"."visibility" = 'PUBLIC' OR ("reviews"."visibility" = 'SHARED' AND "reviews"."user_id" IN (SELECT "user_id" FROM "book_club_members" WHERE "book_club_members"."book_club_id" IN (SELECT "book_club_id" FROM "book_club_members" WHERE "book_club_members"."user_id" = '42')))"#
+		);
+	}
+
+	#[test]
+	fn test_average_rating_subquery() {
+		let stmt_str = average_rating_subquery().to_string(SqliteQueryBuilder);
+		assert_eq!(
+			stmt_str,
+			r#"SELECT AVG("reviews"."rating") FROM "reviews" WHERE "reviews"."visibility" = 'PUBLIC' AND "reviews"."media_id" = "media"."id""#
+		);
+	}
+}
@@ -1,5 +1,5 @@
 use sea_orm::entity::prelude::*;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 use crate::shared::enums::{MetadataFetchStatus, ScheduledJobKind};
 
@@ -19,6 +19,10 @@ pub struct Model {
 	pub enabled: bool,
 	pub created_at: DateTimeUtc,
 	pub last_run_at: Option<DateTimeUtc>,
+	/// Why the job last failed to start or run, if it did. This is cleared after the next
+	/// successful run
+	#[sea_orm(column_type = "Text", nullable)]
+	pub last_error: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -31,6 +35,8 @@ impl ActiveModelBehavior for ActiveModel {}
 #[serde(rename_all = "camelCase")]
 pub struct LibraryScanConfig {
 	/// The library IDs to scan. An empty vec is treated as "all libraries"
+	// Configs were previously persisted with snake_case keys
+	#[serde(alias = "library_ids")]
 	pub library_ids: Vec<String>,
 }
 
@@ -42,16 +48,161 @@ pub struct MetadataRetryConfig {
 	pub statuses: Vec<MetadataFetchStatus>,
 }
 
+/// Configuration for a thumbnail regeneration scheduled job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThumbnailRegenerationConfig {
+	/// The library IDs to generate thumbnails for. An empty vec is treated as "all libraries"
+	pub library_ids: Vec<String>,
+	/// Whether to regenerate thumbnails which already exist, rather than only generating
+	/// missing ones
+	#[serde(default)]
+	pub force_regenerate: bool,
+}
+
+/// Configuration for a media analysis scheduled job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaAnalysisConfig {
+	/// The library IDs to analyze. An empty vec is treated as "all libraries"
+	pub library_ids: Vec<String>,
+	/// Whether to analyze books which were already analyzed
+	#[serde(default)]
+	pub force_reanalysis: bool,
+}
+
+/// Configuration for a library cleanup scheduled job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryCleanupConfig {
+	/// The library IDs to clean. An empty vec is treated as "all libraries"
+	pub library_ids: Vec<String>,
+	/// Whether to delete books and series which are missing from disk
+	#[serde(default)]
+	pub remove_missing: bool,
+	/// Whether to run duplicate detection across all libraries
+	#[serde(default)]
+	pub detect_duplicates: bool,
+}
+
+/// Configuration for a database backup scheduled job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseBackupConfig {
+	/// The number of backups to keep. Older backups are deleted after a new one is made
+	pub retain_count: u32,
+}
+
+/// Configuration for a history pruning scheduled job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryPruningConfig {
+	/// Delete logs older than this many days. Logs aren't pruned if this is not set
+	pub log_retention_days: Option<u32>,
+	/// Delete finished jobs (and their logs) older than this many days. Job history isn't
+	/// pruned if this is not set
+	pub job_retention_days: Option<u32>,
+}
+
+/// Configuration for an OPDS cache warmup scheduled job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpdsCacheWarmupConfig {
+	/// The library IDs to warm. An empty vec is treated as "all libraries"
+	pub library_ids: Vec<String>,
+	/// The number of pages to pre-render, from the start of each PDF
+	pub page_count: u32,
+	/// Only warm books added within this many days. All books are warmed if this is not set
+	pub added_within_days: Option<u32>,
+}
+
 impl Model {
-	pub fn library_scan_config(&self) -> Option<LibraryScanConfig> {
+	fn typed_config<T: DeserializeOwned>(&self) -> Option<T> {
 		self.config
 			.as_ref()
 			.and_then(|v| serde_json::from_value(v.clone()).ok())
 	}
 
+	pub fn library_scan_config(&self) -> Option<LibraryScanConfig> {
+		self.typed_config()
+	}
+
 	pub fn metadata_retry_config(&self) -> Option<MetadataRetryConfig> {
-		self.config
-			.as_ref()
-			.and_then(|v| serde_json::from_value(v.clone()).ok())
+		self.typed_config()
+	}
+
+	pub fn thumbnail_regeneration_config(&self) -> Option<ThumbnailRegenerationConfig> {
+		self.typed_config()
+	}
+
+	pub fn media_analysis_config(&self) -> Option<MediaAnalysisConfig> {
+		self.typed_config()
+	}
+
+	pub fn library_cleanup_config(&self) -> Option<LibraryCleanupConfig> {
+		self.typed_config()
+	}
+
+	pub fn database_backup_config(&self) -> Option<DatabaseBackupConfig> {
+		self.typed_config()
+	}
+
+	pub fn history_pruning_config(&self) -> Option<HistoryPruningConfig> {
+		self.typed_config()
+	}
+
+	pub fn opds_cache_warmup_config(&self) -> Option<OpdsCacheWarmupConfig> {
+		self.typed_config()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn model_with_config(config: serde_json::Value) -> Model {
+		Model {
+			id: 1,
+			name: "test".to_string(),
+			kind: ScheduledJobKind::LibraryScan,
+			schedule: "0 0 0 * * *".to_string(),
+			config: Some(config),
+			enabled: true,
+			created_at: Default::default(),
+			last_run_at: None,
+			last_error: None,
+		}
+	}
+
+	#[test]
+	fn test_library_scan_config_keys() {
+		let legacy = model_with_config(serde_json::json!({
+			"config_type": "LibraryScan",
+			"library_ids": ["1"],
+		}));
+		assert_eq!(legacy.library_scan_config().unwrap().library_ids, vec!["1"]);
+
+		let current = model_with_config(serde_json::json!({
+			"config_type": "LibraryScan",
+			"libraryIds": ["2"],
+		}));
+		assert_eq!(
+			current.library_scan_config().unwrap().library_ids,
+			vec!["2"]
+		);
+	}
+
+	#[test]
+	fn test_config_defaults() {
+		let model = model_with_config(serde_json::json!({ "libraryIds": [] }));
+		let config = model.thumbnail_regeneration_config().unwrap();
+		assert!(config.library_ids.is_empty());
+		assert!(!config.force_regenerate);
+
+		let model = model_with_config(serde_json::json!({}));
+		let config = model.history_pruning_config().unwrap();
+		assert!(config.log_retention_days.is_none());
+		assert!(config.job_retention_days.is_none());
+		assert!(model.database_backup_config().is_none());
 	}
 }
@@ -92,7 +92,7 @@ impl Entity {
 	}
 }
 
-#[derive(FromQueryResult)]
+#[derive(Clone, Debug, FromQueryResult)]
 pub struct SeriesIdentSelect {
 	pub id: String,
 	pub path: String,
@@ -98,6 +98,48 @@ pub enum EntityVisibility {
 	Private,
 }
 
+/// The reason a group of media items were flagged as possible duplicates of each other
+#[derive(
+	Eq,
+	Copy,
+	Hash,
+	Debug,
+	Default,
+	Clone,
+	EnumIter,
+	PartialEq,
+	Serialize,
+	Deserialize,
+	DeriveActiveEnum,
+	Enum,
+)]
+#[sea_orm(
+	rs_type = "String",
+	rename_all = "SCREAMING_SNAKE_CASE",
+	db_type = "String(StringLen::None)"
+)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DuplicateReason {
+	/// The files have the same content hash
+	#[default]
+	Hash,
+	/// The books share an identifier in their metadata, e.g. an ISBN or ComicVine ID
+	Identifier,
+	/// The books have the same series, number and volume in their metadata, e.g. a CBR
+	/// and CBZ of the same issue
+	SeriesNumber,
+}
+
+/// What to do with the other members of a duplicate group once a survivor is chosen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DuplicateResolution {
+	/// Delete the duplicates, after moving everything attached to them onto the survivor
+	Delete,
+	/// Keep the duplicates, but never flag them as duplicates of the survivor again
+	Ignore,
+}
+
 /// The different statuses a file reference can have
 #[derive(
 	Eq,
@@ -257,6 +299,48 @@ impl JobStatus {
 	}
 }
 
+/// A grouping of jobs which are scheduled alike. Interactive scans are started before
+/// metadata fetches, which are started before thumbnail generation and analysis
+#[derive(
+	Eq,
+	Copy,
+	Hash,
+	Debug,
+	Clone,
+	EnumIter,
+	PartialEq,
+	Serialize,
+	Deserialize,
+	DeriveActiveEnum,
+	EnumString,
+	Display,
+)]
+#[sea_orm(
+	rs_type = "String",
+	rename_all = "SCREAMING_SNAKE_CASE",
+	db_type = "String(StringLen::None)"
+)]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum JobCategory {
+	Scan,
+	Metadata,
+	Thumbnail,
+	Analysis,
+}
+
+impl JobCategory {
+	/// The default priority of jobs in this category when they are enqueued
+	pub fn priority(&self) -> i32 {
+		match self {
+			JobCategory::Scan => 30,
+			JobCategory::Metadata => 20,
+			JobCategory::Thumbnail => 10,
+			JobCategory::Analysis => 0,
+		}
+	}
+}
+
 /// The different patterns a library may be organized by
 #[derive(
 	Eq,
@@ -310,6 +394,36 @@ pub enum LibraryViewMode {
 	Books,
 }
 
+/// How a library is watched for changes on disk
+#[derive(
+	Eq,
+	Copy,
+	Hash,
+	Debug,
+	Default,
+	Clone,
+	EnumIter,
+	PartialEq,
+	Serialize,
+	Deserialize,
+	DeriveActiveEnum,
+	Enum,
+)]
+#[sea_orm(
+	rs_type = "String",
+	rename_all = "SCREAMING_SNAKE_CASE",
+	db_type = "String(StringLen::None)"
+)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum LibraryWatchMode {
+	/// Use the filesystem events of the host OS (e.g. inotify)
+	#[default]
+	Native,
+	/// Periodically check the library for changes. This is useful for network mounts
+	/// (e.g. NFS or SMB), which generally don't emit filesystem events
+	Poll,
+}
+
 /// The type of content a library contains
 #[derive(
 	Eq,
@@ -435,6 +549,14 @@ pub enum MetadataFetchStatus {
 pub enum MetadataProvider {
 	/// Hardcover (https://hardcover.app)
 	Hardcover,
+	/// AniList (https://anilist.co)
+	Anilist,
+	/// Metron (https://metron.cloud)
+	Metron,
+	/// ComicVine (https://comicvine.gamespot.com)
+	ComicVine,
+	/// Sidecar files stored alongside series and books, e.g. `metadata.opf`
+	Local,
 }
 
 impl MetadataProvider {
@@ -447,8 +569,29 @@ impl MetadataProvider {
 				LibraryType::Manga,
 				LibraryType::LightNovel,
 			],
+			Self::Anilist => &[
+				LibraryType::Manga,
+				LibraryType::Manhwa,
+				LibraryType::LightNovel,
+			],
+			Self::Metron | Self::ComicVine => &[LibraryType::Comic],
+			Self::Local => &[
+				LibraryType::Comic,
+				LibraryType::Manga,
+				LibraryType::Book,
+				LibraryType::LightNovel,
+				LibraryType::Manhwa,
+				LibraryType::WebNovel,
+				LibraryType::Webtoon,
+			],
 		}
 	}
+
+	/// Whether the provider needs an API token to be configured before it can be used.
+	/// AniList's API may be used anonymously, so a token is optional for it
+	pub fn requires_api_token(&self) -> bool {
+		matches!(self, Self::Hardcover | Self::Metron | Self::ComicVine)
+	}
 }
 
 impl LibraryType {
@@ -797,4 +940,17 @@ pub enum ScheduledJobKind {
 	LibraryScan,
 	/// Retry fetching metadata for records that were rate-limited or failed
 	MetadataRetry,
+	/// Generate (or regenerate) thumbnails for the books in one or more libraries
+	ThumbnailRegeneration,
+	/// Analyze the books in one or more libraries
+	MediaAnalysis,
+	/// Remove missing books and series from one or more libraries and detect duplicates
+	LibraryCleanup,
+	/// Back up the database to the backups directory
+	DatabaseBackup,
+	/// Delete old logs and job history
+	HistoryPruning,
+	/// Pre-render the pages of PDFs so they are served from the page cache, e.g. to OPDS
+	/// page streaming clients
+	OpdsCacheWarmup,
 }
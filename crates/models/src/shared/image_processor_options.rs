@@ -83,15 +83,15 @@ pub enum ImageResizeMethod {
 	FitWithin(FitWithinResize),
 }
 
-// TODO(images): Support JpegXl and Avif
-
-/// Supported image formats for processing images throughout Stump
+/// Supported image formats for processing images throughout Stump. Note that JPEG XL images
+/// can be read, but not generated, so there is no variant for it
 #[derive(Default, Copy, Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Enum)]
 pub enum SupportedImageFormat {
 	Webp,
 	#[default]
 	Jpeg,
 	Png,
+	Avif,
 }
 
 impl SupportedImageFormat {
@@ -101,6 +101,7 @@ impl SupportedImageFormat {
 			SupportedImageFormat::Webp => "webp",
 			SupportedImageFormat::Jpeg => "jpeg",
 			SupportedImageFormat::Png => "png",
+			SupportedImageFormat::Avif => "avif",
 		}
 	}
 }
@@ -129,7 +130,7 @@ pub struct ImageProcessorOptions {
 	pub format: SupportedImageFormat,
 	/// The quality to use when generating an image. This is a number between 1 and 100,
 	/// where 100 is the highest quality. Omitting this value will use the default quality
-	/// of 100.
+	/// of 100, or 80 for AVIF.
 	pub quality: Option<u16>,
 	/// The page to use when generating an image. This is not applicable to all media formats.
 	pub page: Option<i32>,
@@ -1,7 +1,10 @@
 use models::entity::{
-	finished_reading_session, kobo_sync_session, library, library_exclusion, media,
-	media_metadata, media_tag, reading_session, refresh_token, registered_reading_device,
-	series, series_metadata, server_config, tag, user, user_preferences,
+	bookmark, duplicate_media_group, duplicate_media_group_member, favorite_media,
+	finished_reading_session, ignored_duplicate_media, job, kobo_sync_session, library,
+	library_config, library_exclusion, log, media, media_annotation, media_metadata,
+	media_tag, reading_list, reading_list_item, reading_list_rule, reading_session,
+	refresh_token, registered_reading_device, series, series_metadata, server_config,
+	smart_list, smart_list_access_rule, tag, user, user_preferences,
 };
 use sea_orm::{ConnectionTrait, Database, DbBackend, DbConn, DbErr, Schema};
 pub async fn test_database() -> DbConn {
@@ -29,6 +32,7 @@ pub async fn create_database_tables(db: &DbConn) -> Result<(), DbErr> {
 		schema.create_table_from_entity(user::Entity),
 		schema.create_table_from_entity(user_preferences::Entity),
 		schema.create_table_from_entity(library::Entity),
+		schema.create_table_from_entity(library_config::Entity),
 		schema.create_table_from_entity(reading_session::Entity),
 		schema.create_table_from_entity(finished_reading_session::Entity),
 		schema.create_table_from_entity(registered_reading_device::Entity),
@@ -36,6 +40,19 @@ pub async fn create_database_tables(db: &DbConn) -> Result<(), DbErr> {
 		schema.create_table_from_entity(media_tag::Entity),
 		schema.create_table_from_entity(server_config::Entity),
 		schema.create_table_from_entity(refresh_token::Entity),
+		schema.create_table_from_entity(reading_list::Entity),
+		schema.create_table_from_entity(reading_list_item::Entity),
+		schema.create_table_from_entity(reading_list_rule::Entity),
+		schema.create_table_from_entity(smart_list::Entity),
+		schema.create_table_from_entity(smart_list_access_rule::Entity),
+		schema.create_table_from_entity(job::Entity),
+		schema.create_table_from_entity(log::Entity),
+		schema.create_table_from_entity(bookmark::Entity),
+		schema.create_table_from_entity(favorite_media::Entity),
+		schema.create_table_from_entity(media_annotation::Entity),
+		schema.create_table_from_entity(duplicate_media_group::Entity),
+		schema.create_table_from_entity(duplicate_media_group_member::Entity),
+		schema.create_table_from_entity(ignored_duplicate_media::Entity),
 	];
 
 	for stmt in tables {
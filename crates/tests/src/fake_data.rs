@@ -1,6 +1,12 @@
 use chrono::Utc;
-use models::entity::{finished_reading_session, media, reading_session, series, user};
-use models::shared::enums::FileStatus;
+use models::entity::{
+	finished_reading_session, library, library_config, media, reading_list,
+	reading_list_item, reading_session, series, user,
+};
+use models::shared::enums::{
+	FileStatus, LibraryPattern, LibraryType, LibraryViewMode, ReadingDirection,
+	ReadingImageScaleFit, ReadingMode,
+};
 use rand::distr::SampleString;
 use rust_decimal::prelude::FromPrimitive;
 use sea_orm::{prelude::DateTimeWithTimeZone, ActiveModelTrait, ActiveValue, DbConn};
@@ -98,9 +104,17 @@ impl User {
 pub struct Series {
 	name: Option<String>,
 	path: Option<String>,
+	library_id: Option<String>,
 }
 
 impl Series {
+	pub fn in_library<T: ToString>(library_id: T) -> Self {
+		Series {
+			library_id: Some(library_id.to_string()),
+			..Default::default()
+		}
+	}
+
 	pub async fn insert(&self, db: &DbConn) -> series::Model {
 		let name = self.name.clone().unwrap_or_else(|| {
 			rand::distr::Alphabetic.sample_string(&mut rand::rng(), 16)
@@ -111,6 +125,7 @@ impl Series {
 		let model = series::ActiveModel {
 			name: sea_orm::Set(name),
 			path: sea_orm::Set(path),
+			library_id: sea_orm::Set(self.library_id.clone()),
 			..Default::default()
 		};
 
@@ -118,6 +133,54 @@ impl Series {
 	}
 }
 
+#[derive(Default)]
+pub struct Library {
+	pub name: Option<String>,
+	pub kobo_convert_to_epub: bool,
+	pub kobo_convert_enabled_at: Option<DateTimeWithTimeZone>,
+}
+
+impl Library {
+	pub async fn insert(&self, db: &DbConn) -> library::Model {
+		let name = self.name.clone().unwrap_or_else(|| {
+			rand::distr::Alphabetic.sample_string(&mut rand::rng(), 16)
+		});
+
+		let config = library_config::ActiveModel {
+			convert_rar_to_zip: sea_orm::Set(false),
+			hard_delete_conversions: sea_orm::Set(false),
+			default_reading_dir: sea_orm::Set(ReadingDirection::Ltr),
+			default_reading_mode: sea_orm::Set(ReadingMode::Paged),
+			default_reading_image_scale_fit: sea_orm::Set(ReadingImageScaleFit::Height),
+			generate_file_hashes: sea_orm::Set(false),
+			generate_koreader_hashes: sea_orm::Set(false),
+			process_metadata: sea_orm::Set(true),
+			watch: sea_orm::Set(false),
+			library_pattern: sea_orm::Set(LibraryPattern::SeriesBased),
+			default_library_view_mode: sea_orm::Set(LibraryViewMode::Series),
+			hide_series_view: sea_orm::Set(false),
+			library_type: sea_orm::Set(LibraryType::Mixed),
+			skip_book_overview: sea_orm::Set(false),
+			kobo_convert_to_epub: sea_orm::Set(self.kobo_convert_to_epub),
+			kobo_convert_enabled_at: sea_orm::Set(self.kobo_convert_enabled_at),
+			process_thumbnail_colors_even_without_config: sea_orm::Set(false),
+			..Default::default()
+		}
+		.insert(db)
+		.await
+		.expect("could not insert library config");
+
+		let model = library::ActiveModel {
+			name: sea_orm::Set(name.clone()),
+			path: sea_orm::Set(format!("/tmp/{name}")),
+			config_id: sea_orm::Set(config.id),
+			..Default::default()
+		};
+
+		model.insert(db).await.expect("could not insert library")
+	}
+}
+
 #[derive(Default)]
 pub struct ReadingSession {
 	pub media_id: String,
@@ -165,3 +228,53 @@ impl FinishedReadingSession {
 			.expect("could not insert finished reading session")
 	}
 }
+
+#[derive(Default)]
+pub struct ReadingList {
+	pub id: Option<String>,
+	pub name: Option<String>,
+	pub creating_user_id: String,
+	pub media_ids: Vec<String>,
+}
+
+impl ReadingList {
+	pub async fn insert(&self, db: &DbConn) -> reading_list::Model {
+		let id = self
+			.id
+			.clone()
+			.unwrap_or_else(|| Uuid::new_v4().to_string());
+
+		let model = reading_list::ActiveModel {
+			id: sea_orm::Set(id.clone()),
+			name: sea_orm::Set(
+				self.name
+					.clone()
+					.unwrap_or_else(|| format!("Test Reading List {id}")),
+			),
+			updated_at: sea_orm::Set(Utc::now().into()),
+			visibility: sea_orm::Set("PRIVATE".to_string()),
+			ordering: sea_orm::Set("MANUAL".to_string()),
+			creating_user_id: sea_orm::Set(self.creating_user_id.clone()),
+			..Default::default()
+		};
+
+		let reading_list = model
+			.insert(db)
+			.await
+			.expect("could not insert reading list");
+
+		for (display_order, media_id) in self.media_ids.iter().enumerate() {
+			reading_list_item::ActiveModel {
+				display_order: sea_orm::Set(display_order as i32),
+				media_id: sea_orm::Set(media_id.clone()),
+				reading_list_id: sea_orm::Set(id.clone()),
+				..Default::default()
+			}
+			.insert(db)
+			.await
+			.expect("could not insert reading list item");
+		}
+
+		reading_list
+	}
+}
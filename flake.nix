@@ -27,6 +27,7 @@
           glib
           dbus
           openssl
+          dav1d
         ];
 
         rustVersion = (builtins.fromTOML (builtins.readFile ./rust-toolchain.toml)).toolchain.channel;
@@ -48,6 +49,9 @@
           cargo-watch
           bacon
 
+          # AVIF decoding
+          dav1d
+
           # Tauri deps
           curl
           wget
@@ -16,6 +16,7 @@ export default function ExtensionSelect() {
 					{ label: 'Any', value: '' },
 					{ label: 'CBZ', value: 'cbz' },
 					{ label: 'CBR', value: 'cbr' },
+					{ label: 'CB7', value: 'cb7' },
 					{ label: 'ZIP', value: 'zip' },
 					{ label: 'RAR', value: 'rar' },
 					{ label: 'EPUB', value: 'epub' },
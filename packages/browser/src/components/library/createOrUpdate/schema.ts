@@ -63,6 +63,7 @@ const imageFormatSchema = z.union([
 	z.literal(SupportedImageFormat.Webp),
 	z.literal(SupportedImageFormat.Jpeg),
 	z.literal(SupportedImageFormat.Png),
+	z.literal(SupportedImageFormat.Avif),
 ])
 
 const exactResize = z.object({
@@ -124,6 +125,7 @@ export const buildSchema = (
 		generateFileHashes: z.boolean().default(false),
 		generateKoreaderHashes: z.boolean().default(false),
 		hardDeleteConversions: z.boolean().default(false),
+		koboConvertToEpub: z.boolean().default(false),
 		watch: z.boolean().default(true),
 		ignoreRules: z
 			.array(
@@ -226,6 +228,7 @@ export const formDefaults = (
 	generateFileHashes: library?.config.generateFileHashes ?? false,
 	generateKoreaderHashes: library?.config.generateKoreaderHashes ?? false,
 	hardDeleteConversions: library?.config.hardDeleteConversions ?? false,
+	koboConvertToEpub: library?.config.koboConvertToEpub ?? false,
 	watch: library?.config.watch ?? true,
 	ignoreRules: toFormIgnoreRules(library?.config.ignoreRules || []),
 	libraryPattern: library?.config.libraryPattern || LibraryPattern.SeriesBased,
@@ -10,16 +10,19 @@ type Props = {
 	 * A callback that is triggered when the form values change, debounced by 1 second.
 	 */
 	onDidChange?: (
-		values: Pick<CreateOrUpdateLibrarySchema, 'convertRarToZip' | 'hardDeleteConversions'>,
+		values: Pick<
+			CreateOrUpdateLibrarySchema,
+			'convertRarToZip' | 'hardDeleteConversions' | 'koboConvertToEpub'
+		>,
 	) => void
 }
 
 export default function FileConversionOptions({ onDidChange }: Props) {
 	const form = useFormContext<CreateOrUpdateLibrarySchema>()
 
-	const [convertRarToZip, hardDeleteConversions] = useWatch({
+	const [convertRarToZip, hardDeleteConversions, koboConvertToEpub] = useWatch({
 		control: form.control,
-		name: ['convertRarToZip', 'hardDeleteConversions'],
+		name: ['convertRarToZip', 'hardDeleteConversions', 'koboConvertToEpub'],
 	})
 
 	const { t } = useLocaleContext()
@@ -36,9 +39,10 @@ export default function FileConversionOptions({ onDidChange }: Props) {
 			onDidChange({
 				convertRarToZip: !convertRarToZip,
 				hardDeleteConversions,
+				koboConvertToEpub,
 			})
 		}
-	}, [form, convertRarToZip, hardDeleteConversions, onDidChange])
+	}, [form, convertRarToZip, hardDeleteConversions, koboConvertToEpub, onDidChange])
 
 	const handleChangeHardDelete = useCallback(() => {
 		form.setValue('hardDeleteConversions', !hardDeleteConversions)
@@ -46,9 +50,21 @@ export default function FileConversionOptions({ onDidChange }: Props) {
 			onDidChange({
 				convertRarToZip,
 				hardDeleteConversions: !hardDeleteConversions,
+				koboConvertToEpub,
 			})
 		}
-	}, [form, convertRarToZip, hardDeleteConversions, onDidChange])
+	}, [form, convertRarToZip, hardDeleteConversions, koboConvertToEpub, onDidChange])
+
+	const handleChangeKoboConversion = useCallback(() => {
+		form.setValue('koboConvertToEpub', !koboConvertToEpub)
+		if (onDidChange) {
+			onDidChange({
+				convertRarToZip,
+				hardDeleteConversions,
+				koboConvertToEpub: !koboConvertToEpub,
+			})
+		}
+	}, [form, convertRarToZip, hardDeleteConversions, koboConvertToEpub, onDidChange])
 
 	return (
 		<div className="gap-y-6 flex flex-col">
@@ -79,6 +95,16 @@ export default function FileConversionOptions({ onDidChange }: Props) {
 				onClick={handleChangeHardDelete}
 				{...form.register('hardDeleteConversions')}
 			/>
+
+			<CheckBox
+				id="koboConvertToEpub"
+				variant="primary"
+				label={t(getKey('koboConvertToEpub.label'))}
+				description={t(getKey('koboConvertToEpub.description'))}
+				checked={koboConvertToEpub}
+				onClick={handleChangeKoboConversion}
+				{...form.register('koboConvertToEpub')}
+			/>
 		</div>
 	)
 }
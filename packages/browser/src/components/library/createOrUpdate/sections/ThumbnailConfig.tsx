@@ -29,6 +29,7 @@ const formatOptions = [
 	{ label: 'WebP', value: 'WEBP' },
 	{ label: 'JPEG', value: 'JPEG' },
 	{ label: 'PNG', value: 'PNG' },
+	{ label: 'AVIF', value: 'AVIF' },
 ]
 
 export default function ThumbnailConfigForm() {
@@ -75,6 +75,7 @@ export default function CreateLibraryForm({ existingLibraries, onSubmit, isLoadi
 						'ignoreRules',
 						'convertRarToZip',
 						'hardDeleteConversions',
+						'koboConvertToEpub',
 						'watch',
 					])
 					break
@@ -204,6 +204,13 @@ export default function LibraryReview() {
 							{state.hardDeleteConversions ? 'Yes' : 'No'}
 						</Text>
 					</div>
+
+					<div>
+						<Label>{t(getLabelKey('koboConvertToEpub'))}</Label>
+						<Text variant="muted" size="sm">
+							{state.koboConvertToEpub ? 'Yes' : 'No'}
+						</Text>
+					</div>
 				</div>
 			</StepContainer>
 
@@ -36,10 +36,13 @@ export const LibrarySettingsConfig = graphql(`
 			defaultLibraryViewMode
 			hideSeriesView
 			skipBookOverview
+			koboConvertToEpub
 			generateFileHashes
 			generateKoreaderHashes
 			processMetadata
 			watch
+			watchMode
+			watchPollIntervalSeconds
 			libraryPattern
 			thumbnailConfig {
 				__typename
@@ -26,11 +26,16 @@ export default function FileConversionOptionsPatchForm() {
 		({
 			convertRarToZip,
 			hardDeleteConversions,
-		}: Pick<CreateOrUpdateLibrarySchema, 'convertRarToZip' | 'hardDeleteConversions'>) => {
+			koboConvertToEpub,
+		}: Pick<
+			CreateOrUpdateLibrarySchema,
+			'convertRarToZip' | 'hardDeleteConversions' | 'koboConvertToEpub'
+		>) => {
 			patch({
 				config: {
 					convertRarToZip,
 					hardDeleteConversions,
+					koboConvertToEpub,
 				},
 				scanAfterPersist: false,
 			})
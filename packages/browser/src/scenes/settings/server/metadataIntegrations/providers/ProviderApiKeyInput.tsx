@@ -136,5 +136,12 @@ const validateHardcoverApiKey: Validator = async (apiKey, t) => {
 }
 
 const PROVIDER_VALIDATORS: Record<MetadataProvider, Validator | null> = {
+	// AniList tokens are optional, since public data does not require authentication
+	ANILIST: null,
+	// ComicVine and Metron don't allow cross-origin requests, so tokens are only validated
+	// by the server when a search is made
+	COMIC_VINE: null,
 	HARDCOVER: validateHardcoverApiKey,
+	LOCAL: null,
+	METRON: null,
 }
@@ -9,24 +9,27 @@ import {
 	RawSwitch,
 	Text,
 } from '@stump/components'
-import { MergeStrategy } from '@stump/graphql'
+import { MergeStrategy, MetadataProvider } from '@stump/graphql'
 import { useLocaleContext } from '@stump/i18n'
 import { startOfDay } from 'date-fns'
 import { useFormContext, useFormState, useWatch } from 'react-hook-form'
 
+import { TOKENLESS_PROVIDERS } from './constants'
 import { ProviderApiKeyInput } from './ProviderApiKeyInput'
 import { PatchProviderConfigSchema } from './schema'
 
 export default function ProviderForm() {
-	const form = useFormContext<PatchProviderConfigSchema>()
+	const form = useFormContext<PatchProviderConfigSchema & { providerType?: MetadataProvider }>()
 	const { t } = useLocaleContext()
 	const { errors } = useFormState({ control: form.control })
 
-	const [providerEnabled, autoApplyEnabled, expirationDate] = useWatch({
+	const [providerEnabled, autoApplyEnabled, expirationDate, providerType] = useWatch({
 		control: form.control,
-		name: ['enabled', 'autoApplyConfig.enabled', 'apiTokenExpiresAt'],
+		name: ['enabled', 'autoApplyConfig.enabled', 'apiTokenExpiresAt', 'providerType'],
 	})
 
+	const requiresToken = !providerType || !TOKENLESS_PROVIDERS.includes(providerType)
+
 	const strategyOptions = [
 		{
 			label: t(getKey('autoApplyConfig.strategy.options.FILL_GAPS')),
@@ -44,19 +47,23 @@ export default function ProviderForm() {
 
 	return (
 		<>
-			<ProviderApiKeyInput />
-
-			<div className="gap-2 flex flex-col">
-				<Label>{t(getKey('apiTokenExpiresAt.label'))}</Label>
-				<DatePicker
-					minDate={startOfDay(new Date())}
-					selected={expirationDate ?? undefined}
-					onChange={(date) => form.setValue('apiTokenExpiresAt', date)}
-				/>
-				<Text className="text-sm text-foreground-muted">
-					{t(getKey('apiTokenExpiresAt.description'))}
-				</Text>
-			</div>
+			{requiresToken && (
+				<>
+					<ProviderApiKeyInput />
+
+					<div className="gap-2 flex flex-col">
+						<Label>{t(getKey('apiTokenExpiresAt.label'))}</Label>
+						<DatePicker
+							minDate={startOfDay(new Date())}
+							selected={expirationDate ?? undefined}
+							onChange={(date) => form.setValue('apiTokenExpiresAt', date)}
+						/>
+						<Text className="text-sm text-foreground-muted">
+							{t(getKey('apiTokenExpiresAt.description'))}
+						</Text>
+					</div>
+				</>
+			)}
 
 			<div className="rounded-lg divide-y divide-edge border border-edge">
 				<div className="gap-2 flex flex-col">
@@ -19,5 +19,9 @@ export function ProviderLogo({ provider, className }: Props) {
 }
 
 const LOGOS: Record<MetadataProvider, string> = {
+	[MetadataProvider.Anilist]: '/assets/logos/anilist.svg',
+	[MetadataProvider.ComicVine]: '/assets/logos/comicvine.svg',
 	[MetadataProvider.Hardcover]: '/assets/logos/hardcover.png',
+	[MetadataProvider.Local]: '/assets/logos/local.svg',
+	[MetadataProvider.Metron]: '/assets/logos/metron.svg',
 }
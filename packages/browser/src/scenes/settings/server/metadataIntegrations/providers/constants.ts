@@ -1,7 +1,16 @@
 import { MetadataProvider } from '@stump/graphql'
 
 export const PROVIDER_LABELS: Record<MetadataProvider, string> = {
+	[MetadataProvider.Anilist]: 'AniList',
+	[MetadataProvider.ComicVine]: 'ComicVine',
 	[MetadataProvider.Hardcover]: 'Hardcover',
+	[MetadataProvider.Local]: 'Local files',
+	[MetadataProvider.Metron]: 'Metron',
 }
 
+/**
+ * Providers which don't connect to an external service, and so don't need an API token
+ */
+export const TOKENLESS_PROVIDERS: MetadataProvider[] = [MetadataProvider.Local]
+
 export const PROVIDERS = Object.values(MetadataProvider)
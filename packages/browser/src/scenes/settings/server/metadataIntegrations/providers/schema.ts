@@ -7,6 +7,8 @@ import {
 } from '@stump/graphql'
 import z from 'zod'
 
+import { TOKENLESS_PROVIDERS } from './constants'
+
 const providerType = z.nativeEnum(MetadataProvider)
 const mergeStrategy = z.nativeEnum(MergeStrategy)
 
@@ -21,10 +23,14 @@ export const createConfig = z
 	.object({
 		providerType,
 		enabled: z.boolean().default(true),
-		apiToken: z.string().min(1),
+		apiToken: z.string(),
 		apiTokenExpiresAt: z.date().nullish(),
 		autoApplyConfig,
 	})
+	.refine((data) => !!data.apiToken || TOKENLESS_PROVIDERS.includes(data.providerType), {
+		path: ['apiToken'],
+		message: 'An API token is required for this provider',
+	})
 	//  Note: I don't _think_ this has perf implications, but ensures the form
 	// will stay in sync with CreateMetadataProviderConfigInput
 	.transform((data) => data satisfies CreateMetadataProviderConfigInput)
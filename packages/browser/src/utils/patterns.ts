@@ -1,3 +1,3 @@
-export const ARCHIVE_EXTENSION = /cbr|cbz|zip|rar/
+export const ARCHIVE_EXTENSION = /cbr|cbz|cb7|zip|rar|7z/
 export const EBOOK_EXTENSION = /epub/
 export const PDF_EXTENSION = /pdf/
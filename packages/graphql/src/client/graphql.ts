@@ -447,6 +447,53 @@ export type CollectedItemInput = {
   series?: InputMaybe<Scalars['String']['input']>;
 };
 
+export type Collection = {
+  __typename?: 'Collection';
+  /**
+   * The access rules of the collection. These are only visible to the users who are able
+   * to manage them, and will be empty for everyone else
+   */
+  accessRules: Array<CollectionAccessRuleModel>;
+  /**
+   * The series whose thumbnail is used as the cover of the collection. If not set, the
+   * first series in the collection is used
+   */
+  coverSeriesId?: Maybe<Scalars['String']['output']>;
+  createdAt: Scalars['DateTime']['output'];
+  creator: User;
+  creatorId: Scalars['String']['output'];
+  description?: Maybe<Scalars['String']['output']>;
+  id: Scalars['String']['output'];
+  name: Scalars['String']['output'];
+  /** The series in the collection which the user has access to, in order */
+  series: Array<Series>;
+  seriesCount: Scalars['Int']['output'];
+  /**
+   * The cover of the collection, which is the thumbnail of the cover series or the
+   * first series in the collection. This is null for an empty collection
+   */
+  thumbnail?: Maybe<ImageRef>;
+  updatedAt?: Maybe<Scalars['DateTime']['output']>;
+  visibility: EntityVisibility;
+};
+
+export type CollectionAccessRuleInput = {
+  role: AccessRole;
+  userId: Scalars['ID']['input'];
+};
+
+/**
+ * Grants a user a role on a collection: readers can browse it, writers can edit it and
+ * co-creators can also manage who has access to it or delete it
+ */
+export type CollectionAccessRuleModel = {
+  __typename?: 'CollectionAccessRuleModel';
+  collectionId: Scalars['String']['output'];
+  id: Scalars['Int']['output'];
+  role: AccessRole;
+  userId: Scalars['String']['output'];
+};
+
 export type ComputedFilterLibraryType =
   { is: LibraryType; isAnyOf?: never; isNoneOf?: never; isNot?: never; }
   |  { is?: never; isAnyOf: Array<LibraryType>; isNoneOf?: never; isNot?: never; }
@@ -470,10 +517,21 @@ export type ConfidenceFactor = {
   weight: Scalars['Float']['output'];
 };
 
+export type ConvertMediaOutput = {
+  __typename?: 'ConvertMediaOutput';
+  /** The number of media items which were converted */
+  mediaConverted: Scalars['Int']['output'];
+  /**
+   * The number of media items which were skipped, either because their format can't be
+   * converted or because the conversion failed
+   */
+  mediaSkipped: Scalars['Int']['output'];
+};
+
 /** An event that is emitted by the core and consumed by a client */
 export type CoreEvent = CreatedManySeries | CreatedMedia | CreatedOrUpdatedManyMedia | DiscoveredMissingLibrary | JobOutput | JobStarted | JobUpdate;
 
-export type CoreJobOutput = AnalyzeMediaOutput | LibraryScanOutput | MetadataFetchJobOutput | PlaceholderGenerationOutput | SeriesScanOutput | ThumbnailGenerationOutput;
+export type CoreJobOutput = AnalyzeMediaOutput | ConvertMediaOutput | DatabaseBackupOutput | DetectDuplicatesOutput | HistoryPruningOutput | LibraryCleanupOutput | LibraryScanOutput | MetadataFetchJobOutput | OpdsCacheWarmupOutput | PlaceholderGenerationOutput | SeriesScanOutput | ThumbnailGenerationOutput | WriteBackMetadataOutput;
 
 export type CreateAnnotationInput = {
   annotationText?: InputMaybe<Scalars['String']['input']>;
@@ -497,6 +555,14 @@ export type CreateBookClubMemberInput = {
   userId: Scalars['String']['input'];
 };
 
+export type CreateCollectionInput = {
+  description?: InputMaybe<Scalars['String']['input']>;
+  name: Scalars['String']['input'];
+  /** The series to add to the collection, in order */
+  seriesIds?: Array<Scalars['ID']['input']>;
+  visibility?: EntityVisibility;
+};
+
 export type CreateCustomEmojiInput = {
   isAnimated: Scalars['Boolean']['input'];
   name: Scalars['String']['input'];
@@ -504,7 +570,10 @@ export type CreateCustomEmojiInput = {
 
 /** Input object for creating a metadata provider configuration */
 export type CreateMetadataProviderConfigInput = {
-  /** The API token for authenticating with the provider */
+  /**
+   * The API token for authenticating with the provider. This may be empty for providers
+   * which don't require a token
+   */
   apiToken: Scalars['String']['input'];
   /**
    * Optional expiration date for the API key. This is exclusively a QOL thing,
@@ -529,6 +598,14 @@ export type CreateOrUpdateLibraryInput = {
   tags?: InputMaybe<Array<Scalars['String']['input']>>;
 };
 
+export type CreateReviewInput = {
+  mediaId: Scalars['ID']['input'];
+  /** The rating given to the book, from 1 to 5 */
+  rating: Scalars['Int']['input'];
+  reviewText?: InputMaybe<Scalars['String']['input']>;
+  visibility?: EntityVisibility;
+};
+
 export type CreateScheduledJobInput = {
   /** The type-specific config. The kind is inferred from the variant provided */
   config: ScheduledJobConfigInput;
@@ -621,6 +698,17 @@ export type CustomEmoji = {
   url: Scalars['String']['output'];
 };
 
+export type DatabaseBackupConfigInput = {
+  /** The number of backups to keep. Older backups are deleted after a new one is made */
+  retainCount?: Scalars['Int']['input'];
+};
+
+export type DatabaseBackupOutput = {
+  __typename?: 'DatabaseBackupOutput';
+  /** The path of the backup which was created */
+  path?: Maybe<Scalars['String']['output']>;
+};
+
 export type DeleteJobAssociatedLogs = {
   __typename?: 'DeleteJobAssociatedLogs';
   /** The number of logs deleted that were related to a job */
@@ -633,6 +721,17 @@ export type DeleteJobHistory = {
   affectedRows: Scalars['Int']['output'];
 };
 
+export type DetectDuplicatesOutput = {
+  __typename?: 'DetectDuplicatesOutput';
+  /** The number of duplicate groups which were found */
+  groupsFound: Scalars['Int']['output'];
+  /**
+   * The number of media items which were flagged. An item in more than one group is
+   * counted once for each group
+   */
+  mediaFlagged: Scalars['Int']['output'];
+};
+
 export enum Dimension {
   Height = 'HEIGHT',
   Width = 'WIDTH'
@@ -673,6 +772,38 @@ export type DiscoveredMissingLibrary = {
   id: Scalars['String']['output'];
 };
 
+export type DuplicateMediaGroup = {
+  __typename?: 'DuplicateMediaGroup';
+  createdAt: Scalars['DateTime']['output'];
+  id: Scalars['String']['output'];
+  /** The value the members of the group have in common, e.g. the hash or ISBN */
+  key: Scalars['String']['output'];
+  /** The media items in the group which the user has access to */
+  media: Array<Media>;
+  reason: DuplicateReason;
+};
+
+/** The reason a group of media items were flagged as possible duplicates of each other */
+export enum DuplicateReason {
+  /** The files have the same content hash */
+  Hash = 'HASH',
+  /** The books share an identifier in their metadata, e.g. an ISBN or ComicVine ID */
+  Identifier = 'IDENTIFIER',
+  /**
+   * The books have the same series, number and volume in their metadata, e.g. a CBR
+   * and CBZ of the same issue
+   */
+  SeriesNumber = 'SERIES_NUMBER'
+}
+
+/** What to do with the other members of a duplicate group once a survivor is chosen */
+export enum DuplicateResolution {
+  /** Delete the duplicates, after moving everything attached to them onto the survivor */
+  Delete = 'DELETE',
+  /** Keep the duplicates, but never flag them as duplicates of the survivor again */
+  Ignore = 'IGNORE'
+}
+
 export type EditMessageInput = {
   content: Scalars['String']['input'];
 };
@@ -815,24 +946,31 @@ export type ExactDimensionResizeInput = {
 export type ExternalMediaMetadata = {
   __typename?: 'ExternalMediaMetadata';
   artists?: Maybe<Array<Scalars['String']['output']>>;
+  characters?: Maybe<Array<Scalars['String']['output']>>;
   colorists?: Maybe<Array<Scalars['String']['output']>>;
   coverArtists?: Maybe<Array<Scalars['String']['output']>>;
   coverUrl?: Maybe<Scalars['String']['output']>;
   day?: Maybe<Scalars['Int']['output']>;
+  editors?: Maybe<Array<Scalars['String']['output']>>;
   externalId: Scalars['String']['output'];
   genres?: Maybe<Array<Scalars['String']['output']>>;
+  inkers?: Maybe<Array<Scalars['String']['output']>>;
   isbn?: Maybe<Scalars['String']['output']>;
   isbn13?: Maybe<Scalars['String']['output']>;
   letterers?: Maybe<Array<Scalars['String']['output']>>;
   month?: Maybe<Scalars['Int']['output']>;
   number?: Maybe<Scalars['Float']['output']>;
   pageCount?: Maybe<Scalars['Int']['output']>;
+  pencillers?: Maybe<Array<Scalars['String']['output']>>;
   provider: Scalars['String']['output'];
   providerUrl?: Maybe<Scalars['String']['output']>;
+  publisher?: Maybe<Scalars['String']['output']>;
   seriesExternalId?: Maybe<Scalars['String']['output']>;
   seriesName?: Maybe<Scalars['String']['output']>;
+  storyArcs?: Maybe<Array<Scalars['String']['output']>>;
   summary?: Maybe<Scalars['String']['output']>;
   tags?: Maybe<Array<Scalars['String']['output']>>;
+  teams?: Maybe<Array<Scalars['String']['output']>>;
   title?: Maybe<Scalars['String']['output']>;
   writers?: Maybe<Array<Scalars['String']['output']>>;
   year?: Maybe<Scalars['Int']['output']>;
@@ -966,6 +1104,24 @@ export type FitWithinResizeInput = {
   width: Scalars['Int']['input'];
 };
 
+export type HistoryPruningConfigInput = {
+  /**
+   * Delete finished jobs older than this many days. Job history isn't pruned if this is
+   * not set
+   */
+  jobRetentionDays?: InputMaybe<Scalars['Int']['input']>;
+  /** Delete logs older than this many days. Logs aren't pruned if this is not set */
+  logRetentionDays?: InputMaybe<Scalars['Int']['input']>;
+};
+
+export type HistoryPruningOutput = {
+  __typename?: 'HistoryPruningOutput';
+  /** The number of finished jobs which were deleted */
+  deletedJobs: Scalars['Int']['output'];
+  /** The number of logs which were deleted */
+  deletedLogs: Scalars['Int']['output'];
+};
+
 export type ImageColor = {
   __typename?: 'ImageColor';
   color: Scalars['String']['output'];
@@ -996,7 +1152,7 @@ export type ImageProcessorOptions = {
   /**
    * The quality to use when generating an image. This is a number between 1 and 100,
    * where 100 is the highest quality. Omitting this value will use the default quality
-   * of 100.
+   * of 100, or 80 for AVIF.
    */
   quality?: Maybe<Scalars['Int']['output']>;
   /** The size factor to use when generating an image. See [`ImageResizeOptions`] */
@@ -1012,7 +1168,7 @@ export type ImageProcessorOptionsInput = {
   /**
    * The quality to use when generating an image. This is a number between 1 and 100,
    * where 100 is the highest quality. Omitting this value will use the default quality
-   * of 100.
+   * of 100, or 80 for AVIF.
    */
   quality?: InputMaybe<Scalars['Int']['input']>;
   /** The size factor to use when generating an image. See [`ImageResizeOptions`] */
@@ -1037,6 +1193,14 @@ export type ImageResizeMethodInput =
   |  { exact?: never; fitWithin?: never; scaleDimension: ScaledDimensionResizeInput; scaleEvenlyByFactor?: never; }
   |  { exact?: never; fitWithin?: never; scaleDimension?: never; scaleEvenlyByFactor: ScaleEvenlyByFactorInput; };
 
+export type ImportCblOutput = {
+  __typename?: 'ImportCBLOutput';
+  /** The number of books in the CBL file which were added to the reading list */
+  matchedCount: Scalars['Int']['output'];
+  readingList: ReadingList;
+  unmatched: Array<UnmatchedCblBook>;
+};
+
 export type InProgressBooks = {
   __typename?: 'InProgressBooks';
   links: Array<FilterableArrangementEntityLink>;
@@ -1071,12 +1235,19 @@ export type Job = {
   completedAt?: Maybe<Scalars['DateTime']['output']>;
   createdAt: Scalars['DateTime']['output'];
   description?: Maybe<Scalars['String']['output']>;
+  /**
+   * The payloads of the tasks which failed during the job. These can be run again with
+   * `retryFailedJobTasks`
+   */
+  failedTasks: Array<Scalars['JSON']['output']>;
   id: Scalars['String']['output'];
   logCount: Scalars['Int']['output'];
   logs: Array<Log>;
   msElapsed: Scalars['Int']['output'];
   name: Scalars['String']['output'];
   outputData?: Maybe<CoreJobOutput>;
+  /** Queued jobs with a higher priority are started first */
+  priority: Scalars['Int']['output'];
   saveState?: Maybe<Scalars['JSON']['output']>;
   status: JobStatus;
 };
@@ -1162,6 +1333,25 @@ export type Library = {
 };
 
 
+export type LibraryCleanupConfigInput = {
+  /** Whether to run duplicate detection across all libraries */
+  detectDuplicates?: Scalars['Boolean']['input'];
+  /** Library IDs to clean. An empty list means "all libraries" */
+  libraryIds: Array<Scalars['String']['input']>;
+  /** Whether to delete books and series which are missing from disk */
+  removeMissing?: Scalars['Boolean']['input'];
+};
+
+export type LibraryCleanupOutput = {
+  __typename?: 'LibraryCleanupOutput';
+  /** The number of media which were deleted */
+  deletedMediaCount: Scalars['Int']['output'];
+  /** The number of series which were deleted */
+  deletedSeriesCount: Scalars['Int']['output'];
+  /** The number of libraries which were cleaned */
+  librariesCleaned: Scalars['Int']['output'];
+};
+
 export type LibraryGenresArgs = {
   sort?: InputMaybe<OrderDirection>;
 };
@@ -1200,6 +1390,7 @@ export type LibraryConfig = {
   hideSeriesView: Scalars['Boolean']['output'];
   id: Scalars['Int']['output'];
   ignoreRules?: Maybe<Array<Scalars['String']['output']>>;
+  koboConvertToEpub: Scalars['Boolean']['output'];
   libraryId?: Maybe<Scalars['String']['output']>;
   libraryPattern: LibraryPattern;
   libraryType: LibraryType;
@@ -1208,6 +1399,9 @@ export type LibraryConfig = {
   skipBookOverview: Scalars['Boolean']['output'];
   thumbnailConfig?: Maybe<ImageProcessorOptions>;
   watch: Scalars['Boolean']['output'];
+  watchMode: LibraryWatchMode;
+  /** The number of seconds between checks when [LibraryWatchMode::Poll] is used */
+  watchPollIntervalSeconds: Scalars['Int']['output'];
 };
 
 export type LibraryConfigInput = {
@@ -1221,6 +1415,11 @@ export type LibraryConfigInput = {
   hardDeleteConversions: Scalars['Boolean']['input'];
   hideSeriesView: Scalars['Boolean']['input'];
   ignoreRules?: InputMaybe<Array<Scalars['String']['input']>>;
+  /**
+   * Whether comics and PDFs are converted to EPUBs for Kobo sync. This is left unchanged
+   * when omitted, and off for new libraries
+   */
+  koboConvertToEpub?: InputMaybe<Scalars['Boolean']['input']>;
   libraryPattern: LibraryPattern;
   libraryType: LibraryType;
   processMetadata: Scalars['Boolean']['input'];
@@ -1228,6 +1427,8 @@ export type LibraryConfigInput = {
   skipBookOverview: Scalars['Boolean']['input'];
   thumbnailConfig?: InputMaybe<ImageProcessorOptionsInput>;
   watch: Scalars['Boolean']['input'];
+  watchMode?: LibraryWatchMode;
+  watchPollIntervalSeconds?: Scalars['Int']['input'];
 };
 
 export type LibraryFilterInput = {
@@ -1332,6 +1533,17 @@ export enum LibraryViewMode {
   Series = 'SERIES'
 }
 
+/** How a library is watched for changes on disk */
+export enum LibraryWatchMode {
+  /** Use the filesystem events of the host OS (e.g. inotify) */
+  Native = 'NATIVE',
+  /**
+   * Periodically check the library for changes. This is useful for network mounts
+   * (e.g. NFS or SMB), which generally don't emit filesystem events
+   */
+  Poll = 'POLL'
+}
+
 export type Log = {
   __typename?: 'Log';
   context?: Maybe<Scalars['String']['output']>;
@@ -1439,6 +1651,8 @@ export type Media = {
   pages: Scalars['Int']['output'];
   /** The path of the underlying media file on disk */
   path: Scalars['String']['output'];
+  /** The average rating of the book, from its public reviews */
+  ratingSummary: RatingSummary;
   readHistory: Array<FinishedReadingSession>;
   readProgress?: Maybe<ActiveReadingSession>;
   /**
@@ -1452,6 +1666,8 @@ export type Media = {
    * metatadata, if available, and fallback to the name derived from the file name
    */
   resolvedName: Scalars['String']['output'];
+  /** The reviews of the book which the user is able to see, newest first */
+  reviews: Array<Review>;
   /** The series the media belongs to */
   series: Series;
   /**
@@ -1483,6 +1699,13 @@ export type Media = {
 };
 
 
+export type MediaAnalysisConfigInput = {
+  /** Whether to analyze books which were already analyzed */
+  forceReanalysis?: Scalars['Boolean']['input'];
+  /** Library IDs to analyze. An empty list means "all libraries" */
+  libraryIds: Array<Scalars['String']['input']>;
+};
+
 export type MediaNextInSeriesArgs = {
   pagination?: Pagination;
 };
@@ -1520,14 +1743,32 @@ export type MediaFilterInput = {
   _and?: InputMaybe<Array<MediaFilterInput>>;
   _not?: InputMaybe<Array<MediaFilterInput>>;
   _or?: InputMaybe<Array<MediaFilterInput>>;
+  /** The average rating of the media, from its public reviews */
+  averageRating?: InputMaybe<NumericFilterF32>;
   createdAt?: InputMaybe<NumericFilterDateTime>;
   extension?: InputMaybe<FieldFilterString>;
+  /**
+   * When the user finished reading the media. Media which were read more than once
+   * match if any of the times they were finished do
+   */
+  finishedAt?: InputMaybe<NumericFilterDateTime>;
   id?: InputMaybe<FieldFilterString>;
   metadata?: InputMaybe<MediaMetadataFilterInput>;
   name?: InputMaybe<FieldFilterString>;
   pages?: InputMaybe<NumericFilterI32>;
   path?: InputMaybe<FieldFilterString>;
+  /** The progress of the user's current reading session for the media, from 0 to 1 */
+  readingProgress?: InputMaybe<NumericFilterF32>;
+  /** When the user started their current reading session for the media */
+  readingStartedAt?: InputMaybe<NumericFilterDateTime>;
   readingStatus?: InputMaybe<ComputedFilterReadingStatus>;
+  /** When the user last made progress in their current reading session for the media */
+  readingUpdatedAt?: InputMaybe<NumericFilterDateTime>;
+  /**
+   * The name of a device the user read the media on, in either their current or a
+   * finished reading session
+   */
+  readOnDevice?: InputMaybe<FieldFilterString>;
   series?: InputMaybe<SeriesFilterInput>;
   seriesId?: InputMaybe<FieldFilterString>;
   size?: InputMaybe<NumericFilterI64>;
@@ -1729,8 +1970,9 @@ export enum MediaModelOrdering {
 }
 
 export type MediaOrderBy =
-  { media: MediaOrderByField; metadata?: never; }
-  |  { media?: never; metadata: MediaMetadataOrderByField; };
+  { averageRating: OrderDirection; media?: never; metadata?: never; }
+  |  { averageRating?: never; media: MediaOrderByField; metadata?: never; }
+  |  { averageRating?: never; media?: never; metadata: MediaMetadataOrderByField; };
 
 export type MediaOrderByField = {
   direction: OrderDirection;
@@ -1868,8 +2110,16 @@ export type MetadataFieldOverride = {
 
 /** The supported external metadata providers */
 export enum MetadataProvider {
+  /** AniList (https://anilist.co) */
+  Anilist = 'ANILIST',
+  /** ComicVine (https://comicvine.gamespot.com) */
+  ComicVine = 'COMIC_VINE',
   /** Hardcover (https://hardcover.app) */
-  Hardcover = 'HARDCOVER'
+  Hardcover = 'HARDCOVER',
+  /** Sidecar files stored alongside series and books, e.g. `metadata.opf` */
+  Local = 'LOCAL',
+  /** Metron (https://metron.cloud) */
+  Metron = 'METRON'
 }
 
 export type MetadataProviderConfigModel = {
@@ -1938,6 +2188,11 @@ export type Mutation = {
   acceptSeriesMatch: MetadataFetchRecord;
   /** Add a book to the club's queue */
   addBookToClub: BookClub;
+  /**
+   * Add series to the end of a collection. Series which are already in the collection
+   * are skipped. This requires the writer role
+   */
+  addSeriesToCollection: Collection;
   analyzeLibrary: Scalars['Boolean']['output'];
   analyzeMedia: Scalars['Boolean']['output'];
   analyzeSeries: Scalars['Boolean']['output'];
@@ -1958,7 +2213,13 @@ export type Mutation = {
   clearScanHistory: Scalars['Int']['output'];
   /** Mark the current book as completed */
   completeBook: BookClub;
+  /**
+   * Convert a book (CBR, CB7 or PDF) into a CBZ archive. The book keeps its ID, so
+   * reading progress and anything else attached to it is preserved.
+   */
   convertMedia: Scalars['Boolean']['output'];
+  /** Convert every CBR, CB7 and PDF book in a series into a CBZ archive */
+  convertSeries: Scalars['Boolean']['output'];
   /** Create an annotation (highlight/note) */
   createAnnotation: MediaAnnotation;
   createApiKey: CreatedApiKey;
@@ -1968,6 +2229,8 @@ export type Mutation = {
   createBookClubMember: BookClubMember;
   /** Create a bookmark for a user */
   createBookmark: Bookmark;
+  /** Create a new collection, optionally with an initial set of series */
+  createCollection: Collection;
   /** Manually create a discussion for a book */
   createDiscussion: BookClubDiscussion;
   createEmailDevice: RegisteredEmailDevice;
@@ -1987,6 +2250,8 @@ export type Mutation = {
    * A result containing the newly created reading list, or an error if creation failed.
    */
   createReadingList: ReadingList;
+  /** Rate and optionally review a book. A user can only review each book once */
+  createReview: Review;
   createScheduledJob: ScheduledJob;
   createSmartList: SmartList;
   createSmartListView: SmartListView;
@@ -2007,6 +2272,8 @@ export type Mutation = {
   deleteBookmark: Bookmark;
   /** Delete a bookmark by epubcfi */
   deleteBookmarkByEpubcfi: Bookmark;
+  /** Delete a collection. This requires the co-creator role */
+  deleteCollection: Collection;
   /** Delete a custom emoji */
   deleteCustomEmoji: Scalars['Boolean']['output'];
   deleteEmailDevice: RegisteredEmailDevice;
@@ -2036,13 +2303,15 @@ export type Mutation = {
   deleteMetadataProvider: MetadataProviderConfigModel;
   deleteNotifier: Notifier;
   /**
-   * Deletes a reading list by ID.
+   * Deletes a reading list by ID. This requires the co-creator role.
    *
    * # Returns
    *
    * A result containing the deleted reading list, or an error if deletion failed.
    */
   deleteReadingList: ReadingList;
+  /** Delete a review. Reviews can be deleted by their author or the server owner */
+  deleteReview: Review;
   deleteScheduledJob: Scalars['Boolean']['output'];
   deleteSmartList: SmartList;
   deleteSmartListView: SmartListView;
@@ -2060,6 +2329,11 @@ export type Mutation = {
   deleteUserAvatar: User;
   deleteUserSessions: Scalars['Int']['output'];
   /** Edit your own message */
+  /**
+   * Start a job which finds groups of likely duplicate media, replacing the groups found
+   * by any previous run
+   */
+  detectDuplicateMedia: Scalars['Boolean']['output'];
   editMessage: BookClubDiscussionMessage;
   favoriteMedia: Media;
   favoriteSeries: Series;
@@ -2070,12 +2344,28 @@ export type Mutation = {
   /** Search external metadata providers for a series and return match candidates */
   fetchSeriesMetadata: Array<MatchCandidate>;
   generateLibraryThumbnails: Scalars['Boolean']['output'];
+  /**
+   * Creates a new reading list from a ComicRack reading list (`.cbl`) file. Each book in
+   * the file is matched to a book on the server by its series, number, volume and year,
+   * and books which can't be matched are left out of the reading list.
+   *
+   * # Returns
+   *
+   * A result containing the newly created reading list and the books which could not be
+   * matched, or an error if the file is not a valid CBL file.
+   */
+  importCblReadingList: ImportCblOutput;
   /** Deletes the membership of the caller to the target book club */
   leaveBookClub: BookClubMember;
   /** Lock or unlock a discussion (Moderator+) */
   lockDiscussion: Scalars['Boolean']['output'];
   markMediaAsComplete?: Maybe<FinishedReadingSessionModel>;
   patchEmailDevice: RegisteredEmailDevice;
+  /**
+   * Pause a job. A running job pauses once its current task finishes, and a queued job
+   * won't start until it is resumed
+   */
+  pauseJob: Scalars['Boolean']['output'];
   /** Pin or unpin a message (Moderator+) */
   pinMessage: Scalars['Boolean']['output'];
   processLibraryThumbnails: Scalars['Boolean']['output'];
@@ -2087,6 +2377,8 @@ export type Mutation = {
   rejectSeriesMatch: MetadataFetchRecord;
   /** Removes a member from the book club */
   removeBookClubMember: BookClubMember;
+  /** Remove series from a collection. This requires the writer role */
+  removeSeriesFromCollection: Collection;
   /** Remove your own suggestion (only before it's resolved) */
   removeSuggestion: BookClubBookSuggestion;
   /**
@@ -2096,9 +2388,30 @@ export type Mutation = {
   renameTag: Tag;
   /** Reorder uncompleted books in the club's queue. Completed books cannot be reordered since they are effectively archived */
   reorderBooks: BookClub;
+  /**
+   * Move queued jobs to the front of the queue, in the given order, returning the updated
+   * queue. Passing every queued job reorders the whole queue. A queued job can be dropped
+   * with `cancelJob` or `deleteJob`
+   */
+  reorderQueuedJobs: Array<Job>;
   resetLibraryMetadata: Library;
   resetSeriesMetadata: Series;
+  /**
+   * Resolve a group of duplicate media by keeping one of its members. When the other
+   * members are deleted, their reading progress, bookmarks, annotations, reading list
+   * memberships and favorites are moved onto it first. Ignored members keep theirs, and
+   * are left out of future detection runs.
+   */
+  resolveDuplicateMedia: Media;
   respondToBookClubInvitation: BookClubInvitation;
+  /** Resume a paused job. The job is queued again, and picks up from where it left off */
+  resumeJob: Scalars['Boolean']['output'];
+  /**
+   * Queue a new job which runs only the tasks that failed during a finished job, returning
+   * the new job. The failed tasks are moved to the new job, so they can only be retried
+   * once
+   */
+  retryFailedJobTasks: Job;
   /**
    * Enqueue a scan job for a library. This will index the filesystem from the library's root path
    * and update the database accordingly.
@@ -2108,6 +2421,17 @@ export type Mutation = {
   sendAttachmentEmail: SendAttachmentEmailOutput;
   /** Send a message in a discussion */
   sendMessage: BookClubDiscussionMessage;
+  /**
+   * Replace the access rules of a collection, which grant other users a role in it. Rules
+   * have no effect on private collections. This requires the co-creator role
+   */
+  setCollectionAccessRules: Collection;
+  /**
+   * Replace the series in a collection with the given series, in the given order. Series
+   * in the collection which the user is unable to see are kept, after the given series.
+   * This requires the writer role
+   */
+  setCollectionSeries: Collection;
   /** Bulk-set locked metadata fields for all media metadata in a library */
   setLibraryMediaLockedFields: Scalars['Int']['output'];
   /** Bulk-set locked metadata fields for all series metadata in a library */
@@ -2119,6 +2443,15 @@ export type Mutation = {
    * and unlinks removed ones. Returns the updated media item.
    */
   setMediaTags: Media;
+  /**
+   * Replaces the rules which grant other users a role on a reading list. Rules have no
+   * effect on private reading lists. This requires the co-creator role.
+   *
+   * # Returns
+   *
+   * A result containing the reading list, or an error if the rules could not be set.
+   */
+  setReadingListRules: ReadingList;
   /** Set the locked metadata fields for a series */
   setSeriesLockedFields: Series;
   /**
@@ -2149,6 +2482,8 @@ export type Mutation = {
   updateAnnotation: MediaAnnotation;
   updateApiKey: Apikey;
   updateBookClub: BookClub;
+  /** Update the details of a collection. This requires the writer role */
+  updateCollection: Collection;
   /** Rename a custom emoji */
   updateCustomEmoji: CustomEmoji;
   updateEmailDevice: RegisteredEmailDevice;
@@ -2189,13 +2524,16 @@ export type Mutation = {
   updateNotifier: Notifier;
   updatePublicUrl: ServerConfigModel;
   /**
-   * Updates an existing reading list.
+   * Updates an existing reading list, replacing its books with the given ones. This
+   * requires the writer role, and changing the visibility requires the co-creator role.
    *
    * # Returns
    *
    * A result containing the updated reading list, or an error if update failed.
    */
   updateReadingList: ReadingList;
+  /** Update a review. Only the author of a review can update it */
+  updateReview: Review;
   updateScheduledJob: ScheduledJob;
   updateSeriesMetadata: Series;
   /**
@@ -2241,6 +2579,15 @@ export type Mutation = {
    * This is used to inform the UI of the last library which was visited by the user
    */
   visitLibrary: Library;
+  /** Write the stored metadata of every book in a library back into the files */
+  writeBackLibraryMetadata: Scalars['Boolean']['output'];
+  /**
+   * Write the stored metadata of a book back into its file (ComicInfo.xml for zip/cbz,
+   * the OPF for epub)
+   */
+  writeBackMediaMetadata: Scalars['Boolean']['output'];
+  /** Write the stored metadata of every book in a series back into the files */
+  writeBackSeriesMetadata: Scalars['Boolean']['output'];
 };
 
 
@@ -2274,6 +2621,12 @@ export type MutationAddBookToClubArgs = {
 };
 
 
+export type MutationAddSeriesToCollectionArgs = {
+  id: Scalars['ID']['input'];
+  seriesIds: Array<Scalars['ID']['input']>;
+};
+
+
 export type MutationAnalyzeLibraryArgs = {
   forceReanalysis?: Scalars['Boolean']['input'];
   id: Scalars['ID']['input'];
@@ -2319,7 +2672,16 @@ export type MutationCompleteBookArgs = {
 
 
 export type MutationConvertMediaArgs = {
+  deleteSource?: Scalars['Boolean']['input'];
   id: Scalars['ID']['input'];
+  imageFormat?: InputMaybe<SupportedImageFormat>;
+};
+
+
+export type MutationConvertSeriesArgs = {
+  deleteSource?: Scalars['Boolean']['input'];
+  id: Scalars['ID']['input'];
+  imageFormat?: InputMaybe<SupportedImageFormat>;
 };
 
 
@@ -2355,6 +2717,11 @@ export type MutationCreateBookmarkArgs = {
 };
 
 
+export type MutationCreateCollectionArgs = {
+  input: CreateCollectionInput;
+};
+
+
 export type MutationCreateDiscussionArgs = {
   bookClubId: Scalars['ID']['input'];
   input: BookClubDiscussionInput;
@@ -2382,6 +2749,7 @@ export type MutationCreateMetadataProviderArgs = {
 
 
 export type MutationCreateNotifierArgs = {
+  events?: InputMaybe<Array<NotificationEventKind>>;
   input: NotifierInput;
 };
 
@@ -2391,6 +2759,11 @@ export type MutationCreateReadingListArgs = {
 };
 
 
+export type MutationCreateReviewArgs = {
+  input: CreateReviewInput;
+};
+
+
 export type MutationCreateScheduledJobArgs = {
   input: CreateScheduledJobInput;
 };
@@ -2441,6 +2814,11 @@ export type MutationDeleteBookmarkByEpubcfiArgs = {
 };
 
 
+export type MutationDeleteCollectionArgs = {
+  id: Scalars['ID']['input'];
+};
+
+
 export type MutationDeleteCustomEmojiArgs = {
   id: Scalars['ID']['input'];
 };
@@ -2522,6 +2900,11 @@ export type MutationDeleteReadingListArgs = {
 };
 
 
+export type MutationDeleteReviewArgs = {
+  id: Scalars['ID']['input'];
+};
+
+
 export type MutationDeleteScheduledJobArgs = {
   id: Scalars['Int']['input'];
 };
@@ -2599,6 +2982,13 @@ export type MutationGenerateLibraryThumbnailsArgs = {
 };
 
 
+export type MutationImportCblReadingListArgs = {
+  name?: InputMaybe<Scalars['String']['input']>;
+  upload: Scalars['Upload']['input'];
+  visibility?: EntityVisibility;
+};
+
+
 export type MutationLeaveBookClubArgs = {
   bookClubId: Scalars['ID']['input'];
 };
@@ -2623,6 +3013,11 @@ export type MutationPatchEmailDeviceArgs = {
 };
 
 
+export type MutationPauseJobArgs = {
+  id: Scalars['ID']['input'];
+};
+
+
 export type MutationPinMessageArgs = {
   messageId: Scalars['ID']['input'];
   pinned: Scalars['Boolean']['input'];
@@ -2653,6 +3048,12 @@ export type MutationRemoveBookClubMemberArgs = {
 };
 
 
+export type MutationRemoveSeriesFromCollectionArgs = {
+  id: Scalars['ID']['input'];
+  seriesIds: Array<Scalars['ID']['input']>;
+};
+
+
 export type MutationRemoveSuggestionArgs = {
   suggestionId: Scalars['ID']['input'];
 };
@@ -2670,6 +3071,11 @@ export type MutationReorderBooksArgs = {
 };
 
 
+export type MutationReorderQueuedJobsArgs = {
+  ids: Array<Scalars['ID']['input']>;
+};
+
+
 export type MutationResetLibraryMetadataArgs = {
   id: Scalars['ID']['input'];
   impact: MetadataResetImpact;
@@ -2682,12 +3088,29 @@ export type MutationResetSeriesMetadataArgs = {
 };
 
 
+export type MutationResolveDuplicateMediaArgs = {
+  action: DuplicateResolution;
+  groupId: Scalars['ID']['input'];
+  survivorId: Scalars['ID']['input'];
+};
+
+
 export type MutationRespondToBookClubInvitationArgs = {
   id: Scalars['ID']['input'];
   input: BookClubInvitationResponseInput;
 };
 
 
+export type MutationResumeJobArgs = {
+  id: Scalars['ID']['input'];
+};
+
+
+export type MutationRetryFailedJobTasksArgs = {
+  id: Scalars['ID']['input'];
+};
+
+
 export type MutationScanLibraryArgs = {
   id: Scalars['ID']['input'];
   options?: InputMaybe<Scalars['JSON']['input']>;
@@ -2710,6 +3133,18 @@ export type MutationSendMessageArgs = {
 };
 
 
+export type MutationSetCollectionAccessRulesArgs = {
+  id: Scalars['ID']['input'];
+  rules: Array<CollectionAccessRuleInput>;
+};
+
+
+export type MutationSetCollectionSeriesArgs = {
+  id: Scalars['ID']['input'];
+  seriesIds: Array<Scalars['ID']['input']>;
+};
+
+
 export type MutationSetLibraryMediaLockedFieldsArgs = {
   libraryId: Scalars['ID']['input'];
   lockedFields: Array<MetadataField>;
@@ -2734,6 +3169,12 @@ export type MutationSetMediaTagsArgs = {
 };
 
 
+export type MutationSetReadingListRulesArgs = {
+  id: Scalars['ID']['input'];
+  rules: Array<ReadingListRuleInput>;
+};
+
+
 export type MutationSetSeriesLockedFieldsArgs = {
   lockedFields: Array<MetadataField>;
   seriesId: Scalars['ID']['input'];
@@ -2793,6 +3234,12 @@ export type MutationUpdateBookClubArgs = {
 };
 
 
+export type MutationUpdateCollectionArgs = {
+  id: Scalars['ID']['input'];
+  input: UpdateCollectionInput;
+};
+
+
 export type MutationUpdateCustomEmojiArgs = {
   id: Scalars['ID']['input'];
   input: UpdateCustomEmojiInput;
@@ -2870,6 +3317,7 @@ export type MutationUpdateNavigationArrangementLockArgs = {
 
 
 export type MutationUpdateNotifierArgs = {
+  events?: InputMaybe<Array<NotificationEventKind>>;
   id: Scalars['Int']['input'];
   input: NotifierInput;
 };
@@ -2885,6 +3333,12 @@ export type MutationUpdateReadingListArgs = {
 };
 
 
+export type MutationUpdateReviewArgs = {
+  id: Scalars['ID']['input'];
+  input: UpdateReviewInput;
+};
+
+
 export type MutationUpdateScheduledJobArgs = {
   id: Scalars['Int']['input'];
   input: UpdateScheduledJobInput;
@@ -3000,22 +3454,58 @@ export type MutationVisitLibraryArgs = {
   id: Scalars['ID']['input'];
 };
 
+
+export type MutationWriteBackLibraryMetadataArgs = {
+  id: Scalars['ID']['input'];
+};
+
+
+export type MutationWriteBackMediaMetadataArgs = {
+  id: Scalars['ID']['input'];
+};
+
+
+export type MutationWriteBackSeriesMetadataArgs = {
+  id: Scalars['ID']['input'];
+};
+
 export type NavigationArrangementInput = {
   sections: Array<ArrangementSectionInput>;
 };
 
+/** The kinds of events a notifier can be subscribed to */
+export enum NotificationEventKind {
+  /** A message was sent in a book club discussion */
+  BookClubMessage = 'BOOK_CLUB_MESSAGE',
+  /** A job failed */
+  JobFailed = 'JOB_FAILED',
+  /** A library could not be found on disk */
+  LibraryMissing = 'LIBRARY_MISSING',
+  /** Fetched metadata matches are waiting to be reviewed */
+  MetadataAwaitingReview = 'METADATA_AWAITING_REVIEW',
+  /** A manual or scheduled library scan finished */
+  ScanCompleted = 'SCAN_COMPLETED',
+  /** New series were found while scanning a library */
+  SeriesDiscovered = 'SERIES_DISCOVERED',
+  /** A new user account was created */
+  UserRegistered = 'USER_REGISTERED'
+}
+
 export type Notifier = {
   __typename?: 'Notifier';
   config: NotifierConfig;
+  /** The events the notifier is sent */
+  events: Array<NotificationEventKind>;
   id: Scalars['Int']['output'];
   type: Scalars['String']['output'];
 };
 
-export type NotifierConfig = DiscordConfig | TelegramConfig;
+export type NotifierConfig = DiscordConfig | TelegramConfig | WebhookConfig;
 
 export type NotifierInput =
-  { discord: DiscordConfigInput; telegram?: never; }
-  |  { discord?: never; telegram: TelegramConfigInput; };
+  { discord: DiscordConfigInput; telegram?: never; webhook?: never; }
+  |  { discord?: never; telegram: TelegramConfigInput; webhook?: never; }
+  |  { discord?: never; telegram?: never; webhook: WebhookConfigInput; };
 
 export type NumericFilterDateTime =
   { anyOf: Array<Scalars['DateTime']['input']>; eq?: never; gt?: never; gte?: never; lt?: never; lte?: never; neq?: never; noneOf?: never; range?: never; }
@@ -3028,6 +3518,17 @@ export type NumericFilterDateTime =
   |  { anyOf?: never; eq?: never; gt?: never; gte?: never; lt?: never; lte?: never; neq?: never; noneOf: Array<Scalars['DateTime']['input']>; range?: never; }
   |  { anyOf?: never; eq?: never; gt?: never; gte?: never; lt?: never; lte?: never; neq?: never; noneOf?: never; range: NumericRangeDateTime; };
 
+export type NumericFilterF32 =
+  { anyOf: Array<Scalars['Float']['input']>; eq?: never; gt?: never; gte?: never; lt?: never; lte?: never; neq?: never; noneOf?: never; range?: never; }
+  |  { anyOf?: never; eq: Scalars['Float']['input']; gt?: never; gte?: never; lt?: never; lte?: never; neq?: never; noneOf?: never; range?: never; }
+  |  { anyOf?: never; eq?: never; gt: Scalars['Float']['input']; gte?: never; lt?: never; lte?: never; neq?: never; noneOf?: never; range?: never; }
+  |  { anyOf?: never; eq?: never; gt?: never; gte: Scalars['Float']['input']; lt?: never; lte?: never; neq?: never; noneOf?: never; range?: never; }
+  |  { anyOf?: never; eq?: never; gt?: never; gte?: never; lt: Scalars['Float']['input']; lte?: never; neq?: never; noneOf?: never; range?: never; }
+  |  { anyOf?: never; eq?: never; gt?: never; gte?: never; lt?: never; lte: Scalars['Float']['input']; neq?: never; noneOf?: never; range?: never; }
+  |  { anyOf?: never; eq?: never; gt?: never; gte?: never; lt?: never; lte?: never; neq: Scalars['Float']['input']; noneOf?: never; range?: never; }
+  |  { anyOf?: never; eq?: never; gt?: never; gte?: never; lt?: never; lte?: never; neq?: never; noneOf: Array<Scalars['Float']['input']>; range?: never; }
+  |  { anyOf?: never; eq?: never; gt?: never; gte?: never; lt?: never; lte?: never; neq?: never; noneOf?: never; range: NumericRangeF32; };
+
 export type NumericFilterI32 =
   { anyOf: Array<Scalars['Int']['input']>; eq?: never; gt?: never; gte?: never; lt?: never; lte?: never; neq?: never; noneOf?: never; range?: never; }
   |  { anyOf?: never; eq: Scalars['Int']['input']; gt?: never; gte?: never; lt?: never; lte?: never; neq?: never; noneOf?: never; range?: never; }
@@ -3056,6 +3557,12 @@ export type NumericRangeDateTime = {
   to: Scalars['DateTime']['input'];
 };
 
+export type NumericRangeF32 = {
+  from: Scalars['Float']['input'];
+  inclusive: Scalars['Boolean']['input'];
+  to: Scalars['Float']['input'];
+};
+
 export type NumericRangeI32 = {
   from: Scalars['Int']['input'];
   inclusive: Scalars['Boolean']['input'];
@@ -3107,6 +3614,23 @@ export enum OrderDirection {
   Desc = 'DESC'
 }
 
+export type OpdsCacheWarmupConfigInput = {
+  /** Only warm books added within this many days. All books are warmed if this is not set */
+  addedWithinDays?: InputMaybe<Scalars['Int']['input']>;
+  /** Library IDs to warm. An empty list means "all libraries" */
+  libraryIds: Array<Scalars['String']['input']>;
+  /** The number of pages to pre-render, from the start of each PDF */
+  pageCount?: Scalars['Int']['input'];
+};
+
+export type OpdsCacheWarmupOutput = {
+  __typename?: 'OpdsCacheWarmupOutput';
+  /** The number of books whose pages were rendered */
+  booksWarmed: Scalars['Int']['output'];
+  /** The number of pages which were rendered */
+  pagesRendered: Scalars['Int']['output'];
+};
+
 export type PageBasedThumbnailInput = {
   /** A flag indicating whether the page is zero based (i.e. 0 is the first page) */
   isZeroBased?: InputMaybe<Scalars['Boolean']['input']>;
@@ -3293,9 +3817,17 @@ export type Query = {
   bookClubs: Array<BookClub>;
   /** Get all bookmarks for a single epub by its media ID */
   bookmarksByMediaId: Array<Bookmark>;
+  collectionById?: Maybe<Collection>;
+  /** The collections the current user is able to see, ordered by name */
+  collections: Array<Collection>;
   /** List the custom emojis available on this server */
   customEmojis: Array<CustomEmoji>;
   duplicateMedia: Array<Media>;
+  /**
+   * The groups of likely duplicate media found by the last duplicate detection job,
+   * optionally limited to a single reason
+   */
+  duplicateMediaGroups: Array<DuplicateMediaGroup>;
   emailDeviceById?: Maybe<RegisteredEmailDevice>;
   emailDevices: Array<RegisteredEmailDevice>;
   emailerById?: Maybe<Emailer>;
@@ -3344,6 +3876,8 @@ export type Query = {
   /** Return all metadata fetch records that are awaiting user review. */
   pendingMetadataMatches: Array<MetadataFetchRecord>;
   previousBookClubDiscussions: Array<BookClubDiscussion>;
+  /** The jobs which are waiting to be started, in the order they will be started */
+  queuedJobs: Array<Job>;
   /**
    * Retrieves a reading list by ID for the current user.
    *
@@ -3475,6 +4009,21 @@ export type QueryBookmarksByMediaIdArgs = {
 };
 
 
+export type QueryCollectionByIdArgs = {
+  id: Scalars['ID']['input'];
+};
+
+
+export type QueryCollectionsArgs = {
+  search?: InputMaybe<Scalars['String']['input']>;
+};
+
+
+export type QueryDuplicateMediaGroupsArgs = {
+  reason?: InputMaybe<DuplicateReason>;
+};
+
+
 export type QueryEmailDeviceByIdArgs = {
   id: Scalars['Int']['input'];
 };
@@ -3655,6 +4204,14 @@ export type QueryUsersArgs = {
   pagination?: Pagination;
 };
 
+/** The aggregate of the public ratings of a book or series */
+export type RatingSummary = {
+  __typename?: 'RatingSummary';
+  /** The average rating, or null if there are no public reviews */
+  averageRating?: Maybe<Scalars['Float']['output']>;
+  ratingCount: Scalars['Int']['output'];
+};
+
 /** The different reading directions supported by any Stump reader */
 export enum ReadingDirection {
   Ltr = 'LTR',
@@ -3676,6 +4233,11 @@ export type ReadingList = {
   id: Scalars['String']['output'];
   name: Scalars['String']['output'];
   ordering: Scalars['String']['output'];
+  /**
+   * The rules which grant other users a role on the reading list. These are only visible
+   * to the users who are able to manage them, and will be empty for everyone else
+   */
+  rules: Array<ReadingListRuleModel>;
   updatedAt: Scalars['DateTime']['output'];
   visibility: Scalars['String']['output'];
 };
@@ -3694,6 +4256,23 @@ export enum ReadingMode {
   Paged = 'PAGED'
 }
 
+export type ReadingListRuleInput = {
+  role: AccessRole;
+  userId: Scalars['ID']['input'];
+};
+
+/**
+ * Grants a user a role on a reading list: readers can view it, writers can edit it and
+ * co-creators can also manage who has access to it or delete it
+ */
+export type ReadingListRuleModel = {
+  __typename?: 'ReadingListRuleModel';
+  id: Scalars['Int']['output'];
+  readingListId: Scalars['String']['output'];
+  role: AccessRole;
+  userId: Scalars['String']['output'];
+};
+
 export type ReadingProgressOutput = ActiveReadingSession | FinishedReadingSession;
 
 export enum ReadingStatus {
@@ -3776,6 +4355,26 @@ export type RegisteredEmailDevice = {
   sendHistory: Array<EmailerSendRecord>;
 };
 
+export type Review = {
+  __typename?: 'Review';
+  createdAt: Scalars['DateTime']['output'];
+  id: Scalars['String']['output'];
+  /** The reviewed book, if the user has access to it */
+  media?: Maybe<Media>;
+  mediaId: Scalars['String']['output'];
+  /** The rating given to the book, from 1 to 5 */
+  rating: Scalars['Int']['output'];
+  reviewText?: Maybe<Scalars['String']['output']>;
+  updatedAt?: Maybe<Scalars['DateTime']['output']>;
+  user: User;
+  userId: Scalars['String']['output'];
+  /**
+   * Who is able to see the review. Shared reviews are visible to the members of the
+   * book clubs the reviewer is in
+   */
+  visibility: EntityVisibility;
+};
+
 export type SaveSmartListInput = {
   defaultGrouping: SmartListGrouping;
   description?: InputMaybe<Scalars['String']['input']>;
@@ -3843,6 +4442,11 @@ export type ScheduledJob = {
   enabled: Scalars['Boolean']['output'];
   id: Scalars['Int']['output'];
   kind: ScheduledJobKind;
+  /**
+   * Why the job last failed to start or run, e.g. an invalid cron expression. This is
+   * cleared after the next successful run
+   */
+  lastError?: Maybe<Scalars['String']['output']>;
   lastRunAt?: Maybe<Scalars['DateTime']['output']>;
   name: Scalars['String']['output'];
   schedule: Scalars['String']['output'];
@@ -3850,15 +4454,36 @@ export type ScheduledJob = {
 
 /** A oneOf input for the schedule config */
 export type ScheduledJobConfigInput =
-  { libraryScan: LibraryScanConfigInput; metadataRetry?: never; }
-  |  { libraryScan?: never; metadataRetry: MetadataRetryConfigInput; };
+  { libraryScan: LibraryScanConfigInput; metadataRetry?: never; thumbnailRegeneration?: never; mediaAnalysis?: never; libraryCleanup?: never; databaseBackup?: never; historyPruning?: never; opdsCacheWarmup?: never; }
+  |  { libraryScan?: never; metadataRetry: MetadataRetryConfigInput; thumbnailRegeneration?: never; mediaAnalysis?: never; libraryCleanup?: never; databaseBackup?: never; historyPruning?: never; opdsCacheWarmup?: never; }
+  |  { libraryScan?: never; metadataRetry?: never; thumbnailRegeneration: ThumbnailRegenerationConfigInput; mediaAnalysis?: never; libraryCleanup?: never; databaseBackup?: never; historyPruning?: never; opdsCacheWarmup?: never; }
+  |  { libraryScan?: never; metadataRetry?: never; thumbnailRegeneration?: never; mediaAnalysis: MediaAnalysisConfigInput; libraryCleanup?: never; databaseBackup?: never; historyPruning?: never; opdsCacheWarmup?: never; }
+  |  { libraryScan?: never; metadataRetry?: never; thumbnailRegeneration?: never; mediaAnalysis?: never; libraryCleanup: LibraryCleanupConfigInput; databaseBackup?: never; historyPruning?: never; opdsCacheWarmup?: never; }
+  |  { libraryScan?: never; metadataRetry?: never; thumbnailRegeneration?: never; mediaAnalysis?: never; libraryCleanup?: never; databaseBackup: DatabaseBackupConfigInput; historyPruning?: never; opdsCacheWarmup?: never; }
+  |  { libraryScan?: never; metadataRetry?: never; thumbnailRegeneration?: never; mediaAnalysis?: never; libraryCleanup?: never; databaseBackup?: never; historyPruning: HistoryPruningConfigInput; opdsCacheWarmup?: never; }
+  |  { libraryScan?: never; metadataRetry?: never; thumbnailRegeneration?: never; mediaAnalysis?: never; libraryCleanup?: never; databaseBackup?: never; historyPruning?: never; opdsCacheWarmup: OpdsCacheWarmupConfigInput; };
 
 /** The kind of a scheduled job, aligned with the config variants */
 export enum ScheduledJobKind {
+  /** Back up the database to the backups directory */
+  DatabaseBackup = 'DATABASE_BACKUP',
+  /** Delete old logs and job history */
+  HistoryPruning = 'HISTORY_PRUNING',
+  /** Remove missing books and series from one or more libraries and detect duplicates */
+  LibraryCleanup = 'LIBRARY_CLEANUP',
   /** Scan one or more libraries on a cron schedule */
   LibraryScan = 'LIBRARY_SCAN',
+  /** Analyze the books in one or more libraries */
+  MediaAnalysis = 'MEDIA_ANALYSIS',
   /** Retry fetching metadata for records that were rate-limited or failed */
-  MetadataRetry = 'METADATA_RETRY'
+  MetadataRetry = 'METADATA_RETRY',
+  /**
+   * Pre-render the pages of PDFs so they are served from the page cache, e.g. to OPDS
+   * page streaming clients
+   */
+  OpdsCacheWarmup = 'OPDS_CACHE_WARMUP',
+  /** Generate (or regenerate) thumbnails for the books in one or more libraries */
+  ThumbnailRegeneration = 'THUMBNAIL_REGENERATION'
 }
 
 export type SendAttachmentEmailOutput = {
@@ -3906,6 +4531,8 @@ export type Series = {
   name: Scalars['String']['output'];
   path: Scalars['String']['output'];
   percentageCompleted: Scalars['Float']['output'];
+  /** The average rating of the books in the series, from their public reviews */
+  ratingSummary: RatingSummary;
   readCount: Scalars['Int']['output'];
   resolvedDescription?: Maybe<Scalars['String']['output']>;
   resolvedName: Scalars['String']['output'];
@@ -4339,6 +4966,26 @@ export type StumpConfig = {
   expiredSessionCleanupInterval: Scalars['Int']['output'];
   /** The directory where the applicaiton logs will be stored */
   logDir?: Maybe<Scalars['String']['output']>;
+  /**
+   * The maximum number of analysis jobs (e.g., media analysis, duplicate detection, metadata
+   * write back and conversion) which may run at the same time.
+   */
+  maxConcurrentAnalysisJobs: Scalars['Int']['output'];
+  /**
+   * The maximum number of jobs of any kind which may run at the same time. This defaults to
+   * one for each job category, so jobs of different kinds can run side by side while the
+   * per-category limits below keep jobs of the same kind from contending for the database.
+   */
+  maxConcurrentJobs: Scalars['Int']['output'];
+  /** The maximum number of metadata fetch jobs which may run at the same time. */
+  maxConcurrentMetadataJobs: Scalars['Int']['output'];
+  /** The maximum number of library and series scans which may run at the same time. */
+  maxConcurrentScanJobs: Scalars['Int']['output'];
+  /**
+   * The maximum number of thumbnail and placeholder generation jobs which may run at the
+   * same time.
+   */
+  maxConcurrentThumbnailJobs: Scalars['Int']['output'];
   /** The maximum size, in bytes, of files that can be uploaded to be included in libraries. */
   maxFileUploadSize: Scalars['Int']['output'];
   /**
@@ -4384,6 +5031,16 @@ export type StumpConfig = {
   refreshTokenTtl: Scalars['Int']['output'];
   /** The time in seconds that a login session will be valid for. */
   sessionTtl: Scalars['Int']['output'];
+  /**
+   * The number of times a failed job task is attempted, including the first attempt. Some
+   * jobs, e.g. scans, never retry their tasks regardless of this value.
+   */
+  taskMaxAttempts: Scalars['Int']['output'];
+  /**
+   * The number of seconds to wait before the first retry of a failed job task. Each retry
+   * after it waits twice as long as the one before.
+   */
+  taskRetryBackoffSecs: Scalars['Int']['output'];
   /** Whether to trust proxy headers for determining client IP and scheme (e.g., X-Forwarded-For) */
   trustProxyHeaders: Scalars['Boolean']['output'];
   /** The verbosity with which system logs are visible (default: 1). */
@@ -4416,8 +5073,12 @@ export enum SupportedFont {
   OpenDyslexic = 'OPEN_DYSLEXIC'
 }
 
-/** Supported image formats for processing images throughout Stump */
+/**
+ * Supported image formats for processing images throughout Stump. Note that JPEG XL images
+ * can be read, but not generated, so there is no variant for it
+ */
 export enum SupportedImageFormat {
+  Avif = 'AVIF',
   Jpeg = 'JPEG',
   Png = 'PNG',
   Webp = 'WEBP'
@@ -4479,6 +5140,24 @@ export enum ThumbnailPlaceholderStyle {
   Thumbhash = 'THUMBHASH'
 }
 
+export type ThumbnailRegenerationConfigInput = {
+  /** Whether to regenerate existing thumbnails, rather than only generating missing ones */
+  forceRegenerate?: Scalars['Boolean']['input'];
+  /** Library IDs to generate thumbnails for. An empty list means "all libraries" */
+  libraryIds: Array<Scalars['String']['input']>;
+};
+
+/** A book in an imported CBL file which could not be matched to a book on the server */
+export type UnmatchedCblBook = {
+  __typename?: 'UnmatchedCBLBook';
+  number?: Maybe<Scalars['String']['output']>;
+  /** The position of the book in the CBL file, starting at 0 */
+  position: Scalars['Int']['output'];
+  series?: Maybe<Scalars['String']['output']>;
+  volume?: Maybe<Scalars['String']['output']>;
+  year?: Maybe<Scalars['String']['output']>;
+};
+
 /**
  * A simple pagination input object which does not paginate. An explicit struct is
  * required as a limitation of async_graphql's [OneofObject], which doesn't allow
@@ -4501,10 +5180,25 @@ export type UpdateBookClubInput = {
   name?: InputMaybe<Scalars['String']['input']>;
 };
 
+export type UpdateCollectionInput = {
+  /** The series to use as the cover of the collection. It must be in the collection */
+  coverSeriesId?: InputMaybe<Scalars['ID']['input']>;
+  description?: InputMaybe<Scalars['String']['input']>;
+  name: Scalars['String']['input'];
+  visibility: EntityVisibility;
+};
+
 export type UpdateCustomEmojiInput = {
   name: Scalars['String']['input'];
 };
 
+export type UpdateReviewInput = {
+  /** The rating given to the book, from 1 to 5 */
+  rating: Scalars['Int']['input'];
+  reviewText?: InputMaybe<Scalars['String']['input']>;
+  visibility: EntityVisibility;
+};
+
 export type UpdateScheduledJobInput = {
   /** Replace the config entirely. The kind is inferred from the variant */
   config?: InputMaybe<ScheduledJobConfigInput>;
@@ -4743,6 +5437,47 @@ export type UserPreferences = {
   userId?: Maybe<Scalars['String']['output']>;
 };
 
+export type WebhookConfig = {
+  __typename?: 'WebhookConfig';
+  /** The encrypted secret used to sign the body of each request, if any */
+  encryptedSecret?: Maybe<Scalars['String']['output']>;
+  headers: Array<WebhookHeader>;
+  url: Scalars['String']['output'];
+};
+
+export type WebhookConfigInput = {
+  headers?: InputMaybe<Array<WebhookHeaderInput>>;
+  /**
+   * A secret used to sign the body of each request. The signature is sent in the
+   * `X-Stump-Signature` header
+   */
+  secret?: InputMaybe<Scalars['String']['input']>;
+  url: Scalars['String']['input'];
+};
+
+export type WebhookHeader = {
+  __typename?: 'WebhookHeader';
+  /** The encrypted value of the header, since it typically holds a token */
+  encryptedValue: Scalars['String']['output'];
+  name: Scalars['String']['output'];
+};
+
+export type WebhookHeaderInput = {
+  name: Scalars['String']['input'];
+  value: Scalars['String']['input'];
+};
+
+export type WriteBackMetadataOutput = {
+  __typename?: 'WriteBackMetadataOutput';
+  /**
+   * The number of files which were skipped, either because they have no stored metadata
+   * or because their format doesn't support writing metadata back
+   */
+  filesSkipped: Scalars['Int']['output'];
+  /** The number of files which had their embedded metadata rewritten */
+  filesWritten: Scalars['Int']['output'];
+};
+
 export type CreateBookClubMobileMutationVariables = Exact<{
   input: CreateBookClubInput;
 }>;
@@ -5745,7 +6480,7 @@ export type SeriesEditorSetLockedFieldsMutation = { __typename?: 'Mutation', set
 export type UseCoreEventSubscriptionVariables = Exact<{ [key: string]: never; }>;
 
 
-export type UseCoreEventSubscription = { __typename?: 'Subscription', readEvents: { __typename: 'CreatedManySeries', count: number, libraryId: string } | { __typename: 'CreatedMedia', id: string, seriesId: string } | { __typename: 'CreatedOrUpdatedManyMedia', count: number, seriesId: string } | { __typename: 'DiscoveredMissingLibrary', id: string } | { __typename: 'JobOutput', id: string, output: { __typename: 'AnalyzeMediaOutput' } | { __typename: 'LibraryScanOutput', createdMedia: number, createdSeries: number, updatedMedia: number, updatedSeries: number } | { __typename: 'MetadataFetchJobOutput' } | { __typename: 'PlaceholderGenerationOutput' } | { __typename: 'SeriesScanOutput', createdMedia: number, updatedMedia: number } | { __typename: 'ThumbnailGenerationOutput' } } | { __typename: 'JobStarted', id: string } | { __typename: 'JobUpdate', id: string, status?: JobStatus | null, message?: string | null, completedTasks?: number | null, remainingTasks?: number | null, completedSubtasks?: number | null, totalSubtasks?: number | null } };
+export type UseCoreEventSubscription = { __typename?: 'Subscription', readEvents: { __typename: 'CreatedManySeries', count: number, libraryId: string } | { __typename: 'CreatedMedia', id: string, seriesId: string } | { __typename: 'CreatedOrUpdatedManyMedia', count: number, seriesId: string } | { __typename: 'DiscoveredMissingLibrary', id: string } | { __typename: 'JobOutput', id: string, output: { __typename: 'AnalyzeMediaOutput' } | { __typename: 'ConvertMediaOutput' } | { __typename: 'DetectDuplicatesOutput' } | { __typename: 'LibraryScanOutput', createdMedia: number, createdSeries: number, updatedMedia: number, updatedSeries: number } | { __typename: 'MetadataFetchJobOutput' } | { __typename: 'PlaceholderGenerationOutput' } | { __typename: 'SeriesScanOutput', createdMedia: number, updatedMedia: number } | { __typename: 'ThumbnailGenerationOutput' } | { __typename: 'WriteBackMetadataOutput' } } | { __typename: 'JobStarted', id: string } | { __typename: 'JobUpdate', id: string, status?: JobStatus | null, message?: string | null, completedTasks?: number | null, remainingTasks?: number | null, completedSubtasks?: number | null, totalSubtasks?: number | null } };
 
 export type UsePreferencesMutationVariables = Exact<{
   input: UpdateUserPreferencesInput;
@@ -6060,7 +6795,7 @@ export type LibrarySeriesGridQueryVariables = Exact<{
 
 export type LibrarySeriesGridQuery = { __typename?: 'Query', series: { __typename?: 'PaginatedSeriesResponse', nodes: Array<{ __typename?: 'Series', id: string, thumbnail: { __typename?: 'ImageRef', url: string } }>, pageInfo: { __typename: 'CursorPaginationInfo', currentCursor?: string | null, nextCursor?: string | null, limit: number } | { __typename: 'OffsetPaginationInfo' } } };
 
-export type LibrarySettingsConfigFragment = { __typename?: 'Library', config: { __typename?: 'LibraryConfig', id: number, convertRarToZip: boolean, hardDeleteConversions: boolean, defaultReadingDir: ReadingDirection, defaultReadingMode: ReadingMode, defaultReadingImageScaleFit: ReadingImageScaleFit, defaultLibraryViewMode: LibraryViewMode, hideSeriesView: boolean, skipBookOverview: boolean, generateFileHashes: boolean, generateKoreaderHashes: boolean, processMetadata: boolean, watch: boolean, libraryPattern: LibraryPattern, processThumbnailColorsEvenWithoutConfig: boolean, ignoreRules?: Array<string> | null, thumbnailConfig?: { __typename: 'ImageProcessorOptions', format: SupportedImageFormat, quality?: number | null, page?: number | null, resizeMethod?: { __typename: 'ExactDimensionResize', width: number, height: number } | { __typename: 'FitWithinResize' } | { __typename: 'ScaleEvenlyByFactor', factor: any } | { __typename: 'ScaledDimensionResize', dimension: Dimension, size: number } | null } | null } } & { ' $fragmentName'?: 'LibrarySettingsConfigFragment' };
+export type LibrarySettingsConfigFragment = { __typename?: 'Library', config: { __typename?: 'LibraryConfig', id: number, convertRarToZip: boolean, hardDeleteConversions: boolean, defaultReadingDir: ReadingDirection, defaultReadingMode: ReadingMode, defaultReadingImageScaleFit: ReadingImageScaleFit, defaultLibraryViewMode: LibraryViewMode, hideSeriesView: boolean, skipBookOverview: boolean, koboConvertToEpub: boolean, generateFileHashes: boolean, generateKoreaderHashes: boolean, processMetadata: boolean, watch: boolean, watchMode: LibraryWatchMode, watchPollIntervalSeconds: number, libraryPattern: LibraryPattern, processThumbnailColorsEvenWithoutConfig: boolean, ignoreRules?: Array<string> | null, thumbnailConfig?: { __typename: 'ImageProcessorOptions', format: SupportedImageFormat, quality?: number | null, page?: number | null, resizeMethod?: { __typename: 'ExactDimensionResize', width: number, height: number } | { __typename: 'FitWithinResize' } | { __typename: 'ScaleEvenlyByFactor', factor: any } | { __typename: 'ScaledDimensionResize', dimension: Dimension, size: number } | null } | null } } & { ' $fragmentName'?: 'LibrarySettingsConfigFragment' };
 
 export type LibrarySettingsRouterEditLibraryMutationMutationVariables = Exact<{
   id: Scalars['ID']['input'];
@@ -6157,7 +6892,7 @@ export type ScanRecordInspectorJobsQueryVariables = Exact<{
 }>;
 
 
-export type ScanRecordInspectorJobsQuery = { __typename?: 'Query', jobById?: { __typename?: 'Job', id: string, outputData?: { __typename: 'AnalyzeMediaOutput' } | { __typename: 'LibraryScanOutput', totalFiles: number, totalDirectories: number, ignoredFiles: number, skippedFiles: number, ignoredDirectories: number, createdMedia: number, updatedMedia: number, createdSeries: number, updatedSeries: number } | { __typename: 'MetadataFetchJobOutput' } | { __typename: 'PlaceholderGenerationOutput' } | { __typename: 'SeriesScanOutput' } | { __typename: 'ThumbnailGenerationOutput' } | null, logs?: Array<{ __typename?: 'Log', id: number }> } | null };
+export type ScanRecordInspectorJobsQuery = { __typename?: 'Query', jobById?: { __typename?: 'Job', id: string, outputData?: { __typename: 'AnalyzeMediaOutput' } | { __typename: 'ConvertMediaOutput' } | { __typename: 'DetectDuplicatesOutput' } | { __typename: 'LibraryScanOutput', totalFiles: number, totalDirectories: number, ignoredFiles: number, skippedFiles: number, ignoredDirectories: number, createdMedia: number, updatedMedia: number, createdSeries: number, updatedSeries: number } | { __typename: 'MetadataFetchJobOutput' } | { __typename: 'PlaceholderGenerationOutput' } | { __typename: 'SeriesScanOutput' } | { __typename: 'ThumbnailGenerationOutput' } | { __typename: 'WriteBackMetadataOutput' } | null, logs?: Array<{ __typename?: 'Log', id: number }> } | null };
 
 export type DeleteLibraryThumbnailsMutationVariables = Exact<{
   id: Scalars['ID']['input'];
@@ -6524,6 +7259,10 @@ export type JobActionMenuDeleteLogsMutation = { __typename?: 'Mutation', deleteJ
 
 type JobDataInspector_AnalyzeMediaOutput_Fragment = { __typename: 'AnalyzeMediaOutput' } & { ' $fragmentName'?: 'JobDataInspector_AnalyzeMediaOutput_Fragment' };
 
+type JobDataInspector_ConvertMediaOutput_Fragment = { __typename: 'ConvertMediaOutput' } & { ' $fragmentName'?: 'JobDataInspector_ConvertMediaOutput_Fragment' };
+
+type JobDataInspector_DetectDuplicatesOutput_Fragment = { __typename: 'DetectDuplicatesOutput' } & { ' $fragmentName'?: 'JobDataInspector_DetectDuplicatesOutput_Fragment' };
+
 type JobDataInspector_LibraryScanOutput_Fragment = { __typename: 'LibraryScanOutput', totalFiles: number, totalDirectories: number, ignoredFiles: number, skippedFiles: number, ignoredDirectories: number, createdMedia: number, updatedMedia: number, createdSeries: number, updatedSeries: number } & { ' $fragmentName'?: 'JobDataInspector_LibraryScanOutput_Fragment' };
 
 type JobDataInspector_MetadataFetchJobOutput_Fragment = { __typename: 'MetadataFetchJobOutput' } & { ' $fragmentName'?: 'JobDataInspector_MetadataFetchJobOutput_Fragment' };
@@ -6534,7 +7273,9 @@ type JobDataInspector_SeriesScanOutput_Fragment = { __typename: 'SeriesScanOutpu
 
 type JobDataInspector_ThumbnailGenerationOutput_Fragment = { __typename: 'ThumbnailGenerationOutput', visitedFiles: number, skippedFiles: number, generatedThumbnails: number, removedThumbnails: number } & { ' $fragmentName'?: 'JobDataInspector_ThumbnailGenerationOutput_Fragment' };
 
-export type JobDataInspectorFragment = JobDataInspector_AnalyzeMediaOutput_Fragment | JobDataInspector_LibraryScanOutput_Fragment | JobDataInspector_MetadataFetchJobOutput_Fragment | JobDataInspector_PlaceholderGenerationOutput_Fragment | JobDataInspector_SeriesScanOutput_Fragment | JobDataInspector_ThumbnailGenerationOutput_Fragment;
+type JobDataInspector_WriteBackMetadataOutput_Fragment = { __typename: 'WriteBackMetadataOutput' } & { ' $fragmentName'?: 'JobDataInspector_WriteBackMetadataOutput_Fragment' };
+
+export type JobDataInspectorFragment = JobDataInspector_AnalyzeMediaOutput_Fragment | JobDataInspector_ConvertMediaOutput_Fragment | JobDataInspector_DetectDuplicatesOutput_Fragment | JobDataInspector_LibraryScanOutput_Fragment | JobDataInspector_MetadataFetchJobOutput_Fragment | JobDataInspector_PlaceholderGenerationOutput_Fragment | JobDataInspector_SeriesScanOutput_Fragment | JobDataInspector_ThumbnailGenerationOutput_Fragment | JobDataInspector_WriteBackMetadataOutput_Fragment;
 
 export type ScheduledJobsQueryVariables = Exact<{ [key: string]: never; }>;
 
@@ -6559,6 +7300,12 @@ export type JobTableQueryVariables = Exact<{
 export type JobTableQuery = { __typename?: 'Query', jobs: { __typename?: 'PaginatedJobResponse', nodes: Array<{ __typename?: 'Job', id: string, name: string, description?: string | null, status: JobStatus, createdAt: any, completedAt?: any | null, msElapsed: number, logCount: number, outputData?: (
         { __typename?: 'AnalyzeMediaOutput' }
         & { ' $fragmentRefs'?: { 'JobDataInspector_AnalyzeMediaOutput_Fragment': JobDataInspector_AnalyzeMediaOutput_Fragment } }
+      ) | (
+        { __typename?: 'ConvertMediaOutput' }
+        & { ' $fragmentRefs'?: { 'JobDataInspector_ConvertMediaOutput_Fragment': JobDataInspector_ConvertMediaOutput_Fragment } }
+      ) | (
+        { __typename?: 'DetectDuplicatesOutput' }
+        & { ' $fragmentRefs'?: { 'JobDataInspector_DetectDuplicatesOutput_Fragment': JobDataInspector_DetectDuplicatesOutput_Fragment } }
       ) | (
         { __typename?: 'LibraryScanOutput' }
         & { ' $fragmentRefs'?: { 'JobDataInspector_LibraryScanOutput_Fragment': JobDataInspector_LibraryScanOutput_Fragment } }
@@ -6574,6 +7321,9 @@ export type JobTableQuery = { __typename?: 'Query', jobs: { __typename?: 'Pagina
       ) | (
         { __typename?: 'ThumbnailGenerationOutput' }
         & { ' $fragmentRefs'?: { 'JobDataInspector_ThumbnailGenerationOutput_Fragment': JobDataInspector_ThumbnailGenerationOutput_Fragment } }
+      ) | (
+        { __typename?: 'WriteBackMetadataOutput' }
+        & { ' $fragmentRefs'?: { 'JobDataInspector_WriteBackMetadataOutput_Fragment': JobDataInspector_WriteBackMetadataOutput_Fragment } }
       ) | null }>, pageInfo: { __typename: 'CursorPaginationInfo' } | { __typename: 'OffsetPaginationInfo', currentPage: number, totalPages: number, pageSize: number, pageOffset: number, zeroBased: boolean } } };
 
 export type ScheduledJobRowFragment = { __typename?: 'ScheduledJob', id: number, name: string, kind: ScheduledJobKind, schedule: string, config?: any | null, enabled: boolean, createdAt: any, lastRunAt?: any | null } & { ' $fragmentName'?: 'ScheduledJobRowFragment' };
@@ -7660,10 +8410,13 @@ export const LibrarySettingsConfigFragmentDoc = new TypedDocumentString(`
     defaultLibraryViewMode
     hideSeriesView
     skipBookOverview
+    koboConvertToEpub
     generateFileHashes
     generateKoreaderHashes
     processMetadata
     watch
+    watchMode
+    watchPollIntervalSeconds
     libraryPattern
     thumbnailConfig {
       __typename
@@ -11670,10 +12423,13 @@ export const LibraryLayoutDocument = new TypedDocumentString(`
     defaultLibraryViewMode
     hideSeriesView
     skipBookOverview
+    koboConvertToEpub
     generateFileHashes
     generateKoreaderHashes
     processMetadata
     watch
+    watchMode
+    watchPollIntervalSeconds
     libraryPattern
     thumbnailConfig {
       __typename